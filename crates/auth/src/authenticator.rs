@@ -1,9 +1,10 @@
-use std::{cell::OnceCell, time::Duration};
+use std::{cell::OnceCell, sync::Arc, time::Duration};
 
 use chrono::Utc;
 use oauth2::{
-    AuthUrl, AuthorizationCode, Client, ClientId, CsrfToken, EndpointNotSet, EndpointSet, HttpClientError,
-    PkceCodeChallenge, RedirectUrl, RefreshToken, RequestTokenError, Scope, StandardErrorResponse,
+    AuthUrl, AuthorizationCode, Client, ClientId, CsrfToken, DeviceAuthorizationUrl, DeviceCodeErrorResponse,
+    DeviceCodeErrorResponseType, EndpointNotSet, EndpointSet, HttpClientError, PkceCodeChallenge, RedirectUrl,
+    RefreshToken, RequestTokenError, Scope, StandardDeviceAuthorizationResponse, StandardErrorResponse,
     StandardRevocableToken, TokenResponse, TokenUrl,
     basic::{
         BasicErrorResponse, BasicErrorResponseType, BasicRevocationErrorResponse, BasicTokenIntrospectionResponse,
@@ -14,9 +15,10 @@ use oauth2::{
 use crate::{
     constants,
     models::{
-        FinishedAuthorization, MinecraftAccessToken, MinecraftLoginWithXboxRequest, MinecraftLoginWithXboxResponse,
-        MinecraftProfileResponse, MsaTokens, PendingAuthorization, TokenWithExpiry, XboxLiveAuthenticateRequest,
-        XboxLiveAuthenticateRequestProperties, XboxLiveAuthenticateResponse, XboxLiveSecurityTokenRequest,
+        FinishedAuthorization, MinecraftAccessToken, MinecraftEntitlementsResponse, MinecraftLoginWithXboxRequest,
+        MinecraftLoginWithXboxResponse, MinecraftProfileResponse, MsaTokens, PendingAuthorization,
+        PendingDeviceAuthorization, TokenWithExpiry, XboxLiveAuthenticateRequest, XboxLiveAuthenticateRequestProperties,
+        XboxLiveAuthenticateResponse, XboxLiveErrorResponse, XboxLiveSecurityTokenRequest,
         XboxLiveSecurityTokenRequestProperties, XboxLiveSecurityTokenResponse, XstsToken,
     },
 };
@@ -34,8 +36,22 @@ type OAuthClient = oauth2::Client<
     EndpointSet,
 >;
 
+type DeviceOAuthClient = oauth2::Client<
+    BasicErrorResponse,
+    BasicTokenResponse,
+    BasicTokenIntrospectionResponse,
+    StandardRevocableToken,
+    BasicRevocationErrorResponse,
+    EndpointNotSet,
+    EndpointSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointSet,
+>;
+
 pub struct Authenticator {
     oauth2_client: OnceCell<OAuthClient>,
+    device_oauth2_client: OnceCell<DeviceOAuthClient>,
     client: reqwest::Client,
 }
 
@@ -53,6 +69,10 @@ pub enum MsaAuthorizationError {
     ConnectionError(HttpClientError<reqwest::Error>),
     #[error("Invalid grant (token is expired, invalid or revoked)")]
     InvalidGrant,
+    #[error("The login was declined")]
+    AccessDenied,
+    #[error("The login code expired before it was entered")]
+    ExpiredDeviceCode,
     #[error("External error")]
     ExternalError(Option<BasicErrorResponseType>),
     #[error("Internal error")]
@@ -66,6 +86,14 @@ impl MsaAuthorizationError {
             _ => false,
         }
     }
+
+    fn from_error_type(error: &BasicErrorResponseType) -> Self {
+        match error {
+            BasicErrorResponseType::InvalidGrant => Self::InvalidGrant,
+            BasicErrorResponseType::Extension(_) => Self::ExternalError(None),
+            error => Self::ExternalError(Some(error.clone())),
+        }
+    }
 }
 
 impl From<RequestTokenError<HttpClientError<reqwest::Error>, StandardErrorResponse<BasicErrorResponseType>>>
@@ -74,23 +102,24 @@ impl From<RequestTokenError<HttpClientError<reqwest::Error>, StandardErrorRespon
     fn from(
         value: RequestTokenError<HttpClientError<reqwest::Error>, StandardErrorResponse<BasicErrorResponseType>>,
     ) -> Self {
+        match value {
+            RequestTokenError::ServerResponse(server_response) => Self::from_error_type(server_response.error()),
+            RequestTokenError::Request(error) => Self::ConnectionError(error),
+            RequestTokenError::Parse(..) => Self::InternalError,
+            RequestTokenError::Other(_) => Self::InternalError,
+        }
+    }
+}
+
+impl From<RequestTokenError<HttpClientError<reqwest::Error>, DeviceCodeErrorResponse>> for MsaAuthorizationError {
+    fn from(value: RequestTokenError<HttpClientError<reqwest::Error>, DeviceCodeErrorResponse>) -> Self {
         match value {
             RequestTokenError::ServerResponse(server_response) => match server_response.error() {
-                BasicErrorResponseType::InvalidClient => {
-                    Self::ExternalError(Some(BasicErrorResponseType::InvalidClient))
-                },
-                BasicErrorResponseType::InvalidGrant => Self::InvalidGrant,
-                BasicErrorResponseType::InvalidRequest => {
-                    Self::ExternalError(Some(BasicErrorResponseType::InvalidRequest))
-                },
-                BasicErrorResponseType::InvalidScope => Self::ExternalError(Some(BasicErrorResponseType::InvalidScope)),
-                BasicErrorResponseType::UnauthorizedClient => {
-                    Self::ExternalError(Some(BasicErrorResponseType::UnauthorizedClient))
-                },
-                BasicErrorResponseType::UnsupportedGrantType => {
-                    Self::ExternalError(Some(BasicErrorResponseType::UnsupportedGrantType))
-                },
-                BasicErrorResponseType::Extension(_) => Self::ExternalError(None),
+                DeviceCodeErrorResponseType::AccessDenied => Self::AccessDenied,
+                DeviceCodeErrorResponseType::ExpiredToken => Self::ExpiredDeviceCode,
+                DeviceCodeErrorResponseType::Basic(error) => Self::from_error_type(error),
+                // Pending and slow down responses are handled while polling
+                _ => Self::InternalError,
             },
             RequestTokenError::Request(error) => Self::ConnectionError(error),
             RequestTokenError::Parse(..) => Self::InternalError,
@@ -111,6 +140,57 @@ pub enum XboxAuthenticateError {
     MissingXui,
     #[error("Missing userhash")]
     MissingUhs,
+    #[error("{0}")]
+    AccountProblem(AccountProblem),
+}
+
+// Problems with the account itself, logging in again won't help until the user has fixed them
+#[derive(thiserror::Error, Debug)]
+pub enum AccountProblem {
+    #[error("This Microsoft account doesn't have an Xbox profile yet. Create one, then log in again")]
+    NoXboxAccount { redirect: Option<Arc<str>> },
+    #[error("Xbox Live isn't available in the country or region of this Microsoft account")]
+    XboxLiveUnavailable,
+    #[error("This Microsoft account has to complete adult verification on the Xbox website before it can play")]
+    AdultVerificationRequired { redirect: Option<Arc<str>> },
+    #[error("This is a child account. An adult has to add it to a Microsoft family before it can play")]
+    ChildAccount,
+    #[error("Xbox Live refused to log in this account (error {0})")]
+    XboxLiveRefused(u64),
+    #[error("This Microsoft account doesn't own Minecraft: Java Edition")]
+    DoesNotOwnMinecraft,
+    #[error("This account owns Minecraft but doesn't have a profile yet. If you play through Game Pass, set up your profile and log in again")]
+    NoMinecraftProfile,
+}
+
+impl AccountProblem {
+    fn from_xerr(error: XboxLiveErrorResponse) -> Self {
+        match error.xerr {
+            2148916233 => Self::NoXboxAccount { redirect: error.redirect },
+            2148916235 => Self::XboxLiveUnavailable,
+            2148916236 | 2148916237 => Self::AdultVerificationRequired { redirect: error.redirect },
+            2148916238 => Self::ChildAccount,
+            xerr => Self::XboxLiveRefused(xerr),
+        }
+    }
+
+    // The page where the user can fix the problem, along with a label for the link
+    pub fn help_link(&self) -> Option<(&'static str, Arc<str>)> {
+        match self {
+            Self::NoXboxAccount { redirect } => Some((
+                "Create an Xbox profile",
+                redirect.clone().unwrap_or_else(|| constants::XBOX_CREATE_ACCOUNT_URL.into()),
+            )),
+            Self::AdultVerificationRequired { redirect } => Some((
+                "Verify your age",
+                redirect.clone().unwrap_or_else(|| constants::XBOX_ACCOUNT_URL.into()),
+            )),
+            Self::ChildAccount => Some(("Open Microsoft Family", constants::MICROSOFT_FAMILY_URL.into())),
+            Self::DoesNotOwnMinecraft => Some(("Buy Minecraft", constants::MINECRAFT_STORE_URL.into())),
+            Self::NoMinecraftProfile => Some(("Set up a profile", constants::MINECRAFT_EDIT_PROFILE_URL.into())),
+            Self::XboxLiveUnavailable | Self::XboxLiveRefused(_) => None,
+        }
+    }
 }
 
 impl XboxAuthenticateError {
@@ -127,6 +207,7 @@ impl Authenticator {
         Self {
             client,
             oauth2_client: OnceCell::new(),
+            device_oauth2_client: OnceCell::new(),
         }
     }
 
@@ -140,6 +221,15 @@ impl Authenticator {
         })
     }
 
+    fn device_oauth2_client(&self) -> &DeviceOAuthClient {
+        self.device_oauth2_client.get_or_init(|| {
+            Client::new(ClientId::new(constants::CLIENT_ID.to_string()))
+                .set_auth_type(oauth2::AuthType::RequestBody)
+                .set_device_authorization_url(DeviceAuthorizationUrl::new(constants::DEVICE_CODE_URL.to_string()).unwrap())
+                .set_token_uri(TokenUrl::new(constants::TOKEN_URL.to_string()).unwrap())
+        })
+    }
+
     pub fn create_authorization(&mut self) -> PendingAuthorization {
         let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
 
@@ -183,6 +273,40 @@ impl Authenticator {
         })
     }
 
+    pub async fn create_device_authorization(&mut self) -> Result<PendingDeviceAuthorization, MsaAuthorizationError> {
+        let details: StandardDeviceAuthorizationResponse = self
+            .device_oauth2_client()
+            .exchange_device_code()
+            .add_scope(Scope::new("XboxLive.signin".to_string()))
+            .add_scope(Scope::new("XboxLive.offline_access".to_string()))
+            .request_async(&self.client)
+            .await?;
+
+        Ok(PendingDeviceAuthorization { details })
+    }
+
+    // Polls until the code has been entered, declined or has expired
+    pub async fn finish_device_authorization(
+        &mut self,
+        pending: &PendingDeviceAuthorization,
+    ) -> Result<MsaTokens, MsaAuthorizationError> {
+        let token_response = self
+            .device_oauth2_client()
+            .exchange_device_access_token(&pending.details)
+            .request_async(&self.client, tokio::time::sleep, None)
+            .await?;
+
+        let expires_in = token_response.expires_in().unwrap_or(Duration::from_secs(3600));
+        let expires_at = Utc::now() + expires_in;
+        Ok(MsaTokens {
+            access: TokenWithExpiry {
+                token: token_response.access_token().secret().as_str().into(),
+                expiry: expires_at,
+            },
+            refresh: token_response.refresh_token().map(|v| v.secret().as_str().into()),
+        })
+    }
+
     pub async fn refresh_msa(&mut self, refresh: &str) -> Result<Option<MsaTokens>, MsaAuthorizationError> {
         let token_response = self
             .oauth2_client()
@@ -251,6 +375,15 @@ impl Authenticator {
 
         let response = self.client.post(constants::XSTS_AUTHORIZE_URL).json(&request).send().await?;
 
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            let status = response.status();
+            let bytes = response.bytes().await?;
+            return Err(match serde_json::from_slice::<XboxLiveErrorResponse>(&bytes) {
+                Ok(error) => XboxAuthenticateError::AccountProblem(AccountProblem::from_xerr(error)),
+                Err(_) => XboxAuthenticateError::NonOkHttpStatus(status),
+            });
+        }
+
         if response.status() != reqwest::StatusCode::OK {
             return Err(XboxAuthenticateError::NonOkHttpStatus(response.status()));
         }
@@ -313,6 +446,16 @@ impl Authenticator {
             .send()
             .await?;
 
+        // Accounts without a profile either don't own the game or got it through Game Pass and haven't picked a name
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            let problem = if self.owns_minecraft(access_token).await? {
+                AccountProblem::NoMinecraftProfile
+            } else {
+                AccountProblem::DoesNotOwnMinecraft
+            };
+            return Err(XboxAuthenticateError::AccountProblem(problem));
+        }
+
         if response.status() != reqwest::StatusCode::OK {
             return Err(XboxAuthenticateError::NonOkHttpStatus(response.status()));
         }
@@ -321,4 +464,23 @@ impl Authenticator {
 
         serde_json::from_slice(&bytes).map_err(|_| XboxAuthenticateError::SerializationError)
     }
+
+    async fn owns_minecraft(&mut self, access_token: &MinecraftAccessToken) -> Result<bool, XboxAuthenticateError> {
+        let response = self
+            .client
+            .get(constants::MINECRAFT_ENTITLEMENTS_URL)
+            .bearer_auth(access_token.secret())
+            .send()
+            .await?;
+
+        if response.status() != reqwest::StatusCode::OK {
+            return Err(XboxAuthenticateError::NonOkHttpStatus(response.status()));
+        }
+
+        let bytes = response.bytes().await?;
+
+        let response: MinecraftEntitlementsResponse =
+            serde_json::from_slice(&bytes).map_err(|_| XboxAuthenticateError::SerializationError)?;
+        Ok(!response.items.is_empty())
+    }
 }
@@ -1,6 +1,7 @@
 pub const CLIENT_ID: &str = "e5226706-5096-431d-9516-ae48fe263401";
 pub const AUTH_URL: &str = "https://login.microsoftonline.com/consumers/oauth2/v2.0/authorize";
 pub const TOKEN_URL: &str = "https://login.microsoftonline.com/consumers/oauth2/v2.0/token";
+pub const DEVICE_CODE_URL: &str = "https://login.microsoftonline.com/consumers/oauth2/v2.0/devicecode";
 pub const REDIRECT_URL_BASE: &str = "http://localhost:3160";
 pub const REDIRECT_URL: &str = "http://localhost:3160/auth";
 pub const SERVER_ADDRESS: &str = "127.0.0.1:3160";
@@ -8,3 +9,9 @@ pub const XBOX_AUTHENTICATE_URL: &str = "https://user.auth.xboxlive.com/user/aut
 pub const XSTS_AUTHORIZE_URL: &str = "https://xsts.auth.xboxlive.com/xsts/authorize";
 pub const MINECRAFT_LOGIN_WITH_XBOX_URL: &str = "https://api.minecraftservices.com/authentication/login_with_xbox";
 pub const MINECRAFT_PROFILE_URL: &str = "https://api.minecraftservices.com/minecraft/profile";
+pub const MINECRAFT_ENTITLEMENTS_URL: &str = "https://api.minecraftservices.com/entitlements/mcstore";
+pub const XBOX_CREATE_ACCOUNT_URL: &str = "https://start.ui.xboxlive.com/CreateAccount";
+pub const MICROSOFT_FAMILY_URL: &str = "https://account.microsoft.com/family";
+pub const XBOX_ACCOUNT_URL: &str = "https://account.xbox.com";
+pub const MINECRAFT_STORE_URL: &str = "https://www.minecraft.net/store/minecraft-deluxe-collection-pc";
+pub const MINECRAFT_EDIT_PROFILE_URL: &str = "https://www.minecraft.net/msaprofile/mygames/editprofile";
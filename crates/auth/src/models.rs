@@ -1,7 +1,7 @@
 use std::{collections::HashMap, sync::Arc};
 
 use chrono::{DateTime, Utc};
-use oauth2::{CsrfToken, PkceCodeVerifier};
+use oauth2::{CsrfToken, PkceCodeVerifier, StandardDeviceAuthorizationResponse};
 use serde::{Deserialize, Serialize};
 use url::Url;
 use uuid::Uuid;
@@ -38,6 +38,21 @@ pub struct FinishedAuthorization {
     pub code: String,
 }
 
+// A login where the user enters a code on another device, for when there's no browser to redirect back from
+pub struct PendingDeviceAuthorization {
+    pub(crate) details: StandardDeviceAuthorizationResponse,
+}
+
+impl PendingDeviceAuthorization {
+    pub fn verification_uri(&self) -> &str {
+        self.details.verification_uri().url().as_str()
+    }
+
+    pub fn user_code(&self) -> &str {
+        self.details.user_code().secret()
+    }
+}
+
 pub struct MsaTokens {
     pub access: TokenWithExpiry,
     pub refresh: Option<Arc<str>>,
@@ -91,6 +106,16 @@ pub struct XboxLiveSecurityTokenResponse {
     pub display_claims: XboxUserIdentityDisplayClaims,
 }
 
+// Returned along with a 401 when Xbox Live refuses to issue a token for the account
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct XboxLiveErrorResponse {
+    #[serde(rename = "XErr")]
+    pub xerr: u64,
+    #[serde(default)]
+    pub redirect: Option<Arc<str>>,
+}
+
 #[derive(Deserialize)]
 pub struct XboxUserIdentityDisplayClaims {
     pub xui: Vec<HashMap<String, String>>,
@@ -110,6 +135,17 @@ pub struct MinecraftLoginWithXboxResponse {
     pub expires_in: usize,
 }
 
+#[derive(Deserialize)]
+pub struct MinecraftEntitlementsResponse {
+    #[serde(default)]
+    pub items: Vec<MinecraftEntitlement>,
+}
+
+#[derive(Deserialize)]
+pub struct MinecraftEntitlement {
+    pub name: Arc<str>,
+}
+
 #[derive(Deserialize)]
 pub struct MinecraftProfileResponse {
     pub id: Uuid,
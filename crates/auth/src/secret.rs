@@ -109,6 +109,44 @@ mod inner {
             keyring.delete(&attributes).await?;
             Ok(())
         }
+
+        pub async fn read_modrinth_token(&self) -> Result<Option<String>, SecretStorageError> {
+            let keyring = self.keyring.as_ref()?;
+            keyring.unlock().await?;
+
+            let attributes = vec![("service", "pandora-launcher"), ("modrinth", "token")];
+
+            let items = keyring.search_items(&attributes).await?;
+
+            if items.is_empty() {
+                Ok(None)
+            } else if items.len() > 1 {
+                Err(SecretStorageError::NotUnique)
+            } else {
+                let raw = items[0].secret().await?;
+                Ok(Some(String::from_utf8(raw.to_vec()).map_err(|_| SecretStorageError::SerializationError)?))
+            }
+        }
+
+        pub async fn write_modrinth_token(&self, token: &str) -> Result<(), SecretStorageError> {
+            let keyring = self.keyring.as_ref()?;
+            keyring.unlock().await?;
+
+            let attributes = vec![("service", "pandora-launcher"), ("modrinth", "token")];
+
+            keyring.create_item("Pandora Modrinth Account", &attributes, token.as_bytes().to_vec(), true).await?;
+            Ok(())
+        }
+
+        pub async fn delete_modrinth_token(&self) -> Result<(), SecretStorageError> {
+            let keyring = self.keyring.as_ref()?;
+            keyring.unlock().await?;
+
+            let attributes = vec![("service", "pandora-launcher"), ("modrinth", "token")];
+
+            keyring.delete(&attributes).await?;
+            Ok(())
+        }
     }
 }
 
@@ -241,6 +279,63 @@ mod inner {
 
             Ok(())
         }
+
+        pub async fn read_modrinth_token(&self) -> Result<Option<String>, SecretStorageError> {
+            let mut target_name: Vec<u16> = "PandoraLauncher_ModrinthToken".encode_utf16().chain(std::iter::once(0)).collect();
+
+            let mut credentials: *mut CREDENTIALW = std::ptr::null_mut();
+
+            unsafe {
+                let result = CredReadW(
+                    windows::core::PWSTR::from_raw(target_name.as_mut_ptr()),
+                    CRED_TYPE_GENERIC,
+                    None,
+                    &mut credentials,
+                );
+
+                if let Err(error) = result {
+                    const ERROR_NOT_FOUND: windows::core::HRESULT =
+                        windows::core::HRESULT::from_win32(windows::Win32::Foundation::ERROR_NOT_FOUND.0);
+                    if error.code() == ERROR_NOT_FOUND {
+                        return Ok(None);
+                    }
+                    return Err(error.into());
+                }
+
+                let Some(credentials) = credentials.as_mut() else {
+                    return Ok(None);
+                };
+
+                let raw =
+                    std::slice::from_raw_parts(credentials.CredentialBlob, credentials.CredentialBlobSize as usize);
+                Ok(Some(String::from_utf8(raw.to_vec()).map_err(|_| SecretStorageError::SerializationError)?))
+            }
+        }
+
+        pub async fn write_modrinth_token(&self, token: &str) -> Result<(), SecretStorageError> {
+            let mut target_name: Vec<u16> = "PandoraLauncher_ModrinthToken".encode_utf16().chain(std::iter::once(0)).collect();
+            let mut bytes = token.as_bytes().to_vec();
+
+            let credentials = CREDENTIALW {
+                Flags: CRED_FLAGS(0),
+                Type: CRED_TYPE_GENERIC,
+                TargetName: windows::core::PWSTR::from_raw(target_name.as_mut_ptr()),
+                CredentialBlobSize: bytes.len() as u32,
+                CredentialBlob: bytes.as_mut_ptr(),
+                Persist: CRED_PERSIST_LOCAL_MACHINE,
+                ..CREDENTIALW::default()
+            };
+
+            unsafe { Ok(CredWriteW(&credentials, 0)?) }
+        }
+
+        pub async fn delete_modrinth_token(&self) -> Result<(), SecretStorageError> {
+            let mut target_name: Vec<u16> = "PandoraLauncher_ModrinthToken".encode_utf16().chain(std::iter::once(0)).collect();
+
+            unsafe {
+                Ok(CredDeleteW(windows::core::PWSTR::from_raw(target_name.as_mut_ptr()), CRED_TYPE_GENERIC, None)?)
+            }
+        }
     }
 }
 
@@ -305,5 +400,38 @@ mod inner {
             item.delete();
             Ok(())
         }
+
+        pub async fn read_modrinth_token(&self) -> Result<Option<String>, SecretStorageError> {
+            let data = match self.keychain.find_generic_password("com.moulberry.pandoralauncher", "modrinth-token") {
+                Ok((data, _)) => data,
+                Err(error) if error.code() == security_framework_sys::base::errSecItemNotFound => {
+                    return Ok(None);
+                },
+                Err(error) => {
+                    return Err(error.into());
+                }
+            };
+            Ok(Some(String::from_utf8(data.as_ref().to_vec()).map_err(|_| SecretStorageError::SerializationError)?))
+        }
+
+        pub async fn write_modrinth_token(&self, token: &str) -> Result<(), SecretStorageError> {
+            self.keychain.set_generic_password("com.moulberry.pandoralauncher", "modrinth-token", token.as_bytes())?;
+            Ok(())
+        }
+
+        pub async fn delete_modrinth_token(&self) -> Result<(), SecretStorageError> {
+            let item = match self.keychain.find_generic_password("com.moulberry.pandoralauncher", "modrinth-token") {
+                Ok((_, item)) => item,
+                Err(error) if error.code() == security_framework_sys::base::errSecItemNotFound => {
+                    return Ok(());
+                },
+                Err(error) => {
+                    return Err(error.into());
+                }
+            };
+
+            item.delete();
+            Ok(())
+        }
     }
 }
@@ -1,11 +1,14 @@
 use std::sync::Arc;
 
 use auth::models::{MinecraftAccessToken, MinecraftProfileResponse};
-use bridge::{account::Account, message::MessageToFrontend};
+use bridge::{account::{Account, ModrinthAccount}, message::MessageToFrontend};
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+// Cached profiles are refreshed in the background once they're older than this
+const PROFILE_REFRESH_INTERVAL_SECS: i64 = 60 * 60 * 24;
+
 pub struct MinecraftLoginInfo {
     pub uuid: Uuid,
     pub username: Arc<str>,
@@ -16,6 +19,19 @@ pub struct MinecraftLoginInfo {
 pub struct BackendAccountInfo {
     pub accounts: FxHashMap<Uuid, BackendAccount>,
     pub selected_account: Option<Uuid>,
+    // The token itself is kept in the secret storage
+    #[serde(default)]
+    pub modrinth_account: Option<BackendModrinthAccount>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackendModrinthAccount {
+    pub id: Arc<str>,
+    pub username: Arc<str>,
+    pub avatar_url: Option<Arc<str>>,
+    // Set when Modrinth rejected the token, cleared once signed in again
+    #[serde(default)]
+    pub needs_relogin: bool,
 }
 
 impl BackendAccountInfo {
@@ -26,12 +42,19 @@ impl BackendAccountInfo {
                 uuid: *uuid,
                 username: account.username.clone(),
                 head: account.head.clone(),
+                needs_reauth: account.needs_reauth,
             });
         }
         accounts.sort_by(|a, b| lexical_sort::natural_lexical_cmp(&a.username, &b.username));
         MessageToFrontend::AccountsUpdated {
             accounts: accounts.into(),
             selected_account: self.selected_account,
+            modrinth_account: self.modrinth_account.as_ref().map(|account| ModrinthAccount {
+                id: account.id.clone(),
+                username: account.username.clone(),
+                avatar_url: account.avatar_url.clone(),
+                needs_relogin: account.needs_relogin,
+            }),
         }
     }
 }
@@ -42,6 +65,15 @@ pub struct BackendAccount {
     #[serde(default)]
     pub offline: bool,
     pub head: Option<Arc<[u8]>>,
+    // Skin the head was made from, so it's only downloaded again when the skin changes
+    #[serde(default)]
+    pub skin_url: Option<Arc<str>>,
+    // Unix timestamp of when the name and skin were last fetched
+    #[serde(default)]
+    pub profile_fetched_at: Option<i64>,
+    // Set when Microsoft rejected the refresh token, cleared once logged in again
+    #[serde(default)]
+    pub needs_reauth: bool,
 }
 
 impl BackendAccount {
@@ -50,6 +82,13 @@ impl BackendAccount {
             username: profile.name.clone(),
             offline: false,
             head: None,
+            skin_url: None,
+            profile_fetched_at: None,
+            needs_reauth: false,
         }
     }
+
+    pub fn is_profile_outdated(&self, now: i64) -> bool {
+        self.profile_fetched_at.is_none_or(|fetched_at| now - fetched_at >= PROFILE_REFRESH_INTERVAL_SECS)
+    }
 }
@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use base64::Engine;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::BackendState;
+
+// Public profiles don't need an access token, so they can be refreshed without logging in
+const SESSION_PROFILE_URL: &str = "https://sessionserver.mojang.com/session/minecraft/profile";
+
+#[derive(Deserialize)]
+struct SessionProfile {
+    name: Arc<str>,
+    #[serde(default)]
+    properties: Vec<SessionProfileProperty>,
+}
+
+#[derive(Deserialize)]
+struct SessionProfileProperty {
+    name: Arc<str>,
+    value: Arc<str>,
+}
+
+#[derive(Deserialize)]
+struct SessionProfileTextures {
+    textures: SessionProfileTextureSet,
+}
+
+#[derive(Deserialize)]
+struct SessionProfileTextureSet {
+    #[serde(rename = "SKIN")]
+    skin: Option<SessionProfileTexture>,
+}
+
+#[derive(Deserialize)]
+struct SessionProfileTexture {
+    url: Arc<str>,
+}
+
+impl SessionProfile {
+    // The textures property is base64 encoded json
+    fn skin_url(&self) -> Option<Arc<str>> {
+        let property = self.properties.iter().find(|property| &*property.name == "textures")?;
+        let json = base64::engine::general_purpose::STANDARD.decode(&*property.value).ok()?;
+        let textures: SessionProfileTextures = serde_json::from_slice(&json).ok()?;
+        Some(textures.textures.skin?.url)
+    }
+}
+
+impl BackendState {
+    // Accounts are shown from the cache right away, outdated names and skins are refreshed here in the background
+    pub async fn refresh_account_profiles(self) {
+        let now = chrono::Utc::now().timestamp();
+        let outdated: Vec<Uuid> = self.account_info.write().get().accounts.iter()
+            .filter(|(_, account)| !account.offline && account.is_profile_outdated(now))
+            .map(|(uuid, _)| *uuid)
+            .collect();
+
+        for uuid in outdated {
+            let profile = match self.fetch_session_profile(uuid).await {
+                Ok(profile) => profile,
+                Err(error) => {
+                    log::warn!("Unable to refresh profile of {uuid}: {error}");
+                    continue;
+                },
+            };
+
+            let skin_url = profile.skin_url();
+            self.account_info.write().modify(|info| {
+                if let Some(account) = info.accounts.get_mut(&uuid) {
+                    account.username = profile.name.clone();
+                    account.profile_fetched_at = Some(now);
+                }
+            });
+            self.update_account_head(uuid, skin_url);
+        }
+    }
+
+    async fn fetch_session_profile(&self, uuid: Uuid) -> reqwest::Result<SessionProfile> {
+        self.http_client.get(format!("{SESSION_PROFILE_URL}/{}", uuid.simple()))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    }
+}
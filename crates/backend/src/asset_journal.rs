@@ -0,0 +1,114 @@
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
+
+// Records which asset objects have already been verified, so that an interrupted install can pick up where it left
+// off and later launches don't need to hash thousands of objects again. An entry is only trusted while the size
+// and modification time of the object on disk are unchanged
+pub struct AssetJournal {
+    verified: FxHashMap<Box<str>, JournalEntry>,
+    file: Mutex<Option<File>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct JournalEntry {
+    size: u64,
+    modified: u128,
+}
+
+impl AssetJournal {
+    // When not trusting existing entries every object is verified again, but the results are still recorded
+    pub fn open(path: PathBuf, trust_existing: bool) -> Self {
+        let mut verified: FxHashMap<Box<str>, JournalEntry> = FxHashMap::default();
+        let mut line_count = 0;
+
+        if let Ok(contents) = std::fs::read_to_string(&path) {
+            for line in contents.lines() {
+                line_count += 1;
+                if let Some((hash, entry)) = parse_line(line) {
+                    verified.insert(hash.into(), entry);
+                }
+            }
+        }
+
+        // Objects which change are appended again, so rewrite the journal once it has accumulated many stale lines
+        if line_count > verified.len() * 2 + 64 {
+            let mut contents = String::new();
+            for (hash, entry) in &verified {
+                contents.push_str(&format_line(hash, *entry));
+            }
+            _ = crate::write_safe(&path, contents.as_bytes());
+        }
+
+        let file = match File::options().create(true).append(true).open(&path) {
+            Ok(file) => Some(file),
+            Err(error) => {
+                log::warn!("Unable to open asset journal {:?}, objects will be verified every launch: {error}", path);
+                None
+            },
+        };
+
+        if !trust_existing {
+            verified.clear();
+        }
+
+        Self {
+            verified,
+            file: Mutex::new(file),
+        }
+    }
+
+    pub fn is_verified(&self, hash: &str, path: &Path, size: u64) -> bool {
+        let Some(journal_entry) = self.verified.get(hash) else {
+            return false;
+        };
+        journal_entry.size == size && read_entry(path).as_ref() == Some(journal_entry)
+    }
+
+    pub fn record(&self, hash: &str, path: &Path) {
+        let Some(entry) = read_entry(path) else {
+            return;
+        };
+        if self.verified.get(hash) == Some(&entry) {
+            return;
+        }
+
+        let mut file = self.file.lock();
+        if let Some(journal_file) = &mut *file
+            && let Err(error) = journal_file.write_all(format_line(hash, entry).as_bytes())
+        {
+            log::warn!("Unable to write to asset journal: {error}");
+            *file = None;
+        }
+    }
+}
+
+fn read_entry(path: &Path) -> Option<JournalEntry> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_nanos();
+    Some(JournalEntry {
+        size: metadata.len(),
+        modified,
+    })
+}
+
+fn parse_line(line: &str) -> Option<(&str, JournalEntry)> {
+    let mut parts = line.split(' ');
+    let hash = parts.next()?;
+    let size = parts.next()?.parse().ok()?;
+    let modified = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((hash, JournalEntry { size, modified }))
+}
+
+fn format_line(hash: &str, entry: JournalEntry) -> String {
+    format!("{} {} {}\n", hash, entry.size, entry.modified)
+}
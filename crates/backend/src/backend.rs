@@ -3,7 +3,7 @@ use std::{
 };
 
 use auth::{
-    authenticator::{Authenticator, MsaAuthorizationError, XboxAuthenticateError},
+    authenticator::{AccountProblem, Authenticator, MsaAuthorizationError, XboxAuthenticateError},
     credentials::{AccountCredentials, AUTH_STAGE_COUNT},
     models::{MinecraftAccessToken, MinecraftProfileResponse, SkinState},
     secret::{PlatformSecretStorage, SecretStorageError},
@@ -11,25 +11,26 @@ use auth::{
 };
 use base64::Engine;
 use bridge::{
-    handle::{BackendHandle, BackendReceiver, FrontendHandle}, install::{ContentDownload, ContentInstall, ContentInstallFile, ContentInstallPath}, instance::{ContentType, InstanceContentSummary, InstanceID, InstanceServerSummary, InstanceWorldSummary}, message::{EmbeddedOrRaw, MessageToFrontend}, modal_action::{ModalAction, ModalActionVisitUrl, ProgressTracker, ProgressTrackerFinishType}, safe_path::SafePath
+    game_output::GameOutputLogLevel, handle::{BackendHandle, BackendReceiver, FrontendHandle}, job::JobPriority, install::{ContentDownload, ContentInstall, ContentInstallFile, ContentInstallPath}, instance::{ContentType, InstanceContentID, InstanceContentSummary, InstanceID, InstanceServerSummary, InstanceStatus, InstanceWorldSummary}, message::{EmbeddedOrRaw, MessageToFrontend, PersistentFile}, meta::MetadataRequest, modal_action::{ModalAction, ModalActionVisitUrl, ProgressTracker, ProgressTrackerFinishType}, safe_path::SafePath
 };
 use image::ImageFormat;
 use indexmap::IndexSet;
 use parking_lot::RwLock;
 use reqwest::{StatusCode, redirect::Policy};
 use rustc_hash::{FxHashMap, FxHashSet};
-use schema::{auxiliary::AuxiliaryContentMeta, backend_config::BackendConfig, instance::InstanceConfiguration, loader::Loader, modrinth::ModrinthSideRequirement};
+use schema::{auxiliary::AuxiliaryContentMeta, backend_config::BackendConfig, launch_metrics::LaunchMetrics, instance::{InstanceConfiguration, InstanceType}, instance_history::InstanceHistoryEvent, loader::Loader, modrinth::ModrinthSideRequirement};
 use serde::Deserialize;
-use sha1::{Digest, Sha1};
+use strum::IntoEnumIterator;
 use tokio::sync::{mpsc::Receiver, OnceCell};
+use tokio_util::sync::CancellationToken;
 use ustr::Ustr;
 use uuid::Uuid;
 
 use crate::{
-    account::{BackendAccountInfo, MinecraftLoginInfo}, directories::LauncherDirectories, id_slab::IdSlab, instance::{Instance, ContentFolder}, launch::Launcher, metadata::{items::MinecraftVersionManifestMetadataItem, manager::MetadataManager}, mod_metadata::ModMetadataManager, persistent::Persistent
+    account::{BackendAccountInfo, MinecraftLoginInfo}, content_download_queue::ContentDownloadQueue, content_library::{ContentHash, ContentLibrary, FileHashes}, data_location::LauncherLocation, directories::LauncherDirectories, event_stream::EventStream, id_slab::IdSlab, instance::{Instance, ContentFolder}, job_queue::JobQueue, launch::{LaunchError, Launcher}, log_reader, server_ping::ServerStatusCacheEntry, server_properties::ServerProperties, tunnel, metadata::{items::MinecraftVersionManifestMetadataItem, manager::MetadataManager}, mod_metadata::ModMetadataManager, parental_controls::ParentalControlsSession, pending_instance::PendingInstance, persistent::{Persistent, PersistentRecovery}
 };
 
-pub fn start(launcher_dir: PathBuf, send: FrontendHandle, self_handle: BackendHandle, recv: BackendReceiver) {
+pub fn start(location: LauncherLocation, send: FrontendHandle, self_handle: BackendHandle, recv: BackendReceiver) {
     let runtime = tokio::runtime::Builder::new_multi_thread()
         .worker_threads(1)
         .enable_all()
@@ -57,13 +58,17 @@ pub fn start(launcher_dir: PathBuf, send: FrontendHandle, self_handle: BackendHa
         .build()
         .unwrap();
 
-    let directories = Arc::new(LauncherDirectories::new(launcher_dir));
+    location.migrate_legacy_cache();
+    let directories = Arc::new(LauncherDirectories::new(&location));
 
     let meta = Arc::new(MetadataManager::new(
         http_client.clone(),
         directories.metadata_dir.clone(),
     ));
 
+    let metadata_dir = directories.metadata_dir.clone();
+    std::thread::spawn(move || crate::metadata::items::prune_modrinth_cache(&metadata_dir));
+
     let (watcher_tx, watcher_rx) = tokio::sync::mpsc::channel::<notify_debouncer_full::DebounceEventResult>(64);
     let watcher = notify_debouncer_full::new_debouncer(Duration::from_millis(100), None, move |event| {
         let _ = watcher_tx.blocking_send(event);
@@ -76,6 +81,7 @@ pub fn start(launcher_dir: PathBuf, send: FrontendHandle, self_handle: BackendHa
         instance_by_path: HashMap::new(),
         instances_generation: 0,
         reload_immediately: Default::default(),
+        scheduled_content_reloads: Default::default(),
     };
 
     let mut state_file_watching = BackendStateFileWatching {
@@ -90,10 +96,21 @@ pub fn start(launcher_dir: PathBuf, send: FrontendHandle, self_handle: BackendHa
     state_file_watching.watch_filesystem(directories.root_launcher_dir.clone(), WatchTarget::RootDir);
 
     // Load accounts
-    let account_info = Persistent::load(directories.accounts_json.clone());
+    let (account_info, accounts_recovery) = Persistent::load_recovering(directories.accounts_json.clone());
 
     // Load config
-    let config = Persistent::load(directories.config_json.clone());
+    let (mut config, config_recovery): (Persistent<BackendConfig>, _) = Persistent::load_recovering(directories.config_json.clone());
+    crate::log_files::set_debug_logging(config.get().debug_logging);
+
+    let launch_metrics = Persistent::load(directories.launch_metrics_json.clone());
+
+    let content_library = ContentLibrary::load(directories.content_library_dir.clone(), directories.content_library_index_json.clone());
+
+    let shutdown = recv.shutdown_token();
+
+    // Observed before the initial load, so the event stream knows every instance
+    let event_stream = Arc::new(EventStream::default());
+    send.set_observer(event_stream.clone());
 
     let mut state = BackendState {
         self_handle,
@@ -106,14 +123,32 @@ pub fn start(launcher_dir: PathBuf, send: FrontendHandle, self_handle: BackendHa
         directories: Arc::clone(&directories),
         launcher: Launcher::new(meta, directories, send),
         mod_metadata_manager: Arc::new(mod_metadata_manager),
+        content_downloads: Default::default(),
+        jobs: Default::default(),
+        content_library: Arc::new(content_library),
         account_info: Arc::new(RwLock::new(account_info)),
         config: Arc::new(RwLock::new(config)),
+        launch_metrics: Arc::new(RwLock::new(launch_metrics)),
+        parental_controls: Default::default(),
         secret_storage: Arc::new(OnceCell::new()),
         head_cache: Default::default(),
+        server_status_cache: Default::default(),
+        pending_metadata_requests: Default::default(),
+        event_stream,
+        shutdown,
     };
 
     log::debug!("Doing initial backend load");
 
+    if let Some(recovery) = accounts_recovery {
+        let backups = state.account_info.read().valid_backups();
+        state.send_recovery_message(PersistentFile::Accounts, recovery, backups);
+    }
+    if let Some(recovery) = config_recovery {
+        let backups = state.config.read().valid_backups();
+        state.send_recovery_message(PersistentFile::Config, recovery, backups);
+    }
+
     runtime.block_on(async {
         state.send.send(state.account_info.write().get().create_update_message());
         state.load_all_instances().await;
@@ -142,6 +177,7 @@ pub struct BackendStateInstances {
     pub instance_by_path: HashMap<PathBuf, InstanceID>,
     pub instances_generation: usize,
     pub reload_immediately: FxHashSet<(InstanceID, ContentFolder)>,
+    pub scheduled_content_reloads: FxHashSet<(InstanceID, ContentFolder)>,
 }
 
 pub struct BackendStateFileWatching {
@@ -163,10 +199,20 @@ pub struct BackendState {
     pub directories: Arc<LauncherDirectories>,
     pub launcher: Launcher,
     pub mod_metadata_manager: Arc<ModMetadataManager>,
+    pub content_downloads: Arc<ContentDownloadQueue>,
+    pub jobs: Arc<JobQueue>,
+    pub content_library: Arc<ContentLibrary>,
     pub account_info: Arc<RwLock<Persistent<BackendAccountInfo>>>,
     pub config: Arc<RwLock<Persistent<BackendConfig>>>,
+    pub launch_metrics: Arc<RwLock<Persistent<LaunchMetrics>>>,
+    pub parental_controls: Arc<RwLock<ParentalControlsSession>>,
     pub secret_storage: Arc<OnceCell<Result<PlatformSecretStorage, SecretStorageError>>>,
-    pub head_cache: Arc<RwLock<FxHashMap<Arc<str>, HeadCacheEntry>>>
+    pub head_cache: Arc<RwLock<FxHashMap<Arc<str>, HeadCacheEntry>>>,
+    pub server_status_cache: Arc<RwLock<FxHashMap<Arc<str>, ServerStatusCacheEntry>>>,
+    pub pending_metadata_requests: Arc<RwLock<FxHashMap<MetadataRequest, tokio::task::AbortHandle>>>,
+    pub event_stream: Arc<EventStream>,
+    // Cancelled by the frontend when the launcher is closing
+    pub shutdown: CancellationToken,
 }
 
 pub enum HeadCacheEntry {
@@ -180,13 +226,74 @@ pub enum HeadCacheEntry {
 }
 
 impl BackendState {
+    fn send_recovery_message(&self, file: PersistentFile, recovery: PersistentRecovery, backups: Vec<PathBuf>) {
+        let restored_from: Option<Arc<Path>> = recovery.restored_from.map(Into::into);
+        let backups = backups.into_iter()
+            .map(Arc::<Path>::from)
+            .filter(|backup| Some(backup) != restored_from.as_ref())
+            .collect();
+        self.send.send(MessageToFrontend::PersistentFileRecovered {
+            file,
+            corrupt_path: recovery.corrupt_path.into(),
+            restored_from,
+            backups,
+        });
+    }
+
+    pub fn restore_persistent_backup(&self, file: PersistentFile, backup: &Path) {
+        let result = match file {
+            PersistentFile::Config => self.config.write().restore_backup(backup),
+            PersistentFile::Accounts => self.account_info.write().restore_backup(backup),
+        };
+
+        match result {
+            Ok(()) => {
+                if file == PersistentFile::Accounts {
+                    self.send.send(self.account_info.write().get().create_update_message());
+                }
+                self.send.send_success(format!("Restored {} from backup", file.file_name()));
+            },
+            Err(error) => {
+                log::error!("Unable to restore {backup:?}: {error}");
+                self.send.send_error(format!("Unable to restore {}: {error}", file.file_name()));
+            },
+        }
+    }
+
     async fn start(self, recv: BackendReceiver, watcher_rx: Receiver<notify_debouncer_full::DebounceEventResult>) {
         log::info!("Starting backend");
 
-        tokio::task::spawn(crate::update::check_for_updates(self.redirecting_http_client.clone(), self.send.clone()));
+        let backend = self.clone();
+        tokio::task::spawn(async move {
+            let options = backend.update_check_options();
+            let update = crate::update::check_for_updates(backend.redirecting_http_client.clone(), backend.send.clone(), options);
+            backend.run_job("Checking for launcher updates", JobPriority::Background, update).await;
+        });
+        tokio::task::spawn(self.clone().load_modrinth_token());
+        let backend = self.clone();
+        tokio::task::spawn(async move {
+            backend.run_job("Refreshing account profiles", JobPriority::Background, backend.clone().refresh_account_profiles()).await;
+        });
+        self.update_event_stream();
+
+        let backend = self.clone();
+        tokio::task::spawn(async move {
+            let _job = backend.start_job("Indexing content library", JobPriority::Background).await;
+            let content_library = backend.content_library.clone();
+            _ = tokio::task::spawn_blocking(move || content_library.migrate()).await;
+        });
+
+        let backend = self.clone();
+        tokio::task::spawn(async move {
+            let _job = backend.start_job("Cleaning up unfinished instances", JobPriority::Background).await;
+            let backend = backend.clone();
+            _ = tokio::task::spawn_blocking(move || backend.remove_orphaned_pending_instances()).await;
+        });
 
         // Pre-fetch version manifest
         self.meta.load(&MinecraftVersionManifestMetadataItem).await;
+        tokio::task::spawn(self.clone().watch_for_new_minecraft_versions());
+        tokio::task::spawn(self.clone().run_scheduled_backups());
 
         self.handle(recv, watcher_rx).await;
     }
@@ -194,7 +301,7 @@ impl BackendState {
     pub async fn load_all_instances(&mut self) {
         log::info!("Loading all instances");
 
-        let mut paths_with_time = Vec::new();
+        let mut paths = Vec::new();
 
         self.file_watching.write().watch_filesystem(self.directories.instances_dir.clone(), WatchTarget::InstancesDir);
         for entry in std::fs::read_dir(&self.directories.instances_dir).unwrap() {
@@ -203,8 +310,21 @@ impl BackendState {
                 continue;
             };
 
-            let path = entry.path();
+            paths.push(entry.path());
+        }
 
+        let external_instances = self.reconcile_external_instances();
+        for path in external_instances {
+            if path.is_dir() {
+                paths.push(path);
+            } else {
+                log::warn!("External instance folder {:?} is missing", path);
+                self.send.send_warning(format!("Unable to find instance folder {}, is the drive it's stored on connected?", path.display()));
+            }
+        }
+
+        let mut paths_with_time = Vec::new();
+        for path in paths {
             let mut time = SystemTime::UNIX_EPOCH;
             if let Ok(metadata) = path.metadata() {
                 if let Ok(created) = metadata.created() {
@@ -232,12 +352,28 @@ impl BackendState {
         }
 
         paths_with_time.sort_by_key(|(_, time)| *time);
+        let mut stray_paths = Vec::new();
         for (path, _) in paths_with_time {
+            // Folders which were never instances aren't an error, the user is offered to adopt them instead
+            if self.is_stray_instance_folder(&path) {
+                self.file_watching.write().watch_filesystem(path.as_path().into(), WatchTarget::InvalidInstanceDir);
+                stray_paths.push(path);
+                continue;
+            }
+
             let success = self.load_instance_from_path(&path, true, false);
             if !success {
                 self.file_watching.write().watch_filesystem(path.into(), WatchTarget::InvalidInstanceDir);
             }
         }
+
+        if !stray_paths.is_empty() {
+            let send = self.send.clone();
+            tokio::task::spawn_blocking(move || {
+                let instances = stray_paths.iter().map(|path| crate::stray_instances::inspect_stray_instance(path)).collect();
+                send.send(MessageToFrontend::StrayInstancesFound { instances });
+            });
+        }
     }
 
     pub fn remove_instance(&mut self, id: InstanceID) {
@@ -246,6 +382,7 @@ impl BackendState {
         let mut instance_state = self.instance_state.write();
 
         if let Some(instance) = instance_state.instances.remove(id) {
+            instance.cancel_tasks.cancel();
             self.send.send(MessageToFrontend::InstanceRemoved { id });
             self.send.send_info(format!("Instance '{}' removed", instance.name));
         }
@@ -262,6 +399,7 @@ impl BackendState {
                 if let Some(existing) = instance_state.instance_by_path.get(path)
                     && let Some(existing_instance) = instance_state.instances.remove(*existing)
                 {
+                    existing_instance.cancel_tasks.cancel();
                     self.send.send(MessageToFrontend::InstanceRemoved { id: existing_instance.id});
                     show_errors = true;
                 }
@@ -289,6 +427,21 @@ impl BackendState {
                 return true;
             }
 
+            // Instances from before uuids existed don't have one yet, and a folder copied by hand has the uuid of the
+            // original, in both cases the instance needs a new one to be referred to on its own. An instance whose
+            // folder is gone was renamed and is about to be removed, so it doesn't count
+            let uuid = instance.configuration.get().uuid;
+            let needs_uuid = uuid.is_none_or(|uuid| {
+                instance_state.instances.iter_mut().any(|other| {
+                    other.configuration.get().uuid == Some(uuid) && other.root_path.exists()
+                })
+            });
+            if needs_uuid {
+                instance.configuration.modify(|configuration| {
+                    configuration.uuid = Some(Uuid::new_v4());
+                });
+            }
+
             let generation = instance_state.instances_generation;
             instance_state.instances_generation = instance_state.instances_generation.wrapping_add(1);
 
@@ -351,6 +504,12 @@ impl BackendState {
                 },
                 _ = interval.tick() => {
                     self.handle_tick().await;
+                },
+                _ = self.shutdown.cancelled() => {
+                    log::info!("Backend is shutting down");
+                    self.content_library.save();
+                    crate::update::run_pending_installer();
+                    break;
                 }
             }
         }
@@ -359,21 +518,67 @@ impl BackendState {
     async fn handle_tick(&mut self) {
         self.meta.expire().await;
 
+        if self.meta.take_modrinth_token_rejected() {
+            self.set_modrinth_needs_relogin();
+        }
+
         let mut instance_state = self.instance_state.write();
         for instance in instance_state.instances.iter_mut() {
             if let Some(child) = &mut instance.child
-                && !matches!(child.try_wait(), Ok(None))
+                && let status = child.try_wait()
+                && !matches!(status, Ok(None))
             {
                 log::debug!("Child process is no longer alive");
+                let pid = child.id();
                 instance.child = None;
+                if let Some((started_at, running_since)) = instance.metrics_launch.take() {
+                    let crashed = matches!(&status, Ok(Some(status)) if !status.success());
+                    self.record_launch_exit(started_at, running_since.elapsed(), crashed);
+                    self.capture_performance(instance, started_at, running_since.elapsed());
+                }
+                if let Ok(Some(status)) = status {
+                    if !status.success() {
+                        self.collect_jvm_crash(instance, pid);
+                    }
+                    self.send.send(MessageToFrontend::InstanceExited { id: instance.id, exit_code: status.code() });
+                }
+                if instance.tunnel.take().is_some() {
+                    self.send.send(MessageToFrontend::InstanceTunnelUpdated { id: instance.id, address: None });
+                }
                 self.send.send(instance.create_modify_message());
             }
         }
+
+        let playing = instance_state.instances.iter_mut()
+            .any(|instance| instance.child.is_some() && instance.configuration.get().instance_type != InstanceType::Server);
+        if self.track_playtime(playing) {
+            self.send.send_warning("The daily playtime limit set by parental controls has been reached, closing the game");
+            for instance in instance_state.instances.iter_mut() {
+                if instance.configuration.get().instance_type == InstanceType::Server {
+                    continue;
+                }
+                if let Some(mut child) = instance.child.take() {
+                    log::info!("Closing {} due to the parental controls playtime limit", instance.name);
+                    if let Err(err) = child.kill() {
+                        log::error!("Failed to kill instance: {:?}", err);
+                    }
+                    if let Some((started_at, running_since)) = instance.metrics_launch.take() {
+                        self.record_launch_exit(started_at, running_since.elapsed(), false);
+                        self.capture_performance(instance, started_at, running_since.elapsed());
+                    }
+                    if instance.tunnel.take().is_some() {
+                        self.send.send(MessageToFrontend::InstanceTunnelUpdated { id: instance.id, address: None });
+                    }
+                    self.send.send(instance.create_modify_message());
+                }
+            }
+        }
     }
 
     pub async fn login(
         &self,
         credentials: &mut AccountCredentials,
+        account: Option<Uuid>,
         login_tracker: &ProgressTracker,
         modal_action: &ModalAction,
     ) -> Result<(MinecraftProfileResponse, MinecraftAccessToken), LoginError> {
@@ -414,6 +619,32 @@ impl BackendState {
             last_auth_stage = Some(stage);
 
             match credentials.stage() {
+                auth::credentials::AuthStageWithData::Initial if self.config.write().get().device_code_login => {
+                    log::debug!("Auth Flow: Initial (device code)");
+
+                    let pending = authenticator.create_device_authorization().await?;
+                    modal_action.set_visit_url(ModalActionVisitUrl {
+                        message: format!("Go to {} and enter the code {}", pending.verification_uri(), pending.user_code()).into(),
+                        url: pending.verification_uri().into(),
+                        code: Some(pending.user_code().into()),
+                        prevent_auto_finish: false,
+                    });
+                    self.send.send(MessageToFrontend::Refresh);
+
+                    log::debug!("Waiting for the device code to be entered");
+                    let msa_tokens = tokio::select! {
+                        msa_tokens = authenticator.finish_device_authorization(&pending) => msa_tokens?,
+                        _ = modal_action.request_cancel.cancelled() => {
+                            return Err(LoginError::CancelledByUser);
+                        }
+                    };
+
+                    modal_action.unset_visit_url();
+                    self.send.send(MessageToFrontend::Refresh);
+
+                    credentials.msa_access = Some(msa_tokens.access);
+                    credentials.msa_refresh = msa_tokens.refresh;
+                },
                 auth::credentials::AuthStageWithData::Initial => {
                     log::debug!("Auth Flow: Initial");
 
@@ -421,6 +652,7 @@ impl BackendState {
                     modal_action.set_visit_url(ModalActionVisitUrl {
                         message: "Login with Microsoft".into(),
                         url: pending.url.as_str().into(),
+                        code: None,
                         prevent_auto_finish: false,
                     });
                     self.send.send(MessageToFrontend::Refresh);
@@ -453,6 +685,10 @@ impl BackendState {
                             credentials.msa_refresh = msa_tokens.refresh;
                         },
                         Ok(None) => {
+                            // The refresh token was revoked, e.g. because the password was changed
+                            if let Some(account) = account {
+                                self.set_account_needs_reauth(account);
+                            }
                             if !allow_backwards {
                                 return Err(MsaAuthorizationError::InvalidGrant.into());
                             }
@@ -462,7 +698,11 @@ impl BackendState {
                             if !allow_backwards || error.is_connection_error() {
                                 return Err(error.into());
                             }
-                            if !matches!(error, MsaAuthorizationError::InvalidGrant) {
+                            if let MsaAuthorizationError::InvalidGrant = error {
+                                if let Some(account) = account {
+                                    self.set_account_needs_reauth(account);
+                                }
+                            } else {
                                 log::warn!("Error using msa refresh to get msa access: {:?}", error);
                             }
                             credentials.msa_refresh = None;
@@ -494,6 +734,9 @@ impl BackendState {
                         Ok(xsts) => {
                             credentials.xsts = Some(xsts);
                         },
+                        Err(XboxAuthenticateError::AccountProblem(problem)) => {
+                            return Err(LoginError::AccountProblem(problem));
+                        },
                         Err(error) => {
                             if !allow_backwards || error.is_connection_error() {
                                 return Err(error.into());
@@ -533,6 +776,9 @@ impl BackendState {
 
                             return Ok((profile, access_token));
                         },
+                        Err(XboxAuthenticateError::AccountProblem(problem)) => {
+                            return Err(LoginError::AccountProblem(problem));
+                        },
                         Err(error) => {
                             if !allow_backwards || error.is_connection_error() {
                                 return Err(error.into());
@@ -548,25 +794,46 @@ impl BackendState {
         }
     }
 
+    pub fn set_account_needs_reauth(&self, uuid: Uuid) {
+        self.account_info.write().modify(|info| {
+            if let Some(account) = info.accounts.get_mut(&uuid) {
+                account.needs_reauth = true;
+            }
+        });
+    }
+
     pub fn update_profile_head(&self, profile: &MinecraftProfileResponse) {
-        log::info!("Updating profile head for {}", profile.id);
+        let skin_url = profile.skins.iter().find(|skin| skin.state == SkinState::Active).map(|skin| skin.url.clone());
+        self.update_account_head(profile.id, skin_url);
+    }
+
+    pub fn update_account_head(&self, uuid: Uuid, skin_url: Option<Arc<str>>) {
+        log::info!("Updating profile head for {}", uuid);
 
-        let Some(skin) = profile.skins.iter().find(|skin| skin.state == SkinState::Active).cloned() else {
+        let Some(skin_url) = skin_url else {
             return;
         };
 
+        // The head is cached along with the account, so it's only made again when the skin changed
+        let cached = self.account_info.write().get().accounts.get(&uuid)
+            .is_some_and(|account| account.head.is_some() && account.skin_url.as_ref() == Some(&skin_url));
+        if cached {
+            return;
+        }
+
         let mut head_cache = self.head_cache.write();
-        if let Some(existing) = head_cache.get_mut(&skin.url) {
+        if let Some(existing) = head_cache.get_mut(&skin_url) {
             match existing {
                 HeadCacheEntry::Pending { accounts } => {
-                    accounts.push(profile.id);
+                    accounts.push(uuid);
                 },
                 HeadCacheEntry::Success { head } => {
                     let head = head.clone();
                     drop(head_cache);
                     self.account_info.write().modify(move |account_info| {
-                        if let Some(account) = account_info.accounts.get_mut(&profile.id) {
+                        if let Some(account) = account_info.accounts.get_mut(&uuid) {
                             account.head = Some(head);
+                            account.skin_url = Some(skin_url);
                         }
                     });
                 },
@@ -575,11 +842,10 @@ impl BackendState {
             return;
         }
 
-        head_cache.insert(skin.url.clone(), HeadCacheEntry::Pending { accounts: vec![profile.id] });
+        head_cache.insert(skin_url.clone(), HeadCacheEntry::Pending { accounts: vec![uuid] });
 
         let head_cache = self.head_cache.clone();
         let account_info = self.account_info.clone();
-        let skin_url = skin.url;
 
         let http_client = self.http_client.clone();
 
@@ -637,6 +903,7 @@ impl BackendState {
                 for uuid in accounts {
                     if let Some(account) = info.accounts.get_mut(&uuid) {
                         account.head = Some(head_png.clone());
+                        account.skin_url = Some(skin_url.clone());
                     }
                 }
             });
@@ -655,7 +922,8 @@ impl BackendState {
             return;
         };
 
-        crate::syncing::apply_to_instance(self.config.write().get().sync_targets, &self.directories, path);
+        let instance_roots = self.instance_roots();
+        crate::syncing::apply_to_instance(self.config.write().get().sync_targets, &self.directories, &instance_roots, path);
     }
 
     pub async fn prelaunch_apply_modpacks(&self, id: InstanceID, modal_action: &ModalAction) -> Vec<PathBuf> {
@@ -742,6 +1010,7 @@ impl BackendState {
                             download: ContentDownload::Url {
                                 url: file.downloads[0].clone(),
                                 sha1: file.hashes.sha1.clone(),
+                                sha512: file.hashes.sha512.clone(),
                                 size: file.file_size,
                             },
                             content_source: schema::content::ContentSource::ModrinthUnknown,
@@ -771,6 +1040,7 @@ impl BackendState {
         };
 
         let mut add_mods = Vec::new();
+        let allow_links = !self.config.write().get().dont_link_content_files;
 
         for modpack_install in modpack_installs {
             let overrides = modpack_install.overrides;
@@ -828,7 +1098,7 @@ impl BackendState {
                     } else if let Some(filename) = dest_path.file_name() {
                         let filename = format!(".pandora.{filename}");
                         let hidden_dest_path = mod_dir.join(filename);
-                        let _ = std::fs::hard_link(path, hidden_dest_path);
+                        let _ = crate::linking::link_or_copy(&path, &hidden_dest_path, allow_links);
                     }
                 } else {
                     let dest_path = dest_path.to_path(&dot_minecraft_path);
@@ -840,7 +1110,7 @@ impl BackendState {
                         }
 
                         let _ = std::fs::create_dir_all(dest_path.parent().unwrap());
-                        let _ = std::fs::copy(path, dest_path);
+                        let _ = crate::linking::clone_or_copy(&path, &dest_path, allow_links);
                     }
                 }
             }
@@ -853,15 +1123,16 @@ impl BackendState {
                 tracker.notify();
 
                 for (rel_path, file) in overrides.iter() {
-                    let mut hasher = Sha1::new();
-                    hasher.update(&file);
-                    let expected_hash = hasher.finalize().into();
+                    let hashes = FileHashes::of_bytes(file);
+                    let expected_hash = hashes.sha1;
 
                     let path = crate::create_content_library_path(content_library_dir, expected_hash, rel_path.extension());
 
                     if !path.exists() {
                         let _ = std::fs::create_dir_all(path.parent().unwrap());
-                        let _ = std::fs::write(&path, file);
+                        if std::fs::write(&path, file).is_ok() {
+                            self.content_library.record(&path, &hashes);
+                        }
                     }
 
                     if rel_path.starts_with("mods") && let Some(extension) = rel_path.extension() && extension == "jar" {
@@ -870,7 +1141,7 @@ impl BackendState {
                         } else if let Some(filename) = rel_path.file_name() {
                             let filename = format!(".pandora.{filename}");
                             let hidden_dest_path = mod_dir.join(filename);
-                            let _ = std::fs::hard_link(path, hidden_dest_path);
+                            let _ = crate::linking::link_or_copy(&path, &hidden_dest_path, allow_links);
                         }
                     } else {
                         let dest_path = rel_path.to_path(&dot_minecraft_path);
@@ -883,7 +1154,7 @@ impl BackendState {
                             }
 
                             let _ = std::fs::create_dir_all(dest_path.parent().unwrap());
-                            let _ = std::fs::copy(path, dest_path);
+                            let _ = crate::linking::clone_or_copy(&path, &dest_path, allow_links);
                         }
                     }
                     tracker.add_count(1);
@@ -897,6 +1168,7 @@ impl BackendState {
                 }
 
                 tracker.set_finished(ProgressTrackerFinishType::Fast);
+                self.content_library.save();
             }
         }
 
@@ -905,6 +1177,294 @@ impl BackendState {
         add_mods
     }
 
+    // Servers don't need an account or content prelaunch, so they take a much shorter path than the game client
+    pub async fn start_server_instance(&self, id: InstanceID, modal_action: ModalAction) {
+        let instance_defaults = self.config.write().get().instance_defaults.clone();
+
+        let (dot_minecraft, configuration) = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+            if instance.child.is_some() {
+                self.send.send_warning("Can't start server, already running");
+                modal_action.set_error_message("Can't start server, already running".into());
+                modal_action.set_finished();
+                return;
+            }
+
+            self.send.send(MessageToFrontend::MoveInstanceToTop {
+                id
+            });
+            self.send.send(instance.create_modify_message_with_status(InstanceStatus::Launching));
+
+            (instance.dot_minecraft_path.clone(), instance.configuration.get().clone().with_defaults(&instance_defaults))
+        } else {
+            self.send.send_error("Can't start server, unknown id");
+            modal_action.set_error_message("Can't start server, unknown id".into());
+            modal_action.set_finished();
+            return;
+        };
+
+        let launch_tracker = ProgressTracker::new(Arc::from("Starting server"), self.send.clone());
+        modal_action.trackers.push(launch_tracker.clone());
+
+        let result = self.launcher.launch_server(&self.redirecting_http_client, dot_minecraft, configuration, &launch_tracker, &modal_action).await;
+
+        if matches!(result, Err(LaunchError::CancelledByUser)) {
+            self.send.send(MessageToFrontend::CloseModal);
+            if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                self.send.send(instance.create_modify_message());
+            }
+            return;
+        }
+
+        let is_err = result.is_err();
+        match result {
+            Ok((mut child, log4j_mitigation)) => {
+                if let Some(log4j_mitigation) = log4j_mitigation {
+                    log::info!("{}", log4j_mitigation.description());
+                }
+
+                // The console is always opened for servers since it's the only way to interact with them
+                if let Some(stdout) = child.stdout.take() {
+                    let output_id = log_reader::start_game_output(stdout, child.stderr.take(), Some(id), self.send.clone());
+                    if let Some(log4j_mitigation) = log4j_mitigation {
+                        log_reader::add_launcher_output(output_id, GameOutputLogLevel::Info, log4j_mitigation.description(), &self.send);
+                    }
+                }
+                child.stderr.take();
+
+                if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    instance.child = Some(child);
+                    instance.stop_requested = false;
+                    instance.mark_played();
+                    instance.record_history(InstanceHistoryEvent::Launched);
+                }
+            },
+            Err(ref err) => {
+                log::error!("Failed to start server due to error: {:?}", &err);
+                modal_action.set_error_message(format!("{}", &err).into());
+            },
+        }
+
+        if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+            self.send.send(instance.create_modify_message());
+        }
+
+        launch_tracker.set_finished(if is_err { ProgressTrackerFinishType::Error } else { ProgressTrackerFinishType::Normal });
+        launch_tracker.notify();
+        modal_action.set_finished();
+    }
+
+    // Downloads everything the instance needs without launching it, so that it can be played offline later
+    pub async fn prepare_instance(&self, id: InstanceID, modal_action: ModalAction) {
+        let (instance_defaults, natives_repositories) = {
+            let mut config = self.config.write();
+            let config = config.get();
+            (config.instance_defaults.clone(), config.natives_repositories.clone())
+        };
+
+        let (name, dot_minecraft, configuration) = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+            if instance.child.is_some() {
+                modal_action.set_error_message("Can't download files while the instance is running".into());
+                modal_action.set_finished();
+                return;
+            }
+
+            (instance.name, instance.dot_minecraft_path.clone(), instance.configuration.get().clone().with_defaults(&instance_defaults))
+        } else {
+            self.send.send_error("Can't download files, unknown id");
+            modal_action.set_error_message("Can't download files, unknown id".into());
+            modal_action.set_finished();
+            return;
+        };
+
+        // Modpack content is downloaded as part of the prelaunch, so it needs to happen here as well
+        if configuration.instance_type != InstanceType::Server {
+            tokio::select! {
+                _ = self.prelaunch_apply_modpacks(id, &modal_action) => {},
+                _ = modal_action.request_cancel.cancelled() => {
+                    self.send.send(MessageToFrontend::CloseModal);
+                    return;
+                }
+            }
+
+            if modal_action.error.read().unwrap().is_some() {
+                modal_action.set_finished();
+                return;
+            }
+        }
+
+        let prepare_tracker = ProgressTracker::new(Arc::from("Downloading game files"), self.send.clone());
+        modal_action.trackers.push(prepare_tracker.clone());
+
+        let result = self.launcher.prepare(&self.redirecting_http_client, dot_minecraft, configuration, &natives_repositories, &prepare_tracker, &modal_action).await;
+
+        match result {
+            Ok(()) => {
+                self.send.send_success(format!("{name} is ready to play offline"));
+                prepare_tracker.set_finished(ProgressTrackerFinishType::Normal);
+            },
+            Err(LaunchError::CancelledByUser) => {
+                self.send.send(MessageToFrontend::CloseModal);
+                return;
+            },
+            Err(err) => {
+                log::error!("Failed to download files due to error: {:?}", &err);
+                modal_action.set_error_message(format!("{}", &err).into());
+                prepare_tracker.set_finished(ProgressTrackerFinishType::Error);
+            },
+        }
+
+        prepare_tracker.notify();
+        modal_action.set_finished();
+    }
+
+    // Exposes the LAN world or server of a running instance through the configured tunnel command
+    pub async fn start_instance_tunnel(self, id: InstanceID) {
+        let (dot_minecraft, is_server) = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+            if instance.child.is_none() {
+                self.send.send_warning("Can't share instance, instance isn't running");
+                return;
+            }
+            if instance.tunnel.is_some() {
+                self.send.send_warning("Instance is already being shared");
+                return;
+            }
+            (instance.dot_minecraft_path.clone(), instance.configuration.get().instance_type == InstanceType::Server)
+        } else {
+            return;
+        };
+
+        let port = if is_server {
+            ServerProperties::load(&dot_minecraft.join("server.properties")).ok()
+                .and_then(|properties| properties.server_properties().server_port)
+                .unwrap_or(25565)
+        } else if let Some(port) = tunnel::find_lan_port(&dot_minecraft) {
+            port
+        } else {
+            self.send.send_warning("Open the world to LAN before sharing it");
+            return;
+        };
+
+        let command = self.config.write().get().tunnel_command.clone();
+        let command = command.as_deref().unwrap_or(tunnel::DEFAULT_TUNNEL_COMMAND);
+
+        self.send.send_info(format!("Starting tunnel for port {port}..."));
+
+        match tunnel::start_tunnel(command, port).await {
+            Ok(tunnel) => {
+                // The tunnel is dropped (and closed) if the instance stopped while it was starting
+                if let Some(instance) = self.instance_state.write().instances.get_mut(id)
+                    && instance.child.is_some()
+                {
+                    let address = tunnel.address.clone();
+                    instance.tunnel = Some(tunnel);
+                    self.send.send(MessageToFrontend::InstanceTunnelUpdated { id, address: Some(address) });
+                }
+            },
+            Err(err) => {
+                log::error!("Unable to start tunnel: {err:?}");
+                self.send.send_error(format!("Unable to share instance: {err}"));
+            },
+        }
+    }
+
+    // Re-hashes the content library files used by modpacks installed in the instance, removing any that are
+    // corrupt and downloading them again. Returns the number of files checked and the number repaired
+    pub async fn verify_instance_content(&self, id: InstanceID, modal_action: &ModalAction) -> (usize, usize) {
+        let (loader, minecraft_version) = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+            let configuration = instance.configuration.get();
+            (configuration.loader, configuration.minecraft_version)
+        } else {
+            return (0, 0);
+        };
+
+        let Some(mods) = self.clone().load_instance_content(id, ContentFolder::Mods).await else {
+            return (0, 0);
+        };
+
+        let mut files = Vec::new();
+        for summary in &*mods {
+            if let ContentType::ModrinthModpack { downloads, .. } = &summary.content_summary.extra {
+                for download in downloads.iter() {
+                    if let Some(env) = download.env && env.client == ModrinthSideRequirement::Unsupported {
+                        continue;
+                    }
+                    let Some(sha1) = ContentHash::parse_sha1(&download.hashes.sha1) else {
+                        continue;
+                    };
+                    let mut expected = vec![sha1];
+                    expected.extend(download.hashes.sha512.as_deref().and_then(ContentHash::parse_sha512));
+                    let Some(path) = SafePath::new(&download.path) else {
+                        continue;
+                    };
+                    files.push((download.clone(), path, expected));
+                }
+            }
+        }
+
+        if files.is_empty() {
+            return (0, 0);
+        }
+
+        let tracker = ProgressTracker::new("Verifying integrity of installed content".into(), self.send.clone());
+        modal_action.trackers.push(tracker.clone());
+        tracker.set_total(files.len());
+        tracker.notify();
+
+        let content_library_dir = self.directories.content_library_dir.clone();
+        let content_library = self.content_library.clone();
+        let checked = files.len();
+        let broken = {
+            let tracker = tracker.clone();
+            tokio::task::spawn_blocking(move || {
+                let mut broken = Vec::new();
+                for (download, path, expected) in files {
+                    let Some(ContentHash::Sha1(expected_hash)) = expected.first().copied() else {
+                        continue;
+                    };
+                    let library_path = crate::create_content_library_path(&content_library_dir, expected_hash, path.extension());
+                    if !content_library.verify(&library_path, &expected) {
+                        log::warn!("Content library file {:?} for {} is missing or corrupt", library_path, download.path);
+                        _ = std::fs::remove_file(&library_path);
+                        broken.push((download, path));
+                    }
+                    tracker.add_count(1);
+                    tracker.notify();
+                }
+                content_library.save();
+                broken
+            }).await.unwrap()
+        };
+
+        tracker.set_finished(ProgressTrackerFinishType::Normal);
+        tracker.notify();
+
+        let repaired = broken.len();
+        if repaired > 0 {
+            let content_install = ContentInstall {
+                target: bridge::install::InstallTarget::Library,
+                loader_hint: loader,
+                version_hint: Some(minecraft_version.into()),
+                files: broken.into_iter().map(|(download, path)| {
+                    ContentInstallFile {
+                        replace_old: None,
+                        path: ContentInstallPath::Safe(path),
+                        download: ContentDownload::Url {
+                            url: download.downloads[0].clone(),
+                            sha1: download.hashes.sha1.clone(),
+                            sha512: download.hashes.sha512.clone(),
+                            size: download.file_size,
+                        },
+                        content_source: schema::content::ContentSource::ModrinthUnknown,
+                    }
+                }).collect(),
+            };
+
+            self.install_content(content_install, modal_action.clone()).await;
+        }
+
+        (checked, repaired)
+    }
+
     pub async fn load_instance_servers(self, id: InstanceID) -> Option<Arc<[InstanceServerSummary]>> {
         if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
             let mut file_watching = self.file_watching.write();
@@ -976,6 +1536,50 @@ impl BackendState {
         result.map(|(content, _)| content)
     }
 
+    pub fn set_content_enabled(&self, id: InstanceID, content_ids: Vec<InstanceContentID>, enabled: bool) {
+        let mut instance_state = self.instance_state.write();
+        let Some(instance) = instance_state.instances.get_mut(id) else {
+            return;
+        };
+
+        let mut reload = FxHashSet::default();
+        let mut changed_ids = Vec::new();
+
+        for content_id in content_ids {
+            if let Some((instance_mod, folder)) = instance.try_get_content(content_id) {
+                if instance_mod.enabled == enabled {
+                    continue;
+                }
+
+                let mut new_path = instance_mod.path.to_path_buf();
+                if instance_mod.enabled {
+                    new_path.add_extension("disabled");
+                } else {
+                    new_path.set_extension("");
+                };
+
+                if std::fs::rename(&instance_mod.path, new_path).is_ok() {
+                    changed_ids.extend(instance_mod.content_summary.id.clone());
+                }
+                reload.insert((id, folder));
+            }
+        }
+
+        if !changed_ids.is_empty() {
+            instance.configuration.modify(|configuration| {
+                for changed_id in changed_ids {
+                    if enabled {
+                        configuration.disabled_content.remove(&changed_id);
+                    } else {
+                        configuration.disabled_content.insert(changed_id);
+                    }
+                }
+            });
+        }
+
+        instance_state.reload_immediately.extend(reload);
+    }
+
     pub async fn load_instance_worlds(self, id: InstanceID) -> Option<Arc<[InstanceWorldSummary]>> {
         if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
             let mut file_watching = self.file_watching.write();
@@ -1012,7 +1616,7 @@ impl BackendState {
         result.map(|(worlds, _)| worlds)
     }
 
-    pub async fn create_instance_sanitized(&self, name: &str, version: &str, loader: Loader, icon: Option<EmbeddedOrRaw>) -> Option<PathBuf> {
+    pub async fn begin_create_instance_sanitized(&self, name: &str, version: &str, loader: Loader, icon: Option<EmbeddedOrRaw>) -> Option<PendingInstance> {
         let mut name = sanitize_filename::sanitize_with_options(name, sanitize_filename::Options { windows: true, ..Default::default() });
 
         if self.instance_state.read().instances.iter().any(|i| i.name == name) {
@@ -1026,15 +1630,24 @@ impl BackendState {
             }
         }
 
-        return self.create_instance(&name, version, loader, icon).await;
+        return self.begin_create_instance(&name, InstanceConfiguration::new(Ustr::from(version), loader), icon).await;
     }
 
-    pub async fn create_instance(&self, name: &str, version: &str, loader: Loader, icon: Option<EmbeddedOrRaw>) -> Option<PathBuf> {
+    // The instance is only visible once the returned PendingInstance is committed, dropping it discards everything
+    pub async fn begin_create_instance(&self, name: &str, mut configuration: InstanceConfiguration, icon: Option<EmbeddedOrRaw>) -> Option<PendingInstance> {
         log::info!("Creating instance {name}");
-        if loader == Loader::Unknown {
+        if let Err(error) = self.check_can_create_instance() {
+            self.send.send_warning(format!("Unable to create instance: {error}"));
+            return None;
+        }
+        if configuration.loader == Loader::Unknown {
             self.send.send_warning(format!("Unable to create instance, unknown loader"));
             return None;
         }
+        if configuration.instance_type == InstanceType::Server && configuration.loader != Loader::Vanilla {
+            self.send.send_warning("Unable to create instance, servers are only supported for Vanilla");
+            return None;
+        }
         if !crate::is_single_component_path(&name) {
             self.send.send_warning(format!("Unable to create instance, name must not be a path: {}", name));
             return None;
@@ -1050,36 +1663,35 @@ impl BackendState {
 
         self.file_watching.write().watch_filesystem(self.directories.instances_dir.clone(), WatchTarget::InstancesDir);
 
-        let instance_dir = self.directories.instances_dir.join(name);
-
-        let _ = tokio::fs::create_dir_all(&instance_dir).await;
-
-        let instance_fallback_icon = if let Some(EmbeddedOrRaw::Embedded(e)) = &icon {
-            Some(Ustr::from(&**e))
-        } else {
-            None
+        let pending = match PendingInstance::new(&self.directories.creating_instances_dir, self.directories.instances_dir.join(name)) {
+            Ok(pending) => pending,
+            Err(err) => {
+                self.send.send_error(format!("Unable to create instance: {err}"));
+                return None;
+            },
         };
 
-        let instance_info = InstanceConfiguration {
-            minecraft_version: Ustr::from(version),
-            loader,
-            preferred_loader_version: None,
-            memory: None,
-            jvm_flags: None,
-            jvm_binary: None,
-            linux_wrapper: None,
-            system_libraries: None,
-            instance_fallback_icon,
-        };
+        if let Some(EmbeddedOrRaw::Embedded(e)) = &icon {
+            configuration.instance_fallback_icon = Some(Ustr::from(&**e));
+        }
+
+        // Configurations are copied when duplicating or using templates, the new instance must not share the uuid
+        configuration.uuid = Some(Uuid::new_v4());
+        configuration.previous_names.clear();
 
-        let info_path = instance_dir.join("info_v1.json");
-        crate::write_safe(&info_path, serde_json::to_string(&instance_info).unwrap().as_bytes()).unwrap();
+        let info_path = pending.root_path().join("info_v1.json");
+        if let Err(err) = crate::write_safe(&info_path, serde_json::to_string(&configuration).unwrap().as_bytes()) {
+            self.send.send_error(format!("Unable to create instance: {err}"));
+            return None;
+        }
 
         if let Some(EmbeddedOrRaw::Raw(image_bytes)) = icon {
             if let Ok(format) = image::guess_format(&*image_bytes) {
                 if format == ImageFormat::Png {
-                    let icon_path = instance_dir.join("icon.png");
-                    crate::write_safe(&icon_path, &*image_bytes).unwrap();
+                    let icon_path = pending.root_path().join("icon.png");
+                    if let Err(err) = crate::write_safe(&icon_path, &*image_bytes) {
+                        self.send.send_error(format!("Unable to apply icon: {err}"));
+                    }
                 } else {
                     self.send.send_error("Unable to apply icon: only pngs are supported");
                 }
@@ -1088,7 +1700,7 @@ impl BackendState {
             }
         }
 
-        Some(instance_dir.clone())
+        Some(pending)
     }
 
     pub async fn rename_instance(&self, id: InstanceID, name: &str) {
@@ -1105,14 +1717,52 @@ impl BackendState {
             return;
         }
 
-        let new_instance_dir = self.directories.instances_dir.join(name);
+        let Some(root_path) = self.instance_state.read().instances.get(id).map(|instance| instance.root_path.clone()) else {
+            return;
+        };
+        let Some(parent) = root_path.parent() else {
+            return;
+        };
+        let new_instance_dir = parent.join(name);
 
+        // Shortcuts created before instances had a uuid launch the instance by its name, remember the old name so
+        // that they keep working
         if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
-            let result = std::fs::rename(&instance.root_path, new_instance_dir);
-            if let Err(err) = result {
-                self.send.send_error(format!("Unable to rename instance folder: {}", err));
-            }
+            let old_name: Arc<str> = instance.name.as_str().into();
+            instance.configuration.modify(|configuration| {
+                configuration.previous_names.remove(name);
+                configuration.previous_names.insert(old_name);
+            });
+        }
+
+        let result = std::fs::rename(&root_path, &new_instance_dir);
+        if let Err(err) = result {
+            self.send.send_error(format!("Unable to rename instance folder: {}", err));
+            return;
         }
+
+        // Renames inside the instances directory are picked up by its watcher, but the parent of an instance stored
+        // elsewhere isn't watched
+        if parent != &*self.directories.instances_dir {
+            self.update_external_instance(&root_path, Some(&new_instance_dir));
+            self.apply_instance_moved(id, new_instance_dir.into());
+            self.send.send_info(format!("Instance renamed to '{}'", name));
+        }
+    }
+
+    // The selected account without logging in, along with whether it would have an access token when launching
+    pub fn get_login_info_without_token(&self) -> Option<(MinecraftLoginInfo, bool)> {
+        let mut account_info = self.account_info.write();
+        let account_info = account_info.get();
+
+        let uuid = account_info.selected_account?;
+        let account = account_info.accounts.get(&uuid)?;
+
+        Some((MinecraftLoginInfo {
+            uuid,
+            username: account.username.clone(),
+            access_token: None,
+        }, !account.offline))
     }
 
     pub async fn get_login_info(&self, modal_action: &ModalAction) -> Option<MinecraftLoginInfo> {
@@ -1177,6 +1827,36 @@ impl BackendStateFileWatching {
         }
     }
 
+    // Watches the root of an instance along with every folder inside it the instance was already watching, used
+    // after the instance folder was renamed or moved
+    pub fn watch_instance(&mut self, instance: &Instance) {
+        let id = instance.id;
+        self.watch_filesystem(instance.root_path.clone(), WatchTarget::InstanceDir { id });
+        if instance.watching_dot_minecraft {
+            self.watch_filesystem(instance.dot_minecraft_path.clone(), WatchTarget::InstanceDotMinecraftDir { id });
+        }
+        if instance.watching_saves_dir {
+            self.watch_filesystem(instance.saves_path.clone(), WatchTarget::InstanceSavesDir { id });
+        }
+        if instance.watching_server_dat {
+            self.watch_filesystem(instance.server_dat_path.clone(), WatchTarget::ServersDat { id });
+        }
+        for folder in ContentFolder::iter() {
+            if instance.content_state[folder].watching_path {
+                self.watch_filesystem(instance.content_state[folder].path.clone(), WatchTarget::InstanceContentDir { id, folder });
+            }
+        }
+    }
+
+    // Stops watching a folder and everything inside it, so that it can be moved away without being treated as removed
+    pub fn remove_all_under(&mut self, root: &Path) {
+        let paths: Vec<Arc<Path>> = self.watching.keys().filter(|path| path.starts_with(root)).cloned().collect();
+        for path in paths {
+            _ = self.watcher.unwatch(&path);
+            self.remove(&path);
+        }
+    }
+
     pub fn get_target(&self, path: &Path) -> Option<&WatchTarget> {
         self.watching.get(path)
     }
@@ -1241,6 +1921,8 @@ pub enum LoginError {
     MsaAuthorizationError(#[from] MsaAuthorizationError),
     #[error("XboxLive authentication error: {0}")]
     XboxAuthenticateError(#[from] XboxAuthenticateError),
+    #[error("{0}")]
+    AccountProblem(AccountProblem),
     #[error("Cancelled by user")]
     CancelledByUser,
 }
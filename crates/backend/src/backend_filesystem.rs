@@ -1,4 +1,4 @@
-use std::{collections::HashSet, ffi::OsStr, path::Path, sync::Arc};
+use std::{collections::HashSet, ffi::OsStr, path::Path, sync::Arc, time::Duration};
 
 use bridge::{instance::InstanceID, message::MessageToFrontend};
 use notify::{
@@ -10,6 +10,10 @@ use strum::IntoEnumIterator;
 
 use crate::{BackendState, WatchTarget, instance::ContentFolder};
 
+// Files copied in by a file manager usually arrive over several debounce periods, so wait for the folder to settle
+// before re-indexing it
+const EXTERNAL_CONTENT_RELOAD_DELAY: Duration = Duration::from_millis(500);
+
 #[derive(Debug)]
 enum FilesystemEvent {
     Change(Arc<Path>),
@@ -29,6 +33,7 @@ impl FilesystemEvent {
 
 struct AfterDebounceEffects {
     reload_immediately: FxHashSet<(InstanceID, ContentFolder)>,
+    reload_after_delay: FxHashSet<(InstanceID, ContentFolder)>,
 }
 
 impl BackendState {
@@ -37,6 +42,7 @@ impl BackendState {
             Ok(events) => {
                 let mut after_debounce_effects = AfterDebounceEffects {
                     reload_immediately: Default::default(),
+                    reload_after_delay: Default::default(),
                 };
 
                 let mut last_event: Option<FilesystemEvent> = None;
@@ -60,6 +66,21 @@ impl BackendState {
                 if let Some(last_event) = last_event.take() {
                     self.handle_filesystem_event(last_event, &mut after_debounce_effects).await;
                 }
+                for (instance_id, folder) in after_debounce_effects.reload_after_delay {
+                    if after_debounce_effects.reload_immediately.contains(&(instance_id, folder)) {
+                        continue;
+                    }
+                    if !self.instance_state.write().scheduled_content_reloads.insert((instance_id, folder)) {
+                        continue;
+                    }
+
+                    let backend = self.clone();
+                    tokio::task::spawn(async move {
+                        tokio::time::sleep(EXTERNAL_CONTENT_RELOAD_DELAY).await;
+                        backend.instance_state.write().scheduled_content_reloads.remove(&(instance_id, folder));
+                        backend.load_instance_content(instance_id, folder).await;
+                    });
+                }
                 for (instance_id, folder) in after_debounce_effects.reload_immediately {
                     tokio::task::spawn(self.clone().load_instance_content(instance_id, folder));
                 }
@@ -181,7 +202,7 @@ impl BackendState {
         &mut self,
         target: WatchTarget,
         path: &Arc<Path>,
-        _after_debounce_effects: &mut AfterDebounceEffects,
+        after_debounce_effects: &mut AfterDebounceEffects,
     ) -> bool {
         match target {
             WatchTarget::RootDir => {
@@ -193,12 +214,20 @@ impl BackendState {
 
                 let mut instance_state = self.instance_state.write();
 
-                for instance in instance_state.instances.drain() {
-                    self.send.send(MessageToFrontend::InstanceRemoved { id: instance.id });
+                // Instances stored outside the instances dir are unaffected
+                let removed: Vec<InstanceID> = instance_state.instances.iter()
+                    .filter(|instance| instance.root_path.starts_with(&*path))
+                    .map(|instance| instance.id)
+                    .collect();
+                for id in removed {
+                    if let Some(instance) = instance_state.instances.remove(id) {
+                        instance.cancel_tasks.cancel();
+                    }
+                    instance_state.reload_immediately.retain(|(reload_id, _)| *reload_id != id);
+                    self.send.send(MessageToFrontend::InstanceRemoved { id });
                 }
 
-                instance_state.instance_by_path.clear();
-                instance_state.reload_immediately.clear();
+                instance_state.instance_by_path.retain(|instance_path, _| !instance_path.starts_with(&*path));
 
                 true
             },
@@ -233,6 +262,7 @@ impl BackendState {
             WatchTarget::InstanceContentDir { id, folder } => {
                 if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
                     instance.content_state[folder].mark_dirty(None);
+                    after_debounce_effects.reload_after_delay.insert((id, folder));
                 }
                 true
             },
@@ -242,6 +272,9 @@ impl BackendState {
                     instance.mark_servers_dirty();
                     for folder in ContentFolder::iter() {
                         instance.content_state[folder].mark_dirty(None);
+                        if instance.content_state[folder].watching_path {
+                            after_debounce_effects.reload_after_delay.insert((id, folder));
+                        }
                     }
                 }
                 true
@@ -267,22 +300,7 @@ impl BackendState {
                     self.send.send_info(format!("Instance '{}' renamed to '{}'", old_name, instance.name));
                     self.send.send(instance.create_modify_message());
 
-                    let mut file_watching = self.file_watching.write();
-                    file_watching.watch_filesystem(to.clone(), WatchTarget::InstanceDir { id });
-                    if instance.watching_dot_minecraft {
-                        file_watching.watch_filesystem(instance.dot_minecraft_path.clone(), WatchTarget::InstanceDotMinecraftDir { id });
-                    }
-                    if instance.watching_saves_dir {
-                        file_watching.watch_filesystem(instance.saves_path.clone(), WatchTarget::InstanceSavesDir { id });
-                    }
-                    if instance.watching_server_dat {
-                        file_watching.watch_filesystem(instance.server_dat_path.clone(), WatchTarget::ServersDat { id });
-                    }
-                    for folder in ContentFolder::iter() {
-                        if instance.content_state[folder].watching_path {
-                            file_watching.watch_filesystem(instance.content_state[folder].path.clone(), WatchTarget::InstanceContentDir { id, folder });
-                        }
-                    }
+                    self.file_watching.write().watch_instance(instance);
                     true
                 } else {
                     false
@@ -372,6 +390,7 @@ impl BackendState {
                     for folder in ContentFolder::iter() {
                         if name == folder.path().as_str() && instance.content_state[folder].watching_path {
                             instance.content_state[folder].mark_dirty(None);
+                            after_debounce_effects.reload_after_delay.insert((id, folder));
                             self.file_watching.write().watch_filesystem(path.clone(), WatchTarget::InstanceContentDir { id, folder });
                             return;
                         }
@@ -415,6 +434,8 @@ impl BackendState {
                     instance.content_state[folder].mark_dirty(Some(path.clone()));
                     if instance_state.reload_immediately.remove(&(id, folder)) {
                         after_debounce_effects.reload_immediately.insert((id, folder));
+                    } else {
+                        after_debounce_effects.reload_after_delay.insert((id, folder));
                     }
                 }
             },
@@ -454,6 +475,8 @@ impl BackendState {
                     instance.content_state[folder].mark_dirty(Some(path.clone()));
                     if instance_state.reload_immediately.remove(&(id, folder)) {
                         after_debounce_effects.reload_immediately.insert((id, folder));
+                    } else {
+                        after_debounce_effects.reload_after_delay.insert((id, folder));
                     }
                 }
             },
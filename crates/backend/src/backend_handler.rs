@@ -1,19 +1,19 @@
-use std::{io::{BufRead, Read, Seek, SeekFrom, Write}, path::Path, sync::{atomic::Ordering, Arc}, time::{Duration, SystemTime}};
+use std::{io::{BufRead, Read, Seek, SeekFrom, Write}, path::Path, sync::{atomic::Ordering, Arc}, time::{Duration, Instant, SystemTime}};
 
 use auth::{credentials::AccountCredentials, models::{MinecraftAccessToken, MinecraftProfileResponse}, secret::PlatformSecretStorage};
 use bridge::{
-    install::{ContentDownload, ContentInstall, ContentInstallFile, InstallTarget}, instance::{InstanceStatus, ContentType, ContentSummary}, message::{LogFiles, MessageToBackend, MessageToFrontend}, meta::MetadataResult, modal_action::{ModalAction, ModalActionVisitUrl, ProgressTracker, ProgressTrackerFinishType}, serial::AtomicOptionSerial
+    game_output::GameOutputLogLevel, job::JobPriority, install::{ContentDownload, ContentInstall, ContentInstallFile, InstallTarget}, instance::{InstanceContentID, InstanceID, InstanceLaunchCommand, InstanceStatus, InstanceVerifySummary, ContentType, ContentSummary}, message::{InstanceSetting, LogFiles, MessageToBackend, MessageToFrontend, PersistentFile}, meta::MetadataResult, modal_action::{ModalAction, ModalActionVisitUrl, ProgressTracker, ProgressTrackerFinishType}, sandbox::Sandbox, serial::AtomicOptionSerial
 };
 use futures::TryFutureExt;
 use rustc_hash::{FxHashMap, FxHashSet};
-use schema::{auxiliary::AuxiliaryContentMeta, content::ContentSource, modrinth::ModrinthLoader, version::{LaunchArgument, LaunchArgumentValue}};
+use schema::{auxiliary::AuxiliaryContentMeta, content::ContentSource, instance::{InstanceConfiguration, InstanceType}, instance_history::InstanceHistoryEvent, launch_metrics::LaunchOutcome, loader::Loader, modrinth::ModrinthLoader, version::{LaunchArgument, LaunchArgumentValue}};
 use serde::Deserialize;
 use strum::IntoEnumIterator;
 use tokio::{io::AsyncBufReadExt, sync::Semaphore};
 use ustr::Ustr;
 
 use crate::{
-    BackendState, LoginError, account::{BackendAccount, MinecraftLoginInfo}, arcfactory::ArcStrFactory, instance::ContentFolder, launch::{ArgumentExpansionKey, LaunchError}, log_reader, metadata::{items::{AssetsIndexMetadataItem, FabricLoaderManifestMetadataItem, ForgeInstallerMavenMetadataItem, MinecraftVersionManifestMetadataItem, MinecraftVersionMetadataItem, ModrinthProjectVersionsMetadataItem, ModrinthSearchMetadataItem, ModrinthV3VersionUpdateMetadataItem, ModrinthVersionUpdateMetadataItem, MojangJavaRuntimeComponentMetadataItem, MojangJavaRuntimesMetadataItem, NeoforgeInstallerMavenMetadataItem, VersionUpdateParameters, VersionV3LoaderFields, VersionV3UpdateParameters}, manager::MetaLoadError}, mod_metadata::ModUpdateAction
+    BackendState, LoginError, account::{BackendAccount, MinecraftLoginInfo}, arcfactory::ArcStrFactory, instance::ContentFolder, launch::{ArgumentExpansionKey, LaunchError}, log_reader, metadata::{items::{AssetsIndexMetadataItem, FabricLoaderManifestMetadataItem, ForgeInstallerMavenMetadataItem, JavaPatchNoteMetadataItem, JavaPatchNotesMetadataItem, MinecraftVersionManifestMetadataItem, MinecraftVersionMetadataItem, ModrinthProjectDependenciesMetadataItem, ModrinthProjectMetadataItem, ModrinthProjectVersionsMetadataItem, ModrinthSearchMetadataItem, ModrinthV3VersionUpdateMetadataItem, ModrinthVersionUpdateMetadataItem, MojangJavaRuntimeComponentMetadataItem, MojangJavaRuntimesMetadataItem, NeoforgeInstallerMavenMetadataItem, VersionUpdateParameters, VersionV3LoaderFields, VersionV3UpdateParameters}, manager::MetaLoadError}, mod_metadata::ModUpdateAction, options_txt::OptionsTxt, server_properties::{self, ServerProperties}
 };
 
 impl BackendState {
@@ -22,7 +22,12 @@ impl BackendState {
             MessageToBackend::RequestMetadata { request, force_reload } => {
                 let meta = self.meta.clone();
                 let send = self.send.clone();
-                tokio::task::spawn(async move {
+                let pending_metadata_requests = self.pending_metadata_requests.clone();
+                let pending_request = request.clone();
+
+                // Hold the lock while spawning so the task can't remove its entry before it has been inserted
+                let mut pending = self.pending_metadata_requests.write();
+                let task = tokio::task::spawn(async move {
                     let (result, keep_alive_handle) = match request {
                         bridge::meta::MetadataRequest::MinecraftVersionManifest => {
                             let (result, handle) = meta.fetch_with_keepalive(&MinecraftVersionManifestMetadataItem, force_reload).await;
@@ -48,14 +53,43 @@ impl BackendState {
                             let (result, handle) = meta.fetch_with_keepalive(&ModrinthProjectVersionsMetadataItem(project_versions), force_reload).await;
                             (result.map(MetadataResult::ModrinthProjectVersionsResult), handle)
                         },
+                        bridge::meta::MetadataRequest::ModrinthProject(ref project_id) => {
+                            let (result, handle) = meta.fetch_with_keepalive(&ModrinthProjectMetadataItem(project_id), force_reload).await;
+                            (result.map(MetadataResult::ModrinthProject), handle)
+                        },
+                        bridge::meta::MetadataRequest::ModrinthProjectDependencies(ref project_id) => {
+                            let (result, handle) = meta.fetch_with_keepalive(&ModrinthProjectDependenciesMetadataItem(project_id), force_reload).await;
+                            (result.map(MetadataResult::ModrinthProjectDependencies), handle)
+                        },
+                        bridge::meta::MetadataRequest::JavaPatchNotes => {
+                            let (result, handle) = meta.fetch_with_keepalive(&JavaPatchNotesMetadataItem, force_reload).await;
+                            (result.map(MetadataResult::JavaPatchNotes), handle)
+                        },
+                        bridge::meta::MetadataRequest::JavaPatchNote(ref content_path) => {
+                            let (result, handle) = meta.fetch_with_keepalive(&JavaPatchNoteMetadataItem(content_path), force_reload).await;
+                            (result.map(MetadataResult::JavaPatchNote), handle)
+                        },
                     };
                     let result = result.map_err(|err| format!("{}", err).into());
+                    pending_metadata_requests.write().remove(&request);
                     send.send(MessageToFrontend::MetadataResult {
                         request,
                         result,
                         keep_alive_handle
                     });
                 });
+                pending.insert(pending_request, task.abort_handle());
+            },
+            MessageToBackend::CancelMetadataRequest { request } => {
+                let Some(task) = self.pending_metadata_requests.write().remove(&request) else {
+                    return;
+                };
+                log::debug!("Cancelling metadata request {request:?}");
+                task.abort();
+
+                if let bridge::meta::MetadataRequest::ModrinthSearch(ref search) = request {
+                    self.meta.cancel(&ModrinthSearchMetadataItem(search)).await;
+                }
             },
             MessageToBackend::RequestLoadWorlds { id } => {
                 tokio::task::spawn(self.clone().load_instance_worlds(id));
@@ -63,39 +97,155 @@ impl BackendState {
             MessageToBackend::RequestLoadServers { id } => {
                 tokio::task::spawn(self.clone().load_instance_servers(id));
             },
+            MessageToBackend::PingServer { address, force } => {
+                self.request_server_status(address, force);
+            },
             MessageToBackend::RequestLoadMods { id } => {
                 tokio::task::spawn(self.clone().load_instance_content(id, ContentFolder::Mods));
             },
             MessageToBackend::RequestLoadResourcePacks { id } => {
                 tokio::task::spawn(self.clone().load_instance_content(id, ContentFolder::ResourcePacks));
             },
-            MessageToBackend::CreateInstance { name, version, loader, icon } => {
-                self.create_instance(&name, &version, loader, icon).await;
+            MessageToBackend::CreateInstance { name, version, loader, preferred_loader_version, group, icon, instance_type, accept_eula, install_fabric_api } => {
+                let mut configuration = InstanceConfiguration::new(version, loader);
+                configuration.preferred_loader_version = preferred_loader_version;
+                configuration.group = group;
+                configuration.instance_type = instance_type;
+                let Some(pending) = self.begin_create_instance(&name, configuration, icon).await else {
+                    return;
+                };
+
+                if instance_type == InstanceType::Server
+                    && accept_eula
+                    && let Err(err) = server_properties::accept_eula(&pending.dot_minecraft_path())
+                {
+                    self.send.send_error(format!("Unable to write eula.txt: {err}"));
+                }
+
+                let Some(instance_dir) = self.commit_instance(pending) else {
+                    return;
+                };
+
+                if install_fabric_api && loader == Loader::Fabric && instance_type == InstanceType::Client {
+                    self.clone().load_instance_from_path(&instance_dir, true, false);
+                    let id = self.instance_state.read().instance_by_path.get(&instance_dir).copied();
+                    if let Some(id) = id {
+                        self.install_fabric_api(id, name, version).await;
+                    }
+                }
+            },
+            MessageToBackend::BulkInstanceAction { ids, action, modal_action } => {
+                let _cancel_link = self.link_cancellation(&modal_action, None);
+                self.run_job("Running bulk action", JobPriority::Interactive,
+                    self.run_bulk_instance_action(ids, action, modal_action.clone())).await;
+                modal_action.set_finished();
+                self.send.send(MessageToFrontend::Refresh);
+            },
+            MessageToBackend::AddExternalInstance { path } => {
+                self.clone().add_external_instance(&path);
+            },
+            MessageToBackend::AdoptStrayInstance { path, minecraft_version, loader, modal_action } => {
+                self.clone().adopt_stray_instance(&path, minecraft_version, loader, &modal_action);
+                modal_action.set_finished();
             },
             MessageToBackend::DeleteInstance { id } => {
-                if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
-                    let result = std::fs::remove_dir_all(&instance.root_path);
+                let root_path = self.instance_state.read().instances.get(id).map(|instance| {
+                    instance.cancel_tasks.cancel();
+                    instance.root_path.clone()
+                });
+                if let Some(root_path) = root_path {
+                    let result = std::fs::remove_dir_all(&root_path);
                     if let Err(err) = result {
                         self.send.send_error(format!("Unable to delete instance folder: {}", err));
+                    } else {
+                        self.update_external_instance(&root_path, None);
                     }
                 }
             },
+            MessageToBackend::SaveInstanceTemplate { id, name } => {
+                self.save_instance_template(id, &name).await;
+            },
+            MessageToBackend::GetInstanceTemplates { channel } => {
+                _ = channel.send(self.list_instance_templates());
+            },
+            MessageToBackend::DeleteInstanceTemplate { name } => {
+                self.delete_instance_template(&name);
+            },
+            MessageToBackend::CreateInstanceFromTemplate { template, name, version, modal_action } => {
+                self.create_instance_from_template(&template, name, version, modal_action.clone()).await;
+                modal_action.set_finished();
+                self.send.send(MessageToFrontend::Refresh);
+            },
+            MessageToBackend::CreateInstanceShareCode { id, channel } => {
+                _ = channel.send(self.create_instance_share_code(id).await);
+            },
+            MessageToBackend::ReadInstanceShareCode { code, channel } => {
+                _ = channel.send(self.read_instance_share_code(&code));
+            },
+            MessageToBackend::ImportInstanceShareCode { code, name, modal_action } => {
+                self.import_instance_share_code(&code, name, modal_action.clone()).await;
+                modal_action.set_finished();
+                self.send.send(MessageToFrontend::Refresh);
+            },
+            MessageToBackend::DuplicateInstance { id, name, options, modal_action } => {
+                self.duplicate_instance(id, name, options, &modal_action).await;
+                modal_action.set_finished();
+            },
             MessageToBackend::RenameInstance { id, name } => {
                 self.rename_instance(id, &name).await;
             },
+            MessageToBackend::MoveInstance { id, target, modal_action } => {
+                let _cancel_link = self.link_cancellation(&modal_action, Some(id));
+                self.move_instance(id, target, &modal_action).await;
+                modal_action.set_finished();
+            },
             MessageToBackend::SetInstanceMinecraftVersion { id, version } => {
                 if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    let from = instance.configuration.get().minecraft_version;
                     instance.configuration.modify(|configuration| {
                         configuration.minecraft_version = version;
                     });
+                    if from != version {
+                        instance.record_history(InstanceHistoryEvent::MinecraftVersionChanged { from, to: version });
+                    }
                 }
             },
+            MessageToBackend::SetInstanceGroup { id, group } => {
+                if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    instance.configuration.modify(|configuration| {
+                        configuration.group = group;
+                    });
+                }
+            },
+            MessageToBackend::SetInstanceFavorite { id, favorite } => {
+                if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    instance.configuration.modify(|configuration| {
+                        configuration.favorite = favorite;
+                    });
+                }
+            },
+            MessageToBackend::SetInstanceVersionTracking { id, version_tracking } => {
+                if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    instance.configuration.modify(|configuration| {
+                        configuration.version_tracking = version_tracking;
+                    });
+                    instance.record_history(InstanceHistoryEvent::SettingChanged { setting: "version tracking".into() });
+                }
+            },
+            MessageToBackend::UpgradeInstance { id, version, modal_action } => {
+                let _cancel_link = self.link_cancellation(&modal_action, Some(id));
+                self.upgrade_instance(id, version, modal_action).await;
+            },
+            MessageToBackend::RestoreInstanceSnapshot { id, modal_action } => {
+                self.restore_instance_snapshot(id, modal_action).await;
+            },
             MessageToBackend::SetInstanceLoader { id, loader } => {
                 if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
                     instance.configuration.modify(|configuration| {
                         configuration.loader = loader;
                         configuration.preferred_loader_version = None;
                     });
+                    instance.record_history(InstanceHistoryEvent::LoaderChanged { loader, loader_version: None });
                 }
             },
             MessageToBackend::SetInstancePreferredLoaderVersion { id, loader_version } => {
@@ -103,6 +253,12 @@ impl BackendState {
                     instance.configuration.modify(|configuration| {
                         configuration.preferred_loader_version = loader_version.map(Ustr::from);
                     });
+                    let configuration = instance.configuration.get();
+                    let event = InstanceHistoryEvent::LoaderChanged {
+                        loader: configuration.loader,
+                        loader_version: configuration.preferred_loader_version,
+                    };
+                    instance.record_history(event);
                 }
             }
             MessageToBackend::SetInstanceMemory { id, memory } => {
@@ -110,6 +266,7 @@ impl BackendState {
                     instance.configuration.modify(|configuration| {
                         configuration.memory = Some(memory);
                     });
+                    instance.record_history(InstanceHistoryEvent::SettingChanged { setting: "memory".into() });
                 }
             },
             MessageToBackend::SetInstanceJvmFlags { id, jvm_flags } => {
@@ -117,6 +274,7 @@ impl BackendState {
                     instance.configuration.modify(|configuration| {
                         configuration.jvm_flags = Some(jvm_flags);
                     });
+                    instance.record_history(InstanceHistoryEvent::SettingChanged { setting: "JVM flags".into() });
                 }
             },
             MessageToBackend::SetInstanceJvmBinary { id, jvm_binary } => {
@@ -124,6 +282,7 @@ impl BackendState {
                     instance.configuration.modify(|configuration| {
                         configuration.jvm_binary = Some(jvm_binary);
                     });
+                    instance.record_history(InstanceHistoryEvent::SettingChanged { setting: "Java binary".into() });
                 }
             },
             MessageToBackend::SetInstanceLinuxWrapper { id, linux_wrapper } => {
@@ -131,18 +290,206 @@ impl BackendState {
                     instance.configuration.modify(|configuration| {
                         configuration.linux_wrapper = Some(linux_wrapper);
                     });
+                    instance.record_history(InstanceHistoryEvent::SettingChanged { setting: "Linux wrapper".into() });
+                }
+            },
+            MessageToBackend::SetInstanceLegacyFixes { id, legacy_fixes } => {
+                if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    instance.configuration.modify(|configuration| {
+                        configuration.legacy_fixes = Some(legacy_fixes);
+                    });
+                    instance.record_history(InstanceHistoryEvent::SettingChanged { setting: "legacy fixes".into() });
+                }
+            },
+            MessageToBackend::SetInstanceNetworkIsolation { id, network_isolation } => {
+                if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    instance.configuration.modify(|configuration| {
+                        configuration.network_isolation = Some(network_isolation);
+                    });
+                    instance.record_history(InstanceHistoryEvent::SettingChanged { setting: "network isolation".into() });
+                }
+            },
+            MessageToBackend::SetInstanceScheduledBackups { id, value } => {
+                if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    instance.configuration.modify(|configuration| {
+                        configuration.scheduled_backups = value;
+                    });
+                    instance.record_history(InstanceHistoryEvent::SettingChanged { setting: "scheduled backups".into() });
                 }
             },
+            MessageToBackend::BackupInstance { id, modal_action } => {
+                let backend = self.clone();
+                tokio::task::spawn(async move {
+                    backend.run_job("Backing up instance", JobPriority::Interactive, backend.backup_instance(id, &modal_action)).await;
+                    modal_action.set_finished();
+                });
+            },
+            MessageToBackend::GetInstanceBackups { id, channel } => {
+                _ = channel.send(self.get_instance_backups(id));
+            },
+            MessageToBackend::RestoreInstanceBackup { id, path, modal_action } => {
+                let backend = self.clone();
+                tokio::task::spawn(async move {
+                    backend.run_job("Restoring backup", JobPriority::Interactive, backend.restore_instance_backup(id, path, &modal_action)).await;
+                    backend.send.send(MessageToFrontend::Refresh);
+                    modal_action.set_finished();
+                });
+            },
             MessageToBackend::SetInstanceSystemLibraries { id, system_libraries } => {
                 if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
                     instance.configuration.modify(|configuration| {
                         configuration.system_libraries = Some(system_libraries);
                     });
+                    instance.record_history(InstanceHistoryEvent::SettingChanged { setting: "system libraries".into() });
+                }
+            },
+            MessageToBackend::SetInstanceResolution { id, resolution } => {
+                if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    instance.configuration.modify(|configuration| {
+                        configuration.resolution = Some(resolution);
+                    });
+                    instance.record_history(InstanceHistoryEvent::SettingChanged { setting: "resolution".into() });
+                }
+            },
+            MessageToBackend::ResetInstanceSetting { id, setting } => {
+                if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    instance.configuration.modify(|configuration| {
+                        match setting {
+                            InstanceSetting::Memory => configuration.memory = None,
+                            InstanceSetting::JvmFlags => configuration.jvm_flags = None,
+                            InstanceSetting::JvmBinary => configuration.jvm_binary = None,
+                            InstanceSetting::Resolution => configuration.resolution = None,
+                        }
+                    });
+                    let setting = match setting {
+                        InstanceSetting::Memory => "memory",
+                        InstanceSetting::JvmFlags => "JVM flags",
+                        InstanceSetting::JvmBinary => "Java binary",
+                        InstanceSetting::Resolution => "resolution",
+                    };
+                    instance.record_history(InstanceHistoryEvent::SettingChanged { setting: setting.into() });
+                }
+            },
+            MessageToBackend::GetInstanceGameOptions { id, channel } => {
+                let options_path = if let Some(instance) = self.instance_state.read().instances.get(id) {
+                    instance.dot_minecraft_path.join("options.txt")
+                } else {
+                    return;
+                };
+
+                match OptionsTxt::load(&options_path) {
+                    Ok(options) => {
+                        _ = channel.send(options.game_options());
+                    },
+                    Err(err) => {
+                        self.send.send_error(format!("Unable to read options.txt: {err}"));
+                    },
+                }
+            },
+            MessageToBackend::SetInstanceGameOptions { ids, options } => {
+                let options_paths: Vec<_> = {
+                    let instance_state = self.instance_state.read();
+                    ids.iter()
+                        .filter_map(|id| instance_state.instances.get(*id))
+                        .map(|instance| (instance.name, instance.dot_minecraft_path.join("options.txt")))
+                        .collect()
+                };
+
+                for (name, options_path) in options_paths {
+                    let result = OptionsTxt::load(&options_path).and_then(|mut options_txt| {
+                        options_txt.apply_game_options(&options);
+                        options_txt.save(&options_path)
+                    });
+                    if let Err(err) = result {
+                        self.send.send_error(format!("Unable to update options.txt for {name}: {err}"));
+                    }
+                }
+            },
+            MessageToBackend::GetServerProperties { id, channel } => {
+                let dot_minecraft = if let Some(instance) = self.instance_state.read().instances.get(id) {
+                    instance.dot_minecraft_path.clone()
+                } else {
+                    return;
+                };
+
+                match ServerProperties::load(&dot_minecraft.join("server.properties")) {
+                    Ok(properties) => {
+                        let mut properties = properties.server_properties();
+                        properties.eula_accepted = server_properties::is_eula_accepted(&dot_minecraft);
+                        _ = channel.send(properties);
+                    },
+                    Err(err) => {
+                        self.send.send_error(format!("Unable to read server.properties: {err}"));
+                    },
+                }
+            },
+            MessageToBackend::SetServerProperties { id, properties } => {
+                let dot_minecraft = if let Some(instance) = self.instance_state.read().instances.get(id) {
+                    instance.dot_minecraft_path.clone()
+                } else {
+                    return;
+                };
+
+                let properties_path = dot_minecraft.join("server.properties");
+                let result = ServerProperties::load(&properties_path).and_then(|mut server_properties| {
+                    server_properties.apply_server_properties(&properties);
+                    server_properties.save(&properties_path)
+                });
+                if let Err(err) = result {
+                    self.send.send_error(format!("Unable to update server.properties: {err}"));
+                }
+
+                if properties.eula_accepted
+                    && !server_properties::is_eula_accepted(&dot_minecraft)
+                    && let Err(err) = server_properties::accept_eula(&dot_minecraft)
+                {
+                    self.send.send_error(format!("Unable to write eula.txt: {err}"));
+                }
+            },
+            MessageToBackend::SendInstanceInput { id, input } => {
+                if let Some(instance) = self.instance_state.write().instances.get_mut(id)
+                    && let Some(child) = &mut instance.child
+                    && let Some(stdin) = &mut child.stdin
+                {
+                    let result = stdin.write_all(input.as_bytes())
+                        .and_then(|_| stdin.write_all(b"\n"))
+                        .and_then(|_| stdin.flush());
+                    if let Err(err) = result {
+                        self.send.send_error(format!("Unable to send input to instance: {err}"));
+                    }
+                    return;
+                }
+
+                self.send.send_warning("Can't send input, instance isn't running");
+            },
+            MessageToBackend::StartInstanceTunnel { id } => {
+                tokio::task::spawn(self.clone().start_instance_tunnel(id));
+            },
+            MessageToBackend::StopInstanceTunnel { id } => {
+                if let Some(instance) = self.instance_state.write().instances.get_mut(id)
+                    && instance.tunnel.take().is_some()
+                {
+                    self.send.send(MessageToFrontend::InstanceTunnelUpdated { id, address: None });
                 }
             },
             MessageToBackend::KillInstance { id } => {
                 if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    // Ask servers to save and shut down on the first request
+                    if !instance.stop_requested
+                        && let Some(child) = &mut instance.child
+                        && let Some(stdin) = &mut child.stdin
+                        && stdin.write_all(b"stop\n").and_then(|_| stdin.flush()).is_ok()
+                    {
+                        instance.stop_requested = true;
+                        self.send.send_info("Stopping server, press kill again to force it to close");
+                        return;
+                    }
+
                     if let Some(mut child) = instance.child.take() {
+                        if instance.tunnel.take().is_some() {
+                            self.send.send(MessageToFrontend::InstanceTunnelUpdated { id, address: None });
+                        }
+
                         let result = child.kill();
                         if result.is_err() {
                             self.send.send_error("Failed to kill instance");
@@ -163,6 +510,31 @@ impl BackendState {
                 quick_play,
                 modal_action,
             } => {
+                let _cancel_link = self.link_cancellation(&modal_action, Some(id));
+                let (name, is_server) = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    (Some(instance.name), instance.configuration.get().instance_type == InstanceType::Server)
+                } else {
+                    (None, false)
+                };
+                let job_name = name.map(|name| format!("Launching {name}")).unwrap_or_else(|| "Launching instance".to_string());
+                let _job = self.start_job(job_name, JobPriority::LaunchPreparation).await;
+                if let Err(error) = self.check_launch_budget(id) {
+                    modal_action.set_error_message(error.into());
+                    modal_action.set_finished();
+                    return;
+                }
+
+                if is_server {
+                    self.start_server_instance(id, modal_action).await;
+                    return;
+                }
+
+                if let Err(error) = self.check_can_play() {
+                    modal_action.set_error_message(error.into());
+                    modal_action.set_finished();
+                    return;
+                }
+
                 let Some(login_info) = self.get_login_info(&modal_action).await else {
                     return;
                 };
@@ -181,7 +553,13 @@ impl BackendState {
                     return;
                 }
 
-                let (dot_minecraft, configuration) = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                let (instance_defaults, natives_repositories) = {
+                    let mut config = self.config.write();
+                    let config = config.get();
+                    (config.instance_defaults.clone(), config.natives_repositories.clone())
+                };
+
+                let (name, dot_minecraft, configuration) = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
                     if instance.child.is_some() {
                         self.send.send_warning("Can't launch instance, already running");
                         modal_action.set_error_message("Can't launch instance, already running".into());
@@ -194,7 +572,7 @@ impl BackendState {
                     });
                     self.send.send(instance.create_modify_message_with_status(InstanceStatus::Launching));
 
-                    (instance.dot_minecraft_path.clone(), instance.configuration.get().clone())
+                    (instance.name, instance.dot_minecraft_path.clone(), instance.configuration.get().clone().with_defaults(&instance_defaults))
                 } else {
                     self.send.send_error("Can't launch instance, unknown id");
                     modal_action.set_error_message("Can't launch instance, unknown id".into());
@@ -205,7 +583,11 @@ impl BackendState {
                 let launch_tracker = ProgressTracker::new(Arc::from("Launching"), self.send.clone());
                 modal_action.trackers.push(launch_tracker.clone());
 
-                let result = self.launcher.launch(&self.redirecting_http_client, dot_minecraft, configuration, quick_play, login_info, add_mods, &launch_tracker, &modal_action).await;
+                let (minecraft_version, loader) = (configuration.minecraft_version, configuration.loader);
+                let started_at = chrono::Utc::now().timestamp();
+                let launch_start = Instant::now();
+
+                let result = self.launcher.launch(&self.redirecting_http_client, dot_minecraft, configuration, quick_play, login_info, add_mods, &natives_repositories, &launch_tracker, &modal_action).await;
 
                 if matches!(result, Err(LaunchError::CancelledByUser)) {
                     self.send.send(MessageToFrontend::CloseModal);
@@ -217,10 +599,17 @@ impl BackendState {
 
                 let is_err = result.is_err();
                 match result {
-                    Ok(mut child) => {
+                    Ok((mut child, log4j_mitigation)) => {
+                        if let Some(log4j_mitigation) = log4j_mitigation {
+                            log::info!("{}", log4j_mitigation.description());
+                        }
+
                         if !self.config.write().get().dont_open_game_output_when_launching {
                             if let Some(stdout) = child.stdout.take() {
-                                log_reader::start_game_output(stdout, child.stderr.take(), self.send.clone());
+                                let output_id = log_reader::start_game_output(stdout, child.stderr.take(), None, self.send.clone());
+                                if let Some(log4j_mitigation) = log4j_mitigation {
+                                    log_reader::add_launcher_output(output_id, GameOutputLogLevel::Info, log4j_mitigation.description(), &self.send);
+                                }
                             }
                         }
 
@@ -229,13 +618,22 @@ impl BackendState {
                         child.stdin.take();
                         child.stdout.take();
 
+                        self.record_launch(name, minecraft_version, loader, started_at, launch_start.elapsed(), LaunchOutcome::Running);
+
                         if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
                             instance.child = Some(child);
+                            instance.stop_requested = false;
+                            if self.is_recording_launch_metrics() {
+                                instance.metrics_launch = Some((started_at, Instant::now()));
+                            }
+                            instance.mark_played();
+                            instance.record_history(InstanceHistoryEvent::Launched);
                         }
                     },
                     Err(ref err) => {
                         log::error!("Failed to launch due to error: {:?}", &err);
                         modal_action.set_error_message(format!("{}", &err).into());
+                        self.record_launch(name, minecraft_version, loader, started_at, launch_start.elapsed(), LaunchOutcome::Failed);
                     },
                 }
 
@@ -250,33 +648,164 @@ impl BackendState {
                 return;
 
             },
-            MessageToBackend::SetContentEnabled { id, content_ids: mod_ids, enabled } => {
-                let mut instance_state = self.instance_state.write();
-                let Some(instance) = instance_state.instances.get_mut(id) else {
+            MessageToBackend::PrepareInstance { id, modal_action } => {
+                let _cancel_link = self.link_cancellation(&modal_action, Some(id));
+                self.prepare_instance(id, modal_action).await;
+            },
+            MessageToBackend::VerifyInstanceFiles { id, modal_action, channel } => {
+                let _cancel_link = self.link_cancellation(&modal_action, Some(id));
+                let (dot_minecraft, configuration) = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    if instance.child.is_some() {
+                        modal_action.set_error_message("Can't verify files while the instance is running".into());
+                        modal_action.set_finished();
+                        return;
+                    }
+
+                    (instance.dot_minecraft_path.clone(), instance.configuration.get().clone())
+                } else {
+                    self.send.send_error("Can't verify files, unknown id");
+                    modal_action.set_error_message("Can't verify files, unknown id".into());
+                    modal_action.set_finished();
                     return;
                 };
 
-                let mut reload = FxHashSet::default();
+                let natives_repositories = self.config.write().get().natives_repositories.clone();
 
-                for mod_id in mod_ids {
-                    if let Some((instance_mod, folder)) = instance.try_get_content(mod_id) {
-                        if instance_mod.enabled == enabled {
-                            return;
-                        }
+                let verify_tracker = ProgressTracker::new(Arc::from("Verifying instance files"), self.send.clone());
+                modal_action.trackers.push(verify_tracker.clone());
 
-                        let mut new_path = instance_mod.path.to_path_buf();
-                        if instance_mod.enabled {
-                            new_path.add_extension("disabled");
-                        } else {
-                            new_path.set_extension("");
-                        };
+                let result = self.launcher.verify(&self.redirecting_http_client, dot_minecraft, configuration, &natives_repositories, &verify_tracker, &modal_action).await;
 
-                        let _ = std::fs::rename(&instance_mod.path, new_path);
-                        reload.insert((id, folder));
-                    }
+                let (asset_counts, library_counts) = match result {
+                    Ok(counts) => counts,
+                    Err(LaunchError::CancelledByUser) => {
+                        self.send.send(MessageToFrontend::CloseModal);
+                        return;
+                    },
+                    Err(err) => {
+                        log::error!("Failed to verify files due to error: {:?}", &err);
+                        modal_action.set_error_message(format!("{}", &err).into());
+                        verify_tracker.set_finished(ProgressTrackerFinishType::Error);
+                        verify_tracker.notify();
+                        modal_action.set_finished();
+                        return;
+                    },
+                };
+
+                let (modpack_checked, modpack_repaired) = self.verify_instance_content(id, &modal_action).await;
+                let (installed_checked, installed_repaired) = self.verify_installed_content(id, &modal_action).await;
+
+                verify_tracker.set_finished(ProgressTrackerFinishType::from_err(modal_action.error.read().unwrap().is_some()));
+                verify_tracker.notify();
+                modal_action.set_finished();
+
+                _ = channel.send(InstanceVerifySummary {
+                    libraries_checked: library_counts.checked(),
+                    libraries_repaired: library_counts.repaired(),
+                    assets_checked: asset_counts.checked(),
+                    assets_repaired: asset_counts.repaired(),
+                    content_checked: modpack_checked + installed_checked,
+                    content_repaired: modpack_repaired + installed_repaired,
+                });
+            },
+            MessageToBackend::ResolveLaunchCommand { id, modal_action, write_script, channel } => {
+                let (instance_defaults, natives_repositories) = {
+                    let mut config = self.config.write();
+                    let config = config.get();
+                    (config.instance_defaults.clone(), config.natives_repositories.clone())
+                };
+
+                let (root_path, dot_minecraft, configuration) = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                    (instance.root_path.clone(), instance.dot_minecraft_path.clone(), instance.configuration.get().clone().with_defaults(&instance_defaults))
+                } else {
+                    modal_action.set_error_message("Can't show launch command, unknown id".into());
+                    modal_action.set_finished();
+                    return;
+                };
+
+                if configuration.instance_type == InstanceType::Server {
+                    modal_action.set_error_message("Launch commands can only be shown for clients".into());
+                    modal_action.set_finished();
+                    return;
                 }
 
-                instance_state.reload_immediately.extend(reload);
+                let Some((login_info, redact_access_token)) = self.get_login_info_without_token() else {
+                    modal_action.set_error_message("Select an account to show the launch command".into());
+                    modal_action.set_finished();
+                    return;
+                };
+
+                let tracker = ProgressTracker::new(Arc::from("Resolving launch command"), self.send.clone());
+                modal_action.trackers.push(tracker.clone());
+
+                let result = self.launcher.launch_command(&self.redirecting_http_client, dot_minecraft, configuration, login_info,
+                    redact_access_token, &natives_repositories, &tracker, &modal_action).await;
+
+                let launch_command = match result {
+                    Ok(launch_command) => launch_command,
+                    Err(LaunchError::CancelledByUser) => {
+                        self.send.send(MessageToFrontend::CloseModal);
+                        return;
+                    },
+                    Err(err) => {
+                        log::error!("Failed to resolve launch command due to error: {:?}", &err);
+                        modal_action.set_error_message(format!("{}", &err).into());
+                        tracker.set_finished(ProgressTrackerFinishType::Error);
+                        tracker.notify();
+                        modal_action.set_finished();
+                        return;
+                    },
+                };
+
+                let command = launch_command.to_shell_script();
+
+                let script_path = if write_script {
+                    let script_path = root_path.join(if cfg!(windows) { "launch.bat" } else { "launch.sh" });
+                    match crate::write_safe(&script_path, command.as_bytes()) {
+                        Ok(()) => {
+                            #[cfg(unix)]
+                            {
+                                use std::os::unix::fs::PermissionsExt;
+                                let _ = std::fs::set_permissions(&script_path, std::fs::Permissions::from_mode(0o755));
+                            }
+                            Some(script_path.into())
+                        },
+                        Err(err) => {
+                            modal_action.set_error_message(format!("Unable to write launch script: {err}").into());
+                            None
+                        },
+                    }
+                } else {
+                    None
+                };
+
+                tracker.set_finished(ProgressTrackerFinishType::from_err(modal_action.error.read().unwrap().is_some()));
+                tracker.notify();
+                modal_action.set_finished();
+
+                _ = channel.send(InstanceLaunchCommand {
+                    command: command.into(),
+                    script_path,
+                });
+            },
+            MessageToBackend::ValidateInstanceContent { id, channel } => {
+                let backend = self.clone();
+                tokio::task::spawn(async move {
+                    _ = channel.send(backend.validate_instance_content(id).await);
+                });
+            },
+            MessageToBackend::SetContentEnabled { id, content_ids, enabled } => {
+                self.set_content_enabled(id, content_ids, enabled);
+            },
+            MessageToBackend::SaveContentProfile { id, name } => {
+                self.save_content_profile(id, name).await;
+            },
+            MessageToBackend::ApplyContentProfile { id, name, modal_action } => {
+                self.apply_content_profile(id, name, &modal_action).await;
+                modal_action.set_finished();
+            },
+            MessageToBackend::DeleteContentProfile { id, name } => {
+                self.delete_content_profile(id, &name);
             },
             MessageToBackend::SetContentChildEnabled { id, content_id: mod_id, child_id, child_name, child_filename, enabled } => {
                 let mut instance_state = self.instance_state.write();
@@ -330,10 +859,23 @@ impl BackendState {
                 self.download_all_metadata().await;
             },
             MessageToBackend::InstallContent { content, modal_action } => {
-                self.install_content(content, modal_action.clone()).await;
+                self.run_job("Installing content", JobPriority::Interactive, self.install_content(content, modal_action.clone())).await;
                 modal_action.set_finished();
                 self.send.send(MessageToFrontend::Refresh);
             },
+            MessageToBackend::CheckModrinthCompatibility { id, project_ids, channel } => {
+                let backend = self.clone();
+                tokio::task::spawn(async move {
+                    _ = channel.send(backend.check_modrinth_compatibility(id, project_ids).await);
+                });
+            },
+            MessageToBackend::IdentifyDroppedFiles { paths, channel } => {
+                _ = channel.send(self.identify_dropped_files(paths).await);
+            },
+            MessageToBackend::ImportWorld { id, path, modal_action } => {
+                self.import_world(id, path, &modal_action).await;
+                modal_action.set_finished();
+            },
             MessageToBackend::DeleteContent { id, content_ids: mod_ids } => {
                 let mut instance_state = self.instance_state.write();
                 let Some(instance) = instance_state.instances.get_mut(id) else {
@@ -349,280 +891,33 @@ impl BackendState {
                         return;
                     };
 
-                    _ = std::fs::remove_file(&instance_mod.path);
-
-                    if let Some(aux_path) = crate::pandora_aux_path_for_content(&instance_mod) {
-                        _ = std::fs::remove_file(aux_path);
-                    }
-
-                    reload.insert((id, folder));
-                }
-
-                instance_state.reload_immediately.extend(reload);
-            },
-            MessageToBackend::UpdateCheck { instance: id, modal_action } => {
-                let (loader, version) = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
-                    let configuration = instance.configuration.get();
-                    (configuration.loader, configuration.minecraft_version)
-                } else {
-                    self.send.send_error("Can't update instance, unknown id");
-                    modal_action.set_error_message("Can't update instance, unknown id".into());
-                    modal_action.set_finished();
-                    return;
-                };
-
-                let mut content = Vec::new();
-                for folder in ContentFolder::iter() {
-                    let Some(summaries) = self.clone().load_instance_content(id, folder).await else {
-                        modal_action.set_finished();
-                        return;
-                    };
-                    content.extend_from_slice(&*summaries);
-                }
-
-                let modrinth_loader = loader.as_modrinth_loader();
-                if modrinth_loader == ModrinthLoader::Unknown {
-                    modal_action.set_error_message("Unable to update instance, unsupported loader".into());
-                    modal_action.set_finished();
-                    return;
-                }
-
-                let tracker = ProgressTracker::new("Checking content".into(), self.send.clone());
-                tracker.set_total(content.len());
-                modal_action.trackers.push(tracker.clone());
-
-                let semaphore = Semaphore::new(8);
-
-                let mod_params = &VersionUpdateParameters {
-                    loaders: [modrinth_loader].into(),
-                    game_versions: [version].into(),
-                };
-
-                let fabric_mod_params = &VersionUpdateParameters {
-                    loaders: [ModrinthLoader::Fabric].into(),
-                    game_versions: [version].into(),
-                };
-
-                let forge_mod_params = &VersionUpdateParameters {
-                    loaders: [ModrinthLoader::Forge].into(),
-                    game_versions: [version].into(),
-                };
-
-                let neoforge_mod_params = &VersionUpdateParameters {
-                    loaders: [ModrinthLoader::NeoForge].into(),
-                    game_versions: [version].into(),
-                };
-
-                let resourcepack_params = &VersionUpdateParameters {
-                    loaders: [ModrinthLoader::Minecraft].into(),
-                    game_versions: [version].into(),
-                };
-
-                let modrinth_modpack_params = &VersionV3UpdateParameters {
-                    loaders: ["mrpack".into()].into(),
-                    loader_fields: VersionV3LoaderFields {
-                        mrpack_loaders: [modrinth_loader].into(),
-                        game_versions: [version].into(),
-                    },
-                };
-
-                let meta = self.meta.clone();
-
-                let mut futures = Vec::new();
-
-                struct UpdateResult {
-                    mod_summary: Arc<ContentSummary>,
-                    action: ModUpdateAction,
-                }
-
-                { // Scope is needed so await doesn't complain about the non-send RwLockReadGuard
-                    let sources = self.mod_metadata_manager.read_content_sources();
-                    for summary in content.iter() {
-                        let source = sources.get(&summary.content_summary.hash).unwrap_or(ContentSource::Manual);
-                        let semaphore = &semaphore;
-                        let meta = &meta;
-                        let tracker = &tracker;
-                        futures.push(async move {
-                            match source {
-                                ContentSource::Manual => {
-                                    tracker.add_count(1);
-                                    tracker.notify();
-                                    Ok(ModUpdateAction::ManualInstall)
-                                },
-                                ContentSource::ModrinthUnknown | ContentSource::ModrinthProject { .. } => {
-                                    let permit = semaphore.acquire().await.unwrap();
-                                    let result = match summary.content_summary.extra {
-                                        ContentType::Fabric => {
-                                            meta.fetch(&ModrinthVersionUpdateMetadataItem {
-                                                sha1: hex::encode(summary.content_summary.hash).into(),
-                                                params: fabric_mod_params.clone()
-                                            }).await
-                                        },
-                                        ContentType::Forge | ContentType::LegacyForge => {
-                                            meta.fetch(&ModrinthVersionUpdateMetadataItem {
-                                                sha1: hex::encode(summary.content_summary.hash).into(),
-                                                params: forge_mod_params.clone()
-                                            }).await
-                                        },
-                                        ContentType::NeoForge => {
-                                            meta.fetch(&ModrinthVersionUpdateMetadataItem {
-                                                sha1: hex::encode(summary.content_summary.hash).into(),
-                                                params: neoforge_mod_params.clone()
-                                            }).await
-                                        },
-                                        ContentType::JavaModule => {
-                                            meta.fetch(&ModrinthVersionUpdateMetadataItem {
-                                                sha1: hex::encode(summary.content_summary.hash).into(),
-                                                params: mod_params.clone()
-                                            }).await
-                                        },
-                                        ContentType::ModrinthModpack { .. } => {
-                                            meta.fetch(&ModrinthV3VersionUpdateMetadataItem {
-                                                sha1: hex::encode(summary.content_summary.hash).into(),
-                                                params: modrinth_modpack_params.clone()
-                                            }).await
-                                        },
-                                        ContentType::ResourcePack => {
-                                            meta.fetch(&ModrinthVersionUpdateMetadataItem {
-                                                sha1: hex::encode(summary.content_summary.hash).into(),
-                                                params: resourcepack_params.clone()
-                                            }).await
-                                        },
-                                    };
-                                    drop(permit);
-
-                                    tracker.add_count(1);
-                                    tracker.notify();
-
-                                    if let Err(MetaLoadError::NonOK(404)) = result {
-                                        return Ok(ModUpdateAction::ErrorNotFound);
-                                    }
-
-                                    let result = result?;
-
-                                    if let ContentSource::ModrinthProject { ref project } = source {
-                                        if &result.0.project_id != project {
-                                            log::error!("Refusing to update {:?}, mismatched project ids: expected {}, got {}",
-                                                summary.content_summary.hash, &result.0.project_id, &project);
-                                            return Ok(ModUpdateAction::ErrorNotFound);
-                                        }
-                                    }
-
-                                    let install_file = result
-                                        .0
-                                        .files
-                                        .iter()
-                                        .find(|file| file.primary)
-                                        .unwrap_or(result.0.files.first().unwrap());
-
-                                    let mut latest_hash = [0u8; 20];
-                                    let Ok(_) = hex::decode_to_slice(&*install_file.hashes.sha1, &mut latest_hash) else {
-                                        return Ok(ModUpdateAction::ErrorInvalidHash);
-                                    };
-
-                                    if latest_hash == summary.content_summary.hash {
-                                        Ok(ModUpdateAction::AlreadyUpToDate)
-                                    } else {
-                                        Ok(ModUpdateAction::Modrinth {
-                                            file: install_file.clone(),
-                                            project_id: result.0.project_id.clone(),
-                                        })
-                                    }
-                                },
-                            }
-                        }.map_ok(|action| UpdateResult {
-                            mod_summary: summary.content_summary.clone(),
-                            action,
-                        }));
-                    }
-                }
+                    _ = std::fs::remove_file(&instance_mod.path);
 
-                let results: Result<Vec<UpdateResult>, MetaLoadError> = futures::future::try_join_all(futures).await;
+                    if let Some(aux_path) = crate::pandora_aux_path_for_content(&instance_mod) {
+                        _ = std::fs::remove_file(aux_path);
+                    }
 
-                match results {
-                    Ok(updates) => {
-                        let mut meta_updates = self.mod_metadata_manager.updates.write();
+                    let name = instance_mod.content_summary.name.as_deref().unwrap_or(&instance_mod.filename).to_string();
+                    instance.record_history(InstanceHistoryEvent::ContentRemoved { name });
 
-                        for update in updates {
-                            update.mod_summary.update_status.store(update.action.to_status(), Ordering::Relaxed);
-                            meta_updates.insert(update.mod_summary.hash, update.action);
-                        }
-                    },
-                    Err(error) => {
-                        tracker.set_finished(ProgressTrackerFinishType::Error);
-                        modal_action.set_error_message(format!("Error checking for updates: {}", error).into());
-                        modal_action.set_finished();
-                        return;
-                    },
+                    reload.insert((id, folder));
                 }
 
-                tracker.set_finished(ProgressTrackerFinishType::Normal);
+                instance_state.reload_immediately.extend(reload);
+            },
+            MessageToBackend::UpdateCheck { instance: id, modal_action } => {
+                let _cancel_link = self.link_cancellation(&modal_action, Some(id));
+                self.check_for_content_updates(id, &modal_action).await;
                 modal_action.set_finished();
             },
             MessageToBackend::UpdateContent { instance: id, content_id: mod_id, modal_action } => {
-                let content_install = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
-                    let configuration = instance.configuration.get();
-                    let (loader, minecraft_version) = (configuration.loader, configuration.minecraft_version);
-                    let Some((mod_summary, _)) = instance.try_get_content(mod_id) else {
-                        self.send.send_error("Can't update mod in instance, unknown mod id");
-                        modal_action.set_finished();
-                        return;
-                    };
-
-                    let Some(update_info) = self.mod_metadata_manager.updates.read().get(&mod_summary.content_summary.hash).cloned() else {
-                        self.send.send_error("Can't update mod in instance, missing update action");
+                let content_install = match self.content_update_install(id, mod_id) {
+                    Ok(content_install) => content_install,
+                    Err(error) => {
+                        self.send.send_error(error);
                         modal_action.set_finished();
                         return;
-                    };
-
-                    match update_info {
-                        ModUpdateAction::ErrorNotFound => {
-                            self.send.send_error("Can't update mod in instance, 404 not found");
-                            modal_action.set_finished();
-                            return;
-                        },
-                        ModUpdateAction::ErrorInvalidHash => {
-                            self.send.send_error("Can't update mod in instance, returned invalid hash");
-                            modal_action.set_finished();
-                            return;
-                        },
-                        ModUpdateAction::AlreadyUpToDate => {
-                            self.send.send_error("Can't update mod in instance, already up-to-date");
-                            modal_action.set_finished();
-                            return;
-                        },
-                        ModUpdateAction::ManualInstall => {
-                            self.send.send_error("Can't update mod in instance, mod was manually installed");
-                            modal_action.set_finished();
-                            return;
-                        },
-                        ModUpdateAction::Modrinth { file, project_id } => {
-                            let mut path = mod_summary.path.with_file_name(&*file.filename);
-                            if !mod_summary.enabled {
-                                path.add_extension("disabled");
-                            }
-                            debug_assert!(path.is_absolute());
-                            ContentInstall {
-                                target: InstallTarget::Instance(id),
-                                loader_hint: loader,
-                                version_hint: Some(minecraft_version.into()),
-                                files: [ContentInstallFile {
-                                    replace_old: Some(mod_summary.path.clone()),
-                                    path: bridge::install::ContentInstallPath::Raw(path.into()),
-                                    download: ContentDownload::Url {
-                                        url: file.url.clone(),
-                                        sha1: file.hashes.sha1.clone(),
-                                        size: file.size,
-                                    },
-                                    content_source: ContentSource::ModrinthProject { project: project_id },
-                                }].into(),
-                            }
-                        },
-                    }
-                } else {
-                    self.send.send_error("Can't update mod in instance, unknown instance id");
-                    modal_action.set_finished();
-                    return;
+                    },
                 };
 
                 self.install_content(content_install, modal_action.clone()).await;
@@ -793,8 +1088,63 @@ impl BackendState {
                     }
                 }
             },
+            MessageToBackend::GetWorldDatapacks { id, world, channel } => {
+                _ = channel.send(self.load_world_datapacks(id, &world).map_err(|error| error.to_string().into()));
+            },
+            MessageToBackend::SetDatapacksEnabled { id, world, pack_ids, enabled, channel } => {
+                let result = self.set_datapacks_enabled(id, &world, &pack_ids, enabled);
+                if let Err(error) = &result {
+                    log::error!("Unable to change datapacks of {world:?}: {error}");
+                }
+                _ = channel.send(result.map_err(|error| error.to_string().into()));
+            },
+            MessageToBackend::GetServerResourcePacks { id, channel } => {
+                _ = channel.send(self.load_server_resource_packs(id).map_err(|error| error.to_string().into()));
+            },
+            MessageToBackend::DeleteServerResourcePacks { id, paths, channel } => {
+                let result = self.delete_server_resource_packs(id, &paths);
+                if let Err(error) = &result {
+                    log::error!("Unable to delete server resource packs: {error}");
+                }
+                _ = channel.send(result.map_err(|error| error.to_string().into()));
+            },
+            MessageToBackend::SeedServerResourcePack { id, source, pack_id, channel } => {
+                // Downloading the pack can take a while, don't hold up other messages
+                let backend = self.clone();
+                tokio::task::spawn(async move {
+                    let result = backend.seed_server_resource_pack(id, &source, pack_id).await;
+                    if let Err(error) = &result {
+                        log::error!("Unable to seed server resource pack from {source:?}: {error}");
+                    }
+                    _ = channel.send(result.map_err(|error| error.to_string().into()));
+                });
+            },
+            MessageToBackend::GetInstanceNotes { id, channel } => {
+                _ = channel.send(self.load_instance_notes(id).map_err(|error| error.to_string().into()));
+            },
+            MessageToBackend::SetInstanceNotes { id, notes, channel } => {
+                let result = self.save_instance_notes(id, &notes);
+                if let Err(error) = &result {
+                    log::error!("Unable to save instance notes: {error}");
+                }
+                _ = channel.send(result.map_err(|error| error.to_string().into()));
+            },
+            MessageToBackend::GetConfigFiles { instance: id, channel } => {
+                _ = channel.send(self.list_config_files(id));
+            },
+            MessageToBackend::ReadConfigFile { instance: id, path, channel } => {
+                _ = channel.send(self.read_config_file(id, &path).map_err(|error| error.to_string().into()));
+            },
+            MessageToBackend::WriteConfigFile { instance: id, path, content, backup, channel } => {
+                let result = self.write_config_file(id, &path, &content, backup);
+                if let Err(error) = &result {
+                    log::error!("Unable to save config file {}: {error}", path.as_str());
+                }
+                _ = channel.send(result.map_err(|error| error.to_string().into()));
+            },
             MessageToBackend::GetSyncState { channel } => {
-                let result = crate::syncing::get_sync_state(self.config.write().get().sync_targets, &self.directories);
+                let instance_roots = self.instance_roots();
+                let result = crate::syncing::get_sync_state(self.config.write().get().sync_targets, &self.directories, &instance_roots);
 
                 match result {
                     Ok(state) => {
@@ -806,12 +1156,13 @@ impl BackendState {
                 }
             },
             MessageToBackend::SetSyncing { target, value } => {
+                let instance_roots = self.instance_roots();
                 let mut write = self.config.write();
 
                 let result = if value {
-                    crate::syncing::enable_all(target, &self.directories)
+                    crate::syncing::enable_all(target, &self.directories, &instance_roots)
                 } else {
-                    crate::syncing::disable_all(target, &self.directories).map(|_| true)
+                    crate::syncing::disable_all(target, &self.directories, &instance_roots).map(|_| true)
                 };
 
                 match result {
@@ -869,6 +1220,12 @@ impl BackendState {
 
                 self.send.send_success(format!("Deleted {} files", deleted));
             },
+            MessageToBackend::CreateProblemReport { instance, description, modal_action, channel } => {
+                if let Some(report) = self.create_problem_report(instance, description, &modal_action).await {
+                    _ = channel.send(report);
+                }
+                modal_action.set_finished();
+            },
             MessageToBackend::UploadLogFile { path, modal_action } => {
                 let file = match std::fs::File::open(path) {
                     Ok(file) => file,
@@ -984,6 +1341,7 @@ impl BackendState {
                         modal_action.set_visit_url(ModalActionVisitUrl {
                             message: format!("Open {}", url).into(),
                             url: url.into(),
+                            code: None,
                             prevent_auto_finish: true,
                         });
                         modal_action.set_finished();
@@ -1009,13 +1367,19 @@ impl BackendState {
             MessageToBackend::AddNewAccount { modal_action } => {
                 self.login_flow(&modal_action, None).await;
             },
+            MessageToBackend::ReauthenticateAccount { uuid, modal_action } => {
+                self.login_flow(&modal_action, Some(uuid)).await;
+            },
             MessageToBackend::AddOfflineAccount { name, uuid } => {
                 let mut account_info = self.account_info.write();
                 account_info.modify(|account_info| {
                     account_info.accounts.insert(uuid, BackendAccount {
                         username: name,
                         offline: true,
-                        head: None
+                        head: None,
+                        skin_url: None,
+                        profile_fetched_at: None,
+                        needs_reauth: false,
                     });
                     account_info.selected_account = Some(uuid);
                 });
@@ -1042,28 +1406,515 @@ impl BackendState {
                     }
                 });
             },
+            MessageToBackend::ModrinthLogin { token, modal_action } => {
+                self.modrinth_login(token, &modal_action).await;
+                modal_action.set_finished();
+            },
+            MessageToBackend::ModrinthLogout => {
+                self.modrinth_logout().await;
+            },
+            MessageToBackend::GetRealms { minecraft_version, modal_action, channel } => {
+                let backend = self.clone();
+                tokio::task::spawn(async move {
+                    let result = backend.fetch_realms(&minecraft_version, &modal_action).await;
+                    if let Err(error) = &result {
+                        log::error!("Unable to get Realms: {error}");
+                    }
+                    modal_action.set_finished();
+                    _ = channel.send(result.map_err(|error| error.to_string().into()));
+                });
+            },
+            MessageToBackend::GetModrinthFollows { channel } => {
+                let state = self.clone();
+                tokio::task::spawn(async move {
+                    let result = state.fetch_modrinth_follows().await.map_err(|error| error.to_string().into());
+                    _ = channel.send(result);
+                });
+            },
+            MessageToBackend::GetModrinthNotifications { channel } => {
+                let state = self.clone();
+                tokio::task::spawn(async move {
+                    let result = state.fetch_modrinth_notifications().await.map_err(|error| error.to_string().into());
+                    _ = channel.send(result);
+                });
+            },
             MessageToBackend::SetOpenGameOutputAfterLaunching { value } => {
                 self.config.write().modify(|config| {
                     config.dont_open_game_output_when_launching = !value;
                 });
             },
+            MessageToBackend::SetLinkContentFiles { value } => {
+                self.config.write().modify(|config| {
+                    config.dont_link_content_files = !value;
+                });
+            },
+            MessageToBackend::SetDebugLogging { value } => {
+                self.config.write().modify(|config| {
+                    config.debug_logging = value;
+                });
+                crate::log_files::set_debug_logging(value);
+            },
+            MessageToBackend::SetDeviceCodeLogin { value } => {
+                self.config.write().modify(|config| {
+                    config.device_code_login = value;
+                });
+            },
+            MessageToBackend::SetMaxRunningInstances { value } => {
+                self.config.write().modify(|config| {
+                    config.max_running_instances = value;
+                });
+            },
+            MessageToBackend::SetMemoryBudget { mode } => {
+                self.config.write().modify(|config| {
+                    config.memory_budget = mode;
+                });
+            },
+            MessageToBackend::SetUpdateChannel { channel } => {
+                self.config.write().modify(|config| {
+                    config.update_channel = channel;
+                });
+
+                let options = self.update_check_options();
+                let backend = self.clone();
+                tokio::task::spawn(async move {
+                    let update = crate::update::check_for_updates(backend.redirecting_http_client.clone(), backend.send.clone(), options);
+                    backend.run_job("Checking for launcher updates", JobPriority::Background, update).await;
+                });
+            },
+            MessageToBackend::RestorePersistentBackup { file, backup } => {
+                // Only the backups next to the file can be restored
+                let valid = match file {
+                    PersistentFile::Config => self.config.read().valid_backups(),
+                    PersistentFile::Accounts => self.account_info.read().valid_backups(),
+                };
+                if valid.iter().any(|valid| valid.as_path() == &*backup) {
+                    self.restore_persistent_backup(file, &backup);
+                } else {
+                    self.send.send_error("Unable to restore backup, it can no longer be read");
+                }
+            },
+            MessageToBackend::SetBackupDirectory { directory } => {
+                self.config.write().modify(|config| {
+                    config.backups.directory = directory;
+                });
+            },
+            MessageToBackend::SetBackupsKept { value } => {
+                self.config.write().modify(|config| {
+                    config.backups.keep = value;
+                });
+            },
+            MessageToBackend::GetLaunchMetrics { channel } => {
+                _ = channel.send(self.launch_metrics_summary());
+            },
+            MessageToBackend::SetLaunchMetricsRecording { value } => {
+                self.config.write().modify(|config| {
+                    config.record_launch_metrics = value;
+                });
+            },
+            MessageToBackend::SetLaunchMetricsUpload { value } => {
+                self.config.write().modify(|config| {
+                    config.upload_launch_metrics = value;
+                });
+                if value {
+                    let state = self.clone();
+                    tokio::task::spawn(async move {
+                        state.upload_launch_metrics().await;
+                    });
+                }
+            },
+            MessageToBackend::ClearLaunchMetrics => {
+                self.clear_launch_metrics();
+            },
+            MessageToBackend::GetJavaRuntimes { channel } => {
+                let backend = self.clone();
+                tokio::task::spawn_blocking(move || {
+                    _ = channel.send(backend.get_java_runtimes());
+                });
+            },
+            MessageToBackend::RemoveUnusedJavaRuntimes { modal_action } => {
+                let backend = self.clone();
+                tokio::task::spawn(async move {
+                    let _job = backend.start_job("Removing unused Java runtimes", JobPriority::Interactive).await;
+                    let backend = backend.clone();
+                    let blocking_modal_action = modal_action.clone();
+                    _ = tokio::task::spawn_blocking(move || backend.remove_unused_java_runtimes(&blocking_modal_action)).await;
+                    modal_action.set_finished();
+                });
+            },
+            MessageToBackend::GetInstancePerformance { id, channel } => {
+                _ = channel.send(self.get_performance_history(id));
+            },
+            MessageToBackend::ClearInstancePerformance { id } => {
+                self.clear_performance_history(id);
+            },
+            MessageToBackend::GetInstanceHistory { id, channel } => {
+                _ = channel.send(self.get_instance_history(id));
+            },
+            MessageToBackend::GetParentalControlsStatus { channel } => {
+                _ = channel.send(self.parental_controls_status());
+            },
+            // Hashing the PIN takes a moment, so it's kept off the message loop
+            MessageToBackend::ConfigureParentalControls { pin, restrictions, channel } => {
+                let backend = self.clone();
+                tokio::task::spawn_blocking(move || {
+                    _ = channel.send(backend.configure_parental_controls(&pin, restrictions));
+                });
+            },
+            MessageToBackend::UnlockParentalControls { pin, channel } => {
+                let backend = self.clone();
+                tokio::task::spawn_blocking(move || {
+                    _ = channel.send(backend.unlock_parental_controls(&pin));
+                });
+            },
+            MessageToBackend::LockParentalControls => {
+                self.lock_parental_controls();
+            },
+            MessageToBackend::ChangeDataDirectory { target, modal_action } => {
+                let _cancel_link = self.link_cancellation(&modal_action, None);
+                self.change_data_directory(target, &modal_action).await;
+                modal_action.set_finished();
+            },
+            MessageToBackend::SetInstanceDefaults { defaults } => {
+                self.config.write().modify(|config| {
+                    config.instance_defaults = defaults;
+                });
+            },
+            MessageToBackend::SetTunnelCommand { command } => {
+                self.config.write().modify(|config| {
+                    config.tunnel_command = command;
+                });
+            },
+            MessageToBackend::SetNativesRepositories { repositories } => {
+                self.config.write().modify(|config| {
+                    config.natives_repositories = repositories;
+                });
+            },
             MessageToBackend::CreateInstanceShortcut { id, path } => {
                 if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
                     let Ok(current_exe) = std::env::current_exe() else {
                         return;
                     };
 
-                    let args = &[
-                        "--run-instance",
-                        instance.name.as_str()
-                    ];
-                    crate::shortcut::create_shortcut(path, &format!("Launch {}", instance.name), &current_exe, args);
+                    // The uuid keeps the shortcut working when the instance is renamed
+                    let uuid = instance.configuration.get().uuid.map(|uuid| uuid.to_string());
+                    let args = match &uuid {
+                        Some(uuid) => ["--run-instance-id", uuid.as_str()],
+                        None => ["--run-instance", instance.name.as_str()],
+                    };
+
+                    let icon = instance.icon.as_deref().map(|png| crate::shortcut::ShortcutIcon {
+                        png,
+                        saved_path: self.directories.shortcut_icons_dir.join(uuid.as_deref().unwrap_or(instance.name.as_str())),
+                    });
+
+                    let name = format!("Launch {}", instance.name);
+                    match crate::shortcut::create_shortcut(path, &name, &current_exe, &args, icon) {
+                        Ok(()) => self.send.send_success(format!("Created shortcut for {}", instance.name)),
+                        Err(err) => self.send.send_error(format!("Unable to create shortcut: {err}")),
+                    }
+                }
+            },
+            MessageToBackend::SetEventStreamEnabled { value } => {
+                self.config.write().modify(|config| {
+                    config.event_stream.enabled = value;
+                });
+                self.update_event_stream();
+            },
+            MessageToBackend::ResetEventStreamToken => {
+                self.config.write().modify(|config| {
+                    config.event_stream.token = None;
+                });
+                self.update_event_stream();
+            },
+            MessageToBackend::RegisterUrlHandler => {
+                // The package registers the links, a desktop file written from inside the container would point at a path which doesn't exist on the host
+                if let Some(sandbox) = Sandbox::detect() {
+                    self.send.send_info(format!("Links are registered by the {} package", sandbox.name()));
+                    return;
+                }
+                let Ok(current_exe) = std::env::current_exe() else {
+                    return;
+                };
+                let Some(base_dirs) = directories::BaseDirs::new() else {
+                    self.send.send_error("Unable to register links, unknown home directory");
+                    return;
+                };
+
+                match crate::url_handler::register_url_handler(&base_dirs.data_dir().join("applications"), &current_exe) {
+                    Ok(()) => self.send.send_success("Pandora Launcher will now open pandora:// and modrinth:// links"),
+                    Err(err) => self.send.send_error(format!("Unable to register links: {err}")),
                 }
             },
             MessageToBackend::InstallUpdate { update, modal_action } => {
                 tokio::task::spawn(crate::update::install_update(self.redirecting_http_client.clone(), self.directories.clone(), self.send.clone(), update, modal_action));
             }
+            MessageToBackend::SkipUpdate { version } => {
+                self.config.write().modify(|config| {
+                    config.skipped_update_version = Some(version);
+                });
+            }
+            MessageToBackend::RemindAboutUpdateLater => {
+                let remind_after = chrono::Utc::now() + crate::update::UPDATE_REMINDER_DELAY;
+                self.config.write().modify(|config| {
+                    config.update_reminder_after = Some(remind_after.timestamp());
+                });
+            }
+            MessageToBackend::RelaunchAfterUpdate { channel } => {
+                _ = channel.send(crate::update::relaunch_after_update());
+            }
+        }
+    }
+
+    pub async fn check_for_content_updates(&self, id: InstanceID, modal_action: &ModalAction) -> bool {
+        let (loader, version) = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+            let configuration = instance.configuration.get();
+            (configuration.loader, configuration.minecraft_version)
+        } else {
+            self.send.send_error("Can't update instance, unknown id");
+            modal_action.set_error_message("Can't update instance, unknown id".into());
+            return false;
+        };
+
+        let mut content = Vec::new();
+        for folder in ContentFolder::iter() {
+            let Some(summaries) = self.clone().load_instance_content(id, folder).await else {
+                return false;
+            };
+            content.extend_from_slice(&*summaries);
+        }
+
+        let modrinth_loader = loader.as_modrinth_loader();
+        if modrinth_loader == ModrinthLoader::Unknown {
+            modal_action.set_error_message("Unable to update instance, unsupported loader".into());
+            return false;
+        }
+
+        let tracker = ProgressTracker::new("Checking content".into(), self.send.clone());
+        tracker.set_total(content.len());
+        modal_action.trackers.push(tracker.clone());
+
+        let semaphore = Semaphore::new(8);
+
+        let mod_params = &VersionUpdateParameters {
+            loaders: [modrinth_loader].into(),
+            game_versions: [version].into(),
+        };
+
+        let fabric_mod_params = &VersionUpdateParameters {
+            loaders: [ModrinthLoader::Fabric].into(),
+            game_versions: [version].into(),
+        };
+
+        let forge_mod_params = &VersionUpdateParameters {
+            loaders: [ModrinthLoader::Forge].into(),
+            game_versions: [version].into(),
+        };
+
+        let neoforge_mod_params = &VersionUpdateParameters {
+            loaders: [ModrinthLoader::NeoForge].into(),
+            game_versions: [version].into(),
+        };
+
+        let resourcepack_params = &VersionUpdateParameters {
+            loaders: [ModrinthLoader::Minecraft].into(),
+            game_versions: [version].into(),
+        };
+
+        let modrinth_modpack_params = &VersionV3UpdateParameters {
+            loaders: ["mrpack".into()].into(),
+            loader_fields: VersionV3LoaderFields {
+                mrpack_loaders: [modrinth_loader].into(),
+                game_versions: [version].into(),
+            },
+        };
+
+        let meta = self.meta.clone();
+
+        let mut futures = Vec::new();
+
+        struct UpdateResult {
+            mod_summary: Arc<ContentSummary>,
+            action: ModUpdateAction,
+        }
+
+        { // Scope is needed so await doesn't complain about the non-send RwLockReadGuard
+            let sources = self.mod_metadata_manager.read_content_sources();
+            for summary in content.iter() {
+                let source = sources.get(&summary.content_summary.hash).unwrap_or(ContentSource::Manual);
+                let semaphore = &semaphore;
+                let meta = &meta;
+                let tracker = &tracker;
+                futures.push(async move {
+                    match source {
+                        ContentSource::Manual => {
+                            tracker.add_count(1);
+                            tracker.notify();
+                            Ok(ModUpdateAction::ManualInstall)
+                        },
+                        ContentSource::ModrinthUnknown | ContentSource::ModrinthProject { .. } => {
+                            let permit = semaphore.acquire().await.unwrap();
+                            let result = match summary.content_summary.extra {
+                                ContentType::Fabric => {
+                                    meta.fetch(&ModrinthVersionUpdateMetadataItem {
+                                        sha1: hex::encode(summary.content_summary.hash).into(),
+                                        params: fabric_mod_params.clone()
+                                    }).await
+                                },
+                                ContentType::Forge | ContentType::LegacyForge => {
+                                    meta.fetch(&ModrinthVersionUpdateMetadataItem {
+                                        sha1: hex::encode(summary.content_summary.hash).into(),
+                                        params: forge_mod_params.clone()
+                                    }).await
+                                },
+                                ContentType::NeoForge => {
+                                    meta.fetch(&ModrinthVersionUpdateMetadataItem {
+                                        sha1: hex::encode(summary.content_summary.hash).into(),
+                                        params: neoforge_mod_params.clone()
+                                    }).await
+                                },
+                                ContentType::JavaModule => {
+                                    meta.fetch(&ModrinthVersionUpdateMetadataItem {
+                                        sha1: hex::encode(summary.content_summary.hash).into(),
+                                        params: mod_params.clone()
+                                    }).await
+                                },
+                                ContentType::ModrinthModpack { .. } => {
+                                    meta.fetch(&ModrinthV3VersionUpdateMetadataItem {
+                                        sha1: hex::encode(summary.content_summary.hash).into(),
+                                        params: modrinth_modpack_params.clone()
+                                    }).await
+                                },
+                                ContentType::ResourcePack => {
+                                    meta.fetch(&ModrinthVersionUpdateMetadataItem {
+                                        sha1: hex::encode(summary.content_summary.hash).into(),
+                                        params: resourcepack_params.clone()
+                                    }).await
+                                },
+                            };
+                            drop(permit);
+
+                            tracker.add_count(1);
+                            tracker.notify();
+
+                            if let Err(MetaLoadError::NonOK(404)) = result {
+                                return Ok(ModUpdateAction::ErrorNotFound);
+                            }
+
+                            let result = result?;
+
+                            if let ContentSource::ModrinthProject { ref project } = source {
+                                if &result.0.project_id != project {
+                                    log::error!("Refusing to update {:?}, mismatched project ids: expected {}, got {}",
+                                        summary.content_summary.hash, &result.0.project_id, &project);
+                                    return Ok(ModUpdateAction::ErrorNotFound);
+                                }
+                            }
+
+                            let install_file = result
+                                .0
+                                .files
+                                .iter()
+                                .find(|file| file.primary)
+                                .unwrap_or(result.0.files.first().unwrap());
+
+                            let mut latest_hash = [0u8; 20];
+                            let Ok(_) = hex::decode_to_slice(&*install_file.hashes.sha1, &mut latest_hash) else {
+                                return Ok(ModUpdateAction::ErrorInvalidHash);
+                            };
+
+                            if latest_hash == summary.content_summary.hash {
+                                Ok(ModUpdateAction::AlreadyUpToDate)
+                            } else {
+                                Ok(ModUpdateAction::Modrinth {
+                                    file: install_file.clone(),
+                                    project_id: result.0.project_id.clone(),
+                                })
+                            }
+                        },
+                    }
+                }.map_ok(|action| UpdateResult {
+                    mod_summary: summary.content_summary.clone(),
+                    action,
+                }));
+            }
         }
+
+        let results: Result<Vec<UpdateResult>, MetaLoadError> = futures::future::try_join_all(futures).await;
+
+        match results {
+            Ok(updates) => {
+                let mut meta_updates = self.mod_metadata_manager.updates.write();
+
+                for update in updates {
+                    update.mod_summary.update_status.store(update.action.to_status(), Ordering::Relaxed);
+                    meta_updates.insert(update.mod_summary.hash, update.action);
+                }
+            },
+            Err(error) => {
+                tracker.set_finished(ProgressTrackerFinishType::Error);
+                modal_action.set_error_message(format!("Error checking for updates: {}", error).into());
+                return false;
+            },
+        }
+
+        tracker.set_finished(ProgressTrackerFinishType::Normal);
+        true
+    }
+
+    pub fn content_update_install(&self, id: InstanceID, mod_id: InstanceContentID) -> Result<ContentInstall, &'static str> {
+        let content_install = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+            let configuration = instance.configuration.get();
+            let (loader, minecraft_version) = (configuration.loader, configuration.minecraft_version);
+            let Some((mod_summary, _)) = instance.try_get_content(mod_id) else {
+                return Err("Can't update mod in instance, unknown mod id");
+            };
+
+            let Some(update_info) = self.mod_metadata_manager.updates.read().get(&mod_summary.content_summary.hash).cloned() else {
+                return Err("Can't update mod in instance, missing update action");
+            };
+
+            match update_info {
+                ModUpdateAction::ErrorNotFound => {
+                    return Err("Can't update mod in instance, 404 not found");
+                },
+                ModUpdateAction::ErrorInvalidHash => {
+                    return Err("Can't update mod in instance, returned invalid hash");
+                },
+                ModUpdateAction::AlreadyUpToDate => {
+                    return Err("Can't update mod in instance, already up-to-date");
+                },
+                ModUpdateAction::ManualInstall => {
+                    return Err("Can't update mod in instance, mod was manually installed");
+                },
+                ModUpdateAction::Modrinth { file, project_id } => {
+                    let mut path = mod_summary.path.with_file_name(&*file.filename);
+                    if !mod_summary.enabled {
+                        path.add_extension("disabled");
+                    }
+                    debug_assert!(path.is_absolute());
+                    ContentInstall {
+                        target: InstallTarget::Instance(id),
+                        loader_hint: loader,
+                        version_hint: Some(minecraft_version.into()),
+                        files: [ContentInstallFile {
+                            replace_old: Some(mod_summary.path.clone()),
+                            path: bridge::install::ContentInstallPath::Raw(path.into()),
+                            download: ContentDownload::Url {
+                                url: file.url.clone(),
+                                sha1: file.hashes.sha1.clone(),
+                                sha512: file.hashes.sha512.clone(),
+                                size: file.size,
+                            },
+                            content_source: ContentSource::ModrinthProject { project: project_id },
+                        }].into(),
+                    }
+                },
+            }
+        } else {
+            return Err("Can't update mod in instance, unknown instance id");
+        };
+
+        Ok(content_install)
     }
 
     pub async fn login_flow(&self, modal_action: &ModalAction, selected_account: Option<uuid::Uuid>) -> Option<(MinecraftProfileResponse, MinecraftAccessToken)> {
@@ -1094,7 +1945,7 @@ impl BackendState {
         let login_tracker = ProgressTracker::new(Arc::from("Logging in"), self.send.clone());
         modal_action.trackers.push(login_tracker.clone());
 
-        let login_result = self.login(&mut credentials, &login_tracker, &modal_action).await;
+        let login_result = self.login(&mut credentials, selected_account, &login_tracker, &modal_action).await;
 
         if matches!(login_result, Err(LoginError::CancelledByUser)) {
             self.send.send(MessageToFrontend::CloseModal);
@@ -1122,6 +1973,15 @@ impl BackendState {
                 }
 
                 modal_action.set_error_message(format!("Error logging in: {}", &err).into());
+                modal_action.unset_visit_url();
+                if let LoginError::AccountProblem(problem) = err && let Some((message, url)) = problem.help_link() {
+                    modal_action.set_visit_url(ModalActionVisitUrl {
+                        message: message.into(),
+                        url,
+                        code: None,
+                        prevent_auto_finish: true,
+                    });
+                }
                 login_tracker.set_finished(ProgressTrackerFinishType::Error);
                 login_tracker.notify();
                 modal_action.set_finished();
@@ -1146,24 +2006,24 @@ impl BackendState {
     }
 
     pub fn update_account_info_with_profile(&self, profile: &MinecraftProfileResponse) {
+        let now = chrono::Utc::now().timestamp();
         let mut account_info = self.account_info.write();
 
         let info = account_info.get();
-        if info.accounts.contains_key(&profile.id) && info.selected_account == Some(profile.id) {
-            drop(account_info);
-            self.update_profile_head(&profile);
-            return;
+        let up_to_date = info.selected_account == Some(profile.id) && info.accounts.get(&profile.id)
+            .is_some_and(|account| account.username == profile.name && !account.is_profile_outdated(now) && !account.needs_reauth);
+
+        if !up_to_date {
+            account_info.modify(|info| {
+                let account = info.accounts.entry(profile.id).or_insert_with(|| BackendAccount::new_from_profile(profile));
+                account.username = profile.name.clone();
+                account.profile_fetched_at = Some(now);
+                account.needs_reauth = false;
+
+                info.selected_account = Some(profile.id);
+            });
         }
 
-        account_info.modify(|info| {
-            if !info.accounts.contains_key(&profile.id) {
-                let account = BackendAccount::new_from_profile(profile);
-                info.accounts.insert(profile.id, account);
-            }
-
-            info.selected_account = Some(profile.id);
-        });
-
         drop(account_info);
         self.update_profile_head(&profile);
     }
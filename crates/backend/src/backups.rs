@@ -0,0 +1,269 @@
+use std::{path::{Path, PathBuf}, sync::Arc, time::{Duration, SystemTime, UNIX_EPOCH}};
+
+use bridge::{
+    instance::{InstanceBackupSummary, InstanceID}, job::JobPriority, modal_action::{ModalAction, ProgressTracker, ProgressTrackerFinishType}
+};
+use chrono::{Local, TimeZone};
+use schema::instance_history::InstanceHistoryEvent;
+
+use crate::BackendState;
+
+// Scheduled backups are made the first time the launcher is open after this hour, in local time
+const BACKUP_HOUR: u32 = 3;
+const BACKUP_CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+// Paths inside .minecraft which are part of a backup. Mods and other content can be downloaded again, so they
+// aren't worth the space
+const BACKUP_PATHS: &[&str] = &["saves", "config", "options.txt"];
+
+// Backups are extracted here before replacing the files of the instance, so that a broken backup doesn't leave the
+// instance half restored
+const RESTORE_DIR: &str = ".pandora_backup_restore";
+
+#[derive(thiserror::Error, Debug)]
+pub enum BackupError {
+    #[error("Instance no longer exists")]
+    MissingInstance,
+    #[error("Can't back up or restore the instance while it is running")]
+    Running,
+    #[error("The backup doesn't belong to this instance")]
+    InvalidBackup,
+    #[error("An I/O error occurred: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+impl BackendState {
+    pub async fn run_scheduled_backups(self) {
+        loop {
+            self.backup_scheduled_instances().await;
+            tokio::select! {
+                _ = tokio::time::sleep(BACKUP_CHECK_INTERVAL) => {},
+                _ = self.shutdown.cancelled() => return,
+            }
+        }
+    }
+
+    async fn backup_scheduled_instances(&self) {
+        let Some(due) = last_scheduled_time() else {
+            return;
+        };
+
+        // Running instances are skipped, they are backed up on the next check after they close
+        let scheduled: Vec<_> = self.instance_state.read().instances.iter()
+            .filter(|instance| instance.child.is_none() && instance.configuration.get().scheduled_backups)
+            .map(|instance| (instance.id, instance.name))
+            .collect();
+
+        for (id, name) in scheduled {
+            let Some(backup_dir) = self.instance_backup_dir(id) else {
+                continue;
+            };
+            let newest = list_backups(&backup_dir).last().map(|backup| backup.created).unwrap_or_default();
+            if newest >= due {
+                continue;
+            }
+
+            log::info!("Creating scheduled backup of {name}");
+            let result = self.run_job(format!("Backing up {name}"), JobPriority::Background, self.try_backup_instance(id)).await;
+            if let Err(error) = result {
+                log::error!("Unable to create scheduled backup of {name}: {error}");
+                self.send.send_error(format!("Unable to back up {name}: {error}"));
+            }
+        }
+    }
+
+    fn instance_backup_dir(&self, id: InstanceID) -> Option<PathBuf> {
+        let name = self.instance_state.read().instances.get(id)?.name;
+        let directory = self.config.write().get().backups.directory.clone();
+        let backups_dir = directory.unwrap_or_else(|| self.directories.backups_dir.to_path_buf());
+        Some(backups_dir.join(name.as_str()))
+    }
+
+    pub async fn backup_instance(&self, id: InstanceID, modal_action: &ModalAction) {
+        let tracker = ProgressTracker::new(Arc::from("Creating backup"), self.send.clone());
+        tracker.set_total(1);
+        tracker.notify();
+        modal_action.trackers.push(tracker.clone());
+
+        match self.try_backup_instance(id).await {
+            Ok(()) => {
+                tracker.set_count(1);
+                tracker.set_finished(ProgressTrackerFinishType::Normal);
+            },
+            Err(error) => {
+                log::error!("Unable to back up instance: {error}");
+                tracker.set_finished(ProgressTrackerFinishType::Error);
+                modal_action.set_error_message(error.to_string().into());
+            },
+        }
+        tracker.notify();
+    }
+
+    async fn try_backup_instance(&self, id: InstanceID) -> Result<(), BackupError> {
+        let dot_minecraft_path = {
+            let instance_state = self.instance_state.read();
+            let instance = instance_state.instances.get(id).ok_or(BackupError::MissingInstance)?;
+            if instance.child.is_some() {
+                return Err(BackupError::Running);
+            }
+            instance.dot_minecraft_path.clone()
+        };
+
+        let backup_dir = self.instance_backup_dir(id).ok_or(BackupError::MissingInstance)?;
+        let keep = self.config.write().get().backups.keep().max(1) as usize;
+
+        tokio::task::spawn_blocking(move || create_backup(&dot_minecraft_path, &backup_dir, keep)).await.unwrap()?;
+        Ok(())
+    }
+
+    pub fn get_instance_backups(&self, id: InstanceID) -> Arc<[InstanceBackupSummary]> {
+        let Some(backup_dir) = self.instance_backup_dir(id) else {
+            return Arc::from([]);
+        };
+        let mut backups = list_backups(&backup_dir);
+        backups.reverse();
+        backups.into()
+    }
+
+    pub async fn restore_instance_backup(&self, id: InstanceID, path: Arc<Path>, modal_action: &ModalAction) {
+        if let Err(error) = self.try_restore_instance_backup(id, path).await {
+            log::error!("Unable to restore backup: {error}");
+            modal_action.set_error_message(error.to_string().into());
+        }
+    }
+
+    async fn try_restore_instance_backup(&self, id: InstanceID, path: Arc<Path>) -> Result<(), BackupError> {
+        let (name, dot_minecraft_path) = {
+            let instance_state = self.instance_state.read();
+            let instance = instance_state.instances.get(id).ok_or(BackupError::MissingInstance)?;
+            if instance.child.is_some() {
+                return Err(BackupError::Running);
+            }
+            (instance.name, instance.dot_minecraft_path.clone())
+        };
+
+        // Only backups listed by get_instance_backups can be restored
+        let backup_dir = self.instance_backup_dir(id).ok_or(BackupError::MissingInstance)?;
+        if path.parent() != Some(backup_dir.as_path()) || !path.is_file() {
+            return Err(BackupError::InvalidBackup);
+        }
+
+        tokio::task::spawn_blocking(move || restore_backup(&path, &dot_minecraft_path)).await.unwrap()?;
+        self.record_instance_history(id, InstanceHistoryEvent::BackupRestored);
+        self.send.send_success(format!("Restored backup of {name}"));
+        Ok(())
+    }
+}
+
+// The most recent time a scheduled backup should have been made at, as a unix timestamp
+fn last_scheduled_time() -> Option<i64> {
+    let now = Local::now();
+    let mut scheduled = now.date_naive().and_hms_opt(BACKUP_HOUR, 0, 0)?;
+    if scheduled > now.naive_local() {
+        scheduled -= chrono::Duration::days(1);
+    }
+    Some(Local.from_local_datetime(&scheduled).earliest()?.timestamp())
+}
+
+fn create_backup(dot_minecraft_path: &Path, backup_dir: &Path, keep: usize) -> std::io::Result<()> {
+    std::fs::create_dir_all(backup_dir)?;
+
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or_default();
+    let partial_path = backup_dir.join(format!("{timestamp}.tar.gz.partial"));
+    let backup_path = backup_dir.join(format!("{timestamp}.tar.gz"));
+
+    if let Err(error) = write_backup(dot_minecraft_path, &partial_path) {
+        _ = std::fs::remove_file(&partial_path);
+        return Err(error);
+    }
+    std::fs::rename(&partial_path, &backup_path)?;
+
+    let mut backups = list_backups(backup_dir);
+    while backups.len() > keep {
+        let oldest = backups.remove(0);
+        _ = std::fs::remove_file(&oldest.path);
+    }
+
+    Ok(())
+}
+
+fn write_backup(dot_minecraft_path: &Path, output: &Path) -> std::io::Result<()> {
+    let file = std::fs::File::create(output)?;
+    let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    // Saves and configs can be links to folders synced between instances, the backup should contain their contents
+    builder.follow_symlinks(true);
+
+    for name in BACKUP_PATHS {
+        let path = dot_minecraft_path.join(name);
+        if path.is_dir() {
+            builder.append_dir_all(name, &path)?;
+        } else if path.is_file() {
+            builder.append_path_with_name(&path, name)?;
+        }
+    }
+
+    builder.into_inner()?.finish()?;
+    Ok(())
+}
+
+// Sorted from oldest to newest
+fn list_backups(backup_dir: &Path) -> Vec<InstanceBackupSummary> {
+    let Ok(read_dir) = std::fs::read_dir(backup_dir) else {
+        return Vec::new();
+    };
+
+    let mut backups: Vec<_> = read_dir.filter_map(|entry| {
+        let entry = entry.ok()?;
+        let created = entry.file_name().to_str()?.strip_suffix(".tar.gz")?.parse().ok()?;
+        let size = entry.metadata().ok()?.len();
+        Some(InstanceBackupSummary { path: entry.path().into(), created, size })
+    }).collect();
+    backups.sort_by_key(|backup| backup.created);
+    backups
+}
+
+fn restore_backup(backup: &Path, dot_minecraft_path: &Path) -> std::io::Result<()> {
+    let extract_dir = dot_minecraft_path.join(RESTORE_DIR);
+    _ = std::fs::remove_dir_all(&extract_dir);
+
+    let result = extract_and_replace(backup, dot_minecraft_path, &extract_dir);
+    _ = std::fs::remove_dir_all(&extract_dir);
+    result
+}
+
+fn extract_and_replace(backup: &Path, dot_minecraft_path: &Path, extract_dir: &Path) -> std::io::Result<()> {
+    let file = std::fs::File::open(backup)?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    archive.unpack(extract_dir)?;
+
+    for name in BACKUP_PATHS {
+        let from = extract_dir.join(name);
+        let to = dot_minecraft_path.join(name);
+        if from.is_dir() {
+            clear_dir(&to)?;
+            crate::linking::copy_dir(&from, &to, false)?;
+        } else if from.is_file() {
+            std::fs::copy(&from, &to)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Removes the contents of the folder but keeps the folder itself, since it may be a link to a synced folder
+fn clear_dir(path: &Path) -> std::io::Result<()> {
+    let Ok(read_dir) = std::fs::read_dir(path) else {
+        return Ok(());
+    };
+
+    for entry in read_dir {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            std::fs::remove_dir_all(entry.path())?;
+        } else {
+            std::fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,189 @@
+use std::{path::{Path, PathBuf}, sync::Arc};
+
+use bridge::{
+    install::{ContentDownload, ContentInstall, ContentInstallFile, ContentInstallPath, InstallTarget}, instance::InstanceID, message::BulkInstanceAction, modal_action::{ModalAction, ProgressTracker, ProgressTrackerFinishType}
+};
+use schema::{content::ContentSource, loader::Loader, modrinth::ModrinthDependencyType};
+use ustr::Ustr;
+
+use crate::{metadata::items::ModrinthVersionMetadataItem, BackendState};
+
+impl BackendState {
+    pub async fn run_bulk_instance_action(&self, ids: Vec<InstanceID>, action: BulkInstanceAction, modal_action: ModalAction) {
+        let title = match action {
+            BulkInstanceAction::Delete => "Deleting instances",
+            BulkInstanceAction::SetJvmBinary(_) => "Changing Java runtime",
+            BulkInstanceAction::UpdateLoader => "Updating loaders",
+            BulkInstanceAction::AddMods(_) => "Adding mods",
+            BulkInstanceAction::Export(_) => "Exporting instances",
+            BulkInstanceAction::InstallModrinthProject { .. } => "Installing content",
+        };
+        let tracker = ProgressTracker::new(Arc::from(title), self.send.clone());
+        tracker.set_total(ids.len());
+        tracker.notify();
+        modal_action.trackers.push(tracker.clone());
+
+        let mut failures = Vec::new();
+
+        for id in ids {
+            if modal_action.has_requested_cancel() {
+                break;
+            }
+
+            let Some((name, root_path, loader, minecraft_version, running)) = self.instance_state.write().instances.get_mut(id).map(|instance| {
+                let configuration = instance.configuration.get();
+                (instance.name, instance.root_path.clone(), configuration.loader, configuration.minecraft_version, instance.child.is_some())
+            }) else {
+                tracker.add_count(1);
+                tracker.notify();
+                continue;
+            };
+
+            let result: Result<(), String> = match &action {
+                BulkInstanceAction::Delete => {
+                    if running {
+                        Err("instance is running".into())
+                    } else {
+                        let result = std::fs::remove_dir_all(&root_path).map_err(|err| err.to_string());
+                        if result.is_ok() {
+                            self.update_external_instance(&root_path, None);
+                        }
+                        result
+                    }
+                },
+                BulkInstanceAction::SetJvmBinary(jvm_binary) => {
+                    if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                        instance.configuration.modify(|configuration| {
+                            configuration.jvm_binary = Some(jvm_binary.clone());
+                        });
+                    }
+                    Ok(())
+                },
+                BulkInstanceAction::UpdateLoader => {
+                    if loader != Loader::Vanilla && let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+                        instance.configuration.modify(|configuration| {
+                            configuration.preferred_loader_version = None;
+                        });
+                    }
+                    Ok(())
+                },
+                BulkInstanceAction::AddMods(paths) => {
+                    let content_install = ContentInstall {
+                        target: InstallTarget::Instance(id),
+                        loader_hint: loader,
+                        version_hint: Some(minecraft_version.as_str().into()),
+                        files: paths.iter().filter_map(|path| {
+                            Some(ContentInstallFile {
+                                replace_old: None,
+                                path: ContentInstallPath::Raw(Path::new("mods").join(path.file_name()?).into()),
+                                download: ContentDownload::File { path: path.clone() },
+                                content_source: ContentSource::Manual,
+                            })
+                        }).collect(),
+                    };
+                    self.install_content(content_install, modal_action.clone()).await;
+                    take_install_error(&modal_action)
+                },
+                BulkInstanceAction::InstallModrinthProject { project_id, versions } => {
+                    match versions.iter().find(|(instance, _)| *instance == id) {
+                        Some((_, version_id)) => {
+                            self.install_modrinth_version(id, loader, minecraft_version, project_id, version_id, &modal_action).await
+                        },
+                        None => Err("no compatible version".into()),
+                    }
+                },
+                BulkInstanceAction::Export(directory) => {
+                    let output = unique_export_path(directory, &name);
+                    tokio::task::spawn_blocking(move || export_instance(&root_path, &output))
+                        .await
+                        .unwrap()
+                        .map_err(|err| err.to_string())
+                },
+            };
+
+            if let Err(err) = result {
+                log::error!("Bulk action failed for {name}: {err}");
+                failures.push(format!("{name}: {err}"));
+            }
+
+            tracker.add_count(1);
+            tracker.notify();
+        }
+
+        if failures.is_empty() {
+            tracker.set_finished(ProgressTrackerFinishType::Normal);
+        } else {
+            tracker.set_finished(ProgressTrackerFinishType::Error);
+            modal_action.set_error_message(failures.join("\n").into());
+        }
+        tracker.notify();
+    }
+
+    async fn install_modrinth_version(
+        &self,
+        id: InstanceID,
+        loader: Loader,
+        minecraft_version: Ustr,
+        project_id: &Arc<str>,
+        version_id: &Arc<str>,
+        modal_action: &ModalAction,
+    ) -> Result<(), String> {
+        let version = self.meta.fetch(&ModrinthVersionMetadataItem(version_id.clone())).await.map_err(|err| err.to_string())?;
+
+        let dependencies = version.dependencies.iter().flatten()
+            .filter(|dependency| dependency.dependency_type == ModrinthDependencyType::Required)
+            .filter_map(|dependency| Some((dependency.project_id.clone()?, dependency.version_id.clone())));
+        let files = dependencies.chain(std::iter::once((project_id.clone(), Some(version_id.clone()))))
+            .map(|(project_id, version_id)| ContentInstallFile {
+                replace_old: None,
+                path: ContentInstallPath::Automatic,
+                download: ContentDownload::Modrinth {
+                    project_id: project_id.clone(),
+                    version_id,
+                },
+                content_source: ContentSource::ModrinthProject { project: project_id },
+            })
+            .collect();
+
+        let content_install = ContentInstall {
+            target: InstallTarget::Instance(id),
+            loader_hint: loader,
+            version_hint: Some(minecraft_version.as_str().into()),
+            files,
+        };
+        self.install_content(content_install, modal_action.clone()).await;
+        take_install_error(modal_action)
+    }
+}
+
+// Errors are collected per instance instead, so that one failure doesn't hide the others
+fn take_install_error(modal_action: &ModalAction) -> Result<(), String> {
+    match modal_action.error.write().unwrap().take() {
+        Some(error) => Err(error.to_string()),
+        None => Ok(()),
+    }
+}
+
+// Earlier exports are kept, later ones are numbered like "{name} (2).tar.gz"
+fn unique_export_path(directory: &Path, name: &str) -> PathBuf {
+    let mut path = directory.join(format!("{name}.tar.gz"));
+    let mut index = 2;
+    while path.exists() {
+        path = directory.join(format!("{name} ({index}).tar.gz"));
+        index += 1;
+    }
+    path
+}
+
+fn export_instance(root_path: &Path, output: &Path) -> std::io::Result<()> {
+    // Fails instead of overwriting a file which was created after the name was picked
+    let file = std::fs::File::create_new(output)?;
+    let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    builder.follow_symlinks(false);
+
+    let name = root_path.file_name().unwrap_or_default();
+    builder.append_dir_all(name, root_path)?;
+    builder.into_inner()?.finish()?;
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use std::path::{Path, PathBuf};
+
+use bridge::{instance::InstanceID, modal_action::ModalAction};
+use tokio_util::sync::{CancellationToken, DropGuard};
+
+use crate::BackendState;
+
+impl BackendState {
+    // Cancels the modal action when the launcher is closing or the instance it's working on is removed, so the
+    // existing cancellation checks for the cancel button also cover those. The link is dropped with the guard
+    #[must_use]
+    pub fn link_cancellation(&self, modal_action: &ModalAction, instance: Option<InstanceID>) -> DropGuard {
+        let request_cancel = modal_action.request_cancel.clone();
+        let shutdown = self.shutdown.clone();
+        let instance_cancel = instance
+            .and_then(|id| self.instance_state.read().instances.get(id).map(|instance| instance.cancel_tasks.clone()))
+            .unwrap_or_default();
+
+        let finished = CancellationToken::new();
+        let guard = finished.clone().drop_guard();
+
+        tokio::task::spawn(async move {
+            tokio::select! {
+                _ = shutdown.cancelled() => request_cancel.cancel(),
+                _ = instance_cancel.cancelled() => request_cancel.cancel(),
+                _ = request_cancel.cancelled() => {},
+                _ = finished.cancelled() => {},
+            }
+        });
+
+        guard
+    }
+}
+
+// A file which is being written to. It's removed when dropped unless it was persisted, so a cancelled download
+// doesn't leave half a file behind
+pub struct PartialFile {
+    path: PathBuf,
+    persisted: bool,
+}
+
+impl PartialFile {
+    pub fn new(target: &Path) -> Self {
+        Self {
+            path: target.with_added_extension("part"),
+            persisted: false,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn persist(mut self, target: &Path) -> std::io::Result<()> {
+        std::fs::rename(&self.path, target)?;
+        self.persisted = true;
+        Ok(())
+    }
+}
+
+impl Drop for PartialFile {
+    fn drop(&mut self) {
+        if !self.persisted {
+            _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
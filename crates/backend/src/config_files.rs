@@ -0,0 +1,118 @@
+use std::{path::Path, sync::Arc};
+
+use bridge::{instance::InstanceID, safe_path::SafePath};
+
+use crate::BackendState;
+
+const CONFIG_EXTENSIONS: &[&str] = &["toml", "json", "json5", "properties", "cfg", "conf", "ini", "txt", "yml", "yaml", "snbt"];
+
+// Files larger than this are probably not meant to be edited by hand
+const MAX_CONFIG_FILE_SIZE: u64 = 4 * 1024 * 1024;
+
+const MAX_DEPTH: usize = 8;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigFileError {
+    #[error("Instance no longer exists")]
+    MissingInstance,
+    #[error("File is too large to edit ({0} bytes)")]
+    TooLarge(u64),
+    #[error("File isn't valid UTF-8 text")]
+    NotText,
+    #[error("Unable to create backup: {0}")]
+    Backup(std::io::Error),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl BackendState {
+    fn instance_config_dir(&self, id: InstanceID) -> Option<Arc<Path>> {
+        let instance_state = self.instance_state.read();
+        let instance = instance_state.instances.get(id)?;
+        Some(instance.dot_minecraft_path.join("config").into())
+    }
+
+    pub fn list_config_files(&self, id: InstanceID) -> Arc<[SafePath]> {
+        let Some(config_dir) = self.instance_config_dir(id) else {
+            return Arc::from([]);
+        };
+
+        let mut files = Vec::new();
+        collect_config_files(&config_dir, "", 0, &mut files);
+        files.sort_by(|a, b| a.as_str().to_lowercase().cmp(&b.as_str().to_lowercase()));
+        files.into()
+    }
+
+    pub fn read_config_file(&self, id: InstanceID, path: &SafePath) -> Result<Arc<str>, ConfigFileError> {
+        let config_dir = self.instance_config_dir(id).ok_or(ConfigFileError::MissingInstance)?;
+        let path = path.to_path(&config_dir);
+
+        let size = std::fs::metadata(&path)?.len();
+        if size > MAX_CONFIG_FILE_SIZE {
+            return Err(ConfigFileError::TooLarge(size));
+        }
+
+        let bytes = std::fs::read(&path)?;
+        let content = String::from_utf8(bytes).map_err(|_| ConfigFileError::NotText)?;
+        Ok(content.into())
+    }
+
+    pub fn write_config_file(&self, id: InstanceID, path: &SafePath, content: &str, backup: bool) -> Result<(), ConfigFileError> {
+        let config_dir = self.instance_config_dir(id).ok_or(ConfigFileError::MissingInstance)?;
+        let path = path.to_path(&config_dir);
+
+        if backup && path.exists() {
+            let mut backup_path = path.clone();
+            backup_path.add_extension("bak");
+            std::fs::copy(&path, &backup_path).map_err(ConfigFileError::Backup)?;
+        }
+
+        crate::write_safe(&path, content.as_bytes())?;
+        Ok(())
+    }
+}
+
+fn collect_config_files(dir: &Path, prefix: &str, depth: usize, files: &mut Vec<SafePath>) {
+    if depth > MAX_DEPTH {
+        return;
+    }
+
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in read_dir {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let filename = entry.file_name();
+        let Some(filename) = filename.to_str() else {
+            continue;
+        };
+
+        let relative = if prefix.is_empty() {
+            filename.to_string()
+        } else {
+            format!("{prefix}/{filename}")
+        };
+
+        if file_type.is_dir() {
+            collect_config_files(&entry.path(), &relative, depth + 1, files);
+            continue;
+        }
+
+        let Some((_, extension)) = filename.rsplit_once('.') else {
+            continue;
+        };
+        if !CONFIG_EXTENSIONS.iter().any(|known| known.eq_ignore_ascii_case(extension)) {
+            continue;
+        }
+
+        if let Some(safe_path) = SafePath::new(&relative) {
+            files.push(safe_path);
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::{path::PathBuf, sync::Arc};
+
+use bridge::modal_action::ProgressTracker;
+use futures::{future::{BoxFuture, Shared}, FutureExt};
+use rustc_hash::FxHashMap;
+use tokio::sync::Semaphore;
+
+use crate::{content_library::ContentHash, install_content::ContentInstallError};
+
+// Maximum number of content files downloaded at the same time, across every install
+const MAX_CONCURRENT_DOWNLOADS: usize = 8;
+
+pub type SharedDownload = Shared<BoxFuture<'static, Result<PathBuf, Arc<ContentInstallError>>>>;
+
+// Content installs go through a single queue so that installing several modpacks at once doesn't open more
+// connections, and a file which is already being downloaded by another install is only fetched once
+pub struct ContentDownloadQueue {
+    pub permits: Semaphore,
+    in_flight: parking_lot::Mutex<FxHashMap<ContentHash, (SharedDownload, ProgressTracker)>>,
+}
+
+impl Default for ContentDownloadQueue {
+    fn default() -> Self {
+        Self {
+            permits: Semaphore::new(MAX_CONCURRENT_DOWNLOADS),
+            in_flight: Default::default(),
+        }
+    }
+}
+
+impl ContentDownloadQueue {
+    // Joins the download of the file with the given hash if it's already in flight, otherwise starts it. The
+    // download finishes even if the install that started it is cancelled, as long as another install is waiting on it
+    pub fn download(
+        self: &Arc<Self>,
+        hash: ContentHash,
+        start: impl FnOnce() -> (BoxFuture<'static, Result<PathBuf, ContentInstallError>>, ProgressTracker),
+    ) -> (QueuedDownload, ProgressTracker) {
+        let mut in_flight = self.in_flight.lock();
+        if let Some((download, tracker)) = in_flight.get(&hash) {
+            return (self.waiter(hash, download.clone()), tracker.clone());
+        }
+
+        let (download, tracker) = start();
+        let queue = self.clone();
+        let download = async move {
+            let result = download.await.map_err(Arc::new);
+            queue.in_flight.lock().remove(&hash);
+            result
+        }.boxed().shared();
+
+        in_flight.insert(hash, (download.clone(), tracker.clone()));
+        (self.waiter(hash, download), tracker)
+    }
+
+    fn waiter(self: &Arc<Self>, hash: ContentHash, download: SharedDownload) -> QueuedDownload {
+        QueuedDownload {
+            queue: self.clone(),
+            hash,
+            download: Some(download),
+        }
+    }
+}
+
+// A handle to a download in the queue. When every install waiting on a download has been cancelled, the download
+// itself is dropped rather than being left paused in the queue
+pub struct QueuedDownload {
+    queue: Arc<ContentDownloadQueue>,
+    hash: ContentHash,
+    download: Option<SharedDownload>,
+}
+
+impl QueuedDownload {
+    pub async fn wait(mut self) -> Result<PathBuf, Arc<ContentInstallError>> {
+        self.download.as_mut().unwrap().await
+    }
+}
+
+impl Drop for QueuedDownload {
+    fn drop(&mut self) {
+        drop(self.download.take());
+
+        let mut in_flight = self.queue.in_flight.lock();
+        let abandoned = if let Some((download, _)) = in_flight.get(&self.hash)
+            && download.strong_count() == Some(1)
+        {
+            in_flight.remove(&self.hash)
+        } else {
+            None
+        };
+        drop(in_flight);
+        drop(abandoned);
+    }
+}
@@ -0,0 +1,248 @@
+use std::{io::Write, path::{Path, PathBuf}, sync::Arc, time::UNIX_EPOCH};
+
+use parking_lot::RwLock;
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use sha2::Sha512;
+
+// A hash which content is expected to match. Files in the content library are stored by their sha1 since that's
+// what Modrinth's version file API and modpack indexes use, sha512 is also checked whenever Modrinth provides it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContentHash {
+    Sha1([u8; 20]),
+    Sha512([u8; 64]),
+}
+
+impl ContentHash {
+    pub fn parse_sha1(hex: &str) -> Option<Self> {
+        let mut hash = [0u8; 20];
+        hex::decode_to_slice(hex, &mut hash).ok()?;
+        Some(Self::Sha1(hash))
+    }
+
+    pub fn parse_sha512(hex: &str) -> Option<Self> {
+        let mut hash = [0u8; 64];
+        hex::decode_to_slice(hex, &mut hash).ok()?;
+        Some(Self::Sha512(hash))
+    }
+
+    pub fn matches(&self, hashes: &FileHashes) -> bool {
+        match self {
+            ContentHash::Sha1(hash) => *hash == hashes.sha1,
+            ContentHash::Sha512(hash) => *hash == hashes.sha512,
+        }
+    }
+}
+
+// Every hash the content library keeps track of, computed in a single pass over the file
+#[derive(Debug, Clone, Copy)]
+pub struct FileHashes {
+    pub sha1: [u8; 20],
+    pub sha512: [u8; 64],
+    pub blake3: [u8; 32],
+}
+
+impl FileHashes {
+    pub fn of_file(path: &Path) -> std::io::Result<Self> {
+        let mut file = std::fs::File::open(path)?;
+        let mut hasher = FileHasher::default();
+        std::io::copy(&mut file, &mut hasher)?;
+        Ok(hasher.finalize())
+    }
+
+    pub fn of_bytes(bytes: &[u8]) -> Self {
+        let mut hasher = FileHasher::default();
+        hasher.update(bytes);
+        hasher.finalize()
+    }
+}
+
+#[derive(Default)]
+pub struct FileHasher {
+    sha1: Sha1,
+    sha512: Sha512,
+    blake3: blake3::Hasher,
+}
+
+impl FileHasher {
+    pub fn update(&mut self, data: &[u8]) {
+        self.sha1.update(data);
+        self.sha512.update(data);
+        self.blake3.update(data);
+    }
+
+    pub fn finalize(self) -> FileHashes {
+        let mut sha512 = [0u8; 64];
+        sha512.copy_from_slice(&self.sha512.finalize());
+        FileHashes {
+            sha1: self.sha1.finalize().into(),
+            sha512,
+            blake3: self.blake3.finalize().into(),
+        }
+    }
+}
+
+impl Write for FileHasher {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct IndexEntry {
+    size: u64,
+    modified_millis: u64,
+    #[serde(with = "hex")]
+    sha512: [u8; 64],
+    #[serde(with = "hex")]
+    blake3: [u8; 32],
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct IndexFile {
+    // Keyed by the hex encoded sha1, i.e. the file name in the content library without its extension
+    entries: FxHashMap<Arc<str>, IndexEntry>,
+}
+
+// Remembers the sha512 and blake3 of files in the content library. Files whose size and modification time haven't
+// changed are verified using blake3, which is much faster than checking sha1 and sha512 again. The index is only a
+// cache, entries which are missing or out of date are rebuilt from the file itself
+pub struct ContentLibrary {
+    dir: Arc<Path>,
+    index_path: Arc<Path>,
+    index: RwLock<IndexFile>,
+    dirty: std::sync::atomic::AtomicBool,
+}
+
+impl ContentLibrary {
+    pub fn load(dir: Arc<Path>, index_path: Arc<Path>) -> Self {
+        let index = crate::read_json(&index_path).unwrap_or_default();
+        Self {
+            dir,
+            index_path,
+            index: RwLock::new(index),
+            dirty: Default::default(),
+        }
+    }
+
+    // Checks that the file at `path` exists and matches every expected hash, the first of which must be the sha1
+    // the file is stored by
+    pub fn verify(&self, path: &Path, expected: &[ContentHash]) -> bool {
+        let Some(ContentHash::Sha1(sha1)) = expected.first() else {
+            return false;
+        };
+        let Some((size, modified_millis)) = size_and_modified(path) else {
+            return false;
+        };
+
+        let key: Arc<str> = hex::encode(sha1).into();
+        let entry = self.index.read().entries.get(&key).copied();
+        if let Some(entry) = entry && entry.size == size && entry.modified_millis == modified_millis {
+            let Ok(mut file) = std::fs::File::open(path) else {
+                return false;
+            };
+            let mut hasher = blake3::Hasher::new();
+            if std::io::copy(&mut file, &mut hasher).is_ok() && *hasher.finalize().as_bytes() == entry.blake3 {
+                return expected.iter().all(|hash| match hash {
+                    ContentHash::Sha1(hash) => hash == sha1,
+                    ContentHash::Sha512(hash) => *hash == entry.sha512,
+                });
+            }
+        }
+
+        let Ok(hashes) = FileHashes::of_file(path) else {
+            return false;
+        };
+        if hashes.sha1 != *sha1 {
+            self.forget(&key);
+            return false;
+        }
+        self.record(path, &hashes);
+        expected.iter().all(|hash| hash.matches(&hashes))
+    }
+
+    pub fn record(&self, path: &Path, hashes: &FileHashes) {
+        let Some((size, modified_millis)) = size_and_modified(path) else {
+            return;
+        };
+        let entry = IndexEntry {
+            size,
+            modified_millis,
+            sha512: hashes.sha512,
+            blake3: hashes.blake3,
+        };
+        self.index.write().entries.insert(hex::encode(hashes.sha1).into(), entry);
+        self.dirty.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    fn forget(&self, key: &Arc<str>) {
+        if self.index.write().entries.remove(key).is_some() {
+            self.dirty.store(true, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+
+    pub fn save(&self) {
+        if !self.dirty.swap(false, std::sync::atomic::Ordering::Relaxed) {
+            return;
+        }
+        let bytes = serde_json::to_vec(&*self.index.read()).unwrap();
+        if let Err(err) = crate::write_safe(&self.index_path, &bytes) {
+            log::error!("Unable to save content library index: {err}");
+        }
+    }
+
+    // Adds files downloaded before the index existed. Files which don't match the sha1 they're stored by are left
+    // alone, they'll be downloaded again the next time they're needed
+    pub fn migrate(&self) {
+        let mut indexed = 0;
+        for path in library_files(&self.dir) {
+            let Some(key) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            if key.len() != 40 || self.index.read().entries.contains_key(key) {
+                continue;
+            }
+
+            let Ok(hashes) = FileHashes::of_file(&path) else {
+                continue;
+            };
+            if hex::encode(hashes.sha1) != key {
+                log::warn!("Content library file {:?} doesn't match its hash", path);
+                continue;
+            }
+            self.record(&path, &hashes);
+            indexed += 1;
+        }
+
+        if indexed > 0 {
+            log::info!("Indexed {indexed} content library files");
+            self.save();
+        }
+    }
+}
+
+fn size_and_modified(path: &Path) -> Option<(u64, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some((metadata.len(), modified.as_millis() as u64))
+}
+
+// The content library is split into folders by the first two characters of the hash
+fn library_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for folder in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+        for entry in std::fs::read_dir(folder.path()).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.is_file() && path.extension().is_none_or(|extension| extension != "lock") {
+                files.push(path);
+            }
+        }
+    }
+    files
+}
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use bridge::{instance::{InstanceContentSummary, InstanceID}, modal_action::ModalAction};
+use schema::instance::InstanceContentProfile;
+
+use crate::{instance::ContentFolder, BackendState};
+
+#[derive(thiserror::Error, Debug)]
+pub enum ContentProfileError {
+    #[error("Instance no longer exists")]
+    MissingInstance,
+    #[error("Profile {0} doesn't exist")]
+    MissingProfile(Arc<str>),
+    #[error("Unable to load the content of the instance")]
+    LoadFailed,
+    #[error("Can't change content while the instance is running")]
+    Running,
+}
+
+// Profiles refer to content by mod id so that they still apply after an update renames the file
+fn profile_key(content: &InstanceContentSummary) -> Arc<str> {
+    match &content.content_summary.id {
+        Some(id) => id.clone(),
+        None => content.filename.strip_suffix(".disabled").unwrap_or(&content.filename).into(),
+    }
+}
+
+impl BackendState {
+    async fn load_all_content(&self, id: InstanceID) -> Result<Vec<InstanceContentSummary>, ContentProfileError> {
+        let mut all_content = Vec::new();
+        for folder in [ContentFolder::Mods, ContentFolder::ResourcePacks] {
+            let content = self.clone().load_instance_content(id, folder).await.ok_or(ContentProfileError::LoadFailed)?;
+            all_content.extend(content.iter().cloned());
+        }
+        Ok(all_content)
+    }
+
+    // Stores the content that is currently disabled as a profile, replacing any profile with the same name
+    pub async fn save_content_profile(&self, id: InstanceID, name: Arc<str>) {
+        let content = match self.load_all_content(id).await {
+            Ok(content) => content,
+            Err(error) => {
+                self.send.send_error(format!("Unable to save content profile: {error}"));
+                return;
+            },
+        };
+
+        let profile = InstanceContentProfile {
+            disabled: content.iter().filter(|content| !content.enabled).map(profile_key).collect(),
+        };
+        if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+            instance.configuration.modify(|configuration| {
+                configuration.content_profiles.insert(name.clone(), profile);
+                configuration.active_content_profile = Some(name);
+            });
+        }
+    }
+
+    pub fn delete_content_profile(&self, id: InstanceID, name: &str) {
+        if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+            instance.configuration.modify(|configuration| {
+                configuration.content_profiles.remove(name);
+                if configuration.active_content_profile.as_deref() == Some(name) {
+                    configuration.active_content_profile = None;
+                }
+            });
+        }
+    }
+
+    pub async fn apply_content_profile(&self, id: InstanceID, name: Arc<str>, modal_action: &ModalAction) {
+        if let Err(error) = self.try_apply_content_profile(id, name).await {
+            log::error!("Unable to apply content profile: {error}");
+            modal_action.set_error_message(error.to_string().into());
+        }
+    }
+
+    async fn try_apply_content_profile(&self, id: InstanceID, name: Arc<str>) -> Result<(), ContentProfileError> {
+        let profile = {
+            let instance_state = self.instance_state.read();
+            let instance = instance_state.instances.get(id).ok_or(ContentProfileError::MissingInstance)?;
+            if instance.child.is_some() {
+                return Err(ContentProfileError::Running);
+            }
+            instance.configuration.get().content_profiles.get(&name).cloned()
+                .ok_or_else(|| ContentProfileError::MissingProfile(name.clone()))?
+        };
+
+        let content = self.load_all_content(id).await?;
+
+        // Content that isn't mentioned by the profile is enabled, so content installed after the profile was saved
+        // is part of every profile
+        let (disable, enable): (Vec<_>, Vec<_>) = content.iter()
+            .filter(|content| content.enabled == profile.disabled.contains(&profile_key(content)))
+            .partition(|content| content.enabled);
+        self.set_content_enabled(id, disable.into_iter().map(|content| content.id).collect(), false);
+        self.set_content_enabled(id, enable.into_iter().map(|content| content.id).collect(), true);
+
+        if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+            instance.configuration.modify(|configuration| configuration.active_content_profile = Some(name));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,496 @@
+use std::{cmp::Ordering, sync::Arc};
+
+use bridge::instance::{ContentDependencyKind, ContentProblem, ContentProblemEntry, ContentType, InstanceContentSummary, InstanceID};
+use rustc_hash::{FxHashMap, FxHashSet};
+use schema::loader::Loader;
+
+use crate::{instance::ContentFolder, BackendState};
+
+// Ids that are satisfied by the game, the loader or libraries bundled with the loader
+const BUILTIN_IDS: &[&str] = &[
+    "minecraft",
+    "java",
+    "fabricloader",
+    "fabric-loader",
+    "quilt_loader",
+    "forge",
+    "fml",
+    "mcp",
+    "neoforge",
+    "mixinextras",
+];
+
+// Pairs of mods that are known to crash when used together, but don't declare it themselves
+const KNOWN_INCOMPATIBILITIES: &[(&str, &str)] = &[
+    ("optifabric", "sodium"),
+    ("optifabric", "iris"),
+    ("optifine", "embeddium"),
+    ("optifine", "rubidium"),
+];
+
+impl BackendState {
+    pub async fn validate_instance_content(self, id: InstanceID) -> Arc<[ContentProblem]> {
+        let (loader, minecraft_version) = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+            let configuration = instance.configuration.get();
+            (configuration.loader, configuration.minecraft_version)
+        } else {
+            return Arc::from([]);
+        };
+
+        if loader == Loader::Vanilla {
+            return Arc::from([]);
+        }
+
+        let Some(mods) = self.load_instance_content(id, ContentFolder::Mods).await else {
+            return Arc::from([]);
+        };
+
+        validate_content(&mods, loader, &minecraft_version).into()
+    }
+}
+
+pub fn validate_content(mods: &[InstanceContentSummary], loader: Loader, minecraft_version: &str) -> Vec<ContentProblem> {
+    let mods: Vec<&InstanceContentSummary> = mods.iter().filter(|summary| summary.enabled).collect();
+
+    let mut by_id: FxHashMap<&str, Vec<&InstanceContentSummary>> = FxHashMap::default();
+    let mut provided: FxHashSet<&str> = FxHashSet::default();
+    for summary in &mods {
+        let content_summary = &summary.content_summary;
+        if let Some(id) = &content_summary.id {
+            by_id.entry(id).or_default().push(summary);
+        }
+        provided.extend(content_summary.provides.iter().map(|id| &**id));
+
+        if let ContentType::ModrinthModpack { summaries, .. } = &content_summary.extra {
+            for child in summaries.iter().flatten() {
+                provided.extend(child.id.iter().chain(child.provides.iter()).map(|id| &**id));
+            }
+        }
+    }
+
+    let has_connector = by_id.contains_key("connector");
+
+    let mut problems = Vec::new();
+
+    let mut duplicates: Vec<_> = by_id.iter().filter(|(_, entries)| entries.len() > 1).collect();
+    duplicates.sort_by_key(|(id, _)| **id);
+    for (id, entries) in duplicates {
+        problems.push(ContentProblem::Duplicate {
+            mod_id: (*id).into(),
+            entries: entries.iter().map(|summary| problem_entry(summary)).collect(),
+        });
+    }
+
+    let mut reported_incompatible: FxHashSet<(usize, usize)> = FxHashSet::default();
+    let mut report_incompatible = |problems: &mut Vec<ContentProblem>, a: &InstanceContentSummary, b: &InstanceContentSummary| {
+        let key = (a.id.index.min(b.id.index), a.id.index.max(b.id.index));
+        if a.id != b.id && reported_incompatible.insert(key) {
+            problems.push(ContentProblem::Incompatible {
+                entry: problem_entry(a),
+                other: problem_entry(b),
+            });
+        }
+    };
+
+    for summary in &mods {
+        let content_summary = &summary.content_summary;
+
+        let wrong_loader = match (&content_summary.extra, loader) {
+            (ContentType::Fabric, Loader::Forge | Loader::NeoForge) => !has_connector,
+            (ContentType::Forge | ContentType::LegacyForge | ContentType::NeoForge, Loader::Fabric) => true,
+            (ContentType::NeoForge, Loader::Forge) => true,
+            // NeoForge only remained compatible with Forge mods for 1.20.1
+            (ContentType::Forge, Loader::NeoForge) => minecraft_version != "1.20.1",
+            (ContentType::LegacyForge, Loader::NeoForge) => true,
+            _ => false,
+        };
+        if wrong_loader {
+            problems.push(ContentProblem::WrongLoader {
+                entry: problem_entry(summary),
+                loader: content_type_loader_name(&content_summary.extra).into(),
+            });
+            continue;
+        }
+
+        let range_matches: fn(&str, &str) -> Option<bool> = match content_summary.extra {
+            ContentType::Fabric => fabric_range_matches,
+            ContentType::Forge | ContentType::LegacyForge | ContentType::NeoForge => maven_range_matches,
+            _ => continue,
+        };
+
+        for dependency in content_summary.dependencies.iter() {
+            let dependency_id = &*dependency.id;
+            match dependency.kind {
+                ContentDependencyKind::Required => {
+                    if dependency_id.eq_ignore_ascii_case("minecraft") {
+                        if let Some(version_range) = &dependency.version_range
+                            && range_matches(version_range, minecraft_version) == Some(false)
+                        {
+                            problems.push(ContentProblem::WrongGameVersion {
+                                entry: problem_entry(summary),
+                                version_range: version_range.clone(),
+                            });
+                        }
+                        continue;
+                    }
+                    if BUILTIN_IDS.iter().any(|builtin| builtin.eq_ignore_ascii_case(dependency_id)) {
+                        continue;
+                    }
+
+                    if let Some(installed) = by_id.get(dependency_id) {
+                        let Some(version_range) = &dependency.version_range else {
+                            continue;
+                        };
+                        let accepted = installed.iter().any(|installed| {
+                            range_matches(version_range, installed_version(installed)) != Some(false)
+                        });
+                        if !accepted {
+                            problems.push(ContentProblem::MissingDependency {
+                                content_name: content_name(summary),
+                                dependency: dependency.id.clone(),
+                                version_range: Some(version_range.clone()),
+                                installed_version: Some(installed_version(installed[0]).into()),
+                            });
+                        }
+                    } else if !provided.contains(dependency_id) {
+                        problems.push(ContentProblem::MissingDependency {
+                            content_name: content_name(summary),
+                            dependency: dependency.id.clone(),
+                            version_range: dependency.version_range.clone(),
+                            installed_version: None,
+                        });
+                    }
+                },
+                ContentDependencyKind::Incompatible => {
+                    let Some(installed) = by_id.get(dependency_id) else {
+                        continue;
+                    };
+                    for other in installed {
+                        let incompatible = match &dependency.version_range {
+                            Some(version_range) => range_matches(version_range, installed_version(other)) != Some(false),
+                            None => true,
+                        };
+                        if incompatible {
+                            report_incompatible(&mut problems, summary, other);
+                        }
+                    }
+                },
+                ContentDependencyKind::Optional => {},
+            }
+        }
+    }
+
+    for (a, b) in KNOWN_INCOMPATIBILITIES {
+        if let Some(a) = by_id.get(a) && let Some(b) = by_id.get(b) {
+            report_incompatible(&mut problems, a[0], b[0]);
+        }
+    }
+
+    problems
+}
+
+fn problem_entry(summary: &InstanceContentSummary) -> ContentProblemEntry {
+    ContentProblemEntry {
+        content_id: summary.id,
+        name: content_name(summary),
+        version: summary.content_summary.version_str.clone(),
+    }
+}
+
+fn content_name(summary: &InstanceContentSummary) -> Arc<str> {
+    summary.content_summary.name.clone().unwrap_or_else(|| summary.filename.clone())
+}
+
+fn installed_version(summary: &InstanceContentSummary) -> &str {
+    let version = &*summary.content_summary.version_str;
+    version.strip_prefix('v').unwrap_or(version)
+}
+
+fn content_type_loader_name(content_type: &ContentType) -> &'static str {
+    match content_type {
+        ContentType::Fabric => "Fabric",
+        ContentType::LegacyForge | ContentType::Forge => "Forge",
+        ContentType::NeoForge => "NeoForge",
+        _ => "Unknown",
+    }
+}
+
+// Compares versions by their numeric components, with pre-releases ordered before their release
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn split(version: &str) -> (&str, Option<&str>) {
+        let version = version.split('+').next().unwrap_or(version);
+        match version.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (version, None),
+        }
+    }
+
+    fn compare_component(a: &str, b: &str) -> Ordering {
+        match (a.parse::<u64>(), b.parse::<u64>()) {
+            (Ok(a), Ok(b)) => a.cmp(&b),
+            (Ok(_), Err(_)) => Ordering::Greater,
+            (Err(_), Ok(_)) => Ordering::Less,
+            (Err(_), Err(_)) => a.cmp(b),
+        }
+    }
+
+    let (a_core, a_pre) = split(a);
+    let (b_core, b_pre) = split(b);
+
+    let mut a_components = a_core.split('.');
+    let mut b_components = b_core.split('.');
+    loop {
+        let ordering = match (a_components.next(), b_components.next()) {
+            (None, None) => break,
+            (a, b) => compare_component(a.unwrap_or("0"), b.unwrap_or("0")),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a_pre), Some(b_pre)) => {
+            let mut a_components = a_pre.split('.');
+            let mut b_components = b_pre.split('.');
+            loop {
+                let ordering = match (a_components.next(), b_components.next()) {
+                    (None, None) => return Ordering::Equal,
+                    (None, Some(_)) => return Ordering::Less,
+                    (Some(_), None) => return Ordering::Greater,
+                    (Some(a), Some(b)) => compare_component(a, b),
+                };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+        },
+    }
+}
+
+// Returns None if the range can't be understood, in which case no problem should be reported
+fn fabric_range_matches(range: &str, version: &str) -> Option<bool> {
+    let mut unknown = false;
+    for alternative in range.split("||") {
+        let mut matches = Some(true);
+        for predicate in alternative.split_whitespace() {
+            match fabric_predicate_matches(predicate, version) {
+                Some(true) => {},
+                Some(false) => {
+                    matches = Some(false);
+                    break;
+                },
+                None => matches = None,
+            }
+        }
+        match matches {
+            Some(true) => return Some(true),
+            Some(false) => {},
+            None => unknown = true,
+        }
+    }
+    if unknown { None } else { Some(false) }
+}
+
+fn fabric_predicate_matches(predicate: &str, version: &str) -> Option<bool> {
+    if predicate == "*" {
+        return Some(true);
+    }
+
+    let (operator, target) = [">=", "<=", ">", "<", "=", "^", "~"].iter()
+        .find_map(|operator| predicate.strip_prefix(operator).map(|target| (*operator, target)))
+        .unwrap_or(("", predicate));
+
+    let is_wildcard = |component: &str| component == "x" || component == "X" || component == "*";
+    if target.split('.').any(is_wildcard) {
+        if !operator.is_empty() && operator != "=" {
+            return None;
+        }
+        let mut version_components = version.split(['.', '-', '+']);
+        for component in target.split('.') {
+            if is_wildcard(component) {
+                return Some(true);
+            }
+            if version_components.next().unwrap_or("0") != component {
+                return Some(false);
+            }
+        }
+        return Some(true);
+    }
+
+    if target.is_empty() {
+        return None;
+    }
+
+    let ordering = compare_versions(version, target);
+    let matches = match operator {
+        ">=" => ordering.is_ge(),
+        "<=" => ordering.is_le(),
+        ">" => ordering.is_gt(),
+        "<" => ordering.is_lt(),
+        "" | "=" => ordering.is_eq(),
+        "^" | "~" => {
+            let mut components = target.split(['-', '+']).next().unwrap_or(target).split('.');
+            let major: u64 = components.next()?.parse().ok()?;
+            let upper = if operator == "^" {
+                format!("{}", major + 1)
+            } else {
+                let minor: u64 = components.next().unwrap_or("0").parse().ok()?;
+                format!("{}.{}", major, minor + 1)
+            };
+            ordering.is_ge() && compare_versions(version, &upper).is_lt()
+        },
+        _ => return None,
+    };
+    Some(matches)
+}
+
+// Returns None if the range can't be understood, in which case no problem should be reported
+fn maven_range_matches(range: &str, version: &str) -> Option<bool> {
+    let range = range.trim();
+    if !range.starts_with(['[', '(']) {
+        // A plain version is only a recommendation in maven ranges
+        return Some(true);
+    }
+
+    let mut rest = range;
+    while !rest.is_empty() {
+        let start_inclusive = match rest.chars().next()? {
+            '[' => true,
+            '(' => false,
+            _ => return None,
+        };
+        let end = rest.find([']', ')'])?;
+        let end_inclusive = rest[end..].starts_with(']');
+        let inner = &rest[1..end];
+        rest = rest[end + 1..].trim_start_matches([',', ' ']);
+
+        let matches = match inner.split_once(',') {
+            None => compare_versions(version, inner.trim()).is_eq(),
+            Some((lower, upper)) => {
+                let lower = lower.trim();
+                let upper = upper.trim();
+                let lower_matches = lower.is_empty() || {
+                    let ordering = compare_versions(version, lower);
+                    if start_inclusive { ordering.is_ge() } else { ordering.is_gt() }
+                };
+                let upper_matches = upper.is_empty() || {
+                    let ordering = compare_versions(version, upper);
+                    if end_inclusive { ordering.is_le() } else { ordering.is_lt() }
+                };
+                lower_matches && upper_matches
+            },
+        };
+        if matches {
+            return Some(true);
+        }
+    }
+    Some(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use schema::fabric_mod::VersionPredicates;
+
+    use super::{compare_versions, fabric_range_matches, maven_range_matches};
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("1.2.10", "1.2.9"), Ordering::Greater);
+        assert_eq!(compare_versions("1.20", "1.20.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0.0+build.5", "1.0.0"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_compare_pre_releases() {
+        assert_eq!(compare_versions("1.0.0-beta.2", "1.0.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0.0-alpha", "1.0.0-beta"), Ordering::Less);
+        assert_eq!(compare_versions("1.0.0-beta.2", "1.0.0-beta.10"), Ordering::Less);
+        assert_eq!(compare_versions("1.0.0-beta", "1.0.0-beta.1"), Ordering::Less);
+        assert_eq!(compare_versions("1.0.0-rc.1", "1.0.0-beta.5"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_fabric_operators() {
+        assert_eq!(fabric_range_matches(">=1.20", "1.20.1"), Some(true));
+        assert_eq!(fabric_range_matches(">=1.20", "1.19.4"), Some(false));
+        assert_eq!(fabric_range_matches("~1.20", "1.20.4"), Some(true));
+        assert_eq!(fabric_range_matches("~1.20", "1.21"), Some(false));
+        assert_eq!(fabric_range_matches("^1.2.3", "1.9.0"), Some(true));
+        assert_eq!(fabric_range_matches("^1.2.3", "2.0.0"), Some(false));
+        assert_eq!(fabric_range_matches("^1.2.3", "1.2.2"), Some(false));
+        assert_eq!(fabric_range_matches("1.20.1", "1.20.1"), Some(true));
+    }
+
+    #[test]
+    fn test_fabric_wildcards() {
+        assert_eq!(fabric_range_matches("*", "1.0"), Some(true));
+        assert_eq!(fabric_range_matches("1.20.x", "1.20.4"), Some(true));
+        assert_eq!(fabric_range_matches("1.20.x", "1.21"), Some(false));
+        assert_eq!(fabric_range_matches("1.X", "1.21.1"), Some(true));
+    }
+
+    #[test]
+    fn test_fabric_and_or() {
+        // Space separated predicates must all match
+        assert_eq!(fabric_range_matches(">=1.20 <1.21", "1.20.6"), Some(true));
+        assert_eq!(fabric_range_matches(">=1.20 <1.21", "1.21"), Some(false));
+
+        // Arrays of predicates are alternatives
+        let predicates: VersionPredicates = serde_json::from_str(r#"["1.19.x", "1.20.x"]"#).unwrap();
+        let range = predicates.to_range_string();
+        assert_eq!(fabric_range_matches(&range, "1.20.1"), Some(true));
+        assert_eq!(fabric_range_matches(&range, "1.18.2"), Some(false));
+    }
+
+    #[test]
+    fn test_fabric_pre_releases() {
+        assert_eq!(fabric_range_matches(">=1.21", "1.21-pre1"), Some(false));
+        assert_eq!(fabric_range_matches("<1.21", "1.21-pre1"), Some(true));
+        assert_eq!(fabric_range_matches(">=1.21-pre1", "1.21-rc1"), Some(true));
+    }
+
+    #[test]
+    fn test_fabric_unparseable() {
+        assert_eq!(fabric_range_matches(">=1.20.x", "1.20.1"), None);
+        assert_eq!(fabric_range_matches(">=", "1.0"), None);
+        assert_eq!(fabric_range_matches("~abc", "1.0"), None);
+        // An alternative that is understood still decides the result when it matches
+        assert_eq!(fabric_range_matches(">=1.x || 1.20.1", "1.20.1"), Some(true));
+        assert_eq!(fabric_range_matches(">=1.x || 1.20.1", "1.19"), None);
+    }
+
+    #[test]
+    fn test_maven_ranges() {
+        assert_eq!(maven_range_matches("[1.0,2.0)", "1.0"), Some(true));
+        assert_eq!(maven_range_matches("[1.0,2.0)", "1.5"), Some(true));
+        assert_eq!(maven_range_matches("[1.0,2.0)", "2.0"), Some(false));
+        assert_eq!(maven_range_matches("[1.0,2.0)", "0.9"), Some(false));
+        assert_eq!(maven_range_matches("(1.0,2.0)", "1.0"), Some(false));
+        assert_eq!(maven_range_matches("(,1.0]", "1.0"), Some(true));
+        assert_eq!(maven_range_matches("(,1.0]", "0.5"), Some(true));
+        assert_eq!(maven_range_matches("(,1.0]", "1.1"), Some(false));
+        assert_eq!(maven_range_matches("[47,)", "47.1.3"), Some(true));
+        assert_eq!(maven_range_matches("[1.20.1]", "1.20.1"), Some(true));
+        assert_eq!(maven_range_matches("[1.20.1]", "1.20.2"), Some(false));
+        assert_eq!(maven_range_matches("[1.0,1.2),[1.5,)", "1.6"), Some(true));
+        assert_eq!(maven_range_matches("[1.0,1.2),[1.5,)", "1.3"), Some(false));
+    }
+
+    #[test]
+    fn test_maven_bare_version() {
+        // A bare version is only a recommendation
+        assert_eq!(maven_range_matches("1.0", "2.0"), Some(true));
+    }
+
+    #[test]
+    fn test_maven_unparseable() {
+        assert_eq!(maven_range_matches("[1.0,2.0", "1.5"), None);
+        assert_eq!(maven_range_matches("[1.0],x", "2.0"), None);
+    }
+}
@@ -0,0 +1,214 @@
+use std::{path::{Path, PathBuf}, sync::Arc};
+
+use bridge::modal_action::{ModalAction, ProgressTracker, ProgressTrackerFinishType};
+use serde::{Deserialize, Serialize};
+
+use crate::BackendState;
+
+// Written to the default launcher directory when the user moves their data somewhere else
+const DATA_LOCATION_FILE: &str = "data_location.json";
+
+// Cache-like directories which used to live in the data directory before they were moved to the cache directory
+const LEGACY_CACHE_DIRS: &[&str] = &["metadata", "temp"];
+
+#[derive(Default, Serialize, Deserialize)]
+struct DataLocationFile {
+    data_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LauncherLocation {
+    // Instances, accounts, config and other state which shouldn't be lost
+    pub launcher_dir: PathBuf,
+    // Metadata and temporary files which can be downloaded or created again
+    pub cache_dir: PathBuf,
+    // Only set when the data directory can be changed from the launcher, i.e. it wasn't chosen by --data-dir or portable mode
+    pub data_location_file: Option<PathBuf>,
+    // Whether the launcher directory is the default or portable one. Directories chosen with --data-dir or moved to by
+    // the user may contain folders which don't belong to the launcher, so the legacy cache migration leaves them alone
+    launcher_owned: bool,
+}
+
+impl LauncherLocation {
+    pub fn resolve(data_dir: Option<PathBuf>, portable_dir: Option<PathBuf>) -> Self {
+        let base_dirs = directories::BaseDirs::new().unwrap();
+        let platform_cache_dir = base_dirs.cache_dir().join("PandoraLauncher");
+
+        if let Some(data_dir) = data_dir {
+            return Self {
+                launcher_dir: data_dir,
+                cache_dir: platform_cache_dir,
+                data_location_file: None,
+                launcher_owned: false,
+            };
+        }
+
+        // Portable installs keep everything next to the executable
+        if let Some(portable_dir) = portable_dir {
+            let launcher_dir = portable_dir.join("PandoraLauncher");
+            return Self {
+                cache_dir: launcher_dir.join("cache"),
+                launcher_dir,
+                data_location_file: None,
+                launcher_owned: true,
+            };
+        }
+
+        let default_launcher_dir = base_dirs.data_dir().join("PandoraLauncher");
+        let data_location_file = default_launcher_dir.join(DATA_LOCATION_FILE);
+
+        let (launcher_dir, launcher_owned) = match crate::read_json::<DataLocationFile>(&data_location_file) {
+            Ok(DataLocationFile { data_dir: Some(data_dir) }) => (data_dir, false),
+            _ => (default_launcher_dir, true),
+        };
+
+        Self {
+            launcher_dir,
+            cache_dir: platform_cache_dir,
+            data_location_file: Some(data_location_file),
+            launcher_owned,
+        }
+    }
+
+    // Moves metadata out of the data directory from before it was split into the cache directory. Temporary files
+    // are deleted instead since they're never reused between launches
+    pub fn migrate_legacy_cache(&self) {
+        // config.json is only missing on the first start, when there can't be anything to migrate yet
+        if !self.launcher_owned || !self.launcher_dir.join("config.json").is_file() {
+            return;
+        }
+
+        for name in LEGACY_CACHE_DIRS {
+            let legacy = self.launcher_dir.join(name);
+            if !legacy.is_dir() {
+                continue;
+            }
+
+            let target = self.cache_dir.join(name);
+            if *name != "temp" && !target.exists() {
+                _ = std::fs::create_dir_all(&self.cache_dir);
+                if std::fs::rename(&legacy, &target).is_ok() {
+                    log::info!("Moved {} to the cache directory", legacy.display());
+                    continue;
+                }
+            }
+
+            if let Err(err) = std::fs::remove_dir_all(&legacy) {
+                log::warn!("Unable to remove legacy cache directory {}: {err}", legacy.display());
+            }
+        }
+    }
+}
+
+// The target was checked to be empty before copying, so everything in it came from the cancelled or failed copy. A
+// directory the user picked is kept, only its contents are removed
+fn remove_partial_copy(target: &Path, target_existed: bool) {
+    let result = if target_existed {
+        std::fs::read_dir(target).and_then(|read_dir| {
+            for entry in read_dir {
+                let path = entry?.path();
+                if path.symlink_metadata()?.is_dir() {
+                    std::fs::remove_dir_all(&path)?;
+                } else {
+                    std::fs::remove_file(&path)?;
+                }
+            }
+            Ok(())
+        })
+    } else {
+        std::fs::remove_dir_all(target)
+    };
+
+    if let Err(error) = result {
+        log::warn!("Unable to remove partially copied data directory {}: {error}", target.display());
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+enum DataMigrationError {
+    #[error("The data directory was chosen with --data-dir or portable mode, so it can't be changed from the launcher")]
+    NotConfigurable,
+    #[error("Close all running instances before moving the data directory")]
+    InstancesRunning,
+    #[error("The new data directory must be empty")]
+    NotEmpty,
+    #[error("The new data directory can't be inside the current one")]
+    InsideCurrent,
+    #[error("Unable to copy {0}: {1}")]
+    Copy(PathBuf, std::io::Error),
+    #[error("Unable to save the new data directory: {0}")]
+    SaveLocation(std::io::Error),
+}
+
+impl BackendState {
+    pub async fn change_data_directory(&self, target: Arc<Path>, modal_action: &ModalAction) {
+        if let Err(error) = self.try_change_data_directory(&target, modal_action).await {
+            log::error!("Unable to change data directory: {error}");
+            modal_action.set_error_message(error.to_string().into());
+        }
+    }
+
+    async fn try_change_data_directory(&self, target: &Path, modal_action: &ModalAction) -> Result<(), DataMigrationError> {
+        let Some(data_location_file) = self.directories.data_location_file.clone() else {
+            return Err(DataMigrationError::NotConfigurable);
+        };
+        if self.instance_state.read().instances.iter().any(|instance| instance.child.is_some()) {
+            return Err(DataMigrationError::InstancesRunning);
+        }
+
+        let old_root = self.directories.root_launcher_dir.clone();
+        if target.starts_with(&old_root) {
+            return Err(DataMigrationError::InsideCurrent);
+        }
+        if std::fs::read_dir(target).is_ok_and(|mut read_dir| read_dir.next().is_some()) {
+            return Err(DataMigrationError::NotEmpty);
+        }
+
+        log::info!("Copying data directory from {} to {}", old_root.display(), target.display());
+
+        let tracker = ProgressTracker::new("Copying launcher data".into(), self.send.clone());
+        modal_action.trackers.push(tracker.clone());
+
+        let target_existed = target.exists();
+        let copy_target = target.to_path_buf();
+        let copy_tracker = tracker.clone();
+        let copy_modal_action = modal_action.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            copy_tracker.set_total(crate::linking::count_files(&old_root));
+            copy_tracker.notify();
+            crate::linking::copy_dir_with_progress(&old_root, &copy_target, &copy_tracker, &copy_modal_action)
+                .map_err(|(path, err)| DataMigrationError::Copy(path, err))?;
+            Ok(copy_target)
+        }).await.unwrap();
+
+        let target = match result {
+            Ok(target) => target,
+            Err(error) => {
+                remove_partial_copy(target, target_existed);
+                tracker.set_finished(ProgressTrackerFinishType::Error);
+                return Err(error);
+            },
+        };
+
+        if modal_action.has_requested_cancel() {
+            remove_partial_copy(&target, target_existed);
+            tracker.set_finished(ProgressTrackerFinishType::Error);
+            return Ok(());
+        }
+
+        let location = DataLocationFile {
+            data_dir: Some(target.clone()),
+        };
+        let bytes = serde_json::to_vec_pretty(&location).unwrap();
+        crate::write_safe(&data_location_file, &bytes).map_err(DataMigrationError::SaveLocation)?;
+
+        tracker.set_finished(ProgressTrackerFinishType::Normal);
+        tracker.notify();
+
+        self.send.send_success(format!(
+            "Launcher data copied to {}. Restart the launcher to start using it, the old directory can be deleted afterwards",
+            target.display()
+        ));
+        Ok(())
+    }
+}
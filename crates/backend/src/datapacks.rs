@@ -0,0 +1,165 @@
+use std::{io::Write, path::Path, sync::Arc};
+
+use bridge::instance::{InstanceDatapackSummary, InstanceID};
+use schema::resourcepack::PackMcmeta;
+
+use crate::BackendState;
+
+#[derive(thiserror::Error, Debug)]
+pub enum DatapackError {
+    #[error("Instance no longer exists")]
+    MissingInstance,
+    #[error("World doesn't belong to this instance")]
+    InvalidWorld,
+    #[error("Datapacks can't be changed while the instance is running")]
+    InstanceRunning,
+    #[error("Unable to parse level.dat: {0}")]
+    InvalidLevelDat(nbt::decode::DecodeError),
+    #[error("level.dat is missing the Data compound")]
+    MissingData,
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+struct DatapackLists {
+    enabled: Vec<String>,
+    disabled: Vec<String>,
+}
+
+impl BackendState {
+    fn world_path(&self, id: InstanceID, world: &Path, require_stopped: bool) -> Result<Arc<Path>, DatapackError> {
+        let instance_state = self.instance_state.read();
+        let instance = instance_state.instances.get(id).ok_or(DatapackError::MissingInstance)?;
+
+        if world.parent() != Some(&*instance.saves_path) || world.file_name().is_none() {
+            return Err(DatapackError::InvalidWorld);
+        }
+        // The game writes level.dat when the world is saved, which would undo any changes
+        if require_stopped && instance.child.is_some() {
+            return Err(DatapackError::InstanceRunning);
+        }
+
+        Ok(world.into())
+    }
+
+    pub fn load_world_datapacks(&self, id: InstanceID, world: &Path) -> Result<Arc<[InstanceDatapackSummary]>, DatapackError> {
+        let world = self.world_path(id, world, false)?;
+
+        let level_dat = read_level_dat(&world)?;
+        let lists = read_datapack_lists(&level_dat)?;
+
+        let Ok(read_dir) = std::fs::read_dir(world.join("datapacks")) else {
+            return Ok(Arc::from([]));
+        };
+
+        let mut datapacks = Vec::new();
+        for entry in read_dir {
+            let Ok(entry) = entry else {
+                continue;
+            };
+            let filename = entry.file_name();
+            let Some(filename) = filename.to_str() else {
+                continue;
+            };
+            let path = entry.path();
+
+            let (description, png_icon) = if path.is_dir() {
+                if !path.join("pack.mcmeta").is_file() {
+                    continue;
+                }
+                let description = crate::read_json::<PackMcmeta>(&path.join("pack.mcmeta")).ok().map(|pack_mcmeta| pack_mcmeta.pack.description);
+                let png_icon = std::fs::read(path.join("pack.png")).ok().map(Arc::from);
+                (description, png_icon)
+            } else if filename.ends_with(".zip") {
+                match self.mod_metadata_manager.get_path(&path) {
+                    Some(summary) => (Some(summary.version_str.clone()), summary.png_icon.clone()),
+                    None => (None, None),
+                }
+            } else {
+                continue;
+            };
+
+            let pack_id = format!("file/{filename}");
+            let enabled = !lists.disabled.contains(&pack_id);
+
+            datapacks.push(InstanceDatapackSummary {
+                pack_id: pack_id.into(),
+                filename: filename.into(),
+                description,
+                png_icon,
+                enabled,
+            });
+        }
+
+        datapacks.sort_by(|a, b| lexical_sort::natural_lexical_cmp(&a.filename, &b.filename));
+
+        Ok(datapacks.into())
+    }
+
+    pub fn set_datapacks_enabled(&self, id: InstanceID, world: &Path, pack_ids: &[Arc<str>], enabled: bool) -> Result<(), DatapackError> {
+        let world = self.world_path(id, world, true)?;
+
+        let mut level_dat = read_level_dat(&world)?;
+        let mut lists = read_datapack_lists(&level_dat)?;
+
+        for pack_id in pack_ids {
+            lists.enabled.retain(|enabled_id| **enabled_id != **pack_id);
+            lists.disabled.retain(|disabled_id| **disabled_id != **pack_id);
+            if enabled {
+                lists.enabled.push(pack_id.to_string());
+            } else {
+                lists.disabled.push(pack_id.to_string());
+            }
+        }
+
+        let mut root = level_dat.as_compound_mut().ok_or(DatapackError::MissingData)?;
+        let mut data = root.find_compound_mut("Data").ok_or(DatapackError::MissingData)?;
+        let mut datapacks = data.entry("DataPacks").or_insert_compound();
+
+        let mut enabled_list = datapacks.create_list("Enabled", nbt::TAG_STRING_ID);
+        for pack_id in lists.enabled {
+            enabled_list.insert_string(pack_id);
+        }
+        let mut disabled_list = datapacks.create_list("Disabled", nbt::TAG_STRING_ID);
+        for pack_id in lists.disabled {
+            disabled_list.insert_string(pack_id);
+        }
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&nbt::encode::write_named(&level_dat))?;
+        let compressed = encoder.finish()?;
+
+        // Minecraft keeps the previous level.dat as level.dat_old, do the same in case something goes wrong
+        let level_dat_path = world.join("level.dat");
+        std::fs::copy(&level_dat_path, world.join("level.dat_old"))?;
+        crate::write_safe(&level_dat_path, &compressed)?;
+
+        Ok(())
+    }
+}
+
+fn read_level_dat(world: &Path) -> Result<nbt::NBT, DatapackError> {
+    let compressed = std::fs::read(world.join("level.dat"))?;
+    let decompressed = crate::gunzip_nbt(&compressed)?;
+
+    let mut nbt_data = decompressed.as_slice();
+    nbt::decode::read_named_with_limits(&mut nbt_data, &nbt::decode::DecodeLimits::untrusted()).map_err(DatapackError::InvalidLevelDat)
+}
+
+fn read_datapack_lists(level_dat: &nbt::NBT) -> Result<DatapackLists, DatapackError> {
+    let root = level_dat.as_compound().ok_or(DatapackError::MissingData)?;
+    let data = root.find_compound("Data").ok_or(DatapackError::MissingData)?;
+
+    let datapacks = data.find_compound("DataPacks");
+    let read_list = |key: &str| -> Vec<String> {
+        let Some(list) = datapacks.as_ref().and_then(|datapacks| datapacks.find_list(key, nbt::TAG_STRING_ID)) else {
+            return Vec::new();
+        };
+        list.into_iter().filter_map(|value| value.as_string().cloned()).collect()
+    };
+
+    Ok(DatapackLists {
+        enabled: read_list("Enabled"),
+        disabled: read_list("Disabled"),
+    })
+}
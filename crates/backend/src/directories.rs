@@ -1,7 +1,11 @@
-use std::{path::{Path, PathBuf}, sync::Arc};
+use std::{path::Path, sync::Arc};
+
+use crate::data_location::LauncherLocation;
 
 pub struct LauncherDirectories {
     pub instances_dir: Arc<Path>,
+    pub creating_instances_dir: Arc<Path>,
+    pub templates_dir: Arc<Path>,
 
     pub synced_dir: Arc<Path>,
 
@@ -19,21 +23,39 @@ pub struct LauncherDirectories {
     pub content_library_dir: Arc<Path>,
     pub content_meta_dir: Arc<Path>,
 
+    pub snapshots_dir: Arc<Path>,
+    pub backups_dir: Arc<Path>,
+
+    pub shortcut_icons_dir: Arc<Path>,
+
+    pub reports_dir: Arc<Path>,
+    pub launcher_logs_dir: Arc<Path>,
+
     pub temp_dir: Arc<Path>,
     pub temp_natives_base_dir: Arc<Path>,
 
+    pub cache_dir: Arc<Path>,
+    pub data_location_file: Option<Arc<Path>>,
+
     pub root_launcher_dir: Arc<Path>,
     pub config_json: Arc<Path>,
     pub accounts_json: Arc<Path>,
+    pub launch_metrics_json: Arc<Path>,
+    pub content_library_index_json: Arc<Path>,
 }
 
 impl LauncherDirectories {
-    pub fn new(launcher_dir: PathBuf) -> Self {
+    pub fn new(location: &LauncherLocation) -> Self {
+        let launcher_dir = location.launcher_dir.clone();
+        let cache_dir = location.cache_dir.clone();
+
         let instances_dir = launcher_dir.join("instances");
+        let creating_instances_dir = launcher_dir.join("creating");
+        let templates_dir = launcher_dir.join("templates");
 
         let synced_dir = launcher_dir.join("synced");
 
-        let metadata_dir = launcher_dir.join("metadata");
+        let metadata_dir = cache_dir.join("metadata");
 
         let assets_root_dir = launcher_dir.join("assets");
         let assets_index_dir = assets_root_dir.join("indexes");
@@ -49,14 +71,26 @@ impl LauncherDirectories {
         let content_library_dir = launcher_dir.join("contentlibrary");
         let content_meta_dir = launcher_dir.join("contentmeta");
 
-        let temp_dir = launcher_dir.join("temp");
+        let snapshots_dir = launcher_dir.join("snapshots");
+        let backups_dir = launcher_dir.join("backups");
+
+        let shortcut_icons_dir = launcher_dir.join("shortcuticons");
+
+        let reports_dir = launcher_dir.join("reports");
+        let launcher_logs_dir = launcher_dir.join("logs");
+
+        let temp_dir = cache_dir.join("temp");
         let temp_natives_base_dir = temp_dir.join("natives");
 
         let config_json = launcher_dir.join("config.json");
         let accounts_json = launcher_dir.join("accounts.json");
+        let launch_metrics_json = launcher_dir.join("launch_metrics.json");
+        let content_library_index_json = launcher_dir.join("content_library_index.json");
 
         Self {
             instances_dir: instances_dir.into(),
+            creating_instances_dir: creating_instances_dir.into(),
+            templates_dir: templates_dir.into(),
 
             synced_dir: synced_dir.into(),
 
@@ -74,12 +108,25 @@ impl LauncherDirectories {
             content_library_dir: content_library_dir.into(),
             content_meta_dir: content_meta_dir.into(),
 
+            snapshots_dir: snapshots_dir.into(),
+            backups_dir: backups_dir.into(),
+
+            shortcut_icons_dir: shortcut_icons_dir.into(),
+
+            reports_dir: reports_dir.into(),
+            launcher_logs_dir: launcher_logs_dir.into(),
+
             temp_dir: temp_dir.into(),
             temp_natives_base_dir: temp_natives_base_dir.into(),
 
+            cache_dir: cache_dir.into(),
+            data_location_file: location.data_location_file.clone().map(Arc::from),
+
             root_launcher_dir: launcher_dir.into(),
             config_json: config_json.into(),
             accounts_json: accounts_json.into(),
+            launch_metrics_json: launch_metrics_json.into(),
+            content_library_index_json: content_library_index_json.into(),
         }
     }
 }
@@ -0,0 +1,33 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+// Process wide download counters, exposed by the daemon's metrics endpoint
+static DOWNLOADS_IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+static DOWNLOADED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+// Counts as in flight until dropped, so downloads which fail or are cancelled are also removed
+pub struct InFlightDownload(());
+
+impl InFlightDownload {
+    pub fn start() -> Self {
+        DOWNLOADS_IN_FLIGHT.fetch_add(1, Ordering::Relaxed);
+        Self(())
+    }
+}
+
+impl Drop for InFlightDownload {
+    fn drop(&mut self) {
+        DOWNLOADS_IN_FLIGHT.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+pub fn add_downloaded_bytes(bytes: usize) {
+    DOWNLOADED_BYTES.fetch_add(bytes as u64, Ordering::Relaxed);
+}
+
+pub fn downloads_in_flight() -> usize {
+    DOWNLOADS_IN_FLIGHT.load(Ordering::Relaxed)
+}
+
+pub fn downloaded_bytes() -> u64 {
+    DOWNLOADED_BYTES.load(Ordering::Relaxed)
+}
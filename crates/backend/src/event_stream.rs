@@ -0,0 +1,255 @@
+use std::{sync::Arc, time::{Duration, Instant}};
+
+use bridge::{
+    handle::{FrontendHandle, FrontendObserver}, instance::{InstanceID, InstanceStatus}, message::MessageToFrontend, modal_action::{ProgressTracker, ProgressTrackerFinishType}
+};
+use futures::{SinkExt, StreamExt};
+use parking_lot::{Mutex, RwLock};
+use rustc_hash::FxHashMap;
+use schema::instance::InstanceConfiguration;
+use serde_json::{json, Value};
+use tokio::{net::{TcpListener, TcpStream}, sync::broadcast};
+use tokio_tungstenite::tungstenite::{
+    handshake::server::{ErrorResponse, Request, Response}, http::StatusCode, Message
+};
+use tokio_util::sync::CancellationToken;
+use ustr::Ustr;
+use uuid::Uuid;
+
+use crate::{BackendState, frontend_events::{self, EVENT_BUFFER, PROGRESS_INTERVAL}};
+
+#[derive(Debug)]
+struct EventStreamInstance {
+    name: Ustr,
+    configuration: InstanceConfiguration,
+    status: InstanceStatus,
+}
+
+impl EventStreamInstance {
+    fn to_json(&self) -> Value {
+        frontend_events::instance_json(&self.name, &self.configuration, self.status)
+    }
+}
+
+// Converts everything the backend sends to the frontend into JSON events for WebSocket clients. Instances are always
+// tracked, so that clients connecting later get the current state
+#[derive(Debug)]
+pub struct EventStream {
+    events: broadcast::Sender<Arc<str>>,
+    instances: RwLock<FxHashMap<InstanceID, EventStreamInstance>>,
+    last_progress: Mutex<FxHashMap<usize, Instant>>,
+    // Cancelled to stop the server and disconnect all clients
+    server: Mutex<Option<CancellationToken>>,
+}
+
+impl Default for EventStream {
+    fn default() -> Self {
+        Self {
+            events: broadcast::channel(EVENT_BUFFER).0,
+            instances: Default::default(),
+            last_progress: Default::default(),
+            server: Default::default(),
+        }
+    }
+}
+
+impl EventStream {
+    fn has_clients(&self) -> bool {
+        self.events.receiver_count() > 0
+    }
+
+    fn emit(&self, event: &str, data: Value) {
+        if self.has_clients() {
+            // Sending only fails when nobody is subscribed
+            _ = self.events.send(json!({ "event": event, "data": data }).to_string().into());
+        }
+    }
+
+    fn lookup_instance(&self, id: InstanceID) -> Option<(Option<Uuid>, Ustr)> {
+        self.instances.read().get(&id).map(|instance| (instance.configuration.uuid, instance.name))
+    }
+
+    fn snapshot(&self) -> String {
+        let instances: Vec<Value> = self.instances.read().values().map(EventStreamInstance::to_json).collect();
+        json!({ "event": "instances", "data": instances }).to_string()
+    }
+}
+
+impl FrontendObserver for EventStream {
+    fn observe_message(&self, message: &MessageToFrontend) {
+        match message {
+            MessageToFrontend::InstanceAdded { id, name, configuration, .. } => {
+                let instance = EventStreamInstance { name: *name, configuration: configuration.clone(), status: InstanceStatus::NotRunning };
+                self.emit("instance_added", instance.to_json());
+                self.instances.write().insert(*id, instance);
+            },
+            MessageToFrontend::InstanceRemoved { id } => {
+                if let Some(instance) = self.instances.write().remove(id) {
+                    self.emit("instance_removed", frontend_events::instance_reference(instance.configuration.uuid, &instance.name));
+                }
+            },
+            MessageToFrontend::InstanceModified { id, name, configuration, status, .. } => {
+                let instance = EventStreamInstance { name: *name, configuration: configuration.clone(), status: *status };
+                self.emit("instance_modified", instance.to_json());
+                let started = frontend_events::instance_reference(configuration.uuid, name);
+                let previous = self.instances.write().insert(*id, instance);
+                if *status == InstanceStatus::Running && previous.is_none_or(|previous| previous.status != InstanceStatus::Running) {
+                    self.emit("instance_started", started);
+                }
+            },
+            _ => {
+                // Game output is the most frequent message, so it's only converted when someone is listening
+                if self.has_clients() && let Some((event, data)) = frontend_events::message_event(message, |id| self.lookup_instance(id)) {
+                    self.emit(event, data);
+                }
+            },
+        }
+    }
+
+    fn observe_progress(&self, tracker: &ProgressTracker) {
+        if !self.has_clients() {
+            return;
+        }
+
+        let id = tracker.id();
+        let finished = tracker.get_finished_at().is_some();
+        {
+            let now = Instant::now();
+            let mut last_progress = self.last_progress.lock();
+            if finished {
+                last_progress.remove(&id);
+            } else if last_progress.get(&id).is_some_and(|last| now.duration_since(*last) < PROGRESS_INTERVAL) {
+                return;
+            } else {
+                // Trackers which are dropped without finishing would otherwise be kept forever
+                if last_progress.len() > 256 {
+                    last_progress.retain(|_, last| now.duration_since(*last) < Duration::from_secs(60));
+                }
+                last_progress.insert(id, now);
+            }
+        }
+
+        let (count, total) = tracker.get();
+        self.emit("progress", json!({
+            "id": id,
+            "title": tracker.get_title(),
+            "count": count,
+            "total": total,
+            "progress": tracker.get_progress(),
+            "finished": finished,
+            "error": finished && tracker.finish_type() == ProgressTrackerFinishType::Error,
+        }));
+    }
+}
+
+fn generate_token() -> Arc<str> {
+    let token: [u8; 32] = rand::random();
+    hex::encode(token).into()
+}
+
+impl BackendState {
+    // Starts or stops the server to match the config. A running server is always restarted, so that a new port or
+    // token applies to every client
+    pub fn update_event_stream(&self) {
+        let configuration = {
+            let mut config = self.config.write();
+            if config.get().event_stream.enabled && config.get().event_stream.token.is_none() {
+                config.modify(|config| config.event_stream.token = Some(generate_token()));
+            }
+            config.get().event_stream.clone()
+        };
+
+        let cancel = CancellationToken::new();
+        let previous = std::mem::replace(&mut *self.event_stream.server.lock(), configuration.enabled.then(|| cancel.clone()));
+        if let Some(previous) = previous {
+            previous.cancel();
+        }
+
+        let Some(token) = configuration.token.filter(|_| configuration.enabled) else {
+            return;
+        };
+        tokio::task::spawn(run_server(self.event_stream.clone(), configuration.port(), token, cancel, self.send.clone()));
+    }
+}
+
+async fn run_server(event_stream: Arc<EventStream>, port: u16, token: Arc<str>, cancel: CancellationToken, send: FrontendHandle) {
+    let listener = match TcpListener::bind(("127.0.0.1", port)).await {
+        Ok(listener) => listener,
+        Err(error) => {
+            log::error!("Unable to start event stream on port {port}: {error}");
+            send.send_error(format!("Unable to start the event stream on port {port}: {error}"));
+            return;
+        },
+    };
+    log::info!("Event stream listening on ws://127.0.0.1:{port}");
+
+    loop {
+        let accepted = tokio::select! {
+            _ = cancel.cancelled() => return,
+            accepted = listener.accept() => accepted,
+        };
+        match accepted {
+            Ok((stream, address)) => {
+                log::debug!("Event stream connection from {address}");
+                tokio::task::spawn(handle_connection(event_stream.clone(), stream, token.clone(), cancel.clone()));
+            },
+            Err(error) => log::error!("Unable to accept event stream connection: {error}"),
+        }
+    }
+}
+
+async fn handle_connection(event_stream: Arc<EventStream>, stream: TcpStream, token: Arc<str>, cancel: CancellationToken) {
+    // The token is a query parameter since browser sources, e.g. in OBS, can't set headers on WebSockets
+    let authenticate = |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+        let authenticated = request.uri().query()
+            .is_some_and(|query| query.split('&').any(|pair| pair.strip_prefix("token=") == Some(&*token)));
+        if authenticated {
+            Ok(response)
+        } else {
+            let mut error = ErrorResponse::new(Some("Invalid token".into()));
+            *error.status_mut() = StatusCode::UNAUTHORIZED;
+            Err(error)
+        }
+    };
+
+    let socket = match tokio_tungstenite::accept_hdr_async(stream, authenticate).await {
+        Ok(socket) => socket,
+        Err(error) => {
+            log::debug!("Event stream handshake failed: {error}");
+            return;
+        },
+    };
+    let (mut write, mut read) = socket.split();
+
+    let mut events = event_stream.events.subscribe();
+    if write.send(Message::text(event_stream.snapshot())).await.is_err() {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => {
+                _ = write.close().await;
+                return;
+            },
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if write.send(Message::text(event.to_string())).await.is_err() {
+                        return;
+                    }
+                },
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("Event stream client fell behind by {skipped} events, disconnecting");
+                    _ = write.close().await;
+                    return;
+                },
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+            // Clients can't send anything, but reading answers pings and notices when they disconnect
+            message = read.next() => match message {
+                Some(Ok(Message::Close(_)) | Err(_)) | None => return,
+                Some(Ok(_)) => {},
+            },
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use std::{path::{Path, PathBuf}, sync::Arc};
+
+use bridge::{
+    install::{DroppedFile, DroppedFileKind}, instance::{ContentType, InstanceID}, modal_action::{ModalAction, ProgressTracker, ProgressTrackerFinishType}, safe_path::SafePath
+};
+use rc_zip_sync::ReadZip;
+use schema::{loader::Loader, mrpack::ModrinthIndexJson};
+
+use crate::{mod_metadata::ModMetadataManager, BackendState};
+
+#[derive(thiserror::Error, Debug)]
+enum WorldImportError {
+    #[error("The instance no longer exists")]
+    MissingInstance,
+    #[error("Unable to find a level.dat in {0}")]
+    NotAWorld(PathBuf),
+    #[error("Unable to read zip: {0}")]
+    Zip(#[from] rc_zip_sync::rc_zip::Error),
+    #[error("Unable to copy {0}: {1}")]
+    Copy(PathBuf, std::io::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+impl BackendState {
+    pub async fn identify_dropped_files(&self, paths: Vec<PathBuf>) -> Arc<[DroppedFile]> {
+        let mod_metadata_manager = self.mod_metadata_manager.clone();
+        tokio::task::spawn_blocking(move || {
+            paths.into_iter().map(|path| {
+                let kind = identify_file(&path, &mod_metadata_manager);
+                log::info!("Identified dropped file {path:?} as {kind:?}");
+                DroppedFile { path: path.into(), kind }
+            }).collect()
+        }).await.unwrap()
+    }
+
+    pub async fn import_world(&self, id: InstanceID, path: Arc<Path>, modal_action: &ModalAction) {
+        if let Err(error) = self.try_import_world(id, &path, modal_action).await {
+            log::error!("Unable to import world {path:?}: {error}");
+            modal_action.set_error_message(error.to_string().into());
+        }
+    }
+
+    async fn try_import_world(&self, id: InstanceID, path: &Path, modal_action: &ModalAction) -> Result<(), WorldImportError> {
+        let saves_path = self.instance_state.read().instances.get(id).ok_or(WorldImportError::MissingInstance)?.saves_path.clone();
+
+        let tracker = ProgressTracker::new("Importing world".into(), self.send.clone());
+        modal_action.trackers.push(tracker.clone());
+
+        let path = path.to_path_buf();
+        let import_tracker = tracker.clone();
+        let import_modal_action = modal_action.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let name = world_name(&path).ok_or_else(|| WorldImportError::NotAWorld(path.clone()))?;
+
+            // The world is imported under a hidden name and only moved into place once complete, so that an incomplete
+            // world never shows up in the world list
+            let target = unique_world_path(&saves_path, &name);
+            let partial = saves_path.join(format!(".pandora.import.{name}"));
+            _ = std::fs::remove_dir_all(&partial);
+
+            let result = if path.is_dir() {
+                let base = if path.join("level.dat").is_file() { path.clone() } else { path.join(&*name) };
+                import_tracker.set_total(crate::linking::count_files(&base));
+                import_tracker.notify();
+                crate::linking::copy_dir_with_progress(&base, &partial, &import_tracker, &import_modal_action)
+                    .map_err(|(path, err)| WorldImportError::Copy(path, err))
+            } else {
+                extract_world_zip(&path, &partial, &import_tracker, &import_modal_action)
+            };
+
+            if result.is_err() || import_modal_action.has_requested_cancel() {
+                _ = std::fs::remove_dir_all(&partial);
+                return result;
+            }
+
+            std::fs::rename(&partial, &target)?;
+            Ok(())
+        }).await.unwrap();
+
+        if let Err(error) = result {
+            tracker.set_finished(ProgressTrackerFinishType::Error);
+            return Err(error);
+        }
+
+        tracker.set_finished(ProgressTrackerFinishType::Normal);
+        tracker.notify();
+        Ok(())
+    }
+}
+
+fn identify_file(path: &Path, mod_metadata_manager: &Arc<ModMetadataManager>) -> DroppedFileKind {
+    if path.is_dir() {
+        return match world_name(path) {
+            Some(name) => DroppedFileKind::World { name },
+            None => DroppedFileKind::Unknown,
+        };
+    }
+
+    let Ok(file) = std::fs::File::open(path) else {
+        return DroppedFileKind::Unknown;
+    };
+    let Ok(archive) = file.read_zip() else {
+        return DroppedFileKind::Unknown;
+    };
+
+    if let Some(index) = archive.by_name("modrinth.index.json") {
+        let Some(index) = index.bytes().ok().and_then(|bytes| serde_json::from_slice::<ModrinthIndexJson>(&bytes).ok()) else {
+            return DroppedFileKind::Unknown;
+        };
+
+        let minecraft_version = index.dependencies.get("minecraft").cloned();
+        let loader = [
+            ("fabric-loader", Loader::Fabric),
+            ("quilt-loader", Loader::Fabric),
+            ("forge", Loader::Forge),
+            ("neoforge", Loader::NeoForge),
+        ].into_iter().find(|(dependency, _)| index.dependencies.contains_key(*dependency)).map(|(_, loader)| loader);
+
+        return DroppedFileKind::Modpack {
+            name: index.name,
+            minecraft_version,
+            loader: loader.unwrap_or(Loader::Vanilla),
+        };
+    }
+
+    if world_zip_prefix(&archive).is_some() {
+        return match world_name(path) {
+            Some(name) => DroppedFileKind::World { name },
+            None => DroppedFileKind::Unknown,
+        };
+    }
+    drop(archive);
+
+    let Some(summary) = mod_metadata_manager.get_path(path) else {
+        return DroppedFileKind::Unknown;
+    };
+    match summary.extra {
+        ContentType::Fabric => DroppedFileKind::Mod { loader: Loader::Fabric },
+        ContentType::Forge | ContentType::LegacyForge => DroppedFileKind::Mod { loader: Loader::Forge },
+        ContentType::NeoForge => DroppedFileKind::Mod { loader: Loader::NeoForge },
+        ContentType::JavaModule => DroppedFileKind::Mod { loader: Loader::Unknown },
+        ContentType::ResourcePack => DroppedFileKind::ResourcePack,
+        // Modpacks were already handled by reading the index
+        ContentType::ModrinthModpack { .. } => DroppedFileKind::Unknown,
+    }
+}
+
+// Worlds are usually zipped either directly or inside a folder with the name of the world
+fn world_zip_prefix<R: rc_zip_sync::HasCursor>(archive: &rc_zip_sync::ArchiveHandle<R>) -> Option<String> {
+    archive.entries().find_map(|entry| {
+        let prefix = entry.name.strip_suffix("level.dat")?;
+        (prefix.is_empty() || prefix.strip_suffix('/').is_some_and(|folder| !folder.is_empty() && !folder.contains('/')))
+            .then(|| prefix.to_string())
+    })
+}
+
+fn world_name(path: &Path) -> Option<Arc<str>> {
+    if path.is_dir() {
+        if path.join("level.dat").is_file() {
+            return path.file_name().map(|name| name.to_string_lossy().into());
+        }
+        // Also accept a folder which contains the world folder
+        let mut worlds = std::fs::read_dir(path).ok()?.flatten().filter(|entry| entry.path().join("level.dat").is_file());
+        return worlds.next().map(|entry| entry.file_name().to_string_lossy().into());
+    }
+
+    let archive = std::fs::File::open(path).ok()?.read_zip().ok()?;
+    let prefix = world_zip_prefix(&archive)?;
+    match prefix.strip_suffix('/') {
+        Some(folder) => Some(folder.into()),
+        None => path.file_stem().map(|name| name.to_string_lossy().into()),
+    }
+}
+
+fn unique_world_path(saves_path: &Path, name: &str) -> PathBuf {
+    let mut path = saves_path.join(name);
+    let mut index = 2;
+    while path.exists() {
+        path = saves_path.join(format!("{name} ({index})"));
+        index += 1;
+    }
+    path
+}
+
+fn extract_world_zip(path: &Path, target: &Path, tracker: &ProgressTracker, modal_action: &ModalAction) -> Result<(), WorldImportError> {
+    let file = std::fs::File::open(path)?;
+    let archive = file.read_zip()?;
+    let prefix = world_zip_prefix(&archive).ok_or_else(|| WorldImportError::NotAWorld(path.to_path_buf()))?;
+
+    let entries: Vec<_> = archive.entries().filter(|entry| entry.kind() == rc_zip_sync::rc_zip::EntryKind::File).collect();
+    tracker.set_total(entries.len());
+    tracker.notify();
+
+    for entry in entries {
+        if modal_action.has_requested_cancel() {
+            return Ok(());
+        }
+
+        let Some(entry_path) = SafePath::new(&entry.name) else {
+            continue;
+        };
+        let entry_path = match prefix.strip_suffix('/') {
+            Some(folder) => entry_path.strip_prefix(folder),
+            None => Some(entry_path),
+        };
+        // Files outside of the world folder, e.g. __MACOSX metadata, are skipped
+        let Some(entry_path) = entry_path else {
+            tracker.add_count(1);
+            continue;
+        };
+
+        let output_path = entry_path.to_path(target);
+        if let Some(parent) = output_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|err| WorldImportError::Copy(output_path.clone(), err))?;
+        }
+        let mut output = std::fs::File::create(&output_path).map_err(|err| WorldImportError::Copy(output_path.clone(), err))?;
+        std::io::copy(&mut entry.reader(), &mut output).map_err(|err| WorldImportError::Copy(output_path.clone(), err))?;
+
+        tracker.add_count(1);
+        tracker.notify();
+    }
+
+    Ok(())
+}
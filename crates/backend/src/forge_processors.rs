@@ -0,0 +1,216 @@
+use std::{
+    collections::BTreeMap, ffi::OsString, io::Write, path::{Path, PathBuf}, process::Stdio, sync::Arc
+};
+
+use bridge::modal_action::ProgressTracker;
+use rustc_hash::FxHashSet;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+
+// Forge processors are mostly single threaded, so running a few side by side speeds up installation
+// without starving the machine
+const MAX_CONCURRENT_PROCESSORS: usize = 4;
+
+pub struct ForgeProcessorJob {
+    pub name: String,
+    pub java_path: PathBuf,
+    pub working_dir: PathBuf,
+    pub args: Vec<OsString>,
+    // Paths that the processor writes to. If none could be determined the processor is treated
+    // as a barrier and runs on its own
+    pub outputs: Vec<PathBuf>,
+    // Every path referenced by the processor, including the outputs
+    pub paths: Vec<PathBuf>,
+    pub cache_key: String,
+    pub up_to_date: bool,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct ProcessorCacheEntry {
+    files: BTreeMap<PathBuf, String>,
+}
+
+pub fn create_cache_key(installer_path: &Path, minecraft_version: &str, jar: &str, classpath: &[Arc<str>], args: &[OsString]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(installer_path.as_os_str().as_encoded_bytes());
+    hasher.update([0]);
+    hasher.update(minecraft_version.as_bytes());
+    hasher.update([0]);
+    hasher.update(jar.as_bytes());
+    for entry in classpath {
+        hasher.update([0]);
+        hasher.update(entry.as_bytes());
+    }
+    for arg in args {
+        hasher.update([0]);
+        hasher.update(arg.as_encoded_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+fn cache_entry_path(cache_dir: &Path, cache_key: &str) -> PathBuf {
+    cache_dir.join(format!("{cache_key}.json"))
+}
+
+fn hash_file(path: &Path) -> std::io::Result<[u8; 20]> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha1::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(hasher.finalize().into())
+}
+
+// Returns true if the processor previously ran with the exact same inputs and all of its files are unchanged
+pub fn is_cached(cache_dir: &Path, cache_key: &str) -> bool {
+    let Ok(entry) = crate::read_json::<ProcessorCacheEntry>(&cache_entry_path(cache_dir, cache_key)) else {
+        return false;
+    };
+
+    entry.files.iter().all(|(path, sha1)| {
+        let mut expected_hash = [0u8; 20];
+        hex::decode_to_slice(sha1, &mut expected_hash).is_ok() && crate::check_sha1_hash(path, expected_hash).unwrap_or(false)
+    })
+}
+
+fn write_cache_entry(cache_dir: &Path, job: &ForgeProcessorJob) {
+    let mut entry = ProcessorCacheEntry::default();
+    for path in &job.paths {
+        if !path.is_file() {
+            continue;
+        }
+        if let Ok(hash) = hash_file(path) {
+            entry.files.insert(path.clone(), hex::encode(hash));
+        }
+    }
+
+    if let Ok(bytes) = serde_json::to_vec(&entry) {
+        _ = crate::write_safe(&cache_entry_path(cache_dir, &job.cache_key), &bytes);
+    }
+}
+
+fn depends_on(job: &ForgeProcessorJob, earlier: &ForgeProcessorJob) -> bool {
+    if job.outputs.is_empty() || earlier.outputs.is_empty() {
+        return true;
+    }
+    earlier.outputs.iter().any(|output| job.paths.contains(output))
+        || job.outputs.iter().any(|output| earlier.paths.contains(output))
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ForgeProcessorError {
+    #[error("Failed to perform I/O operation:\n{0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Processor {name} failed, see {log_path:?}")]
+    ProcessorFailed {
+        name: String,
+        log_path: PathBuf,
+    },
+}
+
+pub async fn run_processors(
+    jobs: Vec<ForgeProcessorJob>,
+    cache_dir: &Path,
+    log_dir: &Path,
+    tracker: &ProgressTracker,
+) -> Result<(), ForgeProcessorError> {
+    // Group the processors into levels, where every processor only depends on processors in earlier levels
+    let mut levels: Vec<usize> = Vec::with_capacity(jobs.len());
+    let mut must_run = Vec::with_capacity(jobs.len());
+    for (index, job) in jobs.iter().enumerate() {
+        let mut level = 0;
+        let mut dependency_runs = false;
+        for (earlier_index, earlier) in jobs[..index].iter().enumerate() {
+            if depends_on(job, earlier) {
+                level = level.max(levels[earlier_index] + 1);
+                dependency_runs |= must_run[earlier_index];
+            }
+        }
+        levels.push(level);
+        must_run.push(dependency_runs || !job.up_to_date);
+    }
+
+    let _ = std::fs::create_dir_all(log_dir);
+
+    let max_level = levels.iter().copied().max().unwrap_or(0);
+    let semaphore = tokio::sync::Semaphore::new(MAX_CONCURRENT_PROCESSORS);
+    let mut jobs: Vec<Option<ForgeProcessorJob>> = jobs.into_iter().map(Some).collect();
+
+    for level in 0..=max_level {
+        let mut tasks = Vec::new();
+
+        for index in 0..jobs.len() {
+            if levels[index] != level {
+                continue;
+            }
+            let Some(job) = jobs[index].take() else {
+                continue;
+            };
+
+            if !must_run[index] {
+                log::debug!("Skipping forge processor {}, outputs are up to date", job.name);
+                tracker.add_count(1);
+                tracker.notify();
+                continue;
+            }
+
+            let semaphore = &semaphore;
+            let log_path = log_dir.join(format!("{index}-{}.log", job.name));
+            tasks.push(async move {
+                let _permit = semaphore.acquire().await.unwrap();
+                let cache_dir = cache_dir.to_path_buf();
+                tokio::task::spawn_blocking(move || {
+                    let result = run_processor(&job, &log_path);
+                    if result.is_ok() {
+                        write_cache_entry(&cache_dir, &job);
+                    }
+                    result
+                }).await.unwrap()?;
+                tracker.add_count(1);
+                tracker.notify();
+                Ok::<(), ForgeProcessorError>(())
+            });
+        }
+
+        futures::future::try_join_all(tasks).await?;
+    }
+
+    Ok(())
+}
+
+fn run_processor(job: &ForgeProcessorJob, log_path: &Path) -> Result<(), ForgeProcessorError> {
+    log::info!("Running forge processor {}", job.name);
+
+    let mut command = std::process::Command::new(&job.java_path);
+    command.current_dir(&job.working_dir);
+    command.stdin(Stdio::null());
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+    command.args(&job.args);
+
+    let output = command.output()?;
+
+    let mut log_file = std::fs::File::create(log_path)?;
+    writeln!(log_file, "{:?} {:?}", job.java_path, job.args)?;
+    log_file.write_all(&output.stdout)?;
+    log_file.write_all(&output.stderr)?;
+
+    if !output.status.success() {
+        log::error!("Forge processor {} exited with {}, output saved to {:?}", job.name, output.status, log_path);
+        return Err(ForgeProcessorError::ProcessorFailed {
+            name: job.name.clone(),
+            log_path: log_path.to_path_buf(),
+        });
+    }
+
+    Ok(())
+}
+
+// Detects the outputs of a processor from its arguments, eg. `--output {PATCHED}`
+pub fn is_output_flag(flag: &str) -> bool {
+    let flag = flag.trim_start_matches('-').to_ascii_lowercase();
+    flag.starts_with("out") || flag == "slim" || flag == "extra"
+}
+
+pub fn dedup_paths(paths: &mut Vec<PathBuf>) {
+    let mut seen = FxHashSet::default();
+    paths.retain(|path| seen.insert(path.clone()));
+}
@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use bridge::{instance::{InstanceID, InstanceStatus}, message::MessageToFrontend};
+use schema::instance::InstanceConfiguration;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+// JSON events for the messages the backend sends to the frontend, used by the WebSocket event stream and the daemon
+
+// Clients which fall this far behind on events are disconnected
+pub const EVENT_BUFFER: usize = 1024;
+// Trackers are notified for every file, so progress is only forwarded this often
+pub const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+pub fn status_name(status: InstanceStatus) -> &'static str {
+    match status {
+        InstanceStatus::NotRunning => "not_running",
+        InstanceStatus::Launching => "launching",
+        InstanceStatus::Running => "running",
+    }
+}
+
+pub fn instance_json(name: &str, configuration: &InstanceConfiguration, status: InstanceStatus) -> Value {
+    json!({
+        "id": configuration.uuid,
+        "name": name,
+        "minecraft_version": configuration.minecraft_version.as_str(),
+        "loader": configuration.loader,
+        "status": status_name(status),
+    })
+}
+
+// Refers to an instance in events which aren't about its configuration
+pub fn instance_reference(uuid: Option<Uuid>, name: &str) -> Value {
+    json!({ "id": uuid, "name": name })
+}
+
+// Converts messages which don't add, modify or remove instances, those change what the caller tracks so it builds
+// their events itself. The lookup returns the uuid and name of instances which are only referred to by their id
+pub fn message_event<N: AsRef<str>>(
+    message: &MessageToFrontend,
+    lookup_instance: impl Fn(InstanceID) -> Option<(Option<Uuid>, N)>,
+) -> Option<(&'static str, Value)> {
+    match message {
+        MessageToFrontend::InstanceExited { id, exit_code } => {
+            let instance = lookup_instance(*id);
+            let (uuid, name) = match &instance {
+                Some((uuid, name)) => (*uuid, Some(name.as_ref())),
+                None => (None, None),
+            };
+            Some(("instance_exited", json!({ "id": uuid, "name": name, "exit_code": exit_code })))
+        },
+        MessageToFrontend::CreateGameOutputWindow { id, console, .. } => {
+            let instance = console.and_then(&lookup_instance);
+            let name = instance.as_ref().map(|(_, name)| name.as_ref());
+            Some(("game_output_started", json!({ "output_id": id, "instance": name })))
+        },
+        MessageToFrontend::AddGameOutput { id, time, level, text } => {
+            Some(("game_output", json!({
+                "output_id": id,
+                "time": time,
+                "level": format!("{level:?}").to_lowercase(),
+                "lines": &**text,
+            })))
+        },
+        MessageToFrontend::MinecraftVersionReleased { version, snapshot } => {
+            Some(("minecraft_version_released", json!({ "version": version.as_str(), "snapshot": snapshot })))
+        },
+        MessageToFrontend::InstanceUpgradeAvailable { name, version, .. } => {
+            Some(("instance_upgrade_available", json!({ "name": name.as_str(), "version": version.as_str() })))
+        },
+        MessageToFrontend::InstanceUpgraded { name, from, to, .. } => {
+            Some(("instance_upgraded", json!({ "name": name.as_str(), "from": from.as_str(), "to": to.as_str() })))
+        },
+        MessageToFrontend::AddNotification { notification_type, message } => {
+            Some(("notification", json!({
+                "type": format!("{notification_type:?}").to_lowercase(),
+                "message": message,
+            })))
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,71 @@
+use std::sync::OnceLock;
+
+use schema::version::MinecraftVersion;
+
+// The architecture of the machine. This differs from std::env::consts::ARCH when the launcher itself
+// is an x64 build running under Rosetta 2 or Windows-on-ARM emulation
+pub fn host_arch() -> &'static str {
+    static HOST_ARCH: OnceLock<&'static str> = OnceLock::new();
+    HOST_ARCH.get_or_init(detect_host_arch)
+}
+
+// The architecture the game should run as. Versions without ARM natives have to use an x64
+// Java runtime under emulation, otherwise LWJGL will fail to load
+pub fn game_arch(version_info: &MinecraftVersion) -> &'static str {
+    let host_arch = host_arch();
+    if host_arch == "aarch64" && !supports_native_arm64(version_info) {
+        "x86_64"
+    } else {
+        host_arch
+    }
+}
+
+fn supports_native_arm64(version_info: &MinecraftVersion) -> bool {
+    let classifier = match std::env::consts::OS {
+        "macos" => "natives-macos-arm64",
+        "windows" => "natives-windows-arm64",
+        // Linux natives are substituted, see natives_substitution
+        _ => return true,
+    };
+    version_info.libraries.iter().any(|library| library.name.ends_with(classifier))
+}
+
+// Normalizes the value of Java's os.arch property to match std::env::consts::ARCH
+pub fn normalize_java_arch(arch: &str) -> &str {
+    match arch {
+        "amd64" | "x86_64" => "x86_64",
+        "x86" | "i386" | "i486" | "i586" | "i686" => "x86",
+        "aarch64" | "arm64" => "aarch64",
+        arch => arch,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn detect_host_arch() -> &'static str {
+    if std::env::consts::ARCH == "x86_64" {
+        let output = std::process::Command::new("sysctl").args(["-in", "sysctl.proc_translated"]).output();
+        if let Ok(output) = output && output.stdout.trim_ascii() == b"1" {
+            return "aarch64";
+        }
+    }
+    std::env::consts::ARCH
+}
+
+#[cfg(target_os = "windows")]
+fn detect_host_arch() -> &'static str {
+    if std::env::consts::ARCH != "aarch64" {
+        let is_arm64 = |key: &str| std::env::var(key).is_ok_and(|value| value.eq_ignore_ascii_case("ARM64"));
+        if is_arm64("PROCESSOR_ARCHITEW6432") || is_arm64("PROCESSOR_ARCHITECTURE") {
+            return "aarch64";
+        }
+        if std::env::var("PROCESSOR_IDENTIFIER").is_ok_and(|value| value.starts_with("ARM")) {
+            return "aarch64";
+        }
+    }
+    std::env::consts::ARCH
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn detect_host_arch() -> &'static str {
+    std::env::consts::ARCH
+}
@@ -1,14 +1,15 @@
-use std::{ffi::{OsStr, OsString}, io::Write, path::{Path, PathBuf}, sync::Arc};
+use std::{collections::BTreeSet, ffi::{OsStr, OsString}, path::{Path, PathBuf}, sync::Arc};
 
 use bridge::{
-    install::{ContentDownload, ContentInstall, ContentInstallFile, ContentInstallPath}, instance::{ContentType, ContentSummary}, modal_action::{ModalAction, ProgressTracker, ProgressTrackerFinishType}, safe_path::SafePath
+    install::{ContentDownload, ContentInstall, ContentInstallFile, ContentInstallPath, InstallTarget}, instance::{ContentType, ContentSummary, InstanceID}, message::MessageToFrontend, modal_action::{ModalAction, ProgressTracker, ProgressTrackerFinishType}, safe_path::SafePath
 };
+use futures::FutureExt;
 use reqwest::StatusCode;
-use schema::{content::ContentSource, loader::Loader, modrinth::{ModrinthLoader, ModrinthProjectVersionsRequest}};
-use sha1::{Digest, Sha1};
+use schema::{content::ContentSource, instance_history::InstanceHistoryEvent, loader::Loader, modrinth::{ModrinthLoader, ModrinthProjectVersionsRequest}};
 use tokio::io::AsyncWriteExt;
+use ustr::Ustr;
 
-use crate::{lockfile::Lockfile, metadata::{items::{MinecraftVersionManifestMetadataItem, ModrinthProjectVersionsMetadataItem, ModrinthVersionMetadataItem}, manager::MetaLoadError}, BackendState};
+use crate::{cancellation::PartialFile, content_library::{ContentHash, FileHasher, FileHashes}, download_metrics::InFlightDownload, lockfile::Lockfile, metadata::{items::{MinecraftVersionManifestMetadataItem, ModrinthProjectVersionsMetadataItem, ModrinthVersionMetadataItem}, manager::MetaLoadError}, BackendState};
 
 #[derive(thiserror::Error, Debug)]
 pub enum ContentInstallError {
@@ -34,6 +35,8 @@ pub enum ContentInstallError {
     MetaLoadError(#[from] MetaLoadError),
     #[error("Mismatched project id for version {0}, expected {1} got {2}")]
     MismatchedProjectIdForVersion(Arc<str>, Arc<str>, Arc<str>),
+    #[error("{0}")]
+    SharedDownload(Arc<ContentInstallError>),
 }
 
 struct InstallFromContentLibrary {
@@ -69,9 +72,46 @@ impl From<&Path> for FilenameAndExtension {
     }
 }
 
+// Modrinth project of Fabric API, which almost every Fabric mod depends on
+const FABRIC_API_PROJECT_ID: &str = "P7dR8mSH";
+
 impl BackendState {
+    // Installs the newest Fabric API for the Minecraft version into a freshly created instance
+    pub async fn install_fabric_api(&self, id: InstanceID, name: Ustr, minecraft_version: Ustr) {
+        let project: Arc<str> = Arc::from(FABRIC_API_PROJECT_ID);
+        let content_install = ContentInstall {
+            target: InstallTarget::Instance(id),
+            loader_hint: Loader::Fabric,
+            version_hint: Some(minecraft_version.as_str().into()),
+            files: [ContentInstallFile {
+                replace_old: None,
+                path: ContentInstallPath::Automatic,
+                download: ContentDownload::Modrinth {
+                    project_id: project.clone(),
+                    version_id: None,
+                },
+                content_source: ContentSource::ModrinthProject { project },
+            }].into(),
+        };
+
+        let modal_action = ModalAction::default();
+        self.install_content(content_install, modal_action.clone()).await;
+        if let Some(error) = modal_action.error.read().unwrap().clone() {
+            self.send.send_error(format!("Unable to install Fabric API into {name}: {error}"));
+        }
+    }
+
     pub async fn install_content(&self, content: ContentInstall, modal_action: ModalAction) {
-        let semaphore = tokio::sync::Semaphore::new(8);
+        if let Err(error) = self.check_can_install_content() {
+            modal_action.set_error_message(error.into());
+            return;
+        }
+
+        let target_instance = match content.target {
+            InstallTarget::Instance(id) => Some(id),
+            _ => None,
+        };
+        let _cancel_link = self.link_cancellation(&modal_action, target_instance);
 
         let mut tasks = Vec::new();
 
@@ -122,6 +162,7 @@ impl BackendState {
 
                             let url = &install_file.url;
                             let sha1 = &install_file.hashes.sha1;
+                            let sha512 = install_file.hashes.sha512.as_ref();
                             let size = install_file.size;
 
                             let Some(safe_filename) = SafePath::new(&install_file.filename) else {
@@ -129,7 +170,7 @@ impl BackendState {
                             };
 
                             let (path, hash, mod_summary) = self.download_file_into_library(&modal_action,
-                                (&safe_filename).into(), url, sha1, size, &semaphore).await?;
+                                (&safe_filename).into(), url, sha1, sha512, size).await?;
 
                             let install_path = match &content_file.path {
                                 ContentInstallPath::Raw(path) => path.clone(),
@@ -177,7 +218,7 @@ impl BackendState {
                             Err(ContentInstallError::UnableToFindDependencyVersion)
                         }
                     },
-                    bridge::install::ContentDownload::Url { ref url, ref sha1, size } => {
+                    bridge::install::ContentDownload::Url { ref url, ref sha1, ref sha512, size } => {
                         let name = match &content_file.path {
                             ContentInstallPath::Raw(path) => (&**path).into(),
                             ContentInstallPath::Safe(safe_path) => safe_path.into(),
@@ -185,7 +226,7 @@ impl BackendState {
                         };
 
                         let (path, hash, mod_summary) = self.download_file_into_library(&modal_action,
-                            name, url, sha1, size, &semaphore).await?;
+                            name, url, sha1, sha512.as_ref(), size).await?;
 
                         let install_path = match &content_file.path {
                             ContentInstallPath::Raw(path) => path.clone(),
@@ -215,9 +256,8 @@ impl BackendState {
                         tracker.set_count(1);
                         tracker.notify();
 
-                        let mut hasher = Sha1::new();
-                        hasher.update(&data);
-                        let hash: [u8; 20] = hasher.finalize().into();
+                        let hashes = FileHashes::of_bytes(&data);
+                        let hash = hashes.sha1;
 
                         let hash_as_str = hex::encode(hash);
 
@@ -238,15 +278,17 @@ impl BackendState {
                         let mod_summary = {
                             let path = path.clone();
                             let mod_metadata_manager = self.mod_metadata_manager.clone();
+                            let content_library = self.content_library.clone();
                             let tracker = tracker.clone();
                             tokio::task::spawn_blocking(move || {
-                                let valid_hash_on_disk = crate::check_sha1_hash(&path, hash).unwrap_or(false);
+                                let valid_hash_on_disk = content_library.verify(&path, &[ContentHash::Sha1(hash)]);
 
                                 tracker.set_count(2);
                                 tracker.notify();
 
                                 if !valid_hash_on_disk {
                                     std::fs::write(&path, &data)?;
+                                    content_library.record(&path, &hashes);
                                 }
 
                                 std::io::Result::Ok(mod_metadata_manager.get_bytes(&data))
@@ -265,7 +307,7 @@ impl BackendState {
                         return Ok(InstallFromContentLibrary {
                             from: path,
                             replace: content_file.replace_old.clone(),
-                            hash: hash.into(),
+                            hash,
                             install_path,
                             content_file: content_file.clone(),
                             mod_summary,
@@ -275,10 +317,21 @@ impl BackendState {
             });
         }
 
-        let result: Result<Vec<InstallFromContentLibrary>, ContentInstallError> = futures::future::try_join_all(tasks).await;
+        // Dropping the tasks stops their downloads, any partially downloaded files are removed
+        let result: Result<Vec<InstallFromContentLibrary>, ContentInstallError> = tokio::select! {
+            result = futures::future::try_join_all(tasks) => result,
+            _ = modal_action.request_cancel.cancelled() => {
+                self.content_library.save();
+                self.send.send(MessageToFrontend::CloseModal);
+                return;
+            }
+        };
+        self.content_library.save();
         match result {
             Ok(files) => {
                 let mut instance_dir = None;
+                let mut pending_instance = None;
+                let mut disabled_content = BTreeSet::new();
 
                 match content.target {
                     bridge::install::InstallTarget::Instance(instance_id) => {
@@ -290,6 +343,7 @@ impl BackendState {
                             }
 
                             instance_dir = Some(instance.dot_minecraft_path.clone());
+                            disabled_content = instance.configuration.get().disabled_content.clone();
                         }
                     },
                     bridge::install::InstallTarget::Library => {},
@@ -303,8 +357,8 @@ impl BackendState {
 
                         if let Some(minecraft_version) = minecraft_version {
                             // todo: use icon of mod/modpack/etc. for icon of instance
-                            instance_dir = self.create_instance_sanitized(&name, &minecraft_version, content.loader_hint, None).await
-                                .map(|v| v.join(".minecraft").into());
+                            pending_instance = self.begin_create_instance_sanitized(&name, &minecraft_version, content.loader_hint, None).await;
+                            instance_dir = pending_instance.as_ref().map(|pending| pending.dot_minecraft_path().into());
                         }
                     },
                 }
@@ -319,9 +373,29 @@ impl BackendState {
                     });
                 self.mod_metadata_manager.set_content_sources(sources);
 
+                let mut history = Vec::new();
                 if let Some(instance_dir) = instance_dir {
+                    let allow_links = !self.config.write().get().dont_link_content_files;
+                    let mut installed = Vec::new();
                     for install in files {
-                        let target_path = instance_dir.join(&install.install_path);
+                        let name = install.mod_summary.as_ref().and_then(|summary| summary.name.as_deref())
+                            .map(str::to_string)
+                            .or_else(|| install.install_path.file_name().map(|name| name.to_string_lossy().into_owned()))
+                            .unwrap_or_default();
+                        history.push(if install.replace.is_some() {
+                            InstanceHistoryEvent::ContentUpdated { name }
+                        } else {
+                            InstanceHistoryEvent::ContentInstalled { name }
+                        });
+
+                        let mut target_path = instance_dir.join(&install.install_path);
+
+                        let disabled = install.mod_summary.as_ref()
+                            .and_then(|summary| summary.id.as_ref())
+                            .is_some_and(|id| disabled_content.contains(id));
+                        if disabled && target_path.extension().is_none_or(|extension| extension != "disabled") {
+                            target_path.add_extension("disabled");
+                        }
 
                         let _ = std::fs::create_dir_all(target_path.parent().unwrap());
 
@@ -329,9 +403,28 @@ impl BackendState {
                             self.replace_aux_path(&replace, &install.mod_summary, &target_path);
                             let _ = std::fs::remove_file(replace);
                         }
-                        let _ = std::fs::hard_link(install.from, target_path);
+                        match crate::linking::link_or_copy(&install.from, &target_path, allow_links) {
+                            Ok(()) => installed.extend(crate::installed_content::installed_content_key(&instance_dir, &target_path)
+                                .map(|key| (key, install.hash))),
+                            Err(error) => log::error!("Unable to install {:?} into {:?}: {error}", install.from, target_path),
+                        }
+                    }
+                    if let Some(root_path) = instance_dir.parent() {
+                        crate::installed_content::record_installed_content(root_path, installed);
                     }
                 }
+
+                if let Some(id) = target_instance
+                    && let Some(instance) = self.instance_state.write().instances.get_mut(id)
+                {
+                    for event in history {
+                        instance.record_history(event);
+                    }
+                }
+
+                if let Some(pending) = pending_instance && self.commit_instance(pending).is_none() {
+                    modal_action.set_error_message("Unable to create instance".into());
+                }
             },
             Err(error) => {
                 modal_action.set_error_message(Arc::from(format!("{}", error).as_str()));
@@ -365,8 +458,8 @@ impl BackendState {
         }
     }
 
-    async fn download_file_into_library(&self, modal_action: &ModalAction, name: FilenameAndExtension, url: &Arc<str>, sha1: &Arc<str>, size: usize, semaphore: &tokio::sync::Semaphore) -> Result<(PathBuf, [u8; 20], Option<Arc<ContentSummary>>), ContentInstallError> {
-        let mut result = self.download_file_into_library_inner(modal_action, name, url, sha1, size, semaphore).await?;
+    async fn download_file_into_library(&self, modal_action: &ModalAction, name: FilenameAndExtension, url: &Arc<str>, sha1: &Arc<str>, sha512: Option<&Arc<str>>, size: usize) -> Result<(PathBuf, [u8; 20], Option<Arc<ContentSummary>>), ContentInstallError> {
+        let mut result = self.download_file_into_library_inner(modal_action, name, url, sha1, sha512, size).await?;
 
         if let Some(summary) = &result.2 {
             if let ContentType::ModrinthModpack { downloads, .. } = &summary.extra {
@@ -383,7 +476,7 @@ impl BackendState {
                     };
 
                     tasks.push(self.download_file_into_library_inner(modal_action, name,
-                        &download.downloads[0], &download.hashes.sha1, download.file_size, semaphore));
+                        &download.downloads[0], &download.hashes.sha1, download.hashes.sha512.as_ref(), download.file_size));
                 }
 
                 _ = futures::future::try_join_all(tasks).await;
@@ -394,13 +487,22 @@ impl BackendState {
         Ok(result)
     }
 
-    async fn download_file_into_library_inner(&self, modal_action: &ModalAction, name: FilenameAndExtension, url: &Arc<str>, sha1: &Arc<str>, size: usize, semaphore: &tokio::sync::Semaphore) -> Result<(PathBuf, [u8; 20], Option<Arc<ContentSummary>>), ContentInstallError> {
-        let mut expected_hash = [0u8; 20];
-        let Ok(_) = hex::decode_to_slice(&**sha1, &mut expected_hash) else {
+    async fn download_file_into_library_inner(&self, modal_action: &ModalAction, name: FilenameAndExtension, url: &Arc<str>, sha1: &Arc<str>, sha512: Option<&Arc<str>>, size: usize) -> Result<(PathBuf, [u8; 20], Option<Arc<ContentSummary>>), ContentInstallError> {
+        let Some(ContentHash::Sha1(expected_hash)) = ContentHash::parse_sha1(sha1) else {
             log::warn!("Content install has invalid sha1: {}", sha1);
             return Err(ContentInstallError::InvalidHash(sha1.clone()));
         };
 
+        // The sha1 comes first since it's what the file is stored by
+        let mut expected = vec![ContentHash::Sha1(expected_hash)];
+        if let Some(sha512) = sha512 {
+            let Some(hash) = ContentHash::parse_sha512(sha512) else {
+                log::warn!("Content install has invalid sha512: {}", sha512);
+                return Err(ContentInstallError::InvalidHash(sha512.clone()));
+            };
+            expected.push(hash);
+        }
+
         // Re-encode as hex just in case the given sha1 was uppercase
         let hash_as_str = hex::encode(expected_hash);
 
@@ -412,23 +514,42 @@ impl BackendState {
             path.set_extension(extension);
         }
 
-        let lockfile = Lockfile::create(path.with_added_extension("lock").into()).await;
+        let (download, tracker) = self.content_downloads.download(ContentHash::Sha1(expected_hash), || {
+            let file_name = name.filename.clone();
+            let title = format!("Downloading {}", file_name.as_deref().map(|s| s.to_string_lossy()).unwrap_or(std::borrow::Cow::Borrowed("???")));
+            let tracker = ProgressTracker::new(title.into(), self.send.clone());
 
-        let _permit = semaphore.acquire().await.unwrap();
+            let state = self.clone();
+            let url = url.clone();
+            let download_tracker = tracker.clone();
+            let download = async move {
+                state.download_into_library(path, &url, &expected, size, &download_tracker).await
+            }.boxed();
 
-        let file_name = name.filename.clone();
+            (download, tracker)
+        });
+        modal_action.trackers.push(tracker);
 
-        let title = format!("Downloading {}", file_name.as_deref().map(|s| s.to_string_lossy()).unwrap_or(std::borrow::Cow::Borrowed("???")));
-        let tracker = ProgressTracker::new(title.into(), self.send.clone());
-        modal_action.trackers.push(tracker.clone());
+        let path = download.wait().await.map_err(ContentInstallError::SharedDownload)?;
+
+        let summary = self.mod_metadata_manager.get_path(&path);
+        Ok((path, expected_hash, summary))
+    }
+
+    async fn download_into_library(&self, path: PathBuf, url: &str, expected: &[ContentHash], size: usize, tracker: &ProgressTracker) -> Result<PathBuf, ContentInstallError> {
+        let lockfile = Lockfile::create(path.with_added_extension("lock").into()).await;
+
+        let _permit = self.content_downloads.permits.acquire().await.unwrap();
 
         tracker.set_total(size);
         tracker.notify();
 
         let valid_hash_on_disk = {
             let path = path.clone();
+            let expected = expected.to_vec();
+            let content_library = self.content_library.clone();
             tokio::task::spawn_blocking(move || {
-                crate::check_sha1_hash(&path, expected_hash).unwrap_or(false)
+                content_library.verify(&path, &expected)
             }).await.unwrap()
         };
 
@@ -436,18 +557,22 @@ impl BackendState {
             tracker.set_count(size);
             tracker.set_finished(ProgressTrackerFinishType::Fast);
             tracker.notify();
-            let summary = self.mod_metadata_manager.get_path(&path);
-            return Ok((path, expected_hash, summary));
+            return Ok(path);
         }
 
-        let response = self.redirecting_http_client.get(&**url).send().await?;
+        let in_flight = InFlightDownload::start();
+        let response = self.redirecting_http_client.get(url).send().await?;
 
         if response.status() != StatusCode::OK {
             return Err(ContentInstallError::NotOK(response.status()));
         }
 
+        // Downloaded next to the final path and moved into place once verified, so a cancelled or failed download never
+        // leaves a truncated file in the library
+        let partial = PartialFile::new(&path);
+
         // Tokio doesn't have lock, so we use std temporarily to lock it
-        let file = std::fs::File::create(&path)?;
+        let file = std::fs::File::create(partial.path())?;
         _ = file.lock();
 
         let mut file = tokio::fs::File::from_std(file);
@@ -457,29 +582,31 @@ impl BackendState {
 
         let mut total_bytes = 0;
 
-        let mut hasher = Sha1::new();
+        let mut hasher = FileHasher::default();
         while let Some(item) = stream.next().await {
             let item = item?;
 
             total_bytes += item.len();
+            crate::download_metrics::add_downloaded_bytes(item.len());
             tracker.add_count(item.len());
             tracker.notify();
 
-            hasher.write_all(&item)?;
+            hasher.update(&item);
             file.write_all(&item).await?;
         }
+        file.flush().await?;
+        drop(in_flight);
 
         tracker.set_finished(ProgressTrackerFinishType::Fast);
 
-        let actual_hash = hasher.finalize();
+        let hashes = hasher.finalize();
 
-        let wrong_hash = *actual_hash != expected_hash;
+        let wrong_hash = !expected.iter().all(|hash| hash.matches(&hashes));
         let wrong_size = total_bytes != size;
 
         if wrong_hash || wrong_size {
-            let _ = file.set_len(0).await;
             drop(file);
-            let _ = tokio::fs::remove_file(&path).await;
+            drop(partial);
 
             if wrong_hash {
                 return Err(ContentInstallError::WrongHash);
@@ -490,9 +617,12 @@ impl BackendState {
             }
         }
 
+        drop(file);
+        partial.persist(&path)?;
+        self.content_library.record(&path, &hashes);
+
         drop(lockfile);
 
-        let summary = self.mod_metadata_manager.get_path(&path);
-        Ok((path, expected_hash, summary))
+        Ok(path)
     }
 }
@@ -0,0 +1,173 @@
+use std::{collections::BTreeMap, path::Path, sync::Arc};
+
+use bridge::{
+    install::{ContentDownload, ContentInstall, ContentInstallFile, ContentInstallPath, InstallTarget}, instance::InstanceID, modal_action::{ModalAction, ProgressTracker, ProgressTrackerFinishType}
+};
+use schema::{content::ContentSource, modrinth::ModrinthProjectVersionsRequest};
+use strum::IntoEnumIterator;
+
+use crate::{content_library::{ContentHash, FileHashes}, instance::ContentFolder, metadata::items::ModrinthProjectVersionsMetadataItem, BackendState};
+
+// Remembers the sha1 of every content file the launcher put into an instance, so files which are damaged afterwards,
+// e.g. by disk errors or antivirus software, can be found and restored. Content added by hand isn't known and can't
+// be verified. Keyed by the path relative to .minecraft without the .disabled suffix, so toggling content keeps it
+const INSTALLED_CONTENT_FILE: &str = "installed_content.json";
+
+pub fn read_installed_content(root_path: &Path) -> BTreeMap<Arc<str>, Arc<str>> {
+    crate::read_json(&root_path.join(INSTALLED_CONTENT_FILE)).unwrap_or_default()
+}
+
+pub fn record_installed_content(root_path: &Path, installed: Vec<(Arc<str>, [u8; 20])>) {
+    if installed.is_empty() {
+        return;
+    }
+    let mut installed_content = read_installed_content(root_path);
+    for (key, hash) in installed {
+        installed_content.insert(key, hex::encode(hash).into());
+    }
+    if let Err(error) = crate::write_safe(&root_path.join(INSTALLED_CONTENT_FILE), serde_json::to_string(&installed_content).unwrap().as_bytes()) {
+        log::error!("Unable to write {INSTALLED_CONTENT_FILE}: {error}");
+    }
+}
+
+pub fn installed_content_key(dot_minecraft: &Path, path: &Path) -> Option<Arc<str>> {
+    let relative = path.strip_prefix(dot_minecraft).ok()?.to_str()?.replace('\\', "/");
+    Some(relative.strip_suffix(".disabled").unwrap_or(&relative).into())
+}
+
+impl BackendState {
+    // Re-hashes the mods and resource packs the launcher installed into the instance. Damaged files are restored from
+    // the content library, or downloaded into it again if the copy there is damaged too. Returns the number of files
+    // checked and the number repaired
+    pub async fn verify_installed_content(&self, id: InstanceID, modal_action: &ModalAction) -> (usize, usize) {
+        let (root_path, dot_minecraft, loader, minecraft_version) = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+            let configuration = instance.configuration.get();
+            (instance.root_path.clone(), instance.dot_minecraft_path.clone(), configuration.loader, configuration.minecraft_version)
+        } else {
+            return (0, 0);
+        };
+
+        let installed_content = read_installed_content(&root_path);
+        let mut files = Vec::new();
+        for folder in ContentFolder::iter() {
+            let Some(summaries) = self.clone().load_instance_content(id, folder).await else {
+                continue;
+            };
+            for summary in summaries.iter() {
+                let Some(key) = installed_content_key(&dot_minecraft, &summary.path) else {
+                    continue;
+                };
+                let Some(ContentHash::Sha1(expected)) = installed_content.get(&key).and_then(|sha1| ContentHash::parse_sha1(sha1)) else {
+                    continue;
+                };
+                files.push((summary.path.clone(), key, expected));
+            }
+        }
+
+        if files.is_empty() {
+            return (0, 0);
+        }
+
+        let tracker = ProgressTracker::new("Verifying installed content".into(), self.send.clone());
+        modal_action.trackers.push(tracker.clone());
+        tracker.set_total(files.len());
+        tracker.notify();
+
+        let checked = files.len();
+        let content_library_dir = self.directories.content_library_dir.clone();
+        let content_library = self.content_library.clone();
+        let allow_links = !self.config.write().get().dont_link_content_files;
+        let (restored, missing) = {
+            let tracker = tracker.clone();
+            tokio::task::spawn_blocking(move || {
+                let mut restored = 0;
+                let mut missing = Vec::new();
+                for (path, key, expected) in files {
+                    tracker.add_count(1);
+                    tracker.notify();
+
+                    if FileHashes::of_file(&path).is_ok_and(|hashes| hashes.sha1 == expected) {
+                        continue;
+                    }
+                    log::warn!("Installed content {key} is missing or corrupt");
+
+                    let extension = Path::new(&*key).extension().and_then(|extension| extension.to_str());
+                    let library_path = crate::create_content_library_path(&content_library_dir, expected, extension);
+                    if content_library.verify(&library_path, &[ContentHash::Sha1(expected)]) {
+                        match crate::linking::link_or_copy(&library_path, &path, allow_links) {
+                            Ok(()) => restored += 1,
+                            Err(error) => log::error!("Unable to restore {key} from the content library: {error}"),
+                        }
+                    } else {
+                        _ = std::fs::remove_file(&library_path);
+                        missing.push((path, key, expected));
+                    }
+                }
+                content_library.save();
+                (restored, missing)
+            }).await.unwrap()
+        };
+
+        tracker.set_finished(ProgressTrackerFinishType::Normal);
+        tracker.notify();
+
+        // Files which aren't in the content library anymore are downloaded again from where they were installed from
+        let mut downloads = Vec::new();
+        let mut unrepairable = Vec::new();
+        for (path, key, expected) in missing {
+            let content_source = self.mod_metadata_manager.read_content_sources().get(&expected);
+            let download = match &content_source {
+                Some(ContentSource::ModrinthProject { project }) => self.find_modrinth_file(project, expected).await,
+                _ => None,
+            };
+            let (Some(download), Some(relative)) = (download, path.strip_prefix(&dot_minecraft).ok()) else {
+                unrepairable.push(key);
+                continue;
+            };
+            downloads.push(ContentInstallFile {
+                replace_old: None,
+                path: ContentInstallPath::Raw(relative.into()),
+                download,
+                content_source: content_source.unwrap_or(ContentSource::Manual),
+            });
+        }
+
+        let repaired = restored + downloads.len();
+        if !downloads.is_empty() {
+            let content_install = ContentInstall {
+                target: InstallTarget::Instance(id),
+                loader_hint: loader,
+                version_hint: Some(minecraft_version.into()),
+                files: downloads.into(),
+            };
+            self.install_content(content_install, modal_action.clone()).await;
+        }
+
+        if !unrepairable.is_empty() {
+            self.send.send_warning(format!("Some damaged content couldn't be downloaded again:\n{}", unrepairable.join("\n")));
+        }
+
+        (checked, repaired)
+    }
+
+    async fn find_modrinth_file(&self, project: &Arc<str>, sha1: [u8; 20]) -> Option<ContentDownload> {
+        let versions = self.meta.fetch(&ModrinthProjectVersionsMetadataItem(&ModrinthProjectVersionsRequest {
+            project_id: project.clone(),
+            game_versions: None,
+            loaders: None,
+        })).await.inspect_err(|err| {
+            log::warn!("Unable to fetch versions for {project}: {err}");
+        }).ok()?;
+
+        let sha1 = hex::encode(sha1);
+        let file = versions.0.iter()
+            .flat_map(|version| version.files.iter())
+            .find(|file| file.hashes.sha1.eq_ignore_ascii_case(&sha1))?;
+        Some(ContentDownload::Url {
+            url: file.url.clone(),
+            sha1: file.hashes.sha1.clone(),
+            sha512: file.hashes.sha512.clone(),
+            size: file.size,
+        })
+    }
+}
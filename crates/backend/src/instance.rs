@@ -1,5 +1,5 @@
 use std::{
-    collections::{BTreeMap, HashSet}, ffi::OsStr, hash::{DefaultHasher, Hash, Hasher}, io::Read, path::{Path, PathBuf}, process::Child, sync::{
+    collections::{BTreeMap, HashSet}, ffi::OsStr, hash::{DefaultHasher, Hash, Hasher}, path::{Path, PathBuf}, process::Child, time::Instant, sync::{
         Arc, atomic::Ordering
     }
 };
@@ -13,13 +13,14 @@ use bridge::{
 };
 use parking_lot::RwLock;
 use relative_path::RelativePath;
-use schema::{auxiliary::{AuxDisabledChildren, AuxiliaryContentMeta}, instance::InstanceConfiguration};
+use schema::{auxiliary::{AuxDisabledChildren, AuxiliaryContentMeta}, instance::InstanceConfiguration, instance_history::InstanceHistoryEvent};
 use strum::IntoEnumIterator;
 use thiserror::Error;
+use tokio_util::sync::CancellationToken;
 
 use ustr::Ustr;
 
-use crate::{id_slab::{GetId, Id}, mod_metadata::ModMetadataManager, persistent::Persistent, BackendStateInstances, IoOrSerializationError};
+use crate::{id_slab::{GetId, Id}, mod_metadata::ModMetadataManager, persistent::Persistent, tunnel::Tunnel, BackendStateInstances, IoOrSerializationError};
 
 #[derive(Debug)]
 pub struct Instance {
@@ -33,6 +34,15 @@ pub struct Instance {
     pub configuration: Persistent<InstanceConfiguration>,
 
     pub child: Option<Child>,
+    // Set once a server has been asked to shut down gracefully, the next kill request terminates it
+    pub stop_requested: bool,
+    pub tunnel: Option<Tunnel>,
+    // When the running game was started and when its process began, for launch metrics
+    pub metrics_launch: Option<(i64, Instant)>,
+    // The last event written to the history file, so repeated setting changes can be merged
+    pub last_history_event: Option<(InstanceHistoryEvent, Instant)>,
+    // Cancelled when the instance is removed, stopping any downloads or installs still running for it
+    pub cancel_tasks: CancellationToken,
 
     pub watching_dot_minecraft: bool,
     pub watching_server_dat: bool,
@@ -667,6 +677,11 @@ impl Instance {
             configuration: instance_info,
 
             child: None,
+            stop_requested: false,
+            metrics_launch: None,
+            last_history_event: None,
+            tunnel: None,
+            cancel_tasks: CancellationToken::new(),
 
             watching_dot_minecraft: false,
             watching_server_dat: false,
@@ -730,6 +745,13 @@ impl Instance {
         self.configuration = new.configuration;
     }
 
+    pub fn mark_played(&mut self) {
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs() as i64).ok();
+        self.configuration.modify(|configuration| {
+            configuration.last_played = now;
+        });
+    }
+
     pub fn status(&self) -> InstanceStatus {
         if self.child.is_some() {
             InstanceStatus::Running
@@ -836,14 +858,10 @@ fn load_world_summary(path: &Path) -> anyhow::Result<InstanceWorldSummary> {
     }
 
     let compressed = std::fs::read(&level_dat_path)?;
-
-    let mut decoder = flate2::bufread::GzDecoder::new(compressed.as_slice());
-
-    let mut decompressed = Vec::new();
-    decoder.read_to_end(&mut decompressed)?;
+    let decompressed = crate::gunzip_nbt(&compressed)?;
 
     let mut nbt_data = decompressed.as_slice();
-    let result = nbt::decode::read_named(&mut nbt_data)?;
+    let result = nbt::decode::read_named_with_limits(&mut nbt_data, &nbt::decode::DecodeLimits::untrusted())?;
 
     let root = result.as_compound().context("Unable to get root compound")?;
     let data = root.find_compound("Data").context("Unable to get Data")?;
@@ -885,16 +903,14 @@ fn load_servers_summary(server_dat_path: &Path) -> anyhow::Result<Vec<InstanceSe
     let raw = std::fs::read(server_dat_path)?;
 
     let mut nbt_data = raw.as_slice();
-    let result = nbt::decode::read_named(&mut nbt_data)?;
+    let result = nbt::decode::read_named_with_limits(&mut nbt_data, &nbt::decode::DecodeLimits::untrusted())?;
 
     let root = result.as_compound().context("Unable to get root compound")?;
     let servers = root.find_list("servers", nbt::TAG_COMPOUND_ID).context("Unable to get servers")?;
 
     let mut summaries = Vec::with_capacity(servers.len());
 
-    for server in servers.iter() {
-        let server = server.as_compound().unwrap();
-
+    for server in servers.compounds() {
         if let Some(hidden) = server.find_byte("hidden")
             && *hidden != 0
         {
@@ -0,0 +1,208 @@
+use std::{collections::HashMap, ffi::OsString, path::{Path, PathBuf}};
+
+use bridge::{
+    instance::{InstanceDuplicateOptions, InstanceID}, message::EmbeddedOrRaw, modal_action::{ModalAction, ProgressTracker, ProgressTrackerFinishType}
+};
+use strum::IntoEnumIterator;
+use ustr::Ustr;
+
+use crate::{instance::ContentFolder, BackendState};
+
+const CONFIG_FOLDERS: &[&str] = &["config", "defaultconfigs"];
+const SAVES_FOLDER: &str = "saves";
+const OPTIONS_FILE: &str = "options.txt";
+
+#[derive(thiserror::Error, Debug)]
+enum InstanceDuplicateError {
+    #[error("The instance no longer exists")]
+    MissingInstance,
+    #[error("Unable to create instance")]
+    CreateFailed,
+    #[error("Unable to load the content of the instance")]
+    ContentFailed,
+    #[error("Unable to copy {0}: {1}")]
+    Copy(PathBuf, std::io::Error),
+    #[error("Unable to load the duplicated instance")]
+    LoadFailed,
+}
+
+impl BackendState {
+    pub async fn duplicate_instance(&self, id: InstanceID, name: Ustr, options: InstanceDuplicateOptions, modal_action: &ModalAction) {
+        if let Err(error) = self.try_duplicate_instance(id, name, options, modal_action).await {
+            log::error!("Unable to duplicate instance: {error}");
+            modal_action.set_error_message(error.to_string().into());
+        }
+    }
+
+    async fn try_duplicate_instance(&self, id: InstanceID, name: Ustr, options: InstanceDuplicateOptions, modal_action: &ModalAction) -> Result<(), InstanceDuplicateError> {
+        let (mut configuration, dot_minecraft, icon) = {
+            let mut instance_state = self.instance_state.write();
+            let instance = instance_state.instances.get_mut(id).ok_or(InstanceDuplicateError::MissingInstance)?;
+            (instance.configuration.get().clone(), instance.dot_minecraft_path.clone(), instance.icon.clone())
+        };
+
+        // The copy starts out as a fresh instance rather than inheriting the state of the original
+        configuration.favorite = false;
+        configuration.last_played = None;
+
+        // Content files are linked from the content library so that identical mods aren't stored twice
+        let mut library_files = HashMap::new();
+        if options.mods && options.link_content {
+            let allow_links = !self.config.write().get().dont_link_content_files;
+            for folder in ContentFolder::iter() {
+                let summaries = self.clone().load_instance_content(id, folder).await
+                    .ok_or(InstanceDuplicateError::ContentFailed)?;
+
+                for summary in summaries.iter() {
+                    let library_path = crate::create_content_library_path(&self.directories.content_library_dir,
+                        summary.content_summary.hash, Path::new(&*summary.filename).extension().and_then(|e| e.to_str()));
+                    if !library_path.exists() {
+                        if let Some(parent) = library_path.parent() {
+                            _ = std::fs::create_dir_all(parent);
+                        }
+                        if let Err(err) = crate::linking::link_or_copy(&summary.path, &library_path, allow_links) {
+                            // The file is copied from the original instance instead
+                            log::warn!("Unable to copy {:?} into the content library: {err}", summary.path);
+                            continue;
+                        }
+                    }
+                    library_files.insert(summary.path.to_path_buf(), library_path);
+                }
+            }
+        }
+
+        let pending = self.begin_create_instance(&name, configuration, icon.map(EmbeddedOrRaw::Raw)).await
+            .ok_or(InstanceDuplicateError::CreateFailed)?;
+        let target = pending.dot_minecraft_path();
+
+        let mut paths = Vec::new();
+        if options.mods {
+            paths.extend(ContentFolder::iter().map(|folder| PathBuf::from(folder.path().as_str())));
+        }
+        if options.configs {
+            paths.extend(CONFIG_FOLDERS.iter().map(PathBuf::from));
+        }
+        if options.saves {
+            paths.push(PathBuf::from(SAVES_FOLDER));
+        }
+        if options.options {
+            paths.push(PathBuf::from(OPTIONS_FILE));
+        }
+
+        let tracker = ProgressTracker::new("Duplicating instance".into(), self.send.clone());
+        modal_action.trackers.push(tracker.clone());
+
+        let copy_target = target.clone();
+        let copy_tracker = tracker.clone();
+        let copy_modal_action = modal_action.clone();
+        let allow_links = !self.config.write().get().dont_link_content_files;
+        let result = tokio::task::spawn_blocking(move || {
+            copy_instance_files(&dot_minecraft, &copy_target, &paths, &library_files, allow_links, &copy_tracker, &copy_modal_action)
+        }).await.unwrap();
+
+        if let Err((path, error)) = result {
+            tracker.set_finished(ProgressTrackerFinishType::Error);
+            return Err(InstanceDuplicateError::Copy(path, error));
+        }
+        if modal_action.has_requested_cancel() {
+            // Dropping the pending instance discards everything that was copied so far
+            tracker.set_finished(ProgressTrackerFinishType::Error);
+            return Ok(());
+        }
+
+        let instance_dir = self.commit_instance(pending).ok_or(InstanceDuplicateError::CreateFailed)?;
+        self.clone().load_instance_from_path(&instance_dir, true, false);
+        if !self.instance_state.read().instance_by_path.contains_key(&instance_dir) {
+            tracker.set_finished(ProgressTrackerFinishType::Error);
+            return Err(InstanceDuplicateError::LoadFailed);
+        }
+
+        tracker.set_finished(ProgressTrackerFinishType::Normal);
+        tracker.notify();
+
+        self.send.send_success(format!("Duplicated instance as {name}"));
+        Ok(())
+    }
+}
+
+fn copy_instance_files(
+    from: &Path,
+    to: &Path,
+    paths: &[PathBuf],
+    library_files: &HashMap<PathBuf, PathBuf>,
+    allow_links: bool,
+    tracker: &ProgressTracker,
+    modal_action: &ModalAction,
+) -> Result<(), (PathBuf, std::io::Error)> {
+    let total: usize = paths.iter().map(|path| {
+        let path = from.join(path);
+        if path.is_dir() { crate::linking::count_files(&path) } else { usize::from(path.exists()) }
+    }).sum();
+    tracker.set_total(total);
+    tracker.notify();
+
+    std::fs::create_dir_all(to).map_err(|err| (to.to_path_buf(), err))?;
+
+    for path in paths {
+        if modal_action.has_requested_cancel() {
+            return Ok(());
+        }
+
+        let source = from.join(path);
+        let target = to.join(path);
+        let Ok(metadata) = std::fs::metadata(&source) else {
+            continue;
+        };
+
+        if metadata.is_file() {
+            std::fs::copy(&source, &target).map_err(|err| (source.clone(), err))?;
+            tracker.add_count(1);
+            tracker.notify();
+        } else if library_files.is_empty() {
+            crate::linking::copy_dir_with_progress(&source, &target, tracker, modal_action)?;
+        } else {
+            copy_content_folder(&source, &target, library_files, allow_links, tracker, modal_action)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Content which is in the content library is linked from there, everything else is copied from the instance
+fn copy_content_folder(
+    from: &Path,
+    to: &Path,
+    library_files: &HashMap<PathBuf, PathBuf>,
+    allow_links: bool,
+    tracker: &ProgressTracker,
+    modal_action: &ModalAction,
+) -> Result<(), (PathBuf, std::io::Error)> {
+    std::fs::create_dir_all(to).map_err(|err| (to.to_path_buf(), err))?;
+
+    let entries: Vec<(OsString, PathBuf)> = std::fs::read_dir(from)
+        .map_err(|err| (from.to_path_buf(), err))?
+        .flatten()
+        .map(|entry| (entry.file_name(), entry.path()))
+        .collect();
+
+    for (filename, path) in entries {
+        if modal_action.has_requested_cancel() {
+            return Ok(());
+        }
+
+        let target = to.join(filename);
+        if let Some(library_path) = library_files.get(&path) {
+            crate::linking::link_or_copy(library_path, &target, allow_links).map_err(|err| (path.clone(), err))?;
+            tracker.add_count(1);
+            tracker.notify();
+        } else if path.is_dir() {
+            crate::linking::copy_dir_with_progress(&path, &target, tracker, modal_action)?;
+        } else {
+            std::fs::copy(&path, &target).map_err(|err| (path.clone(), err))?;
+            tracker.add_count(1);
+            tracker.notify();
+        }
+    }
+
+    Ok(())
+}
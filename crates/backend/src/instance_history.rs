@@ -0,0 +1,73 @@
+use std::{io::Write, path::Path, sync::Arc, time::{Duration, Instant}};
+
+use bridge::instance::InstanceID;
+use schema::instance_history::{InstanceHistoryEntry, InstanceHistoryEvent};
+
+use crate::{BackendState, instance::Instance};
+
+// Stored next to info_v1.json in the instance folder, one json entry per line
+pub const HISTORY_FILE: &str = "history_v1.jsonl";
+// Only the most recent entries are shown and included in problem reports
+pub const MAX_HISTORY_SHOWN: usize = 500;
+// Sliders and text fields send a change for every step, repeated changes to the same setting are recorded once
+const SETTING_CHANGE_COOLDOWN: Duration = Duration::from_secs(60);
+
+impl Instance {
+    pub fn record_history(&mut self, event: InstanceHistoryEvent) {
+        if matches!(event, InstanceHistoryEvent::SettingChanged { .. })
+            && let Some((last_event, last_time)) = &self.last_history_event
+            && *last_event == event
+            && last_time.elapsed() < SETTING_CHANGE_COOLDOWN
+        {
+            return;
+        }
+
+        let entry = InstanceHistoryEntry {
+            timestamp: chrono::Utc::now().timestamp(),
+            event: event.clone(),
+        };
+        if let Err(error) = append_history(&self.root_path.join(HISTORY_FILE), &entry) {
+            log::error!("Unable to record history of {}: {error}", self.name);
+        }
+        self.last_history_event = Some((event, Instant::now()));
+    }
+}
+
+impl BackendState {
+    pub fn record_instance_history(&self, id: InstanceID, event: InstanceHistoryEvent) {
+        if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+            instance.record_history(event);
+        }
+    }
+
+    // Newest entries first
+    pub fn get_instance_history(&self, id: InstanceID) -> Arc<[InstanceHistoryEntry]> {
+        let Some(root_path) = self.instance_state.read().instances.get(id).map(|instance| instance.root_path.clone()) else {
+            return Arc::from([]);
+        };
+        let mut entries = read_history(&root_path.join(HISTORY_FILE));
+        entries.reverse();
+        entries.into()
+    }
+}
+
+fn append_history(path: &Path, entry: &InstanceHistoryEntry) -> std::io::Result<()> {
+    let mut line = serde_json::to_vec(entry)?;
+    line.push(b'\n');
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(&line)
+}
+
+// Oldest entries first. Lines which can't be parsed, e.g. events added by a newer version, are skipped
+pub fn read_history(path: &Path) -> Vec<InstanceHistoryEntry> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<InstanceHistoryEntry> = content.lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    if entries.len() > MAX_HISTORY_SHOWN {
+        entries.drain(..entries.len() - MAX_HISTORY_SHOWN);
+    }
+    entries
+}
@@ -0,0 +1,291 @@
+use std::{path::{Path, PathBuf}, sync::Arc};
+
+use bridge::{instance::InstanceID, modal_action::{ModalAction, ProgressTracker, ProgressTrackerFinishType}};
+
+use crate::{BackendState, instance::{Instance, InstanceLoadError}};
+
+#[derive(thiserror::Error, Debug)]
+enum InstanceMoveError {
+    #[error("The instance no longer exists")]
+    MissingInstance,
+    #[error("Close the instance before moving it")]
+    InstanceRunning,
+    #[error("The instance is already stored in that folder")]
+    SameFolder,
+    #[error("The instance can't be moved inside itself")]
+    InsideItself,
+    #[error("{0} already exists")]
+    AlreadyExists(PathBuf),
+    #[error("Unable to copy {0}: {1}")]
+    Copy(PathBuf, std::io::Error),
+    #[error("The copied instance doesn't match the original, {0} is different")]
+    VerifyFailed(PathBuf),
+    #[error("The copied instance can't be loaded: {0}")]
+    LoadFailed(InstanceLoadError),
+}
+
+enum FolderMove {
+    Renamed,
+    // The original is still there, it's only removed once the copy has been loaded
+    Copied,
+    Cancelled,
+}
+
+impl BackendState {
+    // Every folder which may contain an instance, both inside the instances directory and stored elsewhere
+    pub fn instance_roots(&self) -> Vec<Arc<Path>> {
+        let mut roots: Vec<Arc<Path>> = std::fs::read_dir(&self.directories.instances_dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path().into())
+            .collect();
+        roots.extend(self.config.write().get().external_instances.iter().map(|path| Arc::from(path.as_path())));
+        roots
+    }
+
+    // Keeps the list of instances stored outside the instances directory up to date after an instance folder was
+    // moved, renamed or deleted
+    pub fn update_external_instance(&self, old: &Path, new: Option<&Path>) {
+        let new = new.filter(|new| new.parent() != Some(&*self.directories.instances_dir));
+
+        let mut config = self.config.write();
+        if new.is_none() && !config.get().external_instances.iter().any(|path| path == old) {
+            return;
+        }
+        config.modify(|config| {
+            config.external_instances.retain(|path| path != old);
+            if let Some(new) = new {
+                config.external_instances.push(new.to_path_buf());
+            }
+        });
+    }
+
+    // Each instance folder holds its own info_v1.json, the config only indexes the folders stored outside the
+    // instances directory. When scanning, entries which point into the instances directory, at the same folder twice
+    // or at a folder which no longer contains an instance are dropped. Missing folders are kept, their drive may just
+    // not be connected
+    pub fn reconcile_external_instances(&self) -> Vec<PathBuf> {
+        let mut config = self.config.write();
+        let external_instances = config.get().external_instances.clone();
+
+        let mut seen = Vec::new();
+        let mut reconciled = Vec::new();
+        for path in external_instances {
+            let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+            if seen.contains(&canonical) {
+                continue;
+            }
+            seen.push(canonical);
+
+            if path.parent() == Some(&*self.directories.instances_dir) {
+                continue;
+            }
+            if path.is_dir() && !path.join("info_v1.json").is_file() {
+                log::warn!("External instance folder {:?} no longer contains an instance", path);
+                continue;
+            }
+            reconciled.push(path);
+        }
+
+        if reconciled != config.get().external_instances {
+            log::info!("Reconciled external instance folders");
+            config.modify(|config| config.external_instances = reconciled.clone());
+        }
+        reconciled
+    }
+
+    // Adds an instance folder from elsewhere, e.g. one copied from another computer, without moving it
+    pub fn add_external_instance(&mut self, path: &Path) {
+        if let Err(error) = self.check_can_create_instance() {
+            self.send.send_warning(format!("Unable to add instance: {error}"));
+            return;
+        }
+        if !path.join("info_v1.json").is_file() {
+            self.send.send_error(format!("{} doesn't contain an instance", path.display()));
+            return;
+        }
+        if path.parent() == Some(&*self.directories.instances_dir) || self.instance_state.read().instance_by_path.contains_key(path) {
+            self.send.send_warning("That instance has already been added");
+            return;
+        }
+
+        if self.load_instance_from_path(path, true, true) {
+            self.config.write().modify(|config| config.external_instances.push(path.to_path_buf()));
+        }
+    }
+
+    pub fn apply_instance_moved(&self, id: InstanceID, to: Arc<Path>) {
+        let mut instance_state_guard = self.instance_state.write();
+        let instance_state = &mut *instance_state_guard;
+        let Some(instance) = instance_state.instances.get_mut(id) else {
+            return;
+        };
+
+        let from = instance.root_path.clone();
+        instance.on_root_renamed(&to);
+        instance_state.instance_by_path.remove(&*from);
+        instance_state.instance_by_path.insert(to.to_path_buf(), id);
+
+        self.send.send(instance.create_modify_message());
+
+        let mut file_watching = self.file_watching.write();
+        file_watching.remove_all_under(&from);
+        file_watching.watch_instance(instance);
+    }
+
+    fn rewatch_instance(&self, id: InstanceID) {
+        if let Some(instance) = self.instance_state.read().instances.get(id) {
+            self.file_watching.write().watch_instance(instance);
+        }
+    }
+
+    pub async fn move_instance(&self, id: InstanceID, target: Option<Arc<Path>>, modal_action: &ModalAction) {
+        if let Err(error) = self.try_move_instance(id, target, modal_action).await {
+            log::error!("Unable to move instance: {error}");
+            modal_action.set_error_message(error.to_string().into());
+        }
+    }
+
+    async fn try_move_instance(&self, id: InstanceID, target: Option<Arc<Path>>, modal_action: &ModalAction) -> Result<(), InstanceMoveError> {
+        let from = {
+            let instance_state = self.instance_state.read();
+            let instance = instance_state.instances.get(id).ok_or(InstanceMoveError::MissingInstance)?;
+            if instance.child.is_some() {
+                return Err(InstanceMoveError::InstanceRunning);
+            }
+            instance.root_path.clone()
+        };
+
+        let target_parent = target.unwrap_or_else(|| self.directories.instances_dir.clone());
+        if from.parent() == Some(&*target_parent) {
+            return Err(InstanceMoveError::SameFolder);
+        }
+        if target_parent.starts_with(&from) {
+            return Err(InstanceMoveError::InsideItself);
+        }
+        let to: Arc<Path> = target_parent.join(from.file_name().unwrap()).into();
+        if to.exists() {
+            return Err(InstanceMoveError::AlreadyExists(to.to_path_buf()));
+        }
+
+        log::info!("Moving instance from {} to {}", from.display(), to.display());
+
+        // Stop watching the old folder, otherwise removing it after copying would remove the instance
+        self.file_watching.write().remove_all_under(&from);
+
+        let tracker = ProgressTracker::new("Moving instance".into(), self.send.clone());
+        modal_action.trackers.push(tracker.clone());
+
+        let move_from = from.clone();
+        let move_to = to.clone();
+        let move_tracker = tracker.clone();
+        let move_modal_action = modal_action.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            move_instance_folder(&move_from, &move_to, &move_tracker, &move_modal_action)
+        }).await.unwrap();
+
+        let moved = match result {
+            Ok(moved) => moved,
+            Err(error) => {
+                tracker.set_finished(ProgressTrackerFinishType::Error);
+                self.rewatch_instance(id);
+                return Err(error);
+            },
+        };
+        match moved {
+            FolderMove::Renamed => {},
+            FolderMove::Copied => {
+                let load_to = to.clone();
+                let loaded = tokio::task::spawn_blocking(move || Instance::load_from_folder(&load_to)).await.unwrap();
+                if let Err(error) = loaded {
+                    tracker.set_finished(ProgressTrackerFinishType::Error);
+                    let remove_to = to.clone();
+                    tokio::task::spawn_blocking(move || remove_partial_copy(&remove_to)).await.unwrap();
+                    self.rewatch_instance(id);
+                    return Err(InstanceMoveError::LoadFailed(error));
+                }
+            },
+            FolderMove::Cancelled => {
+                tracker.set_finished(ProgressTrackerFinishType::Error);
+                self.rewatch_instance(id);
+                return Ok(());
+            },
+        }
+
+        self.update_external_instance(&from, Some(&to));
+        self.apply_instance_moved(id, to.clone());
+
+        if let FolderMove::Copied = moved {
+            tokio::task::spawn_blocking(move || {
+                if let Err(err) = std::fs::remove_dir_all(&from) {
+                    log::warn!("Unable to remove old instance folder {}: {err}", from.display());
+                }
+            }).await.unwrap();
+        }
+
+        tracker.set_finished(ProgressTrackerFinishType::Normal);
+        tracker.notify();
+
+        self.send.send_success(format!("Instance moved to {}", to.display()));
+        Ok(())
+    }
+}
+
+// Renames the folder when possible, otherwise copies it (e.g. onto a different drive) and checks the copy. The
+// original is left untouched, a partial or cancelled copy is removed again
+fn move_instance_folder(from: &Path, to: &Path, tracker: &ProgressTracker, modal_action: &ModalAction) -> Result<FolderMove, InstanceMoveError> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(FolderMove::Renamed);
+    }
+
+    tracker.set_total(crate::linking::count_files(from));
+    tracker.notify();
+
+    let result = crate::linking::copy_dir_with_progress(from, to, tracker, modal_action)
+        .map_err(|(path, err)| InstanceMoveError::Copy(path, err))
+        .and_then(|_| {
+            if modal_action.has_requested_cancel() {
+                return Ok(FolderMove::Cancelled);
+            }
+            verify_copy(from, to).map_err(InstanceMoveError::VerifyFailed)?;
+            Ok(FolderMove::Copied)
+        });
+
+    if !matches!(result, Ok(FolderMove::Copied)) {
+        remove_partial_copy(to);
+    }
+    result
+}
+
+fn remove_partial_copy(to: &Path) {
+    if let Err(err) = std::fs::remove_dir_all(to) {
+        log::warn!("Unable to remove partially copied instance {}: {err}", to.display());
+    }
+}
+
+// Checks that everything in `from` also exists in `to` with the same contents, returning the first path which doesn't
+fn verify_copy(from: &Path, to: &Path) -> Result<(), PathBuf> {
+    let read_dir = std::fs::read_dir(from).map_err(|_| from.to_path_buf())?;
+    for entry in read_dir {
+        let entry = entry.map_err(|_| from.to_path_buf())?;
+        let path = entry.path();
+        let target = to.join(entry.file_name());
+        let Ok(file_type) = entry.file_type() else {
+            return Err(path);
+        };
+
+        if file_type.is_symlink() {
+            if std::fs::symlink_metadata(&target).is_err() {
+                return Err(path);
+            }
+        } else if file_type.is_dir() {
+            verify_copy(&path, &target)?;
+        } else {
+            if !crate::linking::same_contents(&path, &target).unwrap_or(false) {
+                return Err(path);
+            }
+        }
+    }
+    Ok(())
+}
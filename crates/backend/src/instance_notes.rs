@@ -0,0 +1,95 @@
+use std::path::Path;
+
+use bridge::instance::{InstanceID, InstanceNotes, InstanceReadme};
+
+use crate::BackendState;
+
+const NOTES_FILENAME: &str = "notes.md";
+
+// Modpacks put their readme either next to the instance or in the overrides, which end up in .minecraft
+const README_FILENAMES: &[&str] = &["readme.md", "readme.txt", "readme"];
+
+const MAX_README_SIZE: u64 = 1024 * 1024;
+
+#[derive(thiserror::Error, Debug)]
+pub enum InstanceNotesError {
+    #[error("Instance no longer exists")]
+    MissingInstance,
+    #[error("Notes aren't valid UTF-8 text")]
+    NotText,
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl BackendState {
+    pub fn load_instance_notes(&self, id: InstanceID) -> Result<InstanceNotes, InstanceNotesError> {
+        let (root_path, dot_minecraft_path) = {
+            let instance_state = self.instance_state.read();
+            let instance = instance_state.instances.get(id).ok_or(InstanceNotesError::MissingInstance)?;
+            (instance.root_path.clone(), instance.dot_minecraft_path.clone())
+        };
+
+        let notes = match std::fs::read(root_path.join(NOTES_FILENAME)) {
+            Ok(bytes) => String::from_utf8(bytes).map_err(|_| InstanceNotesError::NotText)?.into(),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => "".into(),
+            Err(error) => return Err(error.into()),
+        };
+
+        let readme = find_readme(&root_path).or_else(|| find_readme(&dot_minecraft_path));
+
+        Ok(InstanceNotes { notes, readme })
+    }
+
+    pub fn save_instance_notes(&self, id: InstanceID, notes: &str) -> Result<(), InstanceNotesError> {
+        let root_path = {
+            let instance_state = self.instance_state.read();
+            let instance = instance_state.instances.get(id).ok_or(InstanceNotesError::MissingInstance)?;
+            instance.root_path.clone()
+        };
+
+        let path = root_path.join(NOTES_FILENAME);
+
+        // Don't leave an empty file behind when the notes are cleared
+        if notes.trim().is_empty() {
+            return match std::fs::remove_file(&path) {
+                Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.into()),
+                _ => Ok(()),
+            };
+        }
+
+        crate::write_safe(&path, notes.as_bytes())?;
+        Ok(())
+    }
+}
+
+fn find_readme(folder: &Path) -> Option<InstanceReadme> {
+    let read_dir = std::fs::read_dir(folder).ok()?;
+
+    let mut candidates = Vec::new();
+    for entry in read_dir.flatten() {
+        let filename = entry.file_name();
+        let Some(filename) = filename.to_str() else {
+            continue;
+        };
+        let Some(priority) = README_FILENAMES.iter().position(|name| name.eq_ignore_ascii_case(filename)) else {
+            continue;
+        };
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if !metadata.is_file() || metadata.len() > MAX_README_SIZE {
+            continue;
+        }
+        candidates.push((priority, filename.to_string(), entry.path()));
+    }
+
+    candidates.sort_by_key(|(priority, _, _)| *priority);
+
+    candidates.into_iter().find_map(|(_, filename, path)| {
+        let content = String::from_utf8(std::fs::read(path).ok()?).ok()?;
+        Some(InstanceReadme {
+            filename: filename.into(),
+            content: content.into(),
+        })
+    })
+}
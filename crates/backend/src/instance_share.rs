@@ -0,0 +1,230 @@
+use std::{io::{Read, Write}, sync::Arc};
+
+use base64::Engine;
+use bridge::{
+    install::{ContentDownload, ContentInstall, ContentInstallFile, ContentInstallPath, InstallTarget}, instance::{InstanceID, InstanceShareSummary}, modal_action::ModalAction
+};
+use schema::{
+    content::ContentSource, instance::{InstanceConfiguration, InstanceMemoryConfiguration, InstanceResolutionConfiguration}, instance_share::{InstanceShare, InstanceShareContent}, modrinth::ModrinthProjectVersionsRequest
+};
+use strum::IntoEnumIterator;
+use ustr::Ustr;
+
+use crate::{instance::ContentFolder, metadata::items::ModrinthProjectVersionsMetadataItem, BackendState};
+
+// Share codes are links, so that clicking one opens the import dialog
+pub const SHARE_CODE_PREFIX: &str = "pandora://import/";
+
+#[derive(thiserror::Error, Debug)]
+enum InstanceShareError {
+    #[error("The instance no longer exists")]
+    MissingInstance,
+    #[error("Unable to load the content of the instance")]
+    ContentFailed,
+    #[error("The share code is invalid")]
+    InvalidCode,
+    #[error("Unable to create instance")]
+    CreateFailed,
+    #[error("Unable to load the created instance")]
+    LoadFailed,
+}
+
+impl BackendState {
+    pub async fn create_instance_share_code(&self, id: InstanceID) -> Result<Arc<str>, Arc<str>> {
+        let result = self.try_create_instance_share_code(id).await;
+        if let Err(error) = &result {
+            log::error!("Unable to create share code: {error}");
+        }
+        result.map_err(|error| error.to_string().into())
+    }
+
+    async fn try_create_instance_share_code(&self, id: InstanceID) -> Result<Arc<str>, InstanceShareError> {
+        let (name, configuration) = {
+            let mut instance_state = self.instance_state.write();
+            let instance = instance_state.instances.get_mut(id).ok_or(InstanceShareError::MissingInstance)?;
+            (instance.name, instance.configuration.get().clone())
+        };
+
+        let mut content = Vec::new();
+        let mut skipped = Vec::new();
+        for folder in ContentFolder::iter() {
+            let summaries = self.clone().load_instance_content(id, folder).await.ok_or(InstanceShareError::ContentFailed)?;
+
+            for summary in summaries.iter() {
+                if !summary.enabled {
+                    continue;
+                }
+                let ContentSource::ModrinthProject { project } = &summary.content_source else {
+                    skipped.push(summary.filename.clone());
+                    continue;
+                };
+
+                let sha1 = hex::encode(summary.content_summary.hash);
+                let version = self.find_share_content_version(project, &configuration.minecraft_version, &sha1).await;
+                content.push(InstanceShareContent {
+                    project: project.clone(),
+                    version,
+                });
+            }
+        }
+
+        if !skipped.is_empty() {
+            self.send.send_warning(format!("Content which isn't from Modrinth can't be shared:\n{}", skipped.join("\n")));
+        }
+
+        let share = InstanceShare {
+            name: name.as_str().into(),
+            minecraft_version: configuration.minecraft_version,
+            loader: configuration.loader,
+            preferred_loader_version: configuration.preferred_loader_version,
+            memory: configuration.memory.filter(InstanceMemoryConfiguration::is_enabled),
+            resolution: configuration.resolution.filter(InstanceResolutionConfiguration::is_enabled),
+            instance_type: configuration.instance_type,
+            content,
+        };
+        Ok(encode_share_code(&share).into())
+    }
+
+    // The version is found by the hash of the installed file, so that the exact same version is installed on import
+    async fn find_share_content_version(&self, project: &Arc<str>, version: &str, sha1: &str) -> Option<Arc<str>> {
+        let versions = self.meta.fetch(&ModrinthProjectVersionsMetadataItem(&ModrinthProjectVersionsRequest {
+            project_id: project.clone(),
+            game_versions: Some([version.into()].into()),
+            loaders: None,
+        })).await.inspect_err(|err| {
+            log::warn!("Unable to fetch versions for {project}: {err}");
+        }).ok()?;
+
+        versions.0.iter()
+            .find(|version| version.files.iter().any(|file| file.hashes.sha1.eq_ignore_ascii_case(sha1)))
+            .map(|version| version.id.clone())
+    }
+
+    pub fn read_instance_share_code(&self, code: &str) -> Result<InstanceShareSummary, Arc<str>> {
+        let share = decode_share_code(code).ok_or_else(|| Arc::from(InstanceShareError::InvalidCode.to_string()))?;
+        Ok(InstanceShareSummary {
+            name: share.name,
+            minecraft_version: share.minecraft_version,
+            loader: share.loader,
+            content_count: share.content.len(),
+        })
+    }
+
+    pub async fn import_instance_share_code(&self, code: &str, name: Ustr, modal_action: ModalAction) {
+        if let Err(error) = self.try_import_instance_share_code(code, name, &modal_action).await {
+            log::error!("Unable to import share code: {error}");
+            modal_action.set_error_message(error.to_string().into());
+        }
+    }
+
+    async fn try_import_instance_share_code(&self, code: &str, name: Ustr, modal_action: &ModalAction) -> Result<(), InstanceShareError> {
+        let share = decode_share_code(code).ok_or(InstanceShareError::InvalidCode)?;
+        let configuration = share_configuration(&share);
+
+        let pending = self.begin_create_instance(&name, configuration.clone(), None).await
+            .ok_or(InstanceShareError::CreateFailed)?;
+        let instance_dir = self.commit_instance(pending).ok_or(InstanceShareError::CreateFailed)?;
+
+        self.clone().load_instance_from_path(&instance_dir, true, false);
+        let id = self.instance_state.read().instance_by_path.get(&instance_dir).copied()
+            .ok_or(InstanceShareError::LoadFailed)?;
+
+        if share.content.is_empty() {
+            return Ok(());
+        }
+
+        let content_install = ContentInstall {
+            target: InstallTarget::Instance(id),
+            loader_hint: configuration.loader,
+            version_hint: Some(configuration.minecraft_version.as_str().into()),
+            files: share.content.into_iter().map(|content| ContentInstallFile {
+                replace_old: None,
+                path: ContentInstallPath::Automatic,
+                download: ContentDownload::Modrinth {
+                    project_id: content.project.clone(),
+                    version_id: content.version,
+                },
+                content_source: ContentSource::ModrinthProject { project: content.project },
+            }).collect(),
+        };
+        self.install_content(content_install, modal_action.clone()).await;
+        Ok(())
+    }
+}
+
+// Only the settings in the share are taken, everything else starts out like in a new instance
+fn share_configuration(share: &InstanceShare) -> InstanceConfiguration {
+    let mut configuration = InstanceConfiguration::new(share.minecraft_version, share.loader);
+    configuration.preferred_loader_version = share.preferred_loader_version;
+    configuration.memory = share.memory;
+    configuration.resolution = share.resolution;
+    configuration.instance_type = share.instance_type;
+    configuration
+}
+
+// The json is deflated and then base64 encoded, which keeps codes for large modlists short enough to paste in chat
+fn encode_share_code(share: &InstanceShare) -> String {
+    let json = serde_json::to_vec(share).unwrap();
+    let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::best());
+    _ = encoder.write_all(&json);
+    let compressed = encoder.finish().unwrap_or_default();
+    format!("{SHARE_CODE_PREFIX}{}", base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(compressed))
+}
+
+fn decode_share_code(code: &str) -> Option<InstanceShare> {
+    let code = code.trim();
+    let code = code.strip_prefix(SHARE_CODE_PREFIX).unwrap_or(code);
+    let compressed = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(code.trim_end_matches('=')).ok()?;
+
+    let mut json = Vec::new();
+    flate2::read::DeflateDecoder::new(compressed.as_slice()).take(16 * 1024 * 1024).read_to_end(&mut json).ok()?;
+    serde_json::from_slice(&json).inspect_err(|err| {
+        log::warn!("Unable to parse share code: {err}");
+    }).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use base64::Engine;
+    use schema::{instance::InstanceType, loader::Loader};
+
+    use super::{SHARE_CODE_PREFIX, decode_share_code, share_configuration};
+
+    fn code_from_json(json: &str) -> String {
+        let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(json.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+        format!("{SHARE_CODE_PREFIX}{}", base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(compressed))
+    }
+
+    #[test]
+    fn test_share_code_ignores_local_settings() {
+        let settings = r#""jvm_flags": {"enabled": true, "flags": "-XX:OnOutOfMemoryError=calc"},
+            "jvm_binary": {"enabled": true, "path": "/tmp/java"},
+            "linux_wrapper": {"use_mangohud": true, "use_gamemode": true, "use_discrete_gpu": true},
+            "system_libraries": {"override_glfw": true, "glfw": {"Custom": "/tmp/libglfw.so"}}"#;
+        // Codes may have the settings at the top level or inside a configuration like an instance's info_v1.json
+        let json = format!(r#"{{
+            "name": "Shared",
+            "minecraft_version": "1.20.1",
+            "loader": "fabric",
+            "instance_type": "server",
+            {settings},
+            "configuration": {{"minecraft_version": "1.20.1", "loader": "fabric", {settings}}},
+            "content": [{{"p": "AANobbMI"}}]
+        }}"#);
+
+        let share = decode_share_code(&code_from_json(&json)).unwrap();
+        let configuration = share_configuration(&share);
+        assert_eq!(configuration.minecraft_version.as_str(), "1.20.1");
+        assert_eq!(configuration.loader, Loader::Fabric);
+        assert_eq!(configuration.instance_type, InstanceType::Server);
+        assert!(configuration.jvm_flags.is_none());
+        assert!(configuration.jvm_binary.is_none());
+        assert!(configuration.linux_wrapper.is_none());
+        assert!(configuration.system_libraries.is_none());
+        assert_eq!(share.content.len(), 1);
+    }
+}
@@ -0,0 +1,249 @@
+use std::{path::Path, sync::Arc};
+
+use bridge::{
+    install::{ContentDownload, ContentInstall, ContentInstallFile, ContentInstallPath, InstallTarget}, instance::{InstanceID, InstanceTemplateSummary}, modal_action::ModalAction, safe_path::SafePath
+};
+use schema::{
+    content::ContentSource, instance_template::{InstanceTemplate, InstanceTemplateContent}, loader::Loader, modrinth::{ModrinthLoader, ModrinthProjectVersionsRequest}
+};
+use strum::IntoEnumIterator;
+use ustr::Ustr;
+
+use crate::{instance::ContentFolder, metadata::items::ModrinthProjectVersionsMetadataItem, BackendState};
+
+impl BackendState {
+    fn template_path(&self, name: &str) -> Option<std::path::PathBuf> {
+        if !crate::is_single_component_path(name)
+            || !sanitize_filename::is_sanitized_with_options(name, sanitize_filename::OptionsForCheck { windows: true, ..Default::default() })
+        {
+            return None;
+        }
+        Some(self.directories.templates_dir.join(format!("{name}.json")))
+    }
+
+    pub async fn save_instance_template(&self, id: InstanceID, name: &str) {
+        let Some(template_path) = self.template_path(name) else {
+            self.send.send_warning(format!("Unable to save template, name is invalid: {}", name));
+            return;
+        };
+        if template_path.exists() {
+            self.send.send_warning(format!("Unable to save template, a template named {name} already exists"));
+            return;
+        }
+
+        let Some((configuration, dot_minecraft)) = self.instance_state.write().instances.get_mut(id).map(|instance| {
+            (instance.configuration.get().clone(), instance.dot_minecraft_path.clone())
+        }) else {
+            self.send.send_error("Unable to save template, unknown instance id");
+            return;
+        };
+
+        let allow_links = !self.config.write().get().dont_link_content_files;
+        let mut content = Vec::new();
+        for folder in ContentFolder::iter() {
+            let Some(summaries) = self.clone().load_instance_content(id, folder).await else {
+                self.send.send_error("Unable to save template, failed to load instance content");
+                return;
+            };
+
+            for summary in summaries.iter() {
+                let modrinth_project = match &summary.content_source {
+                    ContentSource::ModrinthProject { project } => Some(project.clone()),
+                    _ => None,
+                };
+
+                // Keep a copy in the content library so the exact file can be restored even after the instance is deleted
+                let library_path = crate::create_content_library_path(&self.directories.content_library_dir,
+                    summary.content_summary.hash, Path::new(&*summary.filename).extension().and_then(|e| e.to_str()));
+                if !library_path.exists() {
+                    if let Some(parent) = library_path.parent() {
+                        _ = std::fs::create_dir_all(parent);
+                    }
+                    if let Err(err) = crate::linking::link_or_copy(&summary.path, &library_path, allow_links) {
+                        log::warn!("Unable to copy {:?} into the content library: {err}", summary.path);
+                    }
+                }
+
+                content.push(InstanceTemplateContent {
+                    path: format!("{}/{}", folder.path(), summary.filename).into(),
+                    sha1: hex::encode(summary.content_summary.hash).into(),
+                    modrinth_project,
+                });
+            }
+        }
+
+        let options = std::fs::read_to_string(dot_minecraft.join("options.txt")).ok();
+
+        let template = InstanceTemplate {
+            configuration,
+            content,
+            options,
+        };
+
+        match crate::write_safe(&template_path, serde_json::to_string(&template).unwrap().as_bytes()) {
+            Ok(()) => self.send.send_success(format!("Saved template {name}")),
+            Err(err) => self.send.send_error(format!("Unable to save template: {err}")),
+        }
+    }
+
+    pub fn list_instance_templates(&self) -> Arc<[InstanceTemplateSummary]> {
+        let Ok(read_dir) = std::fs::read_dir(&self.directories.templates_dir) else {
+            return Arc::new([]);
+        };
+
+        let mut templates: Vec<InstanceTemplateSummary> = read_dir.filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "json" {
+                return None;
+            }
+            let name = path.file_stem()?.to_str()?;
+            let template: InstanceTemplate = crate::read_json(&path).inspect_err(|err| {
+                log::warn!("Unable to read template {path:?}: {err}");
+            }).ok()?;
+            Some(InstanceTemplateSummary {
+                name: name.into(),
+                minecraft_version: template.configuration.minecraft_version,
+                loader: template.configuration.loader,
+                content_count: template.content.len(),
+            })
+        }).collect();
+
+        templates.sort_by(|a, b| lexical_sort::natural_lexical_cmp(&a.name, &b.name));
+        templates.into()
+    }
+
+    pub fn delete_instance_template(&self, name: &str) {
+        let Some(template_path) = self.template_path(name) else {
+            return;
+        };
+        if let Err(err) = std::fs::remove_file(template_path) {
+            self.send.send_error(format!("Unable to delete template: {err}"));
+        }
+    }
+
+    pub async fn create_instance_from_template(&self, template_name: &str, name: Ustr, version: Option<Ustr>, modal_action: ModalAction) {
+        let template: InstanceTemplate = match self.template_path(template_name).map(|path| crate::read_json(&path)) {
+            Some(Ok(template)) => template,
+            Some(Err(err)) => {
+                modal_action.set_error_message(format!("Unable to read template: {err}").into());
+                return;
+            },
+            None => {
+                modal_action.set_error_message("Unknown template".into());
+                return;
+            },
+        };
+
+        let mut configuration = template.configuration;
+        let version_changed = version.is_some_and(|version| version != configuration.minecraft_version);
+        if let Some(version) = version {
+            configuration.minecraft_version = version;
+        }
+        if version_changed {
+            // The pinned loader version most likely doesn't support the new game version
+            configuration.preferred_loader_version = None;
+        }
+
+        let Some(pending) = self.begin_create_instance(&name, configuration.clone(), None).await else {
+            modal_action.set_error_message("Unable to create instance".into());
+            return;
+        };
+
+        if let Some(options) = &template.options
+            && let Err(err) = crate::write_safe(&pending.dot_minecraft_path().join("options.txt"), options.as_bytes())
+        {
+            log::warn!("Unable to write options.txt for template instance: {err}");
+        }
+
+        let Some(instance_dir) = self.commit_instance(pending) else {
+            modal_action.set_error_message("Unable to create instance".into());
+            return;
+        };
+
+        self.clone().load_instance_from_path(&instance_dir, true, false);
+        let Some(id) = self.instance_state.read().instance_by_path.get(&instance_dir).copied() else {
+            modal_action.set_error_message("Unable to load created instance".into());
+            return;
+        };
+
+        let mut files = Vec::new();
+        let mut skipped = Vec::new();
+        for content in &template.content {
+            // Templates can be shared, so content must stay inside the content folders of the instance
+            let safe_path = SafePath::new(&content.path)
+                .filter(|path| ContentFolder::iter().any(|folder| path.starts_with(folder.path())));
+            let Some(safe_path) = safe_path else {
+                skipped.push(&*content.path);
+                continue;
+            };
+            let Some(filename) = safe_path.file_name() else {
+                continue;
+            };
+
+            let mut hash = [0u8; 20];
+            let library_path = hex::decode_to_slice(&*content.sha1, &mut hash).ok().map(|_| {
+                crate::create_content_library_path(&self.directories.content_library_dir, hash, safe_path.extension())
+            });
+            let content_source = match &content.modrinth_project {
+                Some(project) => ContentSource::ModrinthProject { project: project.clone() },
+                None => ContentSource::Manual,
+            };
+
+            if !version_changed && let Some(library_path) = library_path && library_path.exists() {
+                files.push(ContentInstallFile {
+                    replace_old: None,
+                    path: ContentInstallPath::Safe(safe_path.clone()),
+                    download: ContentDownload::File { path: library_path },
+                    content_source,
+                });
+            } else if let Some(project) = &content.modrinth_project && !filename.ends_with(".disabled") {
+                let loader_agnostic = safe_path.starts_with(ContentFolder::ResourcePacks.path());
+                match self.find_template_content_version(project, &configuration.minecraft_version, configuration.loader, loader_agnostic).await {
+                    Some(version_id) => {
+                        files.push(ContentInstallFile {
+                            replace_old: None,
+                            path: ContentInstallPath::Automatic,
+                            download: ContentDownload::Modrinth { project_id: project.clone(), version_id: Some(version_id) },
+                            content_source,
+                        });
+                    },
+                    None => skipped.push(&*content.path),
+                }
+            } else {
+                skipped.push(&*content.path);
+            }
+        }
+
+        if !files.is_empty() {
+            let content_install = ContentInstall {
+                target: InstallTarget::Instance(id),
+                loader_hint: configuration.loader,
+                version_hint: Some(configuration.minecraft_version.as_str().into()),
+                files: files.into(),
+            };
+            self.install_content(content_install, modal_action.clone()).await;
+        }
+
+        if !skipped.is_empty() {
+            self.send.send_warning(format!("Some content from the template couldn't be added:\n{}", skipped.join("\n")));
+        }
+    }
+
+    // Versions for other loaders are only used for content that works with any loader, such as resource packs
+    async fn find_template_content_version(&self, project: &Arc<str>, version: &str, loader: Loader, loader_agnostic: bool) -> Option<Arc<str>> {
+        let versions = self.meta.fetch(&ModrinthProjectVersionsMetadataItem(&ModrinthProjectVersionsRequest {
+            project_id: project.clone(),
+            game_versions: Some([version.into()].into()),
+            loaders: None,
+        })).await.inspect_err(|err| {
+            log::warn!("Unable to fetch versions for {project}: {err}");
+        }).ok()?;
+
+        let modrinth_loader = loader.as_modrinth_loader();
+        versions.0.iter()
+            .find(|version| modrinth_loader != ModrinthLoader::Unknown
+                && version.loaders.as_ref().is_some_and(|loaders| loaders.contains(&modrinth_loader)))
+            .or(versions.0.first().filter(|_| loader_agnostic))
+            .map(|version| version.id.clone())
+    }
+}
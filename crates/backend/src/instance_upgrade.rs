@@ -0,0 +1,228 @@
+use std::{path::{Path, PathBuf}, sync::Arc, time::{SystemTime, UNIX_EPOCH}};
+
+use bridge::{
+    install::{ContentInstall, InstallTarget}, instance::InstanceID, message::MessageToFrontend, modal_action::{ModalAction, ProgressTracker, ProgressTrackerFinishType}
+};
+use schema::{instance::VersionTrackingChannel, instance_history::InstanceHistoryEvent, loader::Loader};
+use strum::IntoEnumIterator;
+use ustr::Ustr;
+
+use crate::{instance::ContentFolder, mod_metadata::ModUpdateAction, BackendState};
+
+// Older snapshots of an instance are removed once there are more than this
+const SNAPSHOTS_KEPT: usize = 3;
+
+impl BackendState {
+    pub async fn upgrade_tracking_instances(&self, newest_release: Option<Ustr>, newest_version: Option<Ustr>) {
+        let mut upgrades = Vec::new();
+        for instance in self.instance_state.write().instances.iter_mut() {
+            let running = instance.child.is_some();
+            let configuration = instance.configuration.get();
+            let Some(version_tracking) = configuration.version_tracking else {
+                continue;
+            };
+            let version = match version_tracking.channel {
+                VersionTrackingChannel::Release => newest_release,
+                VersionTrackingChannel::Snapshot => newest_version,
+            };
+            if let Some(version) = version && version != configuration.minecraft_version {
+                // A running instance can't be upgraded, so ask instead to let the user do it later
+                upgrades.push((instance.id, instance.name, version, version_tracking.automatic && !running));
+            }
+        }
+
+        for (id, name, version, automatic) in upgrades {
+            if !automatic {
+                self.send.send(MessageToFrontend::InstanceUpgradeAvailable { id, name, version });
+                continue;
+            }
+
+            log::info!("Upgrading {name} to Minecraft {version}");
+            let modal_action = ModalAction::default();
+            self.upgrade_instance(id, version, modal_action.clone()).await;
+            if let Some(error) = modal_action.error.read().unwrap().clone() {
+                self.send.send_error(format!("Unable to upgrade {name} to Minecraft {version}: {error}"));
+            }
+        }
+    }
+
+    // The instance is snapshotted first so that the upgrade can be rolled back with restore_instance_snapshot
+    pub async fn upgrade_instance(&self, id: InstanceID, version: Ustr, modal_action: ModalAction) {
+        let allow_links = !self.config.write().get().dont_link_content_files;
+
+        let (name, root_path, loader, previous_version) = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+            if instance.child.is_some() {
+                modal_action.set_error_message("Can't upgrade the instance while it is running".into());
+                modal_action.set_finished();
+                return;
+            }
+
+            let configuration = instance.configuration.get();
+            (instance.name, instance.root_path.clone(), configuration.loader, configuration.minecraft_version)
+        } else {
+            self.send.send_error("Can't upgrade instance, unknown id");
+            modal_action.set_error_message("Can't upgrade instance, unknown id".into());
+            modal_action.set_finished();
+            return;
+        };
+
+        let snapshot_tracker = ProgressTracker::new(Arc::from("Creating snapshot"), self.send.clone());
+        snapshot_tracker.set_total(1);
+        snapshot_tracker.notify();
+        modal_action.trackers.push(snapshot_tracker.clone());
+
+        let snapshots_dir = self.directories.snapshots_dir.join(name.as_str());
+        let result = tokio::task::spawn_blocking(move || {
+            create_snapshot(&root_path, &snapshots_dir, previous_version, allow_links)
+        }).await.unwrap();
+
+        if let Err(error) = result {
+            log::error!("Unable to create snapshot of {name}: {error}");
+            snapshot_tracker.set_finished(ProgressTrackerFinishType::Error);
+            snapshot_tracker.notify();
+            modal_action.set_error_message(format!("Unable to create snapshot: {error}").into());
+            modal_action.set_finished();
+            return;
+        }
+
+        snapshot_tracker.set_count(1);
+        snapshot_tracker.set_finished(ProgressTrackerFinishType::Normal);
+        snapshot_tracker.notify();
+
+        // Loader versions are specific to a Minecraft version, so the latest one is resolved again on launch
+        if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+            instance.configuration.modify(|configuration| {
+                configuration.minecraft_version = version;
+                configuration.preferred_loader_version = None;
+            });
+            instance.record_history(InstanceHistoryEvent::MinecraftVersionChanged { from: previous_version, to: version });
+        }
+
+        let mut incompatible = 0;
+
+        if loader != Loader::Vanilla && self.check_for_content_updates(id, &modal_action).await {
+            let mut files = Vec::new();
+
+            for folder in ContentFolder::iter() {
+                let Some(summaries) = self.clone().load_instance_content(id, folder).await else {
+                    continue;
+                };
+
+                for summary in summaries.iter() {
+                    let update = self.mod_metadata_manager.updates.read().get(&summary.content_summary.hash).cloned();
+                    match update {
+                        Some(ModUpdateAction::Modrinth { .. }) => {
+                            match self.content_update_install(id, summary.id) {
+                                Ok(content_install) => files.extend(content_install.files.iter().cloned()),
+                                Err(error) => log::warn!("Unable to update {:?}: {error}", summary.filename),
+                            }
+                        },
+                        Some(ModUpdateAction::ErrorNotFound) => incompatible += 1,
+                        _ => {},
+                    }
+                }
+            }
+
+            if !files.is_empty() {
+                let content_install = ContentInstall {
+                    target: InstallTarget::Instance(id),
+                    loader_hint: loader,
+                    version_hint: Some(version.as_str().into()),
+                    files: files.into(),
+                };
+                self.install_content(content_install, modal_action.clone()).await;
+            }
+        }
+
+        if modal_action.error.read().unwrap().is_none() {
+            if incompatible > 0 {
+                self.send.send_warning(format!("{incompatible} mods in {name} have no version for Minecraft {version} yet"));
+            }
+            self.send.send(MessageToFrontend::InstanceUpgraded { id, name, from: previous_version, to: version });
+        }
+
+        self.send.send(MessageToFrontend::Refresh);
+        modal_action.set_finished();
+    }
+
+    pub async fn restore_instance_snapshot(&self, id: InstanceID, modal_action: ModalAction) {
+        let (name, root_path) = if let Some(instance) = self.instance_state.write().instances.get_mut(id) {
+            if instance.child.is_some() {
+                modal_action.set_error_message("Can't restore the instance while it is running".into());
+                modal_action.set_finished();
+                return;
+            }
+
+            (instance.name, instance.root_path.clone())
+        } else {
+            self.send.send_error("Can't restore instance, unknown id");
+            modal_action.set_error_message("Can't restore instance, unknown id".into());
+            modal_action.set_finished();
+            return;
+        };
+
+        let Some((_, snapshot)) = list_snapshots(&self.directories.snapshots_dir.join(name.as_str())).pop() else {
+            modal_action.set_error_message("There is no snapshot of this instance to restore".into());
+            modal_action.set_finished();
+            return;
+        };
+
+        let result = tokio::task::spawn_blocking(move || restore_snapshot(&snapshot, &root_path)).await.unwrap();
+
+        match result {
+            Ok(()) => self.send.send_success(format!("Restored {name} to before it was upgraded")),
+            Err(error) => {
+                log::error!("Unable to restore snapshot of {name}: {error}");
+                modal_action.set_error_message(format!("Unable to restore snapshot: {error}").into());
+            },
+        }
+
+        self.send.send(MessageToFrontend::Refresh);
+        modal_action.set_finished();
+    }
+}
+
+fn create_snapshot(root_path: &Path, snapshots_dir: &Path, version: Ustr, allow_links: bool) -> std::io::Result<()> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or_default();
+    let snapshot_path = snapshots_dir.join(format!("{timestamp}-{version}"));
+
+    if let Err(error) = crate::linking::copy_dir(root_path, &snapshot_path, allow_links) {
+        _ = std::fs::remove_dir_all(&snapshot_path);
+        return Err(error);
+    }
+
+    let mut snapshots = list_snapshots(snapshots_dir);
+    while snapshots.len() > SNAPSHOTS_KEPT {
+        let (_, oldest) = snapshots.remove(0);
+        _ = std::fs::remove_dir_all(oldest);
+    }
+
+    Ok(())
+}
+
+// Sorted from oldest to newest
+fn list_snapshots(snapshots_dir: &Path) -> Vec<(u64, PathBuf)> {
+    let Ok(read_dir) = std::fs::read_dir(snapshots_dir) else {
+        return Vec::new();
+    };
+
+    let mut snapshots: Vec<_> = read_dir.filter_map(|entry| {
+        let entry = entry.ok()?;
+        let timestamp = entry.file_name().to_str()?.split_once('-')?.0.parse().ok()?;
+        Some((timestamp, entry.path()))
+    }).collect();
+    snapshots.sort();
+    snapshots
+}
+
+fn restore_snapshot(snapshot: &Path, root_path: &Path) -> std::io::Result<()> {
+    std::fs::remove_dir_all(root_path)?;
+    if std::fs::rename(snapshot, root_path).is_ok() {
+        return Ok(());
+    }
+
+    // The snapshots folder can be on a different filesystem than the instance
+    crate::linking::copy_dir(snapshot, root_path, false)?;
+    _ = std::fs::remove_dir_all(snapshot);
+    Ok(())
+}
@@ -0,0 +1,266 @@
+use std::{collections::{BTreeMap, BTreeSet}, path::Path, sync::Arc};
+
+use bridge::{message::JavaRuntimeSummary, modal_action::ModalAction};
+use chrono::{TimeDelta, Utc};
+use rustc_hash::FxHashSet;
+use schema::java_runtime_component::{JavaRuntimeComponentFile, JavaRuntimeComponentManifest};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::BackendState;
+
+// Stored in the runtime directory, records which instances launched with each runtime tree
+const USAGE_FILE: &str = "usage_v1.json";
+
+// Launches prepare runtimes concurrently, the usage file is only read and written while holding this
+static USAGE_LOCK: parking_lot::Mutex<()> = parking_lot::Mutex::new(());
+
+// Runtimes which a launch is verifying or downloading right now, as `component/platform`. They are left alone by the
+// cleanup even when no instance has been recorded for them yet
+static PREPARING: parking_lot::Mutex<Vec<String>> = parking_lot::Mutex::new(Vec::new());
+
+// Runtimes which no instance uses are only removed once they haven't been launched for this long. Runtimes from before
+// usage was recorded get the time they were first seen instead, so they aren't removed while instances still use them
+const UNUSED_AFTER: TimeDelta = TimeDelta::days(14);
+
+#[derive(Default, Serialize, Deserialize)]
+struct RuntimeUsage {
+    // Keyed by `component/platform`, the same layout as the runtime directory
+    #[serde(default)]
+    runtimes: BTreeMap<String, RuntimeUsageEntry>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct RuntimeUsageEntry {
+    #[serde(default)]
+    version: Option<Arc<str>>,
+    // See instance_usage_key
+    #[serde(default)]
+    instances: BTreeSet<String>,
+    // Unix timestamp of the last launch using the runtime
+    #[serde(default)]
+    last_used: Option<i64>,
+}
+
+// Instances are identified by their uuid, or by their .minecraft folder for instances created before uuids existed
+pub fn instance_usage_key(uuid: Option<Uuid>, dot_minecraft_path: &Path) -> String {
+    match uuid {
+        Some(uuid) => uuid.to_string(),
+        None => dot_minecraft_path.to_string_lossy().into_owned(),
+    }
+}
+
+pub struct PreparingRuntime(String);
+
+impl Drop for PreparingRuntime {
+    fn drop(&mut self) {
+        let mut preparing = PREPARING.lock();
+        if let Some(index) = preparing.iter().position(|key| *key == self.0) {
+            preparing.swap_remove(index);
+        }
+    }
+}
+
+// Keeps the runtime from being removed until the returned guard is dropped
+pub fn start_preparing_runtime(component: &str, platform: &str) -> PreparingRuntime {
+    // The launch downloads the runtime again if a cleanup which was running removed it
+    let _guard = USAGE_LOCK.lock();
+    let key = format!("{component}/{platform}");
+    PREPARING.lock().push(key.clone());
+    PreparingRuntime(key)
+}
+
+// Called after a runtime tree was verified for a launch. When Mojang released a new version of the component, files
+// which only belonged to the previous version are removed
+pub fn record_runtime_usage(
+    runtime_base_dir: &Path,
+    component: &str,
+    platform: &str,
+    version: &str,
+    instance: Option<String>,
+    manifest: &JavaRuntimeComponentManifest,
+) {
+    let _guard = USAGE_LOCK.lock();
+
+    let usage_path = runtime_base_dir.join(USAGE_FILE);
+    let mut usage: RuntimeUsage = crate::read_json(&usage_path).unwrap_or_default();
+    let entry = usage.runtimes.entry(format!("{component}/{platform}")).or_default();
+
+    if entry.version.as_deref().is_some_and(|previous| previous != version) {
+        let removed = remove_files_not_in_manifest(&runtime_base_dir.join(component).join(platform), manifest);
+        log::info!("Java runtime {component} was updated to {version}, removed {removed} outdated files");
+    }
+
+    entry.version = Some(version.into());
+    entry.last_used = Some(Utc::now().timestamp());
+    if let Some(instance) = instance {
+        entry.instances.insert(instance);
+    }
+
+    if let Err(error) = crate::write_safe(&usage_path, serde_json::to_string(&usage).unwrap().as_bytes()) {
+        log::error!("Unable to record Java runtime usage: {error}");
+    }
+}
+
+fn remove_files_not_in_manifest(runtime_component_dir: &Path, manifest: &JavaRuntimeComponentManifest) -> usize {
+    let expected: FxHashSet<&Path> = manifest.files.iter()
+        .filter(|(_, file)| !matches!(file, JavaRuntimeComponentFile::Directory))
+        .map(|(path, _)| &**path)
+        .collect();
+
+    let mut removed = 0;
+    let mut pending = vec![runtime_component_dir.to_path_buf()];
+    while let Some(directory) = pending.pop() {
+        let Ok(read_dir) = std::fs::read_dir(&directory) else {
+            continue;
+        };
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let Ok(relative) = path.strip_prefix(runtime_component_dir) else {
+                continue;
+            };
+
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if relative != Path::new("manifest.json") && !expected.contains(relative) && std::fs::remove_file(&path).is_ok() {
+                removed += 1;
+            }
+        }
+    }
+    removed
+}
+
+fn directory_size(path: &Path) -> u64 {
+    let mut size = 0;
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(directory) = pending.pop() {
+        let Ok(read_dir) = std::fs::read_dir(&directory) else {
+            continue;
+        };
+        for entry in read_dir.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else if metadata.is_file() {
+                size += metadata.len();
+            }
+        }
+    }
+    size
+}
+
+impl BackendState {
+    fn known_instance_keys(&self) -> FxHashSet<String> {
+        self.instance_state.write().instances.iter_mut()
+            .map(|instance| instance_usage_key(instance.configuration.get().uuid, &instance.dot_minecraft_path))
+            .collect()
+    }
+
+    // Every runtime tree in the runtime directory. Trees which no known instance launched with for a while can be
+    // removed, they are downloaded again when needed
+    pub fn get_java_runtimes(&self) -> Arc<[JavaRuntimeSummary]> {
+        let _guard = USAGE_LOCK.lock();
+        self.scan_java_runtimes()
+    }
+
+    // Must be called while holding USAGE_LOCK
+    fn scan_java_runtimes(&self) -> Arc<[JavaRuntimeSummary]> {
+        let known_instances = self.known_instance_keys();
+        let runtime_base_dir = &self.directories.runtime_base_dir;
+        let usage_path = runtime_base_dir.join(USAGE_FILE);
+        let mut usage: RuntimeUsage = crate::read_json(&usage_path).unwrap_or_default();
+        let mut seeded = false;
+
+        let now = Utc::now().timestamp();
+        let preparing = PREPARING.lock().clone();
+
+        let mut runtimes = Vec::new();
+        let Ok(components) = std::fs::read_dir(runtime_base_dir) else {
+            return Arc::from([]);
+        };
+        for component in components.flatten() {
+            if !component.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                continue;
+            }
+            let Ok(platforms) = std::fs::read_dir(component.path()) else {
+                continue;
+            };
+            for platform in platforms.flatten() {
+                if !platform.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                    continue;
+                }
+
+                let component_name = component.file_name().to_string_lossy().into_owned();
+                let platform_name = platform.file_name().to_string_lossy().into_owned();
+                let key = format!("{component_name}/{platform_name}");
+
+                let entry = usage.runtimes.entry(key.clone()).or_default();
+                let last_used = *entry.last_used.get_or_insert_with(|| {
+                    seeded = true;
+                    now
+                });
+                let instances = entry.instances.iter().filter(|instance| known_instances.contains(*instance)).count();
+
+                runtimes.push(JavaRuntimeSummary {
+                    component: component_name.into(),
+                    platform: platform_name.into(),
+                    version: entry.version.clone(),
+                    size: directory_size(&platform.path()),
+                    instances,
+                    removable: instances == 0 && now - last_used >= UNUSED_AFTER.num_seconds() && !preparing.contains(&key),
+                });
+            }
+        }
+
+        if seeded && let Err(error) = crate::write_safe(&usage_path, serde_json::to_string(&usage).unwrap().as_bytes()) {
+            log::error!("Unable to record Java runtime usage: {error}");
+        }
+
+        runtimes.sort_by(|a, b| a.component.cmp(&b.component).then_with(|| a.platform.cmp(&b.platform)));
+        runtimes.into()
+    }
+
+    pub fn remove_unused_java_runtimes(&self, modal_action: &ModalAction) {
+        let running = self.instance_state.read().instances.iter().any(|instance| instance.child.is_some());
+        if running {
+            modal_action.set_error_message("Close all running instances before removing Java runtimes".into());
+            return;
+        }
+
+        // Held until the runtimes are removed, so that a launch can't start preparing one of them in the meantime
+        let _guard = USAGE_LOCK.lock();
+        let unused = self.scan_java_runtimes().iter().filter(|runtime| runtime.removable).cloned().collect::<Vec<_>>();
+
+        let runtime_base_dir = &self.directories.runtime_base_dir;
+        let usage_path = runtime_base_dir.join(USAGE_FILE);
+        let mut usage: RuntimeUsage = crate::read_json(&usage_path).unwrap_or_default();
+
+        let mut freed = 0;
+        for runtime in &unused {
+            let path = runtime_base_dir.join(&*runtime.component).join(&*runtime.platform);
+            match std::fs::remove_dir_all(&path) {
+                Ok(()) => {
+                    freed += runtime.size;
+                    usage.runtimes.remove(&format!("{}/{}", runtime.component, runtime.platform));
+                },
+                Err(error) => log::error!("Unable to remove Java runtime {path:?}: {error}"),
+            }
+            // Remove the component directory once its last platform is gone
+            _ = std::fs::remove_dir(runtime_base_dir.join(&*runtime.component));
+        }
+
+        // Entries of instances which no longer exist are dropped as well
+        let known_instances = self.known_instance_keys();
+        for entry in usage.runtimes.values_mut() {
+            entry.instances.retain(|instance| known_instances.contains(instance));
+        }
+        _ = crate::write_safe(&usage_path, serde_json::to_string(&usage).unwrap().as_bytes());
+
+        self.send.send_success(format!("Removed {} unused Java runtimes, freeing {} MiB", unused.len(), freed / 1024 / 1024));
+    }
+}
@@ -0,0 +1,131 @@
+use std::{future::Future, sync::Arc};
+
+use bridge::{handle::FrontendHandle, job::{JobPriority, JobSummary}, message::MessageToFrontend};
+use tokio::sync::Notify;
+
+use crate::BackendState;
+
+// Maximum number of background jobs running at the same time. Interactive and launch jobs are never limited
+const MAX_BACKGROUND_JOBS: usize = 1;
+
+struct Job {
+    id: u64,
+    name: Arc<str>,
+    priority: JobPriority,
+    running: bool,
+}
+
+#[derive(Default)]
+struct JobQueueState {
+    next_id: u64,
+    jobs: Vec<Job>,
+}
+
+// Work which isn't a direct response to the user goes through this queue, so that heavy background work waits
+// while an instance is being launched or the user is waiting on something
+#[derive(Default)]
+pub struct JobQueue {
+    state: parking_lot::Mutex<JobQueueState>,
+    changed: Notify,
+}
+
+impl JobQueue {
+    fn enqueue(self: &Arc<Self>, name: Arc<str>, priority: JobPriority, send: FrontendHandle) -> JobGuard {
+        let id = {
+            let mut state = self.state.lock();
+            let id = state.next_id;
+            state.next_id += 1;
+            state.jobs.push(Job { id, name, priority, running: false });
+            id
+        };
+        self.send_update(&send);
+
+        JobGuard {
+            queue: self.clone(),
+            id,
+            send,
+        }
+    }
+
+    fn try_start(&self, id: u64) -> bool {
+        let mut state = self.state.lock();
+        let Some(index) = state.jobs.iter().position(|job| job.id == id) else {
+            return false;
+        };
+
+        let priority = state.jobs[index].priority;
+        let can_start = match priority {
+            JobPriority::Interactive | JobPriority::LaunchPreparation => true,
+            JobPriority::Background => {
+                let higher_priority = state.jobs.iter().any(|job| job.priority < JobPriority::Background);
+                let running = state.jobs.iter().filter(|job| job.running && job.priority == JobPriority::Background).count();
+                // Background jobs start in the order they were queued in
+                let first_queued = state.jobs.iter().find(|job| !job.running && job.priority == JobPriority::Background)
+                    .is_some_and(|job| job.id == id);
+                !higher_priority && running < MAX_BACKGROUND_JOBS && first_queued
+            },
+        };
+
+        if can_start {
+            state.jobs[index].running = true;
+        }
+        can_start
+    }
+
+    fn send_update(&self, send: &FrontendHandle) {
+        let mut jobs: Vec<JobSummary> = self.state.lock().jobs.iter().map(|job| JobSummary {
+            name: job.name.clone(),
+            priority: job.priority,
+            running: job.running,
+        }).collect();
+        jobs.sort_by_key(|job| (!job.running, job.priority));
+        send.send(MessageToFrontend::BackgroundJobsUpdated { jobs: jobs.into() });
+    }
+}
+
+// Removes the job from the queue when it's dropped, either because the job finished or was cancelled
+pub struct JobGuard {
+    queue: Arc<JobQueue>,
+    id: u64,
+    send: FrontendHandle,
+}
+
+impl JobGuard {
+    async fn wait_for_turn(&self) {
+        loop {
+            let notified = self.queue.changed.notified();
+            tokio::pin!(notified);
+            // Registered before checking, so a job finishing in between isn't missed
+            notified.as_mut().enable();
+
+            if self.queue.try_start(self.id) {
+                self.queue.send_update(&self.send);
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+impl Drop for JobGuard {
+    fn drop(&mut self) {
+        self.queue.state.lock().jobs.retain(|job| job.id != self.id);
+        self.queue.changed.notify_waiters();
+        self.queue.send_update(&self.send);
+    }
+}
+
+impl BackendState {
+    // Waits until the queue allows a job with this priority to start. The job is listed as running until the guard
+    // is dropped
+    pub async fn start_job(&self, name: impl Into<Arc<str>>, priority: JobPriority) -> JobGuard {
+        let guard = self.jobs.enqueue(name.into(), priority, self.send.clone());
+        guard.wait_for_turn().await;
+        guard
+    }
+
+    pub async fn run_job<T>(&self, name: impl Into<Arc<str>>, priority: JobPriority, job: impl Future<Output = T>) -> T {
+        let _guard = self.start_job(name, priority).await;
+        job.await
+    }
+}
@@ -0,0 +1,124 @@
+use std::{path::Path, sync::Arc};
+
+use bridge::message::{JvmCrashReport, MessageToFrontend};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::{BackendState, instance::Instance};
+
+// e.g. "#  EXCEPTION_ACCESS_VIOLATION (0xc0000005) at pc=0x00007ffb1e2b5a7c, pid=12345, tid=6789"
+static ERROR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^#\s+(\S+ \(0x[0-9a-fA-F]+\)) at pc="#).unwrap());
+static JRE_VERSION: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^# JRE version: (.+)$"#).unwrap());
+// e.g. "# C  [ig9icd64.dll+0x65a7c]" or "# C  [libc.so.6+0x9ef3c]  __pthread_kill_implementation+0x11c"
+static FRAME_LIBRARY: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\[([^\]+]+)\+0x"#).unwrap());
+
+impl BackendState {
+    // The JVM writes hs_err_pid<pid>.log to its working directory when it crashes outside of Java code. These are
+    // moved into crash-reports so they don't pile up in .minecraft, and summarized for the crash dialog
+    pub fn collect_jvm_crash(&self, instance: &Instance, pid: u32) {
+        let id = instance.id;
+        let name = instance.name;
+        let dot_minecraft = instance.dot_minecraft_path.clone();
+        let send = self.send.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let filename = format!("hs_err_pid{pid}.log");
+            let path = dot_minecraft.join(&filename);
+            let Ok(content) = std::fs::read(&path) else {
+                return;
+            };
+
+            log::warn!("Java crashed while running {name}, see {}", path.display());
+
+            let crash_reports = dot_minecraft.join("crash-reports");
+            let collected = crash_reports.join(&filename);
+            let path: Arc<Path> = if std::fs::create_dir_all(&crash_reports).is_ok() && std::fs::rename(&path, &collected).is_ok() {
+                collected.into()
+            } else {
+                path.into()
+            };
+
+            let report = parse_jvm_crash(path, &String::from_utf8_lossy(&content));
+            send.send(MessageToFrontend::InstanceJvmCrashed { id, name, report });
+        });
+    }
+}
+
+fn parse_jvm_crash(path: Arc<Path>, content: &str) -> JvmCrashReport {
+    let mut report = JvmCrashReport {
+        path,
+        error: None,
+        problematic_frame: None,
+        library: None,
+        java_version: None,
+        guidance: None,
+    };
+
+    let mut lines = content.lines();
+    while let Some(line) = lines.next() {
+        if line.starts_with("# There is insufficient memory for the Java Runtime Environment to continue") {
+            report.error = Some("Insufficient memory".into());
+            report.guidance = Some("Java was unable to reserve enough memory. Lower the maximum memory in the instance settings or close other programs".into());
+        } else if let Some(captures) = ERROR.captures(line) {
+            report.error = Some(captures[1].into());
+        } else if let Some(captures) = JRE_VERSION.captures(line) {
+            report.java_version = Some(captures[1].trim().into());
+        } else if line.starts_with("# Problematic frame:") {
+            if let Some(frame) = lines.next().and_then(|frame| frame.strip_prefix('#')) {
+                report.problematic_frame = Some(frame.trim().into());
+            }
+        } else if line.starts_with("Dynamic libraries:") {
+            break;
+        }
+    }
+
+    let Some(library_name) = report.problematic_frame.as_deref()
+        .and_then(|frame| FRAME_LIBRARY.captures(frame))
+        .map(|captures| captures[1].to_string())
+    else {
+        return report;
+    };
+
+    // The dynamic libraries section lists the full path of every loaded native, which tells apart e.g. a driver
+    // from a library shipped with the game
+    report.library = lines
+        .take_while(|line| !line.trim().is_empty())
+        .filter_map(library_path)
+        .find(|path| Path::new(path).file_name().is_some_and(|file_name| file_name.eq_ignore_ascii_case(&library_name)))
+        .map(Arc::from)
+        .or(Some(library_name.as_str().into()));
+
+    if report.guidance.is_none() {
+        report.guidance = native_guidance(&library_name.to_ascii_lowercase()).map(Arc::from);
+    }
+
+    report
+}
+
+// Windows lists "<start> - <end> \t<path>", other platforms use the /proc/self/maps format with the path last
+fn library_path(line: &str) -> Option<&str> {
+    let path = match line.rsplit_once('\t') {
+        Some((_, path)) => path,
+        None => line.split_whitespace().last()?,
+    };
+    let path = path.trim();
+    (path.contains('/') || path.contains('\\')).then_some(path)
+}
+
+fn native_guidance(library: &str) -> Option<&'static str> {
+    if library.starts_with("ig") && library.contains("icd") {
+        Some("The crash happened in the Intel graphics driver. Updating the driver from Intel's website usually fixes this, older drivers installed by Windows Update are a common cause")
+    } else if library.starts_with("atio") || library.starts_with("atig") || library.starts_with("amdx") {
+        Some("The crash happened in the AMD graphics driver. Try updating the driver from AMD's website")
+    } else if library.starts_with("nvoglv") || library.contains("nvidia") {
+        Some("The crash happened in the NVIDIA graphics driver. Try updating the driver from NVIDIA's website")
+    } else if library.ends_with("_dri.so") || library.contains("mesa") || library.starts_with("libgl") {
+        Some("The crash happened in the Mesa graphics driver. Try updating Mesa through your package manager")
+    } else if library.contains("lwjgl") || library.contains("glfw") || library.contains("openal") {
+        Some("The crash happened in a native library used by the game. Try verifying the game files from the instance settings")
+    } else if matches!(library, "jvm.dll" | "libjvm.so" | "libjvm.dylib") {
+        Some("The crash happened inside Java itself. Try a different Java version in the instance settings")
+    } else {
+        None
+    }
+}
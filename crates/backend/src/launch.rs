@@ -1,9 +1,9 @@
 use std::{
-    borrow::Cow, cmp::Ordering, collections::{BTreeSet, HashMap, HashSet}, ffi::{OsStr, OsString}, fs::File, io::{BufRead, BufReader, Read, Write}, path::{Path, PathBuf}, process::{Child, Stdio}, sync::{Arc, OnceLock, atomic::AtomicBool}
+    borrow::Cow, cmp::Ordering, collections::{BTreeSet, HashMap, HashSet}, ffi::{OsStr, OsString}, fs::File, io::{BufRead, BufReader, Read, Write}, path::{Path, PathBuf}, process::{Child, Stdio}, sync::{Arc, OnceLock, atomic::{AtomicBool, AtomicUsize}}, time::{Duration, Instant}
 };
 
 use bridge::{
-    handle::FrontendHandle, message::{MessageToFrontend, QuickPlayLaunch}, modal_action::{ModalAction, ProgressTracker, ProgressTrackerFinishType, ProgressTrackers}, safe_path::SafePath
+    handle::FrontendHandle, message::{MessageToFrontend, QuickPlayLaunch}, modal_action::{ModalAction, ProgressTracker, ProgressTrackerFinishType}, safe_path::SafePath
 };
 use futures::{FutureExt, TryFutureExt};
 use rand::seq::SliceRandom;
@@ -11,16 +11,16 @@ use rc_zip_sync::{ArchiveHandle, ReadZip};
 use regex::Regex;
 use rustc_hash::FxHashMap;
 use schema::{
-    assets_index::AssetsIndex, fabric_launch::FabricLaunch, forge::{ForgeInstallProfile, ForgeInstallProfileLegacy, ForgeSide, VersionFragment}, instance::InstanceConfiguration, java_runtime_component::{JavaRuntimeComponentFile, JavaRuntimeComponentManifest}, loader::Loader, maven::{MavenCoordinate, MavenMetadataXml}, version::{
+    assets_index::AssetsIndex, fabric_launch::FabricLaunch, forge::{ForgeInstallProfile, ForgeInstallProfileLegacy, ForgeSide}, instance::{InstanceConfiguration, InstanceLegacyFixesConfiguration, InstanceType}, java_runtime_component::{JavaRuntimeComponentFile, JavaRuntimeComponentManifest}, loader::Loader, maven::{MavenCoordinate, MavenMetadataXml}, version::{
         GameLibrary, GameLibraryArtifact, GameLibraryDownloads, GameLibraryExtractOptions, GameLogging, LaunchArgument, LaunchArgumentValue, MinecraftVersion, OsArch, OsName, PartialMinecraftVersion, Rule, RuleAction
-    }, version_manifest::MinecraftVersionManifest
+    }, version_manifest::{MinecraftVersionManifest, MinecraftVersionType}
 };
 use serde::Deserialize;
 use sha1::{Digest, Sha1};
 use ustr::Ustr;
 
 use crate::{
-    account::MinecraftLoginInfo, directories::LauncherDirectories, launch_wrapper, metadata::{items::{AssetsIndexMetadataItem, FabricLaunchMetadataItem, FabricLoaderManifestMetadataItem, ForgeInstallerMavenMetadataItem, MinecraftVersionManifestMetadataItem, MinecraftVersionMetadataItem, MojangJavaRuntimeComponentMetadataItem, MojangJavaRuntimesMetadataItem, NeoforgeInstallerMavenMetadataItem}, manager::{
+    account::MinecraftLoginInfo, asset_journal::AssetJournal, directories::LauncherDirectories, download_metrics::InFlightDownload, forge_processors::{self, ForgeProcessorError}, host_arch, launch_wrapper, natives_substitution, metadata::{items::{AssetsIndexMetadataItem, FabricLaunchMetadataItem, FabricLoaderManifestMetadataItem, ForgeInstallerMavenMetadataItem, MinecraftVersionManifestMetadataItem, MinecraftVersionMetadataItem, MojangJavaRuntimeComponentMetadataItem, MojangJavaRuntimesMetadataItem, NeoforgeInstallerMavenMetadataItem}, manager::{
         MetaLoadError, MetadataManager,
     }}
 };
@@ -55,12 +55,42 @@ pub enum LaunchError {
     CantFindVersion(&'static str),
     #[error("Invalid instance name: {0}")]
     InvalidInstanceName(&'static str),
-    #[error("Error running forge post processor")]
-    ForgePostProcessorError,
+    #[error("Error running forge post processor:\n{0}")]
+    ForgePostProcessorError(#[from] ForgeProcessorError),
     #[error("Cancelled by user")]
     CancelledByUser,
     #[error("Loader supports the wrong version of Minecraft: {0}")]
     MismatchedLoaderVersions(Arc<str>),
+    #[error("Dedicated servers are not supported for the {0} loader")]
+    UnsupportedServerLoader(&'static str),
+    #[error("Minecraft {0} has no dedicated server download")]
+    MissingServerDownload(&'static str),
+    #[error("The Minecraft EULA must be accepted before starting the server")]
+    EulaNotAccepted,
+    #[error("This version of Minecraft doesn't support joining Realms directly")]
+    QuickPlayRealmsUnsupported,
+    #[error("Multiplayer is disabled for this instance by its network isolation settings")]
+    NetworkIsolated,
+}
+
+// Everything needed to start the game client once its files have been downloaded and verified
+struct PreparedClient {
+    version_info: Arc<MinecraftVersion>,
+    java_path: PathBuf,
+    natives_dir: PathBuf,
+    assets_index_name: String,
+    game_assets: Arc<Path>,
+    classpath: Vec<OsString>,
+    log_configuration: Option<OsString>,
+    log4j_mitigation: Option<Log4jMitigation>,
+    rule_context: LaunchRuleContext,
+}
+
+struct PreparedServer {
+    java_path: PathBuf,
+    server_jar: PathBuf,
+    log_configuration: Option<OsString>,
+    log4j_mitigation: Option<Log4jMitigation>,
 }
 
 #[derive(PartialEq, Eq)]
@@ -88,41 +118,199 @@ impl Launcher {
         quick_play: Option<QuickPlayLaunch>,
         login_info: MinecraftLoginInfo,
         add_mods: Vec<PathBuf>,
+        natives_repositories: &[Arc<str>],
         launch_tracker: &ProgressTracker,
         modal_action: &ModalAction,
-    ) -> Result<Child, LaunchError> {
+    ) -> Result<(Child, Option<Log4jMitigation>), LaunchError> {
         log::info!("Launching {:?}", dot_minecraft_path);
 
         launch_tracker.set_total(6);
 
+        let custom_resolution = instance_info.resolution
+            .filter(|resolution| resolution.enabled && resolution.width > 0 && resolution.height > 0)
+            .map(|resolution| (resolution.width, resolution.height));
+
+        let prepared = self.prepare_client(
+            http_client,
+            &dot_minecraft_path,
+            &instance_info,
+            quick_play,
+            custom_resolution,
+            natives_repositories,
+            launch_tracker,
+            modal_action,
+        ).await?;
+
+        let (launch_context, version_info) = self.create_launch_context(dot_minecraft_path, instance_info, prepared, login_info, add_mods);
+
+        if modal_action.has_requested_cancel() {
+            self.sender.send(MessageToFrontend::CloseModal);
+            return Err(LaunchError::CancelledByUser);
+        }
+
+        let log4j_mitigation = launch_context.log4j_mitigation;
+
+        log::info!("Launching game process");
+        let child = launch_context.launch(&version_info)?;
+
+        launch_tracker.add_count(1);
+
+        Ok((child, log4j_mitigation))
+    }
+
+    // Resolves the command that launching would run without starting the game. Files are downloaded like a normal
+    // launch, but content that is only added when launching (e.g. synced mods) isn't included
+    pub async fn launch_command(
+        &self,
+        http_client: &reqwest::Client,
+        dot_minecraft_path: Arc<Path>,
+        instance_info: InstanceConfiguration,
+        login_info: MinecraftLoginInfo,
+        redact_access_token: bool,
+        natives_repositories: &[Arc<str>],
+        launch_tracker: &ProgressTracker,
+        modal_action: &ModalAction,
+    ) -> Result<LaunchCommand, LaunchError> {
+        log::info!("Resolving launch command for {:?}", dot_minecraft_path);
+
+        launch_tracker.set_total(5);
+
+        let custom_resolution = instance_info.resolution
+            .filter(|resolution| resolution.enabled && resolution.width > 0 && resolution.height > 0)
+            .map(|resolution| (resolution.width, resolution.height));
+
+        let prepared = self.prepare_client(
+            http_client,
+            &dot_minecraft_path,
+            &instance_info,
+            None,
+            custom_resolution,
+            natives_repositories,
+            launch_tracker,
+            modal_action,
+        ).await?;
+
+        let (mut launch_context, version_info) = self.create_launch_context(dot_minecraft_path, instance_info, prepared, login_info, Vec::new());
+        launch_context.redact_access_token = redact_access_token;
+
+        Ok(launch_context.into_command(&version_info))
+    }
+
+    fn create_launch_context(
+        &self,
+        dot_minecraft_path: Arc<Path>,
+        instance_info: InstanceConfiguration,
+        prepared: PreparedClient,
+        login_info: MinecraftLoginInfo,
+        add_mods: Vec<PathBuf>,
+    ) -> (LaunchContext, Arc<MinecraftVersion>) {
+        let launch_context = LaunchContext {
+            launch_wrapper_path: self.launch_wrapper.clone(),
+            java_path: prepared.java_path,
+            natives_dir: prepared.natives_dir,
+            libraries_dir: self.directories.libraries_dir.clone(),
+            game_dir: dot_minecraft_path,
+            configuration: instance_info,
+            assets_root: self.directories.assets_root_dir.clone(),
+            temp_dir: self.directories.temp_dir.clone(),
+            assets_index_name: prepared.assets_index_name,
+            game_assets: prepared.game_assets,
+            classpath: prepared.classpath,
+            log_configuration: prepared.log_configuration,
+            log4j_mitigation: prepared.log4j_mitigation,
+            rule_context: prepared.rule_context,
+            login_info,
+            redact_access_token: false,
+            add_mods,
+            version_type: prepared.version_info.r#type.clone(),
+        };
+        (launch_context, prepared.version_info)
+    }
+
+    // Downloads, verifies and extracts everything needed to start the instance without actually starting it,
+    // so that it can be played without an internet connection later
+    pub async fn prepare(
+        &self,
+        http_client: &reqwest::Client,
+        dot_minecraft_path: Arc<Path>,
+        instance_info: InstanceConfiguration,
+        natives_repositories: &[Arc<str>],
+        prepare_tracker: &ProgressTracker,
+        modal_action: &ModalAction,
+    ) -> Result<(), LaunchError> {
+        log::info!("Preparing {:?}", dot_minecraft_path);
+
+        if instance_info.instance_type == InstanceType::Server {
+            prepare_tracker.set_total(2);
+            self.prepare_server(http_client, &dot_minecraft_path, &instance_info, prepare_tracker, modal_action).await?;
+        } else {
+            prepare_tracker.set_total(5);
+            self.prepare_client(
+                http_client,
+                &dot_minecraft_path,
+                &instance_info,
+                None,
+                None,
+                natives_repositories,
+                prepare_tracker,
+                modal_action,
+            ).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn prepare_client(
+        &self,
+        http_client: &reqwest::Client,
+        dot_minecraft_path: &Arc<Path>,
+        instance_info: &InstanceConfiguration,
+        quick_play: Option<QuickPlayLaunch>,
+        custom_resolution: Option<(u32, u32)>,
+        natives_repositories: &[Arc<str>],
+        launch_tracker: &ProgressTracker,
+        modal_action: &ModalAction,
+    ) -> Result<PreparedClient, LaunchError> {
         log::debug!("Creating launch version");
 
+        let prepare_start = Instant::now();
         let (version_info, add_vanilla_jar) = tokio::select! {
-            result = self.create_launch_version(http_client, &modal_action.trackers, launch_tracker, &instance_info) => result?,
+            result = timed("Creating launch version", self.create_launch_version(http_client, launch_tracker, instance_info)) => result?,
             _ = modal_action.request_cancel.cancelled() => {
                 self.sender.send(MessageToFrontend::CloseModal);
                 return Err(LaunchError::CancelledByUser);
             }
         };
 
+        if matches!(quick_play, Some(QuickPlayLaunch::Realms(_))) && !supports_quick_play_realms(&version_info) {
+            return Err(LaunchError::QuickPlayRealmsUnsupported);
+        }
+        if matches!(quick_play, Some(QuickPlayLaunch::Multiplayer(_) | QuickPlayLaunch::Realms(_)))
+            && instance_info.network_isolation.is_some_and(|network_isolation| network_isolation.is_enabled())
+        {
+            return Err(LaunchError::NetworkIsolated);
+        }
+
         launch_tracker.add_count(1);
         launch_tracker.notify();
 
-        let _ = std::fs::create_dir_all(&dot_minecraft_path);
+        let _ = std::fs::create_dir_all(dot_minecraft_path);
 
         let launch_rule_context = LaunchRuleContext {
             is_demo_user: false,
-            custom_resolution: None,
+            custom_resolution,
             quick_play,
+            arch: host_arch::game_arch(&version_info),
         };
 
         let mut artifacts = Vec::new();
         let mut natives_to_extract = HashMap::new();
         launch_rule_context.collect_libraries(&version_info.libraries, &mut artifacts, &mut natives_to_extract);
+        timed("Substituting natives", natives_substitution::substitute_natives(http_client, natives_repositories, &self.directories.libraries_dir, &mut artifacts, &mut natives_to_extract)).await;
 
-        // Compute natives path based on combined hash of all libraries
-        let natives_dir = self.directories.temp_natives_base_dir.join(calculate_natives_dirname(&artifacts));
-        let _ = std::fs::create_dir_all(&natives_dir);
+        // Extracted natives are reused by every launch of the same version with the same libraries
+        let natives_dirname = calculate_natives_dirname(&version_info.id, launch_rule_context.arch, &artifacts);
+        let natives_dir = self.directories.temp_natives_base_dir.join(&natives_dirname);
 
         if add_vanilla_jar == AddVanillaJar::Yes {
             let client_download = &version_info.downloads.client;
@@ -137,27 +325,53 @@ impl Launcher {
         let mojang_java_binary_future = self.load_mojang_java_binary(
             &self.meta,
             http_client,
-            &instance_info,
+            instance_info,
+            Some(&**dot_minecraft_path),
             &version_info,
-            &modal_action.trackers,
             launch_tracker,
         );
-        let load_assets_future =
-            self.load_assets(&self.meta, http_client, &dot_minecraft_path, &version_info, &modal_action.trackers, launch_tracker);
-        let load_libraries_future =
-            self.load_libraries(http_client, &artifacts, &modal_action.trackers, launch_tracker);
-        let load_log_configuration = self.load_log_configuration(http_client, version_info.logging.as_ref());
+        let asset_counts = FileVerifyCounts::default();
+        let library_counts = FileVerifyCounts::default();
+        let load_assets_future = self.load_assets(
+            &self.meta,
+            http_client,
+            dot_minecraft_path,
+            &version_info,
+            launch_tracker,
+            &asset_counts,
+            true,
+        );
+        // Natives are extracted as soon as the libraries are ready, while the assets and java may still be loading
+        let load_libraries_future = async {
+            let library_paths = self.load_libraries(http_client, &artifacts, launch_tracker, &library_counts).await?;
+
+            let natives_base_dir = self.directories.temp_natives_base_dir.clone();
+            let natives_dir = natives_dir.clone();
+            let natives_dirname = natives_dirname.clone();
+            let natives_to_extract = std::mem::take(&mut natives_to_extract);
+            let classpath = timed("Extracting natives", tokio::task::spawn_blocking(move || {
+                extract_natives_and_collect_classpath(library_paths, &natives_to_extract, &natives_base_dir, &natives_dir, &natives_dirname)
+            })).await.unwrap();
+            Ok::<_, LoadLibrariesError>(classpath)
+        };
+        let log4j_mitigation = detect_log4j_mitigation(&version_info.libraries);
+        let load_log_configuration = self.load_log_configuration(
+            http_client,
+            version_info.logging.as_ref(),
+            log4j_mitigation,
+            log4j_mitigation.and_then(Log4jMitigation::client_configuration),
+        );
 
         log::debug!("Loading java, assets, libraries and log configuration");
 
         let joined = futures::future::try_join4(
-            mojang_java_binary_future.map_err(LaunchError::from),
-            load_assets_future.map_err(LaunchError::from),
-            load_libraries_future.map_err(LaunchError::from),
-            load_log_configuration.map(Ok),
+            timed("Loading java", mojang_java_binary_future.map_err(LaunchError::from)),
+            timed("Loading assets", load_assets_future.map_err(LaunchError::from)),
+            timed("Loading libraries and natives", load_libraries_future.map_err(LaunchError::from)),
+            timed("Loading log configuration", load_log_configuration.map(Ok)),
         );
 
-        let (java_path, assets_index_name, library_paths, log_configuration) = tokio::select! {
+        let (java_path, (assets_index_name, game_assets), classpath, (log_configuration, log4j_mitigation)) = tokio::select! {
             result = joined => result?,
             _ = modal_action.request_cancel.cancelled() => {
                 self.sender.send(MessageToFrontend::CloseModal);
@@ -168,86 +382,249 @@ impl Launcher {
         launch_tracker.add_count(1);
         launch_tracker.notify();
 
-        let mut classpath = Vec::new();
-        for (raw_path, library_path) in library_paths {
-            if let Some(extract_options) = natives_to_extract.get(&raw_path) {
-                let Ok(file) = std::fs::File::open(library_path) else {
-                    continue;
-                };
-                let Ok(archive) = file.read_zip() else {
-                    continue;
-                };
-                for file in archive.entries() {
-                    let Some(path) = SafePath::new(&file.name) else {
-                        continue;
-                    };
-
-                    if let Some(exclude) = &extract_options.exclude {
-                        let mut skip = false;
-                        for to_exclude in exclude.iter() {
-                            if path.starts_with(to_exclude) {
-                                skip = true;
-                                break;
-                            }
-                        }
-                        if skip {
-                            continue;
-                        }
-                    }
-
-                    let output_path = path.to_path(&natives_dir);
-                    match file.kind() {
-                        rc_zip_sync::rc_zip::EntryKind::Directory => {
-                            let _ = std::fs::create_dir(output_path);
-                        },
-                        rc_zip_sync::rc_zip::EntryKind::File => {
-                            let Ok(mut outfile) = std::fs::File::create(&output_path) else {
-                                continue;
-                            };
-                            let _ = std::io::copy(&mut file.reader(), &mut outfile);
-                        },
-                        rc_zip_sync::rc_zip::EntryKind::Symlink => {},
-                    }
-                }
-            } else {
-                classpath.push(library_path.into_os_string());
-            }
+        let repaired = asset_counts.repaired() + library_counts.repaired();
+        if repaired > 0 {
+            log::info!("Downloaded {} missing or corrupt game files", repaired);
         }
 
-        let launch_context = LaunchContext {
-            launch_wrapper_path: self.launch_wrapper.clone(),
+        log::debug!("Preparing launch took {:?}", prepare_start.elapsed());
+
+        Ok(PreparedClient {
+            version_info,
             java_path,
             natives_dir,
-            libraries_dir: self.directories.libraries_dir.clone(),
-            game_dir: dot_minecraft_path,
-            configuration: instance_info,
-            assets_root: self.directories.assets_root_dir.clone(),
-            temp_dir: self.directories.temp_dir.clone(),
             assets_index_name,
+            game_assets,
             classpath,
             log_configuration,
+            log4j_mitigation,
             rule_context: launch_rule_context,
-            login_info,
-            add_mods
+        })
+    }
+
+    pub async fn verify(
+        &self,
+        http_client: &reqwest::Client,
+        dot_minecraft_path: Arc<Path>,
+        instance_info: InstanceConfiguration,
+        natives_repositories: &[Arc<str>],
+        verify_tracker: &ProgressTracker,
+        modal_action: &ModalAction,
+    ) -> Result<(FileVerifyCounts, FileVerifyCounts), LaunchError> {
+        log::info!("Verifying game files for {:?}", dot_minecraft_path);
+
+        verify_tracker.set_total(3);
+
+        let (version_info, add_vanilla_jar) = tokio::select! {
+            result = self.create_launch_version(http_client, verify_tracker, &instance_info) => result?,
+            _ = modal_action.request_cancel.cancelled() => {
+                return Err(LaunchError::CancelledByUser);
+            }
         };
 
-        if modal_action.has_requested_cancel() {
-            self.sender.send(MessageToFrontend::CloseModal);
-            return Err(LaunchError::CancelledByUser);
+        verify_tracker.add_count(1);
+        verify_tracker.notify();
+
+        let launch_rule_context = LaunchRuleContext {
+            is_demo_user: false,
+            custom_resolution: None,
+            quick_play: None,
+            arch: host_arch::game_arch(&version_info),
+        };
+
+        let mut artifacts = Vec::new();
+        let mut natives_to_extract = HashMap::new();
+        launch_rule_context.collect_libraries(&version_info.libraries, &mut artifacts, &mut natives_to_extract);
+        natives_substitution::substitute_natives(http_client, natives_repositories, &self.directories.libraries_dir, &mut artifacts, &mut natives_to_extract).await;
+
+        if add_vanilla_jar == AddVanillaJar::Yes {
+            let client_download = &version_info.downloads.client;
+            artifacts.push(GameLibraryArtifact {
+                path: format!("net/minecraft/{0}/minecraft-client-{0}.jar", instance_info.minecraft_version).into(),
+                sha1: Some(client_download.sha1),
+                size: Some(client_download.size),
+                url: client_download.url,
+            });
         }
 
-        log::info!("Launching game process");
-        let child = launch_context.launch(&version_info)?;
+        let asset_counts = FileVerifyCounts::default();
+        let library_counts = FileVerifyCounts::default();
+        let load_assets_future = self.load_assets(
+            &self.meta,
+            http_client,
+            &dot_minecraft_path,
+            &version_info,
+            verify_tracker,
+            &asset_counts,
+            false,
+        );
+        let load_libraries_future =
+            self.load_libraries(http_client, &artifacts, verify_tracker, &library_counts);
+
+        let joined = futures::future::try_join(
+            load_assets_future.map_err(LaunchError::from),
+            load_libraries_future.map_err(LaunchError::from),
+        );
+
+        tokio::select! {
+            result = joined => { result?; },
+            _ = modal_action.request_cancel.cancelled() => {
+                return Err(LaunchError::CancelledByUser);
+            }
+        };
+
+        Ok((asset_counts, library_counts))
+    }
+
+    pub async fn launch_server(
+        &self,
+        http_client: &reqwest::Client,
+        dot_minecraft_path: Arc<Path>,
+        instance_info: InstanceConfiguration,
+        launch_tracker: &ProgressTracker,
+        modal_action: &ModalAction,
+    ) -> Result<(Child, Option<Log4jMitigation>), LaunchError> {
+        log::info!("Launching server {:?}", dot_minecraft_path);
+
+        if !crate::server_properties::is_eula_accepted(&dot_minecraft_path) {
+            return Err(LaunchError::EulaNotAccepted);
+        }
+
+        launch_tracker.set_total(3);
+
+        let prepared = self.prepare_server(http_client, &dot_minecraft_path, &instance_info, launch_tracker, modal_action).await?;
+
+        let mut command = std::process::Command::new(&*prepared.java_path);
+        command.current_dir(&dot_minecraft_path);
+        command.stdin(Stdio::piped());
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::piped());
+
+        if let Some(memory) = &instance_info.memory && memory.enabled {
+            command.arg(format!("-Xms{}m", memory.min));
+            command.arg(format!("-Xmx{}m", memory.max.max(memory.min).max(128)));
+        }
+        if let Some(jvm_flags) = &instance_info.jvm_flags && jvm_flags.enabled {
+            if let Ok(split) = shell_words::split(&jvm_flags.flags) {
+                command.args(split);
+            } else {
+                command.args(jvm_flags.flags.split_whitespace());
+            }
+        }
+
+        if let Some(log_configuration) = prepared.log_configuration {
+            command.arg(log_configuration);
+        }
+        if let Some(Log4jMitigation::FormatMsgNoLookups) = prepared.log4j_mitigation {
+            command.arg(LOG4J_FORMAT_MSG_NO_LOOKUPS_ARGUMENT);
+        }
+
+        command.arg("-jar");
+        command.arg(prepared.server_jar);
+        command.arg("nogui");
+
+        log::info!("Launching server process");
+        let child = command.spawn()?;
 
         launch_tracker.add_count(1);
 
-        Ok(child)
+        Ok((child, prepared.log4j_mitigation))
+    }
+
+    async fn prepare_server(
+        &self,
+        http_client: &reqwest::Client,
+        dot_minecraft_path: &Arc<Path>,
+        instance_info: &InstanceConfiguration,
+        launch_tracker: &ProgressTracker,
+        modal_action: &ModalAction,
+    ) -> Result<PreparedServer, LaunchError> {
+        if instance_info.loader != Loader::Vanilla {
+            return Err(LaunchError::UnsupportedServerLoader(instance_info.loader.name()));
+        }
+
+        let (version_info, _) = tokio::select! {
+            result = self.create_launch_version(http_client, launch_tracker, instance_info) => result?,
+            _ = modal_action.request_cancel.cancelled() => {
+                self.sender.send(MessageToFrontend::CloseModal);
+                return Err(LaunchError::CancelledByUser);
+            }
+        };
+
+        launch_tracker.add_count(1);
+        launch_tracker.notify();
+
+        let Some(server_download) = &version_info.downloads.server else {
+            return Err(LaunchError::MissingServerDownload(instance_info.minecraft_version.as_str()));
+        };
+
+        let _ = std::fs::create_dir_all(dot_minecraft_path);
+
+        let artifacts = [GameLibraryArtifact {
+            path: format!("net/minecraft/{0}/minecraft-server-{0}.jar", instance_info.minecraft_version).into(),
+            sha1: Some(server_download.sha1),
+            size: Some(server_download.size),
+            url: server_download.url,
+        }];
+
+        let mojang_java_binary_future = self.load_mojang_java_binary(
+            &self.meta,
+            http_client,
+            instance_info,
+            Some(&**dot_minecraft_path),
+            &version_info,
+            launch_tracker,
+        );
+        let library_counts = FileVerifyCounts::default();
+        let load_libraries_future =
+            self.load_libraries(http_client, &artifacts, launch_tracker, &library_counts);
+
+        // The logging configuration of the version is only meant for the client
+        let log4j_mitigation = detect_log4j_mitigation(&version_info.libraries);
+        let load_log_configuration = self.load_log_configuration(
+            http_client,
+            None,
+            log4j_mitigation,
+            log4j_mitigation.and_then(Log4jMitigation::server_configuration),
+        );
+
+        let joined = futures::future::try_join3(
+            mojang_java_binary_future.map_err(LaunchError::from),
+            load_libraries_future.map_err(LaunchError::from),
+            load_log_configuration.map(Ok),
+        );
+
+        let (java_path, library_paths, (log_configuration, log4j_mitigation)) = tokio::select! {
+            result = joined => result?,
+            _ = modal_action.request_cancel.cancelled() => {
+                self.sender.send(MessageToFrontend::CloseModal);
+                return Err(LaunchError::CancelledByUser);
+            }
+        };
+
+        launch_tracker.add_count(1);
+        launch_tracker.notify();
+
+        let Some((_, server_jar)) = library_paths.into_iter().next() else {
+            return Err(LaunchError::MissingServerDownload(instance_info.minecraft_version.as_str()));
+        };
+
+        if modal_action.has_requested_cancel() {
+            self.sender.send(MessageToFrontend::CloseModal);
+            return Err(LaunchError::CancelledByUser);
+        }
+
+        Ok(PreparedServer {
+            java_path,
+            server_jar,
+            log_configuration,
+            log4j_mitigation,
+        })
     }
 
     async fn create_launch_version(
         &self,
         http_client: &reqwest::Client,
-        progress_trackers: &ProgressTrackers,
         launch_tracker: &ProgressTracker,
         instance_info: &InstanceConfiguration,
     ) -> Result<(Arc<MinecraftVersion>, AddVanillaJar), LaunchError> {
@@ -404,7 +781,7 @@ impl Launcher {
                     self.meta.fetch(&ForgeInstallerMavenMetadataItem)
                 ).await?;
 
-                self.create_forgelike_launch_version(http_client, progress_trackers, launch_tracker, instance_info,
+                self.create_forgelike_launch_version(http_client, launch_tracker, instance_info,
                     minecraft_versions,
                     &loader_versions.0,
                     "https://maven.minecraftforge.net/net/minecraftforge/forge/{0}/forge-{0}-installer.jar.sha1",
@@ -424,7 +801,7 @@ impl Launcher {
                     self.meta.fetch(&NeoforgeInstallerMavenMetadataItem)
                 ).await?;
 
-                self.create_forgelike_launch_version(http_client, progress_trackers, launch_tracker, instance_info,
+                self.create_forgelike_launch_version(http_client, launch_tracker, instance_info,
                     minecraft_versions,
                     &loader_versions.0,
                     "https://maven.neoforged.net/releases/net/neoforged/neoforge/{0}/neoforge-{0}-installer.jar.sha1",
@@ -441,7 +818,6 @@ impl Launcher {
     async fn create_forgelike_launch_version(
         &self,
         http_client: &reqwest::Client,
-        progress_trackers: &ProgressTrackers,
         launch_tracker: &ProgressTracker,
         instance_info: &InstanceConfiguration,
         minecraft_versions: Arc<MinecraftVersionManifest>,
@@ -462,37 +838,8 @@ impl Launcher {
         let loader_version = if let Some(preferred_loader_version) = instance_info.preferred_loader_version {
             preferred_loader_version
         } else {
-            let mut minecraft_version_parts = VersionFragment::string_to_parts(instance_info.minecraft_version.as_str());
-            if neoforge_versioning {
-                // 1.21.5 -> 21.5
-                // 25w14craftmine -> 0.25w14craftmine
-                // 1.21 -> 21.0
-                // 26.1 -> 26.1.0
-                if minecraft_version_parts[0] == VersionFragment::String("25w14craftmine".into()) {
-                    minecraft_version_parts.insert(0, VersionFragment::Number(0))
-                } else {
-                    if minecraft_version_parts.len() < 3 {
-                        minecraft_version_parts.push(VersionFragment::Number(0))
-                    }
-                    if minecraft_version_parts[0] == VersionFragment::Number(1) {
-                        minecraft_version_parts.remove(0);
-                    }
-                }
-            }
-
-            let mut latest_loader_version = None;
-            let mut latest_loader_version_parts = Vec::new();
-            for version in loader_versions.iter() {
-                let parts = VersionFragment::string_to_parts(version);
-
-                if parts.starts_with(&minecraft_version_parts) {
-                    if parts > latest_loader_version_parts {
-                        latest_loader_version_parts = parts;
-                        latest_loader_version = Some(version.clone());
-                    }
-                }
-            }
-            let Some(latest_loader_version) = latest_loader_version else {
+            let versions = schema::forge::loader_versions_for_minecraft(loader_versions, instance_info.minecraft_version.as_str(), neoforge_versioning);
+            let Some(latest_loader_version) = versions.first().copied() else {
                 return Err(LaunchError::CantFindVersion(instance_info.minecraft_version.as_str()));
             };
 
@@ -530,11 +877,13 @@ impl Launcher {
             &self.meta,
             http_client,
             instance_info,
+            None,
             &base_version,
-            progress_trackers,
             launch_tracker,
         );
-        let load_installer_library_future = self.load_libraries(http_client, artifacts, progress_trackers, launch_tracker);
+        let installer_library_counts = FileVerifyCounts::default();
+        let load_installer_library_future =
+            self.load_libraries(http_client, artifacts, launch_tracker, &installer_library_counts);
 
         let (artifact_load_result, java_load_result) = futures::future::try_join(
             load_installer_library_future.map_err(LaunchError::from),
@@ -560,14 +909,14 @@ impl Launcher {
             if let Ok(install_profile_legacy) = serde_json::from_slice(&install_profile_bytes) {
                 launch_tracker.add_count(1);
                 let ret = self.create_forgelike_install_version_legacy(install_profile_legacy, installer_zip,
-                    base_version, http_client, progress_trackers, launch_tracker, instance_info, check_mirrors).await;
+                    base_version, http_client, launch_tracker, instance_info, check_mirrors).await;
                 return ret;
             }
         }
 
         self.create_forgelike_install_version_modern(install_profile?, installer_zip,
             installer_path, minecraft_jar_path, &java_load_result, base_version, http_client,
-            progress_trackers, launch_tracker, instance_info, check_mirrors).await
+            launch_tracker, instance_info, check_mirrors).await
     }
 
     async fn create_forgelike_install_version_modern(
@@ -579,7 +928,6 @@ impl Launcher {
         java_path: &PathBuf,
         base_version: Arc<MinecraftVersion>,
         http_client: &reqwest::Client,
-        progress_trackers: &ProgressTrackers,
         launch_tracker: &ProgressTracker,
         instance_info: &InstanceConfiguration,
         check_mirrors: bool,
@@ -619,7 +967,7 @@ impl Launcher {
             Some(artifact)
         }).collect::<Vec<_>>();
 
-        self.load_libraries(http_client, &libraries, progress_trackers, launch_tracker).await?;
+        self.load_libraries(http_client, &libraries, launch_tracker, &FileVerifyCounts::default()).await?;
 
         let forge_temp = self.directories.temp_dir.join("forge_installer");
 
@@ -669,12 +1017,14 @@ impl Launcher {
         data.insert("INSTALLER".into(), installer_path.as_os_str().to_os_string());
         data.insert("LIBRARY_DIR".into(), self.directories.libraries_dir.as_os_str().to_os_string());
 
-        let processor_tracker = ProgressTracker::new("Forge Post Processors".into(), self.sender.clone());
-        progress_trackers.push(processor_tracker.clone());
+        let processor_tracker = launch_tracker.child("Forge Post Processors".into());
 
         processor_tracker.set_total(install_profile.processors.len());
         processor_tracker.notify();
 
+        let processor_cache_dir = self.directories.libraries_dir.join(".processor_cache");
+        let mut jobs = Vec::new();
+
         for processor in install_profile.processors.iter() {
             if let Some(sides) = &processor.sides {
                 if !sides.iter().any(|side| *side == ForgeSide::Client) {
@@ -687,14 +1037,6 @@ impl Launcher {
 
             let jar = MavenCoordinate::create(&processor.jar);
 
-            // Check if the output already exists and the step can be skipped
-            let skip = self.can_skip_forge_processor(&jar, processor, &data);
-            if skip {
-                processor_tracker.add_count(1);
-                processor_tracker.notify();
-                continue;
-            }
-
             let relative_jar_path = jar.artifact_path();
 
             let Some(safe_jar_path) = SafePath::new(&relative_jar_path) else {
@@ -733,22 +1075,27 @@ impl Launcher {
                 continue;
             };
 
-            let mut command = std::process::Command::new(java_path);
-
-            command.current_dir(&forge_temp);
-            command.stdin(Stdio::inherit());
-            command.stdout(Stdio::inherit());
-            command.stderr(Stdio::inherit());
+            let mut args = Vec::new();
+            let mut paths = Vec::new();
+            let mut outputs = Vec::new();
 
-            command.arg("-cp");
-            command.arg(std::env::join_paths(processor.classpath.iter().map(|f| {
+            args.push(OsString::from("-cp"));
+            args.push(std::env::join_paths(processor.classpath.iter().map(|f| {
                 let artifact = MavenCoordinate::create(&**f);
                 self.directories.libraries_dir.join(artifact.artifact_path()).into_os_string()
             }).chain(std::iter::once(jar_path.into_os_string()))).unwrap());
 
-            command.arg(main_class);
+            args.push(OsString::from(main_class));
 
+            let mut last_flag = None;
             for arg in processor.args.iter() {
+                if arg.starts_with("--") {
+                    last_flag = Some(arg.as_str());
+                    args.push(OsString::from(arg.as_str()));
+                    continue;
+                }
+                let flag = last_flag.take();
+
                 let expanded = if arg.starts_with('[') && arg.ends_with(']') {
                     let artifact = MavenCoordinate::create(&arg[1..arg.len()-1]);
                     let artifact_path = artifact.artifact_path();
@@ -764,20 +1111,51 @@ impl Launcher {
                 } else {
                     expand_forge_argument(&arg, &data)
                 };
-                command.arg(expanded);
-            }
 
-            let mut child = command.spawn()?;
-            let exit_code = child.wait()?;
+                let path = Path::new(&*expanded);
+                if path.is_absolute() && path != &*self.directories.libraries_dir {
+                    paths.push(path.to_path_buf());
+                    if flag.is_some_and(forge_processors::is_output_flag) {
+                        outputs.push(path.to_path_buf());
+                    }
+                }
 
-            if !exit_code.success() {
-                return Err(LaunchError::ForgePostProcessorError);
+                args.push(expanded.into_owned());
             }
 
-            processor_tracker.add_count(1);
-            processor_tracker.notify();
+            if let Some(processor_outputs) = &processor.outputs {
+                for key in processor_outputs.keys() {
+                    let path = PathBuf::from(expand_forge_argument(key, &data).into_owned());
+                    paths.push(path.clone());
+                    outputs.push(path);
+                }
+            }
+
+            forge_processors::dedup_paths(&mut paths);
+            forge_processors::dedup_paths(&mut outputs);
+
+            let cache_key = forge_processors::create_cache_key(installer_path, &install_profile.minecraft,
+                &processor.jar, &processor.classpath, &args);
+
+            // Check if the output already exists and the step can be skipped
+            let up_to_date = self.can_skip_forge_processor(&jar, processor, &data)
+                || forge_processors::is_cached(&processor_cache_dir, &cache_key);
+
+            jobs.push(forge_processors::ForgeProcessorJob {
+                name: jar.artifact_id.to_string(),
+                java_path: java_path.clone(),
+                working_dir: forge_temp.clone(),
+                args,
+                outputs,
+                paths,
+                cache_key,
+                up_to_date,
+            });
         }
 
+        let log_dir = forge_temp.join("logs");
+        forge_processors::run_processors(jobs, &processor_cache_dir, &log_dir, &processor_tracker).await?;
+
         processor_tracker.set_finished(ProgressTrackerFinishType::Normal);
 
         launch_tracker.add_count(1);
@@ -792,7 +1170,6 @@ impl Launcher {
         installer_zip: ArchiveHandle<'_, File>,
         base_version: Arc<MinecraftVersion>,
         http_client: &reqwest::Client,
-        progress_trackers: &ProgressTrackers,
         launch_tracker: &ProgressTracker,
         instance_info: &InstanceConfiguration,
         check_mirrors: bool,
@@ -837,13 +1214,13 @@ impl Launcher {
                 Some(artifact)
             }).collect::<Vec<_>>();
 
-            self.load_libraries(http_client, &libraries, progress_trackers, launch_tracker).await?;
+            self.load_libraries(http_client, &libraries, launch_tracker, &FileVerifyCounts::default()).await?;
         }
 
         Ok((Arc::new(version.apply_to(&base_version)), AddVanillaJar::Yes))
     }
 
-    async fn download_sha1(http_client: &reqwest::Client, url: &str) -> Option<Ustr> {
+    pub(crate) async fn download_sha1(http_client: &reqwest::Client, url: &str) -> Option<Ustr> {
         let response = http_client
             .get(url)
             .send().await.ok()?;
@@ -881,13 +1258,17 @@ impl Launcher {
         meta: &MetadataManager,
         http_client: &reqwest::Client,
         configuration: &InstanceConfiguration,
+        // Where the instance is, used to record which instances use the runtime. Not set for loader installers
+        dot_minecraft_path: Option<&Path>,
         version_info: &MinecraftVersion,
-        progress_trackers: &ProgressTrackers,
         launch_tracker: &ProgressTracker,
     ) -> Result<PathBuf, LoadJavaRuntimeError> {
         if let Some(jvm_binary) = &configuration.jvm_binary {
             if jvm_binary.enabled && let Some(path) = &jvm_binary.path {
                 if let Some(binary) = Self::search_for_java_binary(&path) {
+                    if host_arch::host_arch() == "aarch64" {
+                        self.warn_if_java_arch_mismatch(&binary, host_arch::game_arch(version_info));
+                    }
                     return Ok(binary);
                 }
             }
@@ -923,7 +1304,16 @@ impl Launcher {
             return Err(LoadJavaRuntimeError::UnableToFindExternalBinary(needed_version, found_versions.into_iter().collect()));
         }
 
-        let mut platform: Ustr = match (std::env::consts::OS, std::env::consts::ARCH) {
+        let game_arch = host_arch::game_arch(version_info);
+        if game_arch != host_arch::host_arch() {
+            self.sender.send_warning(format!(
+                "Minecraft {} doesn't support {} natively, running under x64 emulation",
+                configuration.minecraft_version,
+                host_arch::host_arch()
+            ));
+        }
+
+        let mut platform: Ustr = match (std::env::consts::OS, game_arch) {
             ("linux", "x86_64") => "linux".into(),
             ("linux", "x86") => "linux-i386".into(),
             ("macos", "x86_64") => "mac-os".into(),
@@ -946,10 +1336,17 @@ impl Launcher {
         let mut runtime_platform = runtimes.platforms.get(&platform).ok_or(LoadJavaRuntimeError::UnknownPlatform)?;
         let mut runtime_components = runtime_platform.components.get(&jre_component);
 
-        // Fall back to x86 runtime on mac-os, since Rosetta exists
+        // Fall back to x64 runtime on mac-os and windows, since Rosetta and Prism exist
         let missing_runtime_component = runtime_components.map(Vec::is_empty).unwrap_or(true);
-        if missing_runtime_component && platform == "mac-os-arm64" {
-            platform = "mac-os".into();
+        let emulated_platform = match platform.as_str() {
+            "mac-os-arm64" => Some("mac-os"),
+            "windows-arm64" => Some("windows-x64"),
+            _ => None,
+        };
+        if missing_runtime_component && let Some(emulated_platform) = emulated_platform {
+            log::warn!("No {} runtime for {}, falling back to {}", jre_component, platform, emulated_platform);
+            self.sender.send_warning(format!("No native Java runtime available for {}, running under x64 emulation", platform));
+            platform = emulated_platform.into();
             runtime_platform = runtimes.platforms.get(&platform).ok_or(LoadJavaRuntimeError::UnknownPlatform)?;
             runtime_components = runtime_platform.components.get(&jre_component);
         }
@@ -966,14 +1363,19 @@ impl Launcher {
             return Err(LoadJavaRuntimeError::InvalidComponentPath);
         }
 
+        // Can wait for a cleanup which is removing runtimes right now
+        let _preparing = tokio::task::spawn_blocking(move || {
+            crate::java_runtimes::start_preparing_runtime(&jre_component, &platform)
+        }).await.unwrap();
+
         let runtime_component_dir = self.directories.runtime_base_dir.join(jre_component).join(platform);
+        // Existing files are verified against the manifest hashes and reused, only missing or changed files are downloaded
+        let fresh_install = !runtime_component_dir.exists();
         let _ = std::fs::create_dir_all(&runtime_component_dir);
         let Ok(runtime_component_dir) = runtime_component_dir.canonicalize() else {
             return Err(LoadJavaRuntimeError::InvalidComponentPath);
         };
 
-        let fresh_install = !runtime_component_dir.exists();
-
         let runtime = meta.fetch(&MojangJavaRuntimeComponentMetadataItem {
             url: runtime_component.manifest.url,
             cache: runtime_component_dir.join("manifest.json").into(),
@@ -986,11 +1388,19 @@ impl Launcher {
             "Verifying integrity of Java Runtime"
         };
 
-        let java_runtime_tracker = ProgressTracker::new(initial_title.into(), self.sender.clone());
-        progress_trackers.push(java_runtime_tracker.clone());
+        let java_runtime_tracker = launch_tracker.child(initial_title.into());
         java_runtime_tracker.notify();
 
-        let result = do_java_runtime_load(http_client, runtime_component_dir, fresh_install, runtime, &java_runtime_tracker).await;
+        let result = do_java_runtime_load(http_client, runtime_component_dir, fresh_install, Arc::clone(&runtime), &java_runtime_tracker).await;
+
+        if result.is_ok() {
+            let runtime_base_dir = self.directories.runtime_base_dir.clone();
+            let version = runtime_component.version.name;
+            let instance = dot_minecraft_path.map(|path| crate::java_runtimes::instance_usage_key(configuration.uuid, path));
+            _ = tokio::task::spawn_blocking(move || {
+                crate::java_runtimes::record_runtime_usage(&runtime_base_dir, &jre_component, &platform, &version, instance, &runtime);
+            }).await;
+        }
 
         java_runtime_tracker.set_finished(ProgressTrackerFinishType::from_err(result.is_err()));
         java_runtime_tracker.notify();
@@ -1007,9 +1417,10 @@ impl Launcher {
         http_client: &reqwest::Client,
         game_dir: &Arc<Path>,
         version_info: &MinecraftVersion,
-        progress_trackers: &ProgressTrackers,
         launch_tracker: &ProgressTracker,
-    ) -> Result<String, LoadAssetObjectsError> {
+        counts: &FileVerifyCounts,
+        trust_journal: bool,
+    ) -> Result<(String, Arc<Path>), LoadAssetObjectsError> {
         let asset_index = format!("{}", version_info.assets);
 
         let assets_index = meta.fetch(&AssetsIndexMetadataItem {
@@ -1019,19 +1430,29 @@ impl Launcher {
         }).await?;
 
         let initial_title = Arc::from("Verifying integrity of game assets");
-        let assets_tracker = ProgressTracker::new(initial_title, self.sender.clone());
-        progress_trackers.push(assets_tracker.clone());
+        let assets_tracker = launch_tracker.child(initial_title);
         assets_tracker.notify();
 
-        let assets_dir = if assets_index.map_to_resources == Some(true) {
-            game_dir.join("resources").into()
-        } else if assets_index.r#virtual == Some(true) {
-            self.directories.assets_root_dir.join("virtual").join("legacy").into()
-        } else {
-            self.directories.assets_objects_dir.clone()
-        };
+        // Old versions read assets by name from a separate directory instead of from the shared objects, 1.5 and
+        // below expect them inside the instance itself
+        let legacy_assets_dir: Option<Arc<Path>> = legacy_assets_dir(&assets_index, &asset_index, game_dir, &self.directories.assets_root_dir)
+            .map(Arc::from);
+
+        let journal = Arc::new(AssetJournal::open(self.directories.assets_index_dir.join(format!("{}.journal", &asset_index)), trust_journal));
 
-        let result = do_asset_objects_load(http_client, assets_index, assets_dir, &assets_tracker).await;
+        let mut result = do_asset_objects_load(http_client, Arc::clone(&assets_index), self.directories.assets_objects_dir.clone(),
+            Some(journal), &assets_tracker, counts).await;
+
+        if result.is_ok() && let Some(legacy_assets_dir) = &legacy_assets_dir {
+            assets_tracker.set_title(Arc::from("Copying legacy game assets"));
+            assets_tracker.notify();
+
+            let objects_dir = self.directories.assets_objects_dir.clone();
+            let legacy_assets_dir = Arc::clone(legacy_assets_dir);
+            result = tokio::task::spawn_blocking(move || {
+                copy_legacy_asset_objects(&assets_index, &objects_dir, &legacy_assets_dir)
+            }).await.unwrap();
+        }
 
         assets_tracker.set_finished(ProgressTrackerFinishType::from_err(result.is_err()));
         assets_tracker.notify();
@@ -1041,23 +1462,23 @@ impl Launcher {
 
         result?;
 
-        Ok(asset_index)
+        let game_assets = legacy_assets_dir.unwrap_or_else(|| self.directories.assets_root_dir.clone());
+        Ok((asset_index, game_assets))
     }
 
     async fn load_libraries(
         &self,
         http_client: &reqwest::Client,
         artifacts: &[GameLibraryArtifact],
-        progress_trackers: &ProgressTrackers,
         launch_tracker: &ProgressTracker,
+        counts: &FileVerifyCounts,
     ) -> Result<Vec<(Ustr, PathBuf)>, LoadLibrariesError> {
         let initial_title = Arc::from("Verifying integrity of game libraries");
-        let libraries_tracker = ProgressTracker::new(initial_title, self.sender.clone());
-        progress_trackers.push(libraries_tracker.clone());
+        let libraries_tracker = launch_tracker.child(initial_title);
         libraries_tracker.notify();
 
         let result =
-            do_libraries_load(http_client, artifacts, self.directories.libraries_dir.clone(), &libraries_tracker).await;
+            do_libraries_load(http_client, artifacts, self.directories.libraries_dir.clone(), &libraries_tracker, counts).await;
 
         libraries_tracker.set_finished(ProgressTrackerFinishType::from_err(result.is_err()));
         libraries_tracker.notify();
@@ -1072,14 +1493,51 @@ impl Launcher {
         &self,
         http_client: &reqwest::Client,
         logging: Option<&GameLogging>,
-    ) -> Option<OsString> {
-        let Some(logging) = logging else {
-            return None;
-        };
-        let Some(client) = &logging.client else {
-            return None;
+        log4j_mitigation: Option<Log4jMitigation>,
+        patched_configuration: Option<&'static Log4jPatchedConfiguration>,
+    ) -> (Option<OsString>, Option<Log4jMitigation>) {
+        let client = logging.and_then(|logging| logging.client.as_ref());
+
+        // Configurations of versions that were never updated by Mojang (e.g. from old modpacks) still use lookups,
+        // so anything other than the patched configuration is replaced
+        if let Some(patched) = patched_configuration
+            && client.is_none_or(|client| client.file.sha1.as_str() != patched.sha1)
+        {
+            if let Some(path) = self.download_log_configuration(http_client, patched.id, patched.sha1, None, patched.url).await {
+                let argument = client.map(|client| client.argument.as_str()).unwrap_or(LOG4J_CONFIGURATION_ARGUMENT);
+                return (Some(expand_logging_argument(argument, &path)), log4j_mitigation);
+            }
+            log::warn!("Unable to load patched log configuration, the game is unprotected against Log4Shell");
+            return (None, None);
+        }
+
+        let Some(client) = client else {
+            return (None, log4j_mitigation);
         };
-        let id = client.file.id.as_str();
+
+        let path = self.download_log_configuration(
+            http_client,
+            client.file.id.as_str(),
+            client.file.sha1.as_str(),
+            Some(client.file.size),
+            client.file.url.as_str(),
+        ).await;
+
+        match path {
+            Some(path) => (Some(expand_logging_argument(client.argument.as_str(), &path)), log4j_mitigation),
+            None if patched_configuration.is_some() => (None, None),
+            None => (None, log4j_mitigation),
+        }
+    }
+
+    async fn download_log_configuration(
+        &self,
+        http_client: &reqwest::Client,
+        id: &str,
+        sha1: &str,
+        size: Option<u32>,
+        url: &str,
+    ) -> Option<PathBuf> {
         if !path_is_normal(id) {
             log::error!("Log configuration has invalid path: {}", id);
             return None;
@@ -1089,8 +1547,8 @@ impl Launcher {
         let _ = std::fs::create_dir(&self.directories.log_configs_dir);
 
         let mut expected_hash = [0u8; 20];
-        let Ok(_) = hex::decode_to_slice(client.file.sha1.as_str(), &mut expected_hash) else {
-            log::error!("Log configuration has invalid sha1: {}", client.file.sha1.as_str());
+        let Ok(_) = hex::decode_to_slice(sha1, &mut expected_hash) else {
+            log::error!("Log configuration has invalid sha1: {}", sha1);
             return None;
         };
 
@@ -1102,10 +1560,10 @@ impl Launcher {
         };
 
         if valid_hash_on_disk {
-            return Some(expand_logging_argument(client.argument.as_str(), &path));
+            return Some(path);
         }
 
-        let Ok(response) = http_client.get(client.file.url.as_str()).send().await else {
+        let Ok(response) = http_client.get(url).send().await else {
             log::error!("Failed to make request to download log configuration");
             return None;
         };
@@ -1115,7 +1573,7 @@ impl Launcher {
         };
         let bytes = Arc::new(bytes);
 
-        if bytes.len() != client.file.size as usize {
+        if let Some(size) = size && bytes.len() != size as usize {
             log::error!("Rejecting log configuration because invalid size");
             return None;
         }
@@ -1142,7 +1600,7 @@ impl Launcher {
             return None;
         };
 
-        Some(expand_logging_argument(client.argument.as_str(), &path))
+        Some(path)
     }
 
     fn can_skip_forge_processor(&self, jar: &MavenCoordinate<'_>, processor: &schema::forge::ForgeInstallProcessor, data: &FxHashMap<String, OsString>) -> bool {
@@ -1295,7 +1753,22 @@ impl Launcher {
         None
     }
 
-    fn get_major_java_version(&self, binary: &Path) -> Option<u32> {
+    fn warn_if_java_arch_mismatch(&self, binary: &Path, game_arch: &str) {
+        let Some(java_arch) = self.get_java_property(binary, "os.arch") else {
+            return;
+        };
+        let java_arch = host_arch::normalize_java_arch(&java_arch);
+        if java_arch != game_arch {
+            log::warn!("Java binary {:?} is {}, but the game should run as {}", binary, java_arch, game_arch);
+            if game_arch == "aarch64" {
+                self.sender.send_warning(format!("The selected Java runtime is {java_arch} and will run under emulation, a native {game_arch} runtime is recommended"));
+            } else {
+                self.sender.send_warning(format!("The selected Java runtime is {java_arch}, but this version of Minecraft requires {game_arch}"));
+            }
+        }
+    }
+
+    fn get_java_property(&self, binary: &Path, property: &str) -> Option<String> {
         let mut command = std::process::Command::new(binary);
         command.arg("-jar");
         command.arg(self.launch_wrapper.as_os_str().to_os_string());
@@ -1305,8 +1778,9 @@ impl Launcher {
         let mut process = command.spawn().ok()?;
 
         let mut stdin = process.stdin.take().unwrap();
-        stdin.write_all(b"printproperty\njava.specification.version\nexit\n").ok()?;
+        stdin.write_all(format!("printproperty\n{property}\nexit\n").as_bytes()).ok()?;
         stdin.flush().ok()?;
+        drop(stdin);
 
         let output = process.wait_with_output().ok()?;
 
@@ -1314,8 +1788,12 @@ impl Launcher {
             return None;
         }
 
-        let output = output.stdout.trim_ascii();
-        let mut output = str::from_utf8(output).ok()?;
+        Some(str::from_utf8(output.stdout.trim_ascii()).ok()?.to_string())
+    }
+
+    fn get_major_java_version(&self, binary: &Path) -> Option<u32> {
+        let output = self.get_java_property(binary, "java.specification.version")?;
+        let mut output = output.as_str();
         if output.starts_with("1.") {
             output = &output[2..];
         }
@@ -1350,7 +1828,151 @@ fn expand_logging_argument(argument: &str, path: &Path) -> OsString {
     builder
 }
 
-fn calculate_natives_dirname(artifacts: &[GameLibraryArtifact]) -> String {
+fn supports_quick_play_realms(version_info: &MinecraftVersion) -> bool {
+    let Some(arguments) = &version_info.arguments else {
+        return false;
+    };
+    arguments.game.iter().any(|argument| match argument {
+        LaunchArgument::Single(_) => false,
+        LaunchArgument::Ruled(ruled) => ruled.rules.iter().any(|rule| {
+            rule.features.as_ref().is_some_and(|features| features.is_quick_play_realms)
+        }),
+    })
+}
+
+// Serves skins to old versions that still request them from the retired skin server
+const LEGACY_SKIN_PROXY_HOST: &str = "betacraft.uk";
+const LEGACY_SKIN_PROXY_PORT: u16 = 11705;
+
+// Versions before 1.6 were started by the old launcher and all share this asset index
+fn uses_pre_1_6_launcher(assets_index_name: &str) -> bool {
+    assets_index_name == "pre-1.6"
+}
+
+// Nothing listens on the discard port, so connections through these proxies fail straight away instead of timing out.
+// Multiplayer connections don't go through the proxies, which is why multiplayer is disabled separately
+const NETWORK_ISOLATION_PROPERTIES: &[(&str, &str)] = &[
+    ("java.net.useSystemProxies", "false"),
+    ("http.proxyHost", "127.0.0.1"),
+    ("http.proxyPort", "9"),
+    ("http.nonProxyHosts", ""),
+    ("https.proxyHost", "127.0.0.1"),
+    ("https.proxyPort", "9"),
+    ("socksProxyHost", "127.0.0.1"),
+    ("socksProxyPort", "9"),
+];
+
+fn pre_1_6_jvm_arguments(game_dir: &Path, legacy_fixes: InstanceLegacyFixesConfiguration) -> Vec<OsString> {
+    // Applet based versions save to ~/.minecraft unless told otherwise
+    let mut target_directory = OsString::from("-Dminecraft.applet.TargetDirectory=");
+    target_directory.push(game_dir.as_os_str());
+
+    let mut arguments = vec![
+        target_directory,
+        // Sorting in old versions breaks the contract that the sort from Java 7 onwards checks, crashing the game
+        OsString::from("-Djava.util.Arrays.useLegacyMergeSort=true"),
+    ];
+
+    if legacy_fixes.use_skin_proxy {
+        arguments.push(OsString::from(format!("-Dhttp.proxyHost={}", LEGACY_SKIN_PROXY_HOST)));
+        arguments.push(OsString::from(format!("-Dhttp.proxyPort={}", LEGACY_SKIN_PROXY_PORT)));
+    }
+
+    arguments
+}
+
+// Logging configurations published by Mojang that don't use message lookups, for versions whose log4j
+// is too old to understand formatMsgNoLookups
+struct Log4jPatchedConfiguration {
+    id: &'static str,
+    sha1: &'static str,
+    url: &'static str,
+}
+
+static LOG4J_PATCHED_CLIENT_1_7: Log4jPatchedConfiguration = Log4jPatchedConfiguration {
+    id: "client-1.7.xml",
+    sha1: "50c9cc4af6d853d9fc137c84bcd153e2bd3a9a82",
+    url: "https://piston-data.mojang.com/v1/objects/50c9cc4af6d853d9fc137c84bcd153e2bd3a9a82/client-1.7.xml",
+};
+
+static LOG4J_PATCHED_CLIENT_1_12: Log4jPatchedConfiguration = Log4jPatchedConfiguration {
+    id: "client-1.12.xml",
+    sha1: "bd65e7d2e3c237be76cfbef4c2405033d7f91521",
+    url: "https://piston-data.mojang.com/v1/objects/bd65e7d2e3c237be76cfbef4c2405033d7f91521/client-1.12.xml",
+};
+
+static LOG4J_PATCHED_SERVER_1_7: Log4jPatchedConfiguration = Log4jPatchedConfiguration {
+    id: "log4j2_17-111.xml",
+    sha1: "4bb89a97a66f350bc9f73b3ca8509632682aea2e",
+    url: "https://piston-data.mojang.com/v1/objects/4bb89a97a66f350bc9f73b3ca8509632682aea2e/log4j2_17-111.xml",
+};
+
+static LOG4J_PATCHED_SERVER_1_12: Log4jPatchedConfiguration = Log4jPatchedConfiguration {
+    id: "log4j2_112-116.xml",
+    sha1: "02937d122c86ce73319ef9975b58896fc1b491d1",
+    url: "https://piston-data.mojang.com/v1/objects/02937d122c86ce73319ef9975b58896fc1b491d1/log4j2_112-116.xml",
+};
+
+const LOG4J_CONFIGURATION_ARGUMENT: &str = "-Dlog4j.configurationFile=${path}";
+const LOG4J_FORMAT_MSG_NO_LOOKUPS_ARGUMENT: &str = "-Dlog4j2.formatMsgNoLookups=true";
+
+// Protection against remote code execution through message lookups (CVE-2021-44228)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Log4jMitigation {
+    FormatMsgNoLookups,
+    // Minecraft 1.7 to 1.11.2, log4j 2.0
+    PatchedConfiguration1_7,
+    // Minecraft 1.12 to 1.16.5, log4j 2.8
+    PatchedConfiguration1_12,
+}
+
+impl Log4jMitigation {
+    pub fn description(self) -> &'static str {
+        match self {
+            Log4jMitigation::FormatMsgNoLookups => "Log4Shell mitigation active: message lookups are disabled",
+            Log4jMitigation::PatchedConfiguration1_7 | Log4jMitigation::PatchedConfiguration1_12 => {
+                "Log4Shell mitigation active: using Mojang's patched logging configuration"
+            },
+        }
+    }
+
+    fn client_configuration(self) -> Option<&'static Log4jPatchedConfiguration> {
+        match self {
+            Log4jMitigation::FormatMsgNoLookups => None,
+            Log4jMitigation::PatchedConfiguration1_7 => Some(&LOG4J_PATCHED_CLIENT_1_7),
+            Log4jMitigation::PatchedConfiguration1_12 => Some(&LOG4J_PATCHED_CLIENT_1_12),
+        }
+    }
+
+    fn server_configuration(self) -> Option<&'static Log4jPatchedConfiguration> {
+        match self {
+            Log4jMitigation::FormatMsgNoLookups => None,
+            Log4jMitigation::PatchedConfiguration1_7 => Some(&LOG4J_PATCHED_SERVER_1_7),
+            Log4jMitigation::PatchedConfiguration1_12 => Some(&LOG4J_PATCHED_SERVER_1_12),
+        }
+    }
+}
+
+// Minecraft 1.7 until 1.18 shipped a log4j affected by Log4Shell, but modded versions can bring their own log4j,
+// so the version of the library is checked rather than the version of Minecraft
+fn detect_log4j_mitigation(libraries: &[GameLibrary]) -> Option<Log4jMitigation> {
+    let version = libraries.iter().find_map(|library| library.name.strip_prefix("org.apache.logging.log4j:log4j-core:"))?;
+    let version = version.split(':').next()?;
+
+    let mut parts = version.split(['.', '-']);
+    let major: u32 = parts.next()?.parse().ok()?;
+    let minor: u32 = parts.next()?.parse().ok()?;
+
+    match (major, minor) {
+        (2, 17..) => None,
+        (2, 10..) => Some(Log4jMitigation::FormatMsgNoLookups),
+        (2, 8..) => Some(Log4jMitigation::PatchedConfiguration1_12),
+        (2, _) => Some(Log4jMitigation::PatchedConfiguration1_7),
+        _ => None,
+    }
+}
+
+fn calculate_natives_dirname(version: &str, arch: &str, artifacts: &[GameLibraryArtifact]) -> String {
     let mut hashes = HashSet::new();
 
     for artifact in artifacts {
@@ -1369,7 +1991,152 @@ fn calculate_natives_dirname(artifacts: &[GameLibraryArtifact]) -> String {
             combined[i] ^= hash[i];
         }
     }
-    hex::encode(combined)
+
+    let version: String = version.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    format!("{}-{}-{}-{}", version, std::env::consts::OS, arch, hex::encode(combined))
+}
+
+// Written once every native has been extracted, its modification time is when the natives were last used
+const NATIVES_COMPLETE_MARKER: &str = ".complete";
+
+// Natives that haven't been used by any launch for this long are removed
+const NATIVES_CACHE_MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24 * 30);
+
+// Directories without a marker are either still being extracted or were abandoned
+const NATIVES_PARTIAL_MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24);
+
+// Splits the libraries into the classpath and natives, extracting the natives unless an earlier launch already did
+fn extract_natives_and_collect_classpath(
+    library_paths: Vec<(Ustr, PathBuf)>,
+    natives_to_extract: &HashMap<Ustr, GameLibraryExtractOptions>,
+    natives_base_dir: &Path,
+    natives_dir: &Path,
+    natives_dirname: &str,
+) -> Vec<OsString> {
+    let natives_cached = natives_dir.join(NATIVES_COMPLETE_MARKER).exists();
+    let natives_extract_dir = if natives_cached {
+        None
+    } else {
+        let partial_dir = natives_base_dir.join(format!("{}.partial-{}", natives_dirname, rand::random::<u32>()));
+        let _ = std::fs::create_dir_all(&partial_dir);
+        Some(partial_dir)
+    };
+
+    let mut classpath = Vec::new();
+    for (raw_path, library_path) in library_paths {
+        if let Some(extract_options) = natives_to_extract.get(&raw_path) {
+            if let Some(natives_extract_dir) = &natives_extract_dir {
+                extract_natives(&library_path, extract_options, natives_extract_dir);
+            }
+        } else {
+            classpath.push(library_path.into_os_string());
+        }
+    }
+
+    if let Some(natives_extract_dir) = natives_extract_dir {
+        finish_natives_extraction(&natives_extract_dir, natives_dir);
+    } else {
+        log::debug!("Reusing extracted natives {}", natives_dirname);
+        mark_natives_used(natives_dir);
+    }
+    cleanup_natives_cache(natives_base_dir, natives_dir);
+
+    classpath
+}
+
+fn extract_natives(library_path: &Path, extract_options: &GameLibraryExtractOptions, natives_dir: &Path) {
+    let Ok(file) = std::fs::File::open(library_path) else {
+        return;
+    };
+    let Ok(archive) = file.read_zip() else {
+        return;
+    };
+    for file in archive.entries() {
+        let Some(path) = SafePath::new(&file.name) else {
+            continue;
+        };
+
+        if let Some(exclude) = &extract_options.exclude {
+            let mut skip = false;
+            for to_exclude in exclude.iter() {
+                if path.starts_with(to_exclude) {
+                    skip = true;
+                    break;
+                }
+            }
+            if skip {
+                continue;
+            }
+        }
+
+        let output_path = path.to_path(natives_dir);
+        match file.kind() {
+            rc_zip_sync::rc_zip::EntryKind::Directory => {
+                let _ = std::fs::create_dir(output_path);
+            },
+            rc_zip_sync::rc_zip::EntryKind::File => {
+                let Ok(mut outfile) = std::fs::File::create(&output_path) else {
+                    continue;
+                };
+                let _ = std::io::copy(&mut file.reader(), &mut outfile);
+            },
+            rc_zip_sync::rc_zip::EntryKind::Symlink => {},
+        }
+    }
+}
+
+// Natives are extracted next to the cache entry and moved into place at the end, so that a launch never sees a
+// partially extracted directory even when another launch of the same version is extracting at the same time
+fn finish_natives_extraction(extract_dir: &Path, natives_dir: &Path) {
+    if let Err(err) = std::fs::write(extract_dir.join(NATIVES_COMPLETE_MARKER), []) {
+        log::warn!("Unable to mark natives as extracted: {err}");
+    }
+
+    // A directory without a marker can't be trusted, and would make the rename fail
+    if natives_dir.exists() && !natives_dir.join(NATIVES_COMPLETE_MARKER).exists() {
+        let _ = std::fs::remove_dir_all(natives_dir);
+    }
+
+    if std::fs::rename(extract_dir, natives_dir).is_err() {
+        // Another launch finished extracting the same natives first
+        let _ = std::fs::remove_dir_all(extract_dir);
+        mark_natives_used(natives_dir);
+    }
+}
+
+fn mark_natives_used(natives_dir: &Path) {
+    let marker = natives_dir.join(NATIVES_COMPLETE_MARKER);
+    if let Ok(file) = std::fs::File::options().write(true).open(&marker) {
+        let _ = file.set_modified(std::time::SystemTime::now());
+    }
+}
+
+fn cleanup_natives_cache(natives_base_dir: &Path, keep: &Path) {
+    let Ok(read_dir) = std::fs::read_dir(natives_base_dir) else {
+        return;
+    };
+
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        if path == keep || !entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+            continue;
+        }
+
+        let (modified, max_age) = match std::fs::metadata(path.join(NATIVES_COMPLETE_MARKER)) {
+            Ok(marker) => (marker.modified(), NATIVES_CACHE_MAX_AGE),
+            Err(_) => (entry.metadata().and_then(|metadata| metadata.modified()), NATIVES_PARTIAL_MAX_AGE),
+        };
+        let Ok(modified) = modified else {
+            continue;
+        };
+
+        if modified.elapsed().is_ok_and(|elapsed| elapsed > max_age) {
+            log::debug!("Removing unused natives {:?}", entry.file_name());
+            let _ = std::fs::remove_dir_all(&path);
+        }
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -1402,6 +2169,18 @@ pub enum LoadJavaRuntimeError {
     UnableToFindExternalBinary(u32, Vec<u32>),
 }
 
+// Logs how long a step of launch preparation took, steps running at the same time are timed separately
+async fn timed<T>(step: &str, future: impl std::future::Future<Output = T>) -> T {
+    let start = Instant::now();
+    let result = future.await;
+    log::debug!("{step} took {:?}", start.elapsed());
+    result
+}
+
+// Files read at once by each verification step. The Java runtime, assets and libraries of a launch are verified
+// at the same time, so a single launch has at most three times this many files open
+const MAX_CONCURRENT_DISK_READS: usize = 32;
+
 async fn do_java_runtime_load(
     http_client: &reqwest::Client,
     runtime_component_dir: PathBuf,
@@ -1409,11 +2188,11 @@ async fn do_java_runtime_load(
     runtime: Arc<JavaRuntimeComponentManifest>,
     java_runtime_tracker: &ProgressTracker,
 ) -> Result<PathBuf, LoadJavaRuntimeError> {
-    let mut links = HashMap::new();
+    let mut links = Vec::new();
 
     // Limit max concurrent connections to 8 to avoid ratelimiting issues
     let download_semaphore = tokio::sync::Semaphore::new(8);
-    let disk_semaphore = tokio::sync::Semaphore::new(32);
+    let disk_semaphore = tokio::sync::Semaphore::new(MAX_CONCURRENT_DISK_READS);
     let started_downloading = AtomicBool::new(fresh_install);
 
     let mut tasks = Vec::new();
@@ -1439,6 +2218,7 @@ async fn do_java_runtime_load(
 
                 total_size += downloads.raw.size;
 
+                let executable = *executable;
                 let started_downloading = &started_downloading;
                 let download_semaphore = &download_semaphore;
                 let disk_semaphore = &disk_semaphore;
@@ -1448,7 +2228,12 @@ async fn do_java_runtime_load(
                         let path = path.clone();
                         let permit = disk_semaphore.acquire().await.unwrap();
                         let result = tokio::task::spawn_blocking(move || {
-                            crate::check_sha1_hash(&path, expected_hash).unwrap_or(false)
+                            let valid = crate::check_sha1_hash(&path, expected_hash).unwrap_or(false);
+                            // Reused files may have lost the flag, eg. when the runtime was copied or extracted by hand
+                            if valid && executable {
+                                ensure_executable(&path);
+                            }
+                            valid
                         }).await.unwrap();
                         drop(permit);
                         result
@@ -1472,8 +2257,11 @@ async fn do_java_runtime_load(
                     };
 
                     let permit = download_semaphore.acquire().await.unwrap();
+                    let in_flight = InFlightDownload::start();
                     let response = http_client.get(download.url.as_str()).send().await?;
                     let bytes = response.bytes().await?;
+                    crate::download_metrics::add_downloaded_bytes(bytes.len());
+                    drop(in_flight);
                     drop(permit);
 
                     if bytes.len() != size {
@@ -1533,10 +2321,8 @@ async fn do_java_runtime_load(
                     }
                     tokio::fs::write(&path, bytes).await?;
 
-                    #[cfg(unix)]
-                    if *executable {
-                        use std::os::unix::fs::PermissionsExt;
-                        let _ = tokio::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).await;
+                    if executable {
+                        ensure_executable(&path);
                     }
 
                     java_runtime_tracker.add_count(downloads.raw.size as usize);
@@ -1546,53 +2332,159 @@ async fn do_java_runtime_load(
                 tasks.push(task);
             },
             JavaRuntimeComponentFile::Link { target } => {
-                links.insert(path, target.clone());
+                links.push((path, target.clone()));
             },
         }
     }
-    java_runtime_tracker.set_total(total_size as usize);
-    java_runtime_tracker.notify();
+    java_runtime_tracker.set_total(total_size as usize);
+    java_runtime_tracker.notify();
+
+    futures::future::try_join_all(tasks).await?;
+
+    create_runtime_links(&runtime_component_dir, links, true);
+
+    let bin_java = runtime_component_dir.join("bin/java");
+    if let Ok(bin_java) = bin_java.canonicalize() {
+        return Ok(bin_java);
+    }
+
+    let bin_javaw = runtime_component_dir.join("bin/javaw.exe");
+    if let Ok(bin_javaw) = bin_javaw.canonicalize() {
+        return Ok(bin_javaw);
+    }
+
+    let jre_bundle_path = runtime_component_dir.join("jre.bundle/Contents/Home/bin/java");
+    if let Ok(jre_bundle_path) = jre_bundle_path.canonicalize() {
+        return Ok(jre_bundle_path);
+    }
+
+    let legacy_exe = runtime_component_dir.join("MinecraftJava.exe");
+    if let Ok(legacy_exe) = legacy_exe.canonicalize() {
+        return Ok(legacy_exe);
+    }
+
+    Err(LoadJavaRuntimeError::UnableToFindBinary)
+}
+
+#[cfg(unix)]
+fn ensure_executable(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+    if let Ok(metadata) = std::fs::metadata(path)
+        && metadata.permissions().mode() & 0o111 != 0o111
+    {
+        let _ = std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755));
+    }
+}
 
-    futures::future::try_join_all(tasks).await?;
+#[cfg(not(unix))]
+fn ensure_executable(_path: &Path) {
+}
+
+// Links are created once every file has been written. A link may point at another link, so links whose target doesn't
+// exist yet are retried for as long as the previous pass created at least one link
+fn create_runtime_links(runtime_component_dir: &Path, mut links: Vec<(PathBuf, Arc<Path>)>, allow_symlinks: bool) {
+    loop {
+        let remaining = links.len();
+        links.retain(|(path, target)| !create_runtime_link(runtime_component_dir, path, target, allow_symlinks));
+        if links.is_empty() || links.len() == remaining {
+            break;
+        }
+    }
 
     for (path, target) in links {
-        if let Some(parent) = path.parent()
-            && let Ok(absolute_target) = parent.join(target).canonicalize()
-            && absolute_target.starts_with(&runtime_component_dir)
-        {
-            #[cfg(unix)]
-            let _ = std::os::unix::fs::symlink(absolute_target, path);
+        log::warn!("Unable to resolve Java runtime link {path:?} -> {target:?}");
+    }
+}
 
-            #[cfg(windows)]
-            if absolute_target.is_dir() {
-                let _ = std::os::windows::fs::symlink_dir(absolute_target, path);
-            } else {
-                let _ = std::os::windows::fs::symlink_file(absolute_target, path);
+// Returns false if the target doesn't exist yet
+fn create_runtime_link(runtime_component_dir: &Path, path: &Path, target: &Path, allow_symlinks: bool) -> bool {
+    let Some(parent) = path.parent() else {
+        return true;
+    };
+    let Ok(absolute_target) = parent.join(target).canonicalize() else {
+        return false;
+    };
+    if !absolute_target.starts_with(runtime_component_dir) {
+        log::warn!("Java runtime link {path:?} points outside of the runtime, ignoring");
+        return true;
+    }
+
+    if let Ok(metadata) = path.symlink_metadata() {
+        // Copies made by the fallback below are refreshed when the runtime was updated and the target changed
+        if metadata.is_symlink() {
+            return true;
+        }
+        if metadata.is_dir() {
+            if let Err(error) = copy_dir_all(&absolute_target, path) {
+                log::warn!("Unable to refresh Java runtime link {path:?} from {absolute_target:?}: {error}");
             }
+            return true;
+        }
+        if is_up_to_date_copy(&absolute_target, path) {
+            return true;
         }
+        _ = std::fs::remove_file(path);
     }
 
-    let bin_java = runtime_component_dir.join("bin/java");
-    if let Ok(bin_java) = bin_java.canonicalize() {
-        return Ok(bin_java);
+    if allow_symlinks && create_symlink(&absolute_target, path).is_ok() {
+        return true;
     }
 
-    let bin_javaw = runtime_component_dir.join("bin/javaw.exe");
-    if let Ok(bin_javaw) = bin_javaw.canonicalize() {
-        return Ok(bin_javaw);
+    // Creating symlinks on Windows requires developer mode or administrator privileges, copy the target instead
+    let result = if absolute_target.is_dir() {
+        copy_dir_all(&absolute_target, path)
+    } else {
+        std::fs::copy(&absolute_target, path).map(|_| ())
+    };
+    if let Err(error) = result {
+        log::warn!("Unable to copy Java runtime link {path:?} from {absolute_target:?}: {error}");
     }
+    true
+}
 
-    let jre_bundle_path = runtime_component_dir.join("jre.bundle/Contents/Home/bin/java");
-    if let Ok(jre_bundle_path) = jre_bundle_path.canonicalize() {
-        return Ok(jre_bundle_path);
+#[cfg(unix)]
+fn create_symlink(target: &Path, path: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, path)
+}
+
+#[cfg(windows)]
+fn create_symlink(target: &Path, path: &Path) -> std::io::Result<()> {
+    if target.is_dir() {
+        std::os::windows::fs::symlink_dir(target, path)
+    } else {
+        std::os::windows::fs::symlink_file(target, path)
     }
+}
 
-    let legacy_exe = runtime_component_dir.join("MinecraftJava.exe");
-    if let Ok(legacy_exe) = legacy_exe.canonicalize() {
-        return Ok(legacy_exe);
+// A copy is written after its source, so a copy of the same size which is still newer is up to date. Otherwise the
+// source was replaced since, e.g. by a runtime update, and only then are the contents compared
+fn is_up_to_date_copy(source: &Path, copy: &Path) -> bool {
+    let (Ok(source_metadata), Ok(copy_metadata)) = (std::fs::metadata(source), std::fs::metadata(copy)) else {
+        return false;
+    };
+    if source_metadata.len() != copy_metadata.len() {
+        return false;
+    }
+    match (source_metadata.modified(), copy_metadata.modified()) {
+        (Ok(source_modified), Ok(copy_modified)) if copy_modified >= source_modified => true,
+        _ => crate::linking::same_contents(source, copy).unwrap_or(false),
     }
+}
 
-    Err(LoadJavaRuntimeError::UnableToFindBinary)
+// std::fs::copy keeps the permissions of each file, so executables stay executable. Files which are already up to
+// date are skipped, so that directories copied by an earlier launch are refreshed cheaply
+fn copy_dir_all(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_all(&entry.path(), &target)?;
+        } else if !is_up_to_date_copy(&entry.path(), &target) {
+            std::fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -1607,20 +2499,42 @@ pub enum LoadAssetObjectsError {
     WrongResponseSize(usize, usize),
     #[error("Downloaded file had the wrong hash")]
     WrongHash,
+    #[error("Asset has an invalid name\n{0}")]
+    InvalidAssetName(Ustr),
     #[error("Failed to load metadata:\n{0}")]
     MetaLoadError(#[from] MetaLoadError),
 }
 
+// Number of files whose hash was checked, and how many of those had to be (re)downloaded
+#[derive(Default)]
+pub struct FileVerifyCounts {
+    checked: AtomicUsize,
+    repaired: AtomicUsize,
+}
+
+impl FileVerifyCounts {
+    pub fn checked(&self) -> usize {
+        self.checked.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    pub fn repaired(&self) -> usize {
+        self.repaired.load(std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
 async fn do_asset_objects_load(
     http_client: &reqwest::Client,
     assets_index: Arc<AssetsIndex>,
     assets_objects_dir: Arc<Path>,
+    journal: Option<Arc<AssetJournal>>,
     assets_tracker: &ProgressTracker,
+    counts: &FileVerifyCounts,
 ) -> Result<(), LoadAssetObjectsError> {
     // Limit max concurrent connections to 8 to avoid ratelimiting issues
     let download_semaphore = tokio::sync::Semaphore::new(8);
-    let disk_semaphore = tokio::sync::Semaphore::new(32);
+    let disk_semaphore = tokio::sync::Semaphore::new(MAX_CONCURRENT_DISK_READS);
     let started_downloading = AtomicBool::new(false);
+    let finished_objects = AtomicUsize::new(0);
 
     let mut total_size = 0;
 
@@ -1641,36 +2555,51 @@ async fn do_asset_objects_load(
         total_size += asset.size;
 
         let started_downloading = &started_downloading;
+        let finished_objects = &finished_objects;
         let download_semaphore = &download_semaphore;
         let disk_semaphore = &disk_semaphore;
+        let journal = journal.clone();
 
         let url = format!("https://resources.download.minecraft.net/{}/{}", &asset.hash[..2], &asset.hash);
 
         let task = async move {
             let valid_hash_on_disk = {
                 let path = path.clone();
+                let journal = journal.clone();
+                let (hash, size) = (asset.hash, asset.size);
                 let permit = disk_semaphore.acquire().await.unwrap();
                 let result = tokio::task::spawn_blocking(move || {
-                    crate::check_sha1_hash(&path, expected_hash).unwrap_or(false)
+                    if let Some(journal) = &journal && journal.is_verified(&hash, &path, size as u64) {
+                        return true;
+                    }
+
+                    let valid = crate::check_sha1_hash(&path, expected_hash).unwrap_or(false);
+                    if valid && let Some(journal) = &journal {
+                        journal.record(&hash, &path);
+                    }
+                    valid
                 }).await.unwrap();
                 drop(permit);
                 result
             };
 
+            counts.checked.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
             if valid_hash_on_disk {
+                finished_objects.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
                 assets_tracker.add_count(asset.size as usize);
                 assets_tracker.notify();
                 return Ok(());
             }
 
-            let was_downloading = started_downloading.swap(true, std::sync::atomic::Ordering::Relaxed);
-            if !was_downloading {
-                assets_tracker.set_title(Arc::from("Downloading game assets"));
-            }
+            started_downloading.store(true, std::sync::atomic::Ordering::Relaxed);
 
             let permit = download_semaphore.acquire().await.unwrap();
+            let in_flight = InFlightDownload::start();
             let response = http_client.get(&url).send().await?;
             let bytes = Arc::new(response.bytes().await?);
+            crate::download_metrics::add_downloaded_bytes(bytes.len());
+            drop(in_flight);
             drop(permit);
 
             if bytes.len() != asset.size as usize {
@@ -1694,6 +2623,11 @@ async fn do_asset_objects_load(
             }
 
             tokio::fs::write(path.clone(), &*bytes).await?;
+            if let Some(journal) = &journal {
+                journal.record(&asset.hash, &path);
+            }
+            counts.repaired.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            finished_objects.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
             assets_tracker.add_count(asset.size as usize);
             assets_tracker.notify();
             Ok(())
@@ -1704,11 +2638,100 @@ async fn do_asset_objects_load(
     assets_tracker.set_total(total_size as usize);
     assets_tracker.notify();
 
-    futures::future::try_join_all(tasks).await?;
+    let total_objects = tasks.len();
+    let update_title = async {
+        let started = Instant::now();
+        loop {
+            tokio::time::sleep(ASSET_PROGRESS_INTERVAL).await;
+            let title = asset_progress_title(
+                started_downloading.load(std::sync::atomic::Ordering::Relaxed),
+                finished_objects.load(std::sync::atomic::Ordering::Relaxed),
+                total_objects,
+                started.elapsed(),
+            );
+            assets_tracker.set_title(title.into());
+            assets_tracker.notify();
+        }
+    };
+
+    tokio::select! {
+        result = futures::future::try_join_all(tasks) => { result?; },
+        _ = update_title => {},
+    }
+
+    Ok(())
+}
+
+// Places verified objects at their names for versions that don't read the shared objects directory. Files
+// which already have the right size are kept, they were copied from verified objects on a previous launch
+fn copy_legacy_asset_objects(assets_index: &AssetsIndex, objects_dir: &Path, legacy_assets_dir: &Path) -> Result<(), LoadAssetObjectsError> {
+    for (name, asset) in &assets_index.objects {
+        let Some(target) = legacy_asset_path(legacy_assets_dir, name) else {
+            return Err(LoadAssetObjectsError::InvalidAssetName(*name));
+        };
+
+        if std::fs::metadata(&target).is_ok_and(|metadata| metadata.len() == asset.size as u64) {
+            continue;
+        }
+
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let object = objects_dir.join(&asset.hash[..2]).join(asset.hash.as_str());
+        crate::linking::clone_or_copy(&object, &target, true)?;
+    }
 
     Ok(())
 }
 
+// Where versions that read assets by name expect them, or None if the game reads the shared objects
+fn legacy_assets_dir(assets_index: &AssetsIndex, asset_index: &str, game_dir: &Path, assets_root_dir: &Path) -> Option<PathBuf> {
+    if assets_index.map_to_resources == Some(true) {
+        Some(game_dir.join("resources"))
+    } else if assets_index.r#virtual == Some(true) {
+        Some(assets_root_dir.join("virtual").join(asset_index))
+    } else {
+        None
+    }
+}
+
+fn legacy_asset_path(legacy_assets_dir: &Path, name: &str) -> Option<PathBuf> {
+    if name.is_empty() || !path_is_normal(name) {
+        return None;
+    }
+    Some(legacy_assets_dir.join(name))
+}
+
+const ASSET_PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+fn asset_progress_title(downloading: bool, finished: usize, total: usize, elapsed: Duration) -> String {
+    let action = if downloading {
+        "Downloading game assets"
+    } else {
+        "Verifying integrity of game assets"
+    };
+
+    let per_second = finished as f64 / elapsed.as_secs_f64();
+    if finished == 0 || !per_second.is_finite() {
+        return format!("{action} ({finished}/{total})");
+    }
+
+    let remaining = Duration::from_secs_f64((total - finished) as f64 / per_second);
+    format!("{action} ({finished}/{total}, {per_second:.0} objects/s, {} left)", format_remaining(remaining))
+}
+
+fn format_remaining(remaining: Duration) -> String {
+    let seconds = remaining.as_secs();
+    if seconds >= 3600 {
+        format!("{}h {}m", seconds / 3600, (seconds % 3600) / 60)
+    } else if seconds >= 60 {
+        format!("{}m {}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum LoadLibrariesError {
     #[error("Failed to load remote content")]
@@ -1730,10 +2753,11 @@ async fn do_libraries_load(
     artifacts: &[GameLibraryArtifact],
     libraries_dir: Arc<Path>,
     libraries_tracker: &ProgressTracker,
+    counts: &FileVerifyCounts,
 ) -> Result<Vec<(Ustr, PathBuf)>, LoadLibrariesError> {
     // Limit max concurrent connections to 8 to avoid ratelimiting issues
     let download_semaphore = tokio::sync::Semaphore::new(8);
-    let disk_semaphore = tokio::sync::Semaphore::new(32);
+    let disk_semaphore = tokio::sync::Semaphore::new(MAX_CONCURRENT_DISK_READS);
     let started_downloading = AtomicBool::new(false);
 
     let mut total_size = 0;
@@ -1783,6 +2807,8 @@ async fn do_libraries_load(
                 artifact_path.exists()
             };
 
+            counts.checked.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
             if valid_hash_on_disk {
                 libraries_tracker.add_count(tracker_size as usize);
                 libraries_tracker.notify();
@@ -1795,8 +2821,11 @@ async fn do_libraries_load(
             }
 
             let permit = download_semaphore.acquire().await.unwrap();
+            let in_flight = InFlightDownload::start();
             let response = http_client.get(artifact.url.as_str()).send().await?;
             let bytes = Arc::new(response.bytes().await?);
+            crate::download_metrics::add_downloaded_bytes(bytes.len());
+            drop(in_flight);
             drop(permit);
 
             if let Some(artifact_size) = artifact.size && bytes.len() != artifact_size as usize {
@@ -1824,6 +2853,7 @@ async fn do_libraries_load(
             }
 
             tokio::fs::write(artifact_path.clone(), &*bytes).await?;
+            counts.repaired.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
             libraries_tracker.add_count(tracker_size as usize);
             libraries_tracker.notify();
             Ok((artifact.path, artifact_path))
@@ -1848,9 +2878,11 @@ pub enum ArgumentExpansionKey {
     VersionName,
     GameDirectory,
     AssetsRoot,
+    GameAssets,
     AssetsIndexName,
     AuthUuid,
     AuthAccessToken,
+    AuthSession,
     Clientid,
     AuthXuid,
     VersionType,
@@ -1876,10 +2908,12 @@ impl ArgumentExpansionKey {
             "auth_player_name" => Some(Self::AuthPlayerName),
             "version_name" => Some(Self::VersionName),
             "game_directory" => Some(Self::GameDirectory),
-            "assets_root" | "game_assets" => Some(Self::AssetsRoot),
+            "assets_root" => Some(Self::AssetsRoot),
+            "game_assets" => Some(Self::GameAssets),
             "assets_index_name" => Some(Self::AssetsIndexName),
             "auth_uuid" => Some(Self::AuthUuid),
-            "auth_access_token" | "auth_session" => Some(Self::AuthAccessToken),
+            "auth_access_token" => Some(Self::AuthAccessToken),
+            "auth_session" => Some(Self::AuthSession),
             "clientid" => Some(Self::Clientid),
             "auth_xuid" => Some(Self::AuthXuid),
             "version_type" => Some(Self::VersionType),
@@ -1900,6 +2934,7 @@ pub struct LaunchRuleContext {
     pub is_demo_user: bool,
     pub custom_resolution: Option<(u32, u32)>,
     pub quick_play: Option<QuickPlayLaunch>,
+    pub arch: &'static str,
 }
 
 impl LaunchRuleContext {
@@ -2029,15 +3064,26 @@ impl LaunchRuleContext {
             if let Some(arch) = &os.arch {
                 match arch {
                     OsArch::Arm64 => {
-                        if std::env::consts::ARCH != "aarch64" {
+                        if self.arch != "aarch64" {
                             return false;
                         }
                     },
                     OsArch::X86 => {
-                        if std::env::consts::ARCH != "x86" {
+                        if self.arch != "x86" {
                             return false;
                         }
                     },
+                    OsArch::X86_64 => {
+                        if self.arch != "x86_64" {
+                            return false;
+                        }
+                    },
+                    OsArch::Riscv64 => {
+                        if self.arch != "riscv64" {
+                            return false;
+                        }
+                    },
+                    OsArch::Unknown => return false,
                 }
             }
             if let Some(version) = &os.version && let Ok(regex) = Regex::new(version.as_str()) {
@@ -2063,121 +3109,244 @@ pub struct LaunchContext {
     pub assets_root: Arc<Path>,
     pub temp_dir: Arc<Path>,
     pub assets_index_name: String,
+    pub game_assets: Arc<Path>,
     pub classpath: Vec<OsString>,
     pub log_configuration: Option<OsString>,
+    pub log4j_mitigation: Option<Log4jMitigation>,
     pub rule_context: LaunchRuleContext,
     pub login_info: MinecraftLoginInfo,
+    // Shows a placeholder instead of the access token, for commands that are displayed or written to disk
+    pub redact_access_token: bool,
     pub add_mods: Vec<PathBuf>,
+    pub version_type: MinecraftVersionType,
 }
 
-impl LaunchContext {
-    pub fn launch(mut self, version_info: &MinecraftVersion) -> std::io::Result<std::process::Child> {
-        #[cfg(target_os = "linux")]
-        let use_mangohud = self.configuration.linux_wrapper.map(|w| w.use_mangohud).unwrap_or(false);
-        #[cfg(target_os = "linux")]
-        let use_gamemode = self.configuration.linux_wrapper.map(|w| w.use_gamemode).unwrap_or(false);
-
-        #[cfg(target_os = "linux")]
-        let mut command = match (use_mangohud, use_gamemode) {
-            (true, true) => {
-                let mut cmd = std::process::Command::new("mangohud");
-                cmd.arg("gamemoderun");
-                cmd.arg(&*self.java_path);
-                cmd
-            }
-            (true, false) => {
-                let mut cmd = std::process::Command::new("mangohud");
-                cmd.arg(&*self.java_path);
-                cmd
-            }
-            (false, true) => {
-                let mut cmd = std::process::Command::new("gamemoderun");
-                cmd.arg(&*self.java_path);
-                cmd
-            }
-            (false, false) => {
-                std::process::Command::new(&*self.java_path)
+// The game process as it will be started, before its arguments are handed to the launch wrapper
+pub struct LaunchCommand {
+    // Programs that start java, such as mangohud
+    pub wrappers: Vec<OsString>,
+    pub java_path: PathBuf,
+    pub env: Vec<(OsString, OsString)>,
+    pub jvm_arguments: Vec<OsString>,
+    // System properties which the launch wrapper sets before starting the game
+    pub properties: Vec<(String, String)>,
+    pub main_class: Ustr,
+    pub game_arguments: Vec<OsString>,
+    pub game_dir: Arc<Path>,
+}
+
+impl LaunchCommand {
+    // The equivalent command when starting the game directly instead of through the launch wrapper
+    pub fn to_shell_script(&self) -> String {
+        let mut script = String::new();
+        if cfg!(windows) {
+            script.push_str("@echo off\r\n");
+            script.push_str(&format!("cd /d {}\r\n", quote_argument(self.game_dir.as_os_str())));
+            for (key, value) in &self.env {
+                script.push_str(&format!("set {}={}\r\n", key.to_string_lossy(), value.to_string_lossy()));
             }
-        };
+        } else {
+            script.push_str("#!/bin/sh\n");
+            script.push_str(&format!("cd {} || exit 1\n", quote_argument(self.game_dir.as_os_str())));
+            for (key, value) in &self.env {
+                script.push_str(&format!("export {}={}\n", key.to_string_lossy(), quote_argument(value)));
+            }
+        }
 
-        #[cfg(target_os = "linux")]
-        if self.configuration.linux_wrapper.map(|w| w.use_discrete_gpu).unwrap_or(true) {
-            command.env("DRI_PRIME", "1");
+        let mut arguments: Vec<String> = Vec::new();
+        arguments.extend(self.wrappers.iter().map(|wrapper| quote_argument(wrapper)));
+        arguments.push(quote_argument(self.java_path.as_os_str()));
+        arguments.extend(self.jvm_arguments.iter().map(|argument| quote_argument(argument)));
+        for (key, value) in &self.properties {
+            arguments.push(quote_argument(OsStr::new(&format!("-D{key}={value}"))));
         }
+        arguments.push(self.main_class.to_string());
+        arguments.extend(self.game_arguments.iter().map(|argument| quote_argument(argument)));
 
-        #[cfg(not(target_os = "linux"))]
-        let mut command = std::process::Command::new(&*self.java_path);
+        script.push_str(&arguments.join(" "));
+        script.push_str(if cfg!(windows) { "\r\n" } else { "\n" });
+        script
+    }
+}
+
+fn quote_argument(argument: &OsStr) -> String {
+    let argument = argument.to_string_lossy();
+    if !cfg!(windows) {
+        shell_words::quote(&argument).into_owned()
+    } else if argument.is_empty() || argument.contains([' ', '\t', '"', '&', '|', '<', '>', '^', '(', ')']) {
+        format!("\"{}\"", argument.replace('"', "\"\""))
+    } else {
+        argument.into_owned()
+    }
+}
+
+impl LaunchContext {
+    pub fn launch(self, version_info: &MinecraftVersion) -> std::io::Result<std::process::Child> {
+        let launch_command = self.into_command(version_info);
+
+        let mut wrappers = launch_command.wrappers.iter();
+        let mut command = if let Some(program) = wrappers.next() {
+            let mut command = std::process::Command::new(program);
+            command.args(wrappers);
+            command.arg(&launch_command.java_path);
+            command
+        } else {
+            std::process::Command::new(&launch_command.java_path)
+        };
 
-        command.current_dir(&self.game_dir);
+        command.envs(launch_command.env.iter().map(|(key, value)| (key, value)));
+        command.current_dir(&launch_command.game_dir);
         command.stdin(Stdio::piped());
         command.stdout(Stdio::piped());
         command.stderr(Stdio::piped());
 
+        // Run the game in its own process group so signals sent to the launcher (e.g. closing the terminal
+        // it was started from) don't also stop the game, the launcher can be closed while playing
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+
+        command.args(&launch_command.jvm_arguments);
+        command.arg("com.moulberry.pandora.LaunchWrapper");
+
+        let mut child = command.spawn()?;
+
+        let mut stdin = child.stdin.take().expect("stdin present");
+
+        let mut stdin_arguments = String::new();
+
+        for argument in &launch_command.game_arguments {
+            stdin_arguments.push_str("arg\n");
+            stdin_arguments.push_str(argument.to_string_lossy().as_ref());
+            stdin_arguments.push('\n');
+        }
+
+        for (key, value) in &launch_command.properties {
+            stdin_arguments.push_str("property\n");
+            stdin_arguments.push_str(key);
+            stdin_arguments.push('\n');
+            stdin_arguments.push_str(value);
+            stdin_arguments.push('\n');
+        }
+
+        stdin_arguments.push_str("launch\n");
+        stdin_arguments.push_str(launch_command.main_class.as_str());
+        stdin_arguments.push('\n');
+
+        stdin.write_all(stdin_arguments.as_bytes())?;
+        stdin.flush()?;
+
+        Ok(child)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn wrappers(&self) -> (Vec<OsString>, Vec<(OsString, OsString)>) {
+        let mut wrappers = Vec::new();
+        let mut env = Vec::new();
+        if self.configuration.linux_wrapper.map(|w| w.use_mangohud).unwrap_or(false) {
+            wrappers.push(OsString::from("mangohud"));
+        }
+        if self.configuration.linux_wrapper.map(|w| w.use_gamemode).unwrap_or(false) {
+            wrappers.push(OsString::from("gamemoderun"));
+        }
+        if self.configuration.linux_wrapper.map(|w| w.use_discrete_gpu).unwrap_or(true) {
+            env.push((OsString::from("DRI_PRIME"), OsString::from("1")));
+        }
+        (wrappers, env)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn wrappers(&self) -> (Vec<OsString>, Vec<(OsString, OsString)>) {
+        (Vec::new(), Vec::new())
+    }
+
+    // Resolves every rule and placeholder into the final command without starting anything
+    pub fn into_command(mut self, version_info: &MinecraftVersion) -> LaunchCommand {
+        let (wrappers, env) = self.wrappers();
+
         self.classpath.push(self.launch_wrapper_path.as_os_str().to_os_string());
 
+        let mut jvm_arguments = Vec::new();
+
         if let Some(arguments) = &version_info.arguments {
             self.process_arguments(&arguments.jvm, &mut |arg| {
-                command.arg(arg);
+                jvm_arguments.push(arg.to_os_string());
             });
         } else {
             let mut java_library_path = OsString::new();
             java_library_path.push("-Djava.library.path=");
             java_library_path.push(self.natives_dir.as_os_str());
 
-            command.arg(java_library_path);
-            command.arg("-cp");
-            command.arg(std::env::join_paths(&self.classpath).unwrap());
+            jvm_arguments.push(java_library_path);
+            jvm_arguments.push(OsString::from("-cp"));
+            jvm_arguments.push(std::env::join_paths(&self.classpath).unwrap());
+
+            if uses_pre_1_6_launcher(&version_info.assets) {
+                let legacy_fixes = self.configuration.legacy_fixes.unwrap_or_default();
+                jvm_arguments.extend(pre_1_6_jvm_arguments(&self.game_dir, legacy_fixes));
+            }
         }
 
         if let Some(log_configuration) = &self.log_configuration {
-            command.arg(log_configuration);
+            jvm_arguments.push(log_configuration.clone());
+        }
+        if let Some(Log4jMitigation::FormatMsgNoLookups) = self.log4j_mitigation {
+            jvm_arguments.push(OsString::from(LOG4J_FORMAT_MSG_NO_LOOKUPS_ARGUMENT));
         }
 
         if let Some(memory) = &self.configuration.memory && memory.enabled {
-            command.arg(format!("-Xms{}m", memory.min));
-            command.arg(format!("-Xmx{}m", memory.max.max(memory.min).max(128)));
+            jvm_arguments.push(format!("-Xms{}m", memory.min).into());
+            jvm_arguments.push(format!("-Xmx{}m", memory.max.max(memory.min).max(128)).into());
         }
         if let Some(jvm_flags) = &self.configuration.jvm_flags && jvm_flags.enabled {
             if let Ok(split) = shell_words::split(&jvm_flags.flags) {
-                command.args(split);
+                jvm_arguments.extend(split.into_iter().map(OsString::from));
             } else {
-                command.args(jvm_flags.flags.split_whitespace());
+                jvm_arguments.extend(jvm_flags.flags.split_whitespace().map(OsString::from));
             }
         }
 
-        command.arg("com.moulberry.pandora.LaunchWrapper");
-
-        let mut child = command.spawn()?;
-
-        let mut stdin = child.stdin.take().expect("stdin present");
+        let mut game_arguments = Vec::new();
 
-        let mut stdin_arguments = String::new();
+        // Some pre-1.6 argument strings contain --gameDir, but only the launcher from 1.6 onwards understands the rest
+        let supports_named_arguments = version_info.arguments.is_some() || !uses_pre_1_6_launcher(&version_info.assets);
 
         if let Some(arguments) = &version_info.arguments {
             self.process_arguments(&arguments.game, &mut |arg| {
-                stdin_arguments.push_str("arg\n");
-                stdin_arguments.push_str(arg.to_string_lossy().as_ref());
-                stdin_arguments.push('\n');
+                game_arguments.push(arg.to_os_string());
             });
         }
         if let Some(legacy_arguments) = &version_info.minecraft_arguments {
             for argument in legacy_arguments.split_ascii_whitespace() {
-                stdin_arguments.push_str("arg\n");
-                stdin_arguments.push_str(self.expand_argument(argument).to_string_lossy().as_ref());
-                stdin_arguments.push('\n');
+                game_arguments.push(self.expand_argument(argument).into_owned());
+            }
+
+            // Legacy argument strings don't contain resolution rules, but versions using named
+            // arguments (1.6+) still accept --width/--height
+            if let Some((width, height)) = self.rule_context.custom_resolution && supports_named_arguments {
+                game_arguments.extend([
+                    OsString::from("--width"), OsString::from(width.to_string()),
+                    OsString::from("--height"), OsString::from(height.to_string()),
+                ]);
             }
         }
 
+        if let Some(resolution) = self.configuration.resolution && resolution.fullscreen && supports_named_arguments {
+            game_arguments.push(OsString::from("--fullscreen"));
+        }
+
+        let network_isolation = self.configuration.network_isolation.unwrap_or_default();
+        if network_isolation.disable_multiplayer && supports_named_arguments {
+            game_arguments.push(OsString::from("--disableMultiplayer"));
+        }
+
+        let mut properties = Vec::new();
+
         if !self.add_mods.is_empty() {
             match self.configuration.loader {
                 Loader::Vanilla => {},
                 Loader::Fabric => {
-                    let mods = std::env::join_paths(self.add_mods).unwrap();
-
-                    stdin_arguments.push_str("property\n");
-                    stdin_arguments.push_str("fabric.addMods\n");
-                    stdin_arguments.push_str(&mods.to_string_lossy());
-                    stdin_arguments.push('\n');
+                    let mods = std::env::join_paths(&self.add_mods).unwrap();
+                    properties.push(("fabric.addMods".to_string(), mods.to_string_lossy().into_owned()));
                 },
                 _ => {
                     if cfg!(debug_assertions) {
@@ -2187,29 +3356,29 @@ impl LaunchContext {
             }
         }
 
-        if let Some(system_libraries) = self.configuration.system_libraries {
+        if network_isolation.block_connections {
+            properties.extend(NETWORK_ISOLATION_PROPERTIES.iter().map(|(key, value)| (key.to_string(), value.to_string())));
+        }
+
+        if let Some(system_libraries) = &self.configuration.system_libraries {
             if let Some(path) = system_libraries.glfw.get_path() {
-                stdin_arguments.push_str("property\n");
-                stdin_arguments.push_str("org.lwjgl.glfw.libname\n");
-                stdin_arguments.push_str(&path.to_string_lossy());
-                stdin_arguments.push('\n');
+                properties.push(("org.lwjgl.glfw.libname".to_string(), path.to_string_lossy().into_owned()));
             }
             if let Some(path) = system_libraries.openal.get_path() {
-                stdin_arguments.push_str("property\n");
-                stdin_arguments.push_str("org.lwjgl.openal.libname\n");
-                stdin_arguments.push_str(&path.to_string_lossy());
-                stdin_arguments.push('\n');
+                properties.push(("org.lwjgl.openal.libname".to_string(), path.to_string_lossy().into_owned()));
             }
         }
 
-        stdin_arguments.push_str("launch\n");
-        stdin_arguments.push_str(version_info.main_class.as_str());
-        stdin_arguments.push('\n');
-
-        stdin.write_all(stdin_arguments.as_bytes())?;
-        stdin.flush()?;
-
-        Ok(child)
+        LaunchCommand {
+            wrappers,
+            java_path: self.java_path,
+            env,
+            jvm_arguments,
+            properties,
+            main_class: version_info.main_class,
+            game_arguments,
+            game_dir: self.game_dir,
+        }
     }
 
     fn process_arguments(&self, arguments: &[LaunchArgument], handler: &mut impl FnMut(&OsStr)) {
@@ -2271,6 +3440,14 @@ impl LaunchContext {
         Cow::Borrowed(OsStr::new(argument))
     }
 
+    fn access_token(&self) -> Option<&str> {
+        if self.redact_access_token {
+            Some("<access token>")
+        } else {
+            self.login_info.access_token.as_ref().map(|access_token| access_token.secret())
+        }
+    }
+
     fn resolve_expansion(&self, key: ArgumentExpansionKey) -> Cow<'_, OsStr> {
         match key {
             ArgumentExpansionKey::NativesDirectory => self.natives_dir.as_os_str().into(),
@@ -2286,19 +3463,22 @@ impl LaunchContext {
             ArgumentExpansionKey::LauncherVersion => OsStr::new("1.0.0").into(),
             ArgumentExpansionKey::Classpath => std::env::join_paths(&self.classpath).unwrap().into(),
             ArgumentExpansionKey::AuthPlayerName => OsStr::new(&*self.login_info.username).into(),
-            ArgumentExpansionKey::VersionName => OsStr::new("1.21.10").into(),
+            ArgumentExpansionKey::VersionName => OsStr::new(self.configuration.minecraft_version.as_str()).into(),
             ArgumentExpansionKey::GameDirectory => self.game_dir.as_os_str().into(),
             ArgumentExpansionKey::AssetsRoot => self.assets_root.as_os_str().into(),
+            ArgumentExpansionKey::GameAssets => self.game_assets.as_os_str().into(),
             ArgumentExpansionKey::AssetsIndexName => OsStr::new(&self.assets_index_name).into(),
             ArgumentExpansionKey::AuthUuid => OsString::from(self.login_info.uuid.as_hyphenated().to_string()).into(),
-            ArgumentExpansionKey::AuthAccessToken => OsStr::new(if let Some(access_token) = &self.login_info.access_token {
-                access_token.secret()
+            ArgumentExpansionKey::AuthAccessToken => OsStr::new(self.access_token().unwrap_or("offline")).into(),
+            // Versions before 1.7.3 expect the token and profile id together, "-" starts them in offline mode
+            ArgumentExpansionKey::AuthSession => if let Some(access_token) = self.access_token() {
+                OsString::from(format!("token:{}:{}", access_token, self.login_info.uuid.as_simple())).into()
             } else {
-                "offline"
-            }).into(),
+                OsStr::new("-").into()
+            },
             ArgumentExpansionKey::Clientid => OsStr::new("").into(), // These are just used for telemetry
             ArgumentExpansionKey::AuthXuid => OsStr::new("").into(), // These are just used for telemetry
-            ArgumentExpansionKey::VersionType => OsStr::new("release").into(),
+            ArgumentExpansionKey::VersionType => OsStr::new(self.version_type.as_str()).into(),
             ArgumentExpansionKey::QuickPlayPath => OsStr::new("quickPlay/log.json").into(),
             ArgumentExpansionKey::UserProperties => OsStr::new("{}").into(),
             ArgumentExpansionKey::UserType => OsStr::new("msa").into(),
@@ -2369,3 +3549,330 @@ fn expand_forge_argument<'a>(argument: &'a str, map: &FxHashMap<String, OsString
     }
     Cow::Borrowed(OsStr::new(argument))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{ffi::OsStr, path::{Path, PathBuf}, sync::Arc};
+
+    use schema::{
+        assets_index::AssetsIndex, instance::{InstanceConfiguration, InstanceLegacyFixesConfiguration}, java_runtime_component::{JavaRuntimeComponentFile, JavaRuntimeComponentManifest},
+        loader::Loader, version::{GameLibrary, GameLibraryArtifact, GameLibraryDownloads}, version_manifest::MinecraftVersionType
+    };
+    use uuid::Uuid;
+
+    use crate::account::MinecraftLoginInfo;
+
+    use super::{
+        ArgumentExpansionKey, LaunchContext, LaunchRuleContext, Log4jMitigation, calculate_natives_dirname, copy_legacy_asset_objects, create_runtime_links,
+        detect_log4j_mitigation, ensure_executable, legacy_asset_path, legacy_assets_dir, pre_1_6_jvm_arguments, uses_pre_1_6_launcher
+    };
+
+    // (version, asset index, minecraft_arguments) as published by Mojang
+    const LEGACY_VERSIONS: &[(&str, &str, &str)] = &[
+        ("rd-132211", "pre-1.6", "${auth_player_name} ${auth_session} --gameDir ${game_directory} --assetsDir ${game_assets} --tweakClass net.minecraft.launchwrapper.AlphaVanillaTweaker"),
+        ("c0.30_01c", "pre-1.6", "${auth_player_name} ${auth_session} --gameDir ${game_directory} --assetsDir ${game_assets} --tweakClass net.minecraft.launchwrapper.AlphaVanillaTweaker"),
+        ("a1.2.6", "pre-1.6", "${auth_player_name} ${auth_session} --gameDir ${game_directory} --assetsDir ${game_assets} --tweakClass net.minecraft.launchwrapper.AlphaVanillaTweaker"),
+        ("b1.7.3", "pre-1.6", "${auth_player_name} ${auth_session} --gameDir ${game_directory} --assetsDir ${game_assets}"),
+        ("1.2.5", "pre-1.6", "${auth_player_name} ${auth_session} --gameDir ${game_directory} --assetsDir ${game_assets}"),
+        ("1.5.2", "pre-1.6", "${auth_player_name} ${auth_session} --gameDir ${game_directory} --assetsDir ${game_assets}"),
+        ("1.6.4", "legacy", "--username ${auth_player_name} --session ${auth_session} --version ${version_name} --gameDir ${game_directory} --assetsDir ${game_assets}"),
+        ("1.7.2", "legacy", "--username ${auth_player_name} --session ${auth_session} --version ${version_name} --gameDir ${game_directory} --assetsDir ${game_assets} --uuid ${auth_uuid} --accessToken ${auth_access_token} --userProperties ${user_properties} --userType ${user_type}"),
+        ("1.7.10", "1.7.10", "--username ${auth_player_name} --version ${version_name} --gameDir ${game_directory} --assetsDir ${assets_root} --assetIndex ${assets_index_name} --uuid ${auth_uuid} --accessToken ${auth_access_token} --userProperties ${user_properties} --userType ${user_type}"),
+        ("1.12.2", "1.12", "--username ${auth_player_name} --version ${version_name} --gameDir ${game_directory} --assetsDir ${assets_root} --assetIndex ${assets_index_name} --uuid ${auth_uuid} --accessToken ${auth_access_token} --userType ${user_type} --versionType ${version_type}"),
+    ];
+
+    #[test]
+    fn test_legacy_arguments_expand() {
+        for (version, _, arguments) in LEGACY_VERSIONS {
+            for argument in arguments.split_ascii_whitespace() {
+                let Some(key) = argument.strip_prefix("${").and_then(|argument| argument.strip_suffix('}')) else {
+                    continue;
+                };
+                assert!(ArgumentExpansionKey::from_str(key).is_some(), "{version} uses unsupported argument {key}");
+            }
+        }
+    }
+
+    fn legacy_assets_index(name: &str) -> AssetsIndex {
+        match name {
+            "pre-1.6" => serde_json::from_str(r#"{"map_to_resources": true, "objects": {}}"#).unwrap(),
+            "legacy" => serde_json::from_str(r#"{"virtual": true, "objects": {}}"#).unwrap(),
+            _ => serde_json::from_str(r#"{"objects": {}}"#).unwrap(),
+        }
+    }
+
+    fn legacy_version_type(version: &str) -> MinecraftVersionType {
+        if version.starts_with("b") {
+            MinecraftVersionType::OldBeta
+        } else if version.starts_with("rd-") || version.starts_with("c") || version.starts_with("a") {
+            MinecraftVersionType::OldAlpha
+        } else {
+            MinecraftVersionType::Release
+        }
+    }
+
+    fn legacy_launch_context(version: &str, assets: &str, redact_access_token: bool) -> LaunchContext {
+        let game_dir: Arc<Path> = Path::new("instance/.minecraft").into();
+        let assets_root: Arc<Path> = Path::new("assets").into();
+        let game_assets = legacy_assets_dir(&legacy_assets_index(assets), assets, &game_dir, &assets_root)
+            .map(Arc::from)
+            .unwrap_or_else(|| assets_root.clone());
+
+        LaunchContext {
+            launch_wrapper_path: Path::new("launch_wrapper.jar").into(),
+            java_path: PathBuf::from("java"),
+            natives_dir: PathBuf::from("natives"),
+            libraries_dir: Path::new("libraries").into(),
+            game_dir,
+            configuration: InstanceConfiguration::new(version.into(), Loader::Vanilla),
+            assets_root,
+            temp_dir: Path::new("temp").into(),
+            assets_index_name: assets.to_string(),
+            game_assets,
+            classpath: Vec::new(),
+            log_configuration: None,
+            log4j_mitigation: None,
+            rule_context: LaunchRuleContext {
+                is_demo_user: false,
+                custom_resolution: None,
+                quick_play: None,
+                arch: "x86_64",
+            },
+            login_info: MinecraftLoginInfo {
+                uuid: Uuid::nil(),
+                username: "Player".into(),
+                access_token: None,
+            },
+            redact_access_token,
+            add_mods: Vec::new(),
+            version_type: legacy_version_type(version),
+        }
+    }
+
+    #[test]
+    fn test_legacy_assets_directory() {
+        let game_dir = Path::new("instance/.minecraft");
+        let assets_root = Path::new("assets");
+
+        assert_eq!(legacy_assets_dir(&legacy_assets_index("pre-1.6"), "pre-1.6", game_dir, assets_root),
+            Some(PathBuf::from("instance/.minecraft/resources")));
+        assert_eq!(legacy_assets_dir(&legacy_assets_index("legacy"), "legacy", game_dir, assets_root),
+            Some(PathBuf::from("assets/virtual/legacy")));
+        assert_eq!(legacy_assets_dir(&legacy_assets_index("1.12"), "1.12", game_dir, assets_root), None);
+
+        assert!(uses_pre_1_6_launcher("pre-1.6"));
+        assert!(!uses_pre_1_6_launcher("legacy"));
+    }
+
+    #[test]
+    fn test_legacy_arguments_substitution() {
+        for (version, assets, arguments) in LEGACY_VERSIONS {
+            let context = legacy_launch_context(version, assets, false);
+            let expanded: Vec<String> = arguments.split_ascii_whitespace()
+                .map(|argument| context.expand_argument(argument).to_string_lossy().into_owned())
+                .collect();
+            assert!(expanded.iter().all(|argument| !argument.contains("${")), "{version}: {expanded:?}");
+
+            let value_after = |flag: &str| expanded.iter().position(|argument| argument == flag).map(|index| expanded[index + 1].as_str());
+            let expected_assets = match *assets {
+                "pre-1.6" => "instance/.minecraft/resources",
+                "legacy" => "assets/virtual/legacy",
+                _ => "assets",
+            };
+            assert_eq!(value_after("--assetsDir"), Some(expected_assets), "{version}");
+            assert_eq!(value_after("--gameDir"), Some("instance/.minecraft"), "{version}");
+
+            if *assets == "pre-1.6" {
+                // Positional username and session, the offline session is "-"
+                assert_eq!(&expanded[..2], ["Player", "-"], "{version}");
+            }
+            if arguments.contains("--version ") {
+                assert_eq!(value_after("--version"), Some(*version), "{version}");
+            }
+            if arguments.contains("--versionType ") {
+                assert_eq!(value_after("--versionType"), Some(legacy_version_type(version).as_str()), "{version}");
+            }
+        }
+
+        let context = legacy_launch_context("1.7.2", "legacy", true);
+        assert_eq!(context.expand_argument("${auth_session}"), OsStr::new("token:<access token>:00000000000000000000000000000000"));
+
+        let context = legacy_launch_context("b1.7.3", "pre-1.6", false);
+        assert_eq!(context.expand_argument("--versionType ${version_type}"), OsStr::new("--versionType old_beta"));
+    }
+
+    #[test]
+    fn test_pre_1_6_jvm_arguments() {
+        let game_dir = Path::new("instance/.minecraft");
+
+        let arguments = pre_1_6_jvm_arguments(game_dir, InstanceLegacyFixesConfiguration { use_skin_proxy: true });
+        assert!(arguments.iter().any(|argument| argument.to_string_lossy() == "-Dminecraft.applet.TargetDirectory=instance/.minecraft"));
+        assert!(arguments.iter().any(|argument| argument.to_string_lossy().starts_with("-Dhttp.proxyHost=")));
+
+        let arguments = pre_1_6_jvm_arguments(game_dir, InstanceLegacyFixesConfiguration { use_skin_proxy: false });
+        assert!(!arguments.iter().any(|argument| argument.to_string_lossy().starts_with("-Dhttp.proxy")));
+    }
+
+    #[test]
+    fn test_legacy_asset_path() {
+        let dir = Path::new("resources");
+        assert_eq!(legacy_asset_path(dir, "sound/step/grass1.ogg"), Some(dir.join("sound/step/grass1.ogg")));
+        assert_eq!(legacy_asset_path(dir, "../options.txt"), None);
+        assert_eq!(legacy_asset_path(dir, "/etc/passwd"), None);
+        assert_eq!(legacy_asset_path(dir, ""), None);
+    }
+
+    #[test]
+    fn test_copy_legacy_asset_objects() {
+        let dir = std::env::temp_dir().join(format!("pandora-legacy-assets-{}", std::process::id()));
+        let objects_dir = dir.join("objects");
+        let resources_dir = dir.join("resources");
+
+        let hash = "b84e5ba7fc6b1c6cdbf0a2ac1c7a5cf3a38a7bd5";
+        std::fs::create_dir_all(objects_dir.join(&hash[..2])).unwrap();
+        std::fs::write(objects_dir.join(&hash[..2]).join(hash), b"grass").unwrap();
+
+        let assets_index: AssetsIndex = serde_json::from_str(&format!(
+            r#"{{"map_to_resources": true, "objects": {{"sound/step/grass1.ogg": {{"hash": "{hash}", "size": 5}}}}}}"#
+        )).unwrap();
+
+        copy_legacy_asset_objects(&assets_index, &objects_dir, &resources_dir).unwrap();
+        assert_eq!(std::fs::read(resources_dir.join("sound/step/grass1.ogg")).unwrap(), b"grass");
+
+        // Already copied objects are left alone
+        copy_legacy_asset_objects(&assets_index, &objects_dir, &resources_dir).unwrap();
+
+        _ = std::fs::remove_dir_all(&dir);
+    }
+
+    // Trimmed down from a macOS runtime manifest, which uses links for the jre.bundle layout. The link to a link is listed
+    // before its target, as happens in the published manifests
+    const JAVA_RUNTIME_MANIFEST: &str = r#"{"files": {
+        "jre.bundle": {"type": "directory"},
+        "jre.bundle/Contents": {"type": "directory"},
+        "jre.bundle/Contents/Home": {"type": "directory"},
+        "jre.bundle/Contents/Home/bin": {"type": "directory"},
+        "jre.bundle/Contents/Home/bin/java": {"type": "file", "executable": true, "downloads": {"raw": {"sha1": "0000000000000000000000000000000000000000", "size": 4, "url": "https://example.invalid/java"}}},
+        "jre.bundle/Contents/Home/release": {"type": "file", "executable": false, "downloads": {"raw": {"sha1": "0000000000000000000000000000000000000000", "size": 7, "url": "https://example.invalid/release"}}},
+        "jre.bundle/Contents/MacOS": {"type": "directory"},
+        "jre.bundle/Contents/MacOS/java": {"type": "link", "target": "libjli.dylib"},
+        "jre.bundle/Contents/MacOS/libjli.dylib": {"type": "link", "target": "../Home/bin/java"},
+        "jre.bundle/Contents/Home/jre": {"type": "link", "target": "bin"},
+        "jre.bundle/Contents/escape": {"type": "link", "target": "../../../outside"}
+    }}"#;
+
+    fn write_java_runtime_fixture(dir: &Path, allow_symlinks: bool) {
+        let manifest: JavaRuntimeComponentManifest = serde_json::from_str(JAVA_RUNTIME_MANIFEST).unwrap();
+
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.parent().unwrap().join("outside"), b"outside").unwrap();
+        let dir = dir.canonicalize().unwrap();
+
+        let mut links = Vec::new();
+        for (filename, contents) in &manifest.files {
+            let path = dir.join(filename);
+            match contents {
+                JavaRuntimeComponentFile::Directory => std::fs::create_dir_all(path).unwrap(),
+                JavaRuntimeComponentFile::File { executable, downloads } => {
+                    std::fs::write(&path, &b"release"[..downloads.raw.size as usize]).unwrap();
+                    if *executable {
+                        ensure_executable(&path);
+                    }
+                },
+                JavaRuntimeComponentFile::Link { target } => links.push((path, target.clone())),
+            }
+        }
+        create_runtime_links(&dir, links, allow_symlinks);
+    }
+
+    fn check_java_runtime_fixture(dir: &Path, symlinks: bool) {
+        let contents = dir.join("jre.bundle/Contents");
+        assert_eq!(std::fs::read(contents.join("MacOS/java")).unwrap(), b"rele");
+        assert_eq!(std::fs::read(contents.join("MacOS/libjli.dylib")).unwrap(), b"rele");
+        assert_eq!(std::fs::read(contents.join("Home/jre/java")).unwrap(), b"rele");
+        assert!(contents.join("escape").symlink_metadata().is_err());
+
+        for link in ["MacOS/java", "MacOS/libjli.dylib", "Home/jre"] {
+            assert_eq!(contents.join(link).symlink_metadata().unwrap().is_symlink(), symlinks, "{link}");
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |path: &str| std::fs::metadata(contents.join(path)).unwrap().permissions().mode();
+            assert_eq!(mode("Home/bin/java") & 0o111, 0o111);
+            assert_eq!(mode("Home/release") & 0o111, 0);
+            assert_eq!(mode("MacOS/java") & 0o111, 0o111);
+            assert_eq!(mode("Home/jre/java") & 0o111, 0o111);
+        }
+    }
+
+    #[test]
+    fn test_java_runtime_links_copy_fallback() {
+        let root = std::env::temp_dir().join(format!("pandora-java-runtime-copy-{}", std::process::id()));
+        let dir = root.join("runtime");
+        write_java_runtime_fixture(&dir, false);
+        check_java_runtime_fixture(&dir, false);
+
+        // Copies which are already up to date are kept, outdated ones are replaced, also inside copied directories
+        std::fs::write(dir.join("jre.bundle/Contents/MacOS/java"), b"old").unwrap();
+        std::fs::write(dir.join("jre.bundle/Contents/Home/jre/java"), b"olds").unwrap();
+        write_java_runtime_fixture(&dir, false);
+        check_java_runtime_fixture(&dir, false);
+
+        _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_java_runtime_links_symlink() {
+        let root = std::env::temp_dir().join(format!("pandora-java-runtime-symlink-{}", std::process::id()));
+        let dir = root.join("runtime");
+        write_java_runtime_fixture(&dir, true);
+        check_java_runtime_fixture(&dir, true);
+
+        _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_natives_dirname() {
+        let artifacts = [GameLibraryArtifact {
+            path: "org/lwjgl/lwjgl/3.3.3/lwjgl-3.3.3-natives-linux.jar".into(),
+            sha1: Some("2ca6d6fd8d7e7b1ab1d5b0e6d28b0a6ef9bcd0a4".into()),
+            size: Some(1),
+            url: "https://libraries.minecraft.net/org/lwjgl/lwjgl/3.3.3/lwjgl-3.3.3-natives-linux.jar".into(),
+        }];
+
+        let x86_64 = calculate_natives_dirname("1.21.1", "x86_64", &artifacts);
+        assert_eq!(x86_64, calculate_natives_dirname("1.21.1", "x86_64", &artifacts));
+        assert_ne!(x86_64, calculate_natives_dirname("1.21.1", "aarch64", &artifacts));
+        assert_ne!(x86_64, calculate_natives_dirname("1.21.1", "x86_64", &[]));
+        assert!(calculate_natives_dirname("../1.21 custom", "x86_64", &artifacts).starts_with(".._1.21_custom-"));
+    }
+
+    #[test]
+    fn test_log4j_mitigation() {
+        let library = |name: &str| GameLibrary {
+            downloads: GameLibraryDownloads { artifact: None, classifiers: None },
+            name: name.into(),
+            rules: None,
+            natives: None,
+            extract: None,
+        };
+        let mitigation = |version: &str| {
+            detect_log4j_mitigation(&[
+                library("com.mojang:authlib:1.5.21"),
+                library(&format!("org.apache.logging.log4j:log4j-core:{}", version)),
+            ])
+        };
+
+        assert_eq!(mitigation("2.0-beta9"), Some(Log4jMitigation::PatchedConfiguration1_7));
+        assert_eq!(mitigation("2.0-beta9-fixed"), Some(Log4jMitigation::PatchedConfiguration1_7));
+        assert_eq!(mitigation("2.8.1"), Some(Log4jMitigation::PatchedConfiguration1_12));
+        assert_eq!(mitigation("2.14.1"), Some(Log4jMitigation::FormatMsgNoLookups));
+        assert_eq!(mitigation("2.15.0"), Some(Log4jMitigation::FormatMsgNoLookups));
+        assert_eq!(mitigation("2.17.0"), None);
+        assert_eq!(mitigation("2.19.0"), None);
+        assert_eq!(detect_log4j_mitigation(&[library("net.minecraft:launchwrapper:1.5")]), None);
+    }
+}
@@ -0,0 +1,215 @@
+use std::{sync::Arc, time::Duration};
+
+use bridge::message::{InstanceLaunchMetrics, LaunchMetricsSummary};
+use rustc_hash::FxHashMap;
+use schema::{launch_metrics::{LaunchOutcome, LaunchRecord}, loader::Loader};
+use serde::Serialize;
+use ustr::Ustr;
+
+use crate::BackendState;
+
+// Older launches are forgotten so the file doesn't grow forever
+const MAX_RECORDED_LAUNCHES: usize = 1000;
+
+// Builds without an endpoint can still record metrics locally, they just can't upload them
+const LAUNCH_METRICS_UPLOAD_URL: Option<&str> = option_env!("PANDORA_METRICS_URL");
+
+// Deliberately excludes the instance name, timestamps and anything else that could identify the user
+#[derive(Serialize)]
+struct UploadedLaunch {
+    minecraft_version: Ustr,
+    loader: &'static str,
+    outcome: LaunchOutcome,
+    launch_millis: u64,
+    play_seconds: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct UploadedLaunchMetrics {
+    launcher_version: &'static str,
+    os: &'static str,
+    arch: &'static str,
+    launches: Vec<UploadedLaunch>,
+}
+
+impl BackendState {
+    pub fn is_recording_launch_metrics(&self) -> bool {
+        self.config.write().get().record_launch_metrics
+    }
+
+    pub fn record_launch(
+        &self,
+        instance: Ustr,
+        minecraft_version: Ustr,
+        loader: Loader,
+        started_at: i64,
+        launch_duration: Duration,
+        outcome: LaunchOutcome,
+    ) {
+        if !self.is_recording_launch_metrics() {
+            return;
+        }
+
+        let record = LaunchRecord {
+            started_at,
+            instance,
+            minecraft_version,
+            loader,
+            outcome,
+            launch_millis: launch_duration.as_millis() as u64,
+            play_seconds: None,
+        };
+
+        self.launch_metrics.write().modify(|metrics| {
+            metrics.launches.push(record);
+            if metrics.launches.len() > MAX_RECORDED_LAUNCHES {
+                let excess = metrics.launches.len() - MAX_RECORDED_LAUNCHES;
+                metrics.launches.drain(..excess);
+            }
+        });
+    }
+
+    pub fn record_launch_exit(&self, started_at: i64, play_duration: Duration, crashed: bool) {
+        if !self.is_recording_launch_metrics() {
+            return;
+        }
+
+        self.launch_metrics.write().modify(|metrics| {
+            let record = metrics.launches.iter_mut().rev().find(|record| record.started_at == started_at);
+            if let Some(record) = record {
+                record.outcome = if crashed { LaunchOutcome::Crashed } else { LaunchOutcome::Exited };
+                record.play_seconds = Some(play_duration.as_secs());
+            }
+        });
+
+        let state = self.clone();
+        tokio::task::spawn(async move {
+            state.upload_launch_metrics().await;
+        });
+    }
+
+    pub fn clear_launch_metrics(&self) {
+        self.launch_metrics.write().modify(|metrics| {
+            metrics.launches.clear();
+        });
+    }
+
+    pub fn launch_metrics_summary(&self) -> LaunchMetricsSummary {
+        let (recording, uploading) = {
+            let mut config = self.config.write();
+            let config = config.get();
+            (config.record_launch_metrics, config.upload_launch_metrics)
+        };
+
+        let mut launch_metrics = self.launch_metrics.write();
+        let launches = &launch_metrics.get().launches;
+
+        let mut failed_launches = 0;
+        let mut crashes = 0;
+        let mut total_play_seconds = 0;
+        let mut total_launch_millis = 0;
+        let mut successful_launches = 0;
+        let mut instances: FxHashMap<Ustr, InstanceLaunchMetrics> = FxHashMap::default();
+
+        for record in launches {
+            let instance = instances.entry(record.instance).or_insert_with(|| InstanceLaunchMetrics {
+                name: record.instance,
+                launches: 0,
+                crashes: 0,
+                play_seconds: 0,
+                last_launched: 0,
+            });
+            instance.launches += 1;
+            instance.last_launched = instance.last_launched.max(record.started_at);
+
+            match record.outcome {
+                LaunchOutcome::Failed => failed_launches += 1,
+                LaunchOutcome::Crashed => {
+                    crashes += 1;
+                    instance.crashes += 1;
+                },
+                LaunchOutcome::Running | LaunchOutcome::Exited => {},
+            }
+
+            if record.outcome != LaunchOutcome::Failed {
+                successful_launches += 1;
+                total_launch_millis += record.launch_millis;
+            }
+
+            let play_seconds = record.play_seconds.unwrap_or(0);
+            total_play_seconds += play_seconds;
+            instance.play_seconds += play_seconds;
+        }
+
+        let mut instances: Vec<InstanceLaunchMetrics> = instances.into_values().collect();
+        instances.sort_by_key(|instance| std::cmp::Reverse(instance.play_seconds));
+
+        LaunchMetricsSummary {
+            recording,
+            uploading,
+            upload_available: LAUNCH_METRICS_UPLOAD_URL.is_some(),
+            total_launches: launches.len(),
+            failed_launches,
+            crashes,
+            total_play_seconds,
+            average_launch_millis: total_launch_millis.checked_div(successful_launches),
+            instances: Arc::from(instances),
+        }
+    }
+
+    pub async fn upload_launch_metrics(&self) {
+        let Some(url) = LAUNCH_METRICS_UPLOAD_URL else {
+            return;
+        };
+
+        {
+            let mut config = self.config.write();
+            let config = config.get();
+            if !config.record_launch_metrics || !config.upload_launch_metrics {
+                return;
+            }
+        }
+
+        let (launches, uploaded_until) = {
+            let mut launch_metrics = self.launch_metrics.write();
+            let launch_metrics = launch_metrics.get();
+
+            let finished = launch_metrics.launches.iter()
+                .filter(|record| record.started_at > launch_metrics.uploaded_until && record.outcome != LaunchOutcome::Running);
+
+            let uploaded_until = finished.clone().map(|record| record.started_at).max();
+            let launches: Vec<UploadedLaunch> = finished.map(|record| UploadedLaunch {
+                minecraft_version: record.minecraft_version,
+                loader: record.loader.name(),
+                outcome: record.outcome,
+                launch_millis: record.launch_millis,
+                play_seconds: record.play_seconds,
+            }).collect();
+
+            (launches, uploaded_until)
+        };
+
+        let Some(uploaded_until) = uploaded_until else {
+            return;
+        };
+
+        let body = UploadedLaunchMetrics {
+            launcher_version: option_env!("PANDORA_RELEASE_VERSION").unwrap_or("dev"),
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            launches,
+        };
+
+        let result = self.http_client.post(url).json(&body).send().await.and_then(|response| response.error_for_status());
+        match result {
+            Ok(_) => {
+                self.launch_metrics.write().modify(|metrics| {
+                    metrics.uploaded_until = metrics.uploaded_until.max(uploaded_until);
+                });
+            },
+            Err(error) => {
+                log::warn!("Unable to upload launch metrics: {error}");
+            },
+        }
+    }
+}
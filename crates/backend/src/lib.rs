@@ -11,24 +11,74 @@ use sha1::{Digest, Sha1};
 
 mod backend_filesystem;
 mod backend_handler;
+mod backups;
 
 mod account;
+mod account_profile;
 mod arcfactory;
+mod asset_journal;
+mod bulk_actions;
+mod cancellation;
+mod config_files;
+mod content_download_queue;
+mod content_library;
+mod content_profiles;
+mod content_validation;
+pub mod data_location;
+mod datapacks;
 mod directories;
+pub mod download_metrics;
+mod event_stream;
+mod file_import;
+mod forge_processors;
+pub mod frontend_events;
+mod host_arch;
 mod install_content;
 mod instance;
+mod instance_duplicate;
+mod instance_history;
+mod installed_content;
+mod instance_location;
+mod instance_notes;
+mod instance_share;
+mod instance_template;
+mod instance_upgrade;
 mod java_manifest;
+mod java_runtimes;
+mod job_queue;
+mod jvm_crash;
 mod launch;
+mod launch_metrics;
 mod launch_wrapper;
+mod linking;
 mod lockfile;
+pub mod log_capture;
+pub mod log_files;
 mod log_reader;
+mod memory_budget;
 mod metadata;
+mod modrinth_account;
+mod modrinth_compatibility;
 mod mod_metadata;
+mod natives_substitution;
+mod options_txt;
+mod parental_controls;
+mod pending_instance;
 mod id_slab;
+mod performance_capture;
 mod persistent;
+mod problem_report;
+mod realms;
+mod server_ping;
+mod server_properties;
+mod server_resource_packs;
 mod shortcut;
+mod stray_instances;
 mod syncing;
+mod tunnel;
 mod update;
+mod url_handler;
+mod version_tracking;
 
 pub(crate) fn is_single_component_path(path: &str) -> bool {
     let path = std::path::Path::new(path);
@@ -89,6 +139,21 @@ pub(crate) fn write_safe(path: &Path, content: &[u8]) -> std::io::Result<()> {
     Ok(())
 }
 
+// level.dat is usually under a megabyte uncompressed, this only stops a gzip bomb in a downloaded world from using up
+// memory before the NBT decode limits are checked
+const MAX_DECOMPRESSED_NBT_SIZE: u64 = 64 * 1024 * 1024;
+
+pub(crate) fn gunzip_nbt(compressed: &[u8]) -> std::io::Result<Vec<u8>> {
+    use std::io::Read;
+
+    let mut decompressed = Vec::new();
+    flate2::bufread::GzDecoder::new(compressed).take(MAX_DECOMPRESSED_NBT_SIZE + 1).read_to_end(&mut decompressed)?;
+    if decompressed.len() as u64 > MAX_DECOMPRESSED_NBT_SIZE {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "decompressed NBT is too large"));
+    }
+    Ok(decompressed)
+}
+
 pub(crate) fn pandora_aux_path(id: &Option<Arc<str>>, name: &Option<Arc<str>>, path: &Path) -> Option<PathBuf> {
     let name = id.as_ref().or(name.as_ref());
 
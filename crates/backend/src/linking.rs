@@ -0,0 +1,199 @@
+use std::path::{Path, PathBuf};
+
+use bridge::modal_action::{ModalAction, ProgressTracker};
+
+// Places a file from the content library into an instance without duplicating it on disk when possible. Reflinks
+// are preferred since the instance gets an independent copy-on-write file, then hard links, then a plain copy.
+// Like std::fs::copy, an existing file at the destination is replaced
+pub(crate) fn link_or_copy(from: &Path, to: &Path, allow_links: bool) -> std::io::Result<()> {
+    if allow_links {
+        remove_existing(to);
+        if reflink(from, to).is_ok() {
+            return Ok(());
+        }
+        if std::fs::hard_link(from, to).is_ok() {
+            return Ok(());
+        }
+    }
+    std::fs::copy(from, to).map(|_| ())
+}
+
+// Like link_or_copy but never hard links, for files which the game or user are expected to modify afterwards
+pub(crate) fn clone_or_copy(from: &Path, to: &Path, allow_links: bool) -> std::io::Result<()> {
+    if allow_links {
+        remove_existing(to);
+        if reflink(from, to).is_ok() {
+            return Ok(());
+        }
+    }
+    std::fs::copy(from, to).map(|_| ())
+}
+
+// Copies a directory recursively using clone_or_copy for every file, symlinks are skipped
+pub(crate) fn copy_dir(from: &Path, to: &Path, allow_links: bool) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target = to.join(entry.file_name());
+        if file_type.is_dir() {
+            copy_dir(&entry.path(), &target, allow_links)?;
+        } else if file_type.is_file() {
+            clone_or_copy(&entry.path(), &target, allow_links)?;
+        }
+    }
+    Ok(())
+}
+
+// Compares two files chunk by chunk, so large files are never read into memory at once
+pub(crate) fn same_contents(a: &Path, b: &Path) -> std::io::Result<bool> {
+    let (mut a, mut b) = (std::fs::File::open(a)?, std::fs::File::open(b)?);
+    if a.metadata()?.len() != b.metadata()?.len() {
+        return Ok(false);
+    }
+
+    let mut a_buffer = vec![0; 64 * 1024];
+    let mut b_buffer = vec![0; 64 * 1024];
+    loop {
+        let a_read = read_chunk(&mut a, &mut a_buffer)?;
+        let b_read = read_chunk(&mut b, &mut b_buffer)?;
+        if a_buffer[..a_read] != b_buffer[..b_read] {
+            return Ok(false);
+        }
+        if a_read == 0 {
+            return Ok(true);
+        }
+    }
+}
+
+// Fills the buffer unless the end of the file is reached first
+fn read_chunk(file: &mut std::fs::File, buffer: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match std::io::Read::read(file, &mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => {},
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(filled)
+}
+
+// Counts the files and symlinks inside a directory recursively, used as the total for copy_dir_with_progress
+pub(crate) fn count_files(path: &Path) -> usize {
+    let Ok(read_dir) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    read_dir.flatten().map(|entry| {
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => count_files(&entry.path()),
+            _ => 1,
+        }
+    }).sum()
+}
+
+// Copies a directory recursively, counting every file on the tracker. Symlinks are recreated and links pointing
+// inside `from` are rewritten to point inside `to`. Stops early without an error if the user cancels, the error
+// contains the path which couldn't be copied
+pub(crate) fn copy_dir_with_progress(from: &Path, to: &Path, tracker: &ProgressTracker, modal_action: &ModalAction) -> Result<(), (PathBuf, std::io::Error)> {
+    copy_dir_with_progress_inner(from, to, from, to, tracker, modal_action)
+}
+
+fn copy_dir_with_progress_inner(
+    from: &Path,
+    to: &Path,
+    old_root: &Path,
+    new_root: &Path,
+    tracker: &ProgressTracker,
+    modal_action: &ModalAction,
+) -> Result<(), (PathBuf, std::io::Error)> {
+    std::fs::create_dir_all(to).map_err(|err| (from.to_path_buf(), err))?;
+    for entry in std::fs::read_dir(from).map_err(|err| (from.to_path_buf(), err))? {
+        if modal_action.has_requested_cancel() {
+            return Ok(());
+        }
+
+        let entry = entry.map_err(|err| (from.to_path_buf(), err))?;
+        let path = entry.path();
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type().map_err(|err| (path.clone(), err))?;
+
+        if file_type.is_symlink() {
+            let link = std::fs::read_link(&path).map_err(|err| (path.clone(), err))?;
+            let link = match link.strip_prefix(old_root) {
+                Ok(relative) => new_root.join(relative),
+                Err(_) => link,
+            };
+            create_symlink(&link, &target).map_err(|err| (path.clone(), err))?;
+            tracker.add_count(1);
+        } else if file_type.is_dir() {
+            copy_dir_with_progress_inner(&path, &target, old_root, new_root, tracker, modal_action)?;
+        } else {
+            std::fs::copy(&path, &target).map_err(|err| (path.clone(), err))?;
+            tracker.add_count(1);
+        }
+        tracker.notify();
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn create_symlink(original: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(original, link)
+}
+
+#[cfg(windows)]
+fn create_symlink(original: &Path, link: &Path) -> std::io::Result<()> {
+    if original.is_dir() {
+        junction::create(original, link)
+    } else {
+        std::os::windows::fs::symlink_file(original, link)
+    }
+}
+
+fn remove_existing(path: &Path) {
+    if std::fs::symlink_metadata(path).is_ok_and(|metadata| metadata.is_file()) {
+        _ = std::fs::remove_file(path);
+    }
+}
+
+// FICLONE is supported by btrfs, XFS and bcachefs
+#[cfg(target_os = "linux")]
+fn reflink(from: &Path, to: &Path) -> std::io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let source = std::fs::File::open(from)?;
+    let target = std::fs::File::options().write(true).create_new(true).open(to)?;
+
+    let result = unsafe { libc::ioctl(target.as_raw_fd(), libc::FICLONE, source.as_raw_fd()) };
+    if result != 0 {
+        let error = std::io::Error::last_os_error();
+        drop(target);
+        _ = std::fs::remove_file(to);
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+// clonefile is supported by APFS
+#[cfg(target_os = "macos")]
+fn reflink(from: &Path, to: &Path) -> std::io::Result<()> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let from = CString::new(from.as_os_str().as_bytes())?;
+    let to = CString::new(to.as_os_str().as_bytes())?;
+
+    let result = unsafe { libc::clonefile(from.as_ptr(), to.as_ptr(), 0) };
+    if result != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn reflink(_from: &Path, _to: &Path) -> std::io::Result<()> {
+    Err(std::io::ErrorKind::Unsupported.into())
+}
@@ -0,0 +1,33 @@
+use std::{collections::VecDeque, sync::Mutex};
+
+// Enough to cover a launch and whatever went wrong during it, without holding onto the whole session
+const MAX_CAPTURED_LINES: usize = 4000;
+
+static CAPTURED_LINES: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+// Keeps the most recent lines of the launcher log in memory so that they can be included in problem reports,
+// intended to be chained as an output of the logger after the lines have been formatted
+pub fn capture(record: &log::Record) {
+    let line = record.args().to_string();
+
+    let Ok(mut lines) = CAPTURED_LINES.lock() else {
+        return;
+    };
+    if lines.len() >= MAX_CAPTURED_LINES {
+        lines.pop_front();
+    }
+    lines.push_back(line);
+}
+
+pub(crate) fn captured_lines() -> String {
+    let Ok(lines) = CAPTURED_LINES.lock() else {
+        return String::new();
+    };
+
+    let mut joined = String::new();
+    for line in &*lines {
+        joined.push_str(line);
+        joined.push('\n');
+    }
+    joined
+}
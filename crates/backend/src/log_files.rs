@@ -0,0 +1,145 @@
+use std::{
+    fs::File, io::Write, path::{Path, PathBuf}, time::{Duration, SystemTime}
+};
+
+use chrono::NaiveDate;
+
+// A new file is started once the current one reaches this size, even if the day hasn't changed
+const MAX_LOG_FILE_SIZE: u64 = 10 * 1024 * 1024;
+const MAX_LOG_AGE: Duration = Duration::from_secs(60 * 60 * 24 * 14);
+const MAX_TOTAL_LOG_SIZE: u64 = 100 * 1024 * 1024;
+
+// Launcher log that starts a new file every day, or when the current file gets too large.
+// Old files are removed once they are older than two weeks or when there are too many of them
+pub struct RotatingLogFile {
+    directory: PathBuf,
+    date: NaiveDate,
+    index: usize,
+    file: Option<File>,
+    written: u64,
+}
+
+impl RotatingLogFile {
+    pub fn new(directory: PathBuf) -> std::io::Result<Self> {
+        std::fs::create_dir_all(&directory)?;
+        cleanup_log_files(&directory);
+
+        let mut log_file = Self {
+            directory,
+            date: chrono::Local::now().date_naive(),
+            index: 0,
+            file: None,
+            written: 0,
+        };
+        log_file.open()?;
+        Ok(log_file)
+    }
+
+    fn path(&self) -> PathBuf {
+        if self.index == 0 {
+            self.directory.join(format!("launcher-{}.log", self.date))
+        } else {
+            self.directory.join(format!("launcher-{}.{}.log", self.date, self.index))
+        }
+    }
+
+    fn open(&mut self) -> std::io::Result<()> {
+        self.file = None;
+
+        // Continue where the last launch today left off, unless that file is already full
+        let mut path = self.path();
+        while let Ok(metadata) = std::fs::metadata(&path) && metadata.len() >= MAX_LOG_FILE_SIZE {
+            self.index += 1;
+            path = self.path();
+        }
+
+        let file = File::options().create(true).append(true).open(&path)?;
+        self.written = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        self.file = Some(file);
+        Ok(())
+    }
+
+    fn rotate_if_needed(&mut self) {
+        let today = chrono::Local::now().date_naive();
+        if today != self.date {
+            self.date = today;
+            self.index = 0;
+        } else if self.written >= MAX_LOG_FILE_SIZE {
+            self.index += 1;
+        } else {
+            return;
+        }
+
+        cleanup_log_files(&self.directory);
+        if let Err(error) = self.open() {
+            eprintln!("Unable to open log file: {error}");
+        }
+    }
+}
+
+impl Write for RotatingLogFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.rotate_if_needed();
+
+        // Logging shouldn't fail just because the file couldn't be opened
+        let Some(file) = &mut self.file else {
+            return Ok(buf.len());
+        };
+        let written = file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if let Some(file) = &mut self.file {
+            file.flush()?;
+        }
+        Ok(())
+    }
+}
+
+// Raising the level takes effect immediately, the per-crate filters of the logger always allow debug
+pub fn set_debug_logging(enabled: bool) {
+    if enabled {
+        log::set_max_level(log::LevelFilter::Debug);
+        log::debug!("DEBUG logging enabled");
+    } else {
+        log::set_max_level(log::LevelFilter::Info);
+    }
+}
+
+// Most recently modified first
+pub(crate) fn list_log_files(directory: &Path) -> Vec<(PathBuf, SystemTime, u64)> {
+    let Ok(read_dir) = std::fs::read_dir(directory) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<_> = read_dir.filter_map(Result::ok)
+        .filter(|entry| {
+            let filename = entry.file_name();
+            let filename = filename.to_string_lossy();
+            filename.starts_with("launcher-") && filename.ends_with(".log")
+        })
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some((entry.path(), metadata.modified().ok()?, metadata.len()))
+        })
+        .collect();
+
+    files.sort_by_key(|(_, modified, _)| std::cmp::Reverse(*modified));
+    files
+}
+
+fn cleanup_log_files(directory: &Path) {
+    let now = SystemTime::now();
+    let mut total_size = 0;
+
+    for (path, modified, size) in list_log_files(directory) {
+        let too_old = now.duration_since(modified).is_ok_and(|age| age > MAX_LOG_AGE);
+
+        total_size += size;
+        if too_old || total_size > MAX_TOTAL_LOG_SIZE {
+            _ = std::fs::remove_file(path);
+        }
+    }
+}
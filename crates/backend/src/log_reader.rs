@@ -6,7 +6,7 @@ use std::{
 };
 
 use bridge::{
-    game_output::GameOutputLogLevel, handle::FrontendHandle, keep_alive::KeepAlive, message::MessageToFrontend,
+    game_output::GameOutputLogLevel, handle::FrontendHandle, instance::InstanceID, keep_alive::KeepAlive, message::MessageToFrontend,
 };
 use chrono::Utc;
 use memchr::memchr;
@@ -39,11 +39,11 @@ pub fn replace(string: &str) -> Cow<'_, str> {
     replaced
 }
 
-pub fn start_game_output(stdout: ChildStdout, stderr: Option<ChildStderr>, sender: FrontendHandle) {
+pub fn start_game_output(stdout: ChildStdout, stderr: Option<ChildStderr>, console: Option<InstanceID>, sender: FrontendHandle) -> usize {
     let id = GAME_OUTPUT_ID.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
     let keep_alive = KeepAlive::new();
     let keep_alive_handle = keep_alive.create_handle();
-    sender.send(MessageToFrontend::CreateGameOutputWindow { id, keep_alive });
+    sender.send(MessageToFrontend::CreateGameOutputWindow { id, keep_alive, console });
 
     if let Some(stderr) = stderr {
         let sender = sender.clone();
@@ -125,6 +125,18 @@ pub fn start_game_output(stdout: ChildStdout, stderr: Option<ChildStderr>, sende
             });
         }
     });
+
+    id
+}
+
+// Messages from the launcher itself, shown alongside the output of the game
+pub fn add_launcher_output(id: usize, level: GameOutputLogLevel, text: &str, sender: &FrontendHandle) {
+    sender.send(MessageToFrontend::AddGameOutput {
+        id,
+        time: Utc::now().timestamp_millis(),
+        level,
+        text: Arc::new([format!("(Pandora) {text}").into()]),
+    });
 }
 
 #[derive(Error, Debug)]
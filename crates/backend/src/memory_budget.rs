@@ -0,0 +1,137 @@
+use std::sync::OnceLock;
+
+use bridge::instance::InstanceID;
+use schema::{backend_config::MemoryBudgetMode, instance::InstanceConfiguration};
+
+use crate::BackendState;
+
+// Left for the operating system and the launcher itself when suggesting a smaller heap
+const RESERVED_SYSTEM_MEMORY_MIB: u64 = 2048;
+// Below this a reduced heap isn't worth suggesting, closing another instance is the better option
+const MIN_SUGGESTED_HEAP_MIB: u64 = 1024;
+
+// Total physical memory in MiB, None if it couldn't be determined
+pub fn physical_memory_mib() -> Option<u64> {
+    static PHYSICAL_MEMORY: OnceLock<Option<u64>> = OnceLock::new();
+    *PHYSICAL_MEMORY.get_or_init(|| detect_physical_memory().map(|bytes| bytes / 1024 / 1024))
+}
+
+#[cfg(unix)]
+fn detect_physical_memory() -> Option<u64> {
+    let (pages, page_size) = unsafe { (libc::sysconf(libc::_SC_PHYS_PAGES), libc::sysconf(libc::_SC_PAGESIZE)) };
+    if pages <= 0 || page_size <= 0 {
+        return None;
+    }
+    Some(pages as u64 * page_size as u64)
+}
+
+#[cfg(windows)]
+fn detect_physical_memory() -> Option<u64> {
+    #[repr(C)]
+    struct MemoryStatusEx {
+        length: u32,
+        memory_load: u32,
+        total_phys: u64,
+        avail_phys: u64,
+        total_page_file: u64,
+        avail_page_file: u64,
+        total_virtual: u64,
+        avail_virtual: u64,
+        avail_extended_virtual: u64,
+    }
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn GlobalMemoryStatusEx(buffer: *mut MemoryStatusEx) -> i32;
+    }
+
+    let mut status: MemoryStatusEx = unsafe { std::mem::zeroed() };
+    status.length = std::mem::size_of::<MemoryStatusEx>() as u32;
+    if unsafe { GlobalMemoryStatusEx(&mut status) } == 0 {
+        return None;
+    }
+    Some(status.total_phys)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn detect_physical_memory() -> Option<u64> {
+    None
+}
+
+// The largest heap the instance can grow to. Without -Xmx the JVM defaults to a quarter of physical memory
+fn max_heap_mib(configuration: &InstanceConfiguration, physical_mib: u64) -> u64 {
+    match configuration.memory {
+        Some(memory) if memory.enabled => memory.max.max(memory.min).max(128) as u64,
+        _ => physical_mib / 4,
+    }
+}
+
+impl BackendState {
+    // Checks the concurrent launch limit and memory budget before launching an instance. Returns an error if the
+    // launch should be blocked, exceeding the memory budget in warn mode only sends a warning
+    pub fn check_launch_budget(&self, id: InstanceID) -> Result<(), String> {
+        let (max_running_instances, memory_budget, instance_defaults) = {
+            let mut config = self.config.write();
+            let config = config.get();
+            (config.max_running_instances, config.memory_budget, config.instance_defaults.clone())
+        };
+        let physical_mib = physical_memory_mib();
+
+        let mut running = 0;
+        let mut running_heap_mib = 0;
+        let mut heap_mib = 0;
+        for instance in self.instance_state.write().instances.iter_mut() {
+            let is_target = instance.id == id;
+            if !is_target && instance.child.is_none() {
+                continue;
+            }
+
+            let heap = physical_mib.map(|physical_mib| {
+                let configuration = instance.configuration.get().clone().with_defaults(&instance_defaults);
+                max_heap_mib(&configuration, physical_mib)
+            }).unwrap_or(0);
+
+            if is_target {
+                heap_mib = heap;
+            } else {
+                running += 1;
+                running_heap_mib += heap;
+            }
+        }
+
+        if let Some(max_running_instances) = max_running_instances && running >= max_running_instances {
+            return Err(format!("Only {max_running_instances} instances can run at the same time, close one before launching another"));
+        }
+
+        if memory_budget == MemoryBudgetMode::Ignore {
+            return Ok(());
+        }
+        let Some(physical_mib) = physical_mib else {
+            return Ok(());
+        };
+        if running_heap_mib + heap_mib <= physical_mib {
+            return Ok(());
+        }
+
+        let mut message = format!(
+            "Running this instance would allow games to use up to {} MiB of memory, but this computer only has {physical_mib} MiB",
+            running_heap_mib + heap_mib
+        );
+        // Round down to a multiple of 256 MiB, the same step used by the memory inputs
+        let suggested_mib = physical_mib.saturating_sub(running_heap_mib + RESERVED_SYSTEM_MEMORY_MIB) / 256 * 256;
+        if suggested_mib >= MIN_SUGGESTED_HEAP_MIB && suggested_mib < heap_mib {
+            message.push_str(&format!(". Lower its maximum memory to {suggested_mib} MiB or close another instance"));
+        } else if running > 0 {
+            message.push_str(". Close another instance first");
+        }
+
+        log::warn!("{message}");
+        match memory_budget {
+            MemoryBudgetMode::Block => Err(message),
+            MemoryBudgetMode::Warn | MemoryBudgetMode::Ignore => {
+                self.send.send_warning(message);
+                Ok(())
+            },
+        }
+    }
+}
@@ -1,12 +1,13 @@
 use std::{
-    borrow::Cow, fmt::Debug, path::{Path, PathBuf}, sync::Arc
+    borrow::Cow, fmt::Debug, path::{Path, PathBuf}, sync::Arc, time::{Duration, SystemTime}
 };
 
 use reqwest::RequestBuilder;
 use schema::{
-    assets_index::AssetsIndex, fabric_launch::FabricLaunch, fabric_loader_manifest::{FABRIC_LOADER_MANIFEST_URL, FabricLoaderManifest}, forge::{ForgeMavenManifest, NeoforgeMavenManifest, VersionFragment}, java_runtime_component::JavaRuntimeComponentManifest, java_runtimes::{JAVA_RUNTIMES_URL, JavaRuntimes}, maven::MavenMetadataXml, modrinth::{MODRINTH_SEARCH_URL, ModrinthLoader, ModrinthProjectVersion, ModrinthProjectVersionsRequest, ModrinthProjectVersionsResult, ModrinthSearchRequest, ModrinthSearchResult, ModrinthVersionFileUpdateResult}, version::MinecraftVersion, version_manifest::{MOJANG_VERSION_MANIFEST_URL, MinecraftVersionLink, MinecraftVersionManifest}
+    assets_index::AssetsIndex, fabric_launch::FabricLaunch, fabric_loader_manifest::{FABRIC_LOADER_MANIFEST_URL, FabricLoaderManifest}, forge::{ForgeMavenManifest, NeoforgeMavenManifest, VersionFragment}, java_runtime_component::JavaRuntimeComponentManifest, java_runtimes::{JAVA_RUNTIMES_URL, JavaRuntimes}, maven::MavenMetadataXml, patch_notes::{JAVA_PATCH_NOTES_URL, JavaPatchNote, JavaPatchNotes, LAUNCHER_CONTENT_URL}, modrinth::{MODRINTH_SEARCH_URL, ModrinthLoader, ModrinthProject, ModrinthProjectDependencies, ModrinthProjectVersion, ModrinthProjectVersionsRequest, ModrinthProjectVersionsResult, ModrinthSearchRequest, ModrinthSearchResult, ModrinthVersionFileUpdateResult}, version::MinecraftVersion, version_manifest::{MOJANG_VERSION_MANIFEST_URL, MinecraftVersionLink, MinecraftVersionManifest}
 };
 use serde::Serialize;
+use sha1::{Digest, Sha1};
 use ustr::Ustr;
 
 use crate::metadata::manager::{MetaLoadError, MetaLoadStateWrapper, MetadataManager, MetadataManagerStates};
@@ -27,6 +28,10 @@ pub trait MetadataItem: Debug {
     fn data_hash(&self) -> Option<Ustr> {
         None
     }
+    // Requests to the Modrinth api are sent with the token of the signed in Modrinth account, for higher rate limits
+    fn modrinth_api(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Debug)]
@@ -83,6 +88,66 @@ impl MetadataItem for MojangJavaRuntimesMetadataItem {
     }
 }
 
+#[derive(Debug)]
+pub struct JavaPatchNotesMetadataItem;
+
+impl MetadataItem for JavaPatchNotesMetadataItem {
+    type T = JavaPatchNotes;
+
+    fn request(&self, client: &reqwest::Client) -> RequestBuilder {
+        client.get(JAVA_PATCH_NOTES_URL)
+    }
+
+    fn expires(&self) -> bool {
+        true
+    }
+
+    fn cache_file(&self, metadata_manager: &MetadataManager) -> Option<impl AsRef<Path> + Send + Sync + 'static> {
+        Some(metadata_manager.metadata_cache.join("java_patch_notes.json"))
+    }
+
+    fn state(&self, states: &mut MetadataManagerStates) -> MetaLoadStateWrapper<Self::T> {
+        states.java_patch_notes.clone()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self::T, MetaLoadError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+// Patch notes of a single version, these don't change once they are published
+#[derive(Debug)]
+pub struct JavaPatchNoteMetadataItem<'a>(pub &'a Arc<str>);
+
+impl<'a> MetadataItem for JavaPatchNoteMetadataItem<'a> {
+    type T = JavaPatchNote;
+
+    fn request(&self, client: &reqwest::Client) -> RequestBuilder {
+        client.get(format!("{LAUNCHER_CONTENT_URL}/v2/{}", self.0))
+    }
+
+    fn expires(&self) -> bool {
+        false
+    }
+
+    fn cache_file(&self, metadata_manager: &MetadataManager) -> Option<impl AsRef<Path> + Send + Sync + 'static> {
+        if !crate::is_single_component_path(self.0) {
+            return None;
+        }
+        let mut path = metadata_manager.metadata_cache.join("patch_notes");
+        path.push(&**self.0);
+        Some(path)
+    }
+
+    fn state(&self, states: &mut MetadataManagerStates) -> MetaLoadStateWrapper<Self::T> {
+        states.java_patch_note.entry(self.0.clone()).or_default().clone()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self::T, MetaLoadError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
 #[derive(Debug)]
 pub struct MinecraftVersionMetadataItem<'v>(pub &'v MinecraftVersionLink);
 
@@ -250,6 +315,68 @@ impl MetadataItem for FabricLaunchMetadataItem {
     }
 }
 
+fn modrinth_cache_file(metadata_manager: &MetadataManager, kind: &str, key: &(impl Serialize + ?Sized)) -> Option<PathBuf> {
+    let key = serde_json::to_vec(key).ok()?;
+    let mut hasher = Sha1::new();
+    hasher.update(&key);
+    let hash = hex::encode(hasher.finalize());
+
+    let mut path = metadata_manager.metadata_cache.join("modrinth");
+    path.push(kind);
+    path.push(hash);
+    Some(path)
+}
+
+// Every search query gets its own file, so the Modrinth cache is pruned on startup
+const MODRINTH_CACHE_MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+const MODRINTH_CACHE_MAX_FILES: usize = 1000;
+
+// Removes cached Modrinth responses that are older than a week, and the oldest ones once a kind has too many.
+// Validators are removed together with the response they belong to
+pub fn prune_modrinth_cache(metadata_cache: &Path) {
+    let Ok(kinds) = std::fs::read_dir(metadata_cache.join("modrinth")) else {
+        return;
+    };
+
+    let now = SystemTime::now();
+    for kind in kinds.flatten() {
+        let Ok(entries) = std::fs::read_dir(kind.path()) else {
+            continue;
+        };
+
+        let mut files = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some() {
+                // Validators without a response are left over from an earlier prune
+                let mut response = path.clone();
+                response.set_extension("");
+                response.set_extension("");
+                if !response.exists() {
+                    _ = std::fs::remove_file(&path);
+                }
+                continue;
+            }
+            let Ok(modified) = entry.metadata().and_then(|metadata| metadata.modified()) else {
+                continue;
+            };
+            files.push((path, modified));
+        }
+
+        files.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+        for (index, (path, modified)) in files.into_iter().enumerate() {
+            let too_old = now.duration_since(modified).is_ok_and(|age| age > MODRINTH_CACHE_MAX_AGE);
+            if too_old || index >= MODRINTH_CACHE_MAX_FILES {
+                _ = std::fs::remove_file(&path);
+                let mut validators = path;
+                validators.add_extension("validators");
+                validators.add_extension("json");
+                _ = std::fs::remove_file(validators);
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ModrinthSearchMetadataItem<'a>(pub &'a ModrinthSearchRequest);
 
@@ -264,6 +391,14 @@ impl<'a> MetadataItem for ModrinthSearchMetadataItem<'a> {
         true
     }
 
+    fn modrinth_api(&self) -> bool {
+        true
+    }
+
+    fn cache_file(&self, metadata_manager: &MetadataManager) -> Option<impl AsRef<Path> + Send + Sync + 'static> {
+        modrinth_cache_file(metadata_manager, "search", self.0)
+    }
+
     fn state(&self, states: &mut MetadataManagerStates) -> MetaLoadStateWrapper<Self::T> {
         states.modrinth_search.entry(self.0.clone()).or_default().clone()
     }
@@ -295,6 +430,14 @@ impl<'a> MetadataItem for ModrinthProjectVersionsMetadataItem<'a> {
         true
     }
 
+    fn modrinth_api(&self) -> bool {
+        true
+    }
+
+    fn cache_file(&self, metadata_manager: &MetadataManager) -> Option<impl AsRef<Path> + Send + Sync + 'static> {
+        modrinth_cache_file(metadata_manager, "project_versions", self.0)
+    }
+
     fn state(&self, states: &mut MetadataManagerStates) -> MetaLoadStateWrapper<Self::T> {
         states.modrinth_project_versions.entry(self.0.clone()).or_default().clone()
     }
@@ -304,6 +447,70 @@ impl<'a> MetadataItem for ModrinthProjectVersionsMetadataItem<'a> {
     }
 }
 
+#[derive(Debug)]
+pub struct ModrinthProjectMetadataItem<'a>(pub &'a Arc<str>);
+
+impl<'a> MetadataItem for ModrinthProjectMetadataItem<'a> {
+    type T = ModrinthProject;
+
+    fn request(&self, client: &reqwest::Client) -> RequestBuilder {
+        let url = format!("https://api.modrinth.com/v2/project/{}", self.0);
+        client.get(url)
+    }
+
+    fn expires(&self) -> bool {
+        true
+    }
+
+    fn modrinth_api(&self) -> bool {
+        true
+    }
+
+    fn cache_file(&self, metadata_manager: &MetadataManager) -> Option<impl AsRef<Path> + Send + Sync + 'static> {
+        modrinth_cache_file(metadata_manager, "project", &**self.0)
+    }
+
+    fn state(&self, states: &mut MetadataManagerStates) -> MetaLoadStateWrapper<Self::T> {
+        states.modrinth_projects.entry(self.0.clone()).or_default().clone()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self::T, MetaLoadError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+#[derive(Debug)]
+pub struct ModrinthProjectDependenciesMetadataItem<'a>(pub &'a Arc<str>);
+
+impl<'a> MetadataItem for ModrinthProjectDependenciesMetadataItem<'a> {
+    type T = ModrinthProjectDependencies;
+
+    fn request(&self, client: &reqwest::Client) -> RequestBuilder {
+        let url = format!("https://api.modrinth.com/v2/project/{}/dependencies", self.0);
+        client.get(url)
+    }
+
+    fn expires(&self) -> bool {
+        true
+    }
+
+    fn modrinth_api(&self) -> bool {
+        true
+    }
+
+    fn cache_file(&self, metadata_manager: &MetadataManager) -> Option<impl AsRef<Path> + Send + Sync + 'static> {
+        modrinth_cache_file(metadata_manager, "project_dependencies", &**self.0)
+    }
+
+    fn state(&self, states: &mut MetadataManagerStates) -> MetaLoadStateWrapper<Self::T> {
+        states.modrinth_project_dependencies.entry(self.0.clone()).or_default().clone()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self::T, MetaLoadError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
 #[derive(Debug)]
 pub struct ModrinthVersionMetadataItem(pub Arc<str>);
 
@@ -319,6 +526,14 @@ impl MetadataItem for ModrinthVersionMetadataItem {
         true
     }
 
+    fn modrinth_api(&self) -> bool {
+        true
+    }
+
+    fn cache_file(&self, metadata_manager: &MetadataManager) -> Option<impl AsRef<Path> + Send + Sync + 'static> {
+        modrinth_cache_file(metadata_manager, "version", &*self.0)
+    }
+
     fn state(&self, states: &mut MetadataManagerStates) -> MetaLoadStateWrapper<Self::T> {
         states.modrinth_versions.entry(self.0.clone()).or_default().clone()
     }
@@ -352,6 +567,10 @@ impl MetadataItem for ModrinthVersionUpdateMetadataItem {
         true
     }
 
+    fn modrinth_api(&self) -> bool {
+        true
+    }
+
     fn state(&self, states: &mut MetadataManagerStates) -> MetaLoadStateWrapper<Self::T> {
         states.modrinth_version_updates.entry(self.sha1.clone()).or_default().clone()
     }
@@ -391,6 +610,10 @@ impl MetadataItem for ModrinthV3VersionUpdateMetadataItem {
         true
     }
 
+    fn modrinth_api(&self) -> bool {
+        true
+    }
+
     fn state(&self, states: &mut MetadataManagerStates) -> MetaLoadStateWrapper<Self::T> {
         states.modrinth_version_updates.entry(self.sha1.clone()).or_default().clone()
     }
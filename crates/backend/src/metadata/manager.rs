@@ -1,18 +1,18 @@
 use std::{
-    collections::{HashMap, VecDeque}, fmt::Display, path::Path, sync::Arc, time::{Duration, Instant}
+    collections::{HashMap, VecDeque}, fmt::Display, path::{Path, PathBuf}, sync::{atomic::{AtomicBool, Ordering}, Arc}, time::{Duration, Instant}
 };
 
 use bridge::keep_alive::{KeepAlive, KeepAliveHandle};
-use reqwest::StatusCode;
+use reqwest::{header::{AUTHORIZATION, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED}, StatusCode};
 use schema::{
-    assets_index::AssetsIndex, fabric_launch::FabricLaunch, fabric_loader_manifest::FabricLoaderManifest, forge::{ForgeMavenManifest, NeoforgeMavenManifest}, java_runtime_component::JavaRuntimeComponentManifest, java_runtimes::JavaRuntimes, maven::MavenMetadataXml, modrinth::{ModrinthProjectVersion, ModrinthProjectVersionsRequest, ModrinthProjectVersionsResult, ModrinthSearchRequest, ModrinthSearchResult, ModrinthVersionFileUpdateResult}, version::MinecraftVersion, version_manifest::MinecraftVersionManifest
+    assets_index::AssetsIndex, fabric_launch::FabricLaunch, fabric_loader_manifest::FabricLoaderManifest, forge::{ForgeMavenManifest, NeoforgeMavenManifest}, java_runtime_component::JavaRuntimeComponentManifest, java_runtimes::JavaRuntimes, maven::MavenMetadataXml, patch_notes::{JavaPatchNote, JavaPatchNotes}, modrinth::{ModrinthProject, ModrinthProjectDependencies, ModrinthProjectVersion, ModrinthProjectVersionsRequest, ModrinthProjectVersionsResult, ModrinthSearchRequest, ModrinthSearchResult, ModrinthVersionFileUpdateResult}, version::MinecraftVersion, version_manifest::MinecraftVersionManifest
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use sha1::{Digest, Sha1};
 use tokio::task::JoinHandle;
 use ustr::Ustr;
 
-use crate::metadata::items::MetadataItem;
+use crate::{download_metrics::InFlightDownload, metadata::items::MetadataItem};
 
 const DATA_TTL: Duration = Duration::from_secs(5 * 60);
 
@@ -31,8 +31,12 @@ pub struct MetadataManagerStates {
     pub(super) java_runtime_manifests: HashMap<Ustr, MetaLoadStateWrapper<JavaRuntimeComponentManifest>>,
     pub(super) modrinth_search: HashMap<ModrinthSearchRequest, MetaLoadStateWrapper<ModrinthSearchResult>>,
     pub(super) modrinth_project_versions: HashMap<ModrinthProjectVersionsRequest, MetaLoadStateWrapper<ModrinthProjectVersionsResult>>,
+    pub(super) modrinth_projects: HashMap<Arc<str>, MetaLoadStateWrapper<ModrinthProject>>,
+    pub(super) modrinth_project_dependencies: HashMap<Arc<str>, MetaLoadStateWrapper<ModrinthProjectDependencies>>,
     pub(super) modrinth_versions: HashMap<Arc<str>, MetaLoadStateWrapper<ModrinthProjectVersion>>,
     pub(super) modrinth_version_updates: HashMap<Arc<str>, MetaLoadStateWrapper<ModrinthVersionFileUpdateResult>>,
+    pub(super) java_patch_notes: MetaLoadStateWrapper<JavaPatchNotes>,
+    pub(super) java_patch_note: HashMap<Arc<str>, MetaLoadStateWrapper<JavaPatchNote>>,
 }
 
 pub struct MetadataManager {
@@ -48,6 +52,9 @@ pub struct MetadataManager {
     expiring: tokio::sync::Mutex<VecDeque<(Instant, KeepAlive)>>,
 
     http_client: reqwest::Client,
+    modrinth_token: parking_lot::RwLock<Option<Arc<str>>>,
+    // Set when Modrinth answered a request with the token as unauthorized, see `take_modrinth_token_rejected`
+    modrinth_token_rejected: Arc<AtomicBool>,
 }
 
 #[derive(thiserror::Error, Clone, Debug)]
@@ -135,6 +142,37 @@ impl From<tokio::task::JoinError> for MetaLoadError {
     }
 }
 
+// HTTP validators stored next to cache files so that unchanged data can be revalidated
+// with a conditional request instead of being downloaded again
+#[derive(Serialize, Deserialize, Default, Debug)]
+struct CacheValidators {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<String>,
+}
+
+impl CacheValidators {
+    fn path_for(cache_file: &Path) -> PathBuf {
+        let mut path = cache_file.to_path_buf();
+        path.add_extension("validators");
+        path.add_extension("json");
+        path
+    }
+
+    fn from_headers(headers: &reqwest::header::HeaderMap) -> Self {
+        let get = |name| headers.get(name).and_then(|value: &reqwest::header::HeaderValue| value.to_str().ok()).map(str::to_string);
+        Self {
+            etag: get(ETAG),
+            last_modified: get(LAST_MODIFIED),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
 #[derive(Default)]
 pub enum MetaLoadState<T> {
     #[default]
@@ -159,6 +197,28 @@ impl MetadataManager {
             expiring: Default::default(),
 
             http_client,
+            modrinth_token: Default::default(),
+            modrinth_token_rejected: Default::default(),
+        }
+    }
+
+    pub fn set_modrinth_token(&self, token: Option<Arc<str>>) {
+        *self.modrinth_token.write() = token;
+        self.modrinth_token_rejected.store(false, Ordering::Relaxed);
+    }
+
+    pub fn modrinth_token(&self) -> Option<Arc<str>> {
+        self.modrinth_token.read().clone()
+    }
+
+    // Returns true once after Modrinth rejected the token, e.g. because it expired or was revoked. The token is
+    // forgotten so later requests aren't sent with it, the account has to sign in again
+    pub fn take_modrinth_token_rejected(&self) -> bool {
+        if self.modrinth_token_rejected.swap(false, Ordering::Relaxed) {
+            *self.modrinth_token.write() = None;
+            true
+        } else {
+            false
         }
     }
 
@@ -194,10 +254,23 @@ impl MetadataManager {
                 item,
                 cache_file,
                 &self.http_client,
+                self.modrinth_token(),
+                &self.modrinth_token_rejected,
             );
         }
     }
 
+    // Stops a download that nobody is waiting for anymore, the next fetch will start a new one
+    pub async fn cancel<I: MetadataItem>(&self, item: &I) {
+        let wrapper = item.state(&mut *self.states.lock().await);
+        let mut wrapper = wrapper.lock().await;
+
+        if let MetaLoadState::Pending(join_handle) = &wrapper.1 {
+            join_handle.abort();
+            wrapper.1 = MetaLoadState::Unloaded;
+        }
+    }
+
     pub async fn fetch<I: MetadataItem>(&self, item: &I) -> Result<Arc<<I as MetadataItem>::T>, MetaLoadError> {
         self.fetch_with_keepalive(item, false).await.0
     }
@@ -221,6 +294,8 @@ impl MetadataManager {
                 item,
                 cache_file,
                 &self.http_client,
+                self.modrinth_token(),
+                &self.modrinth_token_rejected,
             );
         }
 
@@ -255,10 +330,14 @@ impl MetadataManager {
         item: &I,
         cache_file: Option<impl AsRef<Path> + Send + Sync + 'static>,
         http_client: &reqwest::Client,
+        modrinth_token: Option<Arc<str>>,
+        modrinth_token_rejected: &Arc<AtomicBool>,
     ) {
         log::debug!("Loading metadata {:?}", item);
 
         let request = item.request(http_client);
+        let modrinth_token = modrinth_token.filter(|_| item.modrinth_api());
+        let modrinth_token_rejected = modrinth_token_rejected.clone();
         let expected_hash = item.data_hash().and_then(|sha1| {
             let mut expected_hash = [0u8; 20];
             hex::decode_to_slice(sha1.as_str(), &mut expected_hash).ok()?;
@@ -309,10 +388,43 @@ impl MetadataManager {
                 }
             }
 
+            let validators_file = cache_file.as_ref().map(|cache_file| CacheValidators::path_for(cache_file.as_ref()));
+            let not_modified_fallback = file_fallback.clone();
+
             let mut result: Result<Arc<I::T>, MetaLoadError> = async move {
-                let response = request.send().await?;
+                let mut request = request;
+                if not_modified_fallback.is_some() && let Some(validators_file) = &validators_file {
+                    let validators: CacheValidators = crate::read_json(validators_file).unwrap_or_default();
+                    if let Some(etag) = &validators.etag {
+                        request = request.header(IF_NONE_MATCH, etag);
+                    }
+                    if let Some(last_modified) = &validators.last_modified {
+                        request = request.header(IF_MODIFIED_SINCE, last_modified);
+                    }
+                }
+
+                // The request is kept without the token so it can be sent again if Modrinth rejects the token
+                let unauthenticated_request = if let Some(token) = &modrinth_token {
+                    let unauthenticated_request = request.try_clone();
+                    request = request.header(AUTHORIZATION, &**token);
+                    unauthenticated_request
+                } else {
+                    None
+                };
+
+                let in_flight = InFlightDownload::start();
+                let mut response = request.send().await?;
+                if response.status() == StatusCode::UNAUTHORIZED && let Some(unauthenticated_request) = unauthenticated_request {
+                    log::warn!("Modrinth rejected the token of the signed in account, retrying without it");
+                    modrinth_token_rejected.store(true, Ordering::Relaxed);
+                    response = unauthenticated_request.send().await?;
+                }
 
                 let status = response.status();
+                if status == StatusCode::NOT_MODIFIED && let Some(fallback) = not_modified_fallback {
+                    log::debug!("Cached metadata {:?} is still valid", std::any::type_name::<I::T>());
+                    return Ok(fallback);
+                }
                 if status != StatusCode::OK {
                     if status == StatusCode::BAD_REQUEST {
                         if let Ok(bytes) = response.bytes().await {
@@ -334,7 +446,11 @@ impl MetadataManager {
                     return Err(MetaLoadError::NonOK(status.as_u16()));
                 }
 
+                let validators = CacheValidators::from_headers(response.headers());
+
                 let bytes = response.bytes().await?;
+                crate::download_metrics::add_downloaded_bytes(bytes.len());
+                drop(in_flight);
                 let bytes = I::post_process_download(&bytes)?;
 
                 // We try to decode before checking the hash because it's a more
@@ -360,6 +476,14 @@ impl MetadataManager {
                         let _ = tokio::fs::create_dir_all(parent).await;
                     }
                     let _ = tokio::fs::write(cache_file, bytes).await;
+
+                    if let Some(validators_file) = &validators_file {
+                        if validators.is_empty() {
+                            let _ = tokio::fs::remove_file(validators_file).await;
+                        } else if let Ok(validators) = serde_json::to_vec(&validators) {
+                            let _ = tokio::fs::write(validators_file, validators).await;
+                        }
+                    }
                 }
 
                 Ok(Arc::new(meta))
@@ -2,14 +2,14 @@ use std::{
     io::{BufRead, Cursor, Read, Write}, path::{Path, PathBuf}, sync::Arc
 };
 
-use bridge::{instance::{AtomicContentUpdateStatus, ContentUpdateStatus, ContentType, ContentSummary}, safe_path::SafePath};
+use bridge::{instance::{AtomicContentUpdateStatus, ContentDependency, ContentDependencyKind, ContentUpdateStatus, ContentType, ContentSummary}, safe_path::SafePath};
 use image::imageops::FilterType;
 use indexmap::IndexMap;
 use parking_lot::{RwLock, RwLockReadGuard};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use rc_zip_sync::EntryHandle;
+use rc_zip_sync::{EntryHandle, ReadZip};
 use rustc_hash::{FxHashMap, FxHashSet};
-use schema::{content::ContentSource, fabric_mod::{FabricModJson, Icon, Person}, forge_mod::{JarJarMetadata, McModInfo, ModsToml}, modrinth::{ModrinthFile, ModrinthSideRequirement}, mrpack::ModrinthIndexJson, resourcepack::PackMcmeta};
+use schema::{content::ContentSource, fabric_mod::{FabricModJson, Icon, Person, VersionPredicates}, forge_mod::{JarJarMetadata, McModInfo, ModsToml, ModsTomlDependencyType}, modrinth::{ModrinthFile, ModrinthSideRequirement}, mrpack::ModrinthIndexJson, quilt_mod::{QuiltModJson, QuiltProvides}, resourcepack::PackMcmeta};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DeserializeAs};
 use sha1::{Digest, Sha1};
@@ -160,6 +160,8 @@ impl ModMetadataManager {
             self.load_legacy_forge_mod(hash, &archive, file)
         } else if let Some(file) = archive.by_name("fabric.mod.json") {
             self.load_fabric_mod(hash, &archive, file)
+        } else if let Some(file) = archive.by_name("quilt.mod.json") {
+            self.load_quilt_mod(hash, &archive, file)
         } else if let Some(file) = archive.by_name("META-INF/mods.toml") {
             self.load_forge_mod(hash, &archive, file, ContentType::Forge)
         } else if let Some(file) = archive.by_name("META-INF/neoforge.mods.toml") {
@@ -178,45 +180,38 @@ impl ModMetadataManager {
     }
 
     fn load_fabric_mod<R: rc_zip_sync::HasCursor>(self: &Arc<Self>, hash: [u8; 20], archive: &rc_zip_sync::ArchiveHandle<R>, file: EntryHandle<'_, R>) -> Option<Arc<ContentSummary>> {
-        let mut bytes = file.bytes().ok()?;
-
-        // Some mods violate the JSON spec by using raw newline characters inside strings (e.g. BetterGrassify)
-        for byte in bytes.iter_mut() {
-            if *byte == '\n' as u8 {
-                *byte = ' ' as u8;
-            }
-        }
-
-        let fabric_mod_json: FabricModJson = serde_json::from_slice(&bytes).inspect_err(|e| {
-            log::error!("Error parsing fabric.mod.json: {e}");
-        }).ok()?;
+        let fabric_mod_json = parse_fabric_mod_json(file.bytes().ok()?)?;
 
         drop(file);
 
         let name = fabric_mod_json.name.unwrap_or_else(|| Arc::clone(&fabric_mod_json.id));
 
-        let icon = match fabric_mod_json.icon {
-            Some(icon) => match icon {
-                Icon::Single(icon) => Some(icon),
-                Icon::Sizes(hash_map) => {
-                    const DESIRED_SIZE: usize = 64;
-                    hash_map.iter().min_by_key(|size| size.0.abs_diff(DESIRED_SIZE)).map(|e| Arc::clone(e.1))
-                },
-            },
-            None => None,
-        };
-
         let mut png_icon: Option<Arc<[u8]>> = None;
-        if let Some(icon) = icon && let Some(icon_file) = archive.by_name(&icon) {
+        if let Some(icon) = fabric_mod_json.icon.and_then(select_icon) && let Some(icon_file) = archive.by_name(&icon) {
             png_icon = load_icon(icon_file);
         }
 
-        let authors = if let Some(authors) = fabric_mod_json.authors && let Some(authors) = create_authors_string(&authors) {
+        let authors = if let Some(authors) = fabric_mod_json.authors && let Some(authors) = create_authors_string(authors.iter().map(Person::name)) {
             authors.into()
         } else {
             "".into()
         };
 
+        let dependencies = [
+            (fabric_mod_json.depends, ContentDependencyKind::Required),
+            (fabric_mod_json.recommends, ContentDependencyKind::Optional),
+            (fabric_mod_json.breaks, ContentDependencyKind::Incompatible),
+        ].into_iter().flat_map(|(dependencies, kind)| {
+            dependencies.into_iter().map(move |(id, predicates)| ContentDependency {
+                id,
+                version_range: fabric_version_range(&predicates),
+                kind,
+            })
+        }).collect();
+
+        let mut provides = fabric_mod_json.provides;
+        load_nested_ids(archive, fabric_mod_json.jars.into_iter().map(|jar| jar.file).collect(), &mut provides, 0);
+
         Some(Arc::new(ContentSummary {
             id: Some(fabric_mod_json.id),
             hash,
@@ -224,6 +219,63 @@ impl ModMetadataManager {
             authors,
             version_str: format!("v{}", fabric_mod_json.version).into(),
             png_icon,
+            description: fabric_mod_json.description,
+            dependencies,
+            provides: provides.into(),
+            update_status: Arc::new(AtomicContentUpdateStatus::new(ContentUpdateStatus::Unknown)),
+            extra: ContentType::Fabric
+        }))
+    }
+
+    fn load_quilt_mod<R: rc_zip_sync::HasCursor>(self: &Arc<Self>, hash: [u8; 20], archive: &rc_zip_sync::ArchiveHandle<R>, file: EntryHandle<'_, R>) -> Option<Arc<ContentSummary>> {
+        let bytes = file.bytes().ok()?;
+
+        let quilt_mod_json: QuiltModJson = serde_json::from_slice(&bytes).inspect_err(|e| {
+            log::error!("Error parsing quilt.mod.json: {e}");
+        }).ok()?;
+
+        drop(file);
+
+        let loader = quilt_mod_json.quilt_loader;
+        let metadata = loader.metadata;
+        let name = metadata.name.unwrap_or_else(|| Arc::clone(&loader.id));
+
+        let mut png_icon: Option<Arc<[u8]>> = None;
+        if let Some(icon) = metadata.icon.and_then(select_icon) && let Some(icon_file) = archive.by_name(&icon) {
+            png_icon = load_icon(icon_file);
+        }
+
+        let authors = create_authors_string(metadata.contributors.keys().map(|name| &**name)).unwrap_or_default();
+
+        let required = loader.depends.into_iter().map(|dependency| {
+            let kind = if dependency.optional() {
+                ContentDependencyKind::Optional
+            } else {
+                ContentDependencyKind::Required
+            };
+            (dependency, kind)
+        });
+        let breaks = loader.breaks.into_iter().map(|dependency| (dependency, ContentDependencyKind::Incompatible));
+        let dependencies = required.chain(breaks).map(|(dependency, kind)| ContentDependency {
+            id: dependency.id().clone(),
+            version_range: dependency.versions().and_then(fabric_version_range),
+            kind,
+        }).collect();
+
+        let mut provides: Vec<Arc<str>> = loader.provides.into_iter().map(QuiltProvides::id).collect();
+        load_nested_ids(archive, loader.jars, &mut provides, 0);
+
+        // The launcher has no Quilt loader, Quilt mods are treated like Fabric mods
+        Some(Arc::new(ContentSummary {
+            id: Some(loader.id),
+            hash,
+            name: Some(name),
+            authors: authors.into(),
+            version_str: format!("v{}", loader.version).into(),
+            png_icon,
+            description: metadata.description,
+            dependencies,
+            provides: provides.into(),
             update_status: Arc::new(AtomicContentUpdateStatus::new(ContentUpdateStatus::Unknown)),
             extra: ContentType::Fabric
         }))
@@ -269,6 +321,23 @@ impl ModMetadataManager {
             }
         }
 
+        let dependencies = mods_toml.dependencies.get(&first.mod_id).map(|dependencies| {
+            dependencies.iter().map(|dependency| {
+                let kind = match dependency.r#type {
+                    Some(ModsTomlDependencyType::Required) => ContentDependencyKind::Required,
+                    Some(ModsTomlDependencyType::Incompatible | ModsTomlDependencyType::Discouraged) => ContentDependencyKind::Incompatible,
+                    Some(ModsTomlDependencyType::Optional | ModsTomlDependencyType::Unknown) => ContentDependencyKind::Optional,
+                    None if dependency.mandatory.unwrap_or(false) => ContentDependencyKind::Required,
+                    None => ContentDependencyKind::Optional,
+                };
+                ContentDependency {
+                    id: dependency.mod_id.clone(),
+                    version_range: dependency.version_range.clone().filter(|range| !range.is_empty()),
+                    kind,
+                }
+            }).collect()
+        }).unwrap_or_default();
+
         Some(Arc::new(ContentSummary {
             id: Some(first.mod_id.clone()),
             hash,
@@ -276,6 +345,9 @@ impl ModMetadataManager {
             authors,
             version_str: version.into(),
             png_icon,
+            description: first.description.as_deref().map(|description| description.trim().into()),
+            dependencies,
+            provides: mods_toml.mods.iter().skip(1).map(|other| other.mod_id.clone()).collect(),
             update_status: Arc::new(AtomicContentUpdateStatus::new(ContentUpdateStatus::Unknown)),
             extra,
         }))
@@ -300,7 +372,7 @@ impl ModMetadataManager {
         }
 
         let authors = if let Some(authors) = &first.author_list {
-            create_authors_string(authors.as_slice()).unwrap_or_default()
+            create_authors_string(authors.iter().map(Person::name)).unwrap_or_default()
         } else {
             "".into()
         };
@@ -326,6 +398,19 @@ impl ModMetadataManager {
             authors: authors.into(),
             version_str: version.into(),
             png_icon,
+            description: first.description.clone(),
+            dependencies: first.required_mods.iter().map(|required| {
+                let (id, version_range) = match required.split_once('@') {
+                    Some((id, range)) => (id.into(), Some(range.into())),
+                    None => (required.clone(), None),
+                };
+                ContentDependency {
+                    id,
+                    version_range,
+                    kind: ContentDependencyKind::Required,
+                }
+            }).collect(),
+            provides: mc_mod_info.0.iter().skip(1).map(|other| other.modid.clone()).collect(),
             update_status: Arc::new(AtomicContentUpdateStatus::new(ContentUpdateStatus::Unknown)),
             extra: ContentType::LegacyForge,
         }))
@@ -409,7 +494,7 @@ impl ModMetadataManager {
             png_icon = load_icon(icon);
         }
 
-        let authors = if let Some(authors) = modrinth_index_json.authors && let Some(authors) = create_authors_string(&authors) {
+        let authors = if let Some(authors) = modrinth_index_json.authors && let Some(authors) = create_authors_string(authors.iter().map(Person::name)) {
             authors.into()
         } else if let Some(author) = modrinth_index_json.author {
             format!("By {}", author.name()).into()
@@ -424,6 +509,9 @@ impl ModMetadataManager {
             authors,
             version_str: format!("v{}", modrinth_index_json.version_id).into(),
             png_icon,
+            description: None,
+            dependencies: [].into(),
+            provides: [].into(),
             update_status: Arc::new(AtomicContentUpdateStatus::new(ContentUpdateStatus::Unknown)),
             extra: ContentType::ModrinthModpack {
                 downloads: modrinth_index_json.files,
@@ -497,6 +585,9 @@ impl ModMetadataManager {
             authors: author.unwrap_or_default(),
             version_str: version.unwrap_or_default(),
             png_icon: None,
+            description: None,
+            dependencies: [].into(),
+            provides: [].into(),
             update_status: Arc::new(AtomicContentUpdateStatus::new(ContentUpdateStatus::Unknown)),
             extra: ContentType::JavaModule
         }))
@@ -523,6 +614,9 @@ impl ModMetadataManager {
             authors: "".into(),
             version_str: pack_mcmeta.pack.description,
             png_icon,
+            description: None,
+            dependencies: [].into(),
+            provides: [].into(),
             update_status: Arc::new(AtomicContentUpdateStatus::new(ContentUpdateStatus::Unknown)),
             extra: ContentType::ResourcePack
         }))
@@ -558,21 +652,90 @@ fn load_icon<R: rc_zip_sync::HasCursor>(icon_file: rc_zip_sync::EntryHandle<R>)
     Some(icon_bytes.into())
 }
 
-fn create_authors_string(authors: &[Person]) -> Option<String> {
-    if !authors.is_empty() {
-        let mut authors_string = "By ".to_owned();
-        let mut first = true;
-        for author in authors {
-            if first {
-                first = false;
-            } else {
-                authors_string.push_str(", ");
-            }
-            authors_string.push_str(author.name());
+fn parse_fabric_mod_json(mut bytes: Vec<u8>) -> Option<FabricModJson> {
+    // Some mods violate the JSON spec by using raw newline characters inside strings (e.g. BetterGrassify)
+    for byte in bytes.iter_mut() {
+        if *byte == '\n' as u8 {
+            *byte = ' ' as u8;
+        }
+    }
+
+    serde_json::from_slice(&bytes).inspect_err(|e| {
+        log::error!("Error parsing fabric.mod.json: {e}");
+    }).ok()
+}
+
+// Collects the ids of mods nested inside of a jar, so that dependencies on them are known to be satisfied
+fn load_nested_ids<R: rc_zip_sync::HasCursor>(archive: &rc_zip_sync::ArchiveHandle<R>, jars: Vec<Arc<str>>, provides: &mut Vec<Arc<str>>, depth: usize) {
+    const MAX_DEPTH: usize = 4;
+    if depth >= MAX_DEPTH {
+        return;
+    }
+
+    for jar in jars {
+        let Some(nested) = archive.by_name(&jar) else {
+            continue;
+        };
+        let Ok(nested_bytes) = nested.bytes() else {
+            continue;
+        };
+        let Ok(nested_archive) = nested_bytes.read_zip() else {
+            continue;
+        };
+
+        if let Some(file) = nested_archive.by_name("fabric.mod.json") {
+            let Some(fabric_mod_json) = file.bytes().ok().and_then(parse_fabric_mod_json) else {
+                continue;
+            };
+            provides.push(fabric_mod_json.id);
+            provides.extend(fabric_mod_json.provides);
+            load_nested_ids(&nested_archive, fabric_mod_json.jars.into_iter().map(|jar| jar.file).collect(), provides, depth + 1);
+        } else if let Some(file) = nested_archive.by_name("quilt.mod.json") {
+            let Some(quilt_mod_json) = file.bytes().ok().and_then(|bytes| serde_json::from_slice::<QuiltModJson>(&bytes).ok()) else {
+                continue;
+            };
+            let loader = quilt_mod_json.quilt_loader;
+            provides.push(loader.id);
+            provides.extend(loader.provides.into_iter().map(QuiltProvides::id));
+            load_nested_ids(&nested_archive, loader.jars, provides, depth + 1);
         }
-        Some(authors_string.into())
+    }
+}
+
+fn select_icon(icon: Icon) -> Option<Arc<str>> {
+    match icon {
+        Icon::Single(icon) => Some(icon),
+        Icon::Sizes(hash_map) => {
+            const DESIRED_SIZE: usize = 64;
+            hash_map.iter().min_by_key(|size| size.0.abs_diff(DESIRED_SIZE)).map(|e| Arc::clone(e.1))
+        },
+    }
+}
+
+fn fabric_version_range(predicates: &VersionPredicates) -> Option<Arc<str>> {
+    let range = predicates.to_range_string();
+    if range.is_empty() || &*range == "*" {
+        None
     } else {
+        Some(range)
+    }
+}
+
+fn create_authors_string<'a>(authors: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let mut authors_string = "By ".to_owned();
+    let mut first = true;
+    for author in authors {
+        if first {
+            first = false;
+        } else {
+            authors_string.push_str(", ");
+        }
+        authors_string.push_str(author);
+    }
+    if first {
         None
+    } else {
+        Some(authors_string)
     }
 }
 
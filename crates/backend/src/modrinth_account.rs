@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use auth::secret::{PlatformSecretStorage, SecretStorageError};
+use bridge::modal_action::{ModalAction, ProgressTracker, ProgressTrackerFinishType};
+use reqwest::{header::AUTHORIZATION, StatusCode};
+use schema::modrinth::{ModrinthNotification, ModrinthProject, ModrinthUser};
+use serde::de::DeserializeOwned;
+
+use crate::{account::BackendModrinthAccount, BackendState};
+
+const MODRINTH_API_URL: &str = "https://api.modrinth.com/v2";
+
+#[derive(thiserror::Error, Debug)]
+pub enum ModrinthAccountError {
+    #[error("Not signed into a Modrinth account")]
+    NotSignedIn,
+    #[error("The token was rejected by Modrinth, it may have expired or be missing the required scopes")]
+    InvalidToken,
+    #[error("Secret storage error: {0}")]
+    SecretStorage(#[from] SecretStorageError),
+    #[error("Error initializing secret storage: {0}")]
+    SecretStorageInit(Arc<str>),
+    #[error("Unable to connect to Modrinth: {0}")]
+    Reqwest(#[from] reqwest::Error),
+    #[error("Non-OK response from Modrinth: {0}")]
+    NonOK(u16),
+}
+
+impl BackendState {
+    async fn modrinth_secret_storage(&self) -> Result<&PlatformSecretStorage, ModrinthAccountError> {
+        self.secret_storage.get_or_init(PlatformSecretStorage::new).await.as_ref()
+            .map_err(|error| ModrinthAccountError::SecretStorageInit(error.to_string().into()))
+    }
+
+    async fn modrinth_get<T: DeserializeOwned>(&self, token: &str, path: &str) -> Result<T, ModrinthAccountError> {
+        let response = self.http_client.get(format!("{MODRINTH_API_URL}{path}"))
+            .header(AUTHORIZATION, token)
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => Ok(response.json().await?),
+            StatusCode::UNAUTHORIZED => Err(ModrinthAccountError::InvalidToken),
+            status => Err(ModrinthAccountError::NonOK(status.as_u16())),
+        }
+    }
+
+    // Restores the token of the signed in account so that Modrinth requests are authenticated again after a restart
+    pub async fn load_modrinth_token(self) {
+        // A token that was rejected isn't used again until the account signs in again
+        if self.account_info.write().get().modrinth_account.as_ref().is_none_or(|account| account.needs_relogin) {
+            return;
+        }
+
+        let token = match self.modrinth_secret_storage().await {
+            Ok(secret_storage) => secret_storage.read_modrinth_token().await.map_err(ModrinthAccountError::from),
+            Err(error) => Err(error),
+        };
+
+        match token {
+            Ok(Some(token)) => self.meta.set_modrinth_token(Some(token.into())),
+            Ok(None) => {
+                log::warn!("Modrinth token is missing from the secret storage");
+                self.account_info.write().modify(|account_info| {
+                    account_info.modrinth_account = None;
+                });
+            },
+            Err(error) => {
+                log::warn!("Unable to read Modrinth token: {error}");
+            },
+        }
+    }
+
+    pub async fn modrinth_login(&self, token: Arc<str>, modal_action: &ModalAction) {
+        let tracker = ProgressTracker::new(Arc::from("Signing into Modrinth"), self.send.clone());
+        modal_action.trackers.push(tracker.clone());
+        tracker.notify();
+
+        let result: Result<ModrinthUser, ModrinthAccountError> = async {
+            let user: ModrinthUser = self.modrinth_get(&token, "/user").await?;
+            self.modrinth_secret_storage().await?.write_modrinth_token(&token).await?;
+            Ok(user)
+        }.await;
+
+        let user = match result {
+            Ok(user) => user,
+            Err(error) => {
+                log::error!("Unable to sign into Modrinth: {error}");
+                modal_action.set_error_message(format!("Unable to sign into Modrinth: {error}").into());
+                tracker.set_finished(ProgressTrackerFinishType::Error);
+                tracker.notify();
+                return;
+            },
+        };
+
+        self.meta.set_modrinth_token(Some(token));
+        self.account_info.write().modify(|account_info| {
+            account_info.modrinth_account = Some(BackendModrinthAccount {
+                id: user.id,
+                username: user.username,
+                avatar_url: user.avatar_url,
+                needs_relogin: false,
+            });
+        });
+
+        tracker.set_finished(ProgressTrackerFinishType::Normal);
+        tracker.notify();
+    }
+
+    pub async fn modrinth_logout(&self) {
+        self.meta.set_modrinth_token(None);
+        self.account_info.write().modify(|account_info| {
+            account_info.modrinth_account = None;
+        });
+
+        match self.modrinth_secret_storage().await {
+            Ok(secret_storage) => {
+                if let Err(error) = secret_storage.delete_modrinth_token().await {
+                    log::warn!("Unable to delete Modrinth token: {error}");
+                }
+            },
+            Err(error) => log::warn!("Unable to delete Modrinth token: {error}"),
+        }
+    }
+
+    pub fn set_modrinth_needs_relogin(&self) {
+        log::warn!("Modrinth rejected the token, the account needs to sign in again");
+        self.meta.set_modrinth_token(None);
+        self.account_info.write().modify(|account_info| {
+            if let Some(account) = &mut account_info.modrinth_account {
+                account.needs_relogin = true;
+            }
+        });
+    }
+
+    fn modrinth_session(&self) -> Result<(Arc<str>, Arc<str>), ModrinthAccountError> {
+        let user_id = self.account_info.write().get().modrinth_account.as_ref().map(|account| account.id.clone());
+        match (self.meta.modrinth_token(), user_id) {
+            (Some(token), Some(user_id)) => Ok((token, user_id)),
+            _ => Err(ModrinthAccountError::NotSignedIn),
+        }
+    }
+
+    pub async fn fetch_modrinth_follows(&self) -> Result<Arc<[ModrinthProject]>, ModrinthAccountError> {
+        let (token, user_id) = self.modrinth_session()?;
+        let result = self.modrinth_get(&token, &format!("/user/{user_id}/follows")).await;
+        if matches!(result, Err(ModrinthAccountError::InvalidToken)) {
+            self.set_modrinth_needs_relogin();
+        }
+        result
+    }
+
+    pub async fn fetch_modrinth_notifications(&self) -> Result<Arc<[ModrinthNotification]>, ModrinthAccountError> {
+        let (token, user_id) = self.modrinth_session()?;
+        let result = self.modrinth_get(&token, &format!("/user/{user_id}/notifications")).await;
+        if matches!(result, Err(ModrinthAccountError::InvalidToken)) {
+            self.set_modrinth_needs_relogin();
+        }
+        result
+    }
+}
@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use bridge::{install::ContentCompatibility, instance::InstanceID};
+use schema::modrinth::{ModrinthLoader, ModrinthProjectVersion, ModrinthProjectVersionsRequest};
+use ustr::Ustr;
+
+use crate::{metadata::items::ModrinthProjectVersionsMetadataItem, BackendState};
+
+impl BackendState {
+    // Checks which of the projects have a version for the game version and loader of the instance. Projects whose
+    // versions can't be loaded are left out, so that they aren't shown as incompatible
+    pub async fn check_modrinth_compatibility(self, id: InstanceID, project_ids: Arc<[Arc<str>]>) -> Arc<[(Arc<str>, ContentCompatibility)]> {
+        let Some((minecraft_version, loader)) = self.instance_state.read().instances.get(id).map(|instance| {
+            let configuration = instance.configuration.get();
+            (configuration.minecraft_version, configuration.loader.as_modrinth_loader())
+        }) else {
+            return Arc::from([]);
+        };
+
+        let checks = project_ids.iter().map(|project_id| async {
+            // The full version list is requested so the result is shared with the project page and installs
+            let versions = self.meta.fetch(&ModrinthProjectVersionsMetadataItem(&ModrinthProjectVersionsRequest {
+                project_id: project_id.clone(),
+                game_versions: None,
+                loaders: None,
+            })).await;
+
+            match versions {
+                Ok(versions) => Some((project_id.clone(), compatibility(&versions.0, minecraft_version, loader))),
+                Err(error) => {
+                    log::warn!("Unable to load versions of {project_id} to check compatibility: {error}");
+                    None
+                },
+            }
+        });
+
+        futures::future::join_all(checks).await.into_iter().flatten().collect()
+    }
+}
+
+fn compatibility(versions: &[ModrinthProjectVersion], minecraft_version: Ustr, loader: ModrinthLoader) -> ContentCompatibility {
+    let mut supports_loader = false;
+    for version in versions {
+        // Resource packs and shaders don't depend on the mod loader of the instance
+        let loaders = version.loaders.as_deref().unwrap_or_default();
+        let is_mod = loaders.iter().any(|loader| loader.install_directory() == Some("mods"));
+        if is_mod && !loaders.contains(&loader) {
+            continue;
+        }
+
+        supports_loader = true;
+        if version.game_versions.as_deref().is_some_and(|game_versions| game_versions.contains(&minecraft_version)) {
+            return ContentCompatibility::Compatible;
+        }
+    }
+
+    if supports_loader {
+        ContentCompatibility::DifferentVersion
+    } else {
+        ContentCompatibility::Incompatible
+    }
+}
@@ -0,0 +1,111 @@
+use std::{collections::{HashMap, HashSet}, path::Path, sync::Arc};
+
+use schema::version::{GameLibraryArtifact, GameLibraryExtractOptions};
+use serde::{Deserialize, Serialize};
+use ustr::Ustr;
+
+use crate::launch::Launcher;
+
+const MAVEN_CENTRAL: &str = "https://repo1.maven.org/maven2";
+
+// Written next to a substituted native in the libraries directory, so that launches don't need to look up the hash
+// again once the native has been downloaded
+#[derive(Serialize, Deserialize)]
+struct SubstituteSource {
+    url: Ustr,
+    sha1: Ustr,
+}
+
+// Mojang only publishes x86 natives for Linux, so on other architectures the natives need to be
+// swapped out for an equivalent build, eg. LWJGL's linux-arm64 artifacts on Maven Central
+pub fn host_natives_suffix() -> Option<&'static str> {
+    if std::env::consts::OS != "linux" {
+        return None;
+    }
+    match std::env::consts::ARCH {
+        "aarch64" => Some("arm64"),
+        "arm" => Some("arm32"),
+        "riscv64" => Some("riscv64"),
+        _ => None,
+    }
+}
+
+pub async fn substitute_natives(
+    http_client: &reqwest::Client,
+    repositories: &[Arc<str>],
+    libraries_dir: &Path,
+    artifacts: &mut Vec<GameLibraryArtifact>,
+    natives_to_extract: &mut HashMap<Ustr, GameLibraryExtractOptions>,
+) {
+    let Some(suffix) = host_natives_suffix() else {
+        return;
+    };
+
+    let existing: HashSet<Ustr> = artifacts.iter().map(|artifact| artifact.path).collect();
+
+    let mut substituted = Vec::with_capacity(artifacts.len());
+    for artifact in artifacts.drain(..) {
+        let Some(base) = artifact.path.as_str().strip_suffix("-natives-linux.jar") else {
+            substituted.push(artifact);
+            continue;
+        };
+
+        let path = format!("{base}-natives-linux-{suffix}.jar");
+
+        // The version already ships a build for this architecture
+        if existing.contains(&Ustr::from(&path)) {
+            natives_to_extract.remove(&artifact.path);
+            continue;
+        }
+
+        if let Some(substitute) = find_substitute(http_client, repositories, libraries_dir, &path).await {
+            log::info!("Substituting {} with {}", artifact.path, substitute.url);
+            if let Some(extract) = natives_to_extract.remove(&artifact.path) {
+                natives_to_extract.insert(substitute.path, extract);
+            }
+            substituted.push(substitute);
+        } else {
+            log::warn!("Unable to find {} natives for {}, the game may fail to start", suffix, artifact.path);
+            substituted.push(artifact);
+        }
+    }
+
+    *artifacts = substituted;
+}
+
+async fn find_substitute(http_client: &reqwest::Client, repositories: &[Arc<str>], libraries_dir: &Path, path: &str) -> Option<GameLibraryArtifact> {
+    let native_path = libraries_dir.join(path);
+    let mut source_path = native_path.clone();
+    source_path.add_extension("source.json");
+
+    // The native is verified against the saved hash like any other library, so a corrupted file is downloaded again
+    if native_path.is_file() && let Ok(source) = crate::read_json::<SubstituteSource>(&source_path) {
+        return Some(GameLibraryArtifact {
+            path: path.into(),
+            sha1: Some(source.sha1),
+            size: None,
+            url: source.url,
+        });
+    }
+
+    let repositories = repositories.iter().map(|repository| &**repository).chain(std::iter::once(MAVEN_CENTRAL));
+
+    for repository in repositories {
+        let url = format!("{}/{}", repository.trim_end_matches('/'), path);
+        if let Some(sha1) = Launcher::download_sha1(http_client, &format!("{url}.sha1")).await {
+            let source = SubstituteSource { url: url.as_str().into(), sha1 };
+            if let Err(error) = crate::write_safe(&source_path, serde_json::to_string(&source).unwrap().as_bytes()) {
+                log::warn!("Unable to save the source of {path}: {error}");
+            }
+
+            return Some(GameLibraryArtifact {
+                path: path.into(),
+                sha1: Some(sha1),
+                size: None,
+                url: url.into(),
+            });
+        }
+    }
+
+    None
+}
@@ -0,0 +1,116 @@
+use std::path::Path;
+
+use bridge::instance::InstanceGameOptions;
+
+// Parser for the vanilla options.txt format. Lines that aren't understood are kept verbatim so
+// that writing the file back doesn't lose settings added by newer versions or mods
+#[derive(Debug, Default)]
+pub struct OptionsTxt {
+    lines: Vec<OptionsTxtLine>,
+}
+
+#[derive(Debug)]
+enum OptionsTxtLine {
+    Entry { key: String, value: String },
+    Raw(String),
+}
+
+impl OptionsTxt {
+    pub fn parse(content: &str) -> Self {
+        let lines = content
+            .lines()
+            .map(|line| {
+                if let Some((key, value)) = line.split_once(':')
+                    && !key.is_empty()
+                    && !key.contains(char::is_whitespace)
+                {
+                    OptionsTxtLine::Entry {
+                        key: key.to_string(),
+                        value: value.to_string(),
+                    }
+                } else {
+                    OptionsTxtLine::Raw(line.to_string())
+                }
+            })
+            .collect();
+
+        Self { lines }
+    }
+
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        match std::fs::read(path) {
+            Ok(bytes) => Ok(Self::parse(&String::from_utf8_lossy(&bytes))),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        crate::write_safe(path, self.serialize().as_bytes())
+    }
+
+    pub fn serialize(&self) -> String {
+        let mut output = String::new();
+        for line in &self.lines {
+            match line {
+                OptionsTxtLine::Entry { key, value } => {
+                    output.push_str(key);
+                    output.push(':');
+                    output.push_str(value);
+                },
+                OptionsTxtLine::Raw(raw) => {
+                    output.push_str(raw);
+                },
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.lines.iter().find_map(|line| match line {
+            OptionsTxtLine::Entry { key: entry_key, value } if entry_key == key => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn set(&mut self, key: &str, value: impl Into<String>) {
+        let value = value.into();
+        for line in &mut self.lines {
+            if let OptionsTxtLine::Entry { key: entry_key, value: entry_value } = line
+                && entry_key == key
+            {
+                *entry_value = value;
+                return;
+            }
+        }
+        self.lines.push(OptionsTxtLine::Entry {
+            key: key.to_string(),
+            value,
+        });
+    }
+
+    pub fn game_options(&self) -> InstanceGameOptions {
+        InstanceGameOptions {
+            render_distance: self.get("renderDistance").and_then(|v| v.trim().parse().ok()),
+            enable_vsync: self.get("enableVsync").and_then(|v| v.trim().parse().ok()),
+            lang: self.get("lang").map(|v| v.trim().into()),
+            gui_scale: self.get("guiScale").and_then(|v| v.trim().parse().ok()),
+        }
+    }
+
+    pub fn apply_game_options(&mut self, options: &InstanceGameOptions) {
+        if let Some(render_distance) = options.render_distance {
+            self.set("renderDistance", render_distance.to_string());
+        }
+        if let Some(enable_vsync) = options.enable_vsync {
+            self.set("enableVsync", enable_vsync.to_string());
+        }
+        if let Some(lang) = &options.lang {
+            self.set("lang", lang.to_string());
+        }
+        if let Some(gui_scale) = options.gui_scale {
+            self.set("guiScale", gui_scale.to_string());
+        }
+    }
+}
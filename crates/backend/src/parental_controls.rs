@@ -0,0 +1,261 @@
+use std::time::{Duration, Instant};
+
+use bridge::message::ParentalControlsStatus;
+use rand::RngCore;
+use schema::backend_config::{ParentalControlsConfiguration, ParentalRestrictions};
+use sha1::{Digest, Sha1};
+use sha2::Sha256;
+
+use crate::BackendState;
+
+// Playtime is written to disk at most this often while a game is running
+const PLAYTIME_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+// The hash is in config.json, which the restricted user can read, so it has to be slow to brute force
+const PIN_ITERATIONS: u32 = 600_000;
+
+// After this many wrong PINs in a row, further attempts are refused for a while. The wait doubles with every further
+// wrong PIN, up to the maximum
+const PIN_ATTEMPTS_BEFORE_LOCKOUT: u32 = 5;
+const PIN_LOCKOUT: Duration = Duration::from_secs(30);
+const MAX_PIN_LOCKOUT: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Default)]
+pub struct ParentalControlsSession {
+    // Lifted by entering the PIN, lasts until the launcher is closed or locked again
+    pub unlocked: bool,
+    last_playing_tick: Option<Instant>,
+    unsaved_playtime: Duration,
+    failed_pin_attempts: u32,
+    pin_locked_until: Option<Instant>,
+}
+
+fn hash_pin(salt: &str, pin: &str, iterations: Option<u32>) -> String {
+    match iterations {
+        Some(iterations) => {
+            let mut hash = [0u8; 32];
+            pbkdf2::pbkdf2_hmac::<Sha256>(pin.trim().as_bytes(), salt.as_bytes(), iterations, &mut hash);
+            hex::encode(hash)
+        },
+        None => {
+            let mut hasher = Sha1::new();
+            hasher.update(salt.as_bytes());
+            hasher.update(pin.trim().as_bytes());
+            hex::encode(hasher.finalize())
+        },
+    }
+}
+
+fn new_pin_hash(pin: &str) -> (String, String) {
+    let mut salt = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let salt = hex::encode(salt);
+    let hash = hash_pin(&salt, pin, Some(PIN_ITERATIONS));
+    (salt, hash)
+}
+
+fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+fn verify_pin(configuration: &ParentalControlsConfiguration, pin: &str) -> bool {
+    let (Some(pin_hash), Some(pin_salt)) = (&configuration.pin_hash, &configuration.pin_salt) else {
+        return true;
+    };
+    let hash = hash_pin(pin_salt, pin, configuration.pin_iterations);
+    hash.len() == pin_hash.len() && hash.bytes().zip(pin_hash.bytes()).fold(0, |difference, (a, b)| difference | (a ^ b)) == 0
+}
+
+impl BackendState {
+    // Restrictions currently being enforced, None when parental controls are disabled or unlocked
+    fn active_parental_restrictions(&self) -> Option<ParentalRestrictions> {
+        if self.parental_controls.read().unlocked {
+            return None;
+        }
+        let mut config = self.config.write();
+        let parental_controls = &config.get().parental_controls;
+        parental_controls.is_enabled().then_some(parental_controls.restrictions)
+    }
+
+    pub fn check_can_create_instance(&self) -> Result<(), &'static str> {
+        if self.active_parental_restrictions().is_some_and(|restrictions| restrictions.block_instance_creation) {
+            Err("Creating instances has been disabled by parental controls")
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn check_can_install_content(&self) -> Result<(), &'static str> {
+        if self.active_parental_restrictions().is_some_and(|restrictions| restrictions.block_content_install) {
+            Err("Installing content has been disabled by parental controls")
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn check_can_play(&self) -> Result<(), &'static str> {
+        if self.remaining_playtime().is_some_and(|remaining| remaining.is_zero()) {
+            Err("The daily playtime limit set by parental controls has been reached")
+        } else {
+            Ok(())
+        }
+    }
+
+    fn playtime_today(&self) -> Duration {
+        let saved = {
+            let mut config = self.config.write();
+            let playtime = &config.get().parental_playtime;
+            if *playtime.day == today() {
+                Duration::from_secs(playtime.seconds)
+            } else {
+                Duration::ZERO
+            }
+        };
+        saved + self.parental_controls.read().unsaved_playtime
+    }
+
+    fn remaining_playtime(&self) -> Option<Duration> {
+        let limit = self.active_parental_restrictions()?.daily_playtime_minutes?;
+        let limit = Duration::from_secs(limit as u64 * 60);
+        Some(limit.saturating_sub(self.playtime_today()))
+    }
+
+    fn save_playtime(&self) {
+        let unsaved = std::mem::take(&mut self.parental_controls.write().unsaved_playtime);
+        if unsaved.is_zero() {
+            return;
+        }
+
+        self.config.write().modify(|config| {
+            let today = today();
+            if *config.parental_playtime.day != today {
+                config.parental_playtime.day = today.into();
+                config.parental_playtime.seconds = 0;
+            }
+            config.parental_playtime.seconds += unsaved.as_secs();
+        });
+    }
+
+    // Called every tick, returns true when running games should be closed because the limit was reached
+    pub fn track_playtime(&self, playing: bool) -> bool {
+        let now = Instant::now();
+        let should_save = {
+            let mut session = self.parental_controls.write();
+            let last_tick = if playing {
+                session.last_playing_tick.replace(now)
+            } else {
+                session.last_playing_tick.take()
+            };
+            if let Some(last_tick) = last_tick {
+                session.unsaved_playtime += now - last_tick;
+            }
+            !playing || session.unsaved_playtime >= PLAYTIME_SAVE_INTERVAL
+        };
+
+        if should_save {
+            self.save_playtime();
+        }
+
+        playing && self.check_can_play().is_err()
+    }
+
+    pub fn parental_controls_status(&self) -> ParentalControlsStatus {
+        let unlocked = self.parental_controls.read().unlocked;
+        let (enabled, restrictions) = {
+            let mut config = self.config.write();
+            let parental_controls = &config.get().parental_controls;
+            (parental_controls.is_enabled(), parental_controls.restrictions)
+        };
+
+        ParentalControlsStatus {
+            enabled,
+            unlocked,
+            restrictions,
+            playtime_today_seconds: self.playtime_today().as_secs(),
+        }
+    }
+
+    // Checks the PIN while enforcing the lockout after repeated wrong PINs. Hashes from before PBKDF2 was used are
+    // replaced once the right PIN has been entered
+    fn check_parental_pin(&self, pin: &str) -> bool {
+        {
+            let mut session = self.parental_controls.write();
+            if let Some(locked_until) = session.pin_locked_until {
+                let now = Instant::now();
+                if now < locked_until {
+                    let seconds = (locked_until - now).as_secs() + 1;
+                    self.send.send_warning(format!("Too many incorrect PINs, try again in {seconds} seconds"));
+                    return false;
+                }
+                session.pin_locked_until = None;
+            }
+        }
+
+        let configuration = self.config.write().get().parental_controls.clone();
+        if !verify_pin(&configuration, pin) {
+            log::warn!("Incorrect parental controls PIN entered");
+            let mut session = self.parental_controls.write();
+            session.failed_pin_attempts += 1;
+            if let Some(lockouts) = session.failed_pin_attempts.checked_sub(PIN_ATTEMPTS_BEFORE_LOCKOUT) {
+                let lockout = PIN_LOCKOUT.saturating_mul(1 << lockouts.min(16)).min(MAX_PIN_LOCKOUT);
+                session.pin_locked_until = Some(Instant::now() + lockout);
+            }
+            return false;
+        }
+
+        self.parental_controls.write().failed_pin_attempts = 0;
+
+        if configuration.is_enabled() && configuration.pin_iterations != Some(PIN_ITERATIONS) {
+            let (salt, hash) = new_pin_hash(pin);
+            self.config.write().modify(|config| {
+                config.parental_controls.pin_hash = Some(hash.into());
+                config.parental_controls.pin_salt = Some(salt.into());
+                config.parental_controls.pin_iterations = Some(PIN_ITERATIONS);
+            });
+        }
+        true
+    }
+
+    pub fn unlock_parental_controls(&self, pin: &str) -> bool {
+        let correct = self.check_parental_pin(pin);
+        if correct {
+            self.parental_controls.write().unlocked = true;
+        }
+        correct
+    }
+
+    pub fn lock_parental_controls(&self) {
+        self.parental_controls.write().unlocked = false;
+    }
+
+    // Sets up or changes parental controls, the PIN must match the existing one if controls are already enabled.
+    // Passing None for the restrictions disables parental controls entirely
+    pub fn configure_parental_controls(&self, pin: &str, restrictions: Option<ParentalRestrictions>) -> bool {
+        if pin.trim().is_empty() {
+            return false;
+        }
+
+        if !self.check_parental_pin(pin) {
+            return false;
+        }
+
+        let mut config = self.config.write();
+        let new_pin = (restrictions.is_some() && !config.get().parental_controls.is_enabled()).then(|| new_pin_hash(pin));
+        config.modify(|config| {
+            if let Some(restrictions) = restrictions {
+                if let Some((salt, hash)) = new_pin {
+                    config.parental_controls.pin_hash = Some(hash.into());
+                    config.parental_controls.pin_salt = Some(salt.into());
+                    config.parental_controls.pin_iterations = Some(PIN_ITERATIONS);
+                }
+                config.parental_controls.restrictions = restrictions;
+            } else {
+                config.parental_controls = ParentalControlsConfiguration::default();
+            }
+        });
+        drop(config);
+
+        self.parental_controls.write().unlocked = false;
+        true
+    }
+}
@@ -0,0 +1,82 @@
+use std::path::{Path, PathBuf};
+
+use crate::BackendState;
+
+// A new instance which is still being set up. It's built in the creating directory and only moved into the
+// instances directory once it's complete, so a failure partway through never leaves a broken instance behind
+#[derive(Debug)]
+pub struct PendingInstance {
+    temp_dir: PathBuf,
+    target: PathBuf,
+    committed: bool,
+}
+
+impl PendingInstance {
+    pub fn new(creating_dir: &Path, target: PathBuf) -> std::io::Result<Self> {
+        let name = target.file_name().unwrap_or_default().to_string_lossy();
+        let temp_dir = creating_dir.join(format!("{name}-{:08x}", rand::random::<u32>()));
+        std::fs::create_dir_all(&temp_dir)?;
+
+        Ok(Self {
+            temp_dir,
+            target,
+            committed: false,
+        })
+    }
+
+    pub fn root_path(&self) -> &Path {
+        &self.temp_dir
+    }
+
+    pub fn dot_minecraft_path(&self) -> PathBuf {
+        self.temp_dir.join(".minecraft")
+    }
+
+    // Moves the instance into place. Both folders are inside the launcher directory, so this is a rename and the
+    // instances directory watcher sees the finished instance appear all at once
+    pub fn commit(mut self) -> std::io::Result<PathBuf> {
+        if self.target.exists() {
+            return Err(std::io::Error::new(std::io::ErrorKind::AlreadyExists, format!("{} already exists", self.target.display())));
+        }
+        if let Some(parent) = self.target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::rename(&self.temp_dir, &self.target)?;
+        self.committed = true;
+        Ok(std::mem::take(&mut self.target))
+    }
+}
+
+impl Drop for PendingInstance {
+    fn drop(&mut self) {
+        if !self.committed && let Err(err) = std::fs::remove_dir_all(&self.temp_dir) {
+            log::warn!("Unable to remove unfinished instance {}: {err}", self.temp_dir.display());
+        }
+    }
+}
+
+impl BackendState {
+    pub fn commit_instance(&self, pending: PendingInstance) -> Option<PathBuf> {
+        match pending.commit() {
+            Ok(path) => Some(path),
+            Err(err) => {
+                log::error!("Unable to finish creating instance: {err}");
+                self.send.send_error(format!("Unable to create instance: {err}"));
+                None
+            },
+        }
+    }
+
+    // Instances which were still being created when the launcher closed or crashed
+    pub fn remove_orphaned_pending_instances(&self) {
+        let Ok(read_dir) = std::fs::read_dir(&self.directories.creating_instances_dir) else {
+            return;
+        };
+        for entry in read_dir.flatten() {
+            log::info!("Removing unfinished instance {}", entry.path().display());
+            if let Err(err) = std::fs::remove_dir_all(entry.path()) {
+                log::warn!("Unable to remove unfinished instance {}: {err}", entry.path().display());
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use std::{path::Path, sync::Arc, time::Duration};
+
+use bridge::instance::InstanceID;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use schema::performance::{PerformanceHistory, PerformanceSample, PerformanceSession};
+
+use crate::{BackendState, instance::Instance};
+
+// Stored next to info_v1.json in the instance folder
+const PERFORMANCE_FILE: &str = "performance_v1.json";
+const MAX_SESSIONS: usize = 20;
+// Long sessions are thinned out evenly so the history file stays small
+const MAX_SAMPLES_PER_SESSION: usize = 300;
+
+static LOG_TIMESTAMP: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^\[(\d{2}):(\d{2}):(\d{2})"#).unwrap());
+// Matches the F3 overlay format ("60 fps T: 120 vsync") which mods use when logging or dumping the debug screen
+static FPS: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?i)\b(\d{1,4}) fps\b"#).unwrap());
+static TPS: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?i)\bTPS:? *(\d{1,2}(?:\.\d+)?)\b"#).unwrap());
+// Matches the F3 overlay format ("Mem: 45% 1843/4096MB")
+static MEMORY: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?i)\bMem(?:ory)?:? *\d{1,3}% *(\d+) */ *(\d+) *Mi?B"#).unwrap());
+static LAG: Lazy<Regex> = Lazy::new(|| Regex::new(r#"Can't keep up! Is the server overloaded\? Running (\d+)ms"#).unwrap());
+
+impl BackendState {
+    // Reads the log of a session which just ended. Only called while launch statistics are being recorded
+    pub fn capture_performance(&self, instance: &mut Instance, started_at: i64, play_duration: Duration) {
+        let configuration = instance.configuration.get();
+        let session = PerformanceSession {
+            started_at,
+            play_seconds: play_duration.as_secs(),
+            minecraft_version: configuration.minecraft_version,
+            loader: configuration.loader,
+            loader_version: configuration.preferred_loader_version,
+            mod_count: 0,
+            lag_spikes: 0,
+            lag_millis: 0,
+            samples: Vec::new(),
+        };
+
+        let dot_minecraft = instance.dot_minecraft_path.clone();
+        let history_path = instance.root_path.join(PERFORMANCE_FILE);
+        tokio::task::spawn_blocking(move || {
+            let mut session = session;
+            session.mod_count = count_mods(&dot_minecraft.join("mods"));
+
+            if let Ok(log) = std::fs::read(dot_minecraft.join("logs").join("latest.log")) {
+                read_performance_from_log(&String::from_utf8_lossy(&log), &mut session);
+            }
+
+            let mut history: PerformanceHistory = crate::read_json(&history_path).unwrap_or_default();
+            history.sessions.push(session);
+            if history.sessions.len() > MAX_SESSIONS {
+                let excess = history.sessions.len() - MAX_SESSIONS;
+                history.sessions.drain(..excess);
+            }
+
+            let bytes = serde_json::to_vec(&history).unwrap();
+            if let Err(err) = crate::write_safe(&history_path, &bytes) {
+                log::error!("Unable to save performance history: {err}");
+            }
+        });
+    }
+
+    pub fn get_performance_history(&self, id: InstanceID) -> Arc<[PerformanceSession]> {
+        let Some(root_path) = self.instance_state.read().instances.get(id).map(|instance| instance.root_path.clone()) else {
+            return Arc::from([]);
+        };
+        let history: PerformanceHistory = crate::read_json(&root_path.join(PERFORMANCE_FILE)).unwrap_or_default();
+        history.sessions.into()
+    }
+
+    pub fn clear_performance_history(&self, id: InstanceID) {
+        let Some(root_path) = self.instance_state.read().instances.get(id).map(|instance| instance.root_path.clone()) else {
+            return;
+        };
+        _ = std::fs::remove_file(root_path.join(PERFORMANCE_FILE));
+    }
+}
+
+fn count_mods(mods: &Path) -> usize {
+    let Ok(read_dir) = std::fs::read_dir(mods) else {
+        return 0;
+    };
+    read_dir.flatten().filter(|entry| entry.path().extension().is_some_and(|extension| extension == "jar")).count()
+}
+
+fn read_performance_from_log(log: &str, session: &mut PerformanceSession) {
+    let mut first_seconds = None;
+    let mut offset_seconds = 0;
+
+    for line in log.lines() {
+        // Lines without a timestamp (e.g. stack traces) keep the offset of the previous line
+        if let Some(captures) = LOG_TIMESTAMP.captures(line) {
+            let part = |index: usize| captures[index].parse::<u32>().unwrap_or(0);
+            let seconds = part(1) * 3600 + part(2) * 60 + part(3);
+            let first = *first_seconds.get_or_insert(seconds);
+            // Sessions going past midnight wrap around
+            offset_seconds = (seconds + 86400 - first) % 86400;
+        }
+
+        if let Some(captures) = LAG.captures(line) {
+            session.lag_spikes += 1;
+            session.lag_millis += captures[1].parse::<u64>().unwrap_or(0);
+            continue;
+        }
+
+        let mut sample = PerformanceSample {
+            offset_seconds,
+            ..Default::default()
+        };
+        if let Some(captures) = FPS.captures(line) {
+            sample.fps = captures[1].parse().ok();
+        }
+        if let Some(captures) = TPS.captures(line) {
+            sample.tps = captures[1].parse().ok();
+        }
+        if let Some(captures) = MEMORY.captures(line) {
+            sample.memory_used_mib = captures[1].parse().ok();
+            sample.memory_max_mib = captures[2].parse().ok();
+        }
+
+        if sample.fps.is_some() || sample.tps.is_some() || sample.memory_used_mib.is_some() {
+            session.samples.push(sample);
+        }
+    }
+
+    if session.samples.len() > MAX_SAMPLES_PER_SESSION {
+        let step = session.samples.len().div_ceil(MAX_SAMPLES_PER_SESSION);
+        session.samples = session.samples.iter().step_by(step).copied().collect();
+    }
+}
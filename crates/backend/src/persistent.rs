@@ -1,13 +1,64 @@
-use std::{path::Path, sync::Arc};
+use std::{path::{Path, PathBuf}, sync::Arc, time::{Duration, SystemTime}};
 
 use serde::{Deserialize, Serialize};
 
 use crate::IoOrSerializationError;
 
+// Number of previous versions kept next to the file
+const BACKUPS_KEPT: usize = 3;
+
+// Backups are made before the first change after starting and then at most once a day, so that frequent writes such
+// as the playtime being saved don't replace all of the backups within minutes
+const BACKUP_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+// Describes what happened when a file couldn't be parsed. The unreadable file is moved aside instead of being
+// overwritten, so nothing is lost if none of the backups can be read either
+#[derive(Debug)]
+pub struct PersistentRecovery {
+    pub corrupt_path: PathBuf,
+    pub restored_from: Option<PathBuf>,
+}
+
+fn backup_path(path: &Path, index: usize) -> PathBuf {
+    let mut backup = path.to_path_buf();
+    backup.add_extension(format!("{index}.bak"));
+    backup
+}
+
+// Sorted from newest to oldest
+fn list_backups(path: &Path) -> Vec<PathBuf> {
+    (1..=BACKUPS_KEPT).map(|index| backup_path(path, index)).filter(|backup| backup.is_file()).collect()
+}
+
+fn newest_backup_is_recent(path: &Path) -> bool {
+    std::fs::metadata(backup_path(path, 1))
+        .and_then(|metadata| metadata.modified())
+        .is_ok_and(|modified| SystemTime::now().duration_since(modified).is_ok_and(|age| age < BACKUP_INTERVAL))
+}
+
+fn rotate_backups(path: &Path) {
+    if !path.is_file() {
+        return;
+    }
+
+    for index in (1..BACKUPS_KEPT).rev() {
+        let from = backup_path(path, index);
+        if from.is_file() {
+            _ = std::fs::rename(&from, backup_path(path, index + 1));
+        }
+    }
+    if let Err(error) = std::fs::copy(path, backup_path(path, 1)) {
+        log::warn!("Unable to back up {path:?}: {error}");
+    }
+}
+
 #[derive(Debug)]
 pub struct Persistent<T: Serialize + for <'de> Deserialize<'de>> {
     path: Arc<Path>,
     dirty: bool,
+    // Only files loaded with `load_recovering` keep backups
+    keep_backups: bool,
+    backed_up: bool,
     data: T
 }
 
@@ -17,17 +68,53 @@ impl<T: Serialize + for <'de> Deserialize<'de> + Default> Persistent<T> {
         Self {
             path,
             dirty: false,
+            keep_backups: false,
+            backed_up: false,
             data,
         }
     }
 }
 
+impl<T: Serialize + for <'de> Deserialize<'de> + Default> Persistent<T> {
+    // Like `load`, but a file which can't be parsed is replaced by its newest readable backup instead of the default
+    pub fn load_recovering(path: Arc<Path>) -> (Self, Option<PersistentRecovery>) {
+        let error = match crate::read_json(&path) {
+            Ok(data) => return (Self { path, dirty: false, keep_backups: true, backed_up: false, data }, None),
+            Err(IoOrSerializationError::Serialization(error)) => error,
+            Err(IoOrSerializationError::Io(_)) => return (Self { path, dirty: false, keep_backups: true, backed_up: false, data: T::default() }, None),
+        };
+        log::error!("Unable to parse {path:?}: {error}");
+
+        let mut corrupt_path = path.to_path_buf();
+        corrupt_path.add_extension("corrupt");
+        if let Err(error) = std::fs::rename(&path, &corrupt_path) {
+            log::error!("Unable to move {path:?} aside: {error}");
+        }
+
+        for backup in list_backups(&path) {
+            match crate::read_json(&backup) {
+                Ok(data) => {
+                    log::info!("Recovered {path:?} from {backup:?}");
+                    let mut persistent = Self { path, dirty: false, keep_backups: true, backed_up: false, data: T::default() };
+                    persistent.modify(move |current| *current = data);
+                    return (persistent, Some(PersistentRecovery { corrupt_path, restored_from: Some(backup) }));
+                },
+                Err(error) => log::warn!("Unable to read backup {backup:?}: {error}"),
+            }
+        }
+
+        (Self { path, dirty: false, keep_backups: true, backed_up: false, data: T::default() }, Some(PersistentRecovery { corrupt_path, restored_from: None }))
+    }
+}
+
 impl<T: Serialize + for <'de> Deserialize<'de>> Persistent<T> {
     pub fn try_load(path: Arc<Path>) -> Result<Self, IoOrSerializationError> {
         let data = crate::read_json(&path)?;
         Ok(Self {
             path,
             dirty: false,
+            keep_backups: false,
+            backed_up: false,
             data,
         })
     }
@@ -37,6 +124,8 @@ impl<T: Serialize + for <'de> Deserialize<'de>> Persistent<T> {
         Self {
             path,
             dirty: false,
+            keep_backups: false,
+            backed_up: false,
             data,
         }
     }
@@ -49,6 +138,10 @@ impl<T: Serialize + for <'de> Deserialize<'de>> Persistent<T> {
         (func)(&mut self.data);
 
         if let Ok(bytes) = serde_json::to_vec(&self.data) {
+            if self.keep_backups && !(self.backed_up && newest_backup_is_recent(&self.path)) {
+                rotate_backups(&self.path);
+                self.backed_up = true;
+            }
             if crate::write_safe(&self.path, &bytes).is_ok() {
                 self.dirty = true;
             }
@@ -63,6 +156,19 @@ impl<T: Serialize + for <'de> Deserialize<'de>> Persistent<T> {
         &self.data
     }
 
+    // Backups which can still be parsed, from newest to oldest
+    pub fn valid_backups(&self) -> Vec<PathBuf> {
+        list_backups(&self.path).into_iter()
+            .filter(|backup| crate::read_json::<T>(backup).is_ok())
+            .collect()
+    }
+
+    pub fn restore_backup(&mut self, backup: &Path) -> Result<(), IoOrSerializationError> {
+        let data: T = crate::read_json(backup)?;
+        self.modify(move |current| *current = data);
+        Ok(())
+    }
+
     #[inline(always)]
     pub fn sanity_check_path_eq(&self, path: &Path) {
         debug_assert_eq!(path, &*self.path);
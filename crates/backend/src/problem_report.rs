@@ -0,0 +1,239 @@
+use std::{io::Write, path::{Path, PathBuf}, sync::Arc, time::SystemTime};
+
+use bridge::{
+    instance::InstanceID, message::ProblemReport, modal_action::{ModalAction, ProgressTracker, ProgressTrackerFinishType}
+};
+use schema::instance::InstanceConfiguration;
+
+use crate::{instance_history, log_capture, log_files, log_reader, BackendState};
+
+// Today's log and the one before it are usually enough to see what went wrong
+const INCLUDED_LAUNCHER_LOG_FILES: usize = 2;
+
+const NEW_ISSUE_URL: &str = "https://github.com/Moulberry/PandoraLauncher/issues/new";
+
+struct ReportedInstance {
+    name: String,
+    root_path: Arc<Path>,
+    dot_minecraft_path: Arc<Path>,
+    configuration: InstanceConfiguration,
+}
+
+impl BackendState {
+    pub async fn create_problem_report(
+        &self,
+        instance: Option<InstanceID>,
+        description: Arc<str>,
+        modal_action: &ModalAction,
+    ) -> Option<ProblemReport> {
+        let tracker = ProgressTracker::new(Arc::from("Creating problem report"), self.send.clone());
+        tracker.set_total(1);
+        tracker.notify();
+        modal_action.trackers.push(tracker.clone());
+
+        let reported_instance = if let Some(id) = instance {
+            let mut instance_state = self.instance_state.write();
+            let Some(instance) = instance_state.instances.get_mut(id) else {
+                tracker.set_finished(ProgressTrackerFinishType::Error);
+                tracker.notify();
+                modal_action.set_error_message("Can't create problem report, unknown instance".into());
+                return None;
+            };
+            Some(ReportedInstance {
+                name: instance.name.to_string(),
+                root_path: instance.root_path.clone(),
+                dot_minecraft_path: instance.dot_minecraft_path.clone(),
+                configuration: instance.configuration.get().clone(),
+            })
+        } else {
+            None
+        };
+
+        let system_info = system_info(reported_instance.as_ref());
+        let issue_url = issue_url(&description, &system_info);
+
+        let filename = format!("problem-report-{}.tar.gz", chrono::Local::now().format("%Y-%m-%d_%H-%M-%S"));
+        let output = self.directories.reports_dir.join(filename);
+
+        let result = {
+            let output = output.clone();
+            let launcher_logs_dir = self.directories.launcher_logs_dir.clone();
+            tokio::task::spawn_blocking(move || {
+                write_problem_report(&output, &launcher_logs_dir, &description, &system_info, reported_instance.as_ref())
+            }).await.unwrap()
+        };
+
+        tracker.add_count(1);
+        tracker.set_finished(ProgressTrackerFinishType::from_err(result.is_err()));
+        tracker.notify();
+
+        if let Err(error) = result {
+            log::error!("Failed to write problem report: {error}");
+            let error = format!("Failed to write problem report: {error}");
+            modal_action.set_error_message(log_reader::replace(&error).into());
+            return None;
+        }
+
+        Some(ProblemReport {
+            path: output.into(),
+            issue_url: issue_url.into(),
+        })
+    }
+}
+
+fn system_info(instance: Option<&ReportedInstance>) -> String {
+    let mut info = String::new();
+
+    let version = option_env!("PANDORA_RELEASE_VERSION").unwrap_or("dev");
+    info.push_str(&format!("Launcher: {}\n", version));
+    info.push_str(&format!("OS: {}\n", os_info::get()));
+    info.push_str(&format!("Arch: {}\n", std::env::consts::ARCH));
+    if let Ok(parallelism) = std::thread::available_parallelism() {
+        info.push_str(&format!("CPU threads: {}\n", parallelism));
+    }
+
+    if let Some(instance) = instance {
+        let configuration = &instance.configuration;
+        info.push_str(&format!("Minecraft: {}\n", configuration.minecraft_version));
+        info.push_str(&format!("Loader: {}", configuration.loader.name()));
+        if let Some(loader_version) = &configuration.preferred_loader_version {
+            info.push_str(&format!(" {}", loader_version));
+        }
+        info.push('\n');
+    }
+
+    info
+}
+
+fn issue_url(description: &str, system_info: &str) -> String {
+    let title = description.lines().next().unwrap_or_default().trim();
+    let title = if title.chars().count() > 80 {
+        let mut truncated: String = title.chars().take(77).collect();
+        truncated.push_str("...");
+        truncated
+    } else {
+        title.to_string()
+    };
+
+    let body = format!(
+        "{}\n\n### System information\n```\n{}```\n\nPlease attach the problem report archive created by the launcher to this issue.",
+        description.trim(),
+        system_info,
+    );
+
+    let mut url = reqwest::Url::parse(NEW_ISSUE_URL).unwrap();
+    url.query_pairs_mut()
+        .append_pair("title", &title)
+        .append_pair("body", &log_reader::replace(&body));
+    url.into()
+}
+
+fn write_problem_report(
+    output: &Path,
+    launcher_logs_dir: &Path,
+    description: &str,
+    system_info: &str,
+    instance: Option<&ReportedInstance>,
+) -> std::io::Result<()> {
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let file = std::fs::File::create(output)?;
+    let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+
+    let mut summary = String::new();
+    summary.push_str(description.trim());
+    summary.push_str("\n\n");
+    summary.push_str(system_info);
+    append_text(&mut builder, "report.txt", &summary)?;
+
+    let log_files = log_files::list_log_files(launcher_logs_dir);
+    if log_files.is_empty() {
+        // The log file couldn't be written, but recent lines are still kept in memory
+        append_text(&mut builder, "launcher.log", &log_capture::captured_lines())?;
+    }
+    for (path, _, _) in log_files.iter().take(INCLUDED_LAUNCHER_LOG_FILES) {
+        let Some(filename) = path.file_name() else {
+            continue;
+        };
+        if let Ok(content) = std::fs::read(path) {
+            let name = format!("logs/{}", filename.to_string_lossy());
+            append_text(&mut builder, &name, &String::from_utf8_lossy(&content))?;
+        }
+    }
+
+    if let Some(instance) = instance {
+        // Paths could contain the username of the computer, everything else is fine to share
+        let configuration = serde_json::to_string_pretty(&instance.configuration)?;
+        append_text(&mut builder, "instance/info_v1.json", &configuration)?;
+        append_text(&mut builder, "instance/name.txt", &instance.name)?;
+
+        // What changed recently is often what broke the instance
+        let history = instance_history::read_history(&instance.root_path.join(instance_history::HISTORY_FILE));
+        if !history.is_empty() {
+            let mut content = String::new();
+            for entry in &history {
+                content.push_str(&serde_json::to_string(entry)?);
+                content.push('\n');
+            }
+            append_text(&mut builder, "instance/history.jsonl", &content)?;
+        }
+
+        let latest_log = instance.dot_minecraft_path.join("logs").join("latest.log");
+        if let Ok(content) = std::fs::read(&latest_log) {
+            append_text(&mut builder, "instance/latest.log", &String::from_utf8_lossy(&content))?;
+        }
+
+        if let Some(crash_report) = newest_crash_report(&instance.dot_minecraft_path) {
+            if let Ok(content) = std::fs::read(&crash_report) {
+                append_text(&mut builder, "instance/crash-report.txt", &String::from_utf8_lossy(&content))?;
+            }
+        }
+
+        if let Some(jvm_crash) = newest_jvm_crash(&instance.dot_minecraft_path) {
+            if let Ok(content) = std::fs::read(&jvm_crash) {
+                append_text(&mut builder, "instance/hs_err.log", &String::from_utf8_lossy(&content))?;
+            }
+        }
+    }
+
+    builder.into_inner()?.finish()?.flush()?;
+    Ok(())
+}
+
+fn append_text<W: Write>(builder: &mut tar::Builder<W>, path: &str, content: &str) -> std::io::Result<()> {
+    let content = log_reader::replace(content);
+    let bytes = content.as_bytes();
+
+    let mtime = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or(0);
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(mtime);
+    header.set_cksum();
+    builder.append_data(&mut header, path, bytes)
+}
+
+fn newest_crash_report(dot_minecraft_path: &Path) -> Option<PathBuf> {
+    let read_dir = std::fs::read_dir(dot_minecraft_path.join("crash-reports")).ok()?;
+
+    read_dir.filter_map(Result::ok)
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(".txt"))
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+// Collected into crash-reports by jvm_crash after the JVM crashed
+fn newest_jvm_crash(dot_minecraft_path: &Path) -> Option<PathBuf> {
+    let read_dir = std::fs::read_dir(dot_minecraft_path.join("crash-reports")).ok()?;
+
+    read_dir.filter_map(Result::ok)
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("hs_err_pid"))
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use bridge::{modal_action::ModalAction, realms::{RealmState, RealmSummary}};
+use reqwest::{header::COOKIE, StatusCode};
+use schema::realms::{RealmsLivePlayer, RealmsLivePlayerLists, RealmsServerState, RealmsWorlds};
+use serde::de::DeserializeOwned;
+
+use crate::BackendState;
+
+const REALMS_API_URL: &str = "https://pc.realms.minecraft.net";
+
+#[derive(thiserror::Error, Debug)]
+pub enum RealmsError {
+    #[error("Unable to sign into the selected account")]
+    NotSignedIn,
+    #[error("Realms requires a Microsoft account")]
+    OfflineAccount,
+    #[error("This account doesn't have access to Realms")]
+    Forbidden,
+    #[error("Unable to connect to Realms: {0}")]
+    Reqwest(#[from] reqwest::Error),
+    #[error("Non-OK response from Realms: {0}")]
+    NonOK(u16),
+}
+
+impl BackendState {
+    async fn realms_get<T: DeserializeOwned>(&self, cookie: &str, path: &str) -> Result<T, RealmsError> {
+        let response = self.http_client.get(format!("{REALMS_API_URL}{path}"))
+            .header(COOKIE, cookie)
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => Ok(response.json().await?),
+            StatusCode::FORBIDDEN => Err(RealmsError::Forbidden),
+            status => Err(RealmsError::NonOK(status.as_u16())),
+        }
+    }
+
+    pub async fn fetch_realms(&self, minecraft_version: &str, modal_action: &ModalAction) -> Result<Arc<[RealmSummary]>, RealmsError> {
+        let login_info = self.get_login_info(modal_action).await.ok_or(RealmsError::NotSignedIn)?;
+        let Some(access_token) = &login_info.access_token else {
+            return Err(RealmsError::OfflineAccount);
+        };
+
+        // Realms uses the same session cookie as the game, the version is used to check compatibility
+        let cookie = format!("sid=token:{}:{};user={};version={}", access_token.secret(), login_info.uuid.simple(),
+            login_info.username, minecraft_version);
+
+        let worlds: RealmsWorlds = self.realms_get(&cookie, "/worlds").await?;
+
+        let live_players: Option<RealmsLivePlayerLists> = self.realms_get(&cookie, "/activities/liveplayerlist").await
+            .inspect_err(|error| log::warn!("Unable to get Realms player list: {error}"))
+            .ok();
+
+        let realms = worlds.servers.into_iter().map(|server| {
+            let online_players = live_players.as_ref()
+                .and_then(|live_players| live_players.lists.iter().find(|list| list.server_id == server.id))
+                .and_then(|list| serde_json::from_str::<Vec<RealmsLivePlayer>>(&list.player_list).ok())
+                .map(|players| players.iter().filter(|player| player.online).count())
+                .unwrap_or(0);
+
+            let state = match server.state {
+                _ if server.expired => RealmState::Expired,
+                RealmsServerState::Open => RealmState::Open,
+                RealmsServerState::Uninitialized => RealmState::Uninitialized,
+                RealmsServerState::Closed | RealmsServerState::Unknown => RealmState::Closed,
+            };
+
+            RealmSummary {
+                id: server.id,
+                name: server.name.unwrap_or_else(|| "Unnamed Realm".into()),
+                motd: server.motd.unwrap_or_default(),
+                owner: server.owner.unwrap_or_default(),
+                state,
+                online_players,
+                max_players: server.max_players,
+                minigame: server.minigame_name,
+            }
+        }).collect();
+
+        Ok(realms)
+    }
+}
@@ -0,0 +1,252 @@
+use std::{sync::Arc, time::{Duration, Instant}};
+
+use base64::Engine;
+use bridge::{instance::ServerStatus, message::MessageToFrontend};
+use schema::server_status::ServerStatusResponse;
+use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::TcpStream};
+
+use crate::BackendState;
+
+const DEFAULT_PORT: u16 = 25565;
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+const STATUS_CACHE_DURATION: Duration = Duration::from_secs(30);
+const MAX_PACKET_LENGTH: usize = 1 << 21;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ServerPingError {
+    #[error("Invalid server address")]
+    InvalidAddress,
+    #[error("Timed out")]
+    Timeout,
+    #[error("Unable to connect: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Server sent an invalid response")]
+    InvalidResponse,
+    #[error("Server sent an invalid status: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+}
+
+pub enum ServerStatusCacheEntry {
+    Pending,
+    Done {
+        fetched: Instant,
+        status: Result<ServerStatus, Arc<str>>,
+    },
+}
+
+impl BackendState {
+    pub fn request_server_status(&self, address: Arc<str>, force: bool) {
+        {
+            let mut cache = self.server_status_cache.write();
+            match cache.get(&address) {
+                Some(ServerStatusCacheEntry::Pending) => return,
+                Some(ServerStatusCacheEntry::Done { fetched, status }) if !force && fetched.elapsed() < STATUS_CACHE_DURATION => {
+                    self.send.send(MessageToFrontend::ServerStatusUpdated { address, status: status.clone() });
+                    return;
+                },
+                _ => {},
+            }
+            cache.insert(address.clone(), ServerStatusCacheEntry::Pending);
+        }
+
+        let cache = self.server_status_cache.clone();
+        let send = self.send.clone();
+        tokio::task::spawn(async move {
+            let status = ping_server(&address).await.map_err(|error| {
+                log::debug!("Unable to ping {address}: {error}");
+                Arc::from(error.to_string())
+            });
+
+            cache.write().insert(address.clone(), ServerStatusCacheEntry::Done {
+                fetched: Instant::now(),
+                status: status.clone(),
+            });
+            send.send(MessageToFrontend::ServerStatusUpdated { address, status });
+        });
+    }
+}
+
+pub async fn ping_server(address: &str) -> Result<ServerStatus, ServerPingError> {
+    let (host, port) = split_address(address).ok_or(ServerPingError::InvalidAddress)?;
+
+    // SRV records aren't resolved, servers that rely on them need to be added with an explicit port
+    tokio::time::timeout(PING_TIMEOUT, async {
+        let mut stream = TcpStream::connect((host, port)).await?;
+        ping_stream(&mut stream, host, port).await
+    }).await.map_err(|_| ServerPingError::Timeout)?
+}
+
+async fn ping_stream(stream: &mut TcpStream, host: &str, port: u16) -> Result<ServerStatus, ServerPingError> {
+    let mut handshake = Vec::new();
+    write_varint(&mut handshake, 0x00);
+    // Protocol version -1 is conventionally used when the client doesn't know the server's version
+    write_varint(&mut handshake, -1);
+    write_varint(&mut handshake, host.len() as i32);
+    handshake.extend_from_slice(host.as_bytes());
+    handshake.extend_from_slice(&port.to_be_bytes());
+    write_varint(&mut handshake, 1);
+    write_packet(stream, &handshake).await?;
+
+    let status_start = Instant::now();
+    write_packet(stream, &[0x00]).await?;
+
+    let packet = read_packet(stream).await?;
+    let status_latency = status_start.elapsed();
+
+    let mut data = packet.as_slice();
+    if read_varint_slice(&mut data)? != 0x00 {
+        return Err(ServerPingError::InvalidResponse);
+    }
+    let length = read_varint_slice(&mut data)?;
+    let json = usize::try_from(length).ok().and_then(|length| data.get(..length)).ok_or(ServerPingError::InvalidResponse)?;
+    let response: ServerStatusResponse = serde_json::from_slice(json)?;
+
+    // Some servers close the connection instead of answering the ping, fall back to the status round trip
+    let latency = measure_ping(stream).await.unwrap_or(status_latency);
+
+    let mut motd = String::new();
+    flatten_text_component(&response.description, &mut motd);
+
+    let png_icon = response.favicon.as_deref()
+        .and_then(|favicon| favicon.strip_prefix("data:image/png;base64,"))
+        .and_then(|favicon| base64::engine::general_purpose::STANDARD.decode(favicon.trim()).ok())
+        .map(Arc::from);
+
+    Ok(ServerStatus {
+        motd: strip_formatting_codes(&motd).trim().into(),
+        version_name: strip_formatting_codes(&response.version.name).into(),
+        online_players: response.players.online,
+        max_players: response.players.max,
+        latency_ms: latency.as_millis() as u64,
+        png_icon,
+    })
+}
+
+async fn measure_ping(stream: &mut TcpStream) -> Option<Duration> {
+    let payload = rand::random::<i64>();
+
+    let mut ping = vec![0x01];
+    ping.extend_from_slice(&payload.to_be_bytes());
+
+    let start = Instant::now();
+    write_packet(stream, &ping).await.ok()?;
+    let pong = read_packet(stream).await.ok()?;
+    let elapsed = start.elapsed();
+
+    let (&id, pong_payload) = pong.split_first()?;
+    if id != 0x01 || pong_payload != payload.to_be_bytes() {
+        return None;
+    }
+    Some(elapsed)
+}
+
+fn split_address(address: &str) -> Option<(&str, u16)> {
+    let address = address.trim();
+
+    if let Some(rest) = address.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']')?;
+        let port = match rest.strip_prefix(':') {
+            Some(port) => port.parse().ok()?,
+            None if rest.is_empty() => DEFAULT_PORT,
+            None => return None,
+        };
+        return Some((host, port));
+    }
+
+    match address.rsplit_once(':') {
+        // More than one colon means this is a bare IPv6 address
+        Some((host, _)) if host.contains(':') => Some((address, DEFAULT_PORT)),
+        Some((host, port)) => Some((host, port.parse().ok()?)),
+        None if address.is_empty() => None,
+        None => Some((address, DEFAULT_PORT)),
+    }
+}
+
+fn flatten_text_component(component: &serde_json::Value, output: &mut String) {
+    match component {
+        serde_json::Value::String(text) => output.push_str(text),
+        serde_json::Value::Array(components) => {
+            for component in components {
+                flatten_text_component(component, output);
+            }
+        },
+        serde_json::Value::Object(object) => {
+            if let Some(serde_json::Value::String(text)) = object.get("text") {
+                output.push_str(text);
+            }
+            if let Some(extra) = object.get("extra") {
+                flatten_text_component(extra, output);
+            }
+        },
+        _ => {},
+    }
+}
+
+fn strip_formatting_codes(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(char) = chars.next() {
+        if char == '§' {
+            chars.next();
+        } else {
+            stripped.push(char);
+        }
+    }
+    stripped
+}
+
+fn write_varint(buffer: &mut Vec<u8>, value: i32) {
+    let mut value = value as u32;
+    loop {
+        if value & !0x7F == 0 {
+            buffer.push(value as u8);
+            return;
+        }
+        buffer.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+}
+
+fn read_varint_slice(data: &mut &[u8]) -> Result<i32, ServerPingError> {
+    let mut value = 0u32;
+    for index in 0..5 {
+        let (&byte, rest) = data.split_first().ok_or(ServerPingError::InvalidResponse)?;
+        *data = rest;
+        value |= ((byte & 0x7F) as u32) << (index * 7);
+        if byte & 0x80 == 0 {
+            return Ok(value as i32);
+        }
+    }
+    Err(ServerPingError::InvalidResponse)
+}
+
+async fn read_varint(stream: &mut TcpStream) -> Result<i32, ServerPingError> {
+    let mut value = 0u32;
+    for index in 0..5 {
+        let byte = stream.read_u8().await?;
+        value |= ((byte & 0x7F) as u32) << (index * 7);
+        if byte & 0x80 == 0 {
+            return Ok(value as i32);
+        }
+    }
+    Err(ServerPingError::InvalidResponse)
+}
+
+async fn write_packet(stream: &mut TcpStream, packet: &[u8]) -> std::io::Result<()> {
+    let mut buffer = Vec::with_capacity(packet.len() + 5);
+    write_varint(&mut buffer, packet.len() as i32);
+    buffer.extend_from_slice(packet);
+    stream.write_all(&buffer).await
+}
+
+async fn read_packet(stream: &mut TcpStream) -> Result<Vec<u8>, ServerPingError> {
+    let length = read_varint(stream).await?;
+    let length = usize::try_from(length).map_err(|_| ServerPingError::InvalidResponse)?;
+    if length == 0 || length > MAX_PACKET_LENGTH {
+        return Err(ServerPingError::InvalidResponse);
+    }
+
+    let mut packet = vec![0; length];
+    stream.read_exact(&mut packet).await?;
+    Ok(packet)
+}
@@ -0,0 +1,227 @@
+use std::path::Path;
+
+use bridge::instance::{InstanceServerProperties, SERVER_DIFFICULTIES, SERVER_GAMEMODES};
+
+// Parser for the server.properties format used by dedicated servers. Like options.txt, lines that
+// aren't understood (comments, unknown keys) are kept verbatim when writing the file back
+#[derive(Debug, Default)]
+pub struct ServerProperties {
+    lines: Vec<ServerPropertiesLine>,
+}
+
+#[derive(Debug)]
+enum ServerPropertiesLine {
+    Entry { key: String, value: String },
+    Raw(String),
+}
+
+impl ServerProperties {
+    pub fn parse(content: &str) -> Self {
+        let lines = content
+            .lines()
+            .map(|line| {
+                let trimmed = line.trim_start();
+                if trimmed.starts_with('#') || trimmed.starts_with('!') {
+                    return ServerPropertiesLine::Raw(line.to_string());
+                }
+
+                if let Some((key, value)) = trimmed.split_once('=')
+                    && !key.trim_end().is_empty()
+                    && !key.trim_end().contains(char::is_whitespace)
+                {
+                    ServerPropertiesLine::Entry {
+                        key: key.trim_end().to_string(),
+                        value: unescape(value.trim_start()),
+                    }
+                } else {
+                    ServerPropertiesLine::Raw(line.to_string())
+                }
+            })
+            .collect();
+
+        Self { lines }
+    }
+
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        match std::fs::read(path) {
+            Ok(bytes) => Ok(Self::parse(&String::from_utf8_lossy(&bytes))),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        crate::write_safe(path, self.serialize().as_bytes())
+    }
+
+    pub fn serialize(&self) -> String {
+        let mut output = String::new();
+        for line in &self.lines {
+            match line {
+                ServerPropertiesLine::Entry { key, value } => {
+                    output.push_str(key);
+                    output.push('=');
+                    escape_into(value, &mut output);
+                },
+                ServerPropertiesLine::Raw(raw) => {
+                    output.push_str(raw);
+                },
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.lines.iter().find_map(|line| match line {
+            ServerPropertiesLine::Entry { key: entry_key, value } if entry_key == key => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn set(&mut self, key: &str, value: impl Into<String>) {
+        let value = value.into();
+        for line in &mut self.lines {
+            if let ServerPropertiesLine::Entry { key: entry_key, value: entry_value } = line
+                && entry_key == key
+            {
+                *entry_value = value;
+                return;
+            }
+        }
+        self.lines.push(ServerPropertiesLine::Entry {
+            key: key.to_string(),
+            value,
+        });
+    }
+
+    // Older servers store gamemode/difficulty as numbers, newer ones as names. Keep whatever style the file already uses
+    fn get_named(&self, key: &str, names: &[&'static str]) -> Option<&'static str> {
+        let value = self.get(key)?.trim();
+        if let Ok(index) = value.parse::<usize>() {
+            names.get(index).copied()
+        } else {
+            names.iter().find(|name| name.eq_ignore_ascii_case(value)).copied()
+        }
+    }
+
+    fn set_named(&mut self, key: &str, names: &[&'static str], value: &str) {
+        let Some(index) = names.iter().position(|name| name.eq_ignore_ascii_case(value)) else {
+            return;
+        };
+        if self.get(key).is_some_and(|existing| existing.trim().parse::<usize>().is_ok()) {
+            self.set(key, index.to_string());
+        } else {
+            self.set(key, names[index]);
+        }
+    }
+
+    pub fn server_properties(&self) -> InstanceServerProperties {
+        InstanceServerProperties {
+            eula_accepted: false,
+            motd: self.get("motd").map(Into::into),
+            server_port: self.get("server-port").and_then(|v| v.trim().parse().ok()),
+            max_players: self.get("max-players").and_then(|v| v.trim().parse().ok()),
+            gamemode: self.get_named("gamemode", SERVER_GAMEMODES).map(Into::into),
+            difficulty: self.get_named("difficulty", SERVER_DIFFICULTIES).map(Into::into),
+            view_distance: self.get("view-distance").and_then(|v| v.trim().parse().ok()),
+            online_mode: self.get("online-mode").and_then(|v| v.trim().parse().ok()),
+            pvp: self.get("pvp").and_then(|v| v.trim().parse().ok()),
+            white_list: self.get("white-list").and_then(|v| v.trim().parse().ok()),
+        }
+    }
+
+    pub fn apply_server_properties(&mut self, properties: &InstanceServerProperties) {
+        if let Some(motd) = &properties.motd {
+            self.set("motd", motd.to_string());
+        }
+        if let Some(server_port) = properties.server_port {
+            self.set("server-port", server_port.to_string());
+        }
+        if let Some(max_players) = properties.max_players {
+            self.set("max-players", max_players.to_string());
+        }
+        if let Some(gamemode) = &properties.gamemode {
+            self.set_named("gamemode", SERVER_GAMEMODES, gamemode);
+        }
+        if let Some(difficulty) = &properties.difficulty {
+            self.set_named("difficulty", SERVER_DIFFICULTIES, difficulty);
+        }
+        if let Some(view_distance) = properties.view_distance {
+            self.set("view-distance", view_distance.to_string());
+        }
+        if let Some(online_mode) = properties.online_mode {
+            self.set("online-mode", online_mode.to_string());
+        }
+        if let Some(pvp) = properties.pvp {
+            self.set("pvp", pvp.to_string());
+        }
+        if let Some(white_list) = properties.white_list {
+            self.set("white-list", white_list.to_string());
+        }
+    }
+}
+
+pub fn is_eula_accepted(dot_minecraft: &Path) -> bool {
+    let Ok(eula) = std::fs::read_to_string(dot_minecraft.join("eula.txt")) else {
+        return false;
+    };
+    ServerProperties::parse(&eula).get("eula").is_some_and(|v| v.trim().eq_ignore_ascii_case("true"))
+}
+
+pub fn accept_eula(dot_minecraft: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dot_minecraft)?;
+    let content = "# Accepted through Pandora Launcher, see https://aka.ms/MinecraftEULA\neula=true\n";
+    crate::write_safe(&dot_minecraft.join("eula.txt"), content.as_bytes())
+}
+
+fn unescape(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => output.push('\t'),
+            Some('n') => output.push('\n'),
+            Some('r') => output.push('\r'),
+            Some('f') => output.push('\u{c}'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    Some(c) => output.push(c),
+                    None => {
+                        output.push_str("\\u");
+                        output.push_str(&hex);
+                    }
+                }
+            },
+            Some(c) => output.push(c),
+            None => {},
+        }
+    }
+    output
+}
+
+fn escape_into(value: &str, output: &mut String) {
+    for (index, c) in value.chars().enumerate() {
+        match c {
+            '\\' => output.push_str("\\\\"),
+            '\t' => output.push_str("\\t"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\u{c}' => output.push_str("\\f"),
+            ' ' if index == 0 => output.push_str("\\ "),
+            // Servers before 1.18 read the file as ISO-8859-1, escaping keeps non-ascii text intact on every version
+            c if !c.is_ascii() => {
+                let mut buf = [0u16; 2];
+                for unit in c.encode_utf16(&mut buf) {
+                    output.push_str(&format!("\\u{:04x}", unit));
+                }
+            },
+            c => output.push(c),
+        }
+    }
+}
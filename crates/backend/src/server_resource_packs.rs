@@ -0,0 +1,147 @@
+use std::{path::Path, sync::Arc, time::UNIX_EPOCH};
+
+use bridge::instance::{InstanceID, InstanceServerResourcePack, ServerResourcePackSource};
+use sha1::{Digest, Sha1};
+use uuid::Uuid;
+
+use crate::BackendState;
+
+// Used by versions before 1.20.3, packs are stored as server-resource-packs/<sha1>
+const LEGACY_CACHE_FOLDER: &str = "server-resource-packs";
+// Used by 1.20.3 and newer, packs are stored as downloads/<pack id>/<sha1>
+const CACHE_FOLDER: &str = "downloads";
+
+#[derive(thiserror::Error, Debug)]
+pub enum ServerResourcePackError {
+    #[error("Instance no longer exists")]
+    MissingInstance,
+    #[error("File isn't in the server resource pack cache of this instance")]
+    InvalidPath,
+    #[error("File isn't a resource pack")]
+    NotAZip,
+    #[error("Unable to download resource pack: {0}")]
+    Download(#[from] reqwest::Error),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl BackendState {
+    fn instance_dot_minecraft(&self, id: InstanceID) -> Result<Arc<Path>, ServerResourcePackError> {
+        let instance_state = self.instance_state.read();
+        let instance = instance_state.instances.get(id).ok_or(ServerResourcePackError::MissingInstance)?;
+        Ok(instance.dot_minecraft_path.clone())
+    }
+
+    pub fn load_server_resource_packs(&self, id: InstanceID) -> Result<Arc<[InstanceServerResourcePack]>, ServerResourcePackError> {
+        let dot_minecraft = self.instance_dot_minecraft(id)?;
+
+        let mut packs = Vec::new();
+        collect_cached_packs(&dot_minecraft.join(LEGACY_CACHE_FOLDER), None, &mut packs);
+
+        if let Ok(read_dir) = std::fs::read_dir(dot_minecraft.join(CACHE_FOLDER)) {
+            for entry in read_dir.flatten() {
+                let path = entry.path();
+                // The folder also contains the download log, only the pack id folders are of interest
+                if !path.is_dir() {
+                    continue;
+                }
+                let pack_id = entry.file_name().to_str().map(Arc::from);
+                collect_cached_packs(&path, pack_id, &mut packs);
+            }
+        }
+
+        packs.sort_by_key(|pack| std::cmp::Reverse(pack.modified));
+        Ok(packs.into())
+    }
+
+    pub fn delete_server_resource_packs(&self, id: InstanceID, paths: &[Arc<Path>]) -> Result<(), ServerResourcePackError> {
+        let dot_minecraft = self.instance_dot_minecraft(id)?;
+        let legacy_cache = dot_minecraft.join(LEGACY_CACHE_FOLDER);
+        let cache = dot_minecraft.join(CACHE_FOLDER);
+
+        for path in paths {
+            let in_legacy_cache = path.parent() == Some(&*legacy_cache);
+            let in_cache = path.parent().and_then(Path::parent) == Some(&*cache);
+            if !(in_legacy_cache || in_cache) || path.file_name().is_none() {
+                return Err(ServerResourcePackError::InvalidPath);
+            }
+        }
+
+        for path in paths {
+            match std::fs::remove_file(path) {
+                Ok(()) => {},
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => {},
+                Err(error) => return Err(error.into()),
+            }
+            // Pack id folders are made again by the game when needed, don't leave empty ones behind
+            if let Some(parent) = path.parent() && parent.parent() == Some(&*cache) {
+                _ = std::fs::remove_dir(parent);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn seed_server_resource_pack(
+        &self,
+        id: InstanceID,
+        source: &ServerResourcePackSource,
+        pack_id: Option<Uuid>,
+    ) -> Result<(), ServerResourcePackError> {
+        let dot_minecraft = self.instance_dot_minecraft(id)?;
+
+        let bytes: Arc<[u8]> = match source {
+            ServerResourcePackSource::File(path) => std::fs::read(path)?.into(),
+            ServerResourcePackSource::Url(url) => {
+                let response = self.redirecting_http_client.get(&**url).send().await?.error_for_status()?;
+                response.bytes().await?.to_vec().into()
+            },
+        };
+
+        if !bytes.starts_with(b"PK") {
+            return Err(ServerResourcePackError::NotAZip);
+        }
+
+        let sha1 = hex::encode(Sha1::digest(&bytes));
+
+        // Which cache is used depends on the version, so the pack is placed in both when the id is known
+        crate::write_safe(&dot_minecraft.join(LEGACY_CACHE_FOLDER).join(&sha1), &bytes)?;
+        if let Some(pack_id) = pack_id {
+            crate::write_safe(&dot_minecraft.join(CACHE_FOLDER).join(pack_id.to_string()).join(&sha1), &bytes)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn collect_cached_packs(folder: &Path, pack_id: Option<Arc<str>>, packs: &mut Vec<InstanceServerResourcePack>) {
+    let Ok(read_dir) = std::fs::read_dir(folder) else {
+        return;
+    };
+
+    for entry in read_dir.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        let filename = entry.file_name();
+        let Some(filename) = filename.to_str() else {
+            continue;
+        };
+
+        let modified = metadata.modified().ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_secs() as i64)
+            .unwrap_or(0);
+
+        packs.push(InstanceServerResourcePack {
+            path: entry.path().into(),
+            sha1: filename.into(),
+            pack_id: pack_id.clone(),
+            size: metadata.len(),
+            modified,
+        });
+    }
+}
@@ -2,8 +2,15 @@ use std::ffi::OsStr;
 use std::path::Path;
 use std::path::PathBuf;
 
+// The icon shown for a shortcut. Where the shortcut can only refer to an icon file rather than contain it, the icon
+// is written to `saved_path` with the extension the platform needs
+pub struct ShortcutIcon<'a> {
+    pub png: &'a [u8],
+    pub saved_path: PathBuf,
+}
+
 #[cfg(target_os = "linux")]
-pub fn create_shortcut(mut path: PathBuf, name: &str, bin: &Path, args: &[&str]) {
+pub fn create_shortcut(mut path: PathBuf, name: &str, bin: &Path, args: &[&str], icon: Option<ShortcutIcon>) -> std::io::Result<()> {
     log::info!("Creating linux shortcut at {:?}", path);
 
     if !has_extension(&path, "desktop") {
@@ -11,41 +18,56 @@ pub fn create_shortcut(mut path: PathBuf, name: &str, bin: &Path, args: &[&str])
     }
 
     let Some(bin) = bin.to_str() else {
-        return;
+        return Err(std::io::Error::other("launcher path isn't valid UTF-8"));
     };
     let exec = shell_words::join(std::iter::once(bin).chain(args.iter().map(|s| *s)));
 
-    _ = std::fs::write(&path, format!(r#"[Desktop Entry]
+    let icon_line = icon.and_then(|icon| {
+        let icon_path = icon.saved_path.with_extension("png");
+        if let Err(err) = crate::write_safe(&icon_path, icon.png) {
+            log::warn!("Unable to write shortcut icon: {err}");
+            return None;
+        }
+        Some(format!("Icon={}\n", icon_path.to_string_lossy()))
+    }).unwrap_or_default();
+
+    std::fs::write(&path, format!(r#"[Desktop Entry]
 Type=Application
 Version=1.0
 Name={name}
 Exec=sh -c "{exec}"
-Categories=Games;Minecraft;Launcher;
-"#).as_bytes());
+{icon_line}Categories=Games;Minecraft;Launcher;
+"#).as_bytes())?;
 
     use std::os::unix::fs::PermissionsExt;
-    let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755));
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
 }
 
 #[cfg(target_os = "windows")]
-pub fn create_shortcut(mut path: PathBuf, name: &str, bin: &Path, args: &[&str]) {
+pub fn create_shortcut(mut path: PathBuf, name: &str, bin: &Path, args: &[&str], icon: Option<ShortcutIcon>) -> std::io::Result<()> {
     log::info!("Creating windows shortcut at {:?}", path);
 
     if !has_extension(&path, "lnk") {
         path.add_extension("lnk");
     }
-    let Ok(mut sl) = mslnk::ShellLink::new(bin) else {
-        return;
-    };
+    let mut sl = mslnk::ShellLink::new(bin).map_err(std::io::Error::other)?;
     let args_str = shell_words::join(args);
     sl.set_arguments(Some(args_str.into()));
     sl.set_name(Some(name.into()));
-    _ = sl.create_lnk(path);
 
+    if let Some(icon) = icon {
+        let icon_path = icon.saved_path.with_extension("ico");
+        match png_to_ico(icon.png).and_then(|ico| crate::write_safe(&icon_path, &ico)) {
+            Ok(()) => sl.set_icon_location(Some(icon_path.to_string_lossy().into_owned())),
+            Err(err) => log::warn!("Unable to write shortcut icon: {err}"),
+        }
+    }
+
+    sl.create_lnk(path).map_err(std::io::Error::other)
 }
 
 #[cfg(target_os = "macos")]
-pub fn create_shortcut(mut path: PathBuf, name: &str, bin: &Path, args: &[&str]) {
+pub fn create_shortcut(mut path: PathBuf, name: &str, bin: &Path, args: &[&str], icon: Option<ShortcutIcon>) -> std::io::Result<()> {
     log::info!("Creating macos shortcut at {:?}", path);
 
     if !has_extension(&path, "app") {
@@ -54,10 +76,23 @@ pub fn create_shortcut(mut path: PathBuf, name: &str, bin: &Path, args: &[&str])
 
     path.push("Contents");
 
-    _ = std::fs::create_dir_all(&path);
+    std::fs::create_dir_all(&path)?;
+
+    // The icon is stored inside the app bundle, so it doesn't need the saved path
+    let icon_entry = icon.and_then(|icon| {
+        let resources = path.join("Resources");
+        let result = std::fs::create_dir_all(&resources)
+            .and_then(|_| png_to_icns(icon.png))
+            .and_then(|icns| std::fs::write(resources.join("icon.icns"), icns));
+        if let Err(err) = result {
+            log::warn!("Unable to write shortcut icon: {err}");
+            return None;
+        }
+        Some("\n   \t<key>CFBundleIconFile</key>\n   \t<string>icon</string>")
+    }).unwrap_or_default();
 
     let info_plist = path.join("Info.plist");
-    _ = std::fs::write(&info_plist, format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+    std::fs::write(&info_plist, format!(r#"<?xml version="1.0" encoding="UTF-8"?>
 <!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
 <plist version="1.0">
 <dict>
@@ -68,7 +103,7 @@ pub fn create_shortcut(mut path: PathBuf, name: &str, bin: &Path, args: &[&str])
    	<key>CFBundleName</key>
    	<string>{name}</string>
    	<key>CFBundleDisplayName</key>
-   	<string>{name}</string>
+   	<string>{name}</string>{icon_entry}
    	<key>CFBundlePackageType</key>
    	<string>APPL</string>
    	<key>CFBundleSignature</key>
@@ -80,22 +115,62 @@ pub fn create_shortcut(mut path: PathBuf, name: &str, bin: &Path, args: &[&str])
    	<key>CFBundleVersion</key>
    	<string>0</string>
 </dict>
-</plist>"#).as_bytes());
+</plist>"#).as_bytes())?;
 
     let macos = path.join("MacOS");
-    _ = std::fs::create_dir_all(&macos);
+    std::fs::create_dir_all(&macos)?;
 
     let Some(bin) = bin.to_str() else {
-        return;
+        return Err(std::io::Error::other("launcher path isn't valid UTF-8"));
     };
     let exec = shell_words::join(std::iter::once(bin).chain(args.iter().map(|s| *s)));
 
-    let script_path = path.join("run.sh");
-    _ = std::fs::write(&script_path, format!(r#"#!/bin/sh
-{}"#, exec).as_bytes());
+    let script_path = macos.join("run.sh");
+    std::fs::write(&script_path, format!(r#"#!/bin/sh
+{}"#, exec).as_bytes())?;
 
     use std::os::unix::fs::PermissionsExt;
-    let _ = std::fs::set_permissions(&script_path, std::fs::Permissions::from_mode(0o755));
+    std::fs::set_permissions(&script_path, std::fs::Permissions::from_mode(0o755))
+}
+
+// ICO files can't contain images larger than 256x256
+#[cfg(target_os = "windows")]
+fn png_to_ico(png: &[u8]) -> std::io::Result<Vec<u8>> {
+    let image = image::load_from_memory_with_format(png, image::ImageFormat::Png).map_err(std::io::Error::other)?;
+    let image = if image.width() > 256 || image.height() > 256 {
+        image.resize(256, 256, image::imageops::FilterType::Lanczos3)
+    } else {
+        image
+    };
+
+    let mut ico = std::io::Cursor::new(Vec::new());
+    image.write_to(&mut ico, image::ImageFormat::Ico).map_err(std::io::Error::other)?;
+    Ok(ico.into_inner())
+}
+
+// ICNS files are a list of entries, each being a 4 byte type, a big endian length including the 8 byte entry header
+// and the data. Since macOS 10.7 the data of the ic07 (128x128) and ic08 (256x256) types can be a PNG
+#[cfg(target_os = "macos")]
+fn png_to_icns(png: &[u8]) -> std::io::Result<Vec<u8>> {
+    let image = image::load_from_memory_with_format(png, image::ImageFormat::Png).map_err(std::io::Error::other)?;
+
+    let mut entries = Vec::new();
+    for (entry_type, size) in [(b"ic07", 128), (b"ic08", 256)] {
+        let resized = image.resize_exact(size, size, image::imageops::FilterType::Lanczos3);
+        let mut data = std::io::Cursor::new(Vec::new());
+        resized.write_to(&mut data, image::ImageFormat::Png).map_err(std::io::Error::other)?;
+        let data = data.into_inner();
+
+        entries.extend_from_slice(entry_type);
+        entries.extend_from_slice(&(data.len() as u32 + 8).to_be_bytes());
+        entries.extend_from_slice(&data);
+    }
+
+    let mut icns = Vec::with_capacity(entries.len() + 8);
+    icns.extend_from_slice(b"icns");
+    icns.extend_from_slice(&(entries.len() as u32 + 8).to_be_bytes());
+    icns.extend_from_slice(&entries);
+    Ok(icns)
 }
 
 fn has_extension(path: &Path, extension: &str) -> bool {
@@ -0,0 +1,202 @@
+use std::path::{Path, PathBuf};
+
+use bridge::{instance::StrayInstanceFolder, modal_action::ModalAction};
+use rc_zip_sync::ReadZip;
+use schema::{instance::InstanceConfiguration, loader::Loader};
+use serde::Deserialize;
+use ustr::Ustr;
+
+use crate::BackendState;
+
+// Only this many mods are opened to guess the loader, the first few are almost always enough
+const MAX_MODS_CHECKED: usize = 16;
+
+// Files and folders which only exist in a folder Minecraft has been run in
+const MINECRAFT_MARKERS: &[&str] = &["options.txt", "saves", "mods", "resourcepacks", "config", "logs"];
+
+#[derive(thiserror::Error, Debug)]
+pub enum AdoptInstanceError {
+    #[error("The folder is no longer in the instances directory")]
+    NotInInstancesDir,
+    #[error("The folder has already been adopted")]
+    AlreadyAdopted,
+    #[error("Unable to move the game files into .minecraft: {0}")]
+    Move(std::io::Error),
+    #[error("Unable to write the instance configuration: {0}")]
+    Write(std::io::Error),
+}
+
+// Prism Launcher and MultiMC describe the game version and loader in mmc-pack.json
+#[derive(Deserialize)]
+struct MmcPack {
+    components: Vec<MmcPackComponent>,
+}
+
+#[derive(Deserialize)]
+struct MmcPackComponent {
+    uid: String,
+    version: Option<String>,
+}
+
+impl BackendState {
+    // Folders in the instances directory without an info_v1.json, which contain files from a Minecraft installation,
+    // e.g. restored from a backup, copied from a friend or left behind by another launcher
+    pub fn is_stray_instance_folder(&self, path: &Path) -> bool {
+        path.parent() == Some(&*self.directories.instances_dir)
+            && path.is_dir()
+            && !path.join("info_v1.json").exists()
+            && game_directory(path).is_some()
+    }
+
+    pub fn adopt_stray_instance(&mut self, path: &Path, minecraft_version: Ustr, loader: Loader, modal_action: &ModalAction) {
+        if let Err(error) = self.check_can_create_instance() {
+            modal_action.set_error_message(format!("Unable to adopt instance: {error}").into());
+            return;
+        }
+        if let Err(error) = self.try_adopt_stray_instance(path, minecraft_version, loader) {
+            log::error!("Unable to adopt {path:?}: {error}");
+            modal_action.set_error_message(error.to_string().into());
+        }
+    }
+
+    fn try_adopt_stray_instance(&mut self, path: &Path, minecraft_version: Ustr, loader: Loader) -> Result<(), AdoptInstanceError> {
+        if path.parent() != Some(&*self.directories.instances_dir) {
+            return Err(AdoptInstanceError::NotInInstancesDir);
+        }
+        let info_path = path.join("info_v1.json");
+        if info_path.exists() {
+            return Err(AdoptInstanceError::AlreadyAdopted);
+        }
+
+        move_into_dot_minecraft(path).map_err(AdoptInstanceError::Move)?;
+
+        // The instance is given a uuid when it's loaded
+        let configuration = InstanceConfiguration::new(minecraft_version, loader);
+        crate::write_safe(&info_path, serde_json::to_string(&configuration).unwrap().as_bytes()).map_err(AdoptInstanceError::Write)?;
+
+        self.load_instance_from_path(path, true, true);
+        Ok(())
+    }
+}
+
+// Pandora keeps the game files in .minecraft, other launchers use minecraft or the folder itself
+fn game_directory(path: &Path) -> Option<PathBuf> {
+    [path.join(".minecraft"), path.join("minecraft"), path.to_path_buf()].into_iter()
+        .find(|directory| MINECRAFT_MARKERS.iter().any(|marker| directory.join(marker).exists()))
+}
+
+fn move_into_dot_minecraft(path: &Path) -> std::io::Result<()> {
+    let dot_minecraft = path.join(".minecraft");
+    if dot_minecraft.is_dir() {
+        return Ok(());
+    }
+
+    let prism_minecraft = path.join("minecraft");
+    if prism_minecraft.is_dir() {
+        return std::fs::rename(prism_minecraft, dot_minecraft);
+    }
+
+    // The game files are directly inside the folder, move everything except the files of other launchers
+    std::fs::create_dir(&dot_minecraft)?;
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        if file_name == ".minecraft" || file_name == "mmc-pack.json" || file_name == "instance.cfg" {
+            continue;
+        }
+        std::fs::rename(entry.path(), dot_minecraft.join(file_name))?;
+    }
+    Ok(())
+}
+
+pub fn inspect_stray_instance(path: &Path) -> StrayInstanceFolder {
+    let name = path.file_name().map(|name| name.to_string_lossy().into()).unwrap_or_else(|| "Instance".into());
+    let (mut minecraft_version, mut loader) = read_mmc_pack(path).unwrap_or((None, Loader::Unknown));
+
+    if let Some(game_directory) = game_directory(path) {
+        if let Some((log_version, log_loader)) = read_latest_log(&game_directory) {
+            minecraft_version = minecraft_version.or(log_version);
+            if loader == Loader::Unknown {
+                loader = log_loader;
+            }
+        }
+        if loader == Loader::Unknown {
+            loader = guess_loader_from_mods(&game_directory.join("mods"));
+        }
+    }
+
+    StrayInstanceFolder {
+        path: path.into(),
+        name,
+        minecraft_version,
+        loader,
+    }
+}
+
+fn read_mmc_pack(path: &Path) -> Option<(Option<Ustr>, Loader)> {
+    let pack: MmcPack = crate::read_json(&path.join("mmc-pack.json")).ok()?;
+    let mut minecraft_version = None;
+    let mut loader = Loader::Vanilla;
+    for component in pack.components {
+        match component.uid.as_str() {
+            "net.minecraft" => minecraft_version = component.version.map(|version| Ustr::from(&version)),
+            "net.fabricmc.fabric-loader" => loader = Loader::Fabric,
+            "net.minecraftforge" => loader = Loader::Forge,
+            "net.neoforged" => loader = Loader::NeoForge,
+            _ => {},
+        }
+    }
+    Some((minecraft_version, loader))
+}
+
+// The start of latest.log mentions the game version for Fabric and the Forge launchers
+fn read_latest_log(game_directory: &Path) -> Option<(Option<Ustr>, Loader)> {
+    let log = std::fs::read(game_directory.join("logs").join("latest.log")).ok()?;
+    let log = String::from_utf8_lossy(&log[..log.len().min(64 * 1024)]);
+
+    if let Some(rest) = log.split_once("Loading Minecraft ").map(|(_, rest)| rest)
+        && let Some(version) = rest.split_whitespace().next()
+    {
+        let loader = if rest.starts_with(&format!("{version} with Fabric Loader")) { Loader::Fabric } else { Loader::Unknown };
+        return Some((Some(Ustr::from(version)), loader));
+    }
+
+    let loader = if log.contains("--fml.neoForgeVersion") {
+        Loader::NeoForge
+    } else if log.contains("--fml.forgeVersion") {
+        Loader::Forge
+    } else {
+        Loader::Unknown
+    };
+    let version = log.split_once("--fml.mcVersion, ")
+        .and_then(|(_, rest)| rest.split([',', ']', ' ']).next())
+        .map(Ustr::from);
+    Some((version, loader))
+}
+
+fn guess_loader_from_mods(mods: &Path) -> Loader {
+    let Ok(read_dir) = std::fs::read_dir(mods) else {
+        return Loader::Vanilla;
+    };
+
+    let jars = read_dir.flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "jar"))
+        .take(MAX_MODS_CHECKED);
+    for jar in jars {
+        let Ok(file) = std::fs::File::open(&jar) else {
+            continue;
+        };
+        let Ok(archive) = file.read_zip() else {
+            continue;
+        };
+        if archive.by_name("fabric.mod.json").is_some() {
+            return Loader::Fabric;
+        } else if archive.by_name("META-INF/neoforge.mods.toml").is_some() {
+            return Loader::NeoForge;
+        } else if archive.by_name("META-INF/mods.toml").is_some() {
+            return Loader::Forge;
+        }
+    }
+    Loader::Unknown
+}
@@ -9,7 +9,8 @@ use strum::IntoEnumIterator;
 
 use crate::directories::LauncherDirectories;
 
-pub fn apply_to_instance(sync_targets: EnumSet<SyncTarget>, directories: &LauncherDirectories, dot_minecraft: Arc<Path>) {
+// Instance roots are passed in rather than read from the instances directory since instances can be stored elsewhere
+pub fn apply_to_instance(sync_targets: EnumSet<SyncTarget>, directories: &LauncherDirectories, instance_roots: &[Arc<Path>], dot_minecraft: Arc<Path>) {
     _ = std::fs::create_dir_all(&dot_minecraft);
 
     for target in SyncTarget::iter() {
@@ -38,12 +39,12 @@ pub fn apply_to_instance(sync_targets: EnumSet<SyncTarget>, directories: &Launch
                 SyncTarget::Options => {
                     let fallback = &directories.synced_dir.join("fallback_options.txt");
                     let target = dot_minecraft.join("options.txt");
-                    let combined = create_combined_options_txt(fallback, &target, directories);
+                    let combined = create_combined_options_txt(fallback, &target, instance_roots);
                     _ = crate::write_safe(&fallback, combined.as_bytes());
                     _ = crate::write_safe(&target, combined.as_bytes());
                 },
                 SyncTarget::Servers => {
-                    if let Some(latest) = find_latest("servers.dat", directories) {
+                    if let Some(latest) = find_latest("servers.dat", instance_roots) {
                         let target = dot_minecraft.join("servers.dat");
                         if latest != target {
                             _ = std::fs::copy(latest, target);
@@ -51,7 +52,7 @@ pub fn apply_to_instance(sync_targets: EnumSet<SyncTarget>, directories: &Launch
                     }
                 },
                 SyncTarget::Commands => {
-                    if let Some(latest) = find_latest("command_history.txt", directories) {
+                    if let Some(latest) = find_latest("command_history.txt", instance_roots) {
                         let target = dot_minecraft.join("command_history.txt");
                         if latest != target {
                             _ = std::fs::copy(latest, target);
@@ -59,7 +60,7 @@ pub fn apply_to_instance(sync_targets: EnumSet<SyncTarget>, directories: &Launch
                     }
                 },
                 SyncTarget::Hotbars => {
-                    if let Some(latest) = find_latest("hotbar.nbt", directories) {
+                    if let Some(latest) = find_latest("hotbar.nbt", instance_roots) {
                         let target = dot_minecraft.join("hotbar.nbt");
                         if latest != target {
                             _ = std::fs::copy(latest, target);
@@ -74,19 +75,12 @@ pub fn apply_to_instance(sync_targets: EnumSet<SyncTarget>, directories: &Launch
     }
 }
 
-fn find_latest(filename: &'static str, directories: &LauncherDirectories) -> Option<PathBuf> {
+fn find_latest(filename: &'static str, instance_roots: &[Arc<Path>]) -> Option<PathBuf> {
     let mut latest_time = SystemTime::UNIX_EPOCH;
     let mut latest_path = None;
 
-    let read_dir = std::fs::read_dir(&directories.instances_dir).ok()?;
-
-    for entry in read_dir {
-        let Ok(entry) = entry else {
-            continue;
-        };
-
-        let mut path = entry.path();
-        path.push(".minecraft");
+    for root in instance_roots {
+        let mut path = root.join(".minecraft");
         path.push(filename);
 
         if let Ok(metadata) = std::fs::metadata(&path) {
@@ -109,22 +103,13 @@ fn find_latest(filename: &'static str, directories: &LauncherDirectories) -> Opt
     latest_path
 }
 
-fn create_combined_options_txt(fallback: &Path, current: &Path, directories: &LauncherDirectories) -> String {
+fn create_combined_options_txt(fallback: &Path, current: &Path, instance_roots: &[Arc<Path>]) -> String {
     let mut values = read_options_txt(fallback);
 
-    let Ok(read_dir) = std::fs::read_dir(&directories.instances_dir) else {
-        return create_options_txt(values);
-    };
-
     let mut paths = Vec::new();
 
-    for entry in read_dir {
-        let Ok(entry) = entry else {
-            continue;
-        };
-
-        let mut path = entry.path();
-        path.push(".minecraft");
+    for root in instance_roots {
+        let mut path = root.join(".minecraft");
         path.push("options.txt");
 
         let mut time = SystemTime::UNIX_EPOCH;
@@ -187,15 +172,8 @@ fn read_options_txt(path: &Path) -> FxHashMap<String, String> {
     values
 }
 
-pub fn get_sync_state(want_sync: EnumSet<SyncTarget>, directories: &LauncherDirectories) -> std::io::Result<SyncState> {
-    let mut paths = Vec::new();
-
-    let read_dir = std::fs::read_dir(&directories.instances_dir)?;
-    for entry in read_dir {
-        let mut path = entry?.path();
-        path.push(".minecraft");
-        paths.push(path);
-    }
+pub fn get_sync_state(want_sync: EnumSet<SyncTarget>, directories: &LauncherDirectories, instance_roots: &[Arc<Path>]) -> std::io::Result<SyncState> {
+    let paths: Vec<PathBuf> = instance_roots.iter().map(|root| root.join(".minecraft")).collect();
 
     let total = paths.len();
     let mut synced = EnumMap::default();
@@ -245,20 +223,12 @@ pub fn get_sync_state(want_sync: EnumSet<SyncTarget>, directories: &LauncherDire
     })
 }
 
-pub fn enable_all(target: SyncTarget, directories: &LauncherDirectories) -> std::io::Result<bool> {
+pub fn enable_all(target: SyncTarget, directories: &LauncherDirectories, instance_roots: &[Arc<Path>]) -> std::io::Result<bool> {
     let Some(sync_folder) = target.get_folder() else {
         return Ok(true);
     };
 
-    let mut paths = Vec::new();
-
-    let read_dir = std::fs::read_dir(&directories.instances_dir)?;
-    for entry in read_dir {
-        let mut path = entry?.path();
-        path.push(".minecraft");
-        path.push(sync_folder);
-        paths.push(path);
-    }
+    let mut paths: Vec<PathBuf> = instance_roots.iter().map(|root| root.join(".minecraft").join(sync_folder)).collect();
 
     let non_hidden_sync_folder = if sync_folder.starts_with(".") {
         &sync_folder[1..]
@@ -290,20 +260,12 @@ pub fn enable_all(target: SyncTarget, directories: &LauncherDirectories) -> std:
     Ok(true)
 }
 
-pub fn disable_all(target: SyncTarget, directories: &LauncherDirectories) -> std::io::Result<()> {
+pub fn disable_all(target: SyncTarget, directories: &LauncherDirectories, instance_roots: &[Arc<Path>]) -> std::io::Result<()> {
     let Some(sync_folder) = target.get_folder() else {
         return Ok(());
     };
 
-    let mut paths = Vec::new();
-
-    let read_dir = std::fs::read_dir(&directories.instances_dir)?;
-    for entry in read_dir {
-        let mut path = entry?.path();
-        path.push(".minecraft");
-        path.push(sync_folder);
-        paths.push(path);
-    }
+    let paths: Vec<PathBuf> = instance_roots.iter().map(|root| root.join(".minecraft").join(sync_folder)).collect();
 
     let non_hidden_sync_folder = if sync_folder.starts_with(".") {
         &sync_folder[1..]
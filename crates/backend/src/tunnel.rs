@@ -0,0 +1,116 @@
+use std::{
+    io::{BufRead, BufReader}, path::Path, process::{Child, Stdio}, sync::Arc, time::Duration
+};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+// Used when no command has been configured. ngrok prints its log as json, which contains the public url of the tunnel
+pub const DEFAULT_TUNNEL_COMMAND: &str = "ngrok tcp {port} --log stdout --log-format json";
+
+const TUNNEL_START_TIMEOUT: Duration = Duration::from_secs(30);
+
+static TUNNEL_ADDRESS: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"tcp://([A-Za-z0-9.-]+:\d+)|\b([A-Za-z0-9.-]+\.(?:joinmc\.link|ply\.gg|playit\.gg)(?::\d+)?)\b"#).unwrap()
+});
+static LAN_PORT: Lazy<Regex> = Lazy::new(|| Regex::new(r#"Started (?:serving )?on (\d+)"#).unwrap());
+
+#[derive(thiserror::Error, Debug)]
+pub enum TunnelError {
+    #[error("Invalid tunnel command: {0}")]
+    InvalidCommand(Arc<str>),
+    #[error("Unable to start tunnel:\n{0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Tunnel exited before providing an address{}", .0.as_ref().map(|line| format!(":\n{line}")).unwrap_or_default())]
+    Exited(Option<String>),
+    #[error("Timed out waiting for the tunnel address")]
+    Timeout,
+}
+
+// A running tunnel agent. The process is killed when this is dropped, so removing it from the instance tears the tunnel down
+#[derive(Debug)]
+pub struct Tunnel {
+    child: Child,
+    pub address: Arc<str>,
+}
+
+impl Drop for Tunnel {
+    fn drop(&mut self) {
+        log::info!("Closing tunnel {}", self.address);
+        _ = self.child.kill();
+        _ = self.child.wait();
+    }
+}
+
+pub async fn start_tunnel(command: &str, port: u16) -> Result<Tunnel, TunnelError> {
+    let port = port.to_string();
+    let args: Vec<String> = shell_words::split(command)
+        .map_err(|_| TunnelError::InvalidCommand(command.into()))?
+        .into_iter()
+        .map(|arg| arg.replace("{port}", &port))
+        .collect();
+    let Some((program, args)) = args.split_first() else {
+        return Err(TunnelError::InvalidCommand(command.into()));
+    };
+
+    log::info!("Starting tunnel for port {port}: {program} {args:?}");
+
+    let mut child = std::process::Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    let stdout = child.stdout.take().unwrap();
+    let (send, recv) = tokio::sync::oneshot::channel();
+
+    // The agent keeps logging for as long as it runs, so the output has to be drained even after the address was found
+    std::thread::spawn(move || {
+        let mut send = Some(send);
+        let mut last_line = None;
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if let Some(send) = send.take_if(|_| TUNNEL_ADDRESS.is_match(&line)) {
+                let captures = TUNNEL_ADDRESS.captures(&line).unwrap();
+                let address = captures.get(1).or(captures.get(2)).unwrap().as_str();
+                _ = send.send(Ok(Arc::<str>::from(address)));
+            } else if send.is_some() && !line.trim().is_empty() {
+                last_line = Some(line);
+            }
+        }
+        if let Some(send) = send {
+            _ = send.send(Err(last_line));
+        }
+    });
+
+    match tokio::time::timeout(TUNNEL_START_TIMEOUT, recv).await {
+        Ok(Ok(Ok(address))) => {
+            log::info!("Tunnel started at {address}");
+            Ok(Tunnel { child, address })
+        },
+        Ok(Ok(Err(last_line))) => {
+            _ = child.wait();
+            Err(TunnelError::Exited(last_line))
+        },
+        Ok(Err(_)) => {
+            _ = child.kill();
+            _ = child.wait();
+            Err(TunnelError::Exited(None))
+        },
+        Err(_) => {
+            _ = child.kill();
+            _ = child.wait();
+            Err(TunnelError::Timeout)
+        },
+    }
+}
+
+// Finds the port of a world that was opened to LAN by looking through the latest log of the game
+pub fn find_lan_port(dot_minecraft: &Path) -> Option<u16> {
+    let log = std::fs::read_to_string(dot_minecraft.join("logs").join("latest.log")).ok()?;
+    let captures = LAN_PORT.captures_iter(&log).last()?;
+    captures.get(1)?.as_str().parse().ok()
+}
@@ -1,15 +1,52 @@
 use std::{ffi::{OsStr, OsString}, io::Cursor, path::{Path, PathBuf}, sync::Arc};
 
 use base64::Engine;
-use bridge::{handle::FrontendHandle, message::MessageToFrontend, modal_action::{ModalAction, ProgressTracker}};
+use bridge::{handle::FrontendHandle, message::MessageToFrontend, modal_action::{ModalAction, ProgressTracker}, sandbox::Sandbox};
 use reqwest::StatusCode;
-use schema::pandora_update::{UpdateInstallType, UpdateManifest, UpdatePrompt};
+use schema::{backend_config::UpdateChannel, pandora_update::{UpdateInstallType, UpdateManifest, UpdateManifestExe, UpdatePatchFormat, UpdatePrompt, WindowsInstallerKind}};
 use sha1::{Digest, Sha1};
 use rand::RngCore;
 
-use crate::directories::LauncherDirectories;
+use crate::{BackendState, directories::LauncherDirectories};
+
+// How long "Remind me later" hides available updates for
+pub const UPDATE_REMINDER_DELAY: chrono::TimeDelta = chrono::TimeDelta::hours(24);
+
+// The settings deciding which manifest is checked and whether an available update is offered
+pub struct UpdateCheckOptions {
+    pub channel: UpdateChannel,
+    pub skipped_version: Option<Arc<str>>,
+    pub reminder_after: Option<i64>,
+    pub rollout_seed: u32,
+}
+
+impl BackendState {
+    pub fn update_check_options(&self) -> UpdateCheckOptions {
+        let mut config = self.config.write();
+        let rollout_seed = match config.get().update_rollout_seed {
+            Some(rollout_seed) => rollout_seed,
+            None => {
+                let rollout_seed = rand::thread_rng().next_u32();
+                config.modify(|config| {
+                    config.update_rollout_seed = Some(rollout_seed);
+                });
+                rollout_seed
+            },
+        };
+
+        let config = config.get();
+        UpdateCheckOptions {
+            channel: config.update_channel,
+            skipped_version: config.skipped_update_version.clone(),
+            reminder_after: config.update_reminder_after,
+            rollout_seed,
+        }
+    }
+}
+
+pub async fn check_for_updates(http_client: reqwest::Client, send: FrontendHandle, options: UpdateCheckOptions) {
+    let channel = options.channel;
 
-pub async fn check_for_updates(http_client: reqwest::Client, send: FrontendHandle) {
     if option_env!("PANDORA_UPDATE_PUBKEY").is_none() {
         return;
     }
@@ -32,7 +69,12 @@ pub async fn check_for_updates(http_client: reqwest::Client, send: FrontendHandl
 
     let current_version = schema::forge::VersionFragment::string_to_parts(version);
 
-    let url = format!("{repository_url}/releases/latest/download/update_{}.json", std::env::consts::OS);
+    // Beta and nightly builds are uploaded to pre-releases with a fixed tag, which GitHub never considers the latest release
+    let url = match channel {
+        UpdateChannel::Stable => format!("{repository_url}/releases/latest/download/update_{}.json", std::env::consts::OS),
+        UpdateChannel::Beta => format!("{repository_url}/releases/download/beta/update_{}.json", std::env::consts::OS),
+        UpdateChannel::Nightly => format!("{repository_url}/releases/download/nightly/update_{}.json", std::env::consts::OS),
+    };
     let response = http_client.get(url).send().await;
 
     let response = match response {
@@ -69,9 +111,32 @@ pub async fn check_for_updates(http_client: reqwest::Client, send: FrontendHandl
 
     let update_version = schema::forge::VersionFragment::string_to_parts(&manifest.version);
 
-    if current_version >= update_version {
+    if current_version == update_version {
         log::info!("Pandora is up-to-date");
         return;
+    } else if current_version > update_version {
+        // Never downgrade, e.g. after switching from the beta channel back to stable
+        log::info!("Pandora {} is newer than the latest {} release {}, not downgrading", version, channel.name(), manifest.version);
+        return;
+    }
+
+    if options.skipped_version.as_deref() == Some(&*manifest.version) {
+        log::info!("Not offering Pandora {}, the version was skipped", manifest.version);
+        return;
+    }
+
+    if let Some(reminder_after) = options.reminder_after
+        && chrono::Utc::now().timestamp() < reminder_after
+    {
+        log::info!("Not offering Pandora {} until the reminder is due", manifest.version);
+        return;
+    }
+
+    if let Some(rollout_percentage) = manifest.rollout_percentage
+        && rollout_bucket(options.rollout_seed, &manifest.version) >= u32::from(rollout_percentage)
+    {
+        log::info!("Not offering Pandora {}, it's only rolled out to {}% of launchers so far", manifest.version, rollout_percentage);
+        return;
     }
 
     let exes = if let Some(universal) = manifest.downloads.archs.get("universal") {
@@ -83,27 +148,49 @@ pub async fn check_for_updates(http_client: reqwest::Client, send: FrontendHandl
         return;
     };
 
-    let Some(install_type) = determine_update_install_type() else {
+    let Some(mut install_type) = determine_update_install_type() else {
         log::warn!("Unable to update, can't determine installation type");
         return;
     };
 
+    // Releases without installer builds are installed by replacing the executable, like before installers existed
+    if matches!(install_type, UpdateInstallType::Installer { .. }) && !exes.exes.contains_key(install_type.key()) {
+        log::info!("No \"{}\" download in {:?}, replacing the executable instead", install_type.key(), exes.exes.keys());
+        install_type = UpdateInstallType::Executable;
+    }
+
     let install_type_key = install_type.key();
     let Some(executable) = exes.exes.get(install_type_key) else {
         log::warn!("Unable to update, installation type \"{}\" not in {:?}", install_type_key, exes.exes.keys());
         return;
     };
 
+    if let Some(sandbox) = Sandbox::detect() {
+        log::info!("Not offering Pandora {}, {} packages are updated by the package manager", manifest.version, sandbox.name());
+        send.send_info(format!("Pandora {} is available. {}", manifest.version, sandbox.update_hint()));
+        return;
+    }
+
     send.send(MessageToFrontend::UpdateAvailable {
         update: UpdatePrompt {
             old_version: version.into(),
             new_version: manifest.version.clone(),
+            channel,
             install_type,
             exe: executable.clone(),
         }
     });
 }
 
+// Between 0 and 99, the version is included so a different group of launchers receives each release first
+fn rollout_bucket(rollout_seed: u32, version: &str) -> u32 {
+    let mut hasher = Sha1::new();
+    hasher.update(rollout_seed.to_le_bytes());
+    hasher.update(version.as_bytes());
+    let hash = hasher.finalize();
+    u32::from_le_bytes([hash[0], hash[1], hash[2], hash[3]]) % 100
+}
+
 fn determine_update_install_type() -> Option<UpdateInstallType> {
     if let Some(appimage) = std::env::var_os("APPIMAGE") {
         return Some(UpdateInstallType::AppImage(appimage.into()));
@@ -115,9 +202,76 @@ fn determine_update_install_type() -> Option<UpdateInstallType> {
         return Some(UpdateInstallType::App(app.to_path_buf()));
     }
 
+    if let Some(installer) = determine_windows_installer(&current_exe) {
+        return Some(installer);
+    }
+
     return Some(UpdateInstallType::Executable);
 }
 
+// Installers register an uninstall entry pointing at the install location, replacing only the executable would leave
+// its Start Menu entries and uninstaller describing the old version
+#[cfg(target_os = "windows")]
+fn determine_windows_installer(current_exe: &Path) -> Option<UpdateInstallType> {
+    let install_dir = current_exe.parent()?.to_str()?.trim_end_matches('\\');
+
+    for (root, per_machine) in [("HKCU", false), ("HKLM", true)] {
+        let uninstall_key = format!("{root}\\Software\\Microsoft\\Windows\\CurrentVersion\\Uninstall");
+        let Some(output) = query_registry(&["query", &uninstall_key, "/s", "/f", install_dir, "/d"]) else {
+            continue;
+        };
+
+        for key in output.lines().filter(|line| line.starts_with("HKEY_")) {
+            let Some(values) = query_registry(&["query", key]) else {
+                continue;
+            };
+            let values = parse_registry_values(&values);
+            let value = |name: &str| values.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str());
+
+            let install_location = value("InstallLocation").unwrap_or_default().trim_matches('"').trim_end_matches('\\');
+            if !install_location.eq_ignore_ascii_case(install_dir) || !value("DisplayName").is_some_and(|name| name.contains("Pandora")) {
+                continue;
+            }
+
+            let is_msi = value("WindowsInstaller") == Some("0x1")
+                || value("UninstallString").is_some_and(|uninstall| uninstall.to_ascii_lowercase().contains("msiexec"));
+            let kind = if is_msi { WindowsInstallerKind::Msi } else { WindowsInstallerKind::Nsis };
+            return Some(UpdateInstallType::Installer { kind, per_machine });
+        }
+    }
+
+    None
+}
+
+#[cfg(not(target_os = "windows"))]
+fn determine_windows_installer(_current_exe: &Path) -> Option<UpdateInstallType> {
+    None
+}
+
+#[cfg(target_os = "windows")]
+fn query_registry(args: &[&str]) -> Option<String> {
+    use std::os::windows::process::CommandExt;
+
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let output = std::process::Command::new("reg").creation_flags(CREATE_NO_WINDOW).args(args).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// Value lines look like `    Name    REG_SZ    Data`
+#[cfg(target_os = "windows")]
+fn parse_registry_values(output: &str) -> Vec<(String, String)> {
+    output.lines()
+        .filter_map(|line| {
+            let mut parts = line.trim_start().splitn(3, "    ");
+            let name = parts.next()?;
+            let _kind = parts.next()?;
+            let data = parts.next().unwrap_or_default();
+            Some((name.to_string(), data.trim().to_string()))
+        })
+        .collect()
+}
+
 fn determine_macos_app_path(current_exe: &Path) -> Option<&Path> {
     let parent = current_exe.parent()?;
 
@@ -140,26 +294,135 @@ fn determine_macos_app_path(current_exe: &Path) -> Option<&Path> {
     Some(parent3)
 }
 
+// The executable of the installed update, relaunching only ever starts an executable which was just installed
+static INSTALLED_EXECUTABLE: parking_lot::Mutex<Option<PathBuf>> = parking_lot::Mutex::new(None);
+
+// Installers can't replace the running executable, so they run once the launcher has exited
+static PENDING_INSTALLER: parking_lot::Mutex<Option<PendingInstaller>> = parking_lot::Mutex::new(None);
+
+struct PendingInstaller {
+    path: PathBuf,
+    kind: WindowsInstallerKind,
+    per_machine: bool,
+}
+
 pub async fn install_update(http_client: reqwest::Client, dirs: Arc<LauncherDirectories>, send: FrontendHandle, update: UpdatePrompt, modal_action: ModalAction) {
-    if let Err(error) = install_update_inner(http_client, &dirs, send.clone(), update, modal_action.clone()).await {
-        modal_action.set_error_message(error);
+    let version = update.new_version.clone();
+    match install_update_inner(http_client, &dirs, send.clone(), update, modal_action.clone()).await {
+        Ok(executable) => {
+            *INSTALLED_EXECUTABLE.lock() = Some(executable);
+            send.send(MessageToFrontend::UpdateInstalled { version });
+        },
+        Err(error) => {
+            modal_action.set_error_message(error);
+        },
     }
 
     modal_action.set_finished();
     send.send(MessageToFrontend::Refresh);
 }
 
-async fn install_update_inner(http_client: reqwest::Client, dirs: &LauncherDirectories, send: FrontendHandle, update: UpdatePrompt, modal_action: ModalAction) -> Result<(), Arc<str>> {
+async fn install_update_inner(http_client: reqwest::Client, dirs: &LauncherDirectories, send: FrontendHandle, update: UpdatePrompt, modal_action: ModalAction) -> Result<PathBuf, Arc<str>> {
+    // The executable inside the container is read-only and replacing it would be undone by the package manager anyway
+    if let Some(sandbox) = Sandbox::detect() {
+        return Err(format!("Pandora was installed using {}. {}", sandbox.name(), sandbox.update_hint()).into());
+    }
+
     let title = format!("Downloading Pandora {}", update.new_version);
     let tracker = ProgressTracker::new(title.into(), send.clone());
     modal_action.trackers.push(tracker.clone());
 
-    let mut expected_hash = [0u8; 20];
-    let Ok(_) = hex::decode_to_slice(&*update.exe.sha1, &mut expected_hash) else {
-        return Err("Unable to decode sha1 hash".into());
+    let bytes = match download_patched(&http_client, &update, &tracker).await {
+        Some(bytes) => bytes,
+        None => {
+            let bytes = download(&http_client, &update.exe.download, update.exe.size, &tracker).await?;
+            verify_executable(&bytes, &update.exe)?;
+            bytes
+        },
     };
 
-    let Ok(response) = http_client.get(&*update.exe.download).send().await else {
+    let executable = match update.install_type {
+        UpdateInstallType::AppImage(appimage) => {
+            let Some(filename) = appimage.file_name() else {
+                return Err("Appimage path has no filename".into());
+            };
+
+            let new_filename = replace_os_str(filename, &update.old_version, &update.new_version);
+            let new_appimage = appimage.with_file_name(new_filename);
+
+            write_new_exe(appimage, new_appimage.clone(), &bytes, dirs)?;
+            new_appimage
+        },
+        UpdateInstallType::Executable => {
+            let Ok(current_exe) = std::env::current_exe() else {
+                return Err("Unable to determine current exe path".into());
+            };
+
+            let Some(filename) = current_exe.file_name() else {
+                return Err("Current exe path has no filename".into());
+            };
+
+            let new_filename = replace_os_str(filename, &update.old_version, &update.new_version);
+            let new_exe = current_exe.with_file_name(new_filename);
+
+            write_new_exe(current_exe, new_exe.clone(), &bytes, dirs)?;
+            new_exe
+        },
+        UpdateInstallType::App(current_app_folder) => {
+            // The .app is replaced in place, so the executable inside it keeps its path
+            let Ok(current_exe) = std::env::current_exe() else {
+                return Err("Unable to determine current exe path".into());
+            };
+
+            let mut temp_extract = dirs.temp_dir.join(format!("app_unpack_{}", rand::thread_rng().next_u64()));
+            while temp_extract.exists() {
+                log::warn!("Randomly generated app_unpack folder exists... what are the chances? ({:?})", temp_extract);
+                temp_extract = dirs.temp_dir.join(format!("app_unpack_{}", rand::thread_rng().next_u64()));
+            }
+
+            let mut temp_backup = dirs.temp_dir.join(format!("app_backup_{}", rand::thread_rng().next_u64()));
+            while temp_backup.exists() {
+                log::warn!("Randomly generated app_backup folder exists... what are the chances? ({:?})", temp_backup);
+                temp_backup = dirs.temp_dir.join(format!("app_backup_{}", rand::thread_rng().next_u64()));
+            }
+
+            let result = install_app_update(current_app_folder, &bytes, &temp_extract, &temp_backup);
+
+            _ = std::fs::remove_dir_all(temp_backup);
+            _ = std::fs::remove_dir_all(temp_extract);
+
+            if let Err(err) = result {
+                return Err(err);
+            }
+
+            current_exe
+        },
+        UpdateInstallType::Installer { kind, per_machine } => {
+            let Ok(current_exe) = std::env::current_exe() else {
+                return Err("Unable to determine current exe path".into());
+            };
+
+            let extension = match kind {
+                WindowsInstallerKind::Msi => "msi",
+                WindowsInstallerKind::Nsis => "exe",
+            };
+            let installer = dirs.temp_dir.join(format!("PandoraLauncher-Setup-{}.{}", update.new_version, extension));
+            if let Err(err) = std::fs::write(&installer, &bytes) {
+                log::error!("Error while writing installer: {}", err);
+                return Err("Error while writing installer, see logs for more details".into());
+            }
+
+            // The installer keeps the install location, so the executable keeps its path
+            *PENDING_INSTALLER.lock() = Some(PendingInstaller { path: installer, kind, per_machine });
+            current_exe
+        },
+    };
+
+    Ok(executable)
+}
+
+async fn download(http_client: &reqwest::Client, url: &str, size: usize, tracker: &ProgressTracker) -> Result<Vec<u8>, Arc<str>> {
+    let Ok(response) = http_client.get(url).send().await else {
         return Err("Error making download request".into());
     };
 
@@ -167,7 +430,8 @@ async fn install_update_inner(http_client: reqwest::Client, dirs: &LauncherDirec
         return Err("Download URL returned non-200 status code".into());
     }
 
-    tracker.set_total(update.exe.size);
+    tracker.set_count(0);
+    tracker.set_total(size);
     tracker.notify();
 
     use futures::StreamExt;
@@ -185,25 +449,40 @@ async fn install_update_inner(http_client: reqwest::Client, dirs: &LauncherDirec
         tracker.notify();
     }
 
+    Ok(bytes)
+}
+
+fn verify_sha1(bytes: &[u8], sha1: &str) -> Result<(), Arc<str>> {
+    let mut expected_hash = [0u8; 20];
+    let Ok(_) = hex::decode_to_slice(sha1, &mut expected_hash) else {
+        return Err("Unable to decode sha1 hash".into());
+    };
+
     let mut hasher = Sha1::new();
-    hasher.update(&bytes);
+    hasher.update(bytes);
     let actual_hash = hasher.finalize();
 
     if expected_hash != *actual_hash {
         return Err("Hash of downloaded file does not match".into());
     }
 
+    Ok(())
+}
+
+fn verify_executable(bytes: &[u8], exe: &UpdateManifestExe) -> Result<(), Arc<str>> {
+    verify_sha1(bytes, &exe.sha1)?;
+
     let Some(pubkey) = option_env!("PANDORA_UPDATE_PUBKEY") else {
         return Err("Unable to update, missing PANDORA_UPDATE_PUBKEY at compile time".into());
     };
 
     let pubkey = base64::engine::general_purpose::STANDARD.decode(pubkey).unwrap();
-    let sig = base64::engine::general_purpose::STANDARD.decode(&*update.exe.sig).unwrap();
+    let sig = base64::engine::general_purpose::STANDARD.decode(&*exe.sig).unwrap();
 
     let pk = minisign_verify::PublicKey::decode(std::str::from_utf8(&pubkey).unwrap()).unwrap();
     let signature = minisign_verify::Signature::decode(std::str::from_utf8(&sig).unwrap()).unwrap();
 
-    match pk.verify(&bytes, &signature, false) {
+    match pk.verify(bytes, &signature, false) {
         Err(minisign_verify::Error::InvalidSignature) => {
             return Err("Invalid signature, file was not properly signed".into());
         },
@@ -213,60 +492,148 @@ async fn install_update_inner(http_client: reqwest::Client, dirs: &LauncherDirec
         Ok(_) => {}
     }
 
-    match update.install_type {
-        UpdateInstallType::AppImage(appimage) => {
-            let Some(filename) = appimage.file_name() else {
-                return Err("Appimage path has no filename".into());
-            };
+    Ok(())
+}
 
-            let new_filename = replace_os_str(filename, &update.old_version, &update.new_version);
-            let new_appimage = appimage.with_file_name(new_filename);
+// Downloads a patch from the current version and applies it to the current executable. Returns None when there is no
+// patch or it can't be used, in which case the full executable is downloaded instead
+async fn download_patched(http_client: &reqwest::Client, update: &UpdatePrompt, tracker: &ProgressTracker) -> Option<Vec<u8>> {
+    let patch = update.exe.patches.iter().find(|patch| patch.from == update.old_version)?;
 
-            write_new_exe(appimage, new_appimage, &bytes, dirs)?;
-        },
-        UpdateInstallType::Executable => {
-            let Ok(current_exe) = std::env::current_exe() else {
-                return Err("Unable to determine current exe path".into());
-            };
+    // The .app is distributed as an archive and installers are separate programs, so there is no old file to apply a patch to
+    let old_exe = match &update.install_type {
+        UpdateInstallType::AppImage(appimage) => appimage.clone(),
+        UpdateInstallType::Executable => std::env::current_exe().ok()?,
+        UpdateInstallType::App(_) | UpdateInstallType::Installer { .. } => return None,
+    };
 
-            let Some(filename) = current_exe.file_name() else {
-                return Err("Current exe path has no filename".into());
-            };
+    let result = async {
+        let old_bytes = tokio::fs::read(&old_exe).await
+            .map_err(|err| Arc::<str>::from(format!("Unable to read current executable: {}", err)))?;
 
-            let new_filename = replace_os_str(filename, &update.old_version, &update.new_version);
-            let new_exe = current_exe.with_file_name(new_filename);
+        let patch_bytes = download(http_client, &patch.download, patch.size, tracker).await?;
+        verify_sha1(&patch_bytes, &patch.sha1)?;
+
+        let format = patch.format;
+        let patched = tokio::task::spawn_blocking(move || apply_patch(format, &old_bytes, &patch_bytes)).await
+            .map_err(|_| Arc::<str>::from("Patching was cancelled"))?
+            .map_err(|err| Arc::<str>::from(format!("Unable to apply patch: {}", err)))?;
 
-            write_new_exe(current_exe, new_exe, &bytes, dirs)?;
+        verify_executable(&patched, &update.exe)?;
+        Ok::<_, Arc<str>>(patched)
+    }.await;
+
+    match result {
+        Ok(patched) => {
+            log::info!("Updated using a {:?} patch of {} bytes", patch.format, patch.size);
+            Some(patched)
         },
-        UpdateInstallType::App(current_app_folder) => {
-            let mut temp_extract = dirs.temp_dir.join(format!("app_unpack_{}", rand::thread_rng().next_u64()));
-            while temp_extract.exists() {
-                log::warn!("Randomly generated app_unpack folder exists... what are the chances? ({:?})", temp_extract);
-                temp_extract = dirs.temp_dir.join(format!("app_unpack_{}", rand::thread_rng().next_u64()));
-            }
+        Err(err) => {
+            log::warn!("Unable to update using a patch, downloading the full executable instead: {}", err);
+            None
+        },
+    }
+}
 
-            let mut temp_backup = dirs.temp_dir.join(format!("app_backup_{}", rand::thread_rng().next_u64()));
-            while temp_backup.exists() {
-                log::warn!("Randomly generated app_backup folder exists... what are the chances? ({:?})", temp_backup);
-                temp_backup = dirs.temp_dir.join(format!("app_backup_{}", rand::thread_rng().next_u64()));
-            }
+fn apply_patch(format: UpdatePatchFormat, old: &[u8], patch: &[u8]) -> std::io::Result<Vec<u8>> {
+    match format {
+        UpdatePatchFormat::Bsdiff => {
+            let patcher = qbsdiff::Bspatch::new(patch)?;
+            let mut patched = Vec::with_capacity(patcher.hint_target_size() as usize);
+            patcher.apply(old, &mut patched)?;
+            Ok(patched)
+        },
+        UpdatePatchFormat::Zstd => {
+            let mut decoder = zstd::stream::read::Decoder::with_ref_prefix(patch, old)?;
+            // Patches of large executables need a window as large as the old executable
+            decoder.window_log_max(31)?;
+            let mut patched = Vec::new();
+            std::io::Read::read_to_end(&mut decoder, &mut patched)?;
+            Ok(patched)
+        },
+    }
+}
 
-            let result = install_app_update(current_app_folder, &bytes, &temp_extract, &temp_backup);
+// Starts the installed update with the same arguments as this process. The frontend quits afterwards,
+// links aren't passed on since they have already been opened
+pub fn relaunch_after_update() -> Result<(), Arc<str>> {
+    let Some(executable) = INSTALLED_EXECUTABLE.lock().clone() else {
+        return Err("No update has been installed".into());
+    };
 
-            _ = std::fs::remove_dir_all(temp_backup);
-            _ = std::fs::remove_dir_all(temp_extract);
+    let args = std::env::args_os().skip(1).filter(|arg| {
+        let arg = arg.to_string_lossy();
+        !arg.starts_with("pandora://") && !arg.starts_with("modrinth://")
+    });
 
-            if let Err(err) = result {
-                return Err(err);
-            }
-        },
+    if let Some(installer) = PENDING_INSTALLER.lock().take() {
+        log::info!("Relaunching into {:?} after running {:?}", executable, installer.path);
+        if let Err(err) = run_installer_after_exit(&installer, Some((&executable, args.collect()))) {
+            log::error!("Error while starting installer: {}", err);
+            return Err("Unable to start the installer, see logs for more details".into());
+        }
+        return Ok(());
     }
 
-    send.send_success("Pandora update successful. Restart to apply changes");
+    log::info!("Relaunching into {:?}", executable);
+    if let Err(err) = std::process::Command::new(&executable).args(args).spawn() {
+        log::error!("Error while starting updated executable: {}", err);
+        return Err("Unable to start the updated launcher, see logs for more details".into());
+    }
 
     Ok(())
 }
 
+// Called when the backend shuts down, so an update which wasn't relaunched into is still installed
+pub fn run_pending_installer() {
+    if let Some(installer) = PENDING_INSTALLER.lock().take() {
+        log::info!("Running {:?} after exiting", installer.path);
+        if let Err(err) = run_installer_after_exit(&installer, None) {
+            log::error!("Error while starting installer: {}", err);
+        }
+    }
+}
+
+// Starts a hidden PowerShell process which waits for this process to exit, silently runs the installer and optionally
+// starts the launcher again
+fn run_installer_after_exit(installer: &PendingInstaller, relaunch: Option<(&Path, Vec<OsString>)>) -> std::io::Result<()> {
+    fn quote(value: &OsStr) -> String {
+        format!("'{}'", value.to_string_lossy().replace('\'', "''"))
+    }
+
+    let (file, arguments) = match installer.kind {
+        WindowsInstallerKind::Msi => {
+            let package = format!("\"{}\"", installer.path.to_string_lossy());
+            ("msiexec.exe".to_string(), format!("'/i',{},'/qn','/norestart'", quote(OsStr::new(&package))))
+        },
+        WindowsInstallerKind::Nsis => (installer.path.to_string_lossy().into_owned(), "'/S'".to_string()),
+    };
+
+    let mut script = format!("Wait-Process -Id {} -ErrorAction SilentlyContinue; ", std::process::id());
+    script.push_str(&format!("Start-Process -FilePath {} -ArgumentList {} -Wait", quote(OsStr::new(&file)), arguments));
+    if installer.per_machine {
+        script.push_str(" -Verb RunAs");
+    }
+    if let Some((executable, args)) = relaunch {
+        script.push_str(&format!("; Start-Process -FilePath {}", quote(executable.as_os_str())));
+        if !args.is_empty() {
+            let args = args.iter().map(|arg| quote(arg)).collect::<Vec<_>>().join(",");
+            script.push_str(&format!(" -ArgumentList {}", args));
+        }
+    }
+
+    let mut command = std::process::Command::new("powershell.exe");
+    command.args(["-NoProfile", "-WindowStyle", "Hidden", "-Command", &script]);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    command.spawn().map(|_| ())
+}
+
 fn write_new_exe(old_exe: PathBuf, new_exe: PathBuf, data: &[u8], dirs: &LauncherDirectories) -> Result<(), String> {
     let mut new_exe_data = dirs.temp_dir.join(format!("new_exe_data_{}", rand::thread_rng().next_u64()));
     while new_exe_data.exists() {
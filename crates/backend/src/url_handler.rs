@@ -0,0 +1,76 @@
+use std::path::Path;
+
+// Installed builds register these through the package, this is for the portable executables
+pub const SCHEMES: &[&str] = &["pandora", "modrinth"];
+
+#[cfg(target_os = "linux")]
+pub fn register_url_handler(applications_dir: &Path, bin: &Path) -> std::io::Result<()> {
+    let desktop_file = "pandora-launcher-url-handler.desktop";
+    log::info!("Registering url handler at {:?}", applications_dir.join(desktop_file));
+
+    let Some(bin) = bin.to_str() else {
+        return Err(std::io::Error::other("launcher path isn't valid UTF-8"));
+    };
+    let exec = shell_words::quote(bin);
+    let mime_types: String = SCHEMES.iter().map(|scheme| format!("x-scheme-handler/{scheme};")).collect();
+
+    crate::write_safe(&applications_dir.join(desktop_file), format!(r#"[Desktop Entry]
+Type=Application
+Version=1.0
+Name=Pandora Launcher
+Exec={exec} %u
+NoDisplay=true
+MimeType={mime_types}
+"#).as_bytes())?;
+
+    for scheme in SCHEMES {
+        let status = std::process::Command::new("xdg-mime")
+            .args(["default", desktop_file, &format!("x-scheme-handler/{scheme}")])
+            .status()?;
+        if !status.success() {
+            return Err(std::io::Error::other(format!("xdg-mime exited with {status}")));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+pub fn register_url_handler(_applications_dir: &Path, bin: &Path) -> std::io::Result<()> {
+    use std::os::windows::process::CommandExt;
+
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    log::info!("Registering url handler for {:?}", bin);
+
+    let command = format!("\"{}\" \"%1\"", bin.to_string_lossy());
+    for scheme in SCHEMES {
+        let key = format!("HKCU\\Software\\Classes\\{scheme}");
+        let entries = [
+            (key.clone(), None, format!("URL:{scheme} link")),
+            (key.clone(), Some("URL Protocol"), String::new()),
+            (format!("{key}\\shell\\open\\command"), None, command.clone()),
+        ];
+
+        for (key, value, data) in entries {
+            let mut reg = std::process::Command::new("reg");
+            reg.creation_flags(CREATE_NO_WINDOW).args(["add", &key]);
+            match value {
+                Some(value) => reg.args(["/v", value]),
+                None => reg.arg("/ve"),
+            };
+            let status = reg.args(["/d", &data, "/f"]).status()?;
+            if !status.success() {
+                return Err(std::io::Error::other(format!("reg exited with {status}")));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// The url types are declared by CFBundleURLTypes in the Info.plist of the app bundle, which can't be changed at runtime
+#[cfg(target_os = "macos")]
+pub fn register_url_handler(_applications_dir: &Path, _bin: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::other("links are registered by the app bundle on macOS"))
+}
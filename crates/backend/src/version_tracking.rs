@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use bridge::{job::JobPriority, message::MessageToFrontend};
+use rustc_hash::FxHashSet;
+use schema::version_manifest::{MinecraftVersionLink, MinecraftVersionManifest, MinecraftVersionType};
+use serde::{Deserialize, Serialize};
+
+use crate::{metadata::items::MinecraftVersionManifestMetadataItem, BackendState};
+
+const VERSION_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+// Versions which were in the manifest the last time it was checked, used to detect newly released versions
+#[derive(Serialize, Deserialize, Default)]
+struct KnownVersions {
+    versions: FxHashSet<String>,
+}
+
+impl BackendState {
+    pub async fn watch_for_new_minecraft_versions(self) {
+        let mut force_reload = false;
+        loop {
+            self.run_job("Checking for new Minecraft versions", JobPriority::Background,
+                self.check_for_new_minecraft_versions(force_reload)).await;
+            force_reload = true;
+            tokio::select! {
+                _ = tokio::time::sleep(VERSION_CHECK_INTERVAL) => {},
+                _ = self.shutdown.cancelled() => return,
+            }
+        }
+    }
+
+    async fn check_for_new_minecraft_versions(&self, force_reload: bool) {
+        let (manifest, _) = self.meta.fetch_with_keepalive(&MinecraftVersionManifestMetadataItem, force_reload).await;
+        let Ok(manifest) = manifest else {
+            return;
+        };
+
+        let new_versions = self.update_known_versions(&manifest);
+
+        // The manifest is sorted from newest to oldest, only the newest release and snapshot are worth notifying
+        // about when several came out since the last check
+        let newest_release = new_versions.iter().find(|version| matches!(version.r#type, MinecraftVersionType::Release));
+        let newest_snapshot = new_versions.iter().find(|version| matches!(version.r#type, MinecraftVersionType::Snapshot));
+
+        for version in newest_release.into_iter().chain(newest_snapshot) {
+            log::info!("Minecraft {} was released", version.id);
+            self.send.send(MessageToFrontend::MinecraftVersionReleased {
+                version: version.id,
+                snapshot: matches!(version.r#type, MinecraftVersionType::Snapshot),
+            });
+        }
+
+        let newest_version = new_versions.iter()
+            .find(|version| matches!(version.r#type, MinecraftVersionType::Release | MinecraftVersionType::Snapshot));
+        self.upgrade_tracking_instances(newest_release.map(|version| version.id), newest_version.map(|version| version.id)).await;
+    }
+
+    // Nothing is returned the first time, since every version would be new
+    fn update_known_versions<'a>(&self, manifest: &'a MinecraftVersionManifest) -> Vec<&'a MinecraftVersionLink> {
+        let path = self.directories.metadata_dir.join("known_minecraft_versions.json");
+        let previous: Option<KnownVersions> = crate::read_json(&path).ok();
+
+        let new_versions: Vec<_> = if let Some(previous) = &previous {
+            manifest.versions.iter().filter(|version| !previous.versions.contains(version.id.as_str())).collect()
+        } else {
+            Vec::new()
+        };
+
+        if previous.is_none() || !new_versions.is_empty() {
+            let known = KnownVersions {
+                versions: manifest.versions.iter().map(|version| version.id.to_string()).collect(),
+            };
+            if let Ok(bytes) = serde_json::to_vec(&known) {
+                _ = crate::write_safe(&path, &bytes);
+            }
+        }
+
+        new_versions
+    }
+}
@@ -7,4 +7,14 @@ pub struct Account {
     pub uuid: Uuid,
     pub username: Arc<str>,
     pub head: Option<Arc<[u8]>>,
+    // The login was revoked, e.g. after a password change, and has to be done again
+    pub needs_reauth: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ModrinthAccount {
+    pub id: Arc<str>,
+    pub username: Arc<str>,
+    pub avatar_url: Option<Arc<str>>,
+    pub needs_relogin: bool,
 }
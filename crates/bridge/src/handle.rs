@@ -1,11 +1,13 @@
-use std::sync::Arc;
+use std::sync::{atomic::{AtomicUsize, Ordering}, Arc, OnceLock};
+
+use tokio_util::sync::CancellationToken;
 
 #[cfg(debug_assertions)]
 use tokio::sync::mpsc::{Receiver, Sender};
 #[cfg(not(debug_assertions))]
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 
-use crate::{message::{BridgeNotificationType, MessageToBackend, MessageToFrontend}, serial::{AtomicOptionSerial, AtomicSerialProvider, AtomicSetSerial, Serial}};
+use crate::{message::{BridgeNotificationType, MessageToBackend, MessageToFrontend}, modal_action::ProgressTracker, serial::{AtomicOptionSerial, AtomicSerialProvider, AtomicSetSerial, Serial}};
 
 pub fn create_pair() -> (BackendReceiver, BackendHandle, FrontendReceiver, FrontendHandle) {
     #[cfg(debug_assertions)]
@@ -20,16 +22,22 @@ pub fn create_pair() -> (BackendReceiver, BackendHandle, FrontendReceiver, Front
 
     let backend_serial = AtomicSetSerial::default();
     let frontend_serial = AtomicSetSerial::default();
+    let shutdown = CancellationToken::new();
+    let backend_queued = Arc::new(AtomicUsize::new(0));
 
     (
         BackendReceiver {
             receiver: backend_recv,
             processed_serial: backend_serial.clone(),
+            shutdown: shutdown.clone(),
+            queued: backend_queued.clone(),
         },
         BackendHandle {
             sender: backend_send,
             processed_serial: backend_serial.clone(),
             next_serial: Default::default(),
+            shutdown,
+            queued: backend_queued,
         },
         FrontendReceiver {
             receiver: frontend_recv,
@@ -39,6 +47,7 @@ pub fn create_pair() -> (BackendReceiver, BackendHandle, FrontendReceiver, Front
             sender: frontend_send,
             processed_serial: frontend_serial.clone(),
             next_serial: Default::default(),
+            observer: Default::default(),
         }
     )
 }
@@ -50,11 +59,18 @@ pub struct BackendReceiver {
     #[cfg(not(debug_assertions))]
     receiver: UnboundedReceiver<(MessageToBackend, Option<Serial>)>,
     processed_serial: AtomicSetSerial,
+    shutdown: CancellationToken,
+    queued: Arc<AtomicUsize>,
 }
 
 impl BackendReceiver {
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.shutdown.clone()
+    }
+
     pub async fn recv(&mut self) -> Option<MessageToBackend> {
         let (message, serial) = self.receiver.recv().await?;
+        self.queued.fetch_sub(1, Ordering::Relaxed);
         if let Some(serial) = serial {
             self.processed_serial.set(serial);
         }
@@ -97,6 +113,9 @@ pub struct BackendHandle {
     sender: UnboundedSender<(MessageToBackend, Option<Serial>)>,
     processed_serial: AtomicSetSerial,
     next_serial: AtomicSerialProvider,
+    shutdown: CancellationToken,
+    // Messages which were sent but not yet received by the backend
+    queued: Arc<AtomicUsize>,
 }
 
 unsafe impl Send for BackendHandle {}
@@ -104,6 +123,8 @@ unsafe impl Sync for BackendHandle {}
 
 impl BackendHandle {
     pub fn send(&self, message: MessageToBackend) {
+        crate::replay::record_to_backend(&message);
+        self.queued.fetch_add(1, Ordering::Relaxed);
         #[cfg(debug_assertions)]
         self.sender.try_send((message, None)).unwrap();
         #[cfg(not(debug_assertions))]
@@ -114,6 +135,8 @@ impl BackendHandle {
         if let Some(serial) = serial.get() && self.processed_serial.get() < serial {
             return;
         }
+        crate::replay::record_to_backend(&message);
+        self.queued.fetch_add(1, Ordering::Relaxed);
 
         let next_serial = self.next_serial.next();
         serial.set(next_serial);
@@ -127,6 +150,22 @@ impl BackendHandle {
     pub fn is_closed(&self) -> bool {
         self.sender.is_closed()
     }
+
+    pub fn queue_depth(&self) -> usize {
+        self.queued.load(Ordering::Relaxed)
+    }
+
+    // Bypasses the message queue, since the backend may be busy with a long-running message when the launcher closes
+    pub fn shutdown(&self) {
+        self.shutdown.cancel();
+    }
+}
+
+// Sees everything sent to the frontend, e.g. to forward it to companion tools. Called on the sending thread, so
+// implementations shouldn't block
+pub trait FrontendObserver: Send + Sync + std::fmt::Debug {
+    fn observe_message(&self, message: &MessageToFrontend);
+    fn observe_progress(&self, tracker: &ProgressTracker);
 }
 
 #[derive(Clone, Debug)]
@@ -137,13 +176,34 @@ pub struct FrontendHandle {
     sender: UnboundedSender<(MessageToFrontend, Option<Serial>)>,
     processed_serial: AtomicSetSerial,
     next_serial: AtomicSerialProvider,
+    // Shared between all clones of the handle
+    observer: Arc<OnceLock<Arc<dyn FrontendObserver>>>,
 }
 
 unsafe impl Send for FrontendHandle {}
 unsafe impl Sync for FrontendHandle {}
 
 impl FrontendHandle {
+    // Only the first observer is kept
+    pub fn set_observer(&self, observer: Arc<dyn FrontendObserver>) {
+        _ = self.observer.set(observer);
+    }
+
+    pub fn observe_progress(&self, tracker: &ProgressTracker) {
+        if let Some(observer) = self.observer.get() {
+            observer.observe_progress(tracker);
+        }
+    }
+
+    fn observe_message(&self, message: &MessageToFrontend) {
+        if let Some(observer) = self.observer.get() {
+            observer.observe_message(message);
+        }
+    }
+
     pub fn send(&self, message: MessageToFrontend) {
+        crate::replay::record_to_frontend(&message);
+        self.observe_message(&message);
         #[cfg(debug_assertions)]
         if let Err(tokio::sync::mpsc::error::TrySendError::Full(v)) = self.sender.try_send((message, None)) {
             panic!("Sender is full, unable to send message: {v:?}");
@@ -156,6 +216,8 @@ impl FrontendHandle {
         if let Some(serial) = serial.get() && self.processed_serial.get() < serial {
             return;
         }
+        crate::replay::record_to_frontend(&message);
+        self.observe_message(&message);
 
         let next_serial = self.next_serial.next();
         serial.set(next_serial);
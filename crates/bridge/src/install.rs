@@ -45,9 +45,45 @@ pub enum ContentDownload {
     Url {
         url: Arc<str>,
         sha1: Arc<str>,
+        // Checked in addition to sha1 when known
+        sha512: Option<Arc<str>>,
         size: usize,
     },
     File {
         path: PathBuf,
     }
 }
+
+// Whether a Modrinth project has a version which can be installed into an instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentCompatibility {
+    Compatible,
+    // Supports the loader of the instance, but not its Minecraft version
+    DifferentVersion,
+    Incompatible,
+}
+
+// A file dropped onto the launcher, identified by the backend so it can be sent to the right import
+#[derive(Debug, Clone)]
+pub struct DroppedFile {
+    pub path: Arc<Path>,
+    pub kind: DroppedFileKind,
+}
+
+#[derive(Debug, Clone)]
+pub enum DroppedFileKind {
+    Modpack {
+        name: Arc<str>,
+        minecraft_version: Option<Arc<str>>,
+        loader: Loader,
+    },
+    Mod {
+        loader: Loader,
+    },
+    ResourcePack,
+    // A zip or folder containing a level.dat
+    World {
+        name: Arc<str>,
+    },
+    Unknown,
+}
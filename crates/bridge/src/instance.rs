@@ -1,6 +1,7 @@
 use std::{collections::HashSet, path::Path, sync::Arc};
 
-use schema::{auxiliary::AuxDisabledChildren, content::ContentSource, modification::ModrinthModpackFileDownload};
+use schema::{auxiliary::AuxDisabledChildren, content::ContentSource, loader::Loader, modification::ModrinthModpackFileDownload};
+use ustr::Ustr;
 
 use crate::safe_path::SafePath;
 
@@ -41,6 +42,23 @@ pub enum InstanceStatus {
     Running,
 }
 
+#[derive(Debug, Clone)]
+pub struct InstanceBackupSummary {
+    pub path: Arc<Path>,
+    // Unix timestamp in seconds
+    pub created: i64,
+    pub size: u64,
+}
+
+// A folder in the instances directory that was never an instance, with the version and loader it most likely uses
+#[derive(Debug, Clone)]
+pub struct StrayInstanceFolder {
+    pub path: Arc<Path>,
+    pub name: Arc<str>,
+    pub minecraft_version: Option<Ustr>,
+    pub loader: Loader,
+}
+
 #[derive(Debug, Clone)]
 pub struct InstanceWorldSummary {
     pub title: Arc<str>,
@@ -50,6 +68,16 @@ pub struct InstanceWorldSummary {
     pub png_icon: Option<Arc<[u8]>>,
 }
 
+#[derive(Debug, Clone)]
+pub struct InstanceDatapackSummary {
+    // Id used by the game in level.dat, e.g. "file/example.zip"
+    pub pack_id: Arc<str>,
+    pub filename: Arc<str>,
+    pub description: Option<Arc<str>>,
+    pub png_icon: Option<Arc<[u8]>>,
+    pub enabled: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct InstanceServerSummary {
     pub name: Arc<str>,
@@ -57,6 +85,121 @@ pub struct InstanceServerSummary {
     pub png_icon: Option<Arc<[u8]>>,
 }
 
+// A resource pack the game downloaded from a server, or one that was placed there ahead of time
+#[derive(Debug, Clone)]
+pub struct InstanceServerResourcePack {
+    pub path: Arc<Path>,
+    // The cached file is named after the sha1 of the pack, which the server sends when asking for it
+    pub sha1: Arc<str>,
+    // Only set for packs in the downloads folder used by 1.20.3 and newer, which groups packs by their id
+    pub pack_id: Option<Arc<str>>,
+    pub size: u64,
+    pub modified: i64,
+}
+
+#[derive(Debug, Clone)]
+pub enum ServerResourcePackSource {
+    File(Arc<Path>),
+    Url(Arc<str>),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct InstanceNotes {
+    pub notes: Arc<str>,
+    // A readme shipped with the modpack the instance was created from
+    pub readme: Option<InstanceReadme>,
+}
+
+#[derive(Debug, Clone)]
+pub struct InstanceReadme {
+    pub filename: Arc<str>,
+    pub content: Arc<str>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ServerStatus {
+    pub motd: Arc<str>,
+    pub version_name: Arc<str>,
+    pub online_players: i64,
+    pub max_players: i64,
+    pub latency_ms: u64,
+    pub png_icon: Option<Arc<[u8]>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InstanceGameOptions {
+    pub render_distance: Option<u32>,
+    pub enable_vsync: Option<bool>,
+    pub lang: Option<Arc<str>>,
+    pub gui_scale: Option<u32>,
+}
+
+// Names of the gamemode and difficulty values in server.properties, older servers store their index instead
+pub const SERVER_GAMEMODES: &[&str] = &["survival", "creative", "adventure", "spectator"];
+pub const SERVER_DIFFICULTIES: &[&str] = &["peaceful", "easy", "normal", "hard"];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InstanceServerProperties {
+    pub eula_accepted: bool,
+    pub motd: Option<Arc<str>>,
+    pub server_port: Option<u16>,
+    pub max_players: Option<u32>,
+    pub gamemode: Option<Arc<str>>,
+    pub difficulty: Option<Arc<str>>,
+    pub view_distance: Option<u32>,
+    pub online_mode: Option<bool>,
+    pub pvp: Option<bool>,
+    pub white_list: Option<bool>,
+}
+
+#[derive(Debug, Clone)]
+pub struct InstanceTemplateSummary {
+    pub name: Arc<str>,
+    pub minecraft_version: Ustr,
+    pub loader: Loader,
+    pub content_count: usize,
+}
+
+// What an instance share code contains, shown before importing it
+#[derive(Debug, Clone)]
+pub struct InstanceShareSummary {
+    pub name: Arc<str>,
+    pub minecraft_version: Ustr,
+    pub loader: Loader,
+    pub content_count: usize,
+}
+
+// What is copied into a duplicated instance, the configuration and icon are always copied
+#[derive(Debug, Clone, Copy)]
+pub struct InstanceDuplicateOptions {
+    // Mods and resource packs
+    pub mods: bool,
+    pub configs: bool,
+    pub saves: bool,
+    // options.txt
+    pub options: bool,
+    // Links mods and resource packs from the content library instead of copying them
+    pub link_content: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InstanceVerifySummary {
+    pub libraries_checked: usize,
+    pub libraries_repaired: usize,
+    pub assets_checked: usize,
+    pub assets_repaired: usize,
+    pub content_checked: usize,
+    pub content_repaired: usize,
+}
+
+// The command that launching an instance would run, with the access token redacted
+#[derive(Debug, Clone)]
+pub struct InstanceLaunchCommand {
+    pub command: Arc<str>,
+    // Where the command was written to when a script was requested
+    pub script_path: Option<Arc<Path>>,
+}
+
 #[derive(Debug, Clone)]
 pub struct InstanceContentSummary {
     pub content_summary: Arc<ContentSummary>,
@@ -78,10 +221,63 @@ pub struct ContentSummary {
     pub version_str: Arc<str>,
     pub authors: Arc<str>,
     pub png_icon: Option<Arc<[u8]>>,
+    pub description: Option<Arc<str>>,
+    pub dependencies: Arc<[ContentDependency]>,
+    // Additional ids satisfied by this file, e.g. aliases and nested jars
+    pub provides: Arc<[Arc<str>]>,
     pub update_status: Arc<AtomicContentUpdateStatus>,
     pub extra: ContentType,
 }
 
+#[derive(Debug, Clone)]
+pub struct ContentDependency {
+    pub id: Arc<str>,
+    // Kept in the notation of the mod loader, e.g. ">=0.15" for Fabric or "[47,)" for Forge
+    pub version_range: Option<Arc<str>>,
+    pub kind: ContentDependencyKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentDependencyKind {
+    Required,
+    Optional,
+    Incompatible,
+}
+
+#[derive(Debug, Clone)]
+pub enum ContentProblem {
+    MissingDependency {
+        content_name: Arc<str>,
+        dependency: Arc<str>,
+        version_range: Option<Arc<str>>,
+        // Set if the dependency is installed, but its version isn't accepted
+        installed_version: Option<Arc<str>>,
+    },
+    Duplicate {
+        mod_id: Arc<str>,
+        entries: Arc<[ContentProblemEntry]>,
+    },
+    WrongLoader {
+        entry: ContentProblemEntry,
+        loader: Arc<str>,
+    },
+    WrongGameVersion {
+        entry: ContentProblemEntry,
+        version_range: Arc<str>,
+    },
+    Incompatible {
+        entry: ContentProblemEntry,
+        other: ContentProblemEntry,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct ContentProblemEntry {
+    pub content_id: InstanceContentID,
+    pub name: Arc<str>,
+    pub version: Arc<str>,
+}
+
 #[derive(Debug, Clone)]
 pub enum ContentType {
     Fabric,
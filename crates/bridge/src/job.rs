@@ -0,0 +1,17 @@
+use std::sync::Arc;
+
+// Jobs with a higher priority are started first, background jobs wait until nothing more important is running
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum JobPriority {
+    Interactive,
+    LaunchPreparation,
+    Background,
+}
+
+#[derive(Debug, Clone)]
+pub struct JobSummary {
+    pub name: Arc<str>,
+    pub priority: JobPriority,
+    // Queued jobs are waiting for more important jobs to finish
+    pub running: bool,
+}
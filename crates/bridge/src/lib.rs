@@ -3,10 +3,14 @@ pub mod game_output;
 pub mod handle;
 pub mod install;
 pub mod instance;
+pub mod job;
 pub mod keep_alive;
 pub mod message;
 pub mod meta;
 pub mod modal_action;
+pub mod realms;
+pub mod replay;
 pub mod safe_path;
+pub mod sandbox;
 pub mod serial;
 pub mod notify_signal;
@@ -6,50 +6,157 @@ use std::{
 
 use enumset::{EnumSet, EnumSetType};
 use schema::{
-    backend_config::{BackendConfig, SyncTarget}, instance::{
-        InstanceConfiguration, InstanceJvmBinaryConfiguration, InstanceJvmFlagsConfiguration,
-        InstanceLinuxWrapperConfiguration, InstanceMemoryConfiguration, InstanceSystemLibrariesConfiguration,
-    }, loader::Loader, pandora_update::{UpdateManifest, UpdateManifestExe, UpdatePrompt}
+    backend_config::{BackendConfig, MemoryBudgetMode, ParentalRestrictions, SyncTarget, UpdateChannel}, instance::{
+        InstanceConfiguration, InstanceDefaultsConfiguration, InstanceJvmBinaryConfiguration, InstanceJvmFlagsConfiguration,
+        InstanceLegacyFixesConfiguration, InstanceLinuxWrapperConfiguration, InstanceMemoryConfiguration, InstanceNetworkIsolationConfiguration, InstanceResolutionConfiguration,
+        InstanceSystemLibrariesConfiguration, InstanceType, InstanceVersionTracking,
+    }, instance_history::InstanceHistoryEntry, loader::Loader, modrinth::{ModrinthNotification, ModrinthProject}, pandora_update::{UpdateManifest, UpdateManifestExe, UpdatePrompt}, performance::PerformanceSession
 };
 use ustr::Ustr;
 use uuid::Uuid;
 
 use crate::{
-    account::Account,
+    account::{Account, ModrinthAccount},
     game_output::GameOutputLogLevel,
-    install::ContentInstall,
+    install::{ContentCompatibility, ContentInstall, DroppedFile},
     instance::{
-        InstanceContentID, InstanceContentSummary, InstanceID, InstanceServerSummary, InstanceStatus,
-        InstanceWorldSummary,
+        ContentProblem, InstanceBackupSummary, InstanceContentID, InstanceDatapackSummary, InstanceContentSummary, InstanceGameOptions, InstanceID, InstanceServerProperties, InstanceServerResourcePack, InstanceServerSummary, ServerResourcePackSource, ServerStatus,
+        InstanceDuplicateOptions, InstanceLaunchCommand, InstanceNotes, InstanceShareSummary, InstanceStatus, InstanceTemplateSummary, InstanceVerifySummary, InstanceWorldSummary, StrayInstanceFolder,
     },
+    job::JobSummary,
     keep_alive::{KeepAlive, KeepAliveHandle},
     meta::{MetadataRequest, MetadataResult},
     modal_action::ModalAction,
+    realms::RealmSummary,
+    safe_path::SafePath,
 };
 
+#[derive(Debug, Clone)]
+pub enum BulkInstanceAction {
+    Delete,
+    SetJvmBinary(InstanceJvmBinaryConfiguration),
+    // Removes pinned loader versions so every instance uses the latest loader
+    UpdateLoader,
+    AddMods(Arc<[PathBuf]>),
+    Export(Arc<Path>),
+    // The version of the project to install into each instance, as resolved by the frontend
+    InstallModrinthProject {
+        project_id: Arc<str>,
+        versions: Arc<[(InstanceID, Arc<str>)]>,
+    },
+}
+
 #[derive(Debug)]
 pub enum MessageToBackend {
     RequestMetadata {
         request: MetadataRequest,
         force_reload: bool,
     },
+    // Aborts the fetch for a request the frontend is no longer interested in, e.g. an outdated search query
+    CancelMetadataRequest {
+        request: MetadataRequest,
+    },
     CreateInstance {
         name: Ustr,
         version: Ustr,
         loader: Loader,
+        preferred_loader_version: Option<Ustr>,
+        group: Option<Ustr>,
         icon: Option<EmbeddedOrRaw>,
+        instance_type: InstanceType,
+        accept_eula: bool,
+        // Only used for Fabric clients
+        install_fabric_api: bool,
+    },
+    BulkInstanceAction {
+        ids: Vec<InstanceID>,
+        action: BulkInstanceAction,
+        modal_action: ModalAction,
+    },
+    // Adds an existing instance folder stored outside the instances directory
+    AddExternalInstance {
+        path: PathBuf,
+    },
+    AdoptStrayInstance {
+        path: Arc<Path>,
+        minecraft_version: Ustr,
+        loader: Loader,
+        modal_action: ModalAction,
     },
     DeleteInstance {
         id: InstanceID,
     },
+    SaveInstanceTemplate {
+        id: InstanceID,
+        name: Arc<str>,
+    },
+    GetInstanceTemplates {
+        channel: tokio::sync::oneshot::Sender<Arc<[InstanceTemplateSummary]>>,
+    },
+    DeleteInstanceTemplate {
+        name: Arc<str>,
+    },
+    CreateInstanceFromTemplate {
+        template: Arc<str>,
+        name: Ustr,
+        // Re-resolves Modrinth content for the new version when set
+        version: Option<Ustr>,
+        modal_action: ModalAction,
+    },
+    CreateInstanceShareCode {
+        id: InstanceID,
+        channel: tokio::sync::oneshot::Sender<Result<Arc<str>, Arc<str>>>,
+    },
+    ReadInstanceShareCode {
+        code: Arc<str>,
+        channel: tokio::sync::oneshot::Sender<Result<InstanceShareSummary, Arc<str>>>,
+    },
+    ImportInstanceShareCode {
+        code: Arc<str>,
+        name: Ustr,
+        modal_action: ModalAction,
+    },
+    DuplicateInstance {
+        id: InstanceID,
+        name: Ustr,
+        options: InstanceDuplicateOptions,
+        modal_action: ModalAction,
+    },
     RenameInstance {
         id: InstanceID,
         name: Ustr,
     },
+    // Moves the instance folder into the target folder, or back into the instances directory when None
+    MoveInstance {
+        id: InstanceID,
+        target: Option<Arc<Path>>,
+        modal_action: ModalAction,
+    },
     SetInstanceMinecraftVersion {
         id: InstanceID,
         version: Ustr
     },
+    SetInstanceGroup {
+        id: InstanceID,
+        group: Option<Ustr>,
+    },
+    SetInstanceFavorite {
+        id: InstanceID,
+        favorite: bool,
+    },
+    SetInstanceVersionTracking {
+        id: InstanceID,
+        version_tracking: Option<InstanceVersionTracking>,
+    },
+    UpgradeInstance {
+        id: InstanceID,
+        version: Ustr,
+        modal_action: ModalAction,
+    },
+    RestoreInstanceSnapshot {
+        id: InstanceID,
+        modal_action: ModalAction,
+    },
     SetInstanceLoader {
         id: InstanceID,
         loader: Loader
@@ -74,10 +181,70 @@ pub enum MessageToBackend {
         id: InstanceID,
         linux_wrapper: InstanceLinuxWrapperConfiguration,
     },
+    SetInstanceLegacyFixes {
+        id: InstanceID,
+        legacy_fixes: InstanceLegacyFixesConfiguration,
+    },
+    SetInstanceNetworkIsolation {
+        id: InstanceID,
+        network_isolation: InstanceNetworkIsolationConfiguration,
+    },
+    SetInstanceScheduledBackups {
+        id: InstanceID,
+        value: bool,
+    },
+    BackupInstance {
+        id: InstanceID,
+        modal_action: ModalAction,
+    },
+    GetInstanceBackups {
+        id: InstanceID,
+        channel: tokio::sync::oneshot::Sender<Arc<[InstanceBackupSummary]>>,
+    },
+    // Replaces the saves and configs of the instance with the ones in the backup
+    RestoreInstanceBackup {
+        id: InstanceID,
+        path: Arc<Path>,
+        modal_action: ModalAction,
+    },
     SetInstanceSystemLibraries {
         id: InstanceID,
         system_libraries: InstanceSystemLibrariesConfiguration,
     },
+    SetInstanceResolution {
+        id: InstanceID,
+        resolution: InstanceResolutionConfiguration,
+    },
+    ResetInstanceSetting {
+        id: InstanceID,
+        setting: InstanceSetting,
+    },
+    GetInstanceGameOptions {
+        id: InstanceID,
+        channel: tokio::sync::oneshot::Sender<InstanceGameOptions>,
+    },
+    SetInstanceGameOptions {
+        ids: Vec<InstanceID>,
+        options: InstanceGameOptions,
+    },
+    GetServerProperties {
+        id: InstanceID,
+        channel: tokio::sync::oneshot::Sender<InstanceServerProperties>,
+    },
+    SetServerProperties {
+        id: InstanceID,
+        properties: InstanceServerProperties,
+    },
+    SendInstanceInput {
+        id: InstanceID,
+        input: Arc<str>,
+    },
+    StartInstanceTunnel {
+        id: InstanceID,
+    },
+    StopInstanceTunnel {
+        id: InstanceID,
+    },
     KillInstance {
         id: InstanceID,
     },
@@ -86,18 +253,42 @@ pub enum MessageToBackend {
         quick_play: Option<QuickPlayLaunch>,
         modal_action: ModalAction,
     },
+    PrepareInstance {
+        id: InstanceID,
+        modal_action: ModalAction,
+    },
+    VerifyInstanceFiles {
+        id: InstanceID,
+        modal_action: ModalAction,
+        channel: tokio::sync::oneshot::Sender<InstanceVerifySummary>,
+    },
+    ResolveLaunchCommand {
+        id: InstanceID,
+        modal_action: ModalAction,
+        // Also writes the command to a script in the instance folder
+        write_script: bool,
+        channel: tokio::sync::oneshot::Sender<InstanceLaunchCommand>,
+    },
     RequestLoadWorlds {
         id: InstanceID,
     },
     RequestLoadServers {
         id: InstanceID,
     },
+    PingServer {
+        address: Arc<str>,
+        force: bool,
+    },
     RequestLoadMods {
         id: InstanceID,
     },
     RequestLoadResourcePacks {
         id: InstanceID,
     },
+    ValidateInstanceContent {
+        id: InstanceID,
+        channel: tokio::sync::oneshot::Sender<Arc<[ContentProblem]>>,
+    },
     SetContentEnabled {
         id: InstanceID,
         content_ids: Vec<InstanceContentID>,
@@ -115,10 +306,38 @@ pub enum MessageToBackend {
         id: InstanceID,
         content_ids: Vec<InstanceContentID>,
     },
+    SaveContentProfile {
+        id: InstanceID,
+        name: Arc<str>,
+    },
+    ApplyContentProfile {
+        id: InstanceID,
+        name: Arc<str>,
+        modal_action: ModalAction,
+    },
+    DeleteContentProfile {
+        id: InstanceID,
+        name: Arc<str>,
+    },
     InstallContent {
         content: ContentInstall,
         modal_action: ModalAction,
     },
+    CheckModrinthCompatibility {
+        id: InstanceID,
+        project_ids: Arc<[Arc<str>]>,
+        channel: tokio::sync::oneshot::Sender<Arc<[(Arc<str>, ContentCompatibility)]>>,
+    },
+    IdentifyDroppedFiles {
+        paths: Vec<PathBuf>,
+        channel: tokio::sync::oneshot::Sender<Arc<[DroppedFile]>>,
+    },
+    // Copies a world zip or folder into the saves folder of the instance
+    ImportWorld {
+        id: InstanceID,
+        path: Arc<Path>,
+        modal_action: ModalAction,
+    },
     DownloadAllMetadata,
     UpdateCheck {
         instance: InstanceID,
@@ -138,6 +357,59 @@ pub enum MessageToBackend {
         instance: InstanceID,
         channel: tokio::sync::oneshot::Sender<LogFiles>,
     },
+    GetWorldDatapacks {
+        id: InstanceID,
+        world: Arc<Path>,
+        channel: tokio::sync::oneshot::Sender<Result<Arc<[InstanceDatapackSummary]>, Arc<str>>>,
+    },
+    SetDatapacksEnabled {
+        id: InstanceID,
+        world: Arc<Path>,
+        pack_ids: Vec<Arc<str>>,
+        enabled: bool,
+        channel: tokio::sync::oneshot::Sender<Result<(), Arc<str>>>,
+    },
+    GetServerResourcePacks {
+        id: InstanceID,
+        channel: tokio::sync::oneshot::Sender<Result<Arc<[InstanceServerResourcePack]>, Arc<str>>>,
+    },
+    DeleteServerResourcePacks {
+        id: InstanceID,
+        paths: Vec<Arc<Path>>,
+        channel: tokio::sync::oneshot::Sender<Result<(), Arc<str>>>,
+    },
+    // Places a pack in the cache ahead of time, so that joining the server doesn't have to download it
+    SeedServerResourcePack {
+        id: InstanceID,
+        source: ServerResourcePackSource,
+        pack_id: Option<Uuid>,
+        channel: tokio::sync::oneshot::Sender<Result<(), Arc<str>>>,
+    },
+    GetInstanceNotes {
+        id: InstanceID,
+        channel: tokio::sync::oneshot::Sender<Result<InstanceNotes, Arc<str>>>,
+    },
+    SetInstanceNotes {
+        id: InstanceID,
+        notes: Arc<str>,
+        channel: tokio::sync::oneshot::Sender<Result<(), Arc<str>>>,
+    },
+    GetConfigFiles {
+        instance: InstanceID,
+        channel: tokio::sync::oneshot::Sender<Arc<[SafePath]>>,
+    },
+    ReadConfigFile {
+        instance: InstanceID,
+        path: SafePath,
+        channel: tokio::sync::oneshot::Sender<Result<Arc<str>, Arc<str>>>,
+    },
+    WriteConfigFile {
+        instance: InstanceID,
+        path: SafePath,
+        content: Arc<str>,
+        backup: bool,
+        channel: tokio::sync::oneshot::Sender<Result<(), Arc<str>>>,
+    },
     GetSyncState {
         channel: tokio::sync::oneshot::Sender<SyncState>,
     },
@@ -155,9 +427,20 @@ pub enum MessageToBackend {
         path: Arc<Path>,
         modal_action: ModalAction,
     },
+    CreateProblemReport {
+        instance: Option<InstanceID>,
+        description: Arc<str>,
+        modal_action: ModalAction,
+        channel: tokio::sync::oneshot::Sender<ProblemReport>,
+    },
     AddNewAccount {
         modal_action: ModalAction,
     },
+    // Logs into an existing account again after its login was revoked
+    ReauthenticateAccount {
+        uuid: Uuid,
+        modal_action: ModalAction,
+    },
     AddOfflineAccount {
         name: Arc<str>,
         uuid: Uuid
@@ -168,17 +451,137 @@ pub enum MessageToBackend {
     DeleteAccount {
         uuid: Uuid,
     },
+    ModrinthLogin {
+        token: Arc<str>,
+        modal_action: ModalAction,
+    },
+    ModrinthLogout,
+    GetRealms {
+        minecraft_version: Ustr,
+        modal_action: ModalAction,
+        channel: tokio::sync::oneshot::Sender<Result<Arc<[RealmSummary]>, Arc<str>>>,
+    },
+    GetModrinthFollows {
+        channel: tokio::sync::oneshot::Sender<Result<Arc<[ModrinthProject]>, Arc<str>>>,
+    },
+    GetModrinthNotifications {
+        channel: tokio::sync::oneshot::Sender<Result<Arc<[ModrinthNotification]>, Arc<str>>>,
+    },
     SetOpenGameOutputAfterLaunching {
         value: bool,
     },
+    SetLinkContentFiles {
+        value: bool,
+    },
+    SetDebugLogging {
+        value: bool,
+    },
+    SetDeviceCodeLogin {
+        value: bool,
+    },
+    SetMaxRunningInstances {
+        value: Option<u32>,
+    },
+    SetMemoryBudget {
+        mode: MemoryBudgetMode,
+    },
+    SetUpdateChannel {
+        channel: UpdateChannel,
+    },
+    SetBackupDirectory {
+        directory: Option<PathBuf>,
+    },
+    SetBackupsKept {
+        value: Option<u32>,
+    },
+    GetLaunchMetrics {
+        channel: tokio::sync::oneshot::Sender<LaunchMetricsSummary>,
+    },
+    SetLaunchMetricsRecording {
+        value: bool,
+    },
+    SetLaunchMetricsUpload {
+        value: bool,
+    },
+    ClearLaunchMetrics,
+    GetJavaRuntimes {
+        channel: tokio::sync::oneshot::Sender<Arc<[JavaRuntimeSummary]>>,
+    },
+    RemoveUnusedJavaRuntimes {
+        modal_action: ModalAction,
+    },
+    GetInstancePerformance {
+        id: InstanceID,
+        channel: tokio::sync::oneshot::Sender<Arc<[PerformanceSession]>>,
+    },
+    ClearInstancePerformance {
+        id: InstanceID,
+    },
+    GetInstanceHistory {
+        id: InstanceID,
+        channel: tokio::sync::oneshot::Sender<Arc<[InstanceHistoryEntry]>>,
+    },
+    GetParentalControlsStatus {
+        channel: tokio::sync::oneshot::Sender<ParentalControlsStatus>,
+    },
+    ConfigureParentalControls {
+        pin: Arc<str>,
+        // None disables parental controls
+        restrictions: Option<ParentalRestrictions>,
+        channel: tokio::sync::oneshot::Sender<bool>,
+    },
+    UnlockParentalControls {
+        pin: Arc<str>,
+        channel: tokio::sync::oneshot::Sender<bool>,
+    },
+    LockParentalControls,
+    // Copies the launcher data to a new directory which is used after restarting
+    ChangeDataDirectory {
+        target: Arc<Path>,
+        modal_action: ModalAction,
+    },
+    SetInstanceDefaults {
+        defaults: InstanceDefaultsConfiguration,
+    },
+    SetTunnelCommand {
+        command: Option<Arc<str>>,
+    },
+    SetNativesRepositories {
+        repositories: Vec<Arc<str>>,
+    },
     CreateInstanceShortcut {
         id: InstanceID,
         path: PathBuf
     },
+    RegisterUrlHandler,
+    SetEventStreamEnabled {
+        value: bool,
+    },
+    ResetEventStreamToken,
     InstallUpdate {
         update: UpdatePrompt,
         modal_action: ModalAction,
-    }
+    },
+    SkipUpdate {
+        version: Arc<str>,
+    },
+    RemindAboutUpdateLater,
+    RelaunchAfterUpdate {
+        channel: tokio::sync::oneshot::Sender<Result<(), Arc<str>>>,
+    },
+    RestorePersistentBackup {
+        file: PersistentFile,
+        backup: Arc<Path>,
+    },
+}
+
+// Instance settings that can be inherited from the global instance defaults
+#[derive(Debug, EnumSetType)]
+pub enum InstanceSetting {
+    Memory,
+    JvmFlags,
+    JvmBinary,
+    Resolution,
 }
 
 #[derive(Debug)]
@@ -205,6 +608,20 @@ pub enum MessageToFrontend {
         configuration: InstanceConfiguration,
         status: InstanceStatus,
     },
+    InstanceTunnelUpdated {
+        id: InstanceID,
+        address: Option<Arc<str>>,
+    },
+    InstanceExited {
+        id: InstanceID,
+        // None if the process was terminated by a signal
+        exit_code: Option<i32>,
+    },
+    InstanceJvmCrashed {
+        id: InstanceID,
+        name: Ustr,
+        report: JvmCrashReport,
+    },
     InstanceWorldsUpdated {
         id: InstanceID,
         worlds: Arc<[InstanceWorldSummary]>,
@@ -224,6 +641,7 @@ pub enum MessageToFrontend {
     CreateGameOutputWindow {
         id: usize,
         keep_alive: KeepAlive,
+        console: Option<InstanceID>,
     },
     AddGameOutput {
         id: usize,
@@ -238,6 +656,7 @@ pub enum MessageToFrontend {
     AccountsUpdated {
         accounts: Arc<[Account]>,
         selected_account: Option<Uuid>,
+        modrinth_account: Option<ModrinthAccount>,
     },
     Refresh,
     CloseModal,
@@ -252,6 +671,43 @@ pub enum MessageToFrontend {
     UpdateAvailable {
         update: UpdatePrompt,
     },
+    UpdateInstalled {
+        version: Arc<str>,
+    },
+    MinecraftVersionReleased {
+        version: Ustr,
+        snapshot: bool,
+    },
+    // An instance tracking the latest version can be upgraded, but asks before doing so
+    InstanceUpgradeAvailable {
+        id: InstanceID,
+        name: Ustr,
+        version: Ustr,
+    },
+    InstanceUpgraded {
+        id: InstanceID,
+        name: Ustr,
+        from: Ustr,
+        to: Ustr,
+    },
+    ServerStatusUpdated {
+        address: Arc<str>,
+        status: Result<ServerStatus, Arc<str>>,
+    },
+    BackgroundJobsUpdated {
+        jobs: Arc<[JobSummary]>,
+    },
+    // Sent on startup when a file couldn't be parsed, `backups` are the other backups which can still be restored
+    PersistentFileRecovered {
+        file: PersistentFile,
+        corrupt_path: Arc<Path>,
+        restored_from: Option<Arc<Path>>,
+        backups: Arc<[Arc<Path>]>,
+    },
+    // Sent on startup for folders in the instances directory which don't have an instance configuration
+    StrayInstancesFound {
+        instances: Arc<[StrayInstanceFolder]>,
+    },
 }
 
 #[derive(Debug, Default)]
@@ -260,6 +716,70 @@ pub struct LogFiles {
     pub total_gzipped_size: usize,
 }
 
+#[derive(Debug)]
+pub struct ProblemReport {
+    pub path: Arc<Path>,
+    // Opens a new issue with the description and system information filled in
+    pub issue_url: Arc<str>,
+}
+
+// Summary of the hs_err_pid*.log written by the JVM when it crashes in native code
+#[derive(Debug, Clone)]
+pub struct JvmCrashReport {
+    pub path: Arc<Path>,
+    // e.g. "EXCEPTION_ACCESS_VIOLATION (0xc0000005)"
+    pub error: Option<Arc<str>>,
+    pub problematic_frame: Option<Arc<str>>,
+    // Full path of the native library containing the problematic frame when it could be found
+    pub library: Option<Arc<str>>,
+    pub java_version: Option<Arc<str>>,
+    pub guidance: Option<Arc<str>>,
+}
+
+#[derive(Debug, Default)]
+pub struct LaunchMetricsSummary {
+    pub recording: bool,
+    pub uploading: bool,
+    // False when the launcher was built without an upload endpoint
+    pub upload_available: bool,
+    pub total_launches: usize,
+    pub failed_launches: usize,
+    pub crashes: usize,
+    pub total_play_seconds: u64,
+    pub average_launch_millis: Option<u64>,
+    pub instances: Arc<[InstanceLaunchMetrics]>,
+}
+
+// A Java runtime downloaded from Mojang, stored in runtime/<component>/<platform>
+#[derive(Debug, Clone)]
+pub struct JavaRuntimeSummary {
+    pub component: Arc<str>,
+    pub platform: Arc<str>,
+    pub version: Option<Arc<str>>,
+    pub size: u64,
+    // Known instances which launched with this runtime
+    pub instances: usize,
+    // No instance uses the runtime and it hasn't been launched for a while
+    pub removable: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct ParentalControlsStatus {
+    pub enabled: bool,
+    pub unlocked: bool,
+    pub restrictions: ParentalRestrictions,
+    pub playtime_today_seconds: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct InstanceLaunchMetrics {
+    pub name: Ustr,
+    pub launches: usize,
+    pub crashes: usize,
+    pub play_seconds: u64,
+    pub last_launched: i64,
+}
+
 #[derive(Debug, Default)]
 pub struct SyncState {
     pub sync_folder: Option<Arc<Path>>,
@@ -269,6 +789,22 @@ pub struct SyncState {
     pub cannot_sync: enum_map::EnumMap<SyncTarget, usize>,
 }
 
+// Launcher-wide files which keep backups and are recovered from them if they can't be parsed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PersistentFile {
+    Config,
+    Accounts,
+}
+
+impl PersistentFile {
+    pub fn file_name(self) -> &'static str {
+        match self {
+            PersistentFile::Config => "config.json",
+            PersistentFile::Accounts => "accounts.json",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BridgeNotificationType {
     Success,
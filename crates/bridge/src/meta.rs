@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use schema::{fabric_loader_manifest::FabricLoaderManifest, forge::{ForgeMavenManifest, NeoforgeMavenManifest}, maven::MavenMetadataXml, modrinth::{ModrinthProjectVersionsRequest, ModrinthProjectVersionsResult, ModrinthSearchRequest, ModrinthSearchResult}, version_manifest::MinecraftVersionManifest};
+use schema::{fabric_loader_manifest::FabricLoaderManifest, forge::{ForgeMavenManifest, NeoforgeMavenManifest}, maven::MavenMetadataXml, patch_notes::{JavaPatchNote, JavaPatchNotes}, modrinth::{ModrinthProject, ModrinthProjectDependencies, ModrinthProjectVersionsRequest, ModrinthProjectVersionsResult, ModrinthSearchRequest, ModrinthSearchResult}, version_manifest::MinecraftVersionManifest};
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum MetadataRequest {
@@ -10,6 +10,11 @@ pub enum MetadataRequest {
     NeoforgeMavenManifest,
     ModrinthSearch(ModrinthSearchRequest),
     ModrinthProjectVersions(ModrinthProjectVersionsRequest),
+    ModrinthProject(Arc<str>),
+    ModrinthProjectDependencies(Arc<str>),
+    JavaPatchNotes,
+    // The content path of an entry in JavaPatchNotes
+    JavaPatchNote(Arc<str>),
 }
 
 #[derive(Debug)]
@@ -20,4 +25,8 @@ pub enum MetadataResult {
     NeoforgeMavenManifest(Arc<NeoforgeMavenManifest>),
     ModrinthSearchResult(Arc<ModrinthSearchResult>),
     ModrinthProjectVersionsResult(Arc<ModrinthProjectVersionsResult>),
+    ModrinthProject(Arc<ModrinthProject>),
+    ModrinthProjectDependencies(Arc<ModrinthProjectDependencies>),
+    JavaPatchNotes(Arc<JavaPatchNotes>),
+    JavaPatchNote(Arc<JavaPatchNote>),
 }
@@ -4,13 +4,16 @@ use std::{
         Arc, RwLock,
         atomic::{AtomicUsize, Ordering},
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use atomic_time::AtomicOptionInstant;
 use tokio_util::sync::CancellationToken;
 
-use crate::{handle::FrontendHandle, message::MessageToFrontend, serial::AtomicOptionSerial};
+use crate::{handle::FrontendHandle, message::{MessageToBackend, MessageToFrontend}, serial::AtomicOptionSerial};
+
+// Recreates the backend message which started a modal action, so that it can be started again if it fails
+pub type RetryCommand = Arc<dyn Fn(ModalAction) -> MessageToBackend + Send + Sync>;
 
 #[derive(Default, Clone, Debug)]
 pub struct ModalAction {
@@ -18,9 +21,35 @@ pub struct ModalAction {
 }
 
 impl ModalAction {
+    pub fn retryable(retry: impl Fn(ModalAction) -> MessageToBackend + Send + Sync + 'static) -> Self {
+        Self {
+            inner: Arc::new(ModalActionInner {
+                retry_command: Some(Arc::new(retry)),
+                ..Default::default()
+            }),
+        }
+    }
+
     pub fn refcnt(&self) -> usize {
         Arc::strong_count(&self.inner)
     }
+
+    pub fn is_retryable(&self) -> bool {
+        self.retry_command.is_some()
+    }
+
+    // A fresh modal action for running the same operation again, along with the message which starts it
+    pub fn retry(&self) -> Option<(ModalAction, MessageToBackend)> {
+        let retry_command = self.retry_command.clone()?;
+        let modal_action = Self {
+            inner: Arc::new(ModalActionInner {
+                retry_command: Some(retry_command.clone()),
+                ..Default::default()
+            }),
+        };
+        let message = retry_command(modal_action.clone());
+        Some((modal_action, message))
+    }
 }
 
 impl Deref for ModalAction {
@@ -35,6 +64,8 @@ impl Deref for ModalAction {
 pub struct ModalActionVisitUrl {
     pub message: Arc<str>,
     pub url: Arc<str>,
+    // Code the user has to enter after opening the url, shown separately so that it can be copied
+    pub code: Option<Arc<str>>,
     pub prevent_auto_finish: bool,
 }
 
@@ -45,6 +76,7 @@ pub struct ModalActionInner {
     pub visit_url: RwLock<Option<ModalActionVisitUrl>>,
     pub trackers: ProgressTrackers,
     pub request_cancel: CancellationToken,
+    pub retry_command: Option<RetryCommand>,
 }
 
 impl ModalActionInner {
@@ -85,6 +117,7 @@ impl std::fmt::Debug for ModalActionInner {
             .field("visit_url", &self.visit_url)
             .field("trackers", &self.trackers)
             .field("request_cancel", &self.request_cancel)
+            .field("retryable", &self.retry_command.is_some())
             .finish()
     }
 }
@@ -102,6 +135,35 @@ impl ProgressTrackers {
     pub fn clear(&self) {
         self.trackers.write().unwrap().clear();
     }
+
+    // Combined progress of the top level trackers, weighted by their weight
+    pub fn get_overall_float(&self) -> Option<f32> {
+        let trackers = self.trackers.read().unwrap();
+        if trackers.is_empty() {
+            return None;
+        }
+        Some(weighted_progress(&trackers))
+    }
+
+    pub fn get_overall_eta(&self) -> Option<Duration> {
+        let started_at = self.trackers.read().unwrap().iter().map(|tracker| tracker.inner.started_at).min()?;
+        estimate_remaining(started_at, self.get_overall_float()?)
+    }
+}
+
+fn weighted_progress(trackers: &[ProgressTracker]) -> f32 {
+    let total_weight: usize = trackers.iter().map(ProgressTracker::weight).sum();
+    let progress: f32 = trackers.iter().map(|tracker| tracker.weight() as f32 * tracker.get_progress()).sum();
+    (progress / total_weight.max(1) as f32).clamp(0.0, 1.0)
+}
+
+fn estimate_remaining(started_at: Instant, progress: f32) -> Option<Duration> {
+    let elapsed = started_at.elapsed();
+    // Estimates made this early jump around too much to be useful
+    if progress < 0.05 || progress >= 1.0 || elapsed < Duration::from_secs(2) {
+        return None;
+    }
+    Some(elapsed.mul_f32((1.0 - progress) / progress))
 }
 
 #[derive(Clone, Debug)]
@@ -117,6 +179,10 @@ struct ProgressTrackerInner {
     finished_at: AtomicOptionInstant,
     finish_type: AtomicProgressTrackerFinishType,
     title: RwLock<Arc<str>>,
+    started_at: Instant,
+    // How much of the parent, or of the overall progress for top level trackers, this tracker accounts for
+    weight: AtomicUsize,
+    children: RwLock<Vec<ProgressTracker>>,
 }
 
 #[atomic_enum::atomic_enum]
@@ -143,6 +209,8 @@ impl std::fmt::Debug for ProgressTrackerInner {
             .field("count", &self.count)
             .field("total", &self.total)
             .field("finished_at", &self.finished_at.load(Ordering::Relaxed))
+            .field("weight", &self.weight)
+            .field("children", &self.children)
             .finish()
     }
 }
@@ -156,12 +224,35 @@ impl ProgressTracker {
                 finished_at: AtomicOptionInstant::none(),
                 finish_type: AtomicProgressTrackerFinishType::new(ProgressTrackerFinishType::Normal),
                 title: RwLock::new(title),
+                started_at: Instant::now(),
+                weight: AtomicUsize::new(1),
+                children: RwLock::new(Vec::new()),
             }),
             sender,
             notify_serial: AtomicOptionSerial::default(),
         }
     }
 
+    // Creates a tracker for a part of this one, e.g. downloading libraries while installing a loader. An unfinished
+    // child counts towards the next `weight` steps of this tracker, the child is expected to add them once it finishes
+    pub fn child(&self, title: Arc<str>) -> ProgressTracker {
+        let child = ProgressTracker::new(title, self.sender.clone());
+        self.inner.children.write().unwrap().push(child.clone());
+        child
+    }
+
+    pub fn children(&self) -> Vec<ProgressTracker> {
+        self.inner.children.read().unwrap().clone()
+    }
+
+    pub fn weight(&self) -> usize {
+        self.inner.weight.load(Ordering::SeqCst)
+    }
+
+    pub fn set_weight(&self, weight: usize) {
+        self.inner.weight.store(weight, Ordering::SeqCst);
+    }
+
     pub fn id(&self) -> usize {
         Arc::as_ptr(&self.inner).addr()
     }
@@ -176,11 +267,37 @@ impl ProgressTracker {
 
     pub fn get_float(&self) -> Option<f32> {
         let (count, total) = self.get();
+        let children = self.inner.children.read().unwrap();
         if total == 0 {
-            None
+            // Without steps of its own, a parent is as far along as its children
+            if children.is_empty() {
+                None
+            } else {
+                Some(weighted_progress(&children))
+            }
         } else {
-            Some((count as f32 / total as f32).clamp(0.0, 1.0))
+            let partial: f32 = children.iter()
+                .filter(|child| child.get_finished_at().is_none())
+                .map(|child| child.weight() as f32 * child.get_float().unwrap_or(0.0))
+                .sum();
+            Some(((count as f32 + partial) / total as f32).clamp(0.0, 1.0))
+        }
+    }
+
+    // Like get_float, but finished trackers are always complete and unknown progress counts as none
+    pub fn get_progress(&self) -> f32 {
+        if self.get_finished_at().is_some() {
+            1.0
+        } else {
+            self.get_float().unwrap_or(0.0)
+        }
+    }
+
+    pub fn get_eta(&self) -> Option<Duration> {
+        if self.get_finished_at().is_some() {
+            return None;
         }
+        estimate_remaining(self.inner.started_at, self.get_float()?)
     }
 
     pub fn get(&self) -> (usize, usize) {
@@ -220,6 +337,7 @@ impl ProgressTracker {
     }
 
     pub fn notify(&self) {
+        self.sender.observe_progress(self);
         self.sender.send_with_serial(MessageToFrontend::Refresh, &self.notify_serial);
     }
 }
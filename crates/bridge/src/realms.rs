@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RealmState {
+    Open,
+    Closed,
+    Expired,
+    Uninitialized,
+}
+
+#[derive(Debug, Clone)]
+pub struct RealmSummary {
+    pub id: i64,
+    pub name: Arc<str>,
+    pub motd: Arc<str>,
+    pub owner: Arc<str>,
+    pub state: RealmState,
+    pub online_players: usize,
+    pub max_players: u32,
+    pub minigame: Option<Arc<str>>,
+}
@@ -0,0 +1,317 @@
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    sync::{Mutex, OnceLock},
+    time::Instant,
+};
+
+use schema::{
+    instance::{InstanceJvmFlagsConfiguration, InstanceMemoryConfiguration, InstanceType},
+    loader::Loader,
+};
+use serde::{Deserialize, Serialize};
+use ustr::Ustr;
+
+use crate::{
+    instance::InstanceID,
+    message::{MessageToBackend, MessageToFrontend},
+    modal_action::ModalAction,
+};
+
+// Bumped whenever the format of replay files changes, older replays are rejected instead of being misread
+pub const REPLAY_VERSION: u32 = 1;
+
+// Debug output of messages is cut off after this many characters, so icons, logs and content lists don't bloat
+// the replay file
+const SUMMARY_LIMIT: usize = 1024;
+
+static RECORDER: OnceLock<ReplayRecorder> = OnceLock::new();
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReplayHeader {
+    pub replay_version: u32,
+    pub launcher_version: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayDirection {
+    ToBackend,
+    ToFrontend,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReplayEntry {
+    pub at_millis: u64,
+    pub direction: ReplayDirection,
+    pub summary: String,
+    // Only set for messages which can be sent again when replaying
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<ReplayMessage>,
+}
+
+// Instance ids aren't stable between sessions, so replays refer to the id from the recording and it's mapped to the
+// instance with the same name
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReplayInstanceId {
+    pub index: usize,
+    pub generation: usize,
+}
+
+impl From<InstanceID> for ReplayInstanceId {
+    fn from(id: InstanceID) -> Self {
+        Self {
+            index: id.index,
+            generation: id.generation,
+        }
+    }
+}
+
+// The messages which only carry plain data. Messages with channels, modal actions the frontend is watching or
+// keep alives can't be reconstructed and are only kept as a summary
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReplayMessage {
+    CreateInstance {
+        name: Ustr,
+        version: Ustr,
+        loader: Loader,
+        preferred_loader_version: Option<Ustr>,
+        group: Option<Ustr>,
+        instance_type: InstanceType,
+        accept_eula: bool,
+        #[serde(default)]
+        install_fabric_api: bool,
+    },
+    DeleteInstance { id: ReplayInstanceId },
+    RenameInstance { id: ReplayInstanceId, name: Ustr },
+    SetInstanceMinecraftVersion { id: ReplayInstanceId, version: Ustr },
+    SetInstanceGroup { id: ReplayInstanceId, group: Option<Ustr> },
+    SetInstanceLoader { id: ReplayInstanceId, loader: Loader },
+    SetInstanceMemory { id: ReplayInstanceId, memory: InstanceMemoryConfiguration },
+    SetInstanceJvmFlags { id: ReplayInstanceId, jvm_flags: InstanceJvmFlagsConfiguration },
+    StartInstance { id: ReplayInstanceId },
+    PrepareInstance { id: ReplayInstanceId },
+    KillInstance { id: ReplayInstanceId },
+    RequestLoadWorlds { id: ReplayInstanceId },
+    RequestLoadMods { id: ReplayInstanceId },
+    RequestLoadResourcePacks { id: ReplayInstanceId },
+    // Sent by the backend, used to map recorded instance ids to names
+    InstanceNamed { id: ReplayInstanceId, name: Ustr },
+}
+
+impl ReplayMessage {
+    pub fn from_backend_message(message: &MessageToBackend) -> Option<Self> {
+        Some(match message {
+            MessageToBackend::CreateInstance { name, version, loader, preferred_loader_version, group, instance_type, accept_eula, install_fabric_api, .. } => {
+                Self::CreateInstance {
+                    name: *name,
+                    version: *version,
+                    loader: *loader,
+                    preferred_loader_version: *preferred_loader_version,
+                    group: *group,
+                    instance_type: *instance_type,
+                    accept_eula: *accept_eula,
+                    install_fabric_api: *install_fabric_api,
+                }
+            },
+            MessageToBackend::DeleteInstance { id } => Self::DeleteInstance { id: (*id).into() },
+            MessageToBackend::RenameInstance { id, name } => Self::RenameInstance { id: (*id).into(), name: *name },
+            MessageToBackend::SetInstanceMinecraftVersion { id, version } => {
+                Self::SetInstanceMinecraftVersion { id: (*id).into(), version: *version }
+            },
+            MessageToBackend::SetInstanceGroup { id, group } => Self::SetInstanceGroup { id: (*id).into(), group: *group },
+            MessageToBackend::SetInstanceLoader { id, loader } => Self::SetInstanceLoader { id: (*id).into(), loader: *loader },
+            MessageToBackend::SetInstanceMemory { id, memory } => Self::SetInstanceMemory { id: (*id).into(), memory: *memory },
+            MessageToBackend::SetInstanceJvmFlags { id, jvm_flags } => {
+                Self::SetInstanceJvmFlags { id: (*id).into(), jvm_flags: jvm_flags.clone() }
+            },
+            MessageToBackend::StartInstance { id, quick_play: None, .. } => Self::StartInstance { id: (*id).into() },
+            MessageToBackend::PrepareInstance { id, .. } => Self::PrepareInstance { id: (*id).into() },
+            MessageToBackend::KillInstance { id } => Self::KillInstance { id: (*id).into() },
+            MessageToBackend::RequestLoadWorlds { id } => Self::RequestLoadWorlds { id: (*id).into() },
+            MessageToBackend::RequestLoadMods { id } => Self::RequestLoadMods { id: (*id).into() },
+            MessageToBackend::RequestLoadResourcePacks { id } => Self::RequestLoadResourcePacks { id: (*id).into() },
+            _ => return None,
+        })
+    }
+
+    pub fn from_frontend_message(message: &MessageToFrontend) -> Option<Self> {
+        match message {
+            MessageToFrontend::InstanceAdded { id, name, .. } | MessageToFrontend::InstanceModified { id, name, .. } => {
+                Some(Self::InstanceNamed { id: (*id).into(), name: *name })
+            },
+            _ => None,
+        }
+    }
+
+    // Recreates the message for the current session, returns None if it refers to an instance that doesn't exist
+    pub fn into_backend_message(self, resolve: impl Fn(ReplayInstanceId) -> Option<InstanceID>) -> Option<MessageToBackend> {
+        Some(match self {
+            Self::CreateInstance { name, version, loader, preferred_loader_version, group, instance_type, accept_eula, install_fabric_api } => {
+                MessageToBackend::CreateInstance {
+                    name,
+                    version,
+                    loader,
+                    preferred_loader_version,
+                    group,
+                    icon: None,
+                    instance_type,
+                    accept_eula,
+                    install_fabric_api,
+                }
+            },
+            Self::DeleteInstance { id } => MessageToBackend::DeleteInstance { id: resolve(id)? },
+            Self::RenameInstance { id, name } => MessageToBackend::RenameInstance { id: resolve(id)?, name },
+            Self::SetInstanceMinecraftVersion { id, version } => MessageToBackend::SetInstanceMinecraftVersion { id: resolve(id)?, version },
+            Self::SetInstanceGroup { id, group } => MessageToBackend::SetInstanceGroup { id: resolve(id)?, group },
+            Self::SetInstanceLoader { id, loader } => MessageToBackend::SetInstanceLoader { id: resolve(id)?, loader },
+            Self::SetInstanceMemory { id, memory } => MessageToBackend::SetInstanceMemory { id: resolve(id)?, memory },
+            Self::SetInstanceJvmFlags { id, jvm_flags } => MessageToBackend::SetInstanceJvmFlags { id: resolve(id)?, jvm_flags },
+            Self::StartInstance { id } => MessageToBackend::StartInstance {
+                id: resolve(id)?,
+                quick_play: None,
+                modal_action: ModalAction::default(),
+            },
+            Self::PrepareInstance { id } => MessageToBackend::PrepareInstance {
+                id: resolve(id)?,
+                modal_action: ModalAction::default(),
+            },
+            Self::KillInstance { id } => MessageToBackend::KillInstance { id: resolve(id)? },
+            Self::RequestLoadWorlds { id } => MessageToBackend::RequestLoadWorlds { id: resolve(id)? },
+            Self::RequestLoadMods { id } => MessageToBackend::RequestLoadMods { id: resolve(id)? },
+            Self::RequestLoadResourcePacks { id } => MessageToBackend::RequestLoadResourcePacks { id: resolve(id)? },
+            Self::InstanceNamed { .. } => return None,
+        })
+    }
+}
+
+struct ReplayRecorder {
+    started: Instant,
+    writer: Mutex<BufWriter<File>>,
+}
+
+impl ReplayRecorder {
+    fn record(&self, direction: ReplayDirection, summary: String, message: Option<ReplayMessage>) {
+        let entry = ReplayEntry {
+            at_millis: self.started.elapsed().as_millis() as u64,
+            direction,
+            summary,
+            message,
+        };
+        let Ok(line) = serde_json::to_string(&entry) else {
+            return;
+        };
+
+        let mut writer = self.writer.lock().unwrap();
+        _ = writeln!(writer, "{line}");
+        _ = writer.flush();
+    }
+}
+
+// Records all bridge traffic of this process into the file, used to reproduce bugs with --replay
+pub fn start_recording(path: &Path, launcher_version: Option<&str>) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    let header = ReplayHeader {
+        replay_version: REPLAY_VERSION,
+        launcher_version: launcher_version.map(str::to_string),
+    };
+    writeln!(writer, "{}", serde_json::to_string(&header)?)?;
+    writer.flush()?;
+
+    _ = RECORDER.set(ReplayRecorder {
+        started: Instant::now(),
+        writer: Mutex::new(writer),
+    });
+    Ok(())
+}
+
+pub(crate) fn record_to_backend(message: &MessageToBackend) {
+    if let Some(recorder) = RECORDER.get() {
+        recorder.record(ReplayDirection::ToBackend, summarize_to_backend(message), ReplayMessage::from_backend_message(message));
+    }
+}
+
+pub(crate) fn record_to_frontend(message: &MessageToFrontend) {
+    // Progress refreshes are sent many times a second and carry nothing
+    if let Some(recorder) = RECORDER.get() && !matches!(message, MessageToFrontend::Refresh) {
+        recorder.record(ReplayDirection::ToFrontend, summarize(message), ReplayMessage::from_frontend_message(message));
+    }
+}
+
+// Replays are attached to bug reports, so tokens and PINs are left out of the summary
+fn summarize_to_backend(message: &MessageToBackend) -> String {
+    match message {
+        MessageToBackend::ModrinthLogin { .. } => "ModrinthLogin { token: <redacted>, .. }".into(),
+        MessageToBackend::ConfigureParentalControls { restrictions, .. } => {
+            summarize(&format_args!("ConfigureParentalControls {{ pin: <redacted>, restrictions: {restrictions:?}, .. }}"))
+        },
+        MessageToBackend::UnlockParentalControls { .. } => "UnlockParentalControls { pin: <redacted>, .. }".into(),
+        _ => summarize(message),
+    }
+}
+
+// The debug output of the message, cut off once it gets too long
+pub fn summarize(message: &impl std::fmt::Debug) -> String {
+    let mut summary = LimitedString::default();
+    _ = write!(summary, "{message:?}");
+    if summary.skipped > 0 {
+        _ = write!(summary.text, "... ({} more characters)", summary.skipped);
+    }
+    summary.text
+}
+
+#[derive(Default)]
+struct LimitedString {
+    text: String,
+    skipped: usize,
+}
+
+impl std::fmt::Write for LimitedString {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        let remaining = SUMMARY_LIMIT.saturating_sub(self.text.len());
+        if s.len() <= remaining {
+            self.text.push_str(s);
+        } else {
+            let mut end = remaining;
+            while !s.is_char_boundary(end) {
+                end -= 1;
+            }
+            self.text.push_str(&s[..end]);
+            self.skipped += s.len() - end;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{message::MessageToBackend, modal_action::ModalAction};
+
+    use super::summarize_to_backend;
+
+    #[test]
+    fn test_secrets_are_redacted() {
+        let summary = summarize_to_backend(&MessageToBackend::ModrinthLogin {
+            token: "mrp_secret_token".into(),
+            modal_action: ModalAction::default(),
+        });
+        assert!(summary.starts_with("ModrinthLogin"));
+        assert!(!summary.contains("mrp_secret_token"));
+
+        let (channel, _) = tokio::sync::oneshot::channel();
+        let summary = summarize_to_backend(&MessageToBackend::UnlockParentalControls { pin: "482913".into(), channel });
+        assert!(!summary.contains("482913"));
+
+        let (channel, _) = tokio::sync::oneshot::channel();
+        let summary = summarize_to_backend(&MessageToBackend::ConfigureParentalControls { pin: "482913".into(), restrictions: None, channel });
+        assert!(!summary.contains("482913"));
+        assert!(summary.contains("restrictions: None"));
+    }
+}
@@ -0,0 +1,39 @@
+use std::sync::OnceLock;
+
+// Packages running in a container are updated by their package manager and can't run programs on the host
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sandbox {
+    Flatpak,
+    Snap,
+}
+
+impl Sandbox {
+    pub fn detect() -> Option<Sandbox> {
+        static SANDBOX: OnceLock<Option<Sandbox>> = OnceLock::new();
+        *SANDBOX.get_or_init(|| {
+            if !cfg!(target_os = "linux") {
+                None
+            } else if std::path::Path::new("/.flatpak-info").exists() {
+                Some(Sandbox::Flatpak)
+            } else if std::env::var_os("SNAP").is_some() {
+                Some(Sandbox::Snap)
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Sandbox::Flatpak => "Flatpak",
+            Sandbox::Snap => "Snap",
+        }
+    }
+
+    pub fn update_hint(self) -> &'static str {
+        match self {
+            Sandbox::Flatpak => "Update it through your software center or by running `flatpak update`",
+            Sandbox::Snap => "Update it through your software center or by running `snap refresh`",
+        }
+    }
+}
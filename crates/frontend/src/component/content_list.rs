@@ -3,11 +3,11 @@ use std::{hash::{DefaultHasher, Hash, Hasher}, sync::{
 }};
 
 use bridge::{
-    handle::BackendHandle, instance::{AtomicContentUpdateStatus, InstanceID, InstanceContentID, InstanceContentSummary, ContentType, ContentSummary}, message::MessageToBackend
+    handle::BackendHandle, instance::{AtomicContentUpdateStatus, ContentDependencyKind, InstanceID, InstanceContentID, InstanceContentSummary, ContentType, ContentSummary}, message::MessageToBackend
 };
 use gpui::{prelude::*, *};
 use gpui_component::{
-    button::{Button, ButtonVariants}, h_flex, list::{ListDelegate, ListItem, ListState}, switch::Switch, v_flex, ActiveTheme as _, Icon, IconName, IndexPath, Sizable
+    button::{Button, ButtonVariants}, h_flex, list::{ListDelegate, ListItem, ListState}, switch::Switch, tooltip::Tooltip, v_flex, ActiveTheme as _, Icon, IconName, IndexPath, Sizable
 };
 use parking_lot::Mutex;
 use rustc_hash::FxHashSet;
@@ -75,13 +75,14 @@ impl ContentListDelegate {
         let (desc1, desc2) = create_descriptions(summary.content_summary.name.clone(),
             summary.content_summary.version_str.clone(), summary.content_summary.authors.clone(),
             summary.filename.clone());
+        let desc1 = with_metadata_tooltip(desc1, &summary.content_summary, summary.filename_hash);
 
         let id = self.id;
         let content_id = summary.id;
         let element_id = summary.filename_hash;
 
         let delete_button = if self.confirming_delete.lock().contains(&element_id) {
-            Button::new(("delete", element_id)).danger().icon(IconName::Check).on_click({
+            Button::new(("delete", element_id)).danger().icon(IconName::Check).tooltip("Confirm delete").on_click({
                 let backend_handle = self.backend_handle.clone();
                 cx.listener(move |this, _, _, cx| {
                     cx.stop_propagation();
@@ -101,7 +102,7 @@ impl ContentListDelegate {
             let trash_icon = Icon::default().path("icons/trash-2.svg");
             let confirming_delete = self.confirming_delete.clone();
             let backend_handle = self.backend_handle.clone();
-            Button::new(("delete", element_id)).danger().icon(trash_icon).on_click(cx.listener(move |this, click: &ClickEvent, _, cx| {
+            Button::new(("delete", element_id)).danger().icon(trash_icon).tooltip("Delete").on_click(cx.listener(move |this, click: &ClickEvent, _, cx| {
                 cx.stop_propagation();
                 let delegate = this.delegate();
 
@@ -219,7 +220,7 @@ impl ContentListDelegate {
                 IconName::ArrowRight
             };
 
-            let expand_control = Button::new(("expand", element_id)).icon(expand_icon).compact().small().info().on_click({
+            let expand_control = Button::new(("expand", element_id)).icon(expand_icon).compact().small().info().tooltip("Show contents").on_click({
                 let expanded = self.expanded.clone();
                 let index = ix+1;
                 move |_, _, _| {
@@ -354,6 +355,8 @@ impl ContentListDelegate {
         child.path.hash(&mut hasher);
         let element_id = hasher.finish();
 
+        let desc1 = with_metadata_tooltip(desc1, summary, element_id);
+
         let enabled = child.enabled;
         let visually_enabled = enabled && child.parent_enabled;
 
@@ -404,6 +407,9 @@ impl ContentListDelegate {
             version_str: "unknown".into(),
             authors: "".into(),
             png_icon: None,
+            description: None,
+            dependencies: [].into(),
+            provides: [].into(),
             update_status: Arc::new(AtomicContentUpdateStatus::new(bridge::instance::ContentUpdateStatus::Unknown)),
             extra: ContentType::Fabric,
         });
@@ -522,6 +528,31 @@ impl ContentListDelegate {
         self.confirming_delete.lock().clear();
     }
 
+    // Applies to the selected content, or to all visible content if nothing is selected
+    pub fn set_selected_enabled(&self, enabled: bool) {
+        let has_selection = !self.selected.is_empty() || !self.selected_range.is_empty();
+        let matches = |summary: &InstanceContentSummary| {
+            summary.enabled != enabled && (!has_selection || self.is_selected(summary.filename_hash))
+        };
+
+        let content_ids: Vec<InstanceContentID> = if let Some(searched) = &self.searched {
+            searched.iter().filter_map(|element| match element {
+                SummaryOrChild::Summary(summary) if matches(summary) => Some(summary.id),
+                _ => None,
+            }).collect()
+        } else {
+            self.content.iter().filter(|summary| matches(summary)).map(|summary| summary.id).collect()
+        };
+
+        if !content_ids.is_empty() {
+            self.backend_handle.send(MessageToBackend::SetContentEnabled {
+                id: self.id,
+                content_ids,
+                enabled,
+            });
+        }
+    }
+
     pub fn select_all(&mut self) {
         self.clear_selection();
 
@@ -622,3 +653,42 @@ fn create_descriptions(name: Option<Arc<str>>, version: Arc<str>, authors: Arc<s
 
     (description1, Some(description2))
 }
+
+fn with_metadata_tooltip(description: Div, summary: &ContentSummary, element_id: u64) -> Stateful<Div> {
+    let description = description.id(("description", element_id));
+
+    let mut text = String::new();
+    if let Some(summary_description) = &summary.description {
+        text.push_str(summary_description);
+    }
+    for (kind, label) in [
+        (ContentDependencyKind::Required, "Requires"),
+        (ContentDependencyKind::Optional, "Optional"),
+        (ContentDependencyKind::Incompatible, "Incompatible with"),
+    ] {
+        let mut dependencies = summary.dependencies.iter().filter(|dependency| dependency.kind == kind).peekable();
+        if dependencies.peek().is_none() {
+            continue;
+        }
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(label);
+        text.push(':');
+        for dependency in dependencies {
+            text.push_str("\n  ");
+            text.push_str(&dependency.id);
+            if let Some(version_range) = &dependency.version_range {
+                text.push(' ');
+                text.push_str(version_range);
+            }
+        }
+    }
+
+    if text.is_empty() {
+        return description;
+    }
+
+    let text = SharedString::from(text);
+    description.tooltip(move |window, cx| Tooltip::new(text.clone()).build(window, cx))
+}
@@ -21,9 +21,9 @@ impl ErrorAlert {
 impl RenderOnce for ErrorAlert {
     fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
         let radius = cx.theme().radius;
-        let padding_x = px(16.0);
-        let padding_y = px(10.0);
-        let gap = px(12.0);
+        let padding_x = rems(1.0);
+        let padding_y = rems(0.625);
+        let gap = rems(0.75);
 
         let danger = cx.theme().danger;
         let bg = danger.opacity(0.08);
@@ -50,7 +50,7 @@ impl RenderOnce for ErrorAlert {
                     .flex_1()
                     .overflow_hidden()
                     .gap(gap)
-                    .child(div().mt(px(6.0)).child(IconName::CircleX))
+                    .child(div().mt(rems(0.375)).child(IconName::CircleX))
                     .child(
                         v_flex()
                             .overflow_hidden()
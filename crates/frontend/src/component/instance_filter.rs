@@ -0,0 +1,132 @@
+use std::{cmp::Ordering, sync::atomic};
+
+use gpui::{App, SharedString};
+use schema::loader::Loader;
+
+use crate::entity::instance::InstanceEntry;
+
+const DAY_SECONDS: i64 = 60 * 60 * 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, strum::EnumIter)]
+pub enum PlayedWithin {
+    #[default]
+    Any,
+    Day,
+    Week,
+    Month,
+    Never,
+}
+
+impl PlayedWithin {
+    pub fn name(self) -> SharedString {
+        match self {
+            PlayedWithin::Any => "Any time".into(),
+            PlayedWithin::Day => "Today".into(),
+            PlayedWithin::Week => "This week".into(),
+            PlayedWithin::Month => "This month".into(),
+            PlayedWithin::Never => "Never".into(),
+        }
+    }
+
+    fn matches(self, last_played: Option<i64>, now: i64) -> bool {
+        let max_age = match self {
+            PlayedWithin::Any => return true,
+            PlayedWithin::Never => return last_played.is_none(),
+            PlayedWithin::Day => DAY_SECONDS,
+            PlayedWithin::Week => DAY_SECONDS * 7,
+            PlayedWithin::Month => DAY_SECONDS * 30,
+        };
+        last_played.is_some_and(|last_played| now - last_played <= max_age)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, strum::EnumIter)]
+pub enum InstanceSortOrder {
+    #[default]
+    Recent,
+    LastPlayed,
+    Name,
+    Version,
+}
+
+impl InstanceSortOrder {
+    pub fn name(self) -> SharedString {
+        match self {
+            InstanceSortOrder::Recent => "Recently added".into(),
+            InstanceSortOrder::LastPlayed => "Last played".into(),
+            InstanceSortOrder::Name => "Name".into(),
+            InstanceSortOrder::Version => "Version".into(),
+        }
+    }
+
+    // Returns None for the default order, which keeps the order of the instance entries
+    pub fn compare(self, a: &InstanceEntry, b: &InstanceEntry) -> Option<Ordering> {
+        match self {
+            InstanceSortOrder::Recent => None,
+            InstanceSortOrder::LastPlayed => Some(b.configuration.last_played.cmp(&a.configuration.last_played)),
+            InstanceSortOrder::Name => Some(lexical_sort::natural_lexical_cmp(&a.name, &b.name)),
+            InstanceSortOrder::Version => Some(lexical_sort::natural_lexical_cmp(&b.configuration.minecraft_version,
+                &a.configuration.minecraft_version)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InstanceFilter {
+    // Always lowercase
+    pub query: SharedString,
+    pub version: Option<SharedString>,
+    pub loader: Option<Loader>,
+    pub group: Option<SharedString>,
+    pub played_within: PlayedWithin,
+    pub has_updates: bool,
+}
+
+impl InstanceFilter {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn matches(&self, entry: &InstanceEntry, now: i64, cx: &App) -> bool {
+        if !self.query.is_empty() && !entry.name.to_lowercase().contains(self.query.as_str()) {
+            return false;
+        }
+        if let Some(version) = &self.version && entry.configuration.minecraft_version.as_str() != version.as_str() {
+            return false;
+        }
+        if let Some(loader) = self.loader && entry.configuration.loader != loader {
+            return false;
+        }
+        if let Some(group) = &self.group && entry.configuration.group.map(|g| g.as_str()) != Some(group.as_str()) {
+            return false;
+        }
+        if !self.played_within.matches(entry.configuration.last_played, now) {
+            return false;
+        }
+        if self.has_updates {
+            // Only known once the content of the instance has been checked for updates
+            let has_updates = entry.mods.read(cx).iter().chain(entry.resource_packs.read(cx).iter()).any(|content| {
+                content.content_summary.update_status.load(atomic::Ordering::Relaxed).can_update()
+            });
+            if !has_updates {
+                return false;
+            }
+        }
+        true
+    }
+
+    // Returns true if everything matched by this filter is also matched by `previous`, in which case
+    // the previous results can be filtered further instead of checking every instance again
+    pub fn narrows(&self, previous: &Self) -> bool {
+        self.query.contains(previous.query.as_str())
+            && (previous.version.is_none() || self.version == previous.version)
+            && (previous.loader.is_none() || self.loader == previous.loader)
+            && (previous.group.is_none() || self.group == previous.group)
+            && (previous.played_within == PlayedWithin::Any || self.played_within == previous.played_within)
+            && (!previous.has_updates || self.has_updates)
+    }
+}
+
+pub fn unix_now() -> i64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0)
+}
@@ -1,18 +1,25 @@
-use bridge::handle::BackendHandle;
+use std::collections::HashSet;
+
+use bridge::{handle::BackendHandle, instance::InstanceID, message::MessageToBackend};
 use gpui::{prelude::*, *};
 use gpui_component::{
-    button::{Button, ButtonVariants}, h_flex, table::{Column, ColumnSort, TableDelegate, TableState}, v_flex, ActiveTheme, Icon, IconName, Sizable
+    button::{Button, ButtonVariants}, checkbox::Checkbox, h_flex, table::{Column, ColumnSort, TableDelegate, TableState}, v_flex, ActiveTheme, Icon, IconName, Sizable
 };
 
 use crate::{
     entity::{
         instance::{InstanceAddedEvent, InstanceEntry, InstanceModifiedEvent, InstanceRemovedEvent}, DataEntities
-    }, pages::instance::instance_page::InstanceSubpageType, png_render_cache, root, ui
+    }, component::instance_filter::{unix_now, InstanceFilter, InstanceSortOrder}, pages::instance::instance_page::InstanceSubpageType, png_render_cache, root, ui
 };
 
 pub struct InstanceList {
     columns: Vec<Column>,
+    all_items: Vec<InstanceEntry>,
+    // The instances matching the current filter, in display order
     items: Vec<InstanceEntry>,
+    filter: InstanceFilter,
+    sort_order: InstanceSortOrder,
+    pub selected: HashSet<InstanceID>,
     backend_handle: BackendHandle,
     _instance_added_subscription: Subscription,
     _instance_removed_subscription: Subscription,
@@ -22,28 +29,51 @@ pub struct InstanceList {
 impl InstanceList {
     pub fn create_table(data: &DataEntities, window: &mut Window, cx: &mut App) -> Entity<TableState<Self>> {
         let instances = data.instances.clone();
-        let items = instances.read(cx).entries.values().map(|i| i.read(cx).clone()).collect();
+        let items: Vec<InstanceEntry> = instances.read(cx).entries.values().map(|i| i.read(cx).clone()).collect();
         cx.new(|cx| {
             let _instance_added_subscription = cx.subscribe::<_, InstanceAddedEvent>(&instances, |table: &mut TableState<InstanceList>, _, event, cx| {
-                table.delegate_mut().items.insert(0, event.instance.clone());
+                let delegate = table.delegate_mut();
+                delegate.all_items.insert(0, event.instance.clone());
+                if delegate.filter.matches(&event.instance, unix_now(), cx) {
+                    delegate.items.insert(0, event.instance.clone());
+                    delegate.sort_items();
+                }
                 cx.notify();
             });
             let _instance_removed_subscription = cx.subscribe::<_, InstanceRemovedEvent>(&instances, |table, _, event, cx| {
-                table.delegate_mut().items.retain(|instance| {
-                    instance.id != event.id
-                });
+                let delegate = table.delegate_mut();
+                delegate.all_items.retain(|instance| instance.id != event.id);
+                delegate.items.retain(|instance| instance.id != event.id);
+                delegate.selected.remove(&event.id);
                 cx.notify();
             });
             let _instance_modified_subscription = cx.subscribe::<_, InstanceModifiedEvent>(&instances, |table, _, event, cx| {
-                if let Some(entry) = table.delegate_mut().items.iter_mut().find(|entry| entry.id == event.instance.id) {
+                let delegate = table.delegate_mut();
+                if let Some(entry) = delegate.all_items.iter_mut().find(|entry| entry.id == event.instance.id) {
                     *entry = event.instance.clone();
-                    cx.notify();
                 }
+
+                // Only the modified instance needs to be checked against the filter again
+                let matches = delegate.filter.matches(&event.instance, unix_now(), cx);
+                let position = delegate.items.iter().position(|entry| entry.id == event.instance.id);
+                match (position, matches) {
+                    (Some(position), true) => delegate.items[position] = event.instance.clone(),
+                    (Some(position), false) => _ = delegate.items.remove(position),
+                    (None, true) => {
+                        delegate.items = delegate.all_items.iter()
+                            .filter(|entry| entry.id == event.instance.id || delegate.items.contains(entry))
+                            .cloned()
+                            .collect();
+                    },
+                    (None, false) => return,
+                }
+                delegate.sort_items();
+                cx.notify();
             });
-            let instance_list = Self {
+            let mut instance_list = Self {
                 columns: vec![
                     Column::new("controls", "")
-                        .width(150.)
+                        .width(210.)
                         .fixed_left()
                         .movable(false)
                         .resizable(false),
@@ -62,17 +92,81 @@ impl InstanceList {
                         .fixed_left()
                         .resizable(true),
                 ],
+                all_items: items.clone(),
                 items,
+                filter: InstanceFilter::default(),
+                sort_order: InstanceSortOrder::default(),
+                selected: HashSet::new(),
                 backend_handle: data.backend_handle.clone(),
                 _instance_added_subscription,
                 _instance_removed_subscription,
                 _instance_modified_subscription,
             };
+            instance_list.sort_items();
             TableState::new(instance_list, window, cx)
         })
     }
 
-    pub fn render_card(&self, index: usize, cx: &mut App) -> Div {
+    pub fn all_items(&self) -> &[InstanceEntry] {
+        &self.all_items
+    }
+
+    pub fn set_filter(&mut self, filter: InstanceFilter, cx: &App) {
+        if filter == self.filter {
+            return;
+        }
+
+        let now = unix_now();
+        if filter.narrows(&self.filter) {
+            self.items.retain(|entry| filter.matches(entry, now, cx));
+        } else {
+            self.items = self.all_items.iter().filter(|entry| filter.matches(entry, now, cx)).cloned().collect();
+            self.sort_items();
+        }
+        self.filter = filter;
+    }
+
+    pub fn set_sort_order(&mut self, sort_order: InstanceSortOrder) {
+        if sort_order == self.sort_order {
+            return;
+        }
+        self.sort_order = sort_order;
+        if sort_order == InstanceSortOrder::Recent {
+            self.items = self.all_items.iter().filter(|entry| self.items.contains(entry)).cloned().collect();
+        }
+        self.sort_items();
+    }
+
+    fn sort_items(&mut self) {
+        let sort_order = self.sort_order;
+        // Favorites stay at the top, the sort order applies to the favorites and to the other instances separately
+        self.items.sort_by(|a, b| {
+            b.configuration.favorite.cmp(&a.configuration.favorite)
+                .then_with(|| sort_order.compare(a, b).unwrap_or(std::cmp::Ordering::Equal))
+        });
+    }
+
+    pub fn selected_entries(&self) -> Vec<InstanceEntry> {
+        self.items.iter().filter(|item| self.selected.contains(&item.id)).cloned().collect()
+    }
+
+    fn toggle_selected(&mut self, id: InstanceID, selected: bool) {
+        if selected {
+            self.selected.insert(id);
+        } else {
+            self.selected.remove(&id);
+        }
+    }
+
+    pub fn item(&self, index: usize) -> Option<&InstanceEntry> {
+        self.items.get(index)
+    }
+
+    pub fn position(&self, id: InstanceID) -> Option<usize> {
+        self.items.iter().position(|item| item.id == id)
+    }
+
+    pub fn render_card(&self, index: usize, focused: bool, table: &Entity<TableState<Self>>, cx: &mut App) -> Div {
         let item = &self.items[index];
         let loader_and_version = format!(
             "{} {}",
@@ -100,6 +194,8 @@ impl InstanceList {
             .min_w_64()
             .bg(theme.secondary)
             .rounded(theme.radius_lg)
+            .border_2()
+            .border_color(if focused { theme.ring } else { gpui::transparent_black() })
             .child(h_flex()
                 .w_full()
                 .gap_2()
@@ -110,6 +206,17 @@ impl InstanceList {
                     .child(item.name.clone())
                     .child(loader_and_version)
                 )
+                .child(favorite_button(("favorite", index), item, &self.backend_handle))
+                .child(Checkbox::new(("select", index)).checked(self.selected.contains(&item.id)).on_click({
+                    let id = item.id;
+                    let table = table.clone();
+                    move |value, _, cx| {
+                        table.update(cx, |table, cx| {
+                            table.delegate_mut().toggle_selected(id, *value);
+                            cx.notify();
+                        });
+                    }
+                }))
             ).child(h_flex()
                 .gap_2()
                 .child(Button::new(("start", index)).flex_grow().small().success().label("Start").on_click({
@@ -131,6 +238,21 @@ impl InstanceList {
     }
 }
 
+pub fn favorite_button(id: impl Into<ElementId>, item: &InstanceEntry, backend_handle: &BackendHandle) -> Button {
+    let favorite = item.configuration.favorite;
+    let button = Button::new(id)
+        .small()
+        .icon(IconName::Star)
+        .tooltip(if favorite { "Remove from favorites" } else { "Add to favorites" });
+    let button = if favorite { button.warning() } else { button.ghost() };
+
+    let id = item.id;
+    let backend_handle = backend_handle.clone();
+    button.on_click(move |_, _, _| {
+        backend_handle.send(MessageToBackend::SetInstanceFavorite { id, favorite: !favorite });
+    })
+}
+
 impl TableDelegate for InstanceList {
     fn columns_count(&self, _cx: &App) -> usize {
         self.columns.len()
@@ -166,7 +288,7 @@ impl TableDelegate for InstanceList {
         }
     }
 
-    fn render_td(&mut self, row_ix: usize, col_ix: usize, _window: &mut Window, _cx: &mut Context<TableState<Self>>) -> impl IntoElement {
+    fn render_td(&mut self, row_ix: usize, col_ix: usize, _window: &mut Window, cx: &mut Context<TableState<Self>>) -> impl IntoElement {
         let item = &self.items[row_ix];
         if let Some(col) = self.columns.get(col_ix) {
             match col.key.as_ref() {
@@ -178,6 +300,15 @@ impl TableDelegate for InstanceList {
                         .size_full()
                         .gap_2()
                         .border_r_4()
+                        .items_center()
+                        .child(Checkbox::new("select").checked(self.selected.contains(&item.id)).on_click({
+                            let id = item.id;
+                            cx.listener(move |table, value, _, cx| {
+                                table.delegate_mut().toggle_selected(id, *value);
+                                cx.notify();
+                            })
+                        }))
+                        .child(favorite_button("favorite", item, &self.backend_handle))
                         .child(Button::new("start").w(relative(0.5)).small().success().label("Start").on_click({
                             let name = item.name.clone();
                             let id = item.id;
@@ -0,0 +1,31 @@
+use gpui::{prelude::*, *};
+use gpui_component::text::TextView;
+
+// Renders the markdown used by Modrinth for project descriptions and changelogs
+#[derive(IntoElement)]
+pub struct Markdown {
+    id: ElementId,
+    source: SharedString,
+}
+
+impl Markdown {
+    pub fn new(id: impl Into<ElementId>, source: impl Into<SharedString>) -> Self {
+        Self {
+            id: id.into(),
+            source: source.into(),
+        }
+    }
+}
+
+impl RenderOnce for Markdown {
+    fn render(self, _: &mut Window, _: &mut App) -> impl IntoElement {
+        // Windows line endings show up as stray characters in some descriptions
+        let source = if self.source.contains('\r') {
+            SharedString::new(self.source.replace("\r\n", "\n"))
+        } else {
+            self.source
+        };
+
+        div().w_full().child(TextView::markdown(self.id, source).selectable(true))
+    }
+}
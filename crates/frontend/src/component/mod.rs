@@ -1,7 +1,9 @@
 pub mod content_list;
 pub mod error_alert;
 pub mod instance_dropdown;
+pub mod instance_filter;
 pub mod instance_list;
+pub mod markdown;
 pub mod menu;
 pub mod named_dropdown;
 pub mod page_path;
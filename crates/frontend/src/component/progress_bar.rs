@@ -1,5 +1,5 @@
 use gpui::{
-    App, Hsla, IntoElement, ParentElement, RenderOnce, Styled, Window, div, prelude::FluentBuilder, px, relative,
+    App, Hsla, IntoElement, ParentElement, RenderOnce, Styled, Window, div, prelude::FluentBuilder, px, relative, rems,
 };
 use gpui_component::ActiveTheme;
 
@@ -74,7 +74,7 @@ impl RenderOnce for ProgressBar {
             ProgressBarColor::Success => lerp(progress_bar_color, cx.theme().green, self.color_scale),
         };
 
-        div().w_full().relative().h(px(8.0)).rounded(radius).bg(color.opacity(0.2)).child(
+        div().w_full().relative().h(rems(0.5)).rounded(radius).bg(color.opacity(0.2)).child(
             div()
                 .absolute()
                 .top_0()
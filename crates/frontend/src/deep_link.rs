@@ -0,0 +1,62 @@
+use gpui::SharedString;
+use schema::modrinth::ModrinthProjectType;
+use serde::{de::value::{Error, StrDeserializer}, Deserialize};
+use ustr::Ustr;
+
+use crate::pages::instance::instance_page::InstanceSubpageType;
+
+// Links the launcher is opened with, such as `pandora://launch/<instance id>` or `modrinth://mod/sodium`
+#[derive(Clone, PartialEq)]
+pub enum DeepLink {
+    // The uuid or name of the instance to launch
+    Launch(SharedString),
+    Instance(SharedString, InstanceSubpageType),
+    ModrinthSearch(Option<ModrinthProjectType>),
+    // The id or slug of the project
+    ModrinthProject(Ustr),
+    // The full share code, which is itself a link
+    ImportShareCode(SharedString),
+}
+
+impl DeepLink {
+    pub fn parse(link: &str) -> Option<Self> {
+        let url = url::Url::parse(link).ok()?;
+        let host = decode(url.host_str()?)?;
+        let segments: Vec<String> = url.path_segments()
+            .map(|segments| segments.filter(|segment| !segment.is_empty()).map(decode).collect::<Option<_>>())
+            .unwrap_or(Some(Vec::new()))?;
+
+        match url.scheme() {
+            "modrinth" => Self::parse_modrinth(&host, &segments),
+            "pandora" => match (host.as_str(), segments.as_slice()) {
+                ("launch", [instance]) => Some(Self::Launch(instance.clone().into())),
+                ("instance", [instance]) => Some(Self::Instance(instance.clone().into(), InstanceSubpageType::Quickplay)),
+                ("instance", [instance, subpage]) => {
+                    let subpage = InstanceSubpageType::deserialize(StrDeserializer::<Error>::new(subpage)).ok()?;
+                    Some(Self::Instance(instance.clone().into(), subpage))
+                },
+                ("import", [_]) => Some(Self::ImportShareCode(link.trim().into())),
+                ("modrinth", []) => Some(Self::ModrinthSearch(None)),
+                ("modrinth", [project_type, rest @ ..]) => Self::parse_modrinth(project_type, rest),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    // Modrinth links are `<project type>/<slug>`, the project page works out the type by itself so any type is accepted
+    fn parse_modrinth(project_type: &str, segments: &[String]) -> Option<Self> {
+        match segments {
+            [] => {
+                let project_type = ModrinthProjectType::deserialize(StrDeserializer::<Error>::new(project_type)).ok()?;
+                (project_type != ModrinthProjectType::Other).then_some(Self::ModrinthSearch(Some(project_type)))
+            },
+            [project] => Some(Self::ModrinthProject(project.as_str().into())),
+            _ => None,
+        }
+    }
+}
+
+fn decode(segment: &str) -> Option<String> {
+    percent_encoding::percent_decode_str(segment).decode_utf8().ok().map(|decoded| decoded.into_owned())
+}
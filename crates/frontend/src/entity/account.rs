@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use bridge::account::Account;
+use bridge::account::{Account, ModrinthAccount};
 use gpui::{App, Entity};
 use uuid::Uuid;
 
@@ -9,6 +9,7 @@ pub struct AccountEntries {
     pub accounts: Arc<[Account]>,
     pub selected_account_uuid: Option<Uuid>,
     pub selected_account: Option<Account>,
+    pub modrinth_account: Option<ModrinthAccount>,
 }
 
 impl AccountEntries {
@@ -16,6 +17,7 @@ impl AccountEntries {
         entity: &Entity<Self>,
         accounts: Arc<[Account]>,
         selected_account: Option<Uuid>,
+        modrinth_account: Option<ModrinthAccount>,
         cx: &mut App,
     ) {
         entity.update(cx, |entries, cx| {
@@ -23,6 +25,7 @@ impl AccountEntries {
                 selected_account.and_then(|uuid| accounts.iter().find(|acc| acc.uuid == uuid).cloned());
             entries.accounts = accounts;
             entries.selected_account_uuid = selected_account;
+            entries.modrinth_account = modrinth_account;
             cx.notify();
         });
     }
@@ -0,0 +1,19 @@
+use std::sync::Arc;
+
+use bridge::job::JobSummary;
+use gpui::{App, Entity};
+
+// Jobs running or queued in the backend, the list is replaced whenever a job is queued, started or finished
+#[derive(Default)]
+pub struct BackgroundJobs {
+    pub jobs: Arc<[JobSummary]>,
+}
+
+impl BackgroundJobs {
+    pub fn set(entity: &Entity<Self>, jobs: Arc<[JobSummary]>, cx: &mut App) {
+        entity.update(cx, |background_jobs, cx| {
+            background_jobs.jobs = jobs;
+            cx.notify();
+        });
+    }
+}
@@ -36,6 +36,7 @@ impl InstanceEntries {
                 dot_minecraft_folder,
                 configuration,
                 status: InstanceStatus::NotRunning,
+                tunnel_address: None,
                 worlds_state,
                 worlds: cx.new(|_| [].into()),
                 servers_state,
@@ -52,13 +53,20 @@ impl InstanceEntries {
         });
     }
 
-    pub fn find_id_by_name(entity: &Entity<Self>, name: &SharedString, cx: &App) -> Option<InstanceID> {
-        for (id, entry) in &entity.read(cx).entries {
-            if &entry.read(cx).name == name {
-                return Some(*id);
-            }
-        }
-        None
+    // Accepts the uuid of the instance, or its name for references saved before instances had a uuid
+    pub fn find_id_by_reference(entity: &Entity<Self>, reference: &str, cx: &App) -> Option<InstanceID> {
+        entity.read(cx).entries.iter()
+            .filter_map(|(id, entry)| {
+                let entry = entry.read(cx);
+                Some((entry.configuration.reference_match(&entry.name, reference)?, *id))
+            })
+            .min_by_key(|(rank, _)| *rank)
+            .map(|(_, id)| id)
+    }
+
+    pub fn find_reference_by_id(entity: &Entity<Self>, id: InstanceID, cx: &App) -> Option<SharedString> {
+        let entry = entity.read(cx).entries.get(&id)?.read(cx);
+        Some(entry.configuration.reference(&entry.name).into())
     }
 
     pub fn find_name_by_id(entity: &Entity<Self>, id: InstanceID, cx: &App) -> Option<SharedString> {
@@ -112,6 +120,17 @@ impl InstanceEntries {
         });
     }
 
+    pub fn set_tunnel_address(entity: &Entity<Self>, id: InstanceID, address: Option<SharedString>, cx: &mut App) {
+        entity.update(cx, |entries, cx| {
+            if let Some(instance) = entries.entries.get_mut(&id) {
+                instance.update(cx, |instance, cx| {
+                    instance.tunnel_address = address;
+                    cx.notify();
+                });
+            }
+        });
+    }
+
     pub fn set_worlds(
         entity: &Entity<Self>,
         id: InstanceID,
@@ -196,6 +215,7 @@ pub struct InstanceEntry {
     pub dot_minecraft_folder: Arc<Path>,
     pub configuration: InstanceConfiguration,
     pub status: InstanceStatus,
+    pub tunnel_address: Option<SharedString>,
     pub worlds_state: Arc<AtomicBridgeDataLoadState>,
     pub worlds: Entity<Arc<[InstanceWorldSummary]>>,
     pub servers_state: Arc<AtomicBridgeDataLoadState>,
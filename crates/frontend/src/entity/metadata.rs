@@ -2,7 +2,7 @@ use std::{collections::HashMap, sync::Arc};
 
 use bridge::{handle::BackendHandle, keep_alive::KeepAliveHandle, message::MessageToBackend, meta::{MetadataRequest, MetadataResult}};
 use gpui::{prelude::*, *};
-use schema::{fabric_loader_manifest::FabricLoaderManifest, forge::{ForgeMavenManifest, NeoforgeMavenManifest}, maven::MavenMetadataXml, modrinth::{ModrinthProjectVersionsResult, ModrinthSearchResult}, version_manifest::MinecraftVersionManifest};
+use schema::{fabric_loader_manifest::FabricLoaderManifest, forge::{ForgeMavenManifest, NeoforgeMavenManifest}, maven::MavenMetadataXml, patch_notes::{JavaPatchNote, JavaPatchNotes}, modrinth::{ModrinthProject, ModrinthProjectDependencies, ModrinthProjectVersionsResult, ModrinthSearchResult}, version_manifest::MinecraftVersionManifest};
 
 #[derive(Debug)]
 pub enum FrontendMetadataState {
@@ -92,6 +92,22 @@ impl FrontendMetadata {
         })
     }
 
+    // Forgets a request that is still loading and tells the backend to stop fetching it
+    pub fn cancel(entity: &Entity<Self>, request: &MetadataRequest, cx: &mut App) {
+        entity.update(cx, |this, cx| {
+            let Some(existing) = this.data.get(request) else {
+                return;
+            };
+            if !matches!(existing.read(cx), FrontendMetadataState::Loading) {
+                return;
+            }
+            this.data.remove(request);
+            this.backend_handle.send(MessageToBackend::CancelMetadataRequest {
+                request: request.clone(),
+            });
+        });
+    }
+
     pub fn set(
         entity: &Entity<Self>,
         request: MetadataRequest,
@@ -100,7 +116,11 @@ impl FrontendMetadata {
         cx: &mut App,
     ) {
         entity.update(cx, |this, cx| {
-            this.data.get(&request).unwrap().update(cx, |value, cx| {
+            // The request may have been cancelled while the result was already on its way
+            let Some(existing) = this.data.get(&request) else {
+                return;
+            };
+            existing.update(cx, |value, cx| {
                 *value = FrontendMetadataState::Loaded { result, keep_alive };
                 cx.notify();
             });
@@ -134,6 +154,10 @@ macro_rules! define_as_metadata_result {
 define_as_metadata_result!(MinecraftVersionManifest);
 define_as_metadata_result!(ModrinthSearchResult);
 define_as_metadata_result!(ModrinthProjectVersionsResult);
+define_as_metadata_result!(ModrinthProject);
+define_as_metadata_result!(ModrinthProjectDependencies);
 define_as_metadata_result!(FabricLoaderManifest);
 define_as_metadata_result!(ForgeMavenManifest);
 define_as_metadata_result!(NeoforgeMavenManifest);
+define_as_metadata_result!(JavaPatchNotes);
+define_as_metadata_result!(JavaPatchNote);
@@ -1,27 +1,36 @@
 use std::{path::Path, sync::Arc};
 
 use bridge::handle::BackendHandle;
-use gpui::Entity;
+use gpui::{Entity, Global};
 use parking_lot::RwLock;
 
 use crate::entity::{
-    account::AccountEntries, instance::InstanceEntries, metadata::FrontendMetadata
+    account::AccountEntries, background_jobs::BackgroundJobs, instance::InstanceEntries, metadata::FrontendMetadata, server_status::ServerStatuses
 };
 
 pub mod account;
+pub mod background_jobs;
 pub mod instance;
 pub mod metadata;
+pub mod server_status;
 
 #[derive(Clone)]
 pub struct DataEntities {
     pub instances: Entity<InstanceEntries>,
     pub metadata: Entity<FrontendMetadata>,
     pub accounts: Entity<AccountEntries>,
+    pub server_statuses: Entity<ServerStatuses>,
+    pub background_jobs: Entity<BackgroundJobs>,
     pub backend_handle: BackendHandle,
     pub theme_folder: Arc<Path>,
+    pub logs_folder: Arc<Path>,
+    pub data_folder: Arc<Path>,
     pub panic_messages: Arc<PanicMessages>,
 }
 
+// Available to every window, not only the main one
+impl Global for DataEntities {}
+
 pub struct PanicMessages {
     pub panic_message: Arc<RwLock<Option<String>>>,
     pub deadlock_message: Arc<RwLock<Option<String>>>,
@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use bridge::{handle::BackendHandle, instance::ServerStatus, message::MessageToBackend};
+use gpui::{App, Entity};
+use rustc_hash::FxHashMap;
+
+#[derive(Clone)]
+pub enum ServerStatusEntry {
+    Pinging,
+    Online(ServerStatus),
+    Offline(Arc<str>),
+}
+
+#[derive(Default)]
+pub struct ServerStatuses {
+    pub statuses: FxHashMap<Arc<str>, ServerStatusEntry>,
+}
+
+impl ServerStatuses {
+    pub fn get(&self, address: &str) -> Option<&ServerStatusEntry> {
+        self.statuses.get(address)
+    }
+
+    pub fn request(entity: &Entity<Self>, address: Arc<str>, force: bool, backend_handle: &BackendHandle, cx: &mut App) {
+        entity.update(cx, |statuses, cx| {
+            match statuses.statuses.get(&address) {
+                Some(ServerStatusEntry::Pinging) => return,
+                Some(_) if !force => return,
+                _ => {},
+            }
+
+            statuses.statuses.insert(address.clone(), ServerStatusEntry::Pinging);
+            backend_handle.send(MessageToBackend::PingServer { address, force });
+            cx.notify();
+        });
+    }
+
+    pub fn set(entity: &Entity<Self>, address: Arc<str>, status: Result<ServerStatus, Arc<str>>, cx: &mut App) {
+        entity.update(cx, |statuses, cx| {
+            let entry = match status {
+                Ok(status) => ServerStatusEntry::Online(status),
+                Err(error) => ServerStatusEntry::Offline(error),
+            };
+            statuses.statuses.insert(address, entry);
+            cx.notify();
+        });
+    }
+}
@@ -8,7 +8,7 @@ use gpui_component::{
 use lru::LruCache;
 use rustc_hash::FxBuildHasher;
 
-use bridge::{game_output::GameOutputLogLevel, keep_alive::KeepAlive};
+use bridge::{game_output::GameOutputLogLevel, handle::BackendHandle, instance::InstanceID, keep_alive::KeepAlive, message::MessageToBackend};
 
 use crate::CloseWindow;
 
@@ -831,9 +831,17 @@ pub struct GameOutputRoot {
     search_state: Entity<InputState>,
     _search_task: Task<()>,
     _search_input_subscription: Subscription,
+    console: Option<GameOutputConsole>,
     focus_handle: FocusHandle,
 }
 
+struct GameOutputConsole {
+    instance_id: InstanceID,
+    backend_handle: BackendHandle,
+    input_state: Entity<InputState>,
+    _input_subscription: Subscription,
+}
+
 #[derive(Clone)]
 pub struct ScrollHandler {
     state: Rc<RefCell<GameOutputScrollState>>,
@@ -939,6 +947,7 @@ impl GameOutputRoot {
     pub fn new(
         keep_alive: KeepAlive,
         game_output: Entity<GameOutput>,
+        console: Option<(InstanceID, BackendHandle)>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
@@ -948,6 +957,17 @@ impl GameOutputRoot {
 
         let _search_input_subscription = cx.subscribe_in(&search_state, window, Self::on_search_input_event);
 
+        let console = console.map(|(instance_id, backend_handle)| {
+            let input_state = cx.new(|cx| InputState::new(window, cx).placeholder("Enter a command"));
+            let _input_subscription = cx.subscribe_in(&input_state, window, Self::on_console_input_event);
+            GameOutputConsole {
+                instance_id,
+                backend_handle,
+                input_state,
+                _input_subscription,
+            }
+        });
+
         let focus_handle = cx.focus_handle();
         focus_handle.focus(window, cx);
 
@@ -958,10 +978,44 @@ impl GameOutputRoot {
             search_state,
             _search_task: Task::ready(()),
             _search_input_subscription,
+            console,
             focus_handle,
         }
     }
 
+    fn on_console_input_event(
+        &mut self,
+        state: &Entity<InputState>,
+        event: &InputEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let InputEvent::PressEnter { secondary: false } = event else {
+            return;
+        };
+        let Some(console) = &self.console else {
+            return;
+        };
+
+        let input = state.read(cx).value();
+        let input = input.trim();
+        if input.is_empty() {
+            return;
+        }
+
+        console.backend_handle.send(MessageToBackend::SendInstanceInput {
+            id: console.instance_id,
+            input: input.into(),
+        });
+
+        state.update(cx, |input, cx| input.set_value("", window, cx));
+
+        let mut scroll_state = self.scroll_handler.state.borrow_mut();
+        scroll_state.scrolling = GameOutputScrolling::Bottom;
+        drop(scroll_state);
+        cx.notify();
+    }
+
     fn on_search_input_event(
         &mut self,
         state: &Entity<InputState>,
@@ -1098,6 +1152,9 @@ impl Render for GameOutputRoot {
                             .child(Scrollbar::vertical(&self.scroll_handler)),
                     ),
             )
+            .when_some(self.console.as_ref(), |this, console| {
+                this.child(Input::new(&console.input_state).prefix(Icon::new(IconName::ChevronRight).small()))
+            })
             .on_scroll_wheel(cx.listener(|root, event: &ScrollWheelEvent, _, cx| {
                 let state = root.scroll_handler.state.borrow();
                 let delta = event.delta.pixel_delta(state.line_height).y;
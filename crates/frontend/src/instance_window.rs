@@ -0,0 +1,125 @@
+use bridge::instance::InstanceID;
+use gpui::{prelude::*, *};
+use gpui_component::{v_flex, Root};
+
+use crate::{
+    entity::{instance::{InstanceEntry, InstanceRemovedEvent}, DataEntities},
+    pages::instance::instance_page::{InstanceSubpage, InstanceSubpageType},
+    root, CloseWindow, MAIN_FONT,
+};
+
+// Shows a single instance subpage in its own OS window, e.g. to keep the logs visible on another monitor
+pub struct InstanceWindowRoot {
+    instance_id: InstanceID,
+    title: SharedString,
+    subpage: InstanceSubpage,
+    focus_handle: FocusHandle,
+    _instance_removed_subscription: Subscription,
+}
+
+impl InstanceWindowRoot {
+    fn new(
+        instance: Entity<InstanceEntry>,
+        subpage_type: InstanceSubpageType,
+        title: SharedString,
+        data: &DataEntities,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let instance_id = instance.read(cx).id;
+        let subpage = subpage_type.create(&instance, data, data.backend_handle.clone(), window, cx);
+
+        let _instance_removed_subscription = cx.subscribe_in::<_, InstanceRemovedEvent>(&data.instances, window, move |_, _, event, window, _| {
+            if event.id == instance_id {
+                window.remove_window();
+            }
+        });
+
+        let focus_handle = cx.focus_handle();
+        focus_handle.focus(window, cx);
+
+        Self {
+            instance_id,
+            title,
+            subpage,
+            focus_handle,
+            _instance_removed_subscription,
+        }
+    }
+}
+
+// Remembers the open instance windows so they can be reopened after relaunching into an update
+#[derive(Default)]
+struct OpenInstanceWindows(Vec<WeakEntity<InstanceWindowRoot>>);
+
+impl Global for OpenInstanceWindows {}
+
+pub fn open_windows(cx: &App) -> Vec<(InstanceID, InstanceSubpageType)> {
+    let Some(open_windows) = cx.try_global::<OpenInstanceWindows>() else {
+        return Vec::new();
+    };
+    open_windows.0.iter()
+        .filter_map(|window| window.upgrade())
+        .map(|window| {
+            let window = window.read(cx);
+            (window.instance_id, window.subpage.page_type())
+        })
+        .collect()
+}
+
+impl Render for InstanceWindowRoot {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let sheet_layer = Root::render_sheet_layer(window, cx);
+        let dialog_layer = Root::render_dialog_layer(window, cx);
+        let notification_layer = Root::render_notification_layer(window, cx);
+
+        v_flex()
+            .size_full()
+            .font_family(MAIN_FONT)
+            .text_size(crate::theme::base_text_size(cx))
+            .when(root::has_csd_titlebar(window), |this| {
+                this.child(gpui_component::TitleBar::new().child(self.title.clone()))
+            })
+            .child(self.subpage.clone().into_any_element())
+            .children(sheet_layer)
+            .children(dialog_layer)
+            .children(notification_layer)
+            .track_focus(&self.focus_handle)
+            .on_action(|_: &CloseWindow, window, _| {
+                window.remove_window();
+            })
+    }
+}
+
+pub fn open(instance_id: InstanceID, subpage_type: InstanceSubpageType, data: &DataEntities, cx: &mut App) {
+    let Some(instance) = data.instances.read(cx).entries.get(&instance_id).cloned() else {
+        return;
+    };
+    let title = SharedString::new(format!("{} - {}", instance.read(cx).name, subpage_type.name()));
+
+    let options = WindowOptions {
+        app_id: Some("PandoraLauncher".into()),
+        window_min_size: Some(size(px(360.0), px(240.0))),
+        window_bounds: Some(WindowBounds::centered(size(px(960.0), px(640.0)), cx)),
+        titlebar: Some(TitlebarOptions {
+            title: Some(title.clone()),
+            ..Default::default()
+        }),
+        window_decorations: Some(WindowDecorations::Server),
+        ..Default::default()
+    };
+
+    let data = data.clone();
+    let result = cx.open_window(options, move |window, cx| {
+        let instance_window = cx.new(|cx| InstanceWindowRoot::new(instance, subpage_type, title, &data, window, cx));
+        let open_windows = &mut cx.default_global::<OpenInstanceWindows>().0;
+        open_windows.retain(|window| window.upgrade().is_some());
+        open_windows.push(instance_window.downgrade());
+        window.activate_window();
+        cx.new(|cx| Root::new(instance_window, window, cx))
+    });
+
+    if let Err(error) = result {
+        log::error!("Unable to open instance window: {error}");
+    }
+}
@@ -1,4 +1,4 @@
-use std::{io::Write, path::Path, sync::Arc, time::Duration};
+use std::{collections::BTreeMap, io::Write, path::Path, sync::Arc, time::Duration};
 
 use gpui::{App, SharedString, Task};
 use rand::RngCore;
@@ -20,6 +20,14 @@ pub struct InterfaceConfig {
     #[serde(default, deserialize_with = "schema::try_deserialize")]
     pub active_theme: SharedString,
     #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub active_light_theme: SharedString,
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub theme_mode: ThemeModePreference,
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub ui_scale: f32,
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub font_size: f32,
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
     pub main_window_bounds: WindowBounds,
     #[serde(default, deserialize_with = "schema::try_deserialize")]
     pub sidebar_width: f32,
@@ -35,12 +43,31 @@ pub struct InterfaceConfig {
     pub modrinth_install_normally: bool,
     #[serde(default, deserialize_with = "schema::try_deserialize")]
     pub modrinth_page_project_type: ModrinthProjectType,
+    // Replaced by launch_behaviour, only read to migrate old configs
+    #[serde(default, deserialize_with = "schema::try_deserialize", skip_serializing)]
+    hide_main_window_on_launch: bool,
     #[serde(default, deserialize_with = "schema::try_deserialize")]
-    pub hide_main_window_on_launch: bool,
+    pub launch_behaviour: LaunchBehaviour,
+    // The uuid of the instance, or its name in configs from before instances had a uuid
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub auto_launch_instance: Option<SharedString>,
     #[serde(default, deserialize_with = "schema::try_deserialize")]
     pub show_snapshots_in_create_instance: bool,
     #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub show_old_versions_in_create_instance: bool,
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub new_version_notifications: NewVersionNotifications,
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
     pub instances_view_mode: InstancesViewMode,
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub backup_configs_on_save: bool,
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub minimize_to_tray: bool,
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub global_hotkey: SharedString,
+    // Overrides for the default keyboard shortcuts, an empty value unbinds the shortcut
+    #[serde(default, deserialize_with = "schema::try_deserialize")]
+    pub keymap: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
@@ -76,6 +103,73 @@ pub enum InstancesViewMode {
     List,
 }
 
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, strum::EnumIter)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeModePreference {
+    #[default]
+    Dark,
+    Light,
+    System,
+}
+
+impl ThemeModePreference {
+    pub fn name(self) -> SharedString {
+        match self {
+            ThemeModePreference::Dark => "Dark".into(),
+            ThemeModePreference::Light => "Light".into(),
+            ThemeModePreference::System => "Follow system".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, strum::EnumIter)]
+#[serde(rename_all = "snake_case")]
+pub enum NewVersionNotifications {
+    #[default]
+    Releases,
+    ReleasesAndSnapshots,
+    Off,
+}
+
+impl NewVersionNotifications {
+    pub fn name(self) -> SharedString {
+        match self {
+            NewVersionNotifications::Releases => "Releases".into(),
+            NewVersionNotifications::ReleasesAndSnapshots => "Releases and snapshots".into(),
+            NewVersionNotifications::Off => "Off".into(),
+        }
+    }
+
+    pub fn should_notify(self, snapshot: bool) -> bool {
+        match self {
+            NewVersionNotifications::Releases => !snapshot,
+            NewVersionNotifications::ReleasesAndSnapshots => true,
+            NewVersionNotifications::Off => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, strum::EnumIter)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchBehaviour {
+    #[default]
+    KeepOpen,
+    Minimize,
+    Hide,
+    Close,
+}
+
+impl LaunchBehaviour {
+    pub fn name(self) -> SharedString {
+        match self {
+            LaunchBehaviour::KeepOpen => "Keep the launcher open".into(),
+            LaunchBehaviour::Minimize => "Minimize the launcher".into(),
+            LaunchBehaviour::Hide => "Hide the launcher until the game exits".into(),
+            LaunchBehaviour::Close => "Close the launcher".into(),
+        }
+    }
+}
+
 impl InstancesViewMode {
     pub fn name(self) -> SharedString {
         match self {
@@ -85,10 +179,37 @@ impl InstancesViewMode {
     }
 }
 
+pub const UI_SCALES: &[f32] = &[0.75, 0.85, 1.0, 1.1, 1.25, 1.5, 1.75, 2.0];
+pub const FONT_SIZES: &[f32] = &[12.0, 13.0, 14.0, 15.0, 16.0, 18.0, 20.0, 22.0, 24.0];
+pub const DEFAULT_FONT_SIZE: f32 = 16.0;
+
 impl InterfaceConfig {
+    // A value of zero means the setting was never changed
+    pub fn ui_scale(&self) -> f32 {
+        if self.ui_scale <= 0.0 {
+            1.0
+        } else {
+            self.ui_scale.clamp(0.5, 3.0)
+        }
+    }
+
+    pub fn font_size(&self) -> f32 {
+        if self.font_size <= 0.0 {
+            DEFAULT_FONT_SIZE
+        } else {
+            self.font_size.clamp(8.0, 48.0)
+        }
+    }
+
     pub fn init(cx: &mut App, path: Arc<Path>) {
+        let mut config: InterfaceConfig = try_read_json(&path);
+        if config.hide_main_window_on_launch {
+            config.hide_main_window_on_launch = false;
+            config.launch_behaviour = LaunchBehaviour::Hide;
+        }
+
         cx.set_global(InterfaceConfigHolder {
-            config: try_read_json(&path),
+            config,
             write_task: None,
             path,
         });
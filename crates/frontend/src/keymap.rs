@@ -0,0 +1,96 @@
+use gpui::{actions, App, KeyBinding, Keystroke};
+
+use crate::{interface_config::InterfaceConfig, CloseWindow, Quit};
+
+actions!([OpenSettings, FocusSearch, SelectLeft, SelectRight, SelectUp, SelectDown, LaunchSelected]);
+
+pub const INSTANCE_GRID_CONTEXT: &str = "InstanceGrid";
+
+pub struct Shortcut {
+    pub id: &'static str,
+    pub description: &'static str,
+    pub default: &'static str,
+    bind: fn(&str) -> KeyBinding,
+}
+
+pub static SHORTCUTS: &[Shortcut] = &[
+    Shortcut {
+        id: "quit",
+        description: "Quit",
+        default: "secondary-q",
+        bind: |keystrokes| KeyBinding::new(keystrokes, Quit, None),
+    },
+    Shortcut {
+        id: "close_window",
+        description: "Close window",
+        default: "secondary-w",
+        bind: |keystrokes| KeyBinding::new(keystrokes, CloseWindow, None),
+    },
+    Shortcut {
+        id: "open_settings",
+        description: "Open settings",
+        default: "secondary-,",
+        bind: |keystrokes| KeyBinding::new(keystrokes, OpenSettings, None),
+    },
+    Shortcut {
+        id: "focus_search",
+        description: "Search",
+        default: "secondary-f",
+        bind: |keystrokes| KeyBinding::new(keystrokes, FocusSearch, None),
+    },
+    Shortcut {
+        id: "select_left",
+        description: "Select previous instance",
+        default: "left",
+        bind: |keystrokes| KeyBinding::new(keystrokes, SelectLeft, Some(INSTANCE_GRID_CONTEXT)),
+    },
+    Shortcut {
+        id: "select_right",
+        description: "Select next instance",
+        default: "right",
+        bind: |keystrokes| KeyBinding::new(keystrokes, SelectRight, Some(INSTANCE_GRID_CONTEXT)),
+    },
+    Shortcut {
+        id: "select_up",
+        description: "Select instance above",
+        default: "up",
+        bind: |keystrokes| KeyBinding::new(keystrokes, SelectUp, Some(INSTANCE_GRID_CONTEXT)),
+    },
+    Shortcut {
+        id: "select_down",
+        description: "Select instance below",
+        default: "down",
+        bind: |keystrokes| KeyBinding::new(keystrokes, SelectDown, Some(INSTANCE_GRID_CONTEXT)),
+    },
+    Shortcut {
+        id: "launch_selected",
+        description: "Launch selected instance",
+        default: "enter",
+        bind: |keystrokes| KeyBinding::new(keystrokes, LaunchSelected, Some(INSTANCE_GRID_CONTEXT)),
+    },
+];
+
+// gpui has no way to remove individual bindings, so changes to the keymap only apply after a restart
+pub fn bind_keys(cx: &mut App) {
+    let keymap = &InterfaceConfig::get(cx).keymap;
+
+    let bindings = SHORTCUTS.iter().filter_map(|shortcut| {
+        let keystrokes = match keymap.get(shortcut.id) {
+            Some(keystrokes) if is_valid(keystrokes) => keystrokes.trim(),
+            Some(keystrokes) if keystrokes.trim().is_empty() => return None,
+            Some(keystrokes) => {
+                log::error!("Invalid keystrokes {keystrokes:?} for shortcut {}, using the default", shortcut.id);
+                shortcut.default
+            },
+            None => shortcut.default,
+        };
+        Some((shortcut.bind)(keystrokes))
+    }).collect::<Vec<_>>();
+
+    cx.bind_keys(bindings);
+}
+
+pub fn is_valid(keystrokes: &str) -> bool {
+    let mut parts = keystrokes.split_whitespace().peekable();
+    parts.peek().is_some() && parts.all(|part| Keystroke::parse(part).is_ok())
+}
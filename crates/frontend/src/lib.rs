@@ -1,7 +1,7 @@
 #![deny(unused_must_use)]
 
 use std::{
-    path::{Path, PathBuf}, sync::{Arc, atomic::AtomicBool}
+    cell::RefCell, path::{Path, PathBuf}, rc::Rc, sync::{Arc, atomic::AtomicBool}
 };
 
 use bridge::
@@ -9,26 +9,32 @@ use bridge::
 ;
 use gpui::*;
 use gpui_component::{
-    notification::{Notification, NotificationType}, Root, StyledExt, WindowExt
+    input::{InputState, NumberInputEvent, StepAction}, notification::{Notification, NotificationType}, Root, StyledExt, WindowExt
 };
 use indexmap::IndexMap;
 use parking_lot::RwLock;
 
 use crate::{
     entity::{
-        DataEntities, PanicMessages, account::AccountEntries, instance::InstanceEntries, metadata::FrontendMetadata
-    }, interface_config::InterfaceConfig, processor::Processor, root::{LauncherRoot, LauncherRootGlobal}
+        DataEntities, PanicMessages, account::AccountEntries, background_jobs::BackgroundJobs, instance::InstanceEntries, metadata::FrontendMetadata, server_status::ServerStatuses
+    }, interface_config::InterfaceConfig, processor::Processor, relaunch::RelaunchState, root::{LauncherRoot, LauncherRootGlobal}
 };
 
 pub mod component;
+pub mod deep_link;
 pub mod entity;
 pub mod game_output;
+pub mod instance_window;
 pub mod modals;
 pub mod pages;
 pub mod interface_config;
+pub mod keymap;
 pub mod png_render_cache;
 pub mod processor;
+pub mod relaunch;
 pub mod root;
+pub mod theme;
+pub mod tray;
 pub mod ui;
 
 rust_i18n::i18n!("locales");
@@ -58,6 +64,7 @@ pub(crate) use ts_short;
 #[include = "icons/**/*.svg"]
 #[include = "images/**/*.png"]
 #[include = "fonts/**/*.ttf"]
+#[include = "themes/**/*.json"]
 pub struct Assets;
 
 impl AssetSource for Assets {
@@ -85,10 +92,14 @@ actions!([Quit, CloseWindow]);
 
 pub fn start(
     launcher_dir: PathBuf,
+    cache_dir: PathBuf,
     panic_message: Arc<RwLock<Option<String>>>,
     deadlock_message: Arc<RwLock<Option<String>>>,
     backend_handle: BackendHandle,
     mut recv: FrontendReceiver,
+    // Links the launcher was opened with and ones handed over by later launcher processes
+    open_link_send: tokio::sync::mpsc::UnboundedSender<String>,
+    mut open_link_recv: tokio::sync::mpsc::UnboundedReceiver<String>,
 ) {
     let user_agent = if let Some(version) = option_env!("PANDORA_RELEASE_VERSION") {
         format!("PandoraLauncher/{version} (https://github.com/Moulberry/PandoraLauncher)")
@@ -98,7 +109,16 @@ pub fn start(
 
     let http_client = Arc::new(reqwest_client::ReqwestClient::user_agent(&user_agent).unwrap());
 
-    Application::new().with_http_client(http_client).with_assets(Assets).run(move |cx: &mut App| {
+    let application = Application::new().with_http_client(http_client).with_assets(Assets);
+
+    // On macOS links are sent to the running launcher instead of being passed as an argument
+    application.on_open_urls(move |urls| {
+        for url in urls {
+            _ = open_link_send.send(url);
+        }
+    });
+
+    application.run(move |cx: &mut App| {
         let _ = cx.text_system().add_fonts(vec![
             Assets.load("fonts/inter/Inter-Regular.ttf").unwrap().unwrap(),
             Assets.load("fonts/roboto-mono/RobotoMono-Regular.ttf").unwrap().unwrap(),
@@ -106,48 +126,40 @@ pub fn start(
 
         gpui_component::init(cx);
         InterfaceConfig::init(cx, launcher_dir.join("interface.json").into());
-
-        gpui_component::Theme::change(gpui_component::ThemeMode::Dark, None, cx);
+        if let Some(relaunch_state) = RelaunchState::take(&launcher_dir) {
+            cx.set_global(relaunch_state);
+        }
 
         let theme_folder = launcher_dir.join("themes");
+        theme::init(theme_folder.clone(), cache_dir.join("temp").join("themes"), cx);
 
-        _ = gpui_component::ThemeRegistry::watch_dir(theme_folder.clone(), cx, move |cx| {
-            let theme_name = InterfaceConfig::get(cx).active_theme.clone();
-            if theme_name.is_empty() {
-                return;
+        cx.on_app_quit({
+            let backend_handle = backend_handle.clone();
+            move |cx| {
+                InterfaceConfig::force_save(cx);
+                // Give the backend a moment to stop its tasks and remove partially downloaded files
+                backend_handle.shutdown();
+                cx.background_executor().timer(std::time::Duration::from_millis(50))
             }
-
-            let Some(theme) = gpui_component::ThemeRegistry::global(cx).themes().get(&SharedString::new(theme_name.trim_ascii())).cloned() else {
-                return;
-            };
-
-            gpui_component::Theme::global_mut(cx).apply_config(&theme);
-        });
-
-        let theme = gpui_component::Theme::global_mut(cx);
-        theme.font_family = SharedString::new_static(MAIN_FONT);
-        theme.scrollbar_show = gpui_component::scroll::ScrollbarShow::Always;
-
-        cx.on_app_quit(|cx| {
-            InterfaceConfig::force_save(cx);
-            async {}
         }).detach();
 
         let main_window_hidden = Arc::new(AtomicBool::new(false));
+        let tray_active = Arc::new(AtomicBool::new(false));
 
         cx.on_window_closed({
             let main_window_hidden = main_window_hidden.clone();
+            let tray_active = tray_active.clone();
             move |cx| {
-                if cx.windows().is_empty() && !main_window_hidden.load(std::sync::atomic::Ordering::SeqCst) {
+                if cx.windows().is_empty()
+                    && !main_window_hidden.load(std::sync::atomic::Ordering::SeqCst)
+                    && !tray_active.load(std::sync::atomic::Ordering::SeqCst)
+                {
                     cx.quit();
                 }
             }
         }).detach();
 
-        cx.bind_keys([
-            KeyBinding::new("secondary-q", Quit, None),
-            KeyBinding::new("secondary-w", CloseWindow, None),
-        ]);
+        keymap::bind_keys(cx);
 
         cx.on_action(|_: &Quit, cx| {
             cx.quit();
@@ -158,31 +170,65 @@ pub fn start(
         });
         let metadata = cx.new(|_| FrontendMetadata::new(backend_handle.clone()));
         let accounts = cx.new(|_| AccountEntries::default());
+        let server_statuses = cx.new(|_| ServerStatuses::default());
+        let background_jobs = cx.new(|_| BackgroundJobs::default());
         let data = DataEntities {
             instances,
             metadata,
             backend_handle,
             accounts,
+            server_statuses,
+            background_jobs,
             theme_folder: theme_folder.into(),
+            logs_folder: launcher_dir.join("logs").into(),
+            data_folder: launcher_dir.as_path().into(),
             panic_messages: Arc::new(PanicMessages {
                 panic_message,
                 deadlock_message,
             })
         };
 
-        let mut processor = Processor::new(data.clone(), main_window_hidden);
+        cx.set_global(data.clone());
+
+        let processor = Rc::new(RefCell::new(Processor::new(data.clone(), main_window_hidden)));
 
         while let Some(message) = recv.try_recv() {
-            processor.process(message, cx);
+            processor.borrow_mut().process(message, cx);
         }
 
         let main_window = open_main_window(&data, cx);
-        processor.set_main_window_handle(main_window, cx);
+        processor.borrow_mut().set_main_window_handle(main_window, cx);
+        RelaunchState::reopen_instance_windows(&data, cx);
+
+        let interface_config = InterfaceConfig::get(cx);
+        let tray_enabled = interface_config.minimize_to_tray;
+        let global_hotkey = interface_config.global_hotkey.clone();
+        if let Some(auto_launch_instance) = interface_config.auto_launch_instance.clone() {
+            processor.borrow_mut().set_auto_launch(auto_launch_instance, cx);
+        }
+        let has_tray = tray::init(data.instances.clone(), tray_enabled, &global_hotkey, {
+            let processor = processor.clone();
+            move |action, cx| {
+                processor.borrow_mut().handle_tray_action(action, cx);
+            }
+        }, cx);
+        tray_active.store(has_tray, std::sync::atomic::Ordering::SeqCst);
+
+        cx.spawn({
+            let processor = processor.clone();
+            async move |cx| {
+                while let Some(link) = open_link_recv.recv().await {
+                    _ = cx.update(|cx| {
+                        processor.borrow_mut().open_link(&link, cx);
+                    });
+                }
+            }
+        }).detach();
 
         cx.spawn(async move |cx| {
             while let Some(message) = recv.recv().await {
                 _ = cx.update(|cx| {
-                    processor.process(message, cx);
+                    processor.borrow_mut().process(message, cx);
                 });
             }
         }).detach();
@@ -268,6 +314,7 @@ pub fn open_main_window(data: &DataEntities, cx: &mut App) -> AnyWindowHandle {
 
             cx.set_global(LauncherRootGlobal {
                 root: launcher_root.clone(),
+                window: window.window_handle(),
             });
             cx.new(|cx| Root::new(launcher_root, window, cx))
         },
@@ -301,9 +348,31 @@ pub(crate) fn labelled(label: &'static str, element: impl IntoElement) -> Div {
     gpui_component::v_flex().gap_0p5().child(div().text_sm().font_medium().child(label)).child(element)
 }
 
+// Subscriber for number inputs which don't need any bounds, steps the value by one
+pub(crate) fn step_number_input<T>(
+    _: &mut T,
+    state: &Entity<InputState>,
+    event: &NumberInputEvent,
+    window: &mut Window,
+    cx: &mut Context<T>,
+) {
+    let NumberInputEvent::Step(step_action) = event;
+    let value = state.read(cx).value().parse::<u32>().unwrap_or(0);
+    let value = match step_action {
+        StepAction::Decrement => value.saturating_sub(1),
+        StepAction::Increment => value.saturating_add(1),
+    };
+    state.update(cx, |input, cx| {
+        input.set_value(value.to_string(), window, cx);
+    });
+}
+
 pub(crate) fn open_folder(path: &Path, window: &mut Window, cx: &mut App) {
     if path.is_dir() {
-        if let Err(err) = open::that_detached(path) {
+        // xdg-open inside the container can't reach the file manager of the host, the portal can
+        if bridge::sandbox::Sandbox::detect().is_some() {
+            cx.open_with_system(path);
+        } else if let Err(err) = open::that_detached(path) {
             let notification: Notification = (NotificationType::Error, SharedString::from(format!("Unable to open folder: {err}"))).into();
             window.push_notification(notification.autohide(false), cx);
         }
@@ -0,0 +1,47 @@
+use bridge::job::JobPriority;
+use gpui::{prelude::*, *};
+use gpui_component::{h_flex, sheet::Sheet, v_flex, ActiveTheme, StyledExt, WindowExt};
+
+use crate::entity::background_jobs::BackgroundJobs;
+
+pub fn open_background_jobs(background_jobs: Entity<BackgroundJobs>, window: &mut Window, cx: &mut App) {
+    window.open_sheet_at(gpui_component::Placement::Left, cx, move |sheet: Sheet, window, cx| {
+        let jobs = background_jobs.read(cx).jobs.clone();
+        let theme = cx.theme();
+        let border = theme.border;
+        let muted = theme.muted_foreground;
+
+        let content = if jobs.is_empty() {
+            div().text_color(muted).child("Nothing is running in the background").into_any_element()
+        } else {
+            v_flex().gap_2().children(jobs.iter().map(|job| {
+                let priority = match job.priority {
+                    JobPriority::Interactive => "Requested by you",
+                    JobPriority::LaunchPreparation => "Launch",
+                    JobPriority::Background => "Background",
+                };
+                let state = if job.running { "running" } else { "waiting for other tasks" };
+
+                h_flex()
+                    .p_2()
+                    .rounded_lg()
+                    .border_1()
+                    .border_color(border)
+                    .child(v_flex()
+                        .flex_1()
+                        .overflow_hidden()
+                        .child(div().font_semibold().truncate().child(SharedString::new(job.name.clone())))
+                        .child(div().text_sm().text_color(muted).child(format!("{priority}, {state}"))))
+            })).into_any_element()
+        };
+
+        sheet
+            .title("Background Tasks")
+            .overlay_top(crate::root::sheet_margin_top(window))
+            .child(v_flex()
+                .gap_3()
+                .child(div().text_sm().text_color(muted)
+                    .child("Background work such as update checks and scheduled backups waits while an instance is launching"))
+                .child(content))
+    });
+}
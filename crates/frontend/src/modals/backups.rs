@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use bridge::{handle::BackendHandle, instance::{InstanceBackupSummary, InstanceID}, message::MessageToBackend, modal_action::ModalAction};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, dialog::Dialog, h_flex, scroll::ScrollableElement, spinner::Spinner, v_flex, ActiveTheme, Sizable, StyledExt
+};
+
+struct BackupsModalState {
+    id: InstanceID,
+    backend_handle: BackendHandle,
+    backups: Option<Arc<[InstanceBackupSummary]>>,
+    _task: Task<()>,
+}
+
+impl BackupsModalState {
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self.backend_handle.send(MessageToBackend::GetInstanceBackups {
+            id: self.id,
+            channel: send,
+        });
+
+        self._task = cx.spawn(async move |this, cx| {
+            let backups = recv.await.unwrap_or_default();
+            _ = this.update(cx, |this, cx| {
+                this.backups = Some(backups);
+                cx.notify();
+            });
+        });
+    }
+
+    fn render(&mut self, modal: Dialog, title: SharedString, cx: &mut Context<Self>) -> Dialog {
+        let theme = cx.theme();
+        let border = theme.border;
+        let muted = theme.muted_foreground;
+
+        let list = match &self.backups {
+            None => h_flex().justify_center().child(Spinner::new().large()).into_any_element(),
+            Some(backups) if backups.is_empty() => div().text_color(muted).child("This instance hasn't been backed up yet").into_any_element(),
+            Some(backups) => {
+                let rows = backups.iter().enumerate().map(|(index, backup)| {
+                    let date = chrono::DateTime::from_timestamp(backup.created, 0)
+                        .map(|date_time| date_time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+                        .unwrap_or_else(|| "Unknown date".to_string());
+                    let size = format!("{:.1} MiB", backup.size as f64 / (1024.0 * 1024.0));
+
+                    let path = backup.path.clone();
+                    h_flex()
+                        .p_2()
+                        .gap_3()
+                        .rounded_lg()
+                        .border_1()
+                        .border_color(border)
+                        .child(v_flex()
+                            .flex_1()
+                            .child(div().font_semibold().child(date))
+                            .child(div().text_sm().text_color(muted).child(size)))
+                        .child(Button::new(("restore", index)).warning().small().label("Restore").on_click(
+                            cx.listener(move |this, _, window, cx| {
+                                window.close_all_dialogs(cx);
+
+                                let modal_action = ModalAction::default();
+                                this.backend_handle.send(MessageToBackend::RestoreInstanceBackup {
+                                    id: this.id,
+                                    path: path.clone(),
+                                    modal_action: modal_action.clone(),
+                                });
+                                crate::modals::generic::show_modal(window, cx, "Restoring backup".into(),
+                                    "Error restoring backup".into(), modal_action);
+                            }),
+                        ))
+                }).collect::<Vec<_>>();
+                v_flex().gap_2().max_h(px(400.0)).overflow_y_scrollbar().children(rows).into_any_element()
+            },
+        };
+
+        let content = v_flex()
+            .gap_3()
+            .child(div().text_sm().text_color(muted)
+                .child("Restoring a backup replaces the saves, configs and options of the instance with the ones in the backup"))
+            .child(list);
+
+        modal.title(title).child(content)
+    }
+}
+
+pub fn open_backups(id: InstanceID, name: SharedString, backend_handle: BackendHandle, window: &mut Window, cx: &mut App) {
+    let title = SharedString::new(format!("Backups of {name}"));
+    let state = cx.new(|cx| {
+        let mut state = BackupsModalState {
+            id,
+            backend_handle,
+            backups: None,
+            _task: Task::ready(()),
+        };
+        state.reload(cx);
+        state
+    });
+
+    window.open_dialog(cx, move |modal, _, cx| {
+        cx.update_entity(&state, |state, cx| {
+            state.render(modal, title.clone(), cx)
+        })
+    });
+}
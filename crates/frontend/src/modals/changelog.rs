@@ -0,0 +1,123 @@
+use bridge::meta::MetadataRequest;
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::Button, dialog::Dialog, h_flex, scroll::ScrollableElement, spinner::Spinner, text::TextView, v_flex, ActiveTheme, Sizable, WindowExt
+};
+use schema::patch_notes::{JavaPatchNote, JavaPatchNotes};
+
+use crate::{component::error_alert::ErrorAlert, entity::metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult, FrontendMetadataState}};
+
+struct ChangelogModalState {
+    version: SharedString,
+    metadata: Entity<FrontendMetadata>,
+    patch_notes: Entity<FrontendMetadataState>,
+    patch_note: Option<Entity<FrontendMetadataState>>,
+    image_url: Option<SharedUri>,
+    _patch_notes_subscription: Subscription,
+    _patch_note_subscription: Option<Subscription>,
+}
+
+impl ChangelogModalState {
+    // The index only lists versions, the notes themselves are requested once the entry for the version is known
+    fn request_patch_note(&mut self, cx: &mut Context<Self>) {
+        if self.patch_note.is_some() {
+            return;
+        }
+
+        let result: FrontendMetadataResult<JavaPatchNotes> = self.patch_notes.read(cx).result();
+        let FrontendMetadataResult::Loaded(patch_notes) = result else {
+            return;
+        };
+        let Some(entry) = patch_notes.find(&self.version) else {
+            return;
+        };
+
+        self.image_url = entry.image.as_ref().map(|image| SharedUri::from(image.absolute_url()));
+        let request = MetadataRequest::JavaPatchNote(entry.content_path.clone());
+
+        let patch_note = FrontendMetadata::request(&self.metadata, request, cx);
+        self._patch_note_subscription = Some(cx.observe(&patch_note, |_, _, cx| cx.notify()));
+        self.patch_note = Some(patch_note);
+    }
+
+    fn render_content(&self, cx: &mut Context<Self>) -> AnyElement {
+        let loading = || h_flex().justify_center().p_4().child(Spinner::new().large()).into_any_element();
+
+        let result: FrontendMetadataResult<JavaPatchNotes> = self.patch_notes.read(cx).result();
+        match result {
+            FrontendMetadataResult::Loading => return loading(),
+            FrontendMetadataResult::Error(error) => {
+                return ErrorAlert::new("changelog-error", "Unable to load patch notes".into(), error).into_any_element();
+            },
+            FrontendMetadataResult::Loaded(_) => {},
+        }
+
+        let Some(patch_note) = &self.patch_note else {
+            return div()
+                .text_color(cx.theme().muted_foreground)
+                .child(format!("No patch notes have been published for {}", self.version))
+                .into_any_element();
+        };
+
+        let result: FrontendMetadataResult<JavaPatchNote> = patch_note.read(cx).result();
+        match result {
+            FrontendMetadataResult::Loading => loading(),
+            FrontendMetadataResult::Error(error) => {
+                ErrorAlert::new("changelog-error", "Unable to load patch notes".into(), error).into_any_element()
+            },
+            FrontendMetadataResult::Loaded(patch_note) => {
+                v_flex()
+                    .gap_3()
+                    .children(self.image_url.clone().map(|url| {
+                        img(url).w_full().max_h_64().rounded(cx.theme().radius).object_fit(ObjectFit::Cover)
+                    }))
+                    .child(div().text_lg().child(SharedString::new(patch_note.title.clone())))
+                    .child(TextView::html("changelog-body", SharedString::new(patch_note.body.clone())).selectable(true))
+                    .into_any_element()
+            },
+        }
+    }
+
+    fn render(&mut self, modal: Dialog, _window: &mut Window, cx: &mut Context<Self>) -> Dialog {
+        let content = self.render_content(cx);
+
+        modal.title(SharedString::new(format!("Minecraft {} Changelog", self.version)))
+            .width(px(720.0))
+            .child(v_flex()
+                .gap_3()
+                .child(div().max_h(px(560.0)).overflow_y_scrollbar().child(content))
+                .child(h_flex()
+                    .justify_end()
+                    .child(Button::new("close").small().label("Close").on_click(|_, window, cx| {
+                        window.close_dialog(cx);
+                    }))))
+    }
+}
+
+pub fn open_changelog(version: SharedString, metadata: Entity<FrontendMetadata>, window: &mut Window, cx: &mut App) {
+    let state = cx.new(|cx| {
+        let patch_notes = FrontendMetadata::request(&metadata, MetadataRequest::JavaPatchNotes, cx);
+        let _patch_notes_subscription = cx.observe(&patch_notes, |state: &mut ChangelogModalState, _, cx| {
+            state.request_patch_note(cx);
+            cx.notify();
+        });
+
+        let mut state = ChangelogModalState {
+            version,
+            metadata,
+            patch_notes,
+            patch_note: None,
+            image_url: None,
+            _patch_notes_subscription,
+            _patch_note_subscription: None,
+        };
+        state.request_patch_note(cx);
+        state
+    });
+
+    window.open_dialog(cx, move |modal, window, cx| {
+        cx.update_entity(&state, |state, cx| {
+            state.render(modal, window, cx)
+        })
+    });
+}
@@ -0,0 +1,178 @@
+use std::sync::Arc;
+
+use bridge::{handle::BackendHandle, instance::{ContentProblem, ContentProblemEntry, InstanceContentID, InstanceID}, message::{MessageToBackend, QuickPlayLaunch}};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, dialog::Dialog, h_flex, scroll::ScrollableElement, v_flex, ActiveTheme, Sizable, WindowExt
+};
+use rustc_hash::FxHashSet;
+use schema::modrinth::ModrinthProjectType;
+
+struct ContentProblemsModalState {
+    id: InstanceID,
+    name: SharedString,
+    quick_play: Option<QuickPlayLaunch>,
+    problems: Arc<[ContentProblem]>,
+    disable: FxHashSet<InstanceContentID>,
+    backend_handle: BackendHandle,
+}
+
+impl ContentProblemsModalState {
+    fn toggle_disable(&mut self, content_id: InstanceContentID, cx: &mut Context<Self>) {
+        if !self.disable.remove(&content_id) {
+            self.disable.insert(content_id);
+        }
+        cx.notify();
+    }
+
+    fn disable_button(&self, element_id: impl Into<ElementId>, entry: &ContentProblemEntry, cx: &mut Context<Self>) -> Button {
+        let content_id = entry.content_id;
+        let selected = self.disable.contains(&content_id);
+        Button::new(element_id)
+            .small()
+            .label(SharedString::new(format!("Disable {}", entry.name)))
+            .when(selected, |this| this.warning())
+            .selected(selected)
+            .on_click(cx.listener(move |this, _, _, cx| {
+                this.toggle_disable(content_id, cx);
+            }))
+    }
+
+    fn render_problem(&self, index: usize, problem: &ContentProblem, cx: &mut Context<Self>) -> Div {
+        let theme = cx.theme();
+        let muted = theme.muted_foreground;
+
+        let row = v_flex()
+            .gap_1()
+            .p_2()
+            .rounded_md()
+            .border_1()
+            .border_color(theme.border);
+
+        match problem {
+            ContentProblem::MissingDependency { content_name, dependency, version_range, installed_version } => {
+                let range = version_range.as_deref().map(|range| format!(" {range}")).unwrap_or_default();
+                let message = match installed_version {
+                    Some(installed_version) => format!("{content_name} requires {dependency}{range}, but {installed_version} is installed"),
+                    None => format!("{content_name} requires {dependency}{range}, which isn't installed"),
+                };
+
+                let id = self.id;
+                let dependency = dependency.clone();
+                row.child(message)
+                    .child(h_flex().child(Button::new(("install", index)).small().success().label("Install from Modrinth").on_click(move |_, window, cx| {
+                        // Mod ids usually match the slug of the project on Modrinth
+                        let slug: Arc<str> = dependency.replace('_', "-").into();
+                        let data = crate::root::data_entities(cx);
+                        crate::modals::modrinth_install_auto::open(&dependency, slug, ModrinthProjectType::Mod, id, &data, window, cx);
+                    })))
+            },
+            ContentProblem::Duplicate { mod_id, entries } => {
+                let kept = entries.iter().filter(|entry| !self.disable.contains(&entry.content_id)).count();
+                let all_ids: Vec<InstanceContentID> = entries.iter().map(|entry| entry.content_id).collect();
+
+                row.child(format!("{mod_id} is installed multiple times"))
+                    .children(entries.iter().enumerate().map(|(entry_index, entry)| {
+                        let content_id = entry.content_id;
+                        let keeping = kept == 1 && !self.disable.contains(&content_id);
+                        let all_ids = all_ids.clone();
+                        h_flex()
+                            .gap_2()
+                            .child(div().flex_1().child(SharedString::new(format!("{} {}", entry.name, entry.version))))
+                            .child(Button::new(("keep", index * 1000 + entry_index))
+                                .small()
+                                .label("Keep only this")
+                                .when(keeping, |this| this.success())
+                                .selected(keeping)
+                                .on_click(cx.listener(move |this, _, _, cx| {
+                                    for other in &all_ids {
+                                        if *other != content_id {
+                                            this.disable.insert(*other);
+                                        }
+                                    }
+                                    this.disable.remove(&content_id);
+                                    cx.notify();
+                                })))
+                    }))
+            },
+            ContentProblem::WrongLoader { entry, loader } => {
+                row.child(format!("{} {} is made for {loader} and won't load", entry.name, entry.version))
+                    .child(h_flex().child(self.disable_button(("disable", index), entry, cx)))
+            },
+            ContentProblem::WrongGameVersion { entry, version_range } => {
+                row.child(format!("{} {} requires Minecraft {version_range}", entry.name, entry.version))
+                    .child(h_flex().child(self.disable_button(("disable", index), entry, cx)))
+            },
+            ContentProblem::Incompatible { entry, other } => {
+                row.child(format!("{} is incompatible with {}", entry.name, other.name))
+                    .child(div().text_sm().text_color(muted).child("One of them needs to be disabled"))
+                    .child(h_flex()
+                        .gap_2()
+                        .child(self.disable_button(("disable", index), entry, cx))
+                        .child(self.disable_button(("disable-other", index), other, cx)))
+            },
+        }
+    }
+
+    fn render(&mut self, modal: Dialog, _window: &mut Window, cx: &mut Context<Self>) -> Dialog {
+        let problems = self.problems.clone();
+        let problem_rows = problems.iter().enumerate().map(|(index, problem)| self.render_problem(index, problem, cx)).collect::<Vec<_>>();
+
+        let has_fixes = !self.disable.is_empty();
+        let launch_button = Button::new("launch")
+            .label(if has_fixes { "Disable Selected and Launch" } else { "Launch Anyway" })
+            .map(|this| if has_fixes { this.success() } else { this.warning() })
+            .on_click(cx.listener(|this, _, window, cx| {
+                window.close_all_dialogs(cx);
+
+                if !this.disable.is_empty() {
+                    this.backend_handle.send(MessageToBackend::SetContentEnabled {
+                        id: this.id,
+                        content_ids: this.disable.iter().copied().collect(),
+                        enabled: false,
+                    });
+                }
+
+                crate::root::launch_instance(this.id, this.name.clone(), this.quick_play.clone(), &this.backend_handle, window, cx);
+            }));
+
+        modal.title(SharedString::new(format!("Problems found in {}", self.name)))
+            .child(v_flex()
+                .gap_3()
+                .child(div().text_color(cx.theme().muted_foreground)
+                    .child("The installed mods might prevent the game from starting"))
+                .child(v_flex().gap_2().max_h_96().overflow_y_scrollbar().children(problem_rows))
+                .child(h_flex()
+                    .gap_2()
+                    .justify_end()
+                    .child(Button::new("cancel").label("Cancel").on_click(|_, window, cx| {
+                        window.close_all_dialogs(cx);
+                    }))
+                    .child(launch_button)))
+    }
+}
+
+pub fn open(
+    id: InstanceID,
+    name: SharedString,
+    quick_play: Option<QuickPlayLaunch>,
+    problems: Arc<[ContentProblem]>,
+    backend_handle: BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let state = cx.new(|_| ContentProblemsModalState {
+        id,
+        name,
+        quick_play,
+        problems,
+        disable: FxHashSet::default(),
+        backend_handle,
+    });
+
+    window.open_dialog(cx, move |modal, window, cx| {
+        cx.update_entity(&state, |state, cx| {
+            state.render(modal, window, cx)
+        })
+    });
+}
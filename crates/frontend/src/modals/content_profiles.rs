@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use bridge::{handle::BackendHandle, instance::InstanceID, message::MessageToBackend, modal_action::ModalAction};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, dialog::Dialog, h_flex, input::{Input, InputEvent, InputState}, v_flex, ActiveTheme, Disableable, Icon, Sizable
+};
+
+use crate::entity::instance::InstanceEntry;
+
+struct ContentProfilesModalState {
+    id: InstanceID,
+    instance: Entity<InstanceEntry>,
+    backend_handle: BackendHandle,
+    name_input_state: Entity<InputState>,
+    _instance_subscription: Subscription,
+    _name_input_subscription: Subscription,
+}
+
+impl ContentProfilesModalState {
+    fn new(instance: Entity<InstanceEntry>, backend_handle: BackendHandle, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let id = instance.read(cx).id;
+        let name_input_state = cx.new(|cx| InputState::new(window, cx).placeholder("Profile name"));
+        let _instance_subscription = cx.observe(&instance, |_, _, cx| cx.notify());
+        let _name_input_subscription = cx.subscribe(&name_input_state, |_, _, _: &InputEvent, cx| cx.notify());
+
+        Self {
+            id,
+            instance,
+            backend_handle,
+            name_input_state,
+            _instance_subscription,
+            _name_input_subscription,
+        }
+    }
+
+    fn save(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let name = self.name_input_state.read(cx).value().trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+
+        self.backend_handle.send(MessageToBackend::SaveContentProfile {
+            id: self.id,
+            name: name.into(),
+        });
+        self.name_input_state.update(cx, |input, cx| input.set_value("", window, cx));
+    }
+
+    fn render(&mut self, modal: Dialog, _window: &mut Window, cx: &mut Context<Self>) -> Dialog {
+        let configuration = &self.instance.read(cx).configuration;
+        let profiles: Vec<(Arc<str>, usize)> = configuration.content_profiles.iter()
+            .map(|(name, profile)| (name.clone(), profile.disabled.len()))
+            .collect();
+        let active = configuration.active_content_profile.clone();
+
+        let theme = cx.theme();
+        let border = theme.border;
+        let muted = theme.muted_foreground;
+        let trash_icon = Icon::default().path("icons/trash-2.svg");
+
+        let rows = profiles.into_iter().enumerate().map(|(index, (name, disabled))| {
+            let is_active = active.as_ref() == Some(&name);
+            let details = match disabled {
+                0 => "Everything enabled".to_string(),
+                1 => "1 disabled".to_string(),
+                disabled => format!("{disabled} disabled"),
+            };
+
+            h_flex()
+                .p_2()
+                .gap_3()
+                .rounded_lg()
+                .border_1()
+                .border_color(border)
+                .child(v_flex()
+                    .flex_1()
+                    .overflow_hidden()
+                    .child(div().font_semibold().truncate().child(SharedString::new(name.clone())))
+                    .child(div().text_sm().text_color(muted).child(if is_active { format!("Active, {details}") } else { details })))
+                .child(Button::new(("apply", index)).small().label("Switch").disabled(is_active).on_click({
+                    let name = name.clone();
+                    cx.listener(move |this, _, window, cx| {
+                        let modal_action = ModalAction::default();
+                        this.backend_handle.send(MessageToBackend::ApplyContentProfile {
+                            id: this.id,
+                            name: name.clone(),
+                            modal_action: modal_action.clone(),
+                        });
+                        crate::modals::generic::show_notification(window, cx, "Error switching content profile".into(), modal_action);
+                    })
+                }))
+                .child(Button::new(("delete", index)).danger().small().icon(trash_icon.clone()).tooltip("Delete").on_click(
+                    cx.listener(move |this, _, _, _| {
+                        this.backend_handle.send(MessageToBackend::DeleteContentProfile { id: this.id, name: name.clone() });
+                    }),
+                ))
+        }).collect::<Vec<_>>();
+
+        let list = if rows.is_empty() {
+            div().text_color(muted).child("No profiles have been saved yet").into_any_element()
+        } else {
+            v_flex().gap_2().children(rows).into_any_element()
+        };
+
+        let can_save = !self.name_input_state.read(cx).value().trim().is_empty();
+        let save_row = h_flex()
+            .gap_2()
+            .child(div().flex_1().child(Input::new(&self.name_input_state)))
+            .child(Button::new("save").success().label("Save Current").disabled(!can_save)
+                .on_click(cx.listener(|this, _, window, cx| this.save(window, cx))));
+
+        let content = v_flex()
+            .gap_3()
+            .child(div().text_sm().text_color(muted)
+                .child("Profiles remember which mods and resource packs are disabled. Content added after a profile was saved is enabled when switching to it"))
+            .child(list)
+            .child(save_row);
+
+        modal.title("Content Profiles").child(content)
+    }
+}
+
+pub fn open_content_profiles(instance: Entity<InstanceEntry>, backend_handle: BackendHandle, window: &mut Window, cx: &mut App) {
+    let state = cx.new(|cx| ContentProfilesModalState::new(instance, backend_handle, window, cx));
+
+    window.open_dialog(cx, move |modal, window, cx| {
+        cx.update_entity(&state, |state, cx| {
+            state.render(modal, window, cx)
+        })
+    });
+}
@@ -1,21 +1,51 @@
 use std::sync::Arc;
 
-use bridge::{handle::BackendHandle, message::{EmbeddedOrRaw, MessageToBackend}};
+use bridge::{handle::BackendHandle, message::{EmbeddedOrRaw, MessageToBackend}, meta::MetadataRequest};
 use gpui::{prelude::*, *};
 use gpui_component::{
-    alert::Alert, button::{Button, ButtonGroup, ButtonVariants}, checkbox::Checkbox, dialog::Dialog, h_flex, input::{Input, InputEvent, InputState}, select::{Select, SelectState}, skeleton::Skeleton, v_flex, ActiveTheme, IconName, Selectable, WindowExt
+    alert::Alert, button::{Button, ButtonGroup, ButtonVariants}, checkbox::Checkbox, dialog::{Dialog, DialogButtonProps}, h_flex, input::{Input, InputEvent, InputState}, notification::Notification, select::{SearchableVec, Select, SelectState}, skeleton::Skeleton, v_flex, ActiveTheme, Disableable, IconName, Selectable, Sizable, WindowExt
 };
-use schema::{loader::Loader, version_manifest::{MinecraftVersionManifest, MinecraftVersionType}};
+use schema::{fabric_loader_manifest::FabricLoaderManifest, forge::{ForgeMavenManifest, NeoforgeMavenManifest}, instance::InstanceType, loader::Loader, modrinth::ModrinthProjectType, version_manifest::{MinecraftVersionManifest, MinecraftVersionType}};
 
-use crate::{entity::{instance::InstanceEntries, metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult, FrontendMetadataState}}, interface_config::InterfaceConfig, pages::instances_page::VersionList};
+use crate::{entity::{DataEntities, instance::InstanceEntries, metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult, FrontendMetadataState, TypelessFrontendMetadataResult}}, interface_config::InterfaceConfig, pages::instances_page::VersionList, ui::PageType};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CreateInstanceStep {
+    Source,
+    Version,
+    Loader,
+    Details,
+}
+
+impl CreateInstanceStep {
+    fn title(self) -> &'static str {
+        match self {
+            CreateInstanceStep::Source => "Create Instance",
+            CreateInstanceStep::Version => "Create Instance: Version",
+            CreateInstanceStep::Loader => "Create Instance: Modloader",
+            CreateInstanceStep::Details => "Create Instance: Details",
+        }
+    }
+}
 
 struct CreateInstanceModalState {
     metadata: Entity<FrontendMetadata>,
+    instances: Entity<InstanceEntries>,
     versions: Entity<FrontendMetadataState>,
     backend_handle: BackendHandle,
+    step: CreateInstanceStep,
     minecraft_version_dropdown: Entity<SelectState<VersionList>>,
+    initial_version: Option<SharedString>,
     name_input_state: Entity<InputState>,
+    group_input_state: Entity<InputState>,
     selected_loader: Loader,
+    loader_version_dropdown: Entity<SelectState<SearchableVec<&'static str>>>,
+    loader_versions_state: TypelessFrontendMetadataResult,
+    // Whether no loader version supports the selected Minecraft version
+    no_loader_versions: bool,
+    instance_type: InstanceType,
+    accept_eula: bool,
+    install_fabric_api: bool,
     loaded_versions: bool,
     error_loading_versions: Option<SharedString>,
     name_invalid: bool,
@@ -26,10 +56,11 @@ struct CreateInstanceModalState {
     _versions_updated_subscription: Subscription,
     _name_input_subscription: Subscription,
     _version_selected_subscription: Subscription,
+    _loader_versions_subscription: Option<Subscription>,
 }
 
 impl CreateInstanceModalState {
-    pub fn new(metadata: Entity<FrontendMetadata>, instances: Entity<InstanceEntries>, backend_handle: BackendHandle, window: &mut Window, cx: &mut Context<Self>) -> Self {
+    pub fn new(metadata: Entity<FrontendMetadata>, instances: Entity<InstanceEntries>, backend_handle: BackendHandle, initial_version: Option<SharedString>, window: &mut Window, cx: &mut Context<Self>) -> Self {
         let instance_names: Arc<[SharedString]> =
             instances.read(cx).entries.iter().map(|(_, v)| v.read(cx).name.clone()).collect();
 
@@ -40,11 +71,19 @@ impl CreateInstanceModalState {
             this.update_fallback_name(window, cx);
         });
 
+        let loader_version_dropdown =
+            cx.new(|cx| SelectState::new(SearchableVec::new(vec!["Latest"]), None, window, cx).searchable(true));
+
         let name_input_state = cx.new(|cx| {
             InputState::new(window, cx)
                 .placeholder(SharedString::new_static("Unnamed Instance"))
         });
 
+        let group_input_state = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(SharedString::new_static("No group"))
+        });
+
         let _name_input_subscription = {
             let instance_names = Arc::clone(&instance_names);
             cx.subscribe_in(&name_input_state, window, move |this, input_state, _: &InputEvent, _, cx| {
@@ -61,19 +100,36 @@ impl CreateInstanceModalState {
             })
         };
 
-        let versions = FrontendMetadata::request(&metadata, bridge::meta::MetadataRequest::MinecraftVersionManifest, cx);
+        let versions = FrontendMetadata::request(&metadata, MetadataRequest::MinecraftVersionManifest, cx);
 
         let _versions_updated_subscription = cx.observe_in(&versions, window, move |this, _, window, cx| {
             this.reload_version_dropdown(window, cx);
         });
 
+        // The source was already chosen when opening the wizard for a specific version
+        let step = if initial_version.is_some() {
+            CreateInstanceStep::Version
+        } else {
+            CreateInstanceStep::Source
+        };
+
         let mut this = Self {
             metadata,
+            instances,
             versions,
             backend_handle,
+            step,
             minecraft_version_dropdown,
+            initial_version,
             name_input_state,
+            group_input_state,
             selected_loader: Loader::Vanilla,
+            loader_version_dropdown,
+            loader_versions_state: TypelessFrontendMetadataResult::Loaded,
+            no_loader_versions: false,
+            instance_type: InstanceType::Client,
+            accept_eula: false,
+            install_fabric_api: true,
             loaded_versions: false,
             error_loading_versions: None,
             name_invalid: false,
@@ -84,6 +140,7 @@ impl CreateInstanceModalState {
             _versions_updated_subscription,
             _name_input_subscription,
             _version_selected_subscription,
+            _loader_versions_subscription: None,
         };
 
         this.reload_version_dropdown(window, cx);
@@ -139,19 +196,25 @@ impl CreateInstanceModalState {
                     self.loaded_versions = true;
                     self.error_loading_versions = None;
 
-                    let show_snapshots = InterfaceConfig::get(cx).show_snapshots_in_create_instance;
-                    let versions: Vec<SharedString> = if show_snapshots {
-                        manifest.versions.iter().map(|v| SharedString::from(v.id.as_str())).collect()
-                    } else {
-                        manifest
-                            .versions
-                            .iter()
-                            .filter(|v| !matches!(v.r#type, MinecraftVersionType::Snapshot))
-                            .map(|v| SharedString::from(v.id.as_str()))
-                            .collect()
-                    };
-
-                    (versions, Some(SharedString::from(manifest.latest.release.as_str())))
+                    let interface_config = InterfaceConfig::get(cx);
+                    let show_snapshots = interface_config.show_snapshots_in_create_instance;
+                    let show_old_versions = interface_config.show_old_versions_in_create_instance;
+                    let initial_version = self.initial_version.as_deref();
+
+                    // The version the modal was opened for is kept even if it's filtered out
+                    let versions: Vec<SharedString> = manifest
+                        .versions
+                        .iter()
+                        .filter(|v| match v.r#type {
+                            MinecraftVersionType::Release => true,
+                            MinecraftVersionType::Snapshot => show_snapshots,
+                            MinecraftVersionType::OldBeta | MinecraftVersionType::OldAlpha => show_old_versions,
+                        } || Some(v.id.as_str()) == initial_version)
+                        .map(|v| SharedString::from(v.id.as_str()))
+                        .collect();
+
+                    let preferred = self.initial_version.clone().unwrap_or_else(|| SharedString::from(manifest.latest.release.as_str()));
+                    (versions, Some(preferred))
                 },
             };
 
@@ -195,6 +258,427 @@ impl CreateInstanceModalState {
         }
     }
 
+    fn selected_version(&self, cx: &App) -> Option<SharedString> {
+        self.minecraft_version_dropdown.read(cx).selected_value().cloned()
+    }
+
+    fn update_loader_versions(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        match self.selected_loader {
+            Loader::Vanilla | Loader::Unknown => {
+                self._loader_versions_subscription = None;
+                self.loader_versions_state = TypelessFrontendMetadataResult::Loaded;
+                self.no_loader_versions = false;
+            },
+            Loader::Fabric => {
+                // Fabric Loader isn't tied to a specific Minecraft version
+                self.update_loader_versions_for_loader(MetadataRequest::FabricLoaderManifest, |manifest: &FabricLoaderManifest, _| {
+                    manifest.0.iter().map(|s| s.version.as_str()).collect()
+                }, window, cx);
+            },
+            Loader::Forge => {
+                self.update_loader_versions_for_loader(MetadataRequest::ForgeMavenManifest, |manifest: &ForgeMavenManifest, minecraft_version| {
+                    manifest.versions_for_minecraft(minecraft_version).into_iter().map(|s| s.as_str()).collect()
+                }, window, cx);
+            },
+            Loader::NeoForge => {
+                self.update_loader_versions_for_loader(MetadataRequest::NeoforgeMavenManifest, |manifest: &NeoforgeMavenManifest, minecraft_version| {
+                    manifest.versions_for_minecraft(minecraft_version).into_iter().map(|s| s.as_str()).collect()
+                }, window, cx);
+            },
+        }
+    }
+
+    fn update_loader_versions_for_loader<T>(
+        &mut self,
+        request: MetadataRequest,
+        items_fn: impl Fn(&T, &str) -> Vec<&'static str> + 'static,
+        window: &mut Window,
+        cx: &mut Context<Self>
+    )
+    where
+        FrontendMetadataState: AsMetadataResult<T>,
+    {
+        let request = FrontendMetadata::request(&self.metadata, request, cx);
+        self.apply_loader_versions(&request, &items_fn, window, cx);
+        self._loader_versions_subscription = Some(cx.observe_in(&request, window, move |this, metadata, window, cx| {
+            this.apply_loader_versions(&metadata, &items_fn, window, cx);
+        }));
+    }
+
+    fn apply_loader_versions<T>(
+        &mut self,
+        request: &Entity<FrontendMetadataState>,
+        items_fn: &impl Fn(&T, &str) -> Vec<&'static str>,
+        window: &mut Window,
+        cx: &mut Context<Self>
+    )
+    where
+        FrontendMetadataState: AsMetadataResult<T>,
+    {
+        let minecraft_version = self.selected_version(cx).unwrap_or_default();
+        let result: FrontendMetadataResult<T> = request.read(cx).result();
+        let versions = if let FrontendMetadataResult::Loaded(manifest) = &result {
+            (items_fn)(&manifest, minecraft_version.as_str())
+        } else {
+            vec![]
+        };
+        self.no_loader_versions = matches!(result, FrontendMetadataResult::Loaded(_)) && versions.is_empty();
+        self.loader_versions_state = result.as_typeless();
+
+        let items: Vec<&'static str> = std::iter::once("Latest").chain(versions).collect();
+        self.loader_version_dropdown.update(cx, move |select_state, cx| {
+            let previous = select_state.selected_value().copied().filter(|previous| items.contains(previous)).unwrap_or("Latest");
+            select_state.set_items(SearchableVec::new(items), window, cx);
+            select_state.set_selected_value(&previous, window, cx);
+        });
+        cx.notify();
+    }
+
+    fn next_step(&self) -> Option<CreateInstanceStep> {
+        match self.step {
+            CreateInstanceStep::Source => Some(CreateInstanceStep::Version),
+            // Only vanilla servers can be run at the moment, so there is no loader to pick
+            CreateInstanceStep::Version if self.instance_type == InstanceType::Server => Some(CreateInstanceStep::Details),
+            CreateInstanceStep::Version => Some(CreateInstanceStep::Loader),
+            CreateInstanceStep::Loader => Some(CreateInstanceStep::Details),
+            CreateInstanceStep::Details => None,
+        }
+    }
+
+    fn previous_step(&self) -> Option<CreateInstanceStep> {
+        match self.step {
+            CreateInstanceStep::Source => None,
+            CreateInstanceStep::Version => Some(CreateInstanceStep::Source),
+            CreateInstanceStep::Loader => Some(CreateInstanceStep::Version),
+            CreateInstanceStep::Details if self.instance_type == InstanceType::Server => Some(CreateInstanceStep::Version),
+            CreateInstanceStep::Details => Some(CreateInstanceStep::Loader),
+        }
+    }
+
+    fn go_to_step(&mut self, step: CreateInstanceStep, window: &mut Window, cx: &mut Context<Self>) {
+        self.step = step;
+        if step == CreateInstanceStep::Loader {
+            // Loader versions depend on the Minecraft version that was just picked
+            self.update_loader_versions(window, cx);
+        }
+        cx.notify();
+    }
+
+    // Reason the current step can't be continued from, if any
+    fn step_error(&self, cx: &App) -> Option<SharedString> {
+        match self.step {
+            CreateInstanceStep::Source => None,
+            CreateInstanceStep::Version => {
+                if !self.loaded_versions || self.selected_version(cx).is_none() {
+                    Some("Select a Minecraft version".into())
+                } else if self.instance_type == InstanceType::Server && !self.accept_eula {
+                    Some("The Minecraft EULA must be accepted to create a server".into())
+                } else {
+                    None
+                }
+            },
+            CreateInstanceStep::Loader => match &self.loader_versions_state {
+                TypelessFrontendMetadataResult::Loading => Some(format!("Loading {} versions...", self.selected_loader.name()).into()),
+                TypelessFrontendMetadataResult::Error(error) => {
+                    Some(format!("Unable to load {} versions: {}", self.selected_loader.name(), error).into())
+                },
+                TypelessFrontendMetadataResult::Loaded if self.no_loader_versions => {
+                    let version = self.selected_version(cx).unwrap_or_default();
+                    Some(format!("No version of {} supports Minecraft {}", self.selected_loader.name(), version).into())
+                },
+                TypelessFrontendMetadataResult::Loaded => None,
+            },
+            CreateInstanceStep::Details => {
+                if self.name_invalid {
+                    Some("The name is invalid or already used by another instance".into())
+                } else {
+                    None
+                }
+            },
+        }
+    }
+
+    fn create(&mut self, cx: &mut Context<Self>) -> bool {
+        let Some(selected_version) = self.selected_version(cx) else {
+            return false;
+        };
+
+        let mut name = self.name_input_state.read(cx).value().clone();
+        if name.is_empty() {
+            name = self.unique_fallback_name.clone();
+        }
+
+        let group = self.group_input_state.read(cx).value().trim().to_string();
+        let group = if group.is_empty() {
+            None
+        } else {
+            Some(group.as_str().into())
+        };
+
+        let loader = if self.instance_type == InstanceType::Server {
+            Loader::Vanilla
+        } else {
+            self.selected_loader
+        };
+        let preferred_loader_version = if loader == Loader::Vanilla {
+            None
+        } else {
+            self.loader_version_dropdown.read(cx).selected_value().copied().filter(|version| *version != "Latest").map(Into::into)
+        };
+
+        self.backend_handle.send(MessageToBackend::CreateInstance {
+            name: name.as_str().into(),
+            version: selected_version.as_str().into(),
+            loader,
+            preferred_loader_version,
+            group,
+            icon: self.icon.clone(),
+            instance_type: self.instance_type,
+            accept_eula: self.accept_eula,
+            install_fabric_api: self.install_fabric_api,
+        });
+
+        true
+    }
+
+    fn render_source_step(&mut self, cx: &mut Context<Self>) -> Div {
+        let metadata = self.metadata.clone();
+        let instances = self.instances.clone();
+        let backend_handle = self.backend_handle.clone();
+
+        v_flex()
+            .gap_2()
+            .child(Button::new("source-blank")
+                .w_full()
+                .icon(IconName::Plus)
+                .label("Blank Instance")
+                .on_click(cx.listener(|this, _, window, cx| {
+                    this.go_to_step(CreateInstanceStep::Version, window, cx);
+                })))
+            .child(Button::new("source-template")
+                .w_full()
+                .icon(IconName::Copy)
+                .label("From Template")
+                .on_click(move |_, window, cx| {
+                    window.close_dialog(cx);
+                    crate::modals::instance_template::open_create_from_template(metadata.clone(), instances.clone(),
+                        backend_handle.clone(), window, cx);
+                }))
+            .child(Button::new("source-modpack")
+                .w_full()
+                .icon(IconName::Globe)
+                .label("From Modrinth Modpack")
+                .on_click(|_, window, cx| {
+                    window.close_dialog(cx);
+                    crate::root::switch_page(PageType::Modrinth {
+                        installing_for: None,
+                        project_type: Some(ModrinthProjectType::Modpack),
+                    }, &[], window, cx);
+                }))
+    }
+
+    fn render_version_step(&mut self, cx: &mut Context<Self>) -> Div {
+        let type_button_group = ButtonGroup::new("instance-type")
+            .outline()
+            .child(
+                Button::new("type-client")
+                    .label("Client")
+                    .selected(self.instance_type == InstanceType::Client),
+            )
+            .child(
+                Button::new("type-server")
+                    .label("Server")
+                    .selected(self.instance_type == InstanceType::Server),
+            )
+            .on_click(cx.listener(move |this, selected: &Vec<usize>, _, cx| {
+                match selected.first() {
+                    Some(0) => this.instance_type = InstanceType::Client,
+                    Some(1) => this.instance_type = InstanceType::Server,
+                    _ => {},
+                };
+                cx.notify();
+            }));
+
+        let version_content = if !self.loaded_versions {
+            v_flex()
+                .gap_2()
+                .child(Select::new(&self.minecraft_version_dropdown)
+                    .w_full()
+                    .disabled(true)
+                    .placeholder("Loading Minecraft Versions..."))
+                .child(Skeleton::new().w_full().min_h_4().max_h_4().rounded_md())
+        } else {
+            let show_snapshots = InterfaceConfig::get(cx).show_snapshots_in_create_instance;
+            let show_old_versions = InterfaceConfig::get(cx).show_old_versions_in_create_instance;
+            let filters = h_flex()
+                .gap_3()
+                .child(Checkbox::new("show_releases")
+                    .checked(true)
+                    .disabled(true)
+                    .label("Releases"))
+                .child(Checkbox::new("show_snapshots")
+                    .checked(show_snapshots)
+                    .label("Snapshots")
+                    .on_click(cx.listener(move |this, show, window, cx| {
+                        InterfaceConfig::get_mut(cx).show_snapshots_in_create_instance = *show;
+                        this.reload_version_dropdown(window, cx);
+                    })))
+                .child(Checkbox::new("show_old_versions")
+                    .checked(show_old_versions)
+                    .label("Old Beta/Alpha")
+                    .on_click(cx.listener(move |this, show, window, cx| {
+                        InterfaceConfig::get_mut(cx).show_old_versions_in_create_instance = *show;
+                        this.reload_version_dropdown(window, cx);
+                    })));
+
+            v_flex()
+                .gap_2()
+                .child(Select::new(&self.minecraft_version_dropdown).title_prefix("Minecraft Version: "))
+                .child(h_flex()
+                    .justify_between()
+                    .child(filters)
+                    .child(Button::new("changelog")
+                        .small()
+                        .ghost()
+                        .icon(IconName::File)
+                        .label("Changelog")
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            let Some(version) = this.selected_version(cx) else {
+                                return;
+                            };
+                            crate::modals::changelog::open_changelog(version, this.metadata.clone(), window, cx);
+                        }))))
+        };
+
+        v_flex()
+            .gap_3()
+            .child(crate::labelled("Version", version_content))
+            .child(crate::labelled("Type", type_button_group))
+            .when(self.instance_type == InstanceType::Server, |this| {
+                this.child(Checkbox::new("accept-eula")
+                    .checked(self.accept_eula)
+                    .label("I agree to the Minecraft EULA (https://aka.ms/MinecraftEULA)")
+                    .on_click(cx.listener(|this, value, _, cx| {
+                        this.accept_eula = *value;
+                        cx.notify();
+                    })))
+            })
+    }
+
+    fn render_loader_step(&mut self, cx: &mut Context<Self>) -> Div {
+        let loader_button_group = ButtonGroup::new("loader")
+            .outline()
+            .h_full()
+            .child(
+                Button::new("loader-vanilla")
+                    .label("Vanilla")
+                    .selected(self.selected_loader == Loader::Vanilla),
+            )
+            .child(
+                Button::new("loader-fabric")
+                    .label("Fabric")
+                    .selected(self.selected_loader == Loader::Fabric),
+            )
+            .child(
+                Button::new("loader-forge")
+                    .label("Forge")
+                    .selected(self.selected_loader == Loader::Forge),
+            )
+            .child(
+                Button::new("loader-neoforge")
+                    .label("NeoForge")
+                    .selected(self.selected_loader == Loader::NeoForge),
+            )
+            .on_click(cx.listener(move |this, selected: &Vec<usize>, window, cx| {
+                let loader = match selected.first() {
+                    Some(0) => Loader::Vanilla,
+                    Some(1) => Loader::Fabric,
+                    Some(2) => Loader::Forge,
+                    Some(3) => Loader::NeoForge,
+                    _ => return,
+                };
+                if this.selected_loader != loader {
+                    this.selected_loader = loader;
+                    // A pinned version of another loader makes no sense
+                    this.loader_version_dropdown.update(cx, |select_state, cx| {
+                        select_state.set_selected_value(&"Latest", window, cx);
+                    });
+                    this.update_loader_versions(window, cx);
+                }
+            }));
+
+        let loader_version = match self.selected_loader {
+            Loader::Vanilla | Loader::Unknown => None,
+            loader => Some(match self.loader_versions_state {
+                TypelessFrontendMetadataResult::Loading => {
+                    Skeleton::new().w_full().min_h_8().max_h_8().rounded_md().into_any_element()
+                },
+                TypelessFrontendMetadataResult::Loaded => {
+                    Select::new(&self.loader_version_dropdown)
+                        .title_prefix(format!("{} Version: ", loader.name()))
+                        .disabled(self.no_loader_versions)
+                        .w_full()
+                        .into_any_element()
+                },
+                TypelessFrontendMetadataResult::Error(_) => {
+                    let metadata = self.metadata.clone();
+                    let request = match loader {
+                        Loader::Fabric => MetadataRequest::FabricLoaderManifest,
+                        Loader::Forge => MetadataRequest::ForgeMavenManifest,
+                        _ => MetadataRequest::NeoforgeMavenManifest,
+                    };
+                    Button::new("reload-loader-versions")
+                        .label("Reload Versions")
+                        .on_click(move |_, _, cx| {
+                            FrontendMetadata::force_reload(&metadata, request.clone(), cx);
+                        })
+                        .into_any_element()
+                },
+            }),
+        };
+
+        v_flex()
+            .gap_3()
+            .child(crate::labelled("Modloader", loader_button_group))
+            .when_some(loader_version, |this, loader_version| {
+                this.child(crate::labelled("Loader Version", loader_version))
+            })
+            .when(self.selected_loader == Loader::Fabric, |this| {
+                this.child(Checkbox::new("install-fabric-api")
+                    .checked(self.install_fabric_api)
+                    .label("Install Fabric API, which most Fabric mods require")
+                    .on_click(cx.listener(|this, value, _, cx| {
+                        this.install_fabric_api = *value;
+                        cx.notify();
+                    })))
+            })
+    }
+
+    fn render_details_step(&mut self, cx: &mut Context<Self>) -> Div {
+        v_flex()
+            .gap_3()
+            .child(crate::labelled(
+                "Name",
+                Input::new(&self.name_input_state).when(self.name_invalid, |this| this.border_color(cx.theme().danger)),
+            ))
+            .child(crate::labelled("Group", Input::new(&self.group_input_state)))
+            .child(h_flex().child(Button::new("icon")
+                .icon(IconName::Plus)
+                .label(if self.icon.is_some() { "Change Icon" } else { "Select Icon" })
+                .on_click({
+                    let entity = cx.entity();
+                    move |_, window, cx| {
+                        let entity = entity.clone();
+                        crate::modals::select_icon::open_select_icon(Box::new(move |icon, cx| {
+                            cx.update_entity(&entity, |this, cx| {
+                                this.icon = Some(icon);
+                                cx.notify();
+                            });
+                        }), window, cx);
+                    }
+                })))
+    }
+
     pub fn render(&mut self, modal: Dialog, window: &mut Window, cx: &mut Context<Self>) -> Dialog {
         if let Some(error) = self.error_loading_versions.clone() {
             let error_widget = Alert::new("error", format!("{}", error))
@@ -208,7 +692,7 @@ impl CreateInstanceModalState {
                     .label("Reload Versions")
                     .on_click(cx.listener(move |this, _, _, cx| {
                         this.error_loading_versions = None;
-                        FrontendMetadata::force_reload(&metadata, bridge::meta::MetadataRequest::MinecraftVersionManifest, cx);
+                        FrontendMetadata::force_reload(&metadata, MetadataRequest::MinecraftVersionManifest, cx);
                     }));
 
             return modal
@@ -217,119 +701,75 @@ impl CreateInstanceModalState {
                 .child(v_flex().gap_3().child(error_widget).child(reload_button));
         }
 
-        let version_dropdown;
-        let show_snapshots_button;
-        let loader_button_group;
-
-        if !self.loaded_versions {
-            version_dropdown = Select::new(&self.minecraft_version_dropdown)
-                .w_full()
-                .disabled(true)
-                .placeholder("Loading Minecraft Versions...");
-            show_snapshots_button = Skeleton::new().w_full().min_h_4().max_h_4().rounded_md().into_any_element();
-            loader_button_group = Skeleton::new().w_full().min_h_8().max_h_8().rounded_md().into_any_element();
-        } else {
-            version_dropdown = Select::new(&self.minecraft_version_dropdown).title_prefix("Minecraft Version: ");
-            show_snapshots_button = Checkbox::new("show_snapshots")
-                .checked(InterfaceConfig::get(cx).show_snapshots_in_create_instance)
-                .label("Show Snapshots")
-                .on_click(cx.listener(move |this, show, window, cx| {
-                    InterfaceConfig::get_mut(cx).show_snapshots_in_create_instance = *show;
-                    this.reload_version_dropdown(window, cx);
-                }))
-                .into_any_element();
-            loader_button_group = ButtonGroup::new("loader")
-                .outline()
-                .h_full()
-                .child(
-                    Button::new("loader-vanilla")
-                        .label("Vanilla")
-                        .selected(self.selected_loader == Loader::Vanilla),
-                )
-                .child(
-                    Button::new("loader-fabric")
-                        .label("Fabric")
-                        .selected(self.selected_loader == Loader::Fabric),
-                )
-                .child(
-                    Button::new("loader-forge")
-                        .label("Forge")
-                        .selected(self.selected_loader == Loader::Forge),
-                )
-                .child(
-                    Button::new("loader-neoforge")
-                        .label("NeoForge")
-                        .selected(self.selected_loader == Loader::NeoForge),
-                )
-                .on_click(cx.listener(move |this, selected: &Vec<usize>, _, _| {
-                    match selected.first() {
-                        Some(0) => this.selected_loader = Loader::Vanilla,
-                        Some(1) => this.selected_loader = Loader::Fabric,
-                        Some(2) => this.selected_loader = Loader::Forge,
-                        Some(3) => this.selected_loader = Loader::NeoForge,
-                        _ => {},
-                    };
-                }))
-                .into_any_element();
+        let step = self.step;
+        let content = match step {
+            CreateInstanceStep::Source => self.render_source_step(cx),
+            CreateInstanceStep::Version => self.render_version_step(cx),
+            CreateInstanceStep::Loader => self.render_loader_step(cx),
+            CreateInstanceStep::Details => self.render_details_step(cx),
         };
 
-        let content = v_flex()
-            .gap_3()
-            .child(crate::labelled(
-                "Name",
-                Input::new(&self.name_input_state).when(self.name_invalid, |this| this.border_color(cx.theme().danger)),
-            ))
-            .child(crate::labelled("Version", v_flex().gap_2().child(version_dropdown).child(show_snapshots_button)))
-            .child(crate::labelled("Modloader", loader_button_group))
-            .child(h_flex().child(Button::new("icon").icon(IconName::Plus).label("Select Icon").on_click({
-                let entity = cx.entity();
-                move |_, window, cx| {
-                    let entity = entity.clone();
-                    crate::modals::select_icon::open_select_icon(Box::new(move |icon, cx| {
-                        cx.update_entity(&entity, |this, _| {
-                            this.icon = Some(icon);
-                        });
-                    }), window, cx);
-                }
-            })));
+        let step_error = self.step_error(cx);
+        let content = content.when_some(step_error.clone(), |this, error| {
+            this.child(div().text_sm().text_color(cx.theme().danger).child(error))
+        });
+
+        let can_continue = step_error.is_none();
+        let next_step = self.next_step();
+        let previous_step = self.previous_step();
 
-        let name_is_invalid = self.name_invalid;
         let entity = cx.entity();
         modal
-            .footer(move |ok, cancel, window, cx| {
-                if name_is_invalid {
-                    vec![
-                        cancel(window, cx),
-                        div().child(ok(window, cx)).opacity(0.5).into_any_element(),
-                    ]
-                } else {
-                    vec![cancel(window, cx), ok(window, cx)]
+            .footer({
+                let entity = entity.clone();
+                move |ok, cancel, window, cx| {
+                    let mut buttons = vec![cancel(window, cx)];
+                    if let Some(previous_step) = previous_step {
+                        let entity = entity.clone();
+                        buttons.push(Button::new("back")
+                            .label("Back")
+                            .on_click(move |_, window, cx| {
+                                entity.update(cx, |this, cx| this.go_to_step(previous_step, window, cx));
+                            })
+                            .into_any_element());
+                    }
+                    match next_step {
+                        // The source step continues by picking one of the sources
+                        _ if step == CreateInstanceStep::Source => {},
+                        Some(next_step) => {
+                            let entity = entity.clone();
+                            buttons.push(Button::new("next")
+                                .primary()
+                                .label("Next")
+                                .disabled(!can_continue)
+                                .on_click(move |_, window, cx| {
+                                    entity.update(cx, |this, cx| this.go_to_step(next_step, window, cx));
+                                })
+                                .into_any_element());
+                        },
+                        None if can_continue => buttons.push(ok(window, cx)),
+                        None => buttons.push(div().child(ok(window, cx)).opacity(0.5).into_any_element()),
+                    }
+                    buttons
                 }
             })
+            .button_props(DialogButtonProps::default().ok_text("Create"))
             .overlay_closable(false)
-            .title("Create Instance")
-            .on_ok(move |_, _, cx| {
+            .title(step.title())
+            .on_ok(move |_, window, cx| {
                 entity.update(cx, |this, cx| {
-                    if name_is_invalid {
+                    if !can_continue {
                         return false;
                     }
-                    let Some(selected_version) = this.minecraft_version_dropdown.read(cx).selected_value().cloned() else {
-                        return false;
-                    };
-
-                    let mut name = this.name_input_state.read(cx).value().clone();
-                    if name.is_empty() {
-                        name = this.unique_fallback_name.clone();
+                    match this.next_step() {
+                        // Pressing enter on an earlier step moves to the next one
+                        Some(next_step) if this.step != CreateInstanceStep::Source => {
+                            this.go_to_step(next_step, window, cx);
+                            false
+                        },
+                        Some(_) => false,
+                        None => this.create(cx),
                     }
-
-                    this.backend_handle.send(MessageToBackend::CreateInstance {
-                        name: name.as_str().into(),
-                        version: selected_version.as_str().into(),
-                        loader: this.selected_loader,
-                        icon: this.icon.clone(),
-                    });
-
-                    true
                 })
             })
             .child(content)
@@ -340,11 +780,12 @@ pub fn open_create_instance(
     metadata: Entity<FrontendMetadata>,
     instances: Entity<InstanceEntries>,
     backend_handle: BackendHandle,
+    initial_version: Option<SharedString>,
     window: &mut Window,
     cx: &mut App,
 ) {
     let state = cx.new(|cx| {
-        CreateInstanceModalState::new(metadata, instances, backend_handle, window, cx)
+        CreateInstanceModalState::new(metadata, instances, backend_handle, initial_version, window, cx)
     });
 
     window.open_dialog(cx, move |modal, window, cx| {
@@ -353,3 +794,40 @@ pub fn open_create_instance(
         })
     });
 }
+
+struct VersionReleasedNotificationType;
+
+pub fn notify_version_released(version: SharedString, snapshot: bool, data: &DataEntities, window: &mut Window, cx: &mut App) {
+    let title = if snapshot {
+        format!("Minecraft snapshot {version} released")
+    } else {
+        format!("Minecraft {version} released")
+    };
+
+    let metadata = data.metadata.clone();
+    let instances = data.instances.clone();
+    let backend_handle = data.backend_handle.clone();
+
+    let notification = Notification::new()
+        .id1::<VersionReleasedNotificationType>(version.clone())
+        .title(title)
+        .content(move |_, _, _| {
+            let metadata = metadata.clone();
+            let instances = instances.clone();
+            let backend_handle = backend_handle.clone();
+            let version = version.clone();
+
+            Button::new("create-instance")
+                .success()
+                .small()
+                .icon(IconName::Plus)
+                .label("Create Instance")
+                .on_click(move |_, window, cx| {
+                    open_create_instance(metadata.clone(), instances.clone(), backend_handle.clone(), Some(version.clone()), window, cx);
+                })
+                .into_any_element()
+        })
+        .autohide(false);
+
+    window.push_notification(notification, cx);
+}
@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use bridge::{handle::BackendHandle, instance::{InstanceDuplicateOptions, InstanceID}, message::MessageToBackend, modal_action::ModalAction};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, checkbox::Checkbox, dialog::Dialog, input::{Input, InputEvent, InputState}, v_flex, ActiveTheme, Disableable
+};
+
+use crate::entity::instance::InstanceEntries;
+
+struct DuplicateInstanceModalState {
+    id: InstanceID,
+    backend_handle: BackendHandle,
+    name_input_state: Entity<InputState>,
+    name_invalid: bool,
+    options: InstanceDuplicateOptions,
+    _name_input_subscription: Subscription,
+}
+
+impl DuplicateInstanceModalState {
+    pub fn new(
+        id: InstanceID,
+        instance_name: SharedString,
+        instances: Entity<InstanceEntries>,
+        backend_handle: BackendHandle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let instance_names: Arc<[SharedString]> =
+            instances.read(cx).entries.iter().map(|(_, v)| v.read(cx).name.clone()).collect();
+
+        let mut default_name = SharedString::new(format!("{instance_name} (Copy)"));
+        for i in 2..32 {
+            if !instance_names.contains(&default_name) {
+                break;
+            }
+            default_name = SharedString::new(format!("{instance_name} (Copy {i})"));
+        }
+        let name_invalid = !crate::is_valid_instance_name(default_name.as_str()) || instance_names.contains(&default_name);
+
+        let name_input_state = cx.new(|cx| InputState::new(window, cx).default_value(default_name));
+        let _name_input_subscription = cx.subscribe_in(&name_input_state, window, move |this, input_state, _: &InputEvent, _, cx| {
+            let text = input_state.read(cx).value();
+            this.name_invalid = text.is_empty() || !crate::is_valid_instance_name(text.as_str()) || instance_names.contains(&text);
+            cx.notify();
+        });
+
+        Self {
+            id,
+            backend_handle,
+            name_input_state,
+            name_invalid,
+            options: InstanceDuplicateOptions {
+                mods: true,
+                configs: true,
+                saves: false,
+                options: true,
+                link_content: true,
+            },
+            _name_input_subscription,
+        }
+    }
+
+    fn option_checkbox(
+        &self,
+        id: &'static str,
+        label: &'static str,
+        checked: bool,
+        set: fn(&mut InstanceDuplicateOptions, bool),
+        cx: &mut Context<Self>,
+    ) -> Checkbox {
+        Checkbox::new(id)
+            .label(label)
+            .checked(checked)
+            .on_click(cx.listener(move |this, value, _, cx| {
+                set(&mut this.options, *value);
+                cx.notify();
+            }))
+    }
+
+    pub fn render(&mut self, modal: Dialog, _window: &mut Window, cx: &mut Context<Self>) -> Dialog {
+        let options = self.options;
+
+        let duplicate_button = Button::new("duplicate").label("Duplicate").disabled(self.name_invalid).on_click(cx.listener(|this, _, window, cx| {
+            window.close_all_dialogs(cx);
+
+            let modal_action = ModalAction::default();
+            this.backend_handle.send(MessageToBackend::DuplicateInstance {
+                id: this.id,
+                name: this.name_input_state.read(cx).value().as_str().into(),
+                options: this.options,
+                modal_action: modal_action.clone(),
+            });
+            crate::modals::generic::show_modal(window, cx, "Duplicating instance".into(),
+                "Error duplicating instance".into(), modal_action);
+        }));
+
+        let content = v_flex()
+            .gap_3()
+            .child(crate::labelled(
+                "Name",
+                Input::new(&self.name_input_state).when(self.name_invalid, |this| this.border_color(cx.theme().danger)),
+            ))
+            .child(v_flex()
+                .gap_2()
+                .child("Copy")
+                .child(self.option_checkbox("mods", "Mods and resource packs", options.mods, |o, v| o.mods = v, cx))
+                .child(self.option_checkbox("configs", "Mod configs", options.configs, |o, v| o.configs = v, cx))
+                .child(self.option_checkbox("saves", "Worlds", options.saves, |o, v| o.saves = v, cx))
+                .child(self.option_checkbox("options", "Game options", options.options, |o, v| o.options = v, cx)))
+            .child(v_flex()
+                .gap_1()
+                .child(self.option_checkbox("link_content", "Link mods and resource packs from the content library",
+                    options.link_content, |o, v| o.link_content = v, cx)
+                    .disabled(!options.mods))
+                .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                    .child("Saves disk space by sharing identical files between instances instead of copying them")))
+            .child(if self.name_invalid { duplicate_button } else { duplicate_button.success() });
+
+        modal.title("Duplicate Instance").child(content)
+    }
+}
+
+pub fn open_duplicate_instance(
+    id: InstanceID,
+    instance_name: SharedString,
+    instances: Entity<InstanceEntries>,
+    backend_handle: BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let state = cx.new(|cx| {
+        DuplicateInstanceModalState::new(id, instance_name, instances, backend_handle, window, cx)
+    });
+
+    window.open_dialog(cx, move |modal, window, cx| {
+        cx.update_entity(&state, |state, cx| {
+            state.render(modal, window, cx)
+        })
+    });
+}
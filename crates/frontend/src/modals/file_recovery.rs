@@ -0,0 +1,62 @@
+use std::{path::Path, sync::Arc};
+
+use bridge::{handle::BackendHandle, message::{MessageToBackend, PersistentFile}};
+use gpui::{prelude::*, *};
+use gpui_component::{button::{Button, ButtonVariants}, h_flex, v_flex, ActiveTheme, WindowExt};
+
+pub fn open_file_recovery(
+    file: PersistentFile,
+    corrupt_path: Arc<Path>,
+    restored_from: Option<Arc<Path>>,
+    backups: Arc<[Arc<Path>]>,
+    backend_handle: BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let file_name = file.file_name();
+    let title = SharedString::new(format!("{file_name} was damaged"));
+    let explanation = match &restored_from {
+        Some(backup) => format!("{file_name} couldn't be read, so it was restored from the backup made at {}", modified_time(backup)),
+        None => match file {
+            PersistentFile::Config => format!("{file_name} couldn't be read and there was no backup to restore, so the default settings are used"),
+            PersistentFile::Accounts => format!("{file_name} couldn't be read and there was no backup to restore, so accounts need to be added again"),
+        },
+    };
+    let explanation = SharedString::new(explanation);
+    let corrupt = SharedString::new(format!("The damaged file was kept at {}", corrupt_path.display()));
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let muted = cx.theme().muted_foreground;
+
+        let restore_buttons = backups.iter().enumerate().map(|(index, backup)| {
+            let label = SharedString::new(format!("Restore backup from {}", modified_time(backup)));
+            let backend_handle = backend_handle.clone();
+            let backup = backup.clone();
+            Button::new(("restore", index)).warning().label(label).on_click(move |_, window, cx| {
+                backend_handle.send(MessageToBackend::RestorePersistentBackup { file, backup: backup.clone() });
+                window.close_all_dialogs(cx);
+            })
+        }).collect::<Vec<_>>();
+
+        dialog
+            .title(title.clone())
+            .child(v_flex()
+                .gap_2()
+                .child(explanation.clone())
+                .child(div().text_sm().text_color(muted).child(corrupt.clone()))
+                .when(!restore_buttons.is_empty(), |this| this
+                    .child(div().text_sm().child("If something is missing, an older backup can be restored instead"))
+                    .children(restore_buttons))
+                .child(h_flex().justify_end().child(Button::new("ok").success().label("OK").on_click(|_, window, cx| {
+                    window.close_all_dialogs(cx);
+                }))))
+    });
+}
+
+fn modified_time(path: &Path) -> String {
+    let Ok(modified) = std::fs::metadata(path).and_then(|metadata| metadata.modified()) else {
+        return "an unknown time".to_string();
+    };
+    let date_time: chrono::DateTime<chrono::Local> = modified.into();
+    date_time.format("%Y-%m-%d %H:%M").to_string()
+}
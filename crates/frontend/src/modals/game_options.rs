@@ -0,0 +1,250 @@
+use std::collections::HashSet;
+
+use bridge::{handle::BackendHandle, instance::{InstanceGameOptions, InstanceID}, message::MessageToBackend};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    checkbox::Checkbox, dialog::Dialog, h_flex, input::{Input, InputState, NumberInput}, select::{Select, SelectEvent, SelectState}, spinner::Spinner, v_flex, ActiveTheme, Sizable, WindowExt
+};
+
+use crate::entity::instance::InstanceEntries;
+
+struct GameOptionsPreset {
+    name: &'static str,
+    render_distance: u32,
+    enable_vsync: bool,
+}
+
+const PRESETS: &[GameOptionsPreset] = &[
+    GameOptionsPreset {
+        name: "Performance",
+        render_distance: 6,
+        enable_vsync: false,
+    },
+    GameOptionsPreset {
+        name: "Balanced",
+        render_distance: 12,
+        enable_vsync: true,
+    },
+    GameOptionsPreset {
+        name: "Quality",
+        render_distance: 24,
+        enable_vsync: true,
+    },
+];
+
+struct GameOptionsModalState {
+    instance_id: InstanceID,
+    backend_handle: BackendHandle,
+    loaded: bool,
+    // The backend dropped the request without answering, e.g. because the instance was removed
+    load_failed: bool,
+    render_distance_input_state: Entity<InputState>,
+    gui_scale_input_state: Entity<InputState>,
+    lang_input_state: Entity<InputState>,
+    enable_vsync: Option<bool>,
+    preset_select_state: Entity<SelectState<Vec<&'static str>>>,
+    other_instances: Vec<(InstanceID, SharedString)>,
+    apply_to_instances: HashSet<InstanceID>,
+    _get_options_task: Task<()>,
+}
+
+impl GameOptionsModalState {
+    pub fn new(
+        instance_id: InstanceID,
+        instances: Entity<InstanceEntries>,
+        backend_handle: BackendHandle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let render_distance_input_state = cx.new(|cx| InputState::new(window, cx));
+        cx.subscribe_in(&render_distance_input_state, window, crate::step_number_input).detach();
+        let gui_scale_input_state = cx.new(|cx| InputState::new(window, cx).placeholder("0 (Auto)"));
+        cx.subscribe_in(&gui_scale_input_state, window, crate::step_number_input).detach();
+        let lang_input_state = cx.new(|cx| InputState::new(window, cx).placeholder("en_us"));
+
+        let preset_select_state = cx.new(|cx| {
+            SelectState::new(PRESETS.iter().map(|preset| preset.name).collect(), None, window, cx)
+        });
+        cx.subscribe_in(&preset_select_state, window, Self::on_preset_selected).detach();
+
+        let other_instances = instances
+            .read(cx)
+            .entries
+            .iter()
+            .filter(|(id, _)| **id != instance_id)
+            .map(|(id, entry)| (*id, entry.read(cx).title()))
+            .collect();
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        let this_entity = cx.entity();
+        let _get_options_task = window.spawn(cx, async move |cx| {
+            let Ok(options) = recv.await else {
+                _ = cx.update_window_entity(&this_entity, |this, _, cx| {
+                    this.load_failed = true;
+                    cx.notify();
+                });
+                return;
+            };
+            _ = cx.update_window_entity(&this_entity, move |this, window, cx| {
+                this.set_options(options, window, cx);
+            });
+        });
+
+        backend_handle.send(MessageToBackend::GetInstanceGameOptions {
+            id: instance_id,
+            channel: send,
+        });
+
+        Self {
+            instance_id,
+            backend_handle,
+            loaded: false,
+            load_failed: false,
+            render_distance_input_state,
+            gui_scale_input_state,
+            lang_input_state,
+            enable_vsync: None,
+            preset_select_state,
+            other_instances,
+            apply_to_instances: HashSet::new(),
+            _get_options_task,
+        }
+    }
+
+    fn set_options(&mut self, options: InstanceGameOptions, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(render_distance) = options.render_distance {
+            self.render_distance_input_state.update(cx, |input, cx| {
+                input.set_value(render_distance.to_string(), window, cx);
+            });
+        }
+        if let Some(gui_scale) = options.gui_scale {
+            self.gui_scale_input_state.update(cx, |input, cx| {
+                input.set_value(gui_scale.to_string(), window, cx);
+            });
+        }
+        if let Some(lang) = options.lang {
+            self.lang_input_state.update(cx, |input, cx| {
+                input.set_value(SharedString::new(lang), window, cx);
+            });
+        }
+        self.enable_vsync = options.enable_vsync;
+        self.loaded = true;
+        cx.notify();
+    }
+
+    fn on_preset_selected(
+        &mut self,
+        _state: &Entity<SelectState<Vec<&'static str>>>,
+        event: &SelectEvent<Vec<&'static str>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let SelectEvent::Confirm(value) = event;
+        let Some(preset) = value.and_then(|value| PRESETS.iter().find(|preset| preset.name == value)) else {
+            return;
+        };
+
+        self.render_distance_input_state.update(cx, |input, cx| {
+            input.set_value(preset.render_distance.to_string(), window, cx);
+        });
+        self.enable_vsync = Some(preset.enable_vsync);
+        cx.notify();
+    }
+
+    fn get_options(&self, cx: &App) -> InstanceGameOptions {
+        let lang = self.lang_input_state.read(cx).value();
+        let lang = lang.trim_ascii();
+
+        InstanceGameOptions {
+            render_distance: self.render_distance_input_state.read(cx).value().parse().ok(),
+            enable_vsync: self.enable_vsync,
+            lang: if lang.is_empty() { None } else { Some(lang.into()) },
+            gui_scale: self.gui_scale_input_state.read(cx).value().parse().ok(),
+        }
+    }
+
+    pub fn render(&mut self, modal: Dialog, _window: &mut Window, cx: &mut Context<Self>) -> Dialog {
+        let modal = modal.title("Game Options");
+
+        if self.load_failed {
+            return modal.child(div().text_color(cx.theme().danger).child("Unable to read the game options of this instance"));
+        }
+        if !self.loaded {
+            return modal.child(h_flex().justify_center().child(Spinner::new().large()));
+        }
+
+        let instance_list = v_flex()
+            .id("apply-to-instances")
+            .gap_1()
+            .max_h_48()
+            .overflow_y_scroll()
+            .children(self.other_instances.iter().map(|(id, title)| {
+                let id = *id;
+                Checkbox::new(("apply-to", id.index))
+                    .label(title.clone())
+                    .checked(self.apply_to_instances.contains(&id))
+                    .on_click(cx.listener(move |this, value, _, cx| {
+                        if *value {
+                            this.apply_to_instances.insert(id);
+                        } else {
+                            this.apply_to_instances.remove(&id);
+                        }
+                        cx.notify();
+                    }))
+            }));
+
+        let content = v_flex()
+            .gap_3()
+            .child(crate::labelled("Preset", Select::new(&self.preset_select_state).placeholder("Select a preset...")))
+            .child(crate::labelled("Render Distance", NumberInput::new(&self.render_distance_input_state).small().suffix("chunks")))
+            .child(crate::labelled("GUI Scale", NumberInput::new(&self.gui_scale_input_state).small()))
+            .child(crate::labelled("Language", Input::new(&self.lang_input_state).small()))
+            .child(Checkbox::new("vsync")
+                .label("VSync")
+                .checked(self.enable_vsync.unwrap_or(true))
+                .on_click(cx.listener(|this, value, _, cx| {
+                    this.enable_vsync = Some(*value);
+                    cx.notify();
+                })))
+            .when(!self.other_instances.is_empty(), |this| {
+                this.child(crate::labelled(
+                    "Also apply to",
+                    div().border_1().rounded(cx.theme().radius).border_color(cx.theme().border).p_2().child(instance_list)
+                ))
+            });
+
+        let entity = cx.entity();
+        modal
+            .confirm()
+            .on_ok(move |_, _, cx| {
+                entity.update(cx, |this, cx| {
+                    let mut ids = vec![this.instance_id];
+                    ids.extend(this.apply_to_instances.iter().copied());
+                    this.backend_handle.send(MessageToBackend::SetInstanceGameOptions {
+                        ids,
+                        options: this.get_options(cx),
+                    });
+                    true
+                })
+            })
+            .child(content)
+    }
+}
+
+pub fn open_game_options(
+    instance_id: InstanceID,
+    instances: Entity<InstanceEntries>,
+    backend_handle: BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let state = cx.new(|cx| {
+        GameOptionsModalState::new(instance_id, instances, backend_handle, window, cx)
+    });
+
+    window.open_dialog(cx, move |modal, window, cx| {
+        cx.update_entity(&state, |state, cx| {
+            state.render(modal, window, cx)
+        })
+    });
+}
@@ -1,9 +1,9 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use bridge::modal_action::{ModalAction, ProgressTrackerFinishType};
+use bridge::modal_action::{ModalAction, ProgressTracker, ProgressTrackerFinishType};
 use gpui::{prelude::*, *};
 use gpui_component::{
-    button::{Button, ButtonVariants}, dialog::DialogButtonProps, notification::Notification, v_flex, IconName, WindowExt
+    button::{Button, ButtonVariants}, dialog::DialogButtonProps, h_flex, notification::Notification, v_flex, ActiveTheme, IconName, WindowExt
 };
 
 use crate::component::{
@@ -31,54 +31,30 @@ pub fn show_notification_with_note(
         .autohide(false)
         .content(move |notification, window, cx| {
             if let Some(error) = &*modal_action.error.read().unwrap() {
-                let error_widget = ErrorAlert::new("error", error_title.clone(), error.clone().into());
-                return error_widget.into_any_element();
+                let error_widget = v_flex()
+                    .gap_2()
+                    .child(ErrorAlert::new("error", error_title.clone(), error.clone().into()))
+                    .children(error_help_link(&modal_action));
+                if !modal_action.is_retryable() {
+                    return error_widget.into_any_element();
+                }
+
+                let modal_action = modal_action.clone();
+                let error_title = error_title.clone();
+                let retry = Button::new("retry").label("Retry").icon(IconName::Redo).on_click(move |_, window, cx| {
+                    let Some(modal_action) = send_retry(&modal_action, cx) else {
+                        return;
+                    };
+                    show_notification(window, cx, error_title.clone(), modal_action);
+                });
+                return v_flex().gap_2().child(error_widget).child(retry).into_any_element();
             }
 
             if modal_action.refcnt() <= 1 || modal_action.get_finished_at().is_some() {
                 notification.dismiss(window, cx);
             }
 
-            let trackers = modal_action.trackers.trackers.read().unwrap();
-            let mut progress_entries = Vec::with_capacity(trackers.len());
-            for tracker in &*trackers {
-                let mut opacity = 1.0;
-
-                let mut progress_bar = ProgressBar::new();
-                if let Some(progress_amount) = tracker.get_float() {
-                    progress_bar.amount = progress_amount;
-                }
-
-                if let Some(finished_at) = tracker.get_finished_at() {
-                    let finish_type = tracker.finish_type();
-
-                    if finish_type == ProgressTrackerFinishType::Fast {
-                        continue;
-                    }
-
-                    let elapsed = finished_at.elapsed().as_secs_f32();
-                    if elapsed >= 2.0 {
-                        continue;
-                    } else if elapsed >= 1.0 {
-                        opacity = 2.0 - elapsed;
-                    }
-
-                    if finish_type == ProgressTrackerFinishType::Error {
-                        progress_bar.color = ProgressBarColor::Error;
-                    } else {
-                        progress_bar.color = ProgressBarColor::Success;
-                    }
-                    if elapsed <= 0.5 {
-                        progress_bar.color_scale = elapsed * 2.0;
-                    }
-
-                    window.request_animation_frame();
-                }
-
-                let title = tracker.get_title();
-                progress_entries.push(div().gap_3().child(SharedString::from(title)).child(progress_bar).opacity(opacity));
-            }
-            drop(trackers);
+            let mut progress_entries = tracker_entries(&modal_action, window, cx);
 
             if let Some(visit_url) = &*modal_action.visit_url.read().unwrap() {
                 let message = SharedString::new(Arc::clone(&visit_url.message));
@@ -88,6 +64,7 @@ pub fn show_notification_with_note(
                         cx.open_url(&url);
                     },
                 )));
+                progress_entries.extend(visit_url.code.clone().map(code_entry));
             }
 
             v_flex().gap_2().children(progress_entries).into_any_element()
@@ -106,7 +83,30 @@ pub fn show_modal(
         if let Some(error) = &*modal_action.error.read().unwrap() {
             let error_widget = ErrorAlert::new("error", error_title.clone(), error.clone().into());
 
-            return modal.confirm().title(title.clone()).child(v_flex().gap_3().child(error_widget));
+            let modal = modal.confirm().title(title.clone()).child(v_flex()
+                .gap_3()
+                .child(error_widget)
+                .children(error_help_link(&modal_action)));
+            if !modal_action.is_retryable() {
+                return modal;
+            }
+
+            let modal_action = modal_action.clone();
+            let title = title.clone();
+            let error_title = error_title.clone();
+            return modal.footer(move |ok, _, window, cx| {
+                let modal_action = modal_action.clone();
+                let title = title.clone();
+                let error_title = error_title.clone();
+                let retry = Button::new("retry").label("Retry").icon(IconName::Redo).on_click(move |_, window, cx| {
+                    let Some(modal_action) = send_retry(&modal_action, cx) else {
+                        return;
+                    };
+                    window.close_dialog(cx);
+                    show_modal(window, cx, title.clone(), error_title.clone(), modal_action);
+                });
+                vec![retry.into_any_element(), (ok)(window, cx)]
+            });
         }
 
         if modal_action.refcnt() <= 1 {
@@ -134,46 +134,7 @@ pub fn show_modal(
             }
         }
 
-        let trackers = modal_action.trackers.trackers.read().unwrap();
-        let mut progress_entries = Vec::with_capacity(trackers.len());
-        for tracker in &*trackers {
-            let mut opacity = 1.0;
-
-            let mut progress_bar = ProgressBar::new();
-            if let Some(progress_amount) = tracker.get_float() {
-                progress_bar.amount = progress_amount;
-            }
-
-            if let Some(finished_at) = tracker.get_finished_at() {
-                let finish_type = tracker.finish_type();
-
-                if finish_type == ProgressTrackerFinishType::Fast {
-                    continue;
-                }
-
-                let elapsed = finished_at.elapsed().as_secs_f32();
-                if elapsed >= 2.0 {
-                    continue;
-                } else if elapsed >= 1.0 {
-                    opacity = 2.0 - elapsed;
-                }
-
-                if finish_type == ProgressTrackerFinishType::Error {
-                    progress_bar.color = ProgressBarColor::Error;
-                } else {
-                    progress_bar.color = ProgressBarColor::Success;
-                }
-                if elapsed <= 0.5 {
-                    progress_bar.color_scale = elapsed * 2.0;
-                }
-
-                window.request_animation_frame();
-            }
-
-            let title = tracker.get_title();
-            progress_entries.push(div().gap_3().child(SharedString::from(title)).child(progress_bar).opacity(opacity));
-        }
-        drop(trackers);
+        let mut progress_entries = tracker_entries(&modal_action, window, cx);
 
         if let Some(visit_url) = &*modal_action.visit_url.read().unwrap() {
             let message = SharedString::new(Arc::clone(&visit_url.message));
@@ -183,6 +144,7 @@ pub fn show_modal(
                     cx.open_url(&url);
                 },
             )));
+            progress_entries.extend(visit_url.code.clone().map(code_entry));
         }
 
         let progress = v_flex().gap_2().children(progress_entries);
@@ -205,3 +167,111 @@ pub fn show_modal(
         }
     });
 }
+
+// A failed action can leave a link to the page where the user can fix the problem, e.g. creating an Xbox profile
+fn error_help_link(modal_action: &ModalAction) -> Option<Button> {
+    let visit_url = modal_action.visit_url.read().unwrap();
+    let visit_url = visit_url.as_ref()?;
+    let url = Arc::clone(&visit_url.url);
+    Some(Button::new("help-link").info().icon(IconName::Globe).label(SharedString::new(Arc::clone(&visit_url.message))).on_click(
+        move |_, _, cx| {
+            cx.open_url(&url);
+        },
+    ))
+}
+
+// The overall progress when there's more than one tracker, followed by each tracker with its children indented below it
+// The code to enter on the page that was opened, e.g. for logging in on another device
+fn code_entry(code: Arc<str>) -> Div {
+    h_flex()
+        .px_3()
+        .gap_3()
+        .child(div().text_xl().font_family("Roboto Mono").child(SharedString::new(code.clone())))
+        .child(Button::new("copy-code").small().icon(IconName::Copy).label("Copy code").on_click(move |_, _, cx| {
+            cx.write_to_clipboard(ClipboardItem::new_string(code.to_string()));
+        }))
+}
+
+fn tracker_entries(modal_action: &ModalAction, window: &mut Window, cx: &App) -> Vec<Div> {
+    let trackers = modal_action.trackers.trackers.read().unwrap();
+    let mut entries = Vec::with_capacity(trackers.len() + 1);
+
+    let nested = trackers.len() > 1 || trackers.iter().any(|tracker| !tracker.children().is_empty());
+    if nested && let Some(overall) = modal_action.trackers.get_overall_float() {
+        let mut summary = format!("{}% complete", (overall * 100.0).round() as u32);
+        if let Some(eta) = modal_action.trackers.get_overall_eta() {
+            summary.push_str(&format!(", about {} left", format_eta(eta)));
+        }
+        entries.push(div().text_sm().text_color(cx.theme().muted_foreground).child(summary));
+    }
+
+    for tracker in &*trackers {
+        push_tracker_entries(tracker, 0, &mut entries, window);
+    }
+    entries
+}
+
+fn push_tracker_entries(tracker: &ProgressTracker, depth: usize, entries: &mut Vec<Div>, window: &mut Window) {
+    let mut opacity = 1.0;
+
+    let mut progress_bar = ProgressBar::new();
+    if let Some(progress_amount) = tracker.get_float() {
+        progress_bar.amount = progress_amount;
+    }
+
+    let finished = tracker.get_finished_at();
+    if let Some(finished_at) = finished {
+        let finish_type = tracker.finish_type();
+
+        if finish_type == ProgressTrackerFinishType::Fast {
+            return;
+        }
+
+        let elapsed = finished_at.elapsed().as_secs_f32();
+        if elapsed >= 2.0 {
+            return;
+        } else if elapsed >= 1.0 {
+            opacity = 2.0 - elapsed;
+        }
+
+        if finish_type == ProgressTrackerFinishType::Error {
+            progress_bar.color = ProgressBarColor::Error;
+        } else {
+            progress_bar.color = ProgressBarColor::Success;
+        }
+        if elapsed <= 0.5 {
+            progress_bar.color_scale = elapsed * 2.0;
+        }
+
+        window.request_animation_frame();
+    }
+
+    let title = tracker.get_title();
+    let entry = div().gap_3().pl(px(16.0 * depth as f32)).child(SharedString::from(title)).child(progress_bar).opacity(opacity);
+    entries.push(if depth > 0 { entry.text_sm() } else { entry });
+
+    // Children of a finished tracker fade out along with it
+    if finished.is_none() {
+        for child in tracker.children() {
+            push_tracker_entries(&child, depth + 1, entries, window);
+        }
+    }
+}
+
+fn format_eta(eta: Duration) -> String {
+    let seconds = eta.as_secs().max(1);
+    if seconds < 60 {
+        format!("{seconds}s")
+    } else if seconds < 60 * 60 {
+        format!("{}m {}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{}h {}m", seconds / 3600, (seconds / 60) % 60)
+    }
+}
+
+// Starts the failed operation again with its original parameters, returning the modal action tracking the new attempt
+fn send_retry(modal_action: &ModalAction, cx: &App) -> Option<ModalAction> {
+    let (modal_action, message) = modal_action.retry()?;
+    crate::root::data_entities(cx).backend_handle.send(message);
+    Some(modal_action)
+}
@@ -0,0 +1,263 @@
+use std::{path::{Path, PathBuf}, sync::Arc};
+
+use bridge::{
+    handle::BackendHandle, install::{ContentDownload, ContentInstall, ContentInstallFile, ContentInstallPath, DroppedFile, DroppedFileKind, InstallTarget}, instance::InstanceID, message::MessageToBackend, modal_action::ModalAction
+};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, dialog::Dialog, h_flex, input::{Input, InputEvent, InputState}, notification::NotificationType, select::{Select, SelectState}, v_flex, ActiveTheme, Disableable, IndexPath, WindowExt
+};
+use schema::{content::ContentSource, loader::Loader};
+
+use crate::{component::instance_dropdown::InstanceDropdown, entity::{instance::InstanceEntry, DataEntities}, root};
+
+// Imports files dropped onto the launcher. Modpacks always create a new instance, everything else goes into the
+// instance that is currently open, or one chosen in a prompt
+pub fn open(paths: Vec<PathBuf>, current_instance: Option<InstanceID>, data: &DataEntities, window: &mut Window, cx: &mut App) {
+    if paths.is_empty() {
+        return;
+    }
+
+    let (send, recv) = tokio::sync::oneshot::channel();
+    data.backend_handle.send(MessageToBackend::IdentifyDroppedFiles { paths, channel: send });
+
+    let data = data.clone();
+    window.spawn(cx, async move |cx| {
+        let Ok(files) = recv.await else {
+            return;
+        };
+        _ = cx.update(|window, cx| {
+            import_files(&files, current_instance, &data, window, cx);
+        });
+    }).detach();
+}
+
+fn import_files(files: &[DroppedFile], current_instance: Option<InstanceID>, data: &DataEntities, window: &mut Window, cx: &mut App) {
+    let (known, unknown): (Vec<&DroppedFile>, Vec<&DroppedFile>) = files.iter()
+        .partition(|file| !matches!(file.kind, DroppedFileKind::Unknown));
+
+    if !unknown.is_empty() {
+        let names = unknown.iter().map(|file| file_name(&file.path)).collect::<Vec<_>>().join(", ");
+        window.push_notification((NotificationType::Warning, SharedString::new(format!("Unable to import {names}"))), cx);
+    }
+
+    if let Some(modpack) = known.iter().find(|file| matches!(file.kind, DroppedFileKind::Modpack { .. })) {
+        if known.len() > 1 {
+            window.push_notification((NotificationType::Warning, "Modpacks are imported on their own, the other files were skipped"), cx);
+        }
+        open_dialog(ImportTarget::Modpack((*modpack).clone()), None, data, window, cx);
+        return;
+    }
+
+    if known.is_empty() {
+        return;
+    }
+
+    let files: Arc<[DroppedFile]> = known.into_iter().cloned().collect();
+    if let Some(id) = current_instance
+        && let Some(instance) = data.instances.read(cx).entries.get(&id)
+    {
+        let instance = instance.read(cx).clone();
+        import_into_instance(&instance, &files, &data.backend_handle, window, cx);
+    } else {
+        let instances = data.instances.read(cx).entries.values().map(|entry| entry.read(cx).clone()).collect();
+        open_dialog(ImportTarget::Content(files), Some(instances), data, window, cx);
+    }
+}
+
+fn import_into_instance(instance: &InstanceEntry, files: &[DroppedFile], backend_handle: &BackendHandle, window: &mut Window, cx: &mut App) {
+    let content_install = content_install(InstallTarget::Instance(instance.id), Some(instance.configuration.minecraft_version.as_str().into()), files);
+    if !content_install.files.is_empty() {
+        root::start_install(content_install, backend_handle, window, cx);
+    }
+
+    for file in files {
+        if let DroppedFileKind::World { .. } = file.kind {
+            let modal_action = ModalAction::default();
+            backend_handle.send(MessageToBackend::ImportWorld {
+                id: instance.id,
+                path: file.path.clone(),
+                modal_action: modal_action.clone(),
+            });
+            crate::modals::generic::show_notification(window, cx, "Error importing world".into(), modal_action);
+        }
+    }
+}
+
+// Worlds can't be part of a content install, they're imported separately
+fn content_install(target: InstallTarget, version_hint: Option<Arc<str>>, files: &[DroppedFile]) -> ContentInstall {
+    let loader_hint = files.iter().find_map(|file| match &file.kind {
+        DroppedFileKind::Mod { loader } if *loader != Loader::Unknown => Some(*loader),
+        DroppedFileKind::Modpack { loader, .. } => Some(*loader),
+        _ => None,
+    });
+
+    ContentInstall {
+        target,
+        loader_hint: loader_hint.unwrap_or(Loader::Unknown),
+        version_hint,
+        files: files.iter().filter_map(|file| {
+            let folder = match file.kind {
+                DroppedFileKind::Modpack { .. } | DroppedFileKind::Mod { .. } => "mods",
+                DroppedFileKind::ResourcePack => "resourcepacks",
+                DroppedFileKind::World { .. } | DroppedFileKind::Unknown => return None,
+            };
+            Some(ContentInstallFile {
+                replace_old: None,
+                path: ContentInstallPath::Raw(Path::new(folder).join(file.path.file_name()?).into()),
+                download: ContentDownload::File { path: file.path.to_path_buf() },
+                content_source: ContentSource::Manual,
+            })
+        }).collect(),
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap_or(path.as_os_str()).to_string_lossy().into_owned()
+}
+
+enum ImportTarget {
+    Modpack(DroppedFile),
+    Content(Arc<[DroppedFile]>),
+}
+
+struct ImportFilesDialog {
+    target: ImportTarget,
+    data: DataEntities,
+    instances: Option<Entity<SelectState<InstanceDropdown>>>,
+    name_input_state: Entity<InputState>,
+    name_invalid: bool,
+    _name_input_subscription: Subscription,
+}
+
+fn open_dialog(target: ImportTarget, instances: Option<Arc<[InstanceEntry]>>, data: &DataEntities, window: &mut Window, cx: &mut App) {
+    let default_name = match &target {
+        ImportTarget::Modpack(DroppedFile { kind: DroppedFileKind::Modpack { name, .. }, .. }) => SharedString::new(name.clone()),
+        _ => SharedString::new_static("New Instance"),
+    };
+
+    let instance_names: Arc<[SharedString]> =
+        data.instances.read(cx).entries.iter().map(|(_, v)| v.read(cx).name.clone()).collect();
+    let name_invalid = !crate::is_valid_instance_name(default_name.as_str()) || instance_names.contains(&default_name);
+
+    let state = cx.new(|cx: &mut Context<ImportFilesDialog>| {
+        let name_input_state = cx.new(|cx| InputState::new(window, cx).default_value(default_name));
+        let _name_input_subscription = cx.subscribe_in(&name_input_state, window, move |this: &mut ImportFilesDialog, input_state, _: &InputEvent, _, cx| {
+            let text = input_state.read(cx).value();
+            this.name_invalid = text.is_empty() || !crate::is_valid_instance_name(text.as_str()) || instance_names.contains(&text);
+            cx.notify();
+        });
+
+        let instances = instances.filter(|instances| !instances.is_empty()).map(|instances| {
+            let dropdown = InstanceDropdown::create(instances, window, cx);
+            dropdown.update(cx, |dropdown, cx| {
+                dropdown.set_selected_index(Some(IndexPath::default()), window, cx)
+            });
+            dropdown
+        });
+
+        ImportFilesDialog {
+            target,
+            data: data.clone(),
+            instances,
+            name_input_state,
+            name_invalid,
+            _name_input_subscription,
+        }
+    });
+
+    window.open_dialog(cx, move |modal, window, cx| {
+        cx.update_entity(&state, |state, cx| {
+            state.render(modal, window, cx)
+        })
+    });
+}
+
+impl ImportFilesDialog {
+    fn create_instance(&self, window: &mut Window, cx: &mut App) {
+        let name: Arc<str> = self.name_input_state.read(cx).value().as_str().into();
+        let (version_hint, files) = match &self.target {
+            ImportTarget::Modpack(modpack) => {
+                let DroppedFileKind::Modpack { minecraft_version, .. } = &modpack.kind else {
+                    return;
+                };
+                (minecraft_version.clone(), Arc::from([modpack.clone()]))
+            },
+            ImportTarget::Content(files) => (None, files.clone()),
+        };
+
+        let mut content_install = content_install(InstallTarget::NewInstance { name }, version_hint, &files);
+        if content_install.loader_hint == Loader::Unknown {
+            content_install.loader_hint = Loader::Vanilla;
+        }
+
+        window.close_all_dialogs(cx);
+        root::start_install(content_install, &self.data.backend_handle, window, cx);
+    }
+
+    fn render(&mut self, modal: Dialog, _window: &mut Window, cx: &mut Context<Self>) -> Dialog {
+        let (title, create_label) = match &self.target {
+            ImportTarget::Modpack(_) => ("Import Modpack", "Create instance"),
+            ImportTarget::Content(_) => ("Import Files", "Create new instance with these files"),
+        };
+
+        let mut content = v_flex().gap_3();
+
+        if let ImportTarget::Content(files) = &self.target {
+            content = content.child(v_flex()
+                .gap_1()
+                .text_sm()
+                .children(files.iter().map(|file| {
+                    let kind = match file.kind {
+                        DroppedFileKind::Modpack { .. } => "Modpack",
+                        DroppedFileKind::Mod { .. } => "Mod",
+                        DroppedFileKind::ResourcePack => "Resource pack",
+                        DroppedFileKind::World { .. } => "World",
+                        DroppedFileKind::Unknown => "Unknown",
+                    };
+                    h_flex().gap_2()
+                        .child(div().text_color(cx.theme().muted_foreground).child(kind))
+                        .child(SharedString::new(file_name(&file.path)))
+                })));
+
+            if let Some(instances) = &self.instances {
+                let selected_instance = instances.read(cx).selected_value().cloned();
+                content = content
+                    .child(h_flex()
+                        .gap_2()
+                        .child(div().flex_1().child(Select::new(instances).placeholder("Select an instance").title_prefix("Instance: ")))
+                        .child(Button::new("add").success().label("Add to instance").disabled(selected_instance.is_none()).on_click(
+                            cx.listener(move |this, _, window, cx| {
+                                let Some(instance) = &selected_instance else {
+                                    return;
+                                };
+                                let ImportTarget::Content(files) = &this.target else {
+                                    return;
+                                };
+                                window.close_all_dialogs(cx);
+                                import_into_instance(instance, files, &this.data.backend_handle, window, cx);
+                            }),
+                        )))
+                    .child(div().text_center().child("— OR —"));
+            }
+
+            if files.iter().any(|file| matches!(file.kind, DroppedFileKind::World { .. })) {
+                content = content.child(div().text_sm().text_color(cx.theme().muted_foreground)
+                    .child("Worlds are only imported into existing instances"));
+            }
+        }
+
+        let create_button = Button::new("create").label(create_label).disabled(self.name_invalid).on_click(cx.listener(|this, _, window, cx| {
+            this.create_instance(window, cx);
+        }));
+
+        content = content
+            .child(crate::labelled(
+                "Name",
+                Input::new(&self.name_input_state).when(self.name_invalid, |this| this.border_color(cx.theme().danger)),
+            ))
+            .child(if self.name_invalid { create_button } else { create_button.success() });
+
+        modal.title(title).child(content)
+    }
+}
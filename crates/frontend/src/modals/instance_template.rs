@@ -0,0 +1,235 @@
+use std::sync::Arc;
+
+use bridge::{handle::BackendHandle, instance::{InstanceID, InstanceTemplateSummary}, message::MessageToBackend, modal_action::ModalAction};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, checkbox::Checkbox, dialog::Dialog, h_flex, input::{Input, InputEvent, InputState}, select::{Select, SelectState}, spinner::Spinner, v_flex, ActiveTheme, Disableable, Sizable, WindowExt
+};
+use schema::version_manifest::{MinecraftVersionManifest, MinecraftVersionType};
+
+use crate::{entity::{instance::InstanceEntries, metadata::{FrontendMetadata, FrontendMetadataResult, FrontendMetadataState}}, pages::instances_page::VersionList};
+
+pub fn open_save_template(
+    instance_name: SharedString,
+    id: InstanceID,
+    backend_handle: BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let name_input = cx.new(|cx| {
+        InputState::new(window, cx).default_value(instance_name)
+    });
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let name = name_input.read(cx).value();
+        let valid = !name.is_empty() && crate::is_valid_instance_name(name.as_str());
+
+        let backend_handle = backend_handle.clone();
+        let mut save_button = Button::new("save").label("Save").disabled(!valid).on_click(move |_, window, cx| {
+            window.close_all_dialogs(cx);
+            backend_handle.send(MessageToBackend::SaveInstanceTemplate {
+                id,
+                name: name.as_str().into(),
+            });
+        });
+
+        if valid {
+            save_button = save_button.success();
+        }
+
+        dialog.title("Save as Template")
+            .child(v_flex()
+                .gap_2()
+                .child("Saves the loader, content, JVM settings and game options of this instance")
+                .child(crate::labelled("Template Name", Input::new(&name_input)))
+                .child(save_button))
+    });
+}
+
+struct CreateFromTemplateModalState {
+    backend_handle: BackendHandle,
+    templates: Option<Arc<[InstanceTemplateSummary]>>,
+    template_select_state: Entity<SelectState<Vec<SharedString>>>,
+    name_input_state: Entity<InputState>,
+    name_invalid: bool,
+    change_version: bool,
+    versions: Entity<FrontendMetadataState>,
+    version_select_state: Entity<SelectState<VersionList>>,
+    _get_templates_task: Task<()>,
+    _name_input_subscription: Subscription,
+    _versions_updated_subscription: Subscription,
+}
+
+impl CreateFromTemplateModalState {
+    pub fn new(
+        metadata: Entity<FrontendMetadata>,
+        instances: Entity<InstanceEntries>,
+        backend_handle: BackendHandle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let instance_names: Arc<[SharedString]> =
+            instances.read(cx).entries.iter().map(|(_, v)| v.read(cx).name.clone()).collect();
+
+        let template_select_state = cx.new(|cx| SelectState::new(Vec::new(), None, window, cx));
+        cx.observe(&template_select_state, |_, _, cx| cx.notify()).detach();
+
+        let name_input_state = cx.new(|cx| InputState::new(window, cx).placeholder("Unnamed Instance"));
+        let _name_input_subscription = cx.subscribe_in(&name_input_state, window, move |this, input_state, _: &InputEvent, _, cx| {
+            let text = input_state.read(cx).value();
+            this.name_invalid = text.is_empty() || !crate::is_valid_instance_name(text.as_str()) || instance_names.contains(&text);
+            cx.notify();
+        });
+
+        let versions = FrontendMetadata::request(&metadata, bridge::meta::MetadataRequest::MinecraftVersionManifest, cx);
+        let _versions_updated_subscription = cx.observe_in(&versions, window, |this, _, window, cx| {
+            this.reload_versions(window, cx);
+        });
+        let version_select_state = cx.new(|cx| SelectState::new(VersionList::default(), None, window, cx).searchable(true));
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        let _get_templates_task = cx.spawn_in(window, async move |this, cx| {
+            let Ok(templates) = recv.await else {
+                return;
+            };
+            _ = this.update_in(cx, move |this, window, cx| {
+                let names = templates.iter().map(|template| SharedString::new(template.name.clone())).collect::<Vec<_>>();
+                this.template_select_state.update(cx, |select, cx| {
+                    select.set_items(names.clone(), window, cx);
+                    if let Some(first) = names.first() {
+                        select.set_selected_value(first, window, cx);
+                    }
+                });
+                this.templates = Some(templates);
+                cx.notify();
+            });
+        });
+        backend_handle.send(MessageToBackend::GetInstanceTemplates { channel: send });
+
+        let mut this = Self {
+            backend_handle,
+            templates: None,
+            template_select_state,
+            name_input_state,
+            name_invalid: true,
+            change_version: false,
+            versions,
+            version_select_state,
+            _get_templates_task,
+            _name_input_subscription,
+            _versions_updated_subscription,
+        };
+        this.reload_versions(window, cx);
+        this
+    }
+
+    fn reload_versions(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let result: FrontendMetadataResult<MinecraftVersionManifest> = self.versions.read(cx).result();
+        let FrontendMetadataResult::Loaded(manifest) = result else {
+            return;
+        };
+
+        let versions: Vec<SharedString> = manifest.versions.iter()
+            .filter(|v| !matches!(v.r#type, MinecraftVersionType::Snapshot))
+            .map(|v| SharedString::from(v.id.as_str()))
+            .collect();
+        let latest = SharedString::from(manifest.latest.release.as_str());
+
+        self.version_select_state.update(cx, |select, cx| {
+            select.set_items(VersionList {
+                versions: versions.clone(),
+                matched_versions: versions,
+            }, window, cx);
+            select.set_selected_value(&latest, window, cx);
+        });
+    }
+
+    fn selected_template(&self, cx: &App) -> Option<&InstanceTemplateSummary> {
+        let selected = self.template_select_state.read(cx).selected_value()?;
+        self.templates.as_ref()?.iter().find(|template| &*template.name == selected.as_str())
+    }
+
+    pub fn render(&mut self, modal: Dialog, _window: &mut Window, cx: &mut Context<Self>) -> Dialog {
+        let modal = modal.title("Create Instance from Template");
+
+        let Some(templates) = &self.templates else {
+            return modal.child(h_flex().justify_center().child(Spinner::new().large()));
+        };
+        if templates.is_empty() {
+            return modal.child("No templates have been saved yet. Use 'Save as template' in the settings of an instance to create one");
+        }
+
+        let template_info = self.selected_template(cx).map(|template| {
+            SharedString::new(format!("{} {}, {} content files", template.loader.name(), template.minecraft_version, template.content_count))
+        });
+
+        let create_button = Button::new("create").label("Create Instance").disabled(self.name_invalid).on_click(cx.listener(|this, _, window, cx| {
+            let Some(template) = this.selected_template(cx).map(|template| template.name.clone()) else {
+                return;
+            };
+            let version = if this.change_version {
+                this.version_select_state.read(cx).selected_value().map(|version| version.as_str().into())
+            } else {
+                None
+            };
+
+            window.close_all_dialogs(cx);
+
+            let modal_action = ModalAction::default();
+            this.backend_handle.send(MessageToBackend::CreateInstanceFromTemplate {
+                template,
+                name: this.name_input_state.read(cx).value().as_str().into(),
+                version,
+                modal_action: modal_action.clone(),
+            });
+            crate::modals::generic::show_modal(window, cx, "Creating instance".into(),
+                "Error creating instance from template".into(), modal_action);
+        }));
+
+        let content = v_flex()
+            .gap_3()
+            .child(crate::labelled("Template", v_flex()
+                .gap_1()
+                .child(Select::new(&self.template_select_state))
+                .children(template_info.map(|info| div().text_sm().text_color(cx.theme().muted_foreground).child(info)))))
+            .child(crate::labelled(
+                "Name",
+                Input::new(&self.name_input_state).when(self.name_invalid, |this| this.border_color(cx.theme().danger)),
+            ))
+            .child(Checkbox::new("change-version")
+                .label("Use a different Minecraft version")
+                .checked(self.change_version)
+                .on_click(cx.listener(|this, value, _, cx| {
+                    this.change_version = *value;
+                    cx.notify();
+                })))
+            .when(self.change_version, |this| {
+                this.child(v_flex()
+                    .gap_1()
+                    .child(Select::new(&self.version_select_state).title_prefix("Minecraft Version: "))
+                    .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                        .child("Modrinth content is updated to the selected version, other content can't be carried over")))
+            })
+            .child(if self.name_invalid { create_button } else { create_button.success() });
+
+        modal.child(content)
+    }
+}
+
+pub fn open_create_from_template(
+    metadata: Entity<FrontendMetadata>,
+    instances: Entity<InstanceEntries>,
+    backend_handle: BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let state = cx.new(|cx| {
+        CreateFromTemplateModalState::new(metadata, instances, backend_handle, window, cx)
+    });
+
+    window.open_dialog(cx, move |modal, window, cx| {
+        cx.update_entity(&state, |state, cx| {
+            state.render(modal, window, cx)
+        })
+    });
+}
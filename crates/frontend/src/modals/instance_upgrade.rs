@@ -0,0 +1,60 @@
+use bridge::{handle::BackendHandle, instance::InstanceID, message::MessageToBackend, modal_action::ModalAction};
+use gpui::{prelude::*, *};
+use gpui_component::{button::{Button, ButtonVariants}, notification::Notification, IconName, Sizable, WindowExt};
+use ustr::Ustr;
+
+struct InstanceUpgradeNotificationType;
+
+pub fn notify_upgrade_available(id: InstanceID, name: Ustr, version: Ustr, backend_handle: BackendHandle, window: &mut Window, cx: &mut App) {
+    let notification = Notification::new()
+        .id1::<InstanceUpgradeNotificationType>(SharedString::new(name.as_str()))
+        .title(format!("{name} can be upgraded to Minecraft {version}"))
+        .content(move |_, _, _| {
+            let backend_handle = backend_handle.clone();
+            Button::new("upgrade-instance")
+                .success()
+                .small()
+                .icon(IconName::ArrowUp)
+                .label("Upgrade")
+                .on_click(move |_, window, cx| {
+                    let modal_action = ModalAction::retryable(move |modal_action| MessageToBackend::UpgradeInstance { id, version, modal_action });
+                    backend_handle.send(MessageToBackend::UpgradeInstance {
+                        id,
+                        version,
+                        modal_action: modal_action.clone(),
+                    });
+                    let title = format!("Upgrading {name} to Minecraft {version}").into();
+                    crate::modals::generic::show_modal(window, cx, title, "Error upgrading instance".into(), modal_action);
+                })
+                .into_any_element()
+        })
+        .autohide(false);
+
+    window.push_notification(notification, cx);
+}
+
+pub fn notify_upgraded(id: InstanceID, name: Ustr, from: Ustr, to: Ustr, backend_handle: BackendHandle, window: &mut Window, cx: &mut App) {
+    let notification = Notification::new()
+        .id1::<InstanceUpgradeNotificationType>(SharedString::new(name.as_str()))
+        .title(format!("{name} was upgraded to Minecraft {to}"))
+        .content(move |_, _, _| {
+            let backend_handle = backend_handle.clone();
+            Button::new("restore-instance")
+                .small()
+                .icon(IconName::Undo)
+                .label(format!("Roll back to {from}"))
+                .on_click(move |_, window, cx| {
+                    let modal_action = ModalAction::default();
+                    backend_handle.send(MessageToBackend::RestoreInstanceSnapshot {
+                        id,
+                        modal_action: modal_action.clone(),
+                    });
+                    let title = format!("Restoring {name}").into();
+                    crate::modals::generic::show_modal(window, cx, title, "Error restoring instance".into(), modal_action);
+                })
+                .into_any_element()
+        })
+        .autohide(false);
+
+    window.push_notification(notification, cx);
+}
@@ -0,0 +1,52 @@
+use bridge::{handle::BackendHandle, instance::InstanceID, message::JvmCrashReport};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    alert::Alert, button::{Button, ButtonVariants}, h_flex, v_flex, ActiveTheme, IconName, Sizable, WindowExt
+};
+use ustr::Ustr;
+
+pub fn open(id: InstanceID, name: Ustr, report: JvmCrashReport, backend_handle: BackendHandle, window: &mut Window, cx: &mut App) {
+    let title = SharedString::new(format!("{name} crashed"));
+    let folder = report.path.parent().map(|parent| parent.to_path_buf());
+    let path = SharedString::new(format!("{}", report.path.display()));
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let muted = cx.theme().muted_foreground;
+        let detail = |label: &'static str, value: &Option<std::sync::Arc<str>>| {
+            value.as_ref().map(|value| crate::labelled(label, div().text_sm().text_color(muted).child(SharedString::new(value.as_ref()))))
+        };
+
+        let backend_handle = backend_handle.clone();
+        dialog.title(title.clone())
+            .width(px(600.0))
+            .child(v_flex()
+                .gap_3()
+                .child("Java crashed outside of the game's code, so no Minecraft crash report was created")
+                .when_some(report.guidance.clone(), |this, guidance| {
+                    this.child(Alert::new("guidance", SharedString::new(guidance.as_ref()))
+                        .icon(IconName::TriangleAlert)
+                        .title("Suggestion"))
+                })
+                .children(detail("Error", &report.error))
+                .children(detail("Problematic frame", &report.problematic_frame))
+                .children(detail("Native library", &report.library))
+                .children(detail("Java version", &report.java_version))
+                .child(crate::labelled("Crash log", div().text_sm().text_color(muted).child(path.clone())))
+                .child(h_flex()
+                    .gap_2()
+                    .justify_end()
+                    .when_some(folder.clone(), |this, folder| {
+                        this.child(Button::new("open-folder").small().label("Open Folder").on_click(move |_, window, cx| {
+                            crate::open_folder(&folder, window, cx);
+                        }))
+                    })
+                    .child(Button::new("report").small().label("Report a Problem").on_click(move |_, window, cx| {
+                        window.close_dialog(cx);
+                        let name = SharedString::new(name.as_str());
+                        crate::modals::report_problem::open_report_problem(Some((id, name)), backend_handle.clone(), window, cx);
+                    }))
+                    .child(Button::new("close").small().primary().label("Close").on_click(|_, window, cx| {
+                        window.close_dialog(cx);
+                    }))))
+    });
+}
@@ -0,0 +1,72 @@
+use bridge::{handle::BackendHandle, instance::{InstanceID, InstanceLaunchCommand}, message::MessageToBackend, modal_action::ModalAction};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, dialog::Dialog, h_flex, scroll::ScrollableElement, text::TextView, v_flex, Sizable, WindowExt
+};
+
+pub fn start_show_launch_command(
+    id: InstanceID,
+    name: SharedString,
+    write_script: bool,
+    backend_handle: &BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let modal_action = ModalAction::default();
+    let (send, recv) = tokio::sync::oneshot::channel();
+
+    backend_handle.send(MessageToBackend::ResolveLaunchCommand {
+        id,
+        modal_action: modal_action.clone(),
+        write_script,
+        channel: send,
+    });
+
+    crate::modals::generic::show_notification(window, cx, "Error resolving launch command".into(), modal_action);
+
+    window.spawn(cx, async move |cx| {
+        let Ok(launch_command) = recv.await else {
+            return;
+        };
+        _ = cx.update(move |window, cx| {
+            open_launch_command(name, launch_command, window, cx);
+        });
+    }).detach();
+}
+
+fn open_launch_command(name: SharedString, launch_command: InstanceLaunchCommand, window: &mut Window, cx: &mut App) {
+    let title = SharedString::new(format!("Launch command for {}", name));
+    let source = SharedString::new(format!("```\n{}\n```", launch_command.command.trim_end()));
+
+    window.open_dialog(cx, move |dialog: Dialog, _, _| {
+        let command = launch_command.command.clone();
+        let script_path = launch_command.script_path.clone();
+
+        dialog
+            .title(title.clone())
+            .width(px(720.0))
+            .child(v_flex()
+                .gap_3()
+                .child(div()
+                    .max_h(px(480.0))
+                    .overflow_y_scrollbar()
+                    .child(TextView::markdown("launch-command", source.clone()).selectable(true)))
+                .children(script_path.clone().map(|script_path| {
+                    div().text_sm().child(SharedString::new(format!("Written to {}", script_path.display())))
+                }))
+                .child(h_flex()
+                    .gap_2()
+                    .justify_end()
+                    .when_some(script_path.and_then(|path| path.parent().map(|parent| parent.to_path_buf())), |this, folder| {
+                        this.child(Button::new("open-folder").small().label("Open Folder").on_click(move |_, window, cx| {
+                            crate::open_folder(&folder, window, cx);
+                        }))
+                    })
+                    .child(Button::new("copy").small().label("Copy").on_click(move |_, _, cx| {
+                        cx.write_to_clipboard(ClipboardItem::new_string(command.to_string()));
+                    }))
+                    .child(Button::new("close").small().primary().label("Close").on_click(|_, window, cx| {
+                        window.close_dialog(cx);
+                    }))))
+    });
+}
@@ -1,8 +1,31 @@
+pub mod background_jobs;
+pub mod backups;
+pub mod changelog;
+pub mod content_problems;
+pub mod content_profiles;
 pub mod create_instance;
 pub mod delete_instance;
+pub mod duplicate_instance;
+pub mod file_recovery;
+pub mod game_options;
 pub mod generic;
+pub mod import_files;
+pub mod instance_template;
+pub mod instance_upgrade;
+pub mod jvm_crash;
+pub mod launch_command;
+pub mod modrinth_account;
 pub mod modrinth_install;
 pub mod modrinth_install_auto;
+pub mod modrinth_install_multiple;
+pub mod parental_controls;
+pub mod report_problem;
 pub mod select_icon;
+pub mod server_properties;
+pub mod server_resource_packs;
 pub mod settings;
+pub mod share_code;
+pub mod stray_instances;
 pub mod update_prompt;
+pub mod verify_files;
+pub mod world_datapacks;
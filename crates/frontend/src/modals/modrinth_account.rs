@@ -0,0 +1,224 @@
+use std::sync::Arc;
+
+use bridge::{account::ModrinthAccount, handle::BackendHandle, instance::InstanceID, message::MessageToBackend, modal_action::ModalAction};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    ActiveTheme, IconName, Sizable, StyledExt, WindowExt, button::{Button, ButtonVariants}, h_flex, input::{Input, InputState}, scroll::ScrollableElement, sheet::Sheet, skeleton::Skeleton, spinner::Spinner, v_flex, Disableable
+};
+use schema::modrinth::{ModrinthNotification, ModrinthProject};
+
+use crate::{component::error_alert::ErrorAlert, ui::PageType};
+
+const MODRINTH_PAT_URL: &str = "https://modrinth.com/settings/pats";
+
+pub fn open_login(backend_handle: BackendHandle, window: &mut Window, cx: &mut App) {
+    let token_input = cx.new(|cx| {
+        InputState::new(window, cx).masked(true).placeholder("mrp_...")
+    });
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let token = token_input.read(cx).value();
+        let valid = !token.trim().is_empty();
+
+        let backend_handle = backend_handle.clone();
+        let mut sign_in_button = Button::new("sign_in").label("Sign In").disabled(!valid).on_click(move |_, window, cx| {
+            window.close_all_dialogs(cx);
+
+            let modal_action = ModalAction::default();
+            backend_handle.send(MessageToBackend::ModrinthLogin {
+                token: token.trim().into(),
+                modal_action: modal_action.clone(),
+            });
+            crate::modals::generic::show_modal(window, cx, "Signing into Modrinth".into(),
+                "Error signing into Modrinth".into(), modal_action);
+        });
+
+        if valid {
+            sign_in_button = sign_in_button.success();
+        }
+
+        dialog.title("Sign into Modrinth")
+            .child(v_flex()
+                .gap_2()
+                .child("Create a personal access token with the 'Read user data', 'Read notifications' and 'Read projects' scopes and paste it below")
+                .child(Button::new("create_token").info().icon(IconName::Globe).label("Open Modrinth token settings").on_click(|_, _, cx| {
+                    cx.open_url(MODRINTH_PAT_URL);
+                }))
+                .child(crate::labelled("Personal Access Token", Input::new(&token_input)))
+                .child(sign_in_button))
+    });
+}
+
+pub fn render_account_section(modrinth_account: Option<ModrinthAccount>, backend_handle: &BackendHandle, cx: &App) -> Div {
+    let content = match modrinth_account {
+        Some(account) => {
+            let relogin_backend_handle = backend_handle.clone();
+            let backend_handle = backend_handle.clone();
+            h_flex()
+                .gap_2()
+                .child(avatar(account.avatar_url.as_ref()).size_8().min_w_8().min_h_8().rounded_full())
+                .child(div().flex_1().child(SharedString::new(account.username)))
+                .when(account.needs_relogin, |this| {
+                    this.child(Button::new("modrinth_sign_in_again")
+                        .icon(IconName::TriangleAlert)
+                        .label("Sign in again")
+                        .tooltip("Modrinth rejected the token of this account, it may have expired")
+                        .warning()
+                        .small()
+                        .on_click(move |_, window, cx| {
+                            open_login(relogin_backend_handle.clone(), window, cx);
+                        }))
+                })
+                .child(Button::new("modrinth_sign_out").danger().small().label("Sign Out").on_click(move |_, _, _| {
+                    backend_handle.send(MessageToBackend::ModrinthLogout);
+                }))
+        },
+        None => {
+            let backend_handle = backend_handle.clone();
+            h_flex().child(Button::new("modrinth_sign_in").success().icon(IconName::Plus).label("Sign into Modrinth").on_click(move |_, window, cx| {
+                open_login(backend_handle.clone(), window, cx);
+            }))
+        },
+    };
+
+    v_flex()
+        .pt_4()
+        .gap_2()
+        .border_t_1()
+        .border_color(cx.theme().border)
+        .child(div().font_semibold().child("Modrinth"))
+        .child(content)
+}
+
+fn avatar(url: Option<&Arc<str>>) -> Img {
+    if let Some(url) = url && !url.is_empty() {
+        gpui::img(SharedUri::from(url))
+    } else {
+        gpui::img(ImageSource::Resource(Resource::Embedded("images/default_mod.png".into())))
+    }
+}
+
+struct FollowsSheetState {
+    follows: Option<Result<Arc<[ModrinthProject]>, Arc<str>>>,
+    _task: Task<()>,
+}
+
+pub fn open_follows(backend_handle: &BackendHandle, install_for: Option<InstanceID>, path: Arc<[PageType]>, window: &mut Window, cx: &mut App) {
+    let (send, recv) = tokio::sync::oneshot::channel();
+    backend_handle.send(MessageToBackend::GetModrinthFollows { channel: send });
+
+    let state = cx.new(|cx: &mut Context<FollowsSheetState>| FollowsSheetState {
+        follows: None,
+        _task: cx.spawn(async move |this, cx| {
+            let result = recv.await.unwrap_or_else(|_| Err("Request was cancelled".into()));
+            _ = this.update(cx, |this, cx| {
+                this.follows = Some(result);
+                cx.notify();
+            });
+        }),
+    });
+
+    window.open_sheet_at(gpui_component::Placement::Right, cx, move |sheet: Sheet, window, cx| {
+        let sheet = sheet.title("Followed Projects").overlay_top(crate::root::sheet_margin_top(window));
+        let content = match &state.read(cx).follows {
+            None => v_flex().child(h_flex().justify_center().child(Spinner::new().large())),
+            Some(Err(error)) => v_flex().child(ErrorAlert::new("follows_error", "Error requesting followed projects".into(), error.clone().into())),
+            Some(Ok(follows)) if follows.is_empty() => v_flex().child("You aren't following any projects"),
+            Some(Ok(follows)) => {
+                let theme = cx.theme();
+                v_flex().gap_2().children(follows.iter().enumerate().map(|(index, project)| {
+                    let icon = match &project.icon_url {
+                        Some(icon_url) if !icon_url.is_empty() => gpui::img(SharedUri::from(icon_url))
+                            .with_fallback(|| Skeleton::new().rounded_md().size_10().into_any_element()),
+                        _ => gpui::img(ImageSource::Resource(Resource::Embedded("images/default_mod.png".into()))),
+                    };
+                    let title = project.title.clone().map(SharedString::new).unwrap_or(SharedString::new_static("Unnamed"));
+                    let description = project.description.clone().map(SharedString::new).unwrap_or_default();
+                    let project_id = project.id.clone();
+                    let path = path.clone();
+
+                    h_flex()
+                        .id(("follow", index))
+                        .p_2()
+                        .gap_3()
+                        .rounded_lg()
+                        .border_1()
+                        .border_color(theme.border)
+                        .cursor_pointer()
+                        .hover(|this| this.bg(theme.secondary_hover))
+                        .on_click(move |_, window, cx| {
+                            window.close_sheet(cx);
+                            let page = PageType::ModrinthProject {
+                                project_id: project_id.as_ref().into(),
+                                installing_for: install_for,
+                            };
+                            crate::root::switch_page(page, &path, window, cx);
+                        })
+                        .child(icon.rounded_md().size_10().min_w_10().min_h_10())
+                        .child(v_flex()
+                            .flex_1()
+                            .overflow_hidden()
+                            .child(div().font_semibold().child(title))
+                            .child(div().text_sm().text_color(theme.muted_foreground).truncate().child(description)))
+                }))
+            },
+        };
+        sheet.child(content.overflow_y_scrollbar())
+    });
+}
+
+struct NotificationsSheetState {
+    notifications: Option<Result<Arc<[ModrinthNotification]>, Arc<str>>>,
+    _task: Task<()>,
+}
+
+pub fn open_notifications(backend_handle: &BackendHandle, window: &mut Window, cx: &mut App) {
+    let (send, recv) = tokio::sync::oneshot::channel();
+    backend_handle.send(MessageToBackend::GetModrinthNotifications { channel: send });
+
+    let state = cx.new(|cx: &mut Context<NotificationsSheetState>| NotificationsSheetState {
+        notifications: None,
+        _task: cx.spawn(async move |this, cx| {
+            let result = recv.await.unwrap_or_else(|_| Err("Request was cancelled".into()));
+            _ = this.update(cx, |this, cx| {
+                this.notifications = Some(result);
+                cx.notify();
+            });
+        }),
+    });
+
+    window.open_sheet_at(gpui_component::Placement::Right, cx, move |sheet: Sheet, window, cx| {
+        let sheet = sheet.title("Modrinth Notifications").overlay_top(crate::root::sheet_margin_top(window));
+        let content = match &state.read(cx).notifications {
+            None => v_flex().child(h_flex().justify_center().child(Spinner::new().large())),
+            Some(Err(error)) => v_flex().child(ErrorAlert::new("notifications_error", "Error requesting notifications".into(), error.clone().into())),
+            Some(Ok(notifications)) if notifications.is_empty() => v_flex().child("You have no notifications"),
+            Some(Ok(notifications)) => {
+                let theme = cx.theme();
+                v_flex().gap_2().children(notifications.iter().enumerate().map(|(index, notification)| {
+                    let link = format!("https://modrinth.com{}", notification.link);
+                    let created = notification.created.split('T').next().unwrap_or_default().to_string();
+
+                    v_flex()
+                        .id(("notification", index))
+                        .p_2()
+                        .gap_1()
+                        .rounded_lg()
+                        .border_1()
+                        .border_color(if notification.read { theme.border } else { theme.info })
+                        .cursor_pointer()
+                        .hover(|this| this.bg(theme.secondary_hover))
+                        .on_click(move |_, _, cx| {
+                            cx.open_url(&link);
+                        })
+                        .child(h_flex()
+                            .gap_2()
+                            .child(div().flex_1().font_semibold().child(SharedString::new(notification.title.clone())))
+                            .child(div().text_sm().text_color(theme.muted_foreground).child(created)))
+                        .child(div().text_sm().child(SharedString::new(notification.text.clone())))
+                }))
+            },
+        };
+        sheet.child(content.overflow_y_scrollbar())
+    });
+}
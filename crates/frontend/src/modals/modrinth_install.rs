@@ -684,6 +684,7 @@ impl InstallDialog {
                                 download: ContentDownload::Url {
                                     url: install_file.url.clone(),
                                     sha1: install_file.hashes.sha1.clone(),
+                                    sha512: install_file.hashes.sha512.clone(),
                                     size: install_file.size,
                                 },
                                 content_source: ContentSource::ModrinthProject {
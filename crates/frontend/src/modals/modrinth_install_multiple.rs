@@ -0,0 +1,140 @@
+use std::sync::Arc;
+
+use bridge::{instance::InstanceID, message::{BulkInstanceAction, MessageToBackend}, modal_action::ModalAction};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, checkbox::Checkbox, dialog::Dialog, h_flex, scroll::ScrollableElement, v_flex, ActiveTheme, Disableable
+};
+use rustc_hash::FxHashSet;
+use schema::modrinth::{ModrinthProjectType, ModrinthProjectVersion, ModrinthVersionStatus};
+
+use crate::{entity::DataEntities, pages::modrinth_project_page::ModrinthProjectPage};
+
+struct InstallMultipleTarget {
+    id: InstanceID,
+    name: SharedString,
+    // The newest compatible version, if there is one
+    version: Option<(Arc<str>, SharedString)>,
+}
+
+struct InstallMultipleModalState {
+    data: DataEntities,
+    project_id: Arc<str>,
+    targets: Vec<InstallMultipleTarget>,
+    selected: FxHashSet<InstanceID>,
+}
+
+impl InstallMultipleModalState {
+    fn new(project_id: Arc<str>, project_type: ModrinthProjectType, versions: &[ModrinthProjectVersion], data: DataEntities, cx: &App) -> Self {
+        let versions: Vec<&ModrinthProjectVersion> = versions.iter()
+            .filter(|version| !matches!(version.status, Some(ModrinthVersionStatus::Draft | ModrinthVersionStatus::Unlisted)))
+            .collect();
+
+        let targets: Vec<InstallMultipleTarget> = data.instances.read(cx).entries.values().map(|entry| {
+            let entry = entry.read(cx);
+            let minecraft_version = entry.configuration.minecraft_version;
+            let loader = entry.configuration.loader;
+            let version = versions.iter()
+                .find(|version| ModrinthProjectPage::is_compatible(version, project_type, minecraft_version, loader))
+                .map(|version| {
+                    let name = version.name.clone().or(version.version_number.clone()).unwrap_or_else(|| version.id.clone());
+                    (version.id.clone(), SharedString::new(name))
+                });
+            InstallMultipleTarget { id: entry.id, name: entry.name.clone(), version }
+        }).collect();
+
+        Self {
+            data,
+            project_id,
+            targets,
+            selected: FxHashSet::default(),
+        }
+    }
+
+    fn install(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let versions: Arc<[(InstanceID, Arc<str>)]> = self.targets.iter()
+            .filter(|target| self.selected.contains(&target.id))
+            .filter_map(|target| Some((target.id, target.version.as_ref()?.0.clone())))
+            .collect();
+        if versions.is_empty() {
+            return;
+        }
+
+        window.close_all_dialogs(cx);
+
+        let modal_action = ModalAction::default();
+        self.data.backend_handle.send(MessageToBackend::BulkInstanceAction {
+            ids: versions.iter().map(|(id, _)| *id).collect(),
+            action: BulkInstanceAction::InstallModrinthProject {
+                project_id: self.project_id.clone(),
+                versions,
+            },
+            modal_action: modal_action.clone(),
+        });
+        crate::modals::generic::show_modal(window, cx, "Installing to instances".into(),
+            "Error installing to some instances".into(), modal_action);
+    }
+
+    fn render(&mut self, modal: Dialog, title: SharedString, cx: &mut Context<Self>) -> Dialog {
+        let muted_foreground = cx.theme().muted_foreground;
+        let danger = cx.theme().danger;
+
+        let rows = self.targets.iter().enumerate().map(|(index, target)| {
+            let id = target.id;
+            let resolution = match &target.version {
+                Some((_, name)) => div().text_color(muted_foreground).child(name.clone()),
+                None => div().text_color(danger).child("No compatible version"),
+            };
+            h_flex()
+                .gap_4()
+                .justify_between()
+                .child(Checkbox::new(("instance", index))
+                    .label(target.name.clone())
+                    .checked(self.selected.contains(&id))
+                    .disabled(target.version.is_none())
+                    .on_click(cx.listener(move |this, value, _, cx| {
+                        if *value {
+                            this.selected.insert(id);
+                        } else {
+                            this.selected.remove(&id);
+                        }
+                        cx.notify();
+                    })))
+                .child(resolution.text_sm())
+        }).collect::<Vec<_>>();
+
+        let selected = self.selected.len();
+        let install_button = Button::new("install")
+            .label(if selected == 1 { "Install to 1 instance".into() } else { SharedString::new(format!("Install to {selected} instances")) })
+            .disabled(selected == 0)
+            .on_click(cx.listener(|this, _, window, cx| this.install(window, cx)));
+
+        let content = v_flex()
+            .gap_3()
+            .child(div().text_sm().text_color(muted_foreground)
+                .child("The newest version compatible with the Minecraft version and loader of each instance is installed"))
+            .child(v_flex().gap_2().max_h(px(400.0)).overflow_y_scrollbar().children(rows))
+            .child(if selected == 0 { install_button } else { install_button.success() });
+
+        modal.title(title).child(content)
+    }
+}
+
+pub fn open(
+    name: &str,
+    project_id: Arc<str>,
+    project_type: ModrinthProjectType,
+    versions: &[ModrinthProjectVersion],
+    data: &DataEntities,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let title = SharedString::new(format!("Install {name} to Instances"));
+    let state = cx.new(|cx| InstallMultipleModalState::new(project_id, project_type, versions, data.clone(), cx));
+
+    window.open_dialog(cx, move |modal, _, cx| {
+        cx.update_entity(&state, |state, cx| {
+            state.render(modal, title.clone(), cx)
+        })
+    });
+}
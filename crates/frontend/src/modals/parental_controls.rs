@@ -0,0 +1,197 @@
+use bridge::{handle::BackendHandle, message::{MessageToBackend, ParentalControlsStatus}};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, checkbox::Checkbox, dialog::Dialog, h_flex, input::{Input, InputEvent, InputState}, spinner::Spinner, v_flex, ActiveTheme, Disableable, Sizable, WindowExt
+};
+use schema::backend_config::ParentalRestrictions;
+
+struct ParentalControlsModalState {
+    backend_handle: BackendHandle,
+    status: Option<ParentalControlsStatus>,
+    pin_input_state: Entity<InputState>,
+    playtime_input_state: Entity<InputState>,
+    block_instance_creation: bool,
+    block_content_install: bool,
+    error: Option<SharedString>,
+    _task: Task<()>,
+}
+
+impl ParentalControlsModalState {
+    pub fn new(backend_handle: BackendHandle, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let pin_input_state = cx.new(|cx| InputState::new(window, cx).masked(true).placeholder("PIN"));
+        let playtime_input_state = cx.new(|cx| InputState::new(window, cx).placeholder("No limit"));
+        cx.subscribe(&pin_input_state, |_, _, _: &InputEvent, cx| cx.notify()).detach();
+
+        let mut this = Self {
+            backend_handle,
+            status: None,
+            pin_input_state,
+            playtime_input_state,
+            block_instance_creation: false,
+            block_content_install: false,
+            error: None,
+            _task: Task::ready(()),
+        };
+        this.update_status(window, cx);
+        this
+    }
+
+    fn update_status(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self._task = cx.spawn_in(window, async move |this, cx| {
+            let status: ParentalControlsStatus = recv.await.unwrap_or_default();
+            _ = this.update_in(cx, move |this, window, cx| {
+                this.block_instance_creation = status.restrictions.block_instance_creation;
+                this.block_content_install = status.restrictions.block_content_install;
+                let playtime = status.restrictions.daily_playtime_minutes.map(|minutes| minutes.to_string()).unwrap_or_default();
+                this.playtime_input_state.update(cx, |input, cx| {
+                    input.set_value(playtime, window, cx);
+                });
+                this.status = Some(status);
+                cx.notify();
+            });
+        });
+        self.backend_handle.send(MessageToBackend::GetParentalControlsStatus { channel: send });
+    }
+
+    fn restrictions(&self, cx: &App) -> ParentalRestrictions {
+        let playtime = self.playtime_input_state.read(cx).value();
+        ParentalRestrictions {
+            block_instance_creation: self.block_instance_creation,
+            block_content_install: self.block_content_install,
+            daily_playtime_minutes: playtime.trim().parse::<u32>().ok().filter(|minutes| *minutes > 0),
+        }
+    }
+
+    fn take_pin(&mut self, window: &mut Window, cx: &mut Context<Self>) -> SharedString {
+        self.pin_input_state.update(cx, |input, cx| {
+            let pin = input.value();
+            input.set_value("", window, cx);
+            pin
+        })
+    }
+
+    // Sends a message that requires the PIN, refreshing the status afterwards or showing an error if the PIN was wrong
+    fn send_with_pin(
+        &mut self,
+        message: impl FnOnce(SharedString, tokio::sync::oneshot::Sender<bool>) -> MessageToBackend,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let pin = self.take_pin(window, cx);
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self._task = cx.spawn_in(window, async move |this, cx| {
+            let correct = recv.await.unwrap_or(false);
+            _ = this.update_in(cx, move |this, window, cx| {
+                if correct {
+                    this.error = None;
+                    this.update_status(window, cx);
+                } else {
+                    this.error = Some("Incorrect PIN".into());
+                }
+                cx.notify();
+            });
+        });
+        self.backend_handle.send(message(pin, send));
+    }
+
+    pub fn render(&mut self, modal: Dialog, _window: &mut Window, cx: &mut Context<Self>) -> Dialog {
+        let modal = modal.title("Parental Controls").width(px(520.0));
+
+        let Some(status) = &self.status else {
+            return modal.child(h_flex().justify_center().child(Spinner::new().large()));
+        };
+
+        let enabled = status.enabled;
+        let unlocked = status.unlocked;
+        let has_pin = !self.pin_input_state.read(cx).value().trim().is_empty();
+
+        let description = if !enabled {
+            "Restrict what can be done in the launcher. Changing or disabling these restrictions requires the PIN"
+        } else if unlocked {
+            "Parental controls are unlocked until the launcher is closed or locked again"
+        } else {
+            "Parental controls are enabled"
+        };
+
+        let played_minutes = status.playtime_today_seconds / 60;
+
+        let save_label = if enabled { "Save" } else { "Enable parental controls" };
+        let save_button = Button::new("save").success().label(save_label).disabled(!has_pin).on_click(cx.listener(|this, _, window, cx| {
+            let restrictions = this.restrictions(cx);
+            this.send_with_pin(|pin, channel| MessageToBackend::ConfigureParentalControls {
+                pin: pin.as_str().into(),
+                restrictions: Some(restrictions),
+                channel,
+            }, window, cx);
+        }));
+
+        let content = v_flex()
+            .gap_3()
+            .child(description)
+            .when(enabled, |this| {
+                this.child(div().text_sm().text_color(cx.theme().muted_foreground)
+                    .child(format!("Played today: {}h {}m", played_minutes / 60, played_minutes % 60)))
+            })
+            .child(Checkbox::new("block-instance-creation")
+                .label("Prevent creating instances")
+                .checked(self.block_instance_creation)
+                .on_click(cx.listener(|this, value, _, cx| {
+                    this.block_instance_creation = *value;
+                    cx.notify();
+                })))
+            .child(Checkbox::new("block-content-install")
+                .label("Prevent installing mods and other content")
+                .checked(self.block_content_install)
+                .on_click(cx.listener(|this, value, _, cx| {
+                    this.block_content_install = *value;
+                    cx.notify();
+                })))
+            .child(crate::labelled("Daily playtime limit (minutes)", Input::new(&self.playtime_input_state)))
+            .child(crate::labelled(if enabled { "PIN" } else { "New PIN" }, Input::new(&self.pin_input_state)))
+            .when_some(self.error.clone(), |this, error| {
+                this.child(div().text_color(cx.theme().danger).child(error))
+            })
+            .child(h_flex()
+                .gap_2()
+                .justify_end()
+                .when(enabled && !unlocked, |this| {
+                    this.child(Button::new("unlock").label("Unlock").disabled(!has_pin).on_click(cx.listener(|this, _, window, cx| {
+                        this.send_with_pin(|pin, channel| MessageToBackend::UnlockParentalControls {
+                            pin: pin.as_str().into(),
+                            channel,
+                        }, window, cx);
+                    })))
+                })
+                .when(enabled && unlocked, |this| {
+                    this.child(Button::new("lock").label("Lock").on_click(cx.listener(|this, _, window, cx| {
+                        this.backend_handle.send(MessageToBackend::LockParentalControls);
+                        this.update_status(window, cx);
+                    })))
+                })
+                .when(enabled, |this| {
+                    this.child(Button::new("disable").danger().label("Disable").disabled(!has_pin).on_click(cx.listener(|this, _, window, cx| {
+                        this.send_with_pin(|pin, channel| MessageToBackend::ConfigureParentalControls {
+                            pin: pin.as_str().into(),
+                            restrictions: None,
+                            channel,
+                        }, window, cx);
+                    })))
+                })
+                .child(save_button));
+
+        modal.child(content)
+    }
+}
+
+pub fn open_parental_controls(backend_handle: BackendHandle, window: &mut Window, cx: &mut App) {
+    let state = cx.new(|cx| {
+        ParentalControlsModalState::new(backend_handle, window, cx)
+    });
+
+    window.open_dialog(cx, move |modal, window, cx| {
+        cx.update_entity(&state, |state, cx| {
+            state.render(modal, window, cx)
+        })
+    });
+}
@@ -0,0 +1,121 @@
+use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
+
+use bridge::{handle::BackendHandle, instance::InstanceID, message::{MessageToBackend, ProblemReport}, modal_action::ModalAction};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, checkbox::Checkbox, h_flex, input::{Input, InputState}, v_flex, Disableable, Sizable, WindowExt
+};
+
+pub fn open_report_problem(
+    instance: Option<(InstanceID, SharedString)>,
+    backend_handle: BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let description_input = cx.new(|cx| {
+        InputState::new(window, cx)
+            .multi_line(true)
+            .placeholder("What happened, and what did you expect to happen?")
+    });
+    let open_issue = Arc::new(AtomicBool::new(true));
+
+    let title = if let Some((_, name)) = &instance {
+        SharedString::new(format!("Report a Problem with {}", name))
+    } else {
+        SharedString::new_static("Report a Problem")
+    };
+    let included = if instance.is_some() {
+        "The launcher logs, the latest game log and crash report, the instance configuration and system information will be bundled into an archive. Usernames in paths and access tokens are removed"
+    } else {
+        "The launcher logs and system information will be bundled into an archive. Usernames in paths and access tokens are removed"
+    };
+    let id = instance.map(|(id, _)| id);
+
+    window.open_dialog(cx, move |dialog, _, cx| {
+        let description = description_input.read(cx).value();
+        let valid = !description.trim().is_empty();
+
+        let backend_handle = backend_handle.clone();
+        let open_issue_value = open_issue.load(Ordering::Relaxed);
+        let create_button = Button::new("create").label("Create report").success().disabled(!valid).on_click(move |_, window, cx| {
+            window.close_all_dialogs(cx);
+            start_create_report(id, description.as_str().into(), open_issue_value, &backend_handle, window, cx);
+        });
+
+        dialog.title(title.clone())
+            .width(px(560.0))
+            .child(v_flex()
+                .gap_3()
+                .child(included)
+                .child(crate::labelled("Description", Input::new(&description_input)))
+                .child(Checkbox::new("open_issue")
+                    .checked(open_issue_value)
+                    .label("Open a prefilled GitHub issue")
+                    .on_click({
+                        let open_issue = open_issue.clone();
+                        move |checked, window, _| {
+                            open_issue.store(*checked, Ordering::Relaxed);
+                            window.refresh();
+                        }
+                    }))
+                .child(h_flex().justify_end().child(create_button)))
+    });
+}
+
+fn start_create_report(
+    instance: Option<InstanceID>,
+    description: Arc<str>,
+    open_issue: bool,
+    backend_handle: &BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let modal_action = ModalAction::default();
+    let (send, recv) = tokio::sync::oneshot::channel();
+
+    backend_handle.send(MessageToBackend::CreateProblemReport {
+        instance,
+        description,
+        modal_action: modal_action.clone(),
+        channel: send,
+    });
+
+    crate::modals::generic::show_notification(window, cx, "Error creating problem report".into(), modal_action);
+
+    window.spawn(cx, async move |cx| {
+        let Ok(report) = recv.await else {
+            return;
+        };
+        _ = cx.update(move |window, cx| {
+            if open_issue {
+                cx.open_url(&report.issue_url);
+            }
+            open_report_created(report, window, cx);
+        });
+    }).detach();
+}
+
+fn open_report_created(report: ProblemReport, window: &mut Window, cx: &mut App) {
+    let message = SharedString::new(format!("Saved to {}", report.path.display()));
+    let folder = report.path.parent().map(|parent| parent.to_path_buf());
+
+    window.open_dialog(cx, move |dialog, _, _| {
+        dialog
+            .title("Problem Report Created")
+            .child(v_flex()
+                .gap_3()
+                .child(message.clone())
+                .child("Attach the archive when reporting the problem")
+                .child(h_flex()
+                    .gap_2()
+                    .justify_end()
+                    .when_some(folder.clone(), |this, folder| {
+                        this.child(Button::new("open-folder").small().label("Open Folder").on_click(move |_, window, cx| {
+                            crate::open_folder(&folder, window, cx);
+                        }))
+                    })
+                    .child(Button::new("close").small().primary().label("Close").on_click(|_, window, cx| {
+                        window.close_dialog(cx);
+                    }))))
+    });
+}
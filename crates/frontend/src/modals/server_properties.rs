@@ -0,0 +1,205 @@
+use bridge::{handle::BackendHandle, instance::{InstanceID, InstanceServerProperties, SERVER_DIFFICULTIES, SERVER_GAMEMODES}, message::MessageToBackend};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    checkbox::Checkbox, dialog::Dialog, h_flex, input::{Input, InputState, NumberInput}, select::{Select, SelectState}, spinner::Spinner, v_flex, ActiveTheme, Sizable, WindowExt
+};
+
+struct ServerPropertiesModalState {
+    instance_id: InstanceID,
+    backend_handle: BackendHandle,
+    loaded: bool,
+    // The backend dropped the request without answering, e.g. because server.properties couldn't be read
+    load_failed: bool,
+    properties: InstanceServerProperties,
+    motd_input_state: Entity<InputState>,
+    port_input_state: Entity<InputState>,
+    max_players_input_state: Entity<InputState>,
+    view_distance_input_state: Entity<InputState>,
+    gamemode_select_state: Entity<SelectState<Vec<&'static str>>>,
+    difficulty_select_state: Entity<SelectState<Vec<&'static str>>>,
+    _get_properties_task: Task<()>,
+}
+
+impl ServerPropertiesModalState {
+    pub fn new(
+        instance_id: InstanceID,
+        backend_handle: BackendHandle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let motd_input_state = cx.new(|cx| InputState::new(window, cx).placeholder("A Minecraft Server"));
+        let port_input_state = cx.new(|cx| InputState::new(window, cx).placeholder("25565"));
+        cx.subscribe_in(&port_input_state, window, crate::step_number_input).detach();
+        let max_players_input_state = cx.new(|cx| InputState::new(window, cx).placeholder("20"));
+        cx.subscribe_in(&max_players_input_state, window, crate::step_number_input).detach();
+        let view_distance_input_state = cx.new(|cx| InputState::new(window, cx).placeholder("10"));
+        cx.subscribe_in(&view_distance_input_state, window, crate::step_number_input).detach();
+
+        let gamemode_select_state = cx.new(|cx| SelectState::new(SERVER_GAMEMODES.to_vec(), None, window, cx));
+        let difficulty_select_state = cx.new(|cx| SelectState::new(SERVER_DIFFICULTIES.to_vec(), None, window, cx));
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        let _get_properties_task = cx.spawn_in(window, async move |this, cx| {
+            let Ok(properties) = recv.await else {
+                _ = this.update(cx, |this, cx| {
+                    this.load_failed = true;
+                    cx.notify();
+                });
+                return;
+            };
+            _ = this.update_in(cx, move |this, window, cx| {
+                this.set_properties(properties, window, cx);
+            });
+        });
+
+        backend_handle.send(MessageToBackend::GetServerProperties {
+            id: instance_id,
+            channel: send,
+        });
+
+        Self {
+            instance_id,
+            backend_handle,
+            loaded: false,
+            load_failed: false,
+            properties: InstanceServerProperties::default(),
+            motd_input_state,
+            port_input_state,
+            max_players_input_state,
+            view_distance_input_state,
+            gamemode_select_state,
+            difficulty_select_state,
+            _get_properties_task,
+        }
+    }
+
+    fn set_properties(&mut self, properties: InstanceServerProperties, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(motd) = &properties.motd {
+            self.motd_input_state.update(cx, |input, cx| {
+                input.set_value(SharedString::new(motd.clone()), window, cx);
+            });
+        }
+        for (input_state, value) in [
+            (&self.port_input_state, properties.server_port.map(u32::from)),
+            (&self.max_players_input_state, properties.max_players),
+            (&self.view_distance_input_state, properties.view_distance),
+        ] {
+            if let Some(value) = value {
+                input_state.update(cx, |input, cx| {
+                    input.set_value(value.to_string(), window, cx);
+                });
+            }
+        }
+        if let Some(gamemode) = SERVER_GAMEMODES.iter().find(|v| Some(**v) == properties.gamemode.as_deref()) {
+            self.gamemode_select_state.update(cx, |select, cx| {
+                select.set_selected_value(gamemode, window, cx);
+            });
+        }
+        if let Some(difficulty) = SERVER_DIFFICULTIES.iter().find(|v| Some(**v) == properties.difficulty.as_deref()) {
+            self.difficulty_select_state.update(cx, |select, cx| {
+                select.set_selected_value(difficulty, window, cx);
+            });
+        }
+        self.properties = properties;
+        self.loaded = true;
+        cx.notify();
+    }
+
+    fn get_properties(&self, cx: &App) -> InstanceServerProperties {
+        let motd = self.motd_input_state.read(cx).value();
+
+        InstanceServerProperties {
+            eula_accepted: self.properties.eula_accepted,
+            // An empty field only leaves the MOTD alone if there wasn't one, otherwise it clears it
+            motd: if motd.is_empty() && self.properties.motd.is_none() { None } else { Some(motd.as_str().into()) },
+            server_port: self.port_input_state.read(cx).value().parse().ok(),
+            max_players: self.max_players_input_state.read(cx).value().parse().ok(),
+            gamemode: self.gamemode_select_state.read(cx).selected_value().map(|v| (*v).into()),
+            difficulty: self.difficulty_select_state.read(cx).selected_value().map(|v| (*v).into()),
+            view_distance: self.view_distance_input_state.read(cx).value().parse().ok(),
+            online_mode: self.properties.online_mode,
+            pvp: self.properties.pvp,
+            white_list: self.properties.white_list,
+        }
+    }
+
+    pub fn render(&mut self, modal: Dialog, _window: &mut Window, cx: &mut Context<Self>) -> Dialog {
+        let modal = modal.title("Server Properties");
+
+        if self.load_failed {
+            return modal.child(div().text_color(cx.theme().danger).child("Unable to read the server properties of this instance"));
+        }
+        if !self.loaded {
+            return modal.child(h_flex().justify_center().child(Spinner::new().large()));
+        }
+
+        let content = v_flex()
+            .gap_3()
+            .child(crate::labelled("MOTD", Input::new(&self.motd_input_state).small()))
+            .child(crate::labelled("Port", NumberInput::new(&self.port_input_state).small()))
+            .child(crate::labelled("Max Players", NumberInput::new(&self.max_players_input_state).small()))
+            .child(crate::labelled("View Distance", NumberInput::new(&self.view_distance_input_state).small().suffix("chunks")))
+            .child(crate::labelled("Gamemode", Select::new(&self.gamemode_select_state).placeholder("survival")))
+            .child(crate::labelled("Difficulty", Select::new(&self.difficulty_select_state).placeholder("easy")))
+            .child(Checkbox::new("online-mode")
+                .label("Online Mode")
+                .checked(self.properties.online_mode.unwrap_or(true))
+                .on_click(cx.listener(|this, value, _, cx| {
+                    this.properties.online_mode = Some(*value);
+                    cx.notify();
+                })))
+            .child(Checkbox::new("pvp")
+                .label("PvP")
+                .checked(self.properties.pvp.unwrap_or(true))
+                .on_click(cx.listener(|this, value, _, cx| {
+                    this.properties.pvp = Some(*value);
+                    cx.notify();
+                })))
+            .child(Checkbox::new("white-list")
+                .label("Whitelist")
+                .checked(self.properties.white_list.unwrap_or(false))
+                .on_click(cx.listener(|this, value, _, cx| {
+                    this.properties.white_list = Some(*value);
+                    cx.notify();
+                })))
+            .child(Checkbox::new("eula")
+                .label("I agree to the Minecraft EULA (https://aka.ms/MinecraftEULA)")
+                .checked(self.properties.eula_accepted)
+                .disabled(self.properties.eula_accepted)
+                .on_click(cx.listener(|this, value, _, cx| {
+                    this.properties.eula_accepted = *value;
+                    cx.notify();
+                })));
+
+        let entity = cx.entity();
+        modal
+            .confirm()
+            .on_ok(move |_, _, cx| {
+                entity.update(cx, |this, cx| {
+                    this.backend_handle.send(MessageToBackend::SetServerProperties {
+                        id: this.instance_id,
+                        properties: this.get_properties(cx),
+                    });
+                    true
+                })
+            })
+            .child(content)
+    }
+}
+
+pub fn open_server_properties(
+    instance_id: InstanceID,
+    backend_handle: BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let state = cx.new(|cx| {
+        ServerPropertiesModalState::new(instance_id, backend_handle, window, cx)
+    });
+
+    window.open_dialog(cx, move |modal, window, cx| {
+        cx.update_entity(&state, |state, cx| {
+            state.render(modal, window, cx)
+        })
+    });
+}
@@ -0,0 +1,242 @@
+use std::{path::Path, sync::Arc};
+
+use bridge::{
+    instance::{InstanceID, InstanceServerResourcePack, ServerResourcePackSource}, message::MessageToBackend
+};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, h_flex, input::{Input, InputState}, notification::{Notification, NotificationType}, scroll::ScrollableElement, sheet::Sheet, spinner::Spinner, v_flex, ActiveTheme, Icon, Sizable, StyledExt, WindowExt
+};
+use uuid::Uuid;
+
+use crate::{component::error_alert::ErrorAlert, entity::DataEntities};
+
+struct ServerResourcePacksState {
+    id: InstanceID,
+    data: DataEntities,
+    packs: Option<Result<Arc<[InstanceServerResourcePack]>, Arc<str>>>,
+    url_input: Entity<InputState>,
+    pack_id_input: Entity<InputState>,
+    seeding: bool,
+    _task: Task<()>,
+}
+
+impl ServerResourcePacksState {
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self.data.backend_handle.send(MessageToBackend::GetServerResourcePacks {
+            id: self.id,
+            channel: send,
+        });
+
+        self._task = cx.spawn(async move |this, cx| {
+            let result = recv.await.unwrap_or_else(|_| Err("Request was cancelled".into()));
+            _ = this.update(cx, |this, cx| {
+                this.packs = Some(result);
+                cx.notify();
+            });
+        });
+    }
+
+    fn delete(&mut self, paths: Vec<Arc<Path>>, window: &mut Window, cx: &mut Context<Self>) {
+        if paths.is_empty() {
+            return;
+        }
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self.data.backend_handle.send(MessageToBackend::DeleteServerResourcePacks {
+            id: self.id,
+            paths,
+            channel: send,
+        });
+
+        self._task = cx.spawn_in(window, async move |this, cx| {
+            let result = recv.await.unwrap_or_else(|_| Err("Request was cancelled".into()));
+            _ = this.update_in(cx, |this, window, cx| {
+                if let Err(error) = result {
+                    push_error(window, cx, "Unable to delete server resource pack", error);
+                }
+                this.reload(cx);
+            });
+        });
+    }
+
+    fn seed(&mut self, source: ServerResourcePackSource, window: &mut Window, cx: &mut Context<Self>) {
+        let pack_id = self.pack_id_input.read(cx).value();
+        let pack_id = pack_id.trim();
+        let pack_id = if pack_id.is_empty() {
+            None
+        } else if let Ok(pack_id) = Uuid::try_parse(pack_id) {
+            Some(pack_id)
+        } else {
+            push_error(window, cx, "Unable to add server resource pack", "The pack id isn't a valid UUID".into());
+            return;
+        };
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self.data.backend_handle.send(MessageToBackend::SeedServerResourcePack {
+            id: self.id,
+            source,
+            pack_id,
+            channel: send,
+        });
+        self.seeding = true;
+        cx.notify();
+
+        self._task = cx.spawn_in(window, async move |this, cx| {
+            let result = recv.await.unwrap_or_else(|_| Err("Request was cancelled".into()));
+            _ = this.update_in(cx, |this, window, cx| {
+                this.seeding = false;
+                if let Err(error) = result {
+                    push_error(window, cx, "Unable to add server resource pack", error);
+                }
+                this.reload(cx);
+            });
+        });
+    }
+
+    fn render_packs(&self, cx: &mut Context<Self>) -> AnyElement {
+        let packs = match &self.packs {
+            None => return h_flex().justify_center().child(Spinner::new().large()).into_any_element(),
+            Some(Err(error)) => return ErrorAlert::new("server_packs_error", "Unable to load server resource packs".into(), error.clone().into()).into_any_element(),
+            Some(Ok(packs)) if packs.is_empty() => return div().child("No server resource packs have been downloaded yet").into_any_element(),
+            Some(Ok(packs)) => packs.clone(),
+        };
+
+        let theme = cx.theme();
+        let border = theme.border;
+        let muted = theme.muted_foreground;
+        let trash_icon = Icon::default().path("icons/trash-2.svg");
+
+        v_flex().gap_2().children(packs.iter().enumerate().map(|(index, pack)| {
+            let mut details = format!("{:.1} MiB", pack.size as f64 / (1024.0 * 1024.0));
+            if let Some(pack_id) = &pack.pack_id {
+                details.push_str(&format!(", pack id {pack_id}"));
+            }
+
+            let path = pack.path.clone();
+            h_flex()
+                .p_2()
+                .gap_3()
+                .rounded_lg()
+                .border_1()
+                .border_color(border)
+                .child(v_flex()
+                    .flex_1()
+                    .overflow_hidden()
+                    .child(div().font_semibold().truncate().font_family("Roboto Mono").child(SharedString::new(pack.sha1.clone())))
+                    .child(div().text_sm().text_color(muted).truncate().child(details)))
+                .child(Button::new(("delete", index)).danger().small().icon(trash_icon.clone()).tooltip("Delete").on_click(
+                    cx.listener(move |this, _, window, cx| {
+                        this.delete(vec![path.clone()], window, cx);
+                    }),
+                ))
+        })).into_any_element()
+    }
+}
+
+fn push_error(window: &mut Window, cx: &mut App, title: &'static str, error: Arc<str>) {
+    let notification = Notification::new()
+        .autohide(false)
+        .with_type(NotificationType::Error)
+        .title(title)
+        .message(SharedString::new(error));
+    window.push_notification(notification, cx);
+}
+
+pub fn open(id: InstanceID, title: SharedString, data: &DataEntities, window: &mut Window, cx: &mut App) {
+    let url_input = cx.new(|cx| InputState::new(window, cx).placeholder("Resource pack URL"));
+    let pack_id_input = cx.new(|cx| InputState::new(window, cx).placeholder("Pack id (optional, 1.20.3 and newer)"));
+
+    let state = cx.new(|cx| {
+        let mut state = ServerResourcePacksState {
+            id,
+            data: data.clone(),
+            packs: None,
+            url_input,
+            pack_id_input,
+            seeding: false,
+            _task: Task::ready(()),
+        };
+        state.reload(cx);
+        state
+    });
+
+    window.open_sheet_at(gpui_component::Placement::Right, cx, move |sheet: Sheet, window, cx| {
+        let sheet = sheet.title(SharedString::new(format!("Server resource packs for {title}"))).overlay_top(crate::root::sheet_margin_top(window));
+
+        let (url_input, pack_id_input, seeding, cached_paths) = {
+            let state = state.read(cx);
+            let cached_paths: Vec<Arc<Path>> = match &state.packs {
+                Some(Ok(packs)) => packs.iter().map(|pack| pack.path.clone()).collect(),
+                _ => Vec::new(),
+            };
+            (state.url_input.clone(), state.pack_id_input.clone(), state.seeding, cached_paths)
+        };
+
+        let buttons = h_flex()
+            .gap_2()
+            .child(Button::new("add_file").success().small().label("Add from file").disabled(seeding).on_click({
+                let state = state.clone();
+                move |_, window, cx| {
+                    let receiver = cx.prompt_for_paths(PathPromptOptions {
+                        files: true,
+                        directories: false,
+                        multiple: false,
+                        prompt: Some("Select a resource pack".into())
+                    });
+                    let state = state.clone();
+                    window.spawn(cx, async move |cx| {
+                        let Ok(Ok(Some(paths))) = receiver.await else {
+                            return;
+                        };
+                        let Some(path) = paths.into_iter().next() else {
+                            return;
+                        };
+                        _ = cx.update(move |window, cx| {
+                            state.update(cx, |state, cx| {
+                                state.seed(ServerResourcePackSource::File(path.into()), window, cx);
+                            });
+                        });
+                    }).detach();
+                }
+            }))
+            .child(Button::new("clear").danger().small().label("Clear cache").disabled(cached_paths.is_empty()).on_click({
+                let state = state.clone();
+                move |_, window, cx| {
+                    state.update(cx, |state, cx| {
+                        state.delete(cached_paths.clone(), window, cx);
+                    });
+                }
+            }));
+
+        let url_row = h_flex()
+            .gap_2()
+            .child(Input::new(&url_input).small().flex_1())
+            .child(Button::new("add_url").success().small().label("Add from URL").loading(seeding).disabled(seeding).on_click({
+                let state = state.clone();
+                let url_input = url_input.clone();
+                move |_, window, cx| {
+                    let url = url_input.read(cx).value().trim().to_string();
+                    if url.is_empty() {
+                        return;
+                    }
+                    state.update(cx, |state, cx| {
+                        state.seed(ServerResourcePackSource::Url(url.into()), window, cx);
+                    });
+                }
+            }));
+
+        let packs = cx.update_entity(&state, |state, cx| state.render_packs(cx));
+
+        sheet.child(v_flex()
+            .gap_3()
+            .child(buttons)
+            .child(url_row)
+            .child(Input::new(&pack_id_input).small())
+            .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                .child("Packs added here are used when joining a server that asks for the same pack, instead of downloading it again. \
+                    Minecraft 1.20.3 and newer also need the pack id the server sends"))
+            .child(v_flex().overflow_y_scrollbar().child(packs)))
+    });
+}
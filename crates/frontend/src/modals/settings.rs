@@ -1,23 +1,56 @@
 use std::{path::Path, sync::Arc};
 
-use bridge::{handle::BackendHandle, message::MessageToBackend};
-use gpui::*;
-use gpui_component::{button::{Button, ButtonVariants}, checkbox::Checkbox, select::{SearchableVec, Select, SelectEvent, SelectState}, sheet::Sheet, spinner::Spinner, tab::{Tab, TabBar, TabVariant}, v_flex, ActiveTheme, IconName, Sizable, ThemeRegistry};
-use schema::backend_config::BackendConfig;
+use bridge::{handle::BackendHandle, message::{JavaRuntimeSummary, MessageToBackend}, modal_action::ModalAction, sandbox::Sandbox};
+use gpui::{prelude::*, *};
+use gpui_component::{button::{Button, ButtonGroup, ButtonVariants}, checkbox::Checkbox, h_flex, input::{Input, InputEvent, InputState, NumberInput, NumberInputEvent, StepAction}, notification::{Notification, NotificationType}, select::{SearchableVec, Select, SelectEvent, SelectState}, sheet::Sheet, spinner::Spinner, tab::{Tab, TabBar, TabVariant}, v_flex, ActiveTheme, Disableable, IconName, IndexPath, Selectable, Sizable, ThemeRegistry, WindowExt};
+use schema::{backend_config::{BackendConfig, BackupConfiguration, MemoryBudgetMode, UpdateChannel}, instance::{InstanceDefaultsConfiguration, InstanceJvmBinaryConfiguration, InstanceJvmFlagsConfiguration, InstanceMemoryConfiguration, InstanceResolutionConfiguration}};
 
-use crate::{entity::DataEntities, interface_config::InterfaceConfig};
+use strum::IntoEnumIterator;
+
+use crate::{component::named_dropdown::{NamedDropdown, NamedDropdownItem}, entity::{DataEntities, instance::InstanceEntries}, interface_config::{InterfaceConfig, LaunchBehaviour, NewVersionNotifications, ThemeModePreference, FONT_SIZES, UI_SCALES}, keymap::{Shortcut, SHORTCUTS}};
 
 struct Settings {
     theme_folder: Arc<Path>,
+    logs_folder: Arc<Path>,
+    data_folder: Arc<Path>,
     theme_select: Entity<SelectState<SearchableVec<SharedString>>>,
+    theme_mode_select: Entity<SelectState<NamedDropdown<ThemeModePreference>>>,
+    ui_scale_select: Entity<SelectState<NamedDropdown<f32>>>,
+    font_size_select: Entity<SelectState<NamedDropdown<f32>>>,
     backend_handle: BackendHandle,
     pending_request: bool,
     backend_config: Option<BackendConfig>,
     get_configuration_task: Option<Task<()>>,
+    default_memory_enabled: bool,
+    default_memory_min_input_state: Entity<InputState>,
+    default_memory_max_input_state: Entity<InputState>,
+    default_jvm_flags_enabled: bool,
+    default_jvm_flags_input_state: Entity<InputState>,
+    default_jvm_binary_enabled: bool,
+    default_jvm_binary_path: Option<Arc<Path>>,
+    default_resolution_enabled: bool,
+    default_resolution_fullscreen: bool,
+    default_resolution_width_input_state: Entity<InputState>,
+    default_resolution_height_input_state: Entity<InputState>,
+    natives_repositories_input_state: Entity<InputState>,
+    tunnel_command_input_state: Entity<InputState>,
+    max_running_instances_input_state: Entity<InputState>,
+    backups_kept_input_state: Entity<InputState>,
+    global_hotkey_input_state: Entity<InputState>,
+    shortcut_input_states: Vec<(&'static Shortcut, Entity<InputState>)>,
+    launch_behaviour_select: Entity<SelectState<NamedDropdown<LaunchBehaviour>>>,
+    auto_launch_select: Entity<SelectState<NamedDropdown<Option<SharedString>>>>,
+    new_version_notifications_select: Entity<SelectState<NamedDropdown<NewVersionNotifications>>>,
+    java_runtimes: Option<Arc<[JavaRuntimeSummary]>>,
+    _get_java_runtimes_task: Task<()>,
+    _select_file_task: Task<()>,
 }
 
 pub fn build_settings_sheet(data: &DataEntities, window: &mut Window, cx: &mut App) -> impl Fn(Sheet, &mut Window, &mut App) -> Sheet + 'static {
     let theme_folder = data.theme_folder.clone();
+    let logs_folder = data.logs_folder.clone();
+    let data_folder = data.data_folder.clone();
+    let instances = data.instances.clone();
     let settings = cx.new(|cx| {
         let theme_select_delegate = SearchableVec::new(ThemeRegistry::global(cx).sorted_themes()
             .iter().map(|cfg| cfg.name.clone()).collect::<Vec<_>>());
@@ -33,25 +66,231 @@ pub fn build_settings_sheet(data: &DataEntities, window: &mut Window, cx: &mut A
                 return;
             };
 
-            InterfaceConfig::get_mut(cx).active_theme = theme_name.clone();
+            crate::theme::select(SharedString::new(theme_name.trim_ascii()), cx);
+        }).detach();
+
+        let theme_mode_select = cx.new(|cx| {
+            let items = ThemeModePreference::iter().map(|mode| {
+                NamedDropdownItem { name: mode.name(), item: mode }
+            }).collect::<Vec<_>>();
+            let current = InterfaceConfig::get(cx).theme_mode;
+            let row = items.iter().position(|item| item.item == current).unwrap_or(0);
+            SelectState::new(NamedDropdown::new(items), Some(IndexPath::new(row)), window, cx)
+        });
+        cx.subscribe_in(&theme_mode_select, window, |settings: &mut Settings, _, event: &SelectEvent<NamedDropdown<ThemeModePreference>>, window, cx| {
+            let SelectEvent::Confirm(Some(value)) = event else {
+                return;
+            };
+            InterfaceConfig::get_mut(cx).theme_mode = value.item;
+            crate::theme::apply(cx);
+
+            let theme_name = cx.theme().theme_name().clone();
+            settings.theme_select.update(cx, |select, cx| {
+                select.set_selected_value(&theme_name, window, cx);
+            });
+        }).detach();
+
+        let ui_scale_select = cx.new(|cx| {
+            let items = UI_SCALES.iter().map(|scale| {
+                NamedDropdownItem { name: format!("{}%", (scale * 100.0).round()).into(), item: *scale }
+            }).collect::<Vec<_>>();
+            let current = InterfaceConfig::get(cx).ui_scale();
+            let row = items.iter().position(|item| item.item == current);
+            SelectState::new(NamedDropdown::new(items), row.map(IndexPath::new), window, cx)
+        });
+        cx.subscribe(&ui_scale_select, |_: &mut Settings, _, event: &SelectEvent<NamedDropdown<f32>>, cx| {
+            let SelectEvent::Confirm(Some(value)) = event else {
+                return;
+            };
+            InterfaceConfig::get_mut(cx).ui_scale = value.item;
+            crate::theme::apply(cx);
+        }).detach();
+
+        let font_size_select = cx.new(|cx| {
+            let items = FONT_SIZES.iter().map(|size| {
+                NamedDropdownItem { name: format!("{size}px").into(), item: *size }
+            }).collect::<Vec<_>>();
+            let current = InterfaceConfig::get(cx).font_size();
+            let row = items.iter().position(|item| item.item == current);
+            SelectState::new(NamedDropdown::new(items), row.map(IndexPath::new), window, cx)
+        });
+        cx.subscribe(&font_size_select, |_: &mut Settings, _, event: &SelectEvent<NamedDropdown<f32>>, cx| {
+            let SelectEvent::Confirm(Some(value)) = event else {
+                return;
+            };
+            InterfaceConfig::get_mut(cx).font_size = value.item;
+            cx.refresh_windows();
+        }).detach();
+
+        let default_memory_min_input_state = create_number_input(256, window, cx);
+        let default_memory_max_input_state = create_number_input(256, window, cx);
+        let default_jvm_flags_input_state = cx.new(|cx| InputState::new(window, cx).auto_grow(1, 8));
+        cx.subscribe(&default_jvm_flags_input_state, |settings: &mut Settings, _, event: &InputEvent, cx| {
+            if let InputEvent::Change = event {
+                settings.send_instance_defaults(cx);
+            }
+        }).detach();
+        let default_resolution_width_input_state = create_number_input(1, window, cx);
+        let default_resolution_height_input_state = create_number_input(1, window, cx);
+        let natives_repositories_input_state = cx.new(|cx| {
+            InputState::new(window, cx).auto_grow(1, 4).placeholder("https://maven.example.com/releases")
+        });
+        cx.subscribe(&natives_repositories_input_state, |settings: &mut Settings, _, event: &InputEvent, cx| {
+            if let InputEvent::Change = event {
+                settings.send_natives_repositories(cx);
+            }
+        }).detach();
+        let tunnel_command_input_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder("ngrok tcp {port} --log stdout --log-format json")
+        });
+        cx.subscribe(&tunnel_command_input_state, |settings: &mut Settings, _, event: &InputEvent, cx| {
+            if let InputEvent::Change = event {
+                settings.send_tunnel_command(cx);
+            }
+        }).detach();
+
+        let max_running_instances_input_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder("Unlimited")
+        });
+        cx.subscribe(&max_running_instances_input_state, |settings: &mut Settings, _, event: &InputEvent, cx| {
+            if let InputEvent::Change = event {
+                settings.send_max_running_instances(cx);
+            }
+        }).detach();
+
+        let backups_kept_input_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(BackupConfiguration::DEFAULT_KEEP.to_string())
+        });
+        cx.subscribe(&backups_kept_input_state, |settings: &mut Settings, _, event: &InputEvent, cx| {
+            if let InputEvent::Change = event {
+                settings.send_backups_kept(cx);
+            }
+        }).detach();
+
+        let global_hotkey_input_state = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("ctrl+shift+KeyP")
+                .default_value(InterfaceConfig::get(cx).global_hotkey.clone())
+        });
+        cx.subscribe(&global_hotkey_input_state, |_: &mut Settings, input, event: &InputEvent, cx| {
+            if let InputEvent::Change = event {
+                let value = input.read(cx).value();
+                InterfaceConfig::get_mut(cx).global_hotkey = value;
+            }
+        }).detach();
+
+        let shortcut_input_states = SHORTCUTS.iter().map(|shortcut| {
+            let current = InterfaceConfig::get(cx).keymap.get(shortcut.id).cloned().unwrap_or_else(|| shortcut.default.to_string());
+            let input_state = cx.new(|cx| {
+                InputState::new(window, cx)
+                    .placeholder(shortcut.default)
+                    .default_value(current)
+            });
+            cx.subscribe(&input_state, move |_: &mut Settings, input, event: &InputEvent, cx| {
+                if let InputEvent::Change = event {
+                    let value = input.read(cx).value().trim().to_string();
+                    let keymap = &mut InterfaceConfig::get_mut(cx).keymap;
+                    if value == shortcut.default {
+                        keymap.remove(shortcut.id);
+                    } else {
+                        keymap.insert(shortcut.id.to_string(), value);
+                    }
+                }
+            }).detach();
+            (shortcut, input_state)
+        }).collect();
+
+        let launch_behaviour_select = cx.new(|cx| {
+            let items = LaunchBehaviour::iter().map(|behaviour| {
+                NamedDropdownItem { name: behaviour.name(), item: behaviour }
+            }).collect::<Vec<_>>();
+            let current = InterfaceConfig::get(cx).launch_behaviour;
+            let row = items.iter().position(|item| item.item == current).unwrap_or(0);
+            SelectState::new(NamedDropdown::new(items), Some(IndexPath::new(row)), window, cx)
+        });
+        cx.subscribe(&launch_behaviour_select, |_: &mut Settings, _, event: &SelectEvent<NamedDropdown<LaunchBehaviour>>, cx| {
+            let SelectEvent::Confirm(Some(value)) = event else {
+                return;
+            };
+            InterfaceConfig::get_mut(cx).launch_behaviour = value.item;
+        }).detach();
 
-            let Some(theme) = gpui_component::ThemeRegistry::global(cx).themes().get(&SharedString::new(theme_name.trim_ascii())).cloned() else {
+        let auto_launch_select = cx.new(|cx| {
+            let items = std::iter::once(NamedDropdownItem { name: "None".into(), item: None })
+                .chain(instances.read(cx).entries.values().map(|entry| {
+                    let entry = entry.read(cx);
+                    let reference = SharedString::from(entry.configuration.reference(&entry.name));
+                    NamedDropdownItem { name: entry.name.clone(), item: Some(reference) }
+                }))
+                .collect::<Vec<_>>();
+            // Older configs store the name of the instance instead of its uuid
+            let current = InterfaceConfig::get(cx).auto_launch_instance.clone()
+                .and_then(|reference| InstanceEntries::find_id_by_reference(&instances, &reference, cx))
+                .and_then(|id| InstanceEntries::find_reference_by_id(&instances, id, cx));
+            let row = items.iter().position(|item| item.item == current).unwrap_or(0);
+            SelectState::new(NamedDropdown::new(items), Some(IndexPath::new(row)), window, cx)
+        });
+        cx.subscribe(&auto_launch_select, |_: &mut Settings, _, event: &SelectEvent<NamedDropdown<Option<SharedString>>>, cx| {
+            let SelectEvent::Confirm(Some(value)) = event else {
                 return;
             };
+            InterfaceConfig::get_mut(cx).auto_launch_instance = value.item.clone();
+        }).detach();
 
-            gpui_component::Theme::global_mut(cx).apply_config(&theme);
+        let new_version_notifications_select = cx.new(|cx| {
+            let items = NewVersionNotifications::iter().map(|notifications| {
+                NamedDropdownItem { name: notifications.name(), item: notifications }
+            }).collect::<Vec<_>>();
+            let current = InterfaceConfig::get(cx).new_version_notifications;
+            let row = items.iter().position(|item| item.item == current).unwrap_or(0);
+            SelectState::new(NamedDropdown::new(items), Some(IndexPath::new(row)), window, cx)
+        });
+        cx.subscribe(&new_version_notifications_select, |_: &mut Settings, _, event: &SelectEvent<NamedDropdown<NewVersionNotifications>>, cx| {
+            let SelectEvent::Confirm(Some(value)) = event else {
+                return;
+            };
+            InterfaceConfig::get_mut(cx).new_version_notifications = value.item;
         }).detach();
 
         let mut settings = Settings {
             theme_folder,
+            logs_folder,
+            data_folder,
             theme_select,
+            theme_mode_select,
+            ui_scale_select,
+            font_size_select,
             backend_handle: data.backend_handle.clone(),
             pending_request: false,
             backend_config: None,
             get_configuration_task: None,
+            default_memory_enabled: false,
+            default_memory_min_input_state,
+            default_memory_max_input_state,
+            default_jvm_flags_enabled: false,
+            default_jvm_flags_input_state,
+            default_jvm_binary_enabled: false,
+            default_jvm_binary_path: None,
+            default_resolution_enabled: false,
+            default_resolution_fullscreen: false,
+            default_resolution_width_input_state,
+            default_resolution_height_input_state,
+            natives_repositories_input_state,
+            tunnel_command_input_state,
+            max_running_instances_input_state,
+            backups_kept_input_state,
+            global_hotkey_input_state,
+            shortcut_input_states,
+            launch_behaviour_select,
+            auto_launch_select,
+            new_version_notifications_select,
+            java_runtimes: None,
+            _get_java_runtimes_task: Task::ready(()),
+            _select_file_task: Task::ready(()),
         };
 
-        settings.update_backend_configuration(cx);
+        settings.update_backend_configuration(window, cx);
+        settings.update_java_runtimes(cx);
 
         settings
     });
@@ -80,24 +319,65 @@ pub fn build_settings_sheet(data: &DataEntities, window: &mut Window, cx: &mut A
     }
 }
 
+fn create_number_input(step: u32, window: &mut Window, cx: &mut Context<Settings>) -> Entity<InputState> {
+    let input_state = cx.new(|cx| InputState::new(window, cx));
+    cx.subscribe_in(&input_state, window, move |_, state, event: &NumberInputEvent, window, cx| {
+        let NumberInputEvent::Step(step_action) = event;
+        if let Ok(value) = state.read(cx).value().parse::<u32>() {
+            let value = match step_action {
+                StepAction::Decrement => value.saturating_div(step).saturating_sub(1).saturating_mul(step).max(step),
+                StepAction::Increment => value.saturating_div(step).saturating_add(1).saturating_mul(step),
+            };
+            state.update(cx, |input, cx| {
+                input.set_value(value.to_string(), window, cx);
+            });
+        }
+    }).detach();
+    cx.subscribe(&input_state, |settings: &mut Settings, _, event: &InputEvent, cx| {
+        if let InputEvent::Change = event {
+            settings.send_instance_defaults(cx);
+        }
+    }).detach();
+    input_state
+}
+
 impl Settings {
-    pub fn update_backend_configuration(&mut self, cx: &mut Context<Self>) {
+    pub fn update_backend_configuration(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if self.get_configuration_task.is_some() {
             self.pending_request = true;
             return;
         }
 
         let (send, recv) = tokio::sync::oneshot::channel();
-        self.get_configuration_task = Some(cx.spawn(async move |page, cx| {
+        self.get_configuration_task = Some(cx.spawn_in(window, async move |settings, cx| {
             let result: BackendConfig = recv.await.unwrap_or_default();
-            let _ = page.update(cx, move |settings, cx| {
+            let _ = settings.update_in(cx, move |settings, window, cx| {
+                if settings.backend_config.is_none() {
+                    settings.load_instance_defaults(&result.instance_defaults, window, cx);
+                    let repositories = result.natives_repositories.join("\n");
+                    settings.natives_repositories_input_state.update(cx, |input, cx| {
+                        input.set_value(repositories, window, cx);
+                    });
+                    let tunnel_command = result.tunnel_command.clone().unwrap_or_default();
+                    settings.tunnel_command_input_state.update(cx, |input, cx| {
+                        input.set_value(SharedString::new(tunnel_command), window, cx);
+                    });
+                    let max_running_instances = result.max_running_instances.map(|value| value.to_string()).unwrap_or_default();
+                    settings.max_running_instances_input_state.update(cx, |input, cx| {
+                        input.set_value(max_running_instances, window, cx);
+                    });
+                    let backups_kept = result.backups.keep.map(|value| value.to_string()).unwrap_or_default();
+                    settings.backups_kept_input_state.update(cx, |input, cx| {
+                        input.set_value(backups_kept, window, cx);
+                    });
+                }
                 settings.backend_config = Some(result);
                 settings.get_configuration_task = None;
                 cx.notify();
 
                 if settings.pending_request {
                     settings.pending_request = false;
-                    settings.update_backend_configuration(cx);
+                    settings.update_backend_configuration(window, cx);
                 }
             });
         }));
@@ -106,6 +386,315 @@ impl Settings {
             channel: send,
         });
     }
+
+    fn update_java_runtimes(&mut self, cx: &mut Context<Self>) {
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self._get_java_runtimes_task = cx.spawn(async move |settings, cx| {
+            let result: Arc<[JavaRuntimeSummary]> = recv.await.unwrap_or_else(|_| Arc::from([]));
+            let _ = settings.update(cx, move |settings, cx| {
+                settings.java_runtimes = Some(result);
+                cx.notify();
+            });
+        });
+
+        self.backend_handle.send(MessageToBackend::GetJavaRuntimes {
+            channel: send,
+        });
+    }
+
+    fn render_java_runtimes(&self, cx: &mut Context<Self>) -> Div {
+        let Some(java_runtimes) = &self.java_runtimes else {
+            return div().child(Spinner::new());
+        };
+        if java_runtimes.is_empty() {
+            return div().text_sm().text_color(cx.theme().muted_foreground).child("No Java runtimes have been downloaded");
+        }
+
+        let unused_size: u64 = java_runtimes.iter().filter(|runtime| runtime.removable).map(|runtime| runtime.size).sum();
+        let any_removable = java_runtimes.iter().any(|runtime| runtime.removable);
+
+        v_flex()
+            .gap_1()
+            .children(java_runtimes.iter().map(|runtime| {
+                let name = match &runtime.version {
+                    Some(version) => format!("{} {} ({})", runtime.component, version, runtime.platform),
+                    None => format!("{} ({})", runtime.component, runtime.platform),
+                };
+                let usage = match runtime.instances {
+                    0 if runtime.removable => "unused".to_string(),
+                    0 => "recently used".to_string(),
+                    1 => "1 instance".to_string(),
+                    instances => format!("{instances} instances"),
+                };
+                h_flex()
+                    .gap_2()
+                    .text_sm()
+                    .child(div().flex_1().child(SharedString::new(name)))
+                    .child(div().text_color(cx.theme().muted_foreground).child(SharedString::new(format!("{} MiB, {usage}", runtime.size / 1024 / 1024))))
+            }))
+            .child(Button::new("remove-unused-java-runtimes")
+                .small()
+                .danger()
+                .label(SharedString::new(format!("Remove unused runtimes ({} MiB)", unused_size / 1024 / 1024)))
+                .disabled(!any_removable)
+                .on_click(cx.listener(|settings, _, window, cx| {
+                    let modal_action = ModalAction::default();
+                    settings.backend_handle.send(MessageToBackend::RemoveUnusedJavaRuntimes {
+                        modal_action: modal_action.clone(),
+                    });
+                    crate::modals::generic::show_notification(window, cx, "Error removing Java runtimes".into(), modal_action.clone());
+
+                    settings._get_java_runtimes_task = cx.spawn(async move |settings, cx| {
+                        while modal_action.refcnt() > 1 && modal_action.get_finished_at().is_none() {
+                            gpui::Timer::after(std::time::Duration::from_millis(500)).await;
+                        }
+                        let _ = settings.update(cx, |settings, cx| settings.update_java_runtimes(cx));
+                    });
+                })))
+            .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                .child("Runtimes are shared by instances using the same Java version. Runtimes no instance has used for two weeks can be removed, they are downloaded again when an instance needs them"))
+    }
+
+    fn load_instance_defaults(&mut self, defaults: &InstanceDefaultsConfiguration, window: &mut Window, cx: &mut Context<Self>) {
+        let memory = defaults.memory.unwrap_or_default();
+        self.default_memory_enabled = defaults.memory.is_some_and(|memory| memory.enabled);
+        self.default_memory_min_input_state.update(cx, |input, cx| {
+            input.set_value(memory.min.to_string(), window, cx);
+        });
+        self.default_memory_max_input_state.update(cx, |input, cx| {
+            input.set_value(memory.max.to_string(), window, cx);
+        });
+
+        let jvm_flags = defaults.jvm_flags.clone().unwrap_or_default();
+        self.default_jvm_flags_enabled = jvm_flags.enabled;
+        self.default_jvm_flags_input_state.update(cx, |input, cx| {
+            input.set_value(SharedString::new(jvm_flags.flags), window, cx);
+        });
+
+        let jvm_binary = defaults.jvm_binary.clone().unwrap_or_default();
+        self.default_jvm_binary_enabled = jvm_binary.enabled;
+        self.default_jvm_binary_path = jvm_binary.path;
+
+        let resolution = defaults.resolution.unwrap_or_default();
+        self.default_resolution_enabled = resolution.enabled;
+        self.default_resolution_fullscreen = resolution.fullscreen;
+        self.default_resolution_width_input_state.update(cx, |input, cx| {
+            input.set_value(resolution.width.to_string(), window, cx);
+        });
+        self.default_resolution_height_input_state.update(cx, |input, cx| {
+            input.set_value(resolution.height.to_string(), window, cx);
+        });
+    }
+
+    fn get_instance_defaults(&self, cx: &App) -> InstanceDefaultsConfiguration {
+        let parse = |state: &Entity<InputState>| state.read(cx).value().parse::<u32>().unwrap_or(0);
+
+        let memory = self.default_memory_enabled.then(|| InstanceMemoryConfiguration {
+            enabled: true,
+            min: parse(&self.default_memory_min_input_state),
+            max: parse(&self.default_memory_max_input_state),
+        });
+        let jvm_flags = self.default_jvm_flags_enabled.then(|| InstanceJvmFlagsConfiguration {
+            enabled: true,
+            flags: self.default_jvm_flags_input_state.read(cx).value().into(),
+        });
+        let jvm_binary = self.default_jvm_binary_enabled.then(|| InstanceJvmBinaryConfiguration {
+            enabled: true,
+            path: self.default_jvm_binary_path.clone(),
+        });
+        let resolution = (self.default_resolution_enabled || self.default_resolution_fullscreen).then(|| InstanceResolutionConfiguration {
+            enabled: self.default_resolution_enabled,
+            width: parse(&self.default_resolution_width_input_state),
+            height: parse(&self.default_resolution_height_input_state),
+            fullscreen: self.default_resolution_fullscreen,
+        });
+
+        InstanceDefaultsConfiguration {
+            memory,
+            jvm_flags,
+            jvm_binary,
+            resolution,
+        }
+    }
+
+    fn send_instance_defaults(&mut self, cx: &mut Context<Self>) {
+        let defaults = self.get_instance_defaults(cx);
+        let Some(backend_config) = &mut self.backend_config else {
+            return;
+        };
+        if backend_config.instance_defaults == defaults {
+            return;
+        }
+        backend_config.instance_defaults = defaults.clone();
+        self.backend_handle.send(MessageToBackend::SetInstanceDefaults { defaults });
+        cx.notify();
+    }
+
+    fn send_natives_repositories(&mut self, cx: &mut Context<Self>) {
+        let repositories: Vec<Arc<str>> = self.natives_repositories_input_state.read(cx).value()
+            .lines()
+            .map(str::trim_ascii)
+            .filter(|line| !line.is_empty())
+            .map(Arc::from)
+            .collect();
+        let Some(backend_config) = &mut self.backend_config else {
+            return;
+        };
+        if backend_config.natives_repositories == repositories {
+            return;
+        }
+        backend_config.natives_repositories = repositories.clone();
+        self.backend_handle.send(MessageToBackend::SetNativesRepositories { repositories });
+    }
+
+    fn send_tunnel_command(&mut self, cx: &mut Context<Self>) {
+        let command = self.tunnel_command_input_state.read(cx).value();
+        let command = command.trim_ascii();
+        let command: Option<Arc<str>> = if command.is_empty() { None } else { Some(command.into()) };
+        let Some(backend_config) = &mut self.backend_config else {
+            return;
+        };
+        if backend_config.tunnel_command == command {
+            return;
+        }
+        backend_config.tunnel_command = command.clone();
+        self.backend_handle.send(MessageToBackend::SetTunnelCommand { command });
+    }
+
+    fn send_max_running_instances(&mut self, cx: &mut Context<Self>) {
+        let value = self.max_running_instances_input_state.read(cx).value();
+        let value = value.trim().parse::<u32>().ok().filter(|value| *value > 0);
+        let Some(backend_config) = &mut self.backend_config else {
+            return;
+        };
+        if backend_config.max_running_instances == value {
+            return;
+        }
+        backend_config.max_running_instances = value;
+        self.backend_handle.send(MessageToBackend::SetMaxRunningInstances { value });
+    }
+
+    fn send_backups_kept(&mut self, cx: &mut Context<Self>) {
+        let value = self.backups_kept_input_state.read(cx).value();
+        let value = value.trim().parse::<u32>().ok().filter(|value| *value > 0);
+        let Some(backend_config) = &mut self.backend_config else {
+            return;
+        };
+        if backend_config.backups.keep == value {
+            return;
+        }
+        backend_config.backups.keep = value;
+        self.backend_handle.send(MessageToBackend::SetBackupsKept { value });
+    }
+
+    fn select_backup_directory(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let receiver = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some(SharedString::new_static("Select Backup Directory"))
+        });
+
+        let this_entity = cx.entity();
+        self._select_file_task = window.spawn(cx, async move |cx| {
+            let Ok(Ok(Some(paths))) = receiver.await else {
+                return;
+            };
+            let Some(directory) = paths.into_iter().next() else {
+                return;
+            };
+            _ = cx.update_window_entity(&this_entity, move |this, window, cx| {
+                this.backend_handle.send(MessageToBackend::SetBackupDirectory { directory: Some(directory) });
+                this.update_backend_configuration(window, cx);
+            });
+        });
+    }
+
+    fn select_data_directory(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let receiver = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some(SharedString::new_static("Select Data Directory"))
+        });
+
+        let backend_handle = self.backend_handle.clone();
+        self._select_file_task = window.spawn(cx, async move |cx| {
+            let Ok(Ok(Some(paths))) = receiver.await else {
+                return;
+            };
+            let Some(target) = paths.first() else {
+                return;
+            };
+            let target: Arc<Path> = target.as_path().into();
+            _ = cx.update(move |window, cx| {
+                confirm_move_data_directory(target, backend_handle, window, cx);
+            });
+        });
+    }
+
+    fn select_default_jvm_binary(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let receiver = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some(SharedString::new_static("Select Jvm Binary"))
+        });
+
+        let this_entity = cx.entity();
+        self._select_file_task = window.spawn(cx, async move |cx| {
+            let Ok(result) = receiver.await else {
+                return;
+            };
+            _ = cx.update_window_entity(&this_entity, move |this, window, cx| {
+                match result {
+                    Ok(Some(paths)) => {
+                        this.default_jvm_binary_path = paths.first().map(|v| v.as_path().into());
+                        this.send_instance_defaults(cx);
+                    },
+                    Ok(None) => {},
+                    Err(error) => {
+                        let notification = Notification::new()
+                            .autohide(false)
+                            .with_type(NotificationType::Error)
+                            .title(format!("{}", error));
+                        window.push_notification(notification, cx);
+                    },
+                }
+            });
+        });
+    }
+}
+
+fn confirm_move_data_directory(target: Arc<Path>, backend_handle: BackendHandle, window: &mut Window, cx: &mut App) {
+    let message = SharedString::new(format!("Launcher data will be copied to {}", target.display()));
+
+    window.open_dialog(cx, move |dialog, _, _| {
+        let target = target.clone();
+        let backend_handle = backend_handle.clone();
+        dialog.title("Move Data Directory")
+            .child(v_flex()
+                .gap_3()
+                .child(message.clone())
+                .child("Instances, accounts and settings are copied, downloaded metadata stays in the cache directory. Make sure no games are running")
+                .child("The new directory is used after restarting the launcher, the old one is left in place so it can be deleted once everything works")
+                .child(h_flex()
+                    .gap_2()
+                    .justify_end()
+                    .child(Button::new("cancel").small().label("Cancel").on_click(|_, window, cx| {
+                        window.close_dialog(cx);
+                    }))
+                    .child(Button::new("move").small().success().label("Copy data").on_click(move |_, window, cx| {
+                        window.close_all_dialogs(cx);
+                        let modal_action = ModalAction::default();
+                        backend_handle.send(MessageToBackend::ChangeDataDirectory {
+                            target: target.clone(),
+                            modal_action: modal_action.clone(),
+                        });
+                        crate::modals::generic::show_modal(window, cx, "Moving launcher data".into(),
+                            "Error moving launcher data".into(), modal_action);
+                    }))))
+    });
 }
 
 impl Render for Settings {
@@ -116,6 +705,10 @@ impl Render for Settings {
             .px_4()
             .py_3()
             .gap_3()
+            .child(crate::labelled(
+                "Theme Mode",
+                Select::new(&self.theme_mode_select)
+            ))
             .child(crate::labelled(
                 "Theme",
                 Select::new(&self.theme_select)
@@ -131,6 +724,18 @@ impl Render for Settings {
                     cx.open_url("https://github.com/longbridge/gpui-component/tree/main/themes");
                 }
             }))
+            .child(crate::labelled(
+                "UI Scale",
+                Select::new(&self.ui_scale_select)
+            ))
+            .child(crate::labelled(
+                "Font Size",
+                Select::new(&self.font_size_select)
+            ))
+            .child(crate::labelled(
+                "New Minecraft Versions",
+                Select::new(&self.new_version_notifications_select)
+            ))
             .child(crate::labelled("Deletion",
                 v_flex().gap_2()
                     .child(Checkbox::new("confirm-delete-mods")
@@ -145,6 +750,32 @@ impl Render for Settings {
                             InterfaceConfig::get_mut(cx).quick_delete_instance = *value;
                         }))
                     )
+            )
+            .child(crate::labelled("Tray",
+                v_flex().gap_2()
+                    .child(Checkbox::new("minimize-to-tray")
+                        .label("Keep running in the tray when the main window is closed")
+                        .checked(interface_config.minimize_to_tray)
+                        .on_click(|value, _, cx| {
+                            InterfaceConfig::get_mut(cx).minimize_to_tray = *value;
+                        }))
+                    .child(div().text_sm().child("Global hotkey to open the launcher"))
+                    .child(Input::new(&self.global_hotkey_input_state).small())
+                    .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                        .child("Changes take effect after restarting the launcher"))
+                    )
+            )
+            .child(crate::labelled("Keyboard Shortcuts",
+                v_flex().gap_2()
+                    .children(self.shortcut_input_states.iter().map(|(shortcut, input_state)| {
+                        h_flex()
+                            .gap_2()
+                            .child(div().flex_1().text_sm().child(shortcut.description))
+                            .child(div().w_40().child(Input::new(input_state).small()))
+                    }))
+                    .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                        .child("Leave a shortcut empty to disable it. Changes take effect after restarting the launcher"))
+                    )
             );
 
         if let Some(backend_config) = &self.backend_config {
@@ -152,29 +783,333 @@ impl Render for Settings {
                 .child(crate::labelled(
                     "Launching",
                     v_flex().gap_2()
-                        .child(Checkbox::new("hide-on-launch")
-                            .label("Hide main window on launch")
-                            .checked(interface_config.hide_main_window_on_launch)
-                            .on_click(|value, _, cx| {
-                                InterfaceConfig::get_mut(cx).hide_main_window_on_launch = *value;
-                            }))
+                        .child(div().text_sm().child("When the game starts"))
+                        .child(Select::new(&self.launch_behaviour_select).small())
+                        .child(div().text_sm().child("Launch automatically when Pandora starts"))
+                        .child(Select::new(&self.auto_launch_select).small())
                         .child(Checkbox::new("open-game-output")
                             .label("Open game output on launch")
                             .checked(!backend_config.dont_open_game_output_when_launching)
                             .on_click(cx.listener({
                                 let backend_handle = self.backend_handle.clone();
-                                move |settings, value, _, cx| {
+                                move |settings, value, window, cx| {
                                     backend_handle.send(MessageToBackend::SetOpenGameOutputAfterLaunching {
                                         value: *value
                                     });
-                                    settings.update_backend_configuration(cx);
+                                    settings.update_backend_configuration(window, cx);
+                                }
+                            })))
+                        .child(div().text_sm().child("Maximum running instances"))
+                        .child(Input::new(&self.max_running_instances_input_state).small())
+                        .child(Checkbox::new("memory-budget-warn")
+                            .label("Warn when running instances could use more memory than this computer has")
+                            .checked(backend_config.memory_budget != MemoryBudgetMode::Ignore)
+                            .on_click(cx.listener({
+                                let backend_handle = self.backend_handle.clone();
+                                move |settings, value, window, cx| {
+                                    backend_handle.send(MessageToBackend::SetMemoryBudget {
+                                        mode: if *value { MemoryBudgetMode::Warn } else { MemoryBudgetMode::Ignore }
+                                    });
+                                    settings.update_backend_configuration(window, cx);
+                                }
+                            })))
+                        .child(Checkbox::new("memory-budget-block")
+                            .label("Prevent launching instead of warning")
+                            .disabled(backend_config.memory_budget == MemoryBudgetMode::Ignore)
+                            .checked(backend_config.memory_budget == MemoryBudgetMode::Block)
+                            .on_click(cx.listener({
+                                let backend_handle = self.backend_handle.clone();
+                                move |settings, value, window, cx| {
+                                    backend_handle.send(MessageToBackend::SetMemoryBudget {
+                                        mode: if *value { MemoryBudgetMode::Block } else { MemoryBudgetMode::Warn }
+                                    });
+                                    settings.update_backend_configuration(window, cx);
+                                }
+                            })))
+                ))
+                .child(crate::labelled(
+                    "Storage",
+                    v_flex().gap_2()
+                        .child(Checkbox::new("link-content-files")
+                            .label("Share identical files between instances")
+                            .checked(!backend_config.dont_link_content_files)
+                            .on_click(cx.listener({
+                                let backend_handle = self.backend_handle.clone();
+                                move |settings, value, window, cx| {
+                                    backend_handle.send(MessageToBackend::SetLinkContentFiles {
+                                        value: *value
+                                    });
+                                    settings.update_backend_configuration(window, cx);
                                 }
                             })))
+                        .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                            .child("Mods and resource packs are reflinked or hard linked from the content library instead of copied, which saves disk space"))
+                        .child(div().text_sm().child("Java runtimes"))
+                        .child(self.render_java_runtimes(cx))
+                ))
+                .child(crate::labelled(
+                    "Backups",
+                    v_flex().gap_2()
+                        .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                            .child(match &backend_config.backups.directory {
+                                Some(directory) => SharedString::new(directory.display().to_string()),
+                                None => SharedString::new_static("Backups are stored in the launcher data directory"),
+                            }))
+                        .child(h_flex().gap_2()
+                            .child(Button::new("select-backup-folder").info().small().icon(IconName::FolderOpen).label("Choose folder").on_click(cx.listener(|settings, _, window, cx| {
+                                settings.select_backup_directory(window, cx);
+                            })))
+                            .child(Button::new("reset-backup-folder").small().label("Reset").disabled(backend_config.backups.directory.is_none()).on_click(cx.listener({
+                                let backend_handle = self.backend_handle.clone();
+                                move |settings, _, window, cx| {
+                                    backend_handle.send(MessageToBackend::SetBackupDirectory { directory: None });
+                                    settings.update_backend_configuration(window, cx);
+                                }
+                            }))))
+                        .child(div().text_sm().child("Backups kept per instance"))
+                        .child(Input::new(&self.backups_kept_input_state).small())
+                        .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                            .child("Instances with nightly backups turned on are backed up once a day while the launcher is open. Choose a folder synced by another program to keep copies elsewhere"))
+                ))
+                .child(crate::labelled(
+                    "Accounts",
+                    v_flex().gap_2()
+                        .child(Checkbox::new("device-code-login")
+                            .label("Log in with a code")
+                            .checked(backend_config.device_code_login)
+                            .on_click(cx.listener({
+                                let backend_handle = self.backend_handle.clone();
+                                move |settings, value, window, cx| {
+                                    backend_handle.send(MessageToBackend::SetDeviceCodeLogin {
+                                        value: *value
+                                    });
+                                    settings.update_backend_configuration(window, cx);
+                                }
+                            })))
+                        .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                            .child("Shows a code to enter at microsoft.com/link, on any device, instead of waiting for the browser to redirect back to the launcher"))
+                ))
+                .child(crate::labelled(
+                    "Logging",
+                    v_flex().gap_2()
+                        .child(Checkbox::new("debug-logging")
+                            .label("Debug logging")
+                            .checked(backend_config.debug_logging)
+                            .on_click(cx.listener({
+                                let backend_handle = self.backend_handle.clone();
+                                move |settings, value, window, cx| {
+                                    backend_handle.send(MessageToBackend::SetDebugLogging {
+                                        value: *value
+                                    });
+                                    settings.update_backend_configuration(window, cx);
+                                }
+                            })))
+                        .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                            .child("Writes more detail to the launcher log, which helps when reporting a problem"))
+                        .child(Button::new("open-logs-folder").info().small().icon(IconName::FolderOpen).label("Open logs folder").on_click({
+                            let logs_folder = self.logs_folder.clone();
+                            move |_, window, cx| {
+                                crate::open_folder(&logs_folder, window, cx);
+                            }
+                        }))
+                ))
+                .child(crate::labelled(
+                    "Data Directory",
+                    v_flex().gap_2()
+                        .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                            .child(SharedString::new(self.data_folder.display().to_string())))
+                        .child(h_flex().gap_2()
+                            .child(Button::new("open-data-folder").info().small().icon(IconName::FolderOpen).label("Open data folder").on_click({
+                                let data_folder = self.data_folder.clone();
+                                move |_, window, cx| {
+                                    crate::open_folder(&data_folder, window, cx);
+                                }
+                            }))
+                            .child(Button::new("move-data-folder").small().label("Move data directory").on_click(cx.listener(|settings, _, window, cx| {
+                                settings.select_data_directory(window, cx);
+                            }))))
+                ))
+                // Packaged versions register the links themselves
+                .when(Sandbox::detect().is_none(), |this| this.child(crate::labelled(
+                    "Links",
+                    v_flex().gap_2()
+                        .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                            .child("Open pandora:// and modrinth:// links in this launcher. Installed versions of the launcher do this automatically"))
+                        .child(Button::new("register-links").info().small().icon(IconName::ExternalLink).label("Open links in Pandora").on_click({
+                            let backend_handle = self.backend_handle.clone();
+                            move |_, _, _| {
+                                backend_handle.send(MessageToBackend::RegisterUrlHandler);
+                            }
+                        }))
+                )))
+                .child(crate::labelled(
+                    "Event Stream",
+                    v_flex().gap_2()
+                        .child(Checkbox::new("event-stream")
+                            .label("Stream events to companion tools")
+                            .checked(backend_config.event_stream.enabled)
+                            .on_click(cx.listener({
+                                let backend_handle = self.backend_handle.clone();
+                                move |settings, value, window, cx| {
+                                    backend_handle.send(MessageToBackend::SetEventStreamEnabled {
+                                        value: *value
+                                    });
+                                    settings.update_backend_configuration(window, cx);
+                                }
+                            })))
+                        .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                            .child(SharedString::new(format!(
+                                "A WebSocket on ws://127.0.0.1:{} sends instance changes, game output and progress, e.g. for OBS overlays",
+                                backend_config.event_stream.port()
+                            ))))
+                        .when_some(backend_config.event_stream.token.clone().filter(|_| backend_config.event_stream.enabled), |this, token| {
+                            let url = format!("ws://127.0.0.1:{}/?token={token}", backend_config.event_stream.port());
+                            this.child(h_flex().gap_2()
+                                .child(Button::new("copy-event-stream-url").info().small().label("Copy URL with token").on_click(move |_, window, cx| {
+                                    cx.write_to_clipboard(ClipboardItem::new_string(url.clone()));
+                                    window.push_notification((NotificationType::Success, "Copied event stream URL to clipboard"), cx);
+                                }))
+                                .child(Button::new("reset-event-stream-token").danger().small().label("Reset token").on_click(cx.listener({
+                                    let backend_handle = self.backend_handle.clone();
+                                    move |settings, _, window, cx| {
+                                        backend_handle.send(MessageToBackend::ResetEventStreamToken);
+                                        settings.update_backend_configuration(window, cx);
+                                    }
+                                }))))
+                        })
+                ))
+                .child(crate::labelled(
+                    "Parental Controls",
+                    v_flex().gap_2()
+                        .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                            .child(if backend_config.parental_controls.is_enabled() {
+                                "Enabled, some actions are restricted until unlocked with the PIN"
+                            } else {
+                                "Restrict creating instances, installing content and daily playtime behind a PIN"
+                            }))
+                        .child(Button::new("parental-controls").info().small().label("Configure parental controls").on_click({
+                            let backend_handle = self.backend_handle.clone();
+                            move |_, window, cx| {
+                                crate::modals::parental_controls::open_parental_controls(backend_handle.clone(), window, cx);
+                            }
+                        }))
+                ))
+                .child(crate::labelled(
+                    "Instance Defaults",
+                    v_flex().gap_2()
+                        .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                            .child("Used by instances that don't override these settings"))
+                        .child(Checkbox::new("default-memory")
+                            .label("Set Memory")
+                            .checked(self.default_memory_enabled)
+                            .on_click(cx.listener(|settings, value, _, cx| {
+                                settings.default_memory_enabled = *value;
+                                settings.send_instance_defaults(cx);
+                            })))
+                        .child(h_flex()
+                            .gap_1()
+                            .child(NumberInput::new(&self.default_memory_min_input_state).small().prefix("Min").suffix("MiB").disabled(!self.default_memory_enabled))
+                            .child(NumberInput::new(&self.default_memory_max_input_state).small().prefix("Max").suffix("MiB").disabled(!self.default_memory_enabled)))
+                        .child(Checkbox::new("default-resolution")
+                            .label("Set Window Size")
+                            .checked(self.default_resolution_enabled)
+                            .on_click(cx.listener(|settings, value, _, cx| {
+                                settings.default_resolution_enabled = *value;
+                                settings.send_instance_defaults(cx);
+                            })))
+                        .child(h_flex()
+                            .gap_1()
+                            .child(NumberInput::new(&self.default_resolution_width_input_state).small().prefix("Width").suffix("px").disabled(!self.default_resolution_enabled))
+                            .child(NumberInput::new(&self.default_resolution_height_input_state).small().prefix("Height").suffix("px").disabled(!self.default_resolution_enabled)))
+                        .child(Checkbox::new("default-fullscreen")
+                            .label("Launch in Fullscreen")
+                            .checked(self.default_resolution_fullscreen)
+                            .on_click(cx.listener(|settings, value, _, cx| {
+                                settings.default_resolution_fullscreen = *value;
+                                settings.send_instance_defaults(cx);
+                            })))
+                        .child(Checkbox::new("default-jvm-flags")
+                            .label("Add JVM Flags")
+                            .checked(self.default_jvm_flags_enabled)
+                            .on_click(cx.listener(|settings, value, _, cx| {
+                                settings.default_jvm_flags_enabled = *value;
+                                settings.send_instance_defaults(cx);
+                            })))
+                        .child(Input::new(&self.default_jvm_flags_input_state).disabled(!self.default_jvm_flags_enabled))
+                        .child(Checkbox::new("default-jvm-binary")
+                            .label("Override JVM Binary")
+                            .checked(self.default_jvm_binary_enabled)
+                            .on_click(cx.listener(|settings, value, _, cx| {
+                                settings.default_jvm_binary_enabled = *value;
+                                settings.send_instance_defaults(cx);
+                            })))
+                        .child(Button::new("select-default-jvm-binary")
+                            .success()
+                            .label(self.default_jvm_binary_path.as_ref()
+                                .map(|path| SharedString::new(path.to_string_lossy()))
+                                .unwrap_or(SharedString::new_static("<unset>")))
+                            .disabled(!self.default_jvm_binary_enabled)
+                            .on_click(cx.listener(|settings, _, window, cx| {
+                                settings.select_default_jvm_binary(window, cx);
+                            })))
+                ))
+                .child(crate::labelled(
+                    "Natives Repositories",
+                    v_flex().gap_2()
+                        .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                            .child("Maven repositories searched for ARM and RISC-V Linux natives, one per line"))
+                        .child(Input::new(&self.natives_repositories_input_state))
+                ))
+                .child(crate::labelled(
+                    "Tunnel Command",
+                    v_flex().gap_2()
+                        .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                            .child("Used by \"Share with Friends\" to expose a LAN world or server, {port} is replaced with the local port"))
+                        .child(Input::new(&self.tunnel_command_input_state))
+                ))
+                .child(crate::labelled(
+                    "Updates",
+                    v_flex().gap_2()
+                        .map(|this| match Sandbox::detect() {
+                            Some(sandbox) => this.child(div().text_sm().text_color(cx.theme().muted_foreground)
+                                .child(SharedString::new(format!("Pandora {} was installed using {}. {}",
+                                    option_env!("PANDORA_RELEASE_VERSION").unwrap_or("dev"), sandbox.name(), sandbox.update_hint())))),
+                            None => this
+                                .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                                    .child(SharedString::new(format!("Pandora {} on the {} channel",
+                                        option_env!("PANDORA_RELEASE_VERSION").unwrap_or("dev"), backend_config.update_channel.name()))))
+                                .child(UpdateChannel::iter().fold(ButtonGroup::new("update-channel").outline().small(), |group, channel| {
+                                    group.child(Button::new(channel.name()).label(channel.name()).selected(backend_config.update_channel == channel))
+                                }).on_click(cx.listener({
+                                    let backend_handle = self.backend_handle.clone();
+                                    let current_channel = backend_config.update_channel;
+                                    move |settings, selected: &Vec<usize>, window, cx| {
+                                        let Some(channel) = selected.first().and_then(|index| UpdateChannel::iter().nth(*index)) else {
+                                            return;
+                                        };
+                                        if channel == current_channel {
+                                            return;
+                                        }
+                                        if channel == UpdateChannel::Stable {
+                                            let notification: Notification = (NotificationType::Warning,
+                                                "Pandora doesn't downgrade, you'll keep this version until a newer stable release is available").into();
+                                            window.push_notification(notification.autohide(false), cx);
+                                        }
+                                        backend_handle.send(MessageToBackend::SetUpdateChannel { channel });
+                                        settings.update_backend_configuration(window, cx);
+                                    }
+                                }))),
+                        })
                 ))
         } else {
             div = div.child(Spinner::new().large());
         }
 
-        div
+        div.child(Button::new("report-problem").info().icon(IconName::TriangleAlert).label("Report a problem").on_click({
+            let backend_handle = self.backend_handle.clone();
+            move |_, window, cx| {
+                crate::modals::report_problem::open_report_problem(None, backend_handle.clone(), window, cx);
+            }
+        }))
     }
 }
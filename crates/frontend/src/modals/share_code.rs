@@ -0,0 +1,191 @@
+use std::sync::Arc;
+
+use bridge::{handle::BackendHandle, instance::{InstanceID, InstanceShareSummary}, message::MessageToBackend, modal_action::ModalAction};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, dialog::Dialog, input::{Input, InputEvent, InputState}, notification::{Notification, NotificationType}, v_flex, ActiveTheme, Disableable, WindowExt
+};
+
+use crate::entity::instance::InstanceEntries;
+
+// Share codes are pandora:// links, see `instance_share.rs` in the backend
+const SHARE_CODE_PREFIX: &str = "pandora://import/";
+
+pub fn copy_share_code(id: InstanceID, backend_handle: &BackendHandle, window: &mut Window, cx: &mut App) {
+    let (send, recv) = tokio::sync::oneshot::channel();
+    backend_handle.send(MessageToBackend::CreateInstanceShareCode { id, channel: send });
+
+    window.spawn(cx, async move |cx| {
+        let result = recv.await.unwrap_or_else(|_| Err("Request was cancelled".into()));
+        _ = cx.update(|window, cx| {
+            match result {
+                Ok(code) => {
+                    cx.write_to_clipboard(ClipboardItem::new_string(code.to_string()));
+                    window.push_notification((NotificationType::Success, "Copied share code to clipboard"), cx);
+                },
+                Err(error) => {
+                    let notification = Notification::new()
+                        .autohide(false)
+                        .with_type(NotificationType::Error)
+                        .title("Unable to create share code")
+                        .message(SharedString::new(error));
+                    window.push_notification(notification, cx);
+                },
+            }
+        });
+    }).detach();
+}
+
+struct ImportShareCodeModalState {
+    backend_handle: BackendHandle,
+    code_input_state: Entity<InputState>,
+    name_input_state: Entity<InputState>,
+    instance_names: Arc<[SharedString]>,
+    summary: Option<Result<InstanceShareSummary, Arc<str>>>,
+    name_invalid: bool,
+    _read_task: Task<()>,
+    _code_input_subscription: Subscription,
+    _name_input_subscription: Subscription,
+}
+
+impl ImportShareCodeModalState {
+    pub fn new(
+        code: Option<SharedString>,
+        instances: Entity<InstanceEntries>,
+        backend_handle: BackendHandle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let instance_names: Arc<[SharedString]> =
+            instances.read(cx).entries.iter().map(|(_, v)| v.read(cx).name.clone()).collect();
+
+        // A code that was just copied is most likely the one to import
+        let code = code.or_else(|| {
+            let text = cx.read_from_clipboard()?.text()?;
+            text.trim().starts_with(SHARE_CODE_PREFIX).then(|| SharedString::new(text.trim()))
+        });
+
+        let code_input_state = cx.new(|cx| {
+            let state = InputState::new(window, cx).placeholder("Paste a share code");
+            match &code {
+                Some(code) => state.default_value(code.clone()),
+                None => state,
+            }
+        });
+        let _code_input_subscription = cx.subscribe_in(&code_input_state, window, |this, _, event: &InputEvent, window, cx| {
+            if let InputEvent::Change = event {
+                this.read_code(window, cx);
+            }
+        });
+
+        let name_input_state = cx.new(|cx| InputState::new(window, cx).placeholder("Unnamed Instance"));
+        let _name_input_subscription = cx.subscribe_in(&name_input_state, window, |this, _, _: &InputEvent, _, cx| {
+            this.validate_name(cx);
+            cx.notify();
+        });
+
+        let mut this = Self {
+            backend_handle,
+            code_input_state,
+            name_input_state,
+            instance_names,
+            summary: None,
+            name_invalid: true,
+            _read_task: Task::ready(()),
+            _code_input_subscription,
+            _name_input_subscription,
+        };
+        if code.is_some() {
+            this.read_code(window, cx);
+        }
+        this
+    }
+
+    fn validate_name(&mut self, cx: &App) {
+        let text = self.name_input_state.read(cx).value();
+        self.name_invalid = text.is_empty() || !crate::is_valid_instance_name(text.as_str()) || self.instance_names.contains(&text);
+    }
+
+    fn read_code(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let code = self.code_input_state.read(cx).value();
+        if code.trim().is_empty() {
+            self.summary = None;
+            cx.notify();
+            return;
+        }
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self.backend_handle.send(MessageToBackend::ReadInstanceShareCode { code: code.as_str().into(), channel: send });
+
+        self._read_task = cx.spawn_in(window, async move |this, cx| {
+            let result = recv.await.unwrap_or_else(|_| Err("Request was cancelled".into()));
+            _ = this.update_in(cx, move |this, window, cx| {
+                // The name of the shared instance is used unless one was already entered
+                if let Ok(summary) = &result && this.name_input_state.read(cx).value().is_empty() {
+                    let name = SharedString::new(summary.name.clone());
+                    this.name_input_state.update(cx, |input, cx| input.set_value(name, window, cx));
+                    this.validate_name(cx);
+                }
+                this.summary = Some(result);
+                cx.notify();
+            });
+        });
+    }
+
+    pub fn render(&mut self, modal: Dialog, _window: &mut Window, cx: &mut Context<Self>) -> Dialog {
+        let summary_info = match &self.summary {
+            None => None,
+            Some(Ok(summary)) => Some(div().text_sm().text_color(cx.theme().muted_foreground)
+                .child(SharedString::new(format!("{} {}, {} Modrinth projects", summary.loader.name(), summary.minecraft_version, summary.content_count)))),
+            Some(Err(error)) => Some(div().text_sm().text_color(cx.theme().danger).child(SharedString::new(error.clone()))),
+        };
+
+        let can_import = !self.name_invalid && matches!(self.summary, Some(Ok(_)));
+        let import_button = Button::new("import").label("Import Instance").disabled(!can_import).on_click(cx.listener(|this, _, window, cx| {
+            window.close_all_dialogs(cx);
+
+            let modal_action = ModalAction::default();
+            this.backend_handle.send(MessageToBackend::ImportInstanceShareCode {
+                code: this.code_input_state.read(cx).value().as_str().into(),
+                name: this.name_input_state.read(cx).value().as_str().into(),
+                modal_action: modal_action.clone(),
+            });
+            crate::modals::generic::show_modal(window, cx, "Importing instance".into(),
+                "Error importing instance".into(), modal_action);
+        }));
+
+        let content = v_flex()
+            .gap_3()
+            .child(crate::labelled("Share Code", v_flex()
+                .gap_1()
+                .child(Input::new(&self.code_input_state))
+                .children(summary_info)))
+            .child(crate::labelled(
+                "Name",
+                Input::new(&self.name_input_state).when(self.name_invalid, |this| this.border_color(cx.theme().danger)),
+            ))
+            .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                .child("Content is downloaded from Modrinth, so the instance is set up the same way as the original"))
+            .child(if can_import { import_button.success() } else { import_button });
+
+        modal.title("Import Instance from Code").child(content)
+    }
+}
+
+pub fn open_import_share_code(
+    code: Option<SharedString>,
+    instances: Entity<InstanceEntries>,
+    backend_handle: BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let state = cx.new(|cx| {
+        ImportShareCodeModalState::new(code, instances, backend_handle, window, cx)
+    });
+
+    window.open_dialog(cx, move |modal, window, cx| {
+        cx.update_entity(&state, |state, cx| {
+            state.render(modal, window, cx)
+        })
+    });
+}
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use bridge::{handle::BackendHandle, instance::StrayInstanceFolder, message::MessageToBackend, modal_action::ModalAction};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonGroup, ButtonVariants}, dialog::Dialog, h_flex, input::{Input, InputEvent, InputState}, scroll::ScrollableElement, v_flex, ActiveTheme, Disableable, Selectable, Sizable, StyledExt, WindowExt
+};
+use schema::loader::Loader;
+
+struct StrayInstance {
+    folder: StrayInstanceFolder,
+    version_input_state: Entity<InputState>,
+    loader: Loader,
+}
+
+struct StrayInstancesModalState {
+    backend_handle: BackendHandle,
+    instances: Vec<StrayInstance>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl StrayInstancesModalState {
+    fn render_instance(&self, index: usize, instance: &StrayInstance, cx: &mut Context<Self>) -> Div {
+        let theme = cx.theme();
+        let border = theme.border;
+        let muted = theme.muted_foreground;
+        let version_empty = instance.version_input_state.read(cx).value().trim().is_empty();
+
+        let loader_button_group = ButtonGroup::new(("loader", index))
+            .outline()
+            .small()
+            .child(Button::new("loader-vanilla").label("Vanilla").selected(instance.loader == Loader::Vanilla))
+            .child(Button::new("loader-fabric").label("Fabric").selected(instance.loader == Loader::Fabric))
+            .child(Button::new("loader-forge").label("Forge").selected(instance.loader == Loader::Forge))
+            .child(Button::new("loader-neoforge").label("NeoForge").selected(instance.loader == Loader::NeoForge))
+            .on_click(cx.listener(move |this, selected: &Vec<usize>, _, cx| {
+                let loader = match selected.first() {
+                    Some(0) => Loader::Vanilla,
+                    Some(1) => Loader::Fabric,
+                    Some(2) => Loader::Forge,
+                    Some(3) => Loader::NeoForge,
+                    _ => return,
+                };
+                this.instances[index].loader = loader;
+                cx.notify();
+            }));
+
+        let adopt_button = Button::new(("adopt", index))
+            .success()
+            .small()
+            .label("Adopt")
+            .disabled(version_empty)
+            .on_click(cx.listener(move |this, _, window, cx| {
+                let instance = this.instances.remove(index);
+                let minecraft_version = instance.version_input_state.read(cx).value().trim().into();
+
+                let modal_action = ModalAction::default();
+                this.backend_handle.send(MessageToBackend::AdoptStrayInstance {
+                    path: instance.folder.path.clone(),
+                    minecraft_version,
+                    loader: instance.loader,
+                    modal_action: modal_action.clone(),
+                });
+                crate::modals::generic::show_notification(window, cx, "Error adopting instance".into(), modal_action);
+
+                if this.instances.is_empty() {
+                    window.close_all_dialogs(cx);
+                }
+                cx.notify();
+            }));
+
+        v_flex()
+            .p_2()
+            .gap_2()
+            .rounded_lg()
+            .border_1()
+            .border_color(border)
+            .child(h_flex()
+                .gap_3()
+                .child(v_flex()
+                    .flex_1()
+                    .child(div().font_semibold().child(SharedString::from(instance.folder.name.clone())))
+                    .child(div().text_sm().text_color(muted)
+                        .child(SharedString::from(instance.folder.path.to_string_lossy().to_string()))))
+                .child(adopt_button))
+            .child(h_flex()
+                .gap_2()
+                .child(div().w_32().child(Input::new(&instance.version_input_state).small()))
+                .child(loader_button_group))
+    }
+
+    fn render(&mut self, modal: Dialog, cx: &mut Context<Self>) -> Dialog {
+        let rows = (0..self.instances.len())
+            .map(|index| self.render_instance(index, &self.instances[index], cx))
+            .collect::<Vec<_>>();
+
+        let content = v_flex()
+            .gap_3()
+            .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                .child("These folders in the instances directory aren't instances yet. Check the detected version and loader, then adopt them to play them from the launcher"))
+            .child(v_flex().gap_2().max_h(px(400.0)).overflow_y_scrollbar().children(rows));
+
+        modal.title("Found Instance Folders").child(content)
+    }
+}
+
+pub fn open_stray_instances(instances: Arc<[StrayInstanceFolder]>, backend_handle: BackendHandle, window: &mut Window, cx: &mut App) {
+    let state = cx.new(|cx| {
+        let mut subscriptions = Vec::new();
+        let instances = instances.iter().map(|folder| {
+            let version = folder.minecraft_version.map(|version| version.as_str()).unwrap_or_default();
+            let version_input_state = cx.new(|cx| InputState::new(window, cx).placeholder("Version").default_value(version));
+            subscriptions.push(cx.subscribe(&version_input_state, |_, _, _: &InputEvent, cx| cx.notify()));
+            // A folder without mods or any other hint is most likely a vanilla instance
+            let loader = if folder.loader == Loader::Unknown { Loader::Vanilla } else { folder.loader };
+            StrayInstance { folder: folder.clone(), version_input_state, loader }
+        }).collect();
+
+        StrayInstancesModalState { backend_handle, instances, _subscriptions: subscriptions }
+    });
+
+    window.open_dialog(cx, move |modal, _, cx| {
+        cx.update_entity(&state, |state, cx| {
+            state.render(modal, cx)
+        })
+    });
+}
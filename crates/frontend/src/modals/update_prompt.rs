@@ -3,9 +3,11 @@ use std::sync::{atomic::{AtomicBool, AtomicU8, Ordering}, Arc};
 use bridge::{handle::BackendHandle, instance::InstanceID, modal_action::ModalAction};
 use gpui::{prelude::*, *};
 use gpui_component::{
-    Disableable, WindowExt, button::{Button, ButtonVariants}, h_flex, input::{Input, InputEvent, InputState}, v_flex
+    Disableable, WindowExt, notification::NotificationType, button::{Button, ButtonVariants}, h_flex, input::{Input, InputEvent, InputState}, v_flex
 };
-use schema::pandora_update::UpdatePrompt;
+use schema::{backend_config::UpdateChannel, pandora_update::UpdatePrompt};
+
+use crate::{entity::DataEntities, relaunch::RelaunchState};
 
 pub fn open_update_prompt(
     update: UpdatePrompt,
@@ -15,7 +17,11 @@ pub fn open_update_prompt(
 ) {
     let title = SharedString::new_static("Update Pandora?");
     let old_version = SharedString::new(format!("Current version: {}", update.old_version));
-    let new_version = SharedString::new(format!("New version: {}", update.new_version));
+    let new_version = if update.channel == UpdateChannel::Stable {
+        SharedString::new(format!("New version: {}", update.new_version))
+    } else {
+        SharedString::new(format!("New version: {} ({})", update.new_version, update.channel.name()))
+    };
 
     let size = if update.exe.size < 1000*10 {
         format!("Update size: {} bytes", update.exe.size)
@@ -46,8 +52,22 @@ pub fn open_update_prompt(
                     crate::modals::generic::show_notification(window, cx, "Unable to install update".into(), modal_action);
                 }
             }))
-            .child(Button::new("later").flex_1().label("Later").on_click(|_, window, cx| {
-                window.close_all_dialogs(cx);
+            .child(Button::new("remind-later").flex_1().label("Remind me later").on_click({
+                let handle = handle.clone();
+                move |_, window, cx| {
+                    handle.send(bridge::message::MessageToBackend::RemindAboutUpdateLater);
+                    window.close_all_dialogs(cx);
+                }
+            }))
+            .child(Button::new("skip").flex_1().label("Skip this version").on_click({
+                let handle = handle.clone();
+                let version = update.new_version.clone();
+                move |_, window, cx| {
+                    handle.send(bridge::message::MessageToBackend::SkipUpdate {
+                        version: version.clone(),
+                    });
+                    window.close_all_dialogs(cx);
+                }
             }));
 
         dialog
@@ -60,5 +80,51 @@ pub fn open_update_prompt(
                     .child(size.clone())
                 ).child(buttons))
     });
+}
+
+pub fn open_update_installed(version: Arc<str>, data: &DataEntities, window: &mut Window, cx: &mut App) {
+    let message = SharedString::new(format!("Pandora {version} is ready. Restart now to start using it, running games will keep running"));
+    let data = data.clone();
+
+    window.open_dialog(cx, move |dialog, _, _| {
+        let buttons = h_flex()
+            .w_full()
+            .gap_2()
+            .child(Button::new("restart").flex_1().label("Restart now").success().on_click({
+                let data = data.clone();
+                move |_, window, cx| {
+                    window.close_all_dialogs(cx);
+                    restart_after_update(&data, window, cx);
+                }
+            }))
+            .child(Button::new("later").flex_1().label("Later").on_click(|_, window, cx| {
+                window.close_all_dialogs(cx);
+            }));
+
+        dialog
+            .title("Update Installed")
+            .child(v_flex()
+                .gap_2()
+                .child(message.clone())
+                .child(buttons))
+    });
+}
+
+fn restart_after_update(data: &DataEntities, window: &mut Window, cx: &mut App) {
+    RelaunchState::save(data, cx);
+
+    let (send, recv) = tokio::sync::oneshot::channel();
+    data.backend_handle.send(bridge::message::MessageToBackend::RelaunchAfterUpdate { channel: send });
 
+    window.spawn(cx, async move |cx| {
+        let result = recv.await.unwrap_or_else(|_| Err("Request was cancelled".into()));
+        _ = cx.update(|window, cx| {
+            match result {
+                Ok(()) => cx.quit(),
+                Err(error) => {
+                    window.push_notification((NotificationType::Error, SharedString::new(format!("Unable to restart: {error}"))), cx);
+                },
+            }
+        });
+    }).detach();
 }
@@ -0,0 +1,62 @@
+use bridge::{handle::BackendHandle, instance::{InstanceID, InstanceVerifySummary}, message::MessageToBackend, modal_action::ModalAction};
+use gpui::{prelude::*, *};
+use gpui_component::{v_flex, WindowExt};
+
+pub fn start_verify_files(
+    id: InstanceID,
+    name: SharedString,
+    backend_handle: &BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let modal_action = ModalAction::default();
+    let (send, recv) = tokio::sync::oneshot::channel();
+
+    backend_handle.send(MessageToBackend::VerifyInstanceFiles {
+        id,
+        modal_action: modal_action.clone(),
+        channel: send,
+    });
+
+    crate::modals::generic::show_notification(window, cx, "Error verifying files".into(), modal_action);
+
+    window.spawn(cx, async move |cx| {
+        let Ok(summary) = recv.await else {
+            return;
+        };
+        _ = cx.update(move |window, cx| {
+            open_verify_summary(name, summary, window, cx);
+        });
+    }).detach();
+}
+
+fn open_verify_summary(name: SharedString, summary: InstanceVerifySummary, window: &mut Window, cx: &mut App) {
+    let title = SharedString::new(format!("Verified {}", name));
+
+    let repaired = summary.libraries_repaired + summary.assets_repaired + summary.content_repaired;
+    let message = if repaired == 0 {
+        SharedString::new_static("All files are intact")
+    } else if repaired == 1 {
+        SharedString::new_static("1 missing or corrupt file was downloaded again")
+    } else {
+        SharedString::new(format!("{} missing or corrupt files were downloaded again", repaired))
+    };
+
+    let mut lines = vec![
+        SharedString::new(format!("Libraries: {} checked, {} repaired", summary.libraries_checked, summary.libraries_repaired)),
+        SharedString::new(format!("Assets: {} checked, {} repaired", summary.assets_checked, summary.assets_repaired)),
+    ];
+    if summary.content_checked > 0 {
+        lines.push(SharedString::new(format!("Content: {} checked, {} repaired", summary.content_checked, summary.content_repaired)));
+    }
+
+    window.open_dialog(cx, move |dialog, _, _| {
+        dialog
+            .title(title.clone())
+            .confirm()
+            .child(v_flex()
+                .gap_2()
+                .child(message.clone())
+                .child(v_flex().children(lines.iter().cloned())))
+    });
+}
@@ -0,0 +1,408 @@
+use std::{path::{Path, PathBuf}, sync::Arc, time::Duration};
+
+use bridge::{
+    install::{ContentDownload, ContentInstall, ContentInstallFile, ContentInstallPath, InstallTarget}, instance::{InstanceDatapackSummary, InstanceID}, message::MessageToBackend, meta::MetadataRequest, modal_action::ModalAction
+};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, h_flex, input::{Input, InputEvent, InputState}, notification::{Notification, NotificationType}, scroll::ScrollableElement, sheet::Sheet, spinner::Spinner, switch::Switch, v_flex, ActiveTheme, Sizable, StyledExt, WindowExt
+};
+use schema::{
+    content::ContentSource, loader::Loader, modrinth::{ModrinthHit, ModrinthLoader, ModrinthProjectVersionsRequest, ModrinthProjectVersionsResult, ModrinthSearchIndex, ModrinthSearchRequest, ModrinthSearchResult}
+};
+use ustr::Ustr;
+
+use crate::{component::error_alert::ErrorAlert, entity::{metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult}, DataEntities}, png_render_cache};
+
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+struct WorldDatapacksState {
+    id: InstanceID,
+    world: Arc<Path>,
+    minecraft_version: Ustr,
+    data: DataEntities,
+    datapacks: Option<Result<Arc<[InstanceDatapackSummary]>, Arc<str>>>,
+    _task: Task<()>,
+}
+
+impl WorldDatapacksState {
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self.data.backend_handle.send(MessageToBackend::GetWorldDatapacks {
+            id: self.id,
+            world: self.world.clone(),
+            channel: send,
+        });
+
+        self._task = cx.spawn(async move |this, cx| {
+            let result = recv.await.unwrap_or_else(|_| Err("Request was cancelled".into()));
+            _ = this.update(cx, |this, cx| {
+                this.datapacks = Some(result);
+                cx.notify();
+            });
+        });
+    }
+
+    fn set_enabled(&mut self, pack_id: Arc<str>, enabled: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self.data.backend_handle.send(MessageToBackend::SetDatapacksEnabled {
+            id: self.id,
+            world: self.world.clone(),
+            pack_ids: vec![pack_id],
+            enabled,
+            channel: send,
+        });
+
+        self._task = cx.spawn_in(window, async move |this, cx| {
+            let result = recv.await.unwrap_or_else(|_| Err("Request was cancelled".into()));
+            _ = this.update_in(cx, |this, window, cx| {
+                if let Err(error) = result {
+                    let notification = Notification::new()
+                        .autohide(false)
+                        .with_type(NotificationType::Error)
+                        .title("Unable to change datapack")
+                        .message(SharedString::new(error));
+                    window.push_notification(notification, cx);
+                }
+                this.reload(cx);
+            });
+        });
+    }
+
+    fn datapacks_path(&self, filename: &str) -> Option<Arc<Path>> {
+        let world_folder = self.world.file_name()?;
+        Some(Path::new("saves").join(world_folder).join("datapacks").join(filename).into())
+    }
+
+    fn install(&mut self, files: Vec<ContentInstallFile>, window: &mut Window, cx: &mut Context<Self>) {
+        if files.is_empty() {
+            return;
+        }
+
+        let content_install = ContentInstall {
+            target: InstallTarget::Instance(self.id),
+            loader_hint: Loader::Unknown,
+            version_hint: Some(self.minecraft_version.as_str().into()),
+            files: files.into(),
+        };
+
+        let modal_action = ModalAction::default();
+        self.data.backend_handle.send(MessageToBackend::InstallContent {
+            content: content_install,
+            modal_action: modal_action.clone(),
+        });
+        crate::modals::generic::show_notification(window, cx, "Error installing datapack".into(), modal_action.clone());
+
+        // Datapacks aren't watched, so refresh the list once the install has finished
+        self._task = cx.spawn(async move |this, cx| {
+            while modal_action.refcnt() > 1 && modal_action.get_finished_at().is_none() {
+                gpui::Timer::after(Duration::from_millis(500)).await;
+            }
+            _ = this.update(cx, |this, cx| {
+                this.reload(cx);
+            });
+        });
+    }
+
+    fn install_paths(&mut self, paths: &[PathBuf], window: &mut Window, cx: &mut Context<Self>) {
+        let files = paths.iter().filter_map(|path| {
+            Some(ContentInstallFile {
+                replace_old: None,
+                path: ContentInstallPath::Raw(self.datapacks_path(path.file_name()?.to_str()?)?),
+                download: ContentDownload::File { path: path.clone() },
+                content_source: ContentSource::Manual,
+            })
+        }).collect();
+        self.install(files, window, cx);
+    }
+
+    fn install_from_modrinth(&mut self, project_id: Arc<str>, title: SharedString, window: &mut Window, cx: &mut Context<Self>) {
+        let request = MetadataRequest::ModrinthProjectVersions(ModrinthProjectVersionsRequest {
+            project_id: project_id.clone(),
+            game_versions: Some([Arc::from(self.minecraft_version.as_str())].into()),
+            loaders: Some([ModrinthLoader::Datapack].into()),
+        });
+        let versions = FrontendMetadata::request(&self.data.metadata, request, cx);
+
+        self._task = cx.spawn_in(window, async move |this, cx| {
+            loop {
+                let finished = cx.update(|_, cx| {
+                    let result: FrontendMetadataResult<ModrinthProjectVersionsResult> = versions.read(cx).result();
+                    !matches!(result, FrontendMetadataResult::Loading)
+                }).unwrap_or(true);
+                if finished {
+                    break;
+                }
+                gpui::Timer::after(Duration::from_millis(100)).await;
+            }
+
+            _ = this.update_in(cx, move |this, window, cx| {
+                let result: FrontendMetadataResult<ModrinthProjectVersionsResult> = versions.read(cx).result();
+                let install_file = match result {
+                    FrontendMetadataResult::Loaded(versions) => {
+                        versions.0.iter().find(|version| !version.files.is_empty()).map(|version| {
+                            version.files.iter().find(|file| file.primary).unwrap_or(&version.files[0]).clone()
+                        })
+                    },
+                    _ => None,
+                };
+
+                let Some(install_file) = install_file else {
+                    let notification = Notification::new()
+                        .autohide(false)
+                        .with_type(NotificationType::Error)
+                        .title(SharedString::new(format!("Unable to install {title}")))
+                        .message(SharedString::new(format!("No datapack version available for {}", this.minecraft_version)));
+                    window.push_notification(notification, cx);
+                    return;
+                };
+
+                let Some(path) = this.datapacks_path(&install_file.filename) else {
+                    return;
+                };
+
+                let file = ContentInstallFile {
+                    replace_old: None,
+                    path: ContentInstallPath::Raw(path),
+                    download: ContentDownload::Url {
+                        url: install_file.url.clone(),
+                        sha1: install_file.hashes.sha1.clone(),
+                        sha512: install_file.hashes.sha512.clone(),
+                        size: install_file.size,
+                    },
+                    content_source: ContentSource::ModrinthProject { project: project_id },
+                };
+                this.install(vec![file], window, cx);
+            });
+        });
+    }
+
+    fn render_datapacks(&self, cx: &mut Context<Self>) -> AnyElement {
+        let datapacks = match &self.datapacks {
+            None => return h_flex().justify_center().child(Spinner::new().large()).into_any_element(),
+            Some(Err(error)) => return ErrorAlert::new("datapacks_error", "Unable to load datapacks".into(), error.clone().into()).into_any_element(),
+            Some(Ok(datapacks)) if datapacks.is_empty() => return div().child("This world has no datapacks").into_any_element(),
+            Some(Ok(datapacks)) => datapacks.clone(),
+        };
+
+        let theme = cx.theme();
+        let border = theme.border;
+        let muted = theme.muted_foreground;
+
+        v_flex().gap_2().children(datapacks.iter().enumerate().map(|(index, datapack)| {
+            let icon = if let Some(png_icon) = datapack.png_icon.as_ref() {
+                png_render_cache::render(Arc::clone(png_icon), cx)
+            } else {
+                gpui::img(ImageSource::Resource(Resource::Embedded("images/default_mod.png".into())))
+            };
+
+            let pack_id = datapack.pack_id.clone();
+            h_flex()
+                .p_2()
+                .gap_3()
+                .rounded_lg()
+                .border_1()
+                .border_color(border)
+                .child(icon.size_10().min_w_10().min_h_10())
+                .child(v_flex()
+                    .flex_1()
+                    .overflow_hidden()
+                    .child(div().font_semibold().truncate().child(SharedString::new(datapack.filename.clone())))
+                    .children(datapack.description.clone().map(|description| {
+                        div().text_sm().text_color(muted).truncate().child(SharedString::new(description))
+                    })))
+                .child(Switch::new(("toggle", index)).checked(datapack.enabled).on_click(cx.listener(move |this, checked, window, cx| {
+                    this.set_enabled(pack_id.clone(), *checked, window, cx);
+                })))
+        })).into_any_element()
+    }
+}
+
+pub fn open(id: InstanceID, world: Arc<Path>, title: SharedString, data: &DataEntities, window: &mut Window, cx: &mut App) {
+    let Some(instance) = data.instances.read(cx).entries.get(&id) else {
+        return;
+    };
+    let minecraft_version = instance.read(cx).configuration.minecraft_version;
+
+    let state = cx.new(|cx| {
+        let mut state = WorldDatapacksState {
+            id,
+            world,
+            minecraft_version,
+            data: data.clone(),
+            datapacks: None,
+            _task: Task::ready(()),
+        };
+        state.reload(cx);
+        state
+    });
+
+    window.open_sheet_at(gpui_component::Placement::Right, cx, move |sheet: Sheet, window, cx| {
+        let sheet = sheet.title(SharedString::new(format!("Datapacks for {title}"))).overlay_top(crate::root::sheet_margin_top(window));
+
+        let buttons = h_flex()
+            .gap_2()
+            .child(Button::new("add_modrinth").success().small().label("Add from Modrinth").on_click({
+                let state = state.clone();
+                move |_, window, cx| {
+                    open_modrinth_search(state.clone(), window, cx);
+                }
+            }))
+            .child(Button::new("add_file").success().small().label("Add from file").on_click({
+                let state = state.clone();
+                move |_, window, cx| {
+                    let receiver = cx.prompt_for_paths(PathPromptOptions {
+                        files: true,
+                        directories: false,
+                        multiple: true,
+                        prompt: Some("Select datapacks to install".into())
+                    });
+                    let state = state.clone();
+                    window.spawn(cx, async move |cx| {
+                        let Ok(Ok(Some(paths))) = receiver.await else {
+                            return;
+                        };
+                        _ = cx.update(move |window, cx| {
+                            state.update(cx, |state, cx| {
+                                state.install_paths(&paths, window, cx);
+                            });
+                        });
+                    }).detach();
+                }
+            }));
+
+        let datapacks = cx.update_entity(&state, |state, cx| state.render_datapacks(cx));
+
+        sheet.child(v_flex()
+            .gap_3()
+            .child(buttons)
+            .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                .child("Changes take effect the next time the world is loaded"))
+            .child(v_flex().overflow_y_scrollbar().child(datapacks)))
+    });
+}
+
+struct ModrinthDatapackSearch {
+    datapacks: Entity<WorldDatapacksState>,
+    search_input: Entity<InputState>,
+    hits: Option<Result<Arc<[ModrinthHit]>, SharedString>>,
+    _search_task: Task<()>,
+    _search_subscription: Subscription,
+}
+
+impl ModrinthDatapackSearch {
+    fn search(&mut self, cx: &mut Context<Self>) {
+        let query = self.search_input.read(cx).value().trim().to_string();
+        let minecraft_version = self.datapacks.read(cx).minecraft_version;
+        let metadata = self.datapacks.read(cx).data.metadata.clone();
+
+        let request = MetadataRequest::ModrinthSearch(ModrinthSearchRequest {
+            query: if query.is_empty() { None } else { Some(query.into()) },
+            facets: Some(format!("[[\"project_type:datapack\"],[\"versions:{minecraft_version}\"]]").into()),
+            index: ModrinthSearchIndex::Relevance,
+            offset: 0,
+            limit: 20,
+        });
+        let result = FrontendMetadata::request(&metadata, request, cx);
+
+        self._search_task = cx.spawn(async move |this, cx| {
+            gpui::Timer::after(SEARCH_DEBOUNCE).await;
+            loop {
+                let hits = cx.update(|cx| {
+                    let result: FrontendMetadataResult<ModrinthSearchResult> = result.read(cx).result();
+                    match result {
+                        FrontendMetadataResult::Loading => None,
+                        FrontendMetadataResult::Loaded(result) => Some(Ok(result.hits.clone())),
+                        FrontendMetadataResult::Error(error) => Some(Err(error)),
+                    }
+                });
+                match hits {
+                    Ok(Some(hits)) => {
+                        _ = this.update(cx, |this, cx| {
+                            this.hits = Some(hits);
+                            cx.notify();
+                        });
+                        return;
+                    },
+                    Ok(None) => gpui::Timer::after(Duration::from_millis(100)).await,
+                    Err(_) => return,
+                }
+            }
+        });
+    }
+
+    fn render(&mut self, dialog: gpui_component::dialog::Dialog, _window: &mut Window, cx: &mut Context<Self>) -> gpui_component::dialog::Dialog {
+        let results = match &self.hits {
+            None => h_flex().justify_center().child(Spinner::new().large()).into_any_element(),
+            Some(Err(error)) => ErrorAlert::new("search_error", "Error requesting from Modrinth".into(), error.clone()).into_any_element(),
+            Some(Ok(hits)) if hits.is_empty() => div().child("No datapacks found").into_any_element(),
+            Some(Ok(hits)) => {
+                let theme = cx.theme();
+                let border = theme.border;
+                let muted = theme.muted_foreground;
+                v_flex().gap_2().children(hits.iter().enumerate().map(|(index, hit)| {
+                    let icon = match &hit.icon_url {
+                        Some(icon_url) if !icon_url.is_empty() => gpui::img(SharedUri::from(icon_url)),
+                        _ => gpui::img(ImageSource::Resource(Resource::Embedded("images/default_mod.png".into()))),
+                    };
+                    let title = hit.title.clone().map(SharedString::new).unwrap_or(SharedString::new_static("Unnamed"));
+                    let description = hit.description.clone().map(SharedString::new).unwrap_or_default();
+                    let project_id = hit.project_id.clone();
+                    let datapacks = self.datapacks.clone();
+
+                    h_flex()
+                        .p_2()
+                        .gap_3()
+                        .rounded_lg()
+                        .border_1()
+                        .border_color(border)
+                        .child(icon.rounded_md().size_10().min_w_10().min_h_10())
+                        .child(v_flex()
+                            .flex_1()
+                            .overflow_hidden()
+                            .child(div().font_semibold().child(title.clone()))
+                            .child(div().text_sm().text_color(muted).truncate().child(description)))
+                        .child(Button::new(("install", index)).success().small().label("Install").on_click(move |_, window, cx| {
+                            window.close_dialog(cx);
+                            datapacks.update(cx, |datapacks, cx| {
+                                datapacks.install_from_modrinth(project_id.clone(), title.clone(), window, cx);
+                            });
+                        }))
+                })).into_any_element()
+            },
+        };
+
+        dialog.title("Add Datapack from Modrinth")
+            .child(v_flex()
+                .gap_3()
+                .child(Input::new(&self.search_input))
+                .child(v_flex().max_h_96().overflow_y_scrollbar().child(results)))
+    }
+}
+
+fn open_modrinth_search(datapacks: Entity<WorldDatapacksState>, window: &mut Window, cx: &mut App) {
+    let search_input = cx.new(|cx| InputState::new(window, cx).placeholder("Search datapacks..."));
+
+    let state = cx.new(|cx| {
+        let _search_subscription = cx.subscribe(&search_input, |this: &mut ModrinthDatapackSearch, _, event: &InputEvent, cx| {
+            if let InputEvent::Change = event {
+                this.search(cx);
+            }
+        });
+        let mut state = ModrinthDatapackSearch {
+            datapacks,
+            search_input,
+            hits: None,
+            _search_task: Task::ready(()),
+            _search_subscription,
+        };
+        state.search(cx);
+        state
+    });
+
+    window.open_dialog(cx, move |dialog, window, cx| {
+        cx.update_entity(&state, |state, cx| {
+            state.render(dialog, window, cx)
+        })
+    });
+}
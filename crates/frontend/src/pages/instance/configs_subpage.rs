@@ -0,0 +1,369 @@
+use std::sync::Arc;
+
+use bridge::{handle::BackendHandle, instance::InstanceID, message::MessageToBackend, safe_path::SafePath};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, checkbox::Checkbox, h_flex, input::{Input, InputEvent, InputState}, notification::{Notification, NotificationType}, scroll::ScrollableElement, spinner::Spinner, v_flex, ActiveTheme as _, Disableable, Sizable, WindowExt
+};
+use rustc_hash::FxHashMap;
+
+use crate::{component::error_alert::ErrorAlert, entity::instance::InstanceEntry, interface_config::InterfaceConfig};
+
+pub struct InstanceConfigsSubpage {
+    instance: InstanceID,
+    backend_handle: BackendHandle,
+    files: Option<Arc<[SafePath]>>,
+    filter_input: Entity<InputState>,
+    open_files: FxHashMap<SafePath, OpenConfigFile>,
+    selected: Option<SafePath>,
+    _get_files_task: Task<()>,
+    _filter_subscription: Subscription,
+}
+
+struct OpenConfigFile {
+    state: OpenConfigFileState,
+    saved_content: SharedString,
+    saving: bool,
+    _task: Task<()>,
+}
+
+enum OpenConfigFileState {
+    Loading,
+    Error(SharedString),
+    Loaded {
+        editor: Entity<InputState>,
+        _subscription: Subscription,
+    },
+}
+
+impl OpenConfigFile {
+    fn is_modified(&self, cx: &App) -> bool {
+        match &self.state {
+            OpenConfigFileState::Loaded { editor, .. } => editor.read(cx).value() != self.saved_content,
+            _ => false,
+        }
+    }
+}
+
+impl InstanceConfigsSubpage {
+    pub fn new(
+        instance: &Entity<InstanceEntry>,
+        backend_handle: BackendHandle,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) -> Self {
+        let instance_id = instance.read(cx).id;
+
+        let filter_input = cx.new(|cx| InputState::new(window, cx).placeholder("Search configs..."));
+        let _filter_subscription = cx.subscribe(&filter_input, |_, _, _: &InputEvent, cx| {
+            cx.notify();
+        });
+
+        let mut this = Self {
+            instance: instance_id,
+            backend_handle,
+            files: None,
+            filter_input,
+            open_files: FxHashMap::default(),
+            selected: None,
+            _get_files_task: Task::ready(()),
+            _filter_subscription,
+        };
+
+        this.refresh_files(cx);
+
+        this
+    }
+
+    fn refresh_files(&mut self, cx: &mut Context<Self>) {
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self.backend_handle.send(MessageToBackend::GetConfigFiles {
+            instance: self.instance,
+            channel: send,
+        });
+
+        self._get_files_task = cx.spawn(async move |page, cx| {
+            let files = recv.await.unwrap_or_else(|_| Arc::from([]));
+            _ = page.update(cx, move |page, cx| {
+                // Files that were closed without changes are reloaded from disk when opened again
+                page.open_files.retain(|path, open_file| {
+                    Some(path) == page.selected.as_ref() || open_file.is_modified(cx)
+                });
+                page.files = Some(files);
+                cx.notify();
+            });
+        });
+    }
+
+    fn select_file(&mut self, path: SafePath, window: &mut Window, cx: &mut Context<Self>) {
+        self.selected = Some(path.clone());
+        cx.notify();
+
+        if self.open_files.contains_key(&path) {
+            return;
+        }
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self.backend_handle.send(MessageToBackend::ReadConfigFile {
+            instance: self.instance,
+            path: path.clone(),
+            channel: send,
+        });
+
+        let language = editor_language(&path);
+        let task_path = path.clone();
+        let task = cx.spawn_in(window, async move |page, cx| {
+            let result = recv.await.unwrap_or_else(|_| Err("Request was cancelled".into()));
+            _ = page.update_in(cx, move |page, window, cx| {
+                let Some(open_file) = page.open_files.get_mut(&task_path) else {
+                    return;
+                };
+
+                match result {
+                    Ok(content) => {
+                        let content = SharedString::new(content);
+                        let editor = cx.new(|cx| {
+                            InputState::new(window, cx)
+                                .code_editor(language)
+                                .line_number(true)
+                                .default_value(content.clone())
+                        });
+                        let _subscription = cx.subscribe(&editor, |_, _, _: &InputEvent, cx| {
+                            cx.notify();
+                        });
+                        open_file.saved_content = content;
+                        open_file.state = OpenConfigFileState::Loaded { editor, _subscription };
+                    },
+                    Err(error) => {
+                        open_file.state = OpenConfigFileState::Error(SharedString::new(error));
+                    },
+                }
+                cx.notify();
+            });
+        });
+
+        self.open_files.insert(path, OpenConfigFile {
+            state: OpenConfigFileState::Loading,
+            saved_content: SharedString::default(),
+            saving: false,
+            _task: task,
+        });
+    }
+
+    fn save_selected(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(path) = self.selected.clone() else {
+            return;
+        };
+        let Some(open_file) = self.open_files.get_mut(&path) else {
+            return;
+        };
+        let OpenConfigFileState::Loaded { editor, .. } = &open_file.state else {
+            return;
+        };
+        if open_file.saving {
+            return;
+        }
+
+        let content = editor.read(cx).value();
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self.backend_handle.send(MessageToBackend::WriteConfigFile {
+            instance: self.instance,
+            path: path.clone(),
+            content: Arc::from(content.as_str()),
+            backup: InterfaceConfig::get(cx).backup_configs_on_save,
+            channel: send,
+        });
+
+        open_file.saving = true;
+        open_file._task = cx.spawn_in(window, async move |page, cx| {
+            let result = recv.await.unwrap_or_else(|_| Err("Request was cancelled".into()));
+            _ = page.update_in(cx, move |page, window, cx| {
+                let Some(open_file) = page.open_files.get_mut(&path) else {
+                    return;
+                };
+                open_file.saving = false;
+
+                match result {
+                    Ok(()) => {
+                        open_file.saved_content = content;
+                    },
+                    Err(error) => {
+                        let notification = Notification::new()
+                            .autohide(false)
+                            .with_type(NotificationType::Error)
+                            .title(format!("Unable to save {}", path.as_str()))
+                            .message(SharedString::new(error));
+                        window.push_notification(notification, cx);
+                    },
+                }
+                cx.notify();
+            });
+        });
+        cx.notify();
+    }
+
+    fn revert_selected(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(open_file) = self.selected.as_ref().and_then(|path| self.open_files.get(path)) else {
+            return;
+        };
+        if let OpenConfigFileState::Loaded { editor, .. } = &open_file.state {
+            let saved_content = open_file.saved_content.clone();
+            editor.update(cx, |editor, cx| {
+                editor.set_value(saved_content, window, cx);
+            });
+        }
+        cx.notify();
+    }
+
+    fn render_file_list(&self, cx: &mut Context<Self>) -> AnyElement {
+        let Some(files) = &self.files else {
+            return v_flex().size_full().justify_center().items_center().child(Spinner::new().large()).into_any_element();
+        };
+
+        if files.is_empty() {
+            return v_flex().p_2().child("This instance has no config files").into_any_element();
+        }
+
+        let filter = self.filter_input.read(cx).value().to_lowercase();
+        let theme = cx.theme();
+
+        v_flex()
+            .id("config-files")
+            .size_full()
+            .overflow_y_scrollbar()
+            .children(files.iter().enumerate().filter(|(_, path)| {
+                filter.is_empty() || path.as_str().to_lowercase().contains(&filter)
+            }).map(|(index, path)| {
+                let selected = self.selected.as_ref() == Some(path);
+                let modified = self.open_files.get(path).is_some_and(|open_file| open_file.is_modified(cx));
+                let label = if modified {
+                    SharedString::new(format!("{} •", path.as_str()))
+                } else {
+                    SharedString::new(path.as_str())
+                };
+
+                let path = path.clone();
+                div()
+                    .id(("config-file", index))
+                    .px_2()
+                    .py_1()
+                    .text_sm()
+                    .truncate()
+                    .cursor_pointer()
+                    .when(selected, |this| this.bg(theme.accent))
+                    .when(modified, |this| this.text_color(theme.warning))
+                    .hover(|this| this.bg(theme.secondary_hover))
+                    .on_click(cx.listener(move |page, _, window, cx| {
+                        page.select_file(path.clone(), window, cx);
+                    }))
+                    .child(label)
+            }))
+            .into_any_element()
+    }
+
+    fn render_editor(&self, cx: &mut Context<Self>) -> AnyElement {
+        let Some((path, open_file)) = self.selected.as_ref().and_then(|path| Some((path, self.open_files.get(path)?))) else {
+            return v_flex().size_full().justify_center().items_center()
+                .text_color(cx.theme().muted_foreground)
+                .child("Select a config file to edit")
+                .into_any_element();
+        };
+
+        match &open_file.state {
+            OpenConfigFileState::Loading => {
+                v_flex().size_full().justify_center().items_center().child(Spinner::new().large()).into_any_element()
+            },
+            OpenConfigFileState::Error(error) => {
+                v_flex().p_2().child(ErrorAlert::new("config-error", "Unable to open config file".into(), error.clone())).into_any_element()
+            },
+            OpenConfigFileState::Loaded { editor, .. } => {
+                let modified = open_file.is_modified(cx);
+
+                let header = h_flex()
+                    .gap_2()
+                    .child(div().flex_1().truncate().child(if modified {
+                        SharedString::new(format!("{} (unsaved changes)", path.as_str()))
+                    } else {
+                        SharedString::new(path.as_str())
+                    }))
+                    .child(Button::new("revert").label("Revert").compact().small().disabled(!modified).on_click(cx.listener(|page, _, window, cx| {
+                        page.revert_selected(window, cx);
+                    })))
+                    .child(Button::new("save").label("Save").success().compact().small().loading(open_file.saving)
+                        .disabled(!modified || open_file.saving)
+                        .on_click(cx.listener(|page, _, window, cx| {
+                            page.save_selected(window, cx);
+                        })));
+
+                v_flex()
+                    .size_full()
+                    .gap_2()
+                    .child(header)
+                    .child(Input::new(editor).size_full())
+                    .into_any_element()
+            },
+        }
+    }
+}
+
+// Highlighting for .properties, .cfg and similar formats falls back to plain text
+fn editor_language(path: &SafePath) -> &'static str {
+    match path.extension().map(|extension| extension.to_ascii_lowercase()).as_deref() {
+        Some("toml") => "toml",
+        Some("json" | "json5") => "json",
+        Some("yml" | "yaml") => "yaml",
+        _ => "text",
+    }
+}
+
+impl Render for InstanceConfigsSubpage {
+    fn render(&mut self, _window: &mut gpui::Window, cx: &mut gpui::Context<Self>) -> impl gpui::IntoElement {
+        let theme = cx.theme();
+        let border = theme.border;
+        let radius = theme.radius;
+
+        let header = h_flex()
+            .gap_3()
+            .mb_1()
+            .ml_1()
+            .child(div().text_lg().child("Configs"))
+            .child(Button::new("refresh").label("Refresh").compact().small().on_click(cx.listener(|page, _, _, cx| {
+                page.refresh_files(cx);
+            })))
+            .child(Checkbox::new("backup_on_save")
+                .label("Back up on save")
+                .checked(InterfaceConfig::get(cx).backup_configs_on_save)
+                .on_click(|value, _, cx| {
+                    InterfaceConfig::get_mut(cx).backup_configs_on_save = *value;
+                }));
+
+        let file_list = self.render_file_list(cx);
+        let editor = self.render_editor(cx);
+
+        v_flex().p_4().size_full()
+            .child(header)
+            .child(h_flex()
+                .size_full()
+                .gap_2()
+                .items_start()
+                .child(v_flex()
+                    .w_72()
+                    .h_full()
+                    .gap_2()
+                    .p_2()
+                    .border_1()
+                    .rounded(radius)
+                    .border_color(border)
+                    .child(Input::new(&self.filter_input).small())
+                    .child(file_list))
+                .child(div()
+                    .flex_1()
+                    .h_full()
+                    .p_2()
+                    .border_1()
+                    .rounded(radius)
+                    .border_color(border)
+                    .child(editor)))
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use bridge::{handle::BackendHandle, instance::InstanceID, message::MessageToBackend};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::Button, h_flex, scroll::ScrollableElement, spinner::Spinner, v_flex, ActiveTheme as _, Sizable
+};
+use schema::instance_history::InstanceHistoryEntry;
+
+use crate::entity::instance::InstanceEntry;
+
+pub struct InstanceHistorySubpage {
+    instance: InstanceID,
+    backend_handle: BackendHandle,
+    entries: Option<Arc<[InstanceHistoryEntry]>>,
+    _get_entries_task: Task<()>,
+}
+
+impl InstanceHistorySubpage {
+    pub fn new(
+        instance: &Entity<InstanceEntry>,
+        backend_handle: BackendHandle,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) -> Self {
+        let mut this = Self {
+            instance: instance.read(cx).id,
+            backend_handle,
+            entries: None,
+            _get_entries_task: Task::ready(()),
+        };
+
+        this.update_entries(cx);
+
+        this
+    }
+
+    fn update_entries(&mut self, cx: &mut Context<Self>) {
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self._get_entries_task = cx.spawn(async move |page, cx| {
+            let result: Arc<[InstanceHistoryEntry]> = recv.await.unwrap_or_else(|_| Arc::from([]));
+            let _ = page.update(cx, move |page, cx| {
+                page.entries = Some(result);
+                cx.notify();
+            });
+        });
+
+        self.backend_handle.send(MessageToBackend::GetInstanceHistory {
+            id: self.instance,
+            channel: send,
+        });
+    }
+}
+
+impl Render for InstanceHistorySubpage {
+    fn render(&mut self, _window: &mut gpui::Window, cx: &mut gpui::Context<Self>) -> impl gpui::IntoElement {
+        let theme = cx.theme();
+
+        let header = h_flex()
+            .gap_3()
+            .mb_1()
+            .ml_1()
+            .child(div().text_lg().child("History"))
+            .child(Button::new("refresh").small().label("Refresh").on_click(cx.listener(|page, _, _, cx| {
+                page.update_entries(cx);
+            })));
+
+        let mut content = v_flex().p_3().gap_1().size_full();
+
+        match &self.entries {
+            None => {
+                content = content.child(Spinner::new().large());
+            },
+            Some(entries) if entries.is_empty() => {
+                content = content
+                    .child("Nothing has been recorded yet")
+                    .child(div().text_sm().text_color(theme.muted_foreground)
+                        .child("Launches, installed and removed content and changed settings are listed here"));
+            },
+            Some(entries) => {
+                // Newest first, with a heading whenever the day changes
+                let mut last_day = None;
+                for entry in entries.iter() {
+                    let Some(date_time) = chrono::DateTime::from_timestamp(entry.timestamp, 0) else {
+                        continue;
+                    };
+                    let date_time = date_time.with_timezone(&chrono::Local);
+
+                    let day = date_time.date_naive();
+                    if last_day != Some(day) {
+                        last_day = Some(day);
+                        content = content.child(div()
+                            .mt_2()
+                            .border_b_1()
+                            .border_color(theme.border)
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .child(day.format("%Y-%m-%d").to_string()));
+                    }
+
+                    content = content.child(h_flex()
+                        .gap_3()
+                        .child(div().text_sm().text_color(theme.muted_foreground).child(date_time.format("%H:%M").to_string()))
+                        .child(entry.event.describe()));
+                }
+            },
+        }
+
+        v_flex()
+            .p_4()
+            .size_full()
+            .child(header)
+            .child(div()
+                .size_full()
+                .border_1()
+                .rounded(theme.radius)
+                .border_color(theme.border)
+                .child(content.overflow_y_scrollbar())
+            )
+    }
+}
@@ -5,12 +5,13 @@ use bridge::{
 };
 use gpui::{prelude::*, *};
 use gpui_component::{
-    button::{Button, ButtonVariants}, h_flex, tab::{Tab, TabBar}, Icon, IconName
+    button::{Button, ButtonVariants}, h_flex, notification::NotificationType, tab::{Tab, TabBar}, Icon, IconName, WindowExt
 };
+use schema::instance::InstanceType;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    component::page_path::PagePath, entity::{DataEntities, instance::InstanceEntry}, pages::instance::{logs_subpage::InstanceLogsSubpage, mods_subpage::InstanceModsSubpage, quickplay_subpage::InstanceQuickplaySubpage, resource_packs_subpage::InstanceResourcePacksSubpage, settings_subpage::InstanceSettingsSubpage}, root, ui
+    component::{instance_list, page_path::PagePath}, entity::{DataEntities, instance::InstanceEntry}, pages::instance::{configs_subpage::InstanceConfigsSubpage, history_subpage::InstanceHistorySubpage, logs_subpage::InstanceLogsSubpage, mods_subpage::InstanceModsSubpage, notes_subpage::InstanceNotesSubpage, quickplay_subpage::InstanceQuickplaySubpage, resource_packs_subpage::InstanceResourcePacksSubpage, settings_subpage::InstanceSettingsSubpage, statistics_subpage::InstanceStatisticsSubpage}, root, ui
 };
 
 pub struct InstancePage {
@@ -36,6 +37,10 @@ impl InstancePage {
         }
     }
 
+    pub fn subpage_type(&self) -> InstanceSubpageType {
+        self.subpage.page_type()
+    }
+
     fn set_subpage(&mut self, page_type: InstanceSubpageType, window: &mut Window, cx: &mut Context<Self>) {
         if page_type == self.subpage.page_type() {
             return;
@@ -51,7 +56,11 @@ impl Render for InstancePage {
             InstanceSubpage::Logs(_) => 1,
             InstanceSubpage::Mods(_) => 2,
             InstanceSubpage::ResourcePacks(_) => 3,
-            InstanceSubpage::Settings(_) => 4,
+            InstanceSubpage::Configs(_) => 4,
+            InstanceSubpage::Statistics(_) => 5,
+            InstanceSubpage::Notes(_) => 6,
+            InstanceSubpage::History(_) => 7,
+            InstanceSubpage::Settings(_) => 8,
         };
 
         let play_icon = Icon::empty().path("icons/play.svg");
@@ -60,10 +69,11 @@ impl Render for InstancePage {
         let id = instance.id;
         let name = instance.name.clone();
         let backend_handle = self.backend_handle.clone();
+        let is_server = instance.configuration.instance_type == InstanceType::Server;
 
         let button = match instance.status {
             InstanceStatus::NotRunning => {
-                Button::new("start_instance").success().icon(play_icon).label("Start Instance").on_click(
+                Button::new("start_instance").success().icon(play_icon).label(if is_server { "Start Server" } else { "Start Instance" }).on_click(
                     move |_, window, cx| {
                         root::start_instance(id, name.clone(), None, &backend_handle, window, cx);
                     },
@@ -75,12 +85,25 @@ impl Render for InstancePage {
             InstanceStatus::Running => Button::new("kill_instance")
                 .danger()
                 .icon(IconName::Close)
-                .label("Kill Instance")
+                .label(if is_server { "Stop Server" } else { "Kill Instance" })
                 .on_click(move |_, _, _| {
                     backend_handle.send(MessageToBackend::KillInstance { id });
                 }),
         };
 
+        let prepare_button = (instance.status == InstanceStatus::NotRunning).then(|| {
+            let backend_handle = self.backend_handle.clone();
+            let name = instance.name.clone();
+            Button::new("prepare_instance")
+                .info()
+                .icon(IconName::ArrowDown)
+                .label("Download only")
+                .tooltip("Download all game files so the instance can be played offline")
+                .on_click(move |_, window, cx| {
+                    root::prepare_instance(id, name.clone(), &backend_handle, window, cx);
+                })
+        });
+
         let open_dot_minecraft_button = Button::new("open_dot_minecraft")
             .info()
             .icon(IconName::FolderOpen)
@@ -92,8 +115,30 @@ impl Render for InstancePage {
             }
         });
 
+        let share_buttons = if instance.status != InstanceStatus::Running {
+            None
+        } else if let Some(address) = instance.tunnel_address.clone() {
+            let backend_handle = self.backend_handle.clone();
+            Some(h_flex()
+                .gap_3()
+                .child(Button::new("copy_tunnel_address").info().label(format!("Shared at {address}")).tooltip("Copy address").on_click(move |_, window, cx| {
+                    cx.write_to_clipboard(ClipboardItem::new_string(address.to_string()));
+                    window.push_notification((NotificationType::Success, "Copied address to clipboard"), cx);
+                }))
+                .child(Button::new("stop_tunnel").warning().label("Stop Sharing").on_click(move |_, _, _| {
+                    backend_handle.send(MessageToBackend::StopInstanceTunnel { id });
+                })))
+        } else {
+            let backend_handle = self.backend_handle.clone();
+            Some(h_flex().child(Button::new("start_tunnel").info().icon(IconName::Globe).label("Share with Friends").on_click(move |_, _, _| {
+                backend_handle.send(MessageToBackend::StartInstanceTunnel { id });
+            })))
+        };
+
+        let favorite_button = instance_list::favorite_button("favorite", instance, &self.backend_handle);
+
         let breadcrumb = self.page_path.create_breadcrumb(&self.data, cx);
-        ui::page(cx, h_flex().gap_8().child(breadcrumb).child(h_flex().gap_3().child(button).child(open_dot_minecraft_button)))
+        ui::page(cx, h_flex().gap_8().child(h_flex().gap_2().child(breadcrumb).child(favorite_button)).child(h_flex().gap_3().child(button).children(prepare_button).child(open_dot_minecraft_button).children(share_buttons)))
             .child(
                 TabBar::new("bar")
                     .prefix(div().w_4())
@@ -103,14 +148,31 @@ impl Render for InstancePage {
                     .child(Tab::new().label("Logs"))
                     .child(Tab::new().label("Mods"))
                     .child(Tab::new().label("Resource Packs"))
+                    .child(Tab::new().label("Configs"))
+                    .child(Tab::new().label("Statistics"))
+                    .child(Tab::new().label("Notes"))
+                    .child(Tab::new().label("History"))
                     .child(Tab::new().label("Settings"))
+                    .suffix(Button::new("open_in_window")
+                        .ghost()
+                        .small()
+                        .icon(IconName::ExternalLink)
+                        .tooltip("Open in new window")
+                        .on_click(cx.listener(|page, _, _, cx| {
+                            let id = page.instance.read(cx).id;
+                            crate::instance_window::open(id, page.subpage.page_type(), &page.data, cx);
+                        })))
                     .on_click(cx.listener(|page, index, window, cx| {
                         let page_type = match *index {
                             0 => InstanceSubpageType::Quickplay,
                             1 => InstanceSubpageType::Logs,
                             2 => InstanceSubpageType::Mods,
                             3 => InstanceSubpageType::ResourcePacks,
-                            4 => InstanceSubpageType::Settings,
+                            4 => InstanceSubpageType::Configs,
+                            5 => InstanceSubpageType::Statistics,
+                            6 => InstanceSubpageType::Notes,
+                            7 => InstanceSubpageType::History,
+                            8 => InstanceSubpageType::Settings,
                             _ => {
                                 return;
                             },
@@ -129,10 +191,28 @@ pub enum InstanceSubpageType {
     Logs,
     Mods,
     ResourcePacks,
+    Configs,
+    Statistics,
+    Notes,
+    History,
     Settings,
 }
 
 impl InstanceSubpageType {
+    pub fn name(self) -> &'static str {
+        match self {
+            InstanceSubpageType::Quickplay => "Quickplay",
+            InstanceSubpageType::Logs => "Logs",
+            InstanceSubpageType::Mods => "Mods",
+            InstanceSubpageType::ResourcePacks => "Resource Packs",
+            InstanceSubpageType::Configs => "Configs",
+            InstanceSubpageType::Statistics => "Statistics",
+            InstanceSubpageType::Notes => "Notes",
+            InstanceSubpageType::History => "History",
+            InstanceSubpageType::Settings => "Settings",
+        }
+    }
+
     pub fn create(
         self,
         instance: &Entity<InstanceEntry>,
@@ -154,6 +234,18 @@ impl InstanceSubpageType {
             InstanceSubpageType::ResourcePacks => InstanceSubpage::ResourcePacks(cx.new(|cx| {
                 InstanceResourcePacksSubpage::new(instance, backend_handle, window, cx)
             })),
+            InstanceSubpageType::Configs => InstanceSubpage::Configs(cx.new(|cx| {
+                InstanceConfigsSubpage::new(instance, backend_handle, window, cx)
+            })),
+            InstanceSubpageType::Statistics => InstanceSubpage::Statistics(cx.new(|cx| {
+                InstanceStatisticsSubpage::new(instance, backend_handle, window, cx)
+            })),
+            InstanceSubpageType::Notes => InstanceSubpage::Notes(cx.new(|cx| {
+                InstanceNotesSubpage::new(instance, backend_handle, window, cx)
+            })),
+            InstanceSubpageType::History => InstanceSubpage::History(cx.new(|cx| {
+                InstanceHistorySubpage::new(instance, backend_handle, window, cx)
+            })),
             InstanceSubpageType::Settings => InstanceSubpage::Settings(cx.new(|cx| {
                 InstanceSettingsSubpage::new(instance, data, backend_handle, window, cx)
             })),
@@ -167,6 +259,10 @@ pub enum InstanceSubpage {
     Logs(Entity<InstanceLogsSubpage>),
     Mods(Entity<InstanceModsSubpage>),
     ResourcePacks(Entity<InstanceResourcePacksSubpage>),
+    Configs(Entity<InstanceConfigsSubpage>),
+    Statistics(Entity<InstanceStatisticsSubpage>),
+    Notes(Entity<InstanceNotesSubpage>),
+    History(Entity<InstanceHistorySubpage>),
     Settings(Entity<InstanceSettingsSubpage>),
 }
 
@@ -177,6 +273,10 @@ impl InstanceSubpage {
             InstanceSubpage::Logs(_) => InstanceSubpageType::Logs,
             InstanceSubpage::Mods(_) => InstanceSubpageType::Mods,
             InstanceSubpage::ResourcePacks(_) => InstanceSubpageType::ResourcePacks,
+            InstanceSubpage::Configs(_) => InstanceSubpageType::Configs,
+            InstanceSubpage::Statistics(_) => InstanceSubpageType::Statistics,
+            InstanceSubpage::Notes(_) => InstanceSubpageType::Notes,
+            InstanceSubpage::History(_) => InstanceSubpageType::History,
             InstanceSubpage::Settings(_) => InstanceSubpageType::Settings,
         }
     }
@@ -187,6 +287,10 @@ impl InstanceSubpage {
             Self::Logs(entity) => entity.into_any_element(),
             Self::Mods(entity) => entity.into_any_element(),
             Self::ResourcePacks(entity) => entity.into_any_element(),
+            Self::Configs(entity) => entity.into_any_element(),
+            Self::Statistics(entity) => entity.into_any_element(),
+            Self::Notes(entity) => entity.into_any_element(),
+            Self::History(entity) => entity.into_any_element(),
             Self::Settings(entity) => entity.into_any_element(),
         }
     }
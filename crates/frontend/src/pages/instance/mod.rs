@@ -1,6 +1,10 @@
+pub mod configs_subpage;
+pub mod history_subpage;
 pub mod instance_page;
 pub mod logs_subpage;
 pub mod mods_subpage;
+pub mod notes_subpage;
 pub mod quickplay_subpage;
 pub mod resource_packs_subpage;
 pub mod settings_subpage;
+pub mod statistics_subpage;
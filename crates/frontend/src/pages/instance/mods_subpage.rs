@@ -20,6 +20,7 @@ use super::instance_page::InstanceSubpageType;
 
 pub struct InstanceModsSubpage {
     instance: InstanceID,
+    instance_entry: Entity<InstanceEntry>,
     instance_title: SharedString,
     instance_loader: Loader,
     instance_version: Ustr,
@@ -37,6 +38,7 @@ impl InstanceModsSubpage {
         window: &mut gpui::Window,
         cx: &mut gpui::Context<Self>,
     ) -> Self {
+        let instance_entry = instance.clone();
         let instance = instance.read(cx);
         let instance_title = instance.title().into();
         let instance_loader = instance.configuration.loader;
@@ -62,6 +64,7 @@ impl InstanceModsSubpage {
 
         Self {
             instance: instance_id,
+            instance_entry,
             instance_title,
             instance_loader,
             instance_version,
@@ -112,6 +115,31 @@ impl Render for InstanceModsSubpage {
                     crate::root::start_update_check(instance_id, &backend_handle, window, cx);
                 }
             }))
+            .child(Button::new("enable").label("Enable").compact().small()
+                .tooltip("Enable the selected mods, or all mods if none are selected")
+                .on_click({
+                    let mod_list = self.mod_list.clone();
+                    move |_, _, cx| {
+                        mod_list.read(cx).delegate().set_selected_enabled(true);
+                    }
+                }))
+            .child(Button::new("disable").label("Disable").compact().small()
+                .tooltip("Disable the selected mods, or all mods if none are selected")
+                .on_click({
+                    let mod_list = self.mod_list.clone();
+                    move |_, _, cx| {
+                        mod_list.read(cx).delegate().set_selected_enabled(false);
+                    }
+                }))
+            .child(Button::new("profiles").label("Profiles").compact().small()
+                .tooltip("Switch between saved sets of enabled mods and resource packs")
+                .on_click({
+                    let instance_entry = self.instance_entry.clone();
+                    let backend_handle = self.backend_handle.clone();
+                    move |_, window, cx| {
+                        crate::modals::content_profiles::open_content_profiles(instance_entry.clone(), backend_handle.clone(), window, cx);
+                    }
+                }))
             .child(Button::new("addmr").label("Add from Modrinth").success().compact().small().on_click({
                 let instance = self.instance;
                 move |_, window, cx| {
@@ -0,0 +1,254 @@
+use std::{sync::Arc, time::Duration};
+
+use bridge::{handle::BackendHandle, instance::{InstanceID, InstanceReadme}, message::MessageToBackend};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, h_flex, input::{Input, InputEvent, InputState}, scroll::ScrollableElement, spinner::Spinner, v_flex, ActiveTheme as _, Sizable
+};
+
+use crate::{component::{error_alert::ErrorAlert, markdown::Markdown}, entity::instance::InstanceEntry};
+
+// How long typing has to pause before the notes are saved
+const AUTOSAVE_DELAY: Duration = Duration::from_secs(1);
+
+pub struct InstanceNotesSubpage {
+    instance: InstanceID,
+    backend_handle: BackendHandle,
+    state: NotesState,
+    readme: Option<InstanceReadme>,
+    preview: bool,
+    saved_notes: SharedString,
+    // Notes which have been edited but not saved yet, these are also saved when the page is closed
+    unsaved_notes: Option<SharedString>,
+    saving: bool,
+    save_error: Option<SharedString>,
+    _load_task: Task<()>,
+    _save_task: Task<()>,
+}
+
+enum NotesState {
+    Loading,
+    Error(SharedString),
+    Loaded {
+        editor: Entity<InputState>,
+        _subscription: Subscription,
+    },
+}
+
+impl InstanceNotesSubpage {
+    pub fn new(
+        instance: &Entity<InstanceEntry>,
+        backend_handle: BackendHandle,
+        window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) -> Self {
+        let instance_id = instance.read(cx).id;
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        backend_handle.send(MessageToBackend::GetInstanceNotes {
+            id: instance_id,
+            channel: send,
+        });
+
+        let _load_task = cx.spawn_in(window, async move |page, cx| {
+            let result = recv.await.unwrap_or_else(|_| Err("Request was cancelled".into()));
+            _ = page.update_in(cx, move |page, window, cx| {
+                match result {
+                    Ok(notes) => {
+                        let saved_notes = SharedString::new(notes.notes);
+                        let editor = cx.new(|cx| {
+                            InputState::new(window, cx)
+                                .multi_line(true)
+                                .placeholder("Write down anything about this instance, e.g. which world it's for or what was changed. Markdown is supported")
+                                .default_value(saved_notes.clone())
+                        });
+                        let _subscription = cx.subscribe(&editor, |page, editor, event: &InputEvent, cx| {
+                            if let InputEvent::Change = event {
+                                page.unsaved_notes = Some(editor.read(cx).value());
+                                page.schedule_save(cx);
+                            }
+                        });
+                        // Start with the preview when there's something to read
+                        page.preview = !saved_notes.trim().is_empty();
+                        page.saved_notes = saved_notes;
+                        page.readme = notes.readme;
+                        page.state = NotesState::Loaded { editor, _subscription };
+                    },
+                    Err(error) => {
+                        page.state = NotesState::Error(SharedString::new(error));
+                    },
+                }
+                cx.notify();
+            });
+        });
+
+        Self {
+            instance: instance_id,
+            backend_handle,
+            state: NotesState::Loading,
+            readme: None,
+            preview: false,
+            saved_notes: SharedString::default(),
+            unsaved_notes: None,
+            saving: false,
+            save_error: None,
+            _load_task,
+            _save_task: Task::ready(()),
+        }
+    }
+
+    fn schedule_save(&mut self, cx: &mut Context<Self>) {
+        // Replacing the task cancels the previous timer, so only the last change is saved
+        self.saving = false;
+        self._save_task = cx.spawn(async move |page, cx| {
+            cx.background_executor().timer(AUTOSAVE_DELAY).await;
+            _ = page.update(cx, |page, cx| {
+                page.save(cx);
+            });
+        });
+        cx.notify();
+    }
+
+    fn save(&mut self, cx: &mut Context<Self>) {
+        let Some(notes) = self.unsaved_notes.take() else {
+            return;
+        };
+        if notes == self.saved_notes {
+            return;
+        }
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self.backend_handle.send(MessageToBackend::SetInstanceNotes {
+            id: self.instance,
+            notes: Arc::from(notes.as_str()),
+            channel: send,
+        });
+
+        self.saving = true;
+        self._save_task = cx.spawn(async move |page, cx| {
+            let result = recv.await.unwrap_or_else(|_| Err("Request was cancelled".into()));
+            _ = page.update(cx, move |page, cx| {
+                page.saving = false;
+                match result {
+                    Ok(()) => {
+                        page.saved_notes = notes;
+                        page.save_error = None;
+                    },
+                    Err(error) => {
+                        page.save_error = Some(SharedString::new(error));
+                    },
+                }
+                cx.notify();
+            });
+        });
+    }
+
+    fn render_notes(&self, cx: &mut Context<Self>) -> AnyElement {
+        let editor = match &self.state {
+            NotesState::Loading => {
+                return v_flex().size_full().justify_center().items_center().child(Spinner::new().large()).into_any_element();
+            },
+            NotesState::Error(error) => {
+                return v_flex().p_2().child(ErrorAlert::new("notes-error", "Unable to load notes".into(), error.clone())).into_any_element();
+            },
+            NotesState::Loaded { editor, .. } => editor,
+        };
+
+        let status = if let Some(error) = &self.save_error {
+            div().text_color(cx.theme().danger).truncate().child(SharedString::new(format!("Unable to save notes: {error}")))
+        } else if self.saving || self.unsaved_notes.is_some() {
+            div().text_color(cx.theme().muted_foreground).child("Saving...")
+        } else {
+            div().text_color(cx.theme().muted_foreground).child("Saved")
+        };
+
+        let header = h_flex()
+            .gap_2()
+            .child(div().text_lg().child("Notes"))
+            .child(status.flex_1().text_sm())
+            .child(Button::new("toggle_preview")
+                .label(if self.preview { "Edit" } else { "Preview" })
+                .compact()
+                .small()
+                .on_click(cx.listener(|page, _, _, cx| {
+                    page.preview = !page.preview;
+                    cx.notify();
+                })));
+
+        let content = if self.preview {
+            let notes = editor.read(cx).value();
+            if notes.trim().is_empty() {
+                div().text_color(cx.theme().muted_foreground).child("No notes yet").into_any_element()
+            } else {
+                v_flex().id("notes-preview").size_full().overflow_y_scrollbar().child(Markdown::new("notes-markdown", notes)).into_any_element()
+            }
+        } else {
+            Input::new(editor).size_full().into_any_element()
+        };
+
+        v_flex().size_full().gap_2().child(header).child(content).into_any_element()
+    }
+
+    fn render_readme(&self, readme: &InstanceReadme) -> AnyElement {
+        let content = SharedString::new(readme.content.clone());
+        let body = if readme.filename.to_lowercase().ends_with(".md") {
+            Markdown::new("readme-markdown", content).into_any_element()
+        } else {
+            div().child(content).into_any_element()
+        };
+
+        v_flex()
+            .size_full()
+            .gap_2()
+            .child(div().text_lg().child(SharedString::new(readme.filename.clone())))
+            .child(v_flex().id("readme").size_full().overflow_y_scrollbar().child(body))
+            .into_any_element()
+    }
+}
+
+impl Drop for InstanceNotesSubpage {
+    fn drop(&mut self) {
+        // The autosave timer is cancelled along with the page, so save whatever was typed just before closing it
+        if let Some(notes) = self.unsaved_notes.take() && notes != self.saved_notes {
+            let (send, _) = tokio::sync::oneshot::channel();
+            self.backend_handle.send(MessageToBackend::SetInstanceNotes {
+                id: self.instance,
+                notes: Arc::from(notes.as_str()),
+                channel: send,
+            });
+        }
+    }
+}
+
+impl Render for InstanceNotesSubpage {
+    fn render(&mut self, _window: &mut gpui::Window, cx: &mut gpui::Context<Self>) -> impl gpui::IntoElement {
+        let theme = cx.theme();
+        let border = theme.border;
+        let radius = theme.radius;
+
+        let notes = self.render_notes(cx);
+        let readme = self.readme.as_ref().map(|readme| self.render_readme(readme));
+
+        v_flex().p_4().size_full()
+            .child(h_flex()
+                .size_full()
+                .gap_2()
+                .items_start()
+                .child(div()
+                    .flex_1()
+                    .h_full()
+                    .p_2()
+                    .border_1()
+                    .rounded(radius)
+                    .border_color(border)
+                    .child(notes))
+                .children(readme.map(|readme| div()
+                    .flex_1()
+                    .h_full()
+                    .p_2()
+                    .border_1()
+                    .rounded(radius)
+                    .border_color(border)
+                    .child(readme))))
+    }
+}
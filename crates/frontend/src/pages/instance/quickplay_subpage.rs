@@ -6,18 +6,28 @@ use std::{
 use bridge::{
     handle::BackendHandle,
     instance::{InstanceID, InstanceServerSummary, InstanceWorldSummary},
-    message::{AtomicBridgeDataLoadState, MessageToBackend, QuickPlayLaunch}, serial::AtomicOptionSerial,
+    message::{AtomicBridgeDataLoadState, MessageToBackend, QuickPlayLaunch}, modal_action::ModalAction,
+    realms::{RealmState, RealmSummary}, serial::AtomicOptionSerial,
 };
 use gpui::{prelude::*, *};
 use gpui_component::{
     ActiveTheme as _, Icon, IndexPath,
     button::{Button, ButtonVariants},
+    Disableable, Sizable,
     h_flex,
     list::{ListDelegate, ListItem, ListState},
+    scroll::ScrollableElement,
+    spinner::Spinner,
+    tooltip::Tooltip,
     v_flex,
 };
+use ustr::Ustr;
 
-use crate::{entity::instance::InstanceEntry, png_render_cache, root};
+use crate::{
+    component::error_alert::ErrorAlert,
+    entity::{instance::InstanceEntry, server_status::{ServerStatusEntry, ServerStatuses}},
+    modals, png_render_cache, root,
+};
 
 pub struct InstanceQuickplaySubpage {
     instance: InstanceID,
@@ -28,6 +38,11 @@ pub struct InstanceQuickplaySubpage {
     server_list: Entity<ListState<ServersListDelegate>>,
     worlds_serial: AtomicOptionSerial,
     servers_serial: AtomicOptionSerial,
+    name: SharedString,
+    minecraft_version: Ustr,
+    realms: Option<Result<Arc<[RealmSummary]>, SharedString>>,
+    loading_realms: bool,
+    _realms_task: Task<()>,
 }
 
 impl InstanceQuickplaySubpage {
@@ -39,6 +54,8 @@ impl InstanceQuickplaySubpage {
     ) -> Self {
         let instance = instance.read(cx);
         let instance_id = instance.id;
+        let minecraft_version = instance.configuration.minecraft_version;
+        let name = instance.name.clone();
 
         let worlds_state = Arc::clone(&instance.worlds_state);
         let servers_state = Arc::clone(&instance.servers_state);
@@ -51,16 +68,21 @@ impl InstanceQuickplaySubpage {
             searched: instance.worlds.read(cx).to_vec(),
         };
 
+        let server_statuses = root::data_entities(cx).server_statuses;
+        let server_addresses: Vec<Arc<str>> = instance.servers.read(cx).iter().map(|server| server.ip.clone()).collect();
+
         let servers_list_delegate = ServersListDelegate {
             id: instance_id,
             name: instance.name.clone(),
             backend_handle: backend_handle.clone(),
+            server_statuses: server_statuses.clone(),
             servers: instance.servers.read(cx).to_vec(),
             searched: instance.servers.read(cx).to_vec(),
         };
 
         let worlds = instance.worlds.clone();
         let servers = instance.servers.clone();
+        let observed_statuses = server_statuses.clone();
 
         let window2 = &mut window;
         let world_list = cx.new(move |cx| {
@@ -79,14 +101,22 @@ impl InstanceQuickplaySubpage {
             cx.observe(&servers, |list: &mut ListState<ServersListDelegate>, servers, cx| {
                 let servers = servers.read(cx).to_vec();
                 let delegate = list.delegate_mut();
+                for server in &servers {
+                    ServerStatuses::request(&delegate.server_statuses, server.ip.clone(), false, &delegate.backend_handle, cx);
+                }
                 delegate.servers = servers.clone();
                 delegate.searched = servers;
                 cx.notify();
             }).detach();
+            cx.observe(&observed_statuses, |_, _, cx| cx.notify()).detach();
 
             ListState::new(servers_list_delegate, window, cx).selectable(false).searchable(true)
         });
 
+        for address in server_addresses {
+            ServerStatuses::request(&server_statuses, address, false, &backend_handle, cx);
+        }
+
         Self {
             instance: instance_id,
             backend_handle,
@@ -96,14 +126,143 @@ impl InstanceQuickplaySubpage {
             server_list,
             worlds_serial: AtomicOptionSerial::default(),
             servers_serial: AtomicOptionSerial::default(),
+            name,
+            minecraft_version,
+            realms: None,
+            loading_realms: false,
+            _realms_task: Task::ready(()),
+        }
+    }
+
+    fn refresh_server_statuses(&mut self, cx: &mut Context<Self>) {
+        let server_list = self.server_list.read(cx).delegate();
+        let server_statuses = server_list.server_statuses.clone();
+        let addresses: Vec<Arc<str>> = server_list.servers.iter().map(|server| server.ip.clone()).collect();
+        for address in addresses {
+            ServerStatuses::request(&server_statuses, address, true, &self.backend_handle, cx);
+        }
+    }
+
+    fn load_realms(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.loading_realms {
+            return;
+        }
+        self.loading_realms = true;
+        cx.notify();
+
+        let modal_action = ModalAction::default();
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self.backend_handle.send(MessageToBackend::GetRealms {
+            minecraft_version: self.minecraft_version,
+            modal_action: modal_action.clone(),
+            channel: send,
+        });
+
+        // Signing in may need the user to log in again
+        modals::generic::show_notification(window, cx, "Error signing in".into(), modal_action);
+
+        self._realms_task = cx.spawn(async move |page, cx| {
+            let result = recv.await.unwrap_or_else(|_| Err("Request was cancelled".into()));
+            _ = page.update(cx, move |page, cx| {
+                page.loading_realms = false;
+                page.realms = Some(result.map_err(SharedString::new));
+                cx.notify();
+            });
+        });
+    }
+
+    fn render_realms(&self, cx: &mut Context<Self>) -> AnyElement {
+        if self.loading_realms {
+            return v_flex().size_full().justify_center().items_center().child(Spinner::new().large()).into_any_element();
+        }
+
+        let realms = match &self.realms {
+            None => {
+                return v_flex()
+                    .size_full()
+                    .gap_2()
+                    .justify_center()
+                    .items_center()
+                    .child("Realms are loaded using the selected account")
+                    .child(Button::new("load-realms").label("Load Realms").on_click(cx.listener(|page, _, window, cx| {
+                        page.load_realms(window, cx);
+                    })))
+                    .into_any_element();
+            },
+            Some(Err(error)) => {
+                return v_flex().p_2().child(ErrorAlert::new("realms-error", "Unable to load Realms".into(), error.clone())).into_any_element();
+            },
+            Some(Ok(realms)) => realms,
+        };
+
+        if realms.is_empty() {
+            return v_flex().p_2().child("This account doesn't have any Realms").into_any_element();
         }
+
+        let theme = cx.theme();
+
+        v_flex()
+            .id("realms")
+            .size_full()
+            .overflow_y_scrollbar()
+            .children(realms.iter().enumerate().map(|(index, realm)| {
+                let (state_label, state_color) = match realm.state {
+                    RealmState::Open => ("Open", theme.success),
+                    RealmState::Closed => ("Closed", theme.muted_foreground),
+                    RealmState::Expired => ("Expired", theme.danger),
+                    RealmState::Uninitialized => ("Not set up", theme.muted_foreground),
+                };
+
+                let mut details = format!("{}/{} online", realm.online_players, realm.max_players);
+                if !realm.owner.is_empty() {
+                    details.push_str(&format!(" · Owned by {}", realm.owner));
+                }
+                if let Some(minigame) = &realm.minigame {
+                    details.push_str(&format!(" · Playing {minigame}"));
+                }
+
+                let id = self.instance;
+                let name = self.name.clone();
+                let backend_handle = self.backend_handle.clone();
+                let target = OsString::from(realm.id.to_string());
+
+                h_flex()
+                    .p_1()
+                    .gap_1()
+                    .child(div()
+                        .child(Button::new(("join-realm", index))
+                            .success()
+                            .icon(Icon::empty().path("icons/play.svg"))
+                            .disabled(realm.state != RealmState::Open)
+                            .on_click(move |_, window, cx| {
+                                root::start_instance(
+                                    id,
+                                    name.clone(),
+                                    Some(QuickPlayLaunch::Realms(target.clone())),
+                                    &backend_handle,
+                                    window,
+                                    cx,
+                                );
+                            }))
+                        .px_2())
+                    .child(v_flex()
+                        .flex_1()
+                        .overflow_hidden()
+                        .child(h_flex()
+                            .gap_2()
+                            .child(div().truncate().child(SharedString::from(realm.name.clone())))
+                            .child(div().text_sm().text_color(state_color).child(state_label)))
+                        .when(!realm.motd.is_empty(), |this| {
+                            this.child(div().truncate().text_sm().text_color(theme.muted_foreground).child(SharedString::from(realm.motd.clone())))
+                        })
+                        .child(div().text_sm().text_color(theme.muted_foreground).child(SharedString::new(details))))
+            }))
+            .into_any_element()
     }
 }
 
 impl Render for InstanceQuickplaySubpage {
     fn render(&mut self, _window: &mut gpui::Window, cx: &mut gpui::Context<Self>) -> impl gpui::IntoElement {
-        let theme = cx.theme();
-
         let state = self.worlds_state.load(Ordering::SeqCst);
         if state.should_send_load_request() {
             self.backend_handle.send_with_serial(MessageToBackend::RequestLoadWorlds { id: self.instance }, &self.worlds_serial);
@@ -115,7 +274,31 @@ impl Render for InstanceQuickplaySubpage {
         }
 
         let worlds_header = div().mb_1().ml_1().text_lg().child("Worlds");
-        let servers_header = div().mb_1().ml_1().text_lg().child("Servers");
+        let servers_header = h_flex()
+            .mb_1()
+            .ml_1()
+            .gap_3()
+            .child(div().text_lg().child("Servers"))
+            .child(Button::new("refresh-servers").label("Refresh").compact().small().on_click(cx.listener(|page, _, _, cx| {
+                page.refresh_server_statuses(cx);
+            })))
+            .child(Button::new("server-resource-packs").label("Resource packs").compact().small().on_click(cx.listener(|page, _, window, cx| {
+                let data = root::data_entities(cx);
+                crate::modals::server_resource_packs::open(page.instance, page.name.clone(), &data, window, cx);
+            })));
+        let realms_header = h_flex()
+            .mb_1()
+            .ml_1()
+            .gap_3()
+            .child(div().text_lg().child("Realms"))
+            .when(self.realms.is_some(), |this| {
+                this.child(Button::new("refresh-realms").label("Refresh").compact().small().disabled(self.loading_realms)
+                    .on_click(cx.listener(|page, _, window, cx| {
+                        page.load_realms(window, cx);
+                    })))
+            });
+        let realms = self.render_realms(cx);
+        let theme = cx.theme();
 
         v_flex().p_4().gap_4().size_full().child(
             h_flex()
@@ -142,6 +325,17 @@ impl Render for InstanceQuickplaySubpage {
                             .border_color(theme.border)
                             .child(self.server_list.clone()),
                     ),
+                )
+                .child(
+                    v_flex().size_full().child(realms_header).child(
+                        v_flex()
+                            .text_base()
+                            .size_full()
+                            .border_1()
+                            .rounded(theme.radius)
+                            .border_color(theme.border)
+                            .child(realms),
+                    ),
                 ),
         )
     }
@@ -188,12 +382,14 @@ impl ListDelegate for WorldsListDelegate {
         let name = self.name.clone();
         let backend_handle = self.backend_handle.clone();
         let target = summary.level_path.file_name().unwrap().to_owned();
+        let level_path = summary.level_path.clone();
+        let world_title = SharedString::from(summary.title.clone());
         let item = ListItem::new(ix).p_1().child(
             h_flex()
                 .gap_1()
                 .child(
                     div()
-                        .child(Button::new(ix).success().icon(play_icon).on_click(move |_, window, cx| {
+                        .child(Button::new(ix).success().icon(play_icon).tooltip("Join world").on_click(move |_, window, cx| {
                             root::start_instance(
                                 id,
                                 name.clone(),
@@ -206,7 +402,11 @@ impl ListDelegate for WorldsListDelegate {
                         .px_2(),
                 )
                 .child(icon.size_16().min_w_16().min_h_16())
-                .child(description),
+                .child(description.flex_1())
+                .child(Button::new(("datapacks", ix.row)).small().label("Datapacks").on_click(move |_, window, cx| {
+                    let data = root::data_entities(cx);
+                    crate::modals::world_datapacks::open(id, level_path.clone(), world_title.clone(), &data, window, cx);
+                })),
         );
 
         Some(item)
@@ -226,6 +426,7 @@ pub struct ServersListDelegate {
     id: InstanceID,
     name: SharedString,
     backend_handle: BackendHandle,
+    server_statuses: Entity<ServerStatuses>,
     servers: Vec<InstanceServerSummary>,
     searched: Vec<InstanceServerSummary>,
 }
@@ -239,16 +440,56 @@ impl ListDelegate for ServersListDelegate {
 
     fn render_item(&mut self, ix: IndexPath, _window: &mut Window, cx: &mut Context<ListState<Self>>) -> Option<Self::Item> {
         let summary = self.searched.get(ix.row)?;
+        let status = self.server_statuses.read(cx).get(&summary.ip).cloned();
 
-        let icon = if let Some(png_icon) = summary.png_icon.as_ref() {
+        // The favicon saved in servers.dat can be outdated, prefer the one the server sent
+        let png_icon = match &status {
+            Some(ServerStatusEntry::Online(status)) => status.png_icon.as_ref().or(summary.png_icon.as_ref()),
+            _ => summary.png_icon.as_ref(),
+        };
+        let icon = if let Some(png_icon) = png_icon {
             png_render_cache::render(Arc::clone(png_icon), cx)
         } else {
             gpui::img(ImageSource::Resource(Resource::Embedded("images/default_world.png".into())))
         };
 
+        let danger = cx.theme().danger;
+        let muted = Hsla { h: 0.0, s: 0.0, l: 0.5, a: 1.0};
+
+        let (subtitle, status_element) = match status {
+            Some(ServerStatusEntry::Online(status)) => {
+                let subtitle = if status.motd.is_empty() {
+                    SharedString::from(summary.ip.clone())
+                } else {
+                    SharedString::from(status.motd.clone())
+                };
+                let status_element = v_flex()
+                    .items_end()
+                    .child(SharedString::new(format!("{}/{}", status.online_players, status.max_players)))
+                    .child(div().text_sm().text_color(muted).child(SharedString::new(format!("{} ms", status.latency_ms))));
+                (subtitle, status_element)
+            },
+            Some(ServerStatusEntry::Offline(error)) => {
+                let status_element = v_flex()
+                    .id(("server-status", ix.row))
+                    .items_end()
+                    .text_color(danger)
+                    .child("Offline")
+                    .tooltip({
+                        let error = SharedString::from(error);
+                        move |window, cx| Tooltip::new(error.clone()).build(window, cx)
+                    });
+                (SharedString::from(summary.ip.clone()), v_flex().child(status_element))
+            },
+            Some(ServerStatusEntry::Pinging) | None => {
+                (SharedString::from(summary.ip.clone()), v_flex().text_color(muted).child("Pinging..."))
+            },
+        };
+
         let description = v_flex()
+            .overflow_hidden()
             .child(SharedString::from(summary.name.clone()))
-            .child(div().text_color(Hsla { h: 0.0, s: 0.0, l: 0.5, a: 1.0}).child(SharedString::from(summary.ip.clone())));
+            .child(div().truncate().text_color(muted).child(subtitle));
 
         let play_icon = Icon::empty().path("icons/play.svg");
 
@@ -261,7 +502,7 @@ impl ListDelegate for ServersListDelegate {
                 .gap_1()
                 .child(
                     div()
-                        .child(Button::new(ix).success().icon(play_icon).on_click(move |_, window, cx| {
+                        .child(Button::new(ix).success().icon(play_icon).tooltip("Join server").on_click(move |_, window, cx| {
                             root::start_instance(
                                 id,
                                 name.clone(),
@@ -274,7 +515,8 @@ impl ListDelegate for ServersListDelegate {
                         .px_2(),
                 )
                 .child(icon.size_16().min_w_16().min_h_16())
-                .child(description),
+                .child(description.flex_1())
+                .child(status_element.px_2()),
         );
 
         Some(item)
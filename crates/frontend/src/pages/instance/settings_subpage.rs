@@ -1,17 +1,18 @@
 use std::{borrow::Cow, cmp::Ordering, path::Path, sync::Arc};
 
 use bridge::{
-    handle::BackendHandle, instance::InstanceID, message::MessageToBackend, meta::MetadataRequest
+    handle::BackendHandle, instance::InstanceID, message::{InstanceSetting, MessageToBackend}, meta::MetadataRequest, modal_action::ModalAction
 };
 use gpui::{prelude::*, *};
 use gpui_component::{
-    button::{Button, ButtonGroup, ButtonVariants}, checkbox::Checkbox, h_flex, input::{Input, InputEvent, InputState, NumberInput, NumberInputEvent}, notification::{Notification, NotificationType}, select::{SearchableVec, Select, SelectEvent, SelectState}, skeleton::Skeleton, spinner::Spinner, v_flex, ActiveTheme as _, Disableable, Selectable, Sizable, WindowExt
+    button::{Button, ButtonGroup, ButtonVariants}, checkbox::Checkbox, h_flex, IconName, IndexPath, input::{Input, InputEvent, InputState, NumberInput, NumberInputEvent}, notification::{Notification, NotificationType}, select::{SearchableVec, Select, SelectEvent, SelectState}, skeleton::Skeleton, spinner::Spinner, v_flex, ActiveTheme as _, Disableable, Selectable, Sizable, WindowExt
 };
+use enumset::EnumSet;
 use once_cell::sync::Lazy;
-use schema::{fabric_loader_manifest::FabricLoaderManifest, forge::{ForgeMavenManifest, NeoforgeMavenManifest}, instance::{InstanceJvmBinaryConfiguration, InstanceJvmFlagsConfiguration, InstanceLinuxWrapperConfiguration, InstanceMemoryConfiguration, InstanceSystemLibrariesConfiguration, LwjglLibraryPath}, loader::Loader, version_manifest::MinecraftVersionManifest};
+use schema::{fabric_loader_manifest::FabricLoaderManifest, forge::{ForgeMavenManifest, NeoforgeMavenManifest}, instance::{InstanceDefaultsConfiguration, InstanceJvmBinaryConfiguration, InstanceJvmFlagsConfiguration, InstanceLegacyFixesConfiguration, InstanceLinuxWrapperConfiguration, InstanceMemoryConfiguration, InstanceNetworkIsolationConfiguration, InstanceResolutionConfiguration, InstanceSystemLibrariesConfiguration, InstanceType, InstanceVersionTracking, LwjglLibraryPath, VersionTrackingChannel}, loader::Loader, version_manifest::MinecraftVersionManifest};
 use strum::IntoEnumIterator;
 
-use crate::{entity::{DataEntities, instance::InstanceEntry, metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult, FrontendMetadataState, TypelessFrontendMetadataResult}}, interface_config::InterfaceConfig, pages::instances_page::VersionList};
+use crate::{component::named_dropdown::{NamedDropdown, NamedDropdownItem}, entity::{DataEntities, instance::InstanceEntry, metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult, FrontendMetadataState, TypelessFrontendMetadataResult}}, interface_config::InterfaceConfig, pages::instances_page::VersionList};
 
 #[derive(PartialEq, Eq)]
 enum NewNameChangeState {
@@ -25,12 +26,16 @@ pub struct InstanceSettingsSubpage {
     instance: Entity<InstanceEntry>,
     instance_id: InstanceID,
     new_name_input_state: Entity<InputState>,
+    group_input_state: Entity<InputState>,
     version_state: TypelessFrontendMetadataResult,
     version_select_state: Entity<SelectState<VersionList>>,
     loader: Loader,
     loader_select_state: Entity<SelectState<Vec<&'static str>>>,
     loader_versions_state: TypelessFrontendMetadataResult,
     loader_version_select_state: Entity<SelectState<SearchableVec<&'static str>>>,
+    version_tracking_select_state: Entity<SelectState<NamedDropdown<Option<VersionTrackingChannel>>>>,
+    version_tracking_channel: Option<VersionTrackingChannel>,
+    version_tracking_automatic: bool,
     memory_override_enabled: bool,
     memory_min_input_state: Entity<InputState>,
     memory_max_input_state: Entity<InputState>,
@@ -38,6 +43,10 @@ pub struct InstanceSettingsSubpage {
     jvm_flags_input_state: Entity<InputState>,
     jvm_binary_enabled: bool,
     jvm_binary_path: Option<Arc<Path>>,
+    resolution_enabled: bool,
+    resolution_fullscreen: bool,
+    resolution_width_input_state: Entity<InputState>,
+    resolution_height_input_state: Entity<InputState>,
 
     override_glfw_enabled: bool,
     override_glfw_path: Option<Arc<Path>>,
@@ -50,14 +59,20 @@ pub struct InstanceSettingsSubpage {
     use_gamemode: bool,
     #[cfg(target_os = "linux")]
     use_discrete_gpu: bool,
+    use_legacy_skin_proxy: bool,
+    network_isolation: InstanceNetworkIsolationConfiguration,
+    scheduled_backups: bool,
     #[cfg(target_os = "linux")]
     mangohud_available: bool,
     #[cfg(target_os = "linux")]
     gamemode_available: bool,
+    instance_defaults: InstanceDefaultsConfiguration,
+    overridden: EnumSet<InstanceSetting>,
     new_name_change_state: NewNameChangeState,
     backend_handle: BackendHandle,
     _observe_loader_version_subscription: Option<Subscription>,
     _select_file_task: Task<()>,
+    _get_defaults_task: Task<()>,
 }
 
 impl InstanceSettingsSubpage {
@@ -79,6 +94,24 @@ impl InstanceSettingsSubpage {
         #[cfg(target_os = "linux")]
         let linux_wrapper = entry.configuration.linux_wrapper.unwrap_or_default();
         let system_libraries = entry.configuration.system_libraries.clone().unwrap_or_default();
+        let legacy_fixes = entry.configuration.legacy_fixes.unwrap_or_default();
+        let resolution = entry.configuration.resolution.unwrap_or_default();
+        let version_tracking = entry.configuration.version_tracking;
+
+        // Values which are turned off don't override the defaults, see `InstanceConfiguration::with_defaults`
+        let mut overridden = EnumSet::empty();
+        if memory.is_enabled() {
+            overridden.insert(InstanceSetting::Memory);
+        }
+        if jvm_flags.is_enabled() {
+            overridden.insert(InstanceSetting::JvmFlags);
+        }
+        if jvm_binary.is_enabled() {
+            overridden.insert(InstanceSetting::JvmBinary);
+        }
+        if resolution.is_enabled() {
+            overridden.insert(InstanceSetting::Resolution);
+        }
 
         let glfw_path = system_libraries.glfw.get_or_auto(&*AUTO_LIBRARY_PATH_GLFW);
         let openal_path = system_libraries.openal.get_or_auto(&*AUTO_LIBRARY_PATH_OPENAL);
@@ -86,6 +119,10 @@ impl InstanceSettingsSubpage {
         let new_name_input_state = cx.new(|cx| InputState::new(window, cx));
         cx.subscribe(&new_name_input_state, Self::on_new_name_input).detach();
 
+        let group = entry.configuration.group.map(|group| SharedString::new(group.as_str())).unwrap_or_default();
+        let group_input_state = cx.new(|cx| InputState::new(window, cx).placeholder("No group").default_value(group));
+        cx.subscribe(&group_input_state, Self::on_group_input).detach();
+
         let minecraft_versions = FrontendMetadata::request(&data.metadata, MetadataRequest::MinecraftVersionManifest, cx);
 
         let version_select_state = cx.new(|cx| SelectState::new(VersionList::default(), None, window, cx).searchable(true));
@@ -121,6 +158,18 @@ impl InstanceSettingsSubpage {
         });
         cx.subscribe(&loader_version_select_state, Self::on_loader_version_selected).detach();
 
+        let version_tracking_select_state = cx.new(|cx| {
+            let items = vec![
+                NamedDropdownItem { name: "Stay on this version".into(), item: None },
+                NamedDropdownItem { name: "Follow latest release".into(), item: Some(VersionTrackingChannel::Release) },
+                NamedDropdownItem { name: "Follow latest snapshot".into(), item: Some(VersionTrackingChannel::Snapshot) },
+            ];
+            let current = version_tracking.map(|version_tracking| version_tracking.channel);
+            let row = items.iter().position(|item| item.item == current).unwrap_or(0);
+            SelectState::new(NamedDropdown::new(items), Some(IndexPath::new(row)), window, cx)
+        });
+        cx.subscribe(&version_tracking_select_state, Self::on_version_tracking_selected).detach();
+
         let memory_min_input_state = cx.new(|cx| {
             InputState::new(window, cx).default_value(memory.min.to_string())
         });
@@ -137,16 +186,48 @@ impl InstanceSettingsSubpage {
         });
         cx.subscribe(&jvm_flags_input_state, Self::on_jvm_flags_changed).detach();
 
+        let resolution_width_input_state = cx.new(|cx| {
+            InputState::new(window, cx).default_value(resolution.width.to_string())
+        });
+        cx.subscribe_in(&resolution_width_input_state, window, Self::on_resolution_step).detach();
+        cx.subscribe(&resolution_width_input_state, Self::on_resolution_changed).detach();
+        let resolution_height_input_state = cx.new(|cx| {
+            InputState::new(window, cx).default_value(resolution.height.to_string())
+        });
+        cx.subscribe_in(&resolution_height_input_state, window, Self::on_resolution_step).detach();
+        cx.subscribe(&resolution_height_input_state, Self::on_resolution_changed).detach();
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        let _get_defaults_task = cx.spawn_in(window, async move |page, cx| {
+            let Ok(backend_config) = recv.await else {
+                return;
+            };
+            _ = page.update_in(cx, move |page, window, cx| {
+                page.instance_defaults = backend_config.instance_defaults;
+                for setting in EnumSet::<InstanceSetting>::all() {
+                    if !page.overridden.contains(setting) {
+                        page.apply_default_setting(setting, window, cx);
+                    }
+                }
+                cx.notify();
+            });
+        });
+        backend_handle.send(MessageToBackend::GetBackendConfiguration { channel: send });
+
         let mut page = Self {
             data: data.clone(),
             instance: instance.clone(),
             instance_id,
             new_name_input_state,
+            group_input_state,
             version_state: TypelessFrontendMetadataResult::Loading,
             version_select_state,
             loader,
             loader_select_state,
             loader_version_select_state,
+            version_tracking_select_state,
+            version_tracking_channel: version_tracking.map(|version_tracking| version_tracking.channel),
+            version_tracking_automatic: version_tracking.is_some_and(|version_tracking| version_tracking.automatic),
             memory_override_enabled: memory.enabled,
             memory_min_input_state,
             memory_max_input_state,
@@ -154,6 +235,10 @@ impl InstanceSettingsSubpage {
             jvm_flags_input_state,
             jvm_binary_enabled: jvm_binary.enabled,
             jvm_binary_path: jvm_binary.path.clone(),
+            resolution_enabled: resolution.enabled,
+            resolution_fullscreen: resolution.fullscreen,
+            resolution_width_input_state,
+            resolution_height_input_state,
             override_glfw_enabled: system_libraries.override_glfw,
             override_glfw_path: glfw_path,
             override_openal_enabled: system_libraries.override_openal,
@@ -164,15 +249,21 @@ impl InstanceSettingsSubpage {
             use_gamemode: linux_wrapper.use_gamemode,
             #[cfg(target_os = "linux")]
             use_discrete_gpu: linux_wrapper.use_discrete_gpu,
+            use_legacy_skin_proxy: legacy_fixes.use_skin_proxy,
+            network_isolation: entry.configuration.network_isolation.unwrap_or_default(),
+            scheduled_backups: entry.configuration.scheduled_backups,
             #[cfg(target_os = "linux")]
             mangohud_available: Self::is_command_available("mangohud"),
             #[cfg(target_os = "linux")]
             gamemode_available: Self::is_command_available("gamemoderun"),
+            instance_defaults: InstanceDefaultsConfiguration::default(),
+            overridden,
             new_name_change_state: NewNameChangeState::NoChange,
             backend_handle,
             loader_versions_state: TypelessFrontendMetadataResult::Loading,
             _observe_loader_version_subscription: None,
-            _select_file_task: Task::ready(())
+            _select_file_task: Task::ready(()),
+            _get_defaults_task,
         };
         page.update_minecraft_versions(minecraft_versions, window, cx);
         page.update_loader_versions(window, cx);
@@ -331,6 +422,25 @@ impl InstanceSettingsSubpage {
         }
     }
 
+    pub fn on_group_input(
+        &mut self,
+        state: Entity<InputState>,
+        event: &InputEvent,
+        cx: &mut Context<Self>,
+    ) {
+        if let InputEvent::PressEnter { .. } | InputEvent::Blur = event {
+            let group = state.read(cx).value();
+            let group = group.trim();
+            let group = if group.is_empty() { None } else { Some(group.into()) };
+            if self.instance.read(cx).configuration.group != group {
+                self.backend_handle.send(MessageToBackend::SetInstanceGroup {
+                    id: self.instance_id,
+                    group,
+                });
+            }
+        }
+    }
+
     pub fn on_minecraft_version_selected(
         &mut self,
         _state: Entity<SelectState<VersionList>>,
@@ -397,6 +507,32 @@ impl InstanceSettingsSubpage {
         });
     }
 
+    pub fn on_version_tracking_selected(
+        &mut self,
+        _state: Entity<SelectState<NamedDropdown<Option<VersionTrackingChannel>>>>,
+        event: &SelectEvent<NamedDropdown<Option<VersionTrackingChannel>>>,
+        cx: &mut Context<Self>,
+    ) {
+        let SelectEvent::Confirm(Some(value)) = event else {
+            return;
+        };
+
+        self.version_tracking_channel = value.item;
+        self.send_version_tracking();
+        cx.notify();
+    }
+
+    fn send_version_tracking(&self) {
+        let version_tracking = self.version_tracking_channel.map(|channel| InstanceVersionTracking {
+            channel,
+            automatic: self.version_tracking_automatic,
+        });
+        self.backend_handle.send(MessageToBackend::SetInstanceVersionTracking {
+            id: self.instance_id,
+            version_tracking,
+        });
+    }
+
     pub fn on_memory_step(
         &mut self,
         state: &Entity<InputState>,
@@ -426,6 +562,121 @@ impl InstanceSettingsSubpage {
         }
     }
 
+    fn should_send_setting(&mut self, setting: InstanceSetting, matches_default: bool) -> bool {
+        if !self.overridden.contains(setting) && matches_default {
+            return false;
+        }
+        self.overridden.insert(setting);
+        true
+    }
+
+    fn send_memory_configuration(&mut self, cx: &App) {
+        let memory = self.get_memory_configuration(cx);
+        if self.should_send_setting(InstanceSetting::Memory, memory == self.instance_defaults.memory.unwrap_or_default()) {
+            self.backend_handle.send(MessageToBackend::SetInstanceMemory {
+                id: self.instance_id,
+                memory
+            });
+        }
+    }
+
+    fn send_jvm_flags_configuration(&mut self, cx: &App) {
+        let jvm_flags = self.get_jvm_flags_configuration(cx);
+        if self.should_send_setting(InstanceSetting::JvmFlags, jvm_flags == self.instance_defaults.jvm_flags.clone().unwrap_or_default()) {
+            self.backend_handle.send(MessageToBackend::SetInstanceJvmFlags {
+                id: self.instance_id,
+                jvm_flags
+            });
+        }
+    }
+
+    fn send_jvm_binary_configuration(&mut self) {
+        let jvm_binary = self.get_jvm_binary_configuration();
+        if self.should_send_setting(InstanceSetting::JvmBinary, jvm_binary == self.instance_defaults.jvm_binary.clone().unwrap_or_default()) {
+            self.backend_handle.send(MessageToBackend::SetInstanceJvmBinary {
+                id: self.instance_id,
+                jvm_binary
+            });
+        }
+    }
+
+    fn send_resolution_configuration(&mut self, cx: &App) {
+        let resolution = self.get_resolution_configuration(cx);
+        if self.should_send_setting(InstanceSetting::Resolution, resolution == self.instance_defaults.resolution.unwrap_or_default()) {
+            self.backend_handle.send(MessageToBackend::SetInstanceResolution {
+                id: self.instance_id,
+                resolution
+            });
+        }
+    }
+
+    // Turning off both the size and fullscreen leaves nothing to override, so the defaults are used again
+    fn send_or_reset_resolution_configuration(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.get_resolution_configuration(cx).is_enabled() {
+            self.send_resolution_configuration(cx);
+        } else {
+            self.reset_setting(InstanceSetting::Resolution, window, cx);
+        }
+    }
+
+    fn reset_setting(&mut self, setting: InstanceSetting, window: &mut Window, cx: &mut Context<Self>) {
+        self.overridden.remove(setting);
+        self.backend_handle.send(MessageToBackend::ResetInstanceSetting {
+            id: self.instance_id,
+            setting,
+        });
+        self.apply_default_setting(setting, window, cx);
+        cx.notify();
+    }
+
+    fn apply_default_setting(&mut self, setting: InstanceSetting, window: &mut Window, cx: &mut Context<Self>) {
+        match setting {
+            InstanceSetting::Memory => {
+                let memory = self.instance_defaults.memory.unwrap_or_default();
+                self.memory_override_enabled = memory.enabled;
+                self.memory_min_input_state.update(cx, |input, cx| {
+                    input.set_value(memory.min.to_string(), window, cx);
+                });
+                self.memory_max_input_state.update(cx, |input, cx| {
+                    input.set_value(memory.max.to_string(), window, cx);
+                });
+            },
+            InstanceSetting::JvmFlags => {
+                let jvm_flags = self.instance_defaults.jvm_flags.clone().unwrap_or_default();
+                self.jvm_flags_enabled = jvm_flags.enabled;
+                self.jvm_flags_input_state.update(cx, |input, cx| {
+                    input.set_value(SharedString::new(jvm_flags.flags), window, cx);
+                });
+            },
+            InstanceSetting::JvmBinary => {
+                let jvm_binary = self.instance_defaults.jvm_binary.clone().unwrap_or_default();
+                self.jvm_binary_enabled = jvm_binary.enabled;
+                self.jvm_binary_path = jvm_binary.path;
+            },
+            InstanceSetting::Resolution => {
+                let resolution = self.instance_defaults.resolution.unwrap_or_default();
+                self.resolution_enabled = resolution.enabled;
+                self.resolution_fullscreen = resolution.fullscreen;
+                self.resolution_width_input_state.update(cx, |input, cx| {
+                    input.set_value(resolution.width.to_string(), window, cx);
+                });
+                self.resolution_height_input_state.update(cx, |input, cx| {
+                    input.set_value(resolution.height.to_string(), window, cx);
+                });
+            },
+        }
+    }
+
+    fn render_override_indicator(&self, id: &'static str, setting: InstanceSetting, cx: &Context<Self>) -> impl IntoElement {
+        let muted_foreground = cx.theme().muted_foreground;
+        h_flex().gap_2().when(self.overridden.contains(setting), |this| {
+            this.child(div().text_xs().text_color(muted_foreground).child("Overridden"))
+                .child(Button::new(id).label("Reset to default").xsmall().ghost().on_click(cx.listener(move |page, _, window, cx| {
+                    page.reset_setting(setting, window, cx);
+                })))
+        })
+    }
+
     pub fn on_memory_changed(
         &mut self,
         _: Entity<InputState>,
@@ -433,10 +684,7 @@ impl InstanceSettingsSubpage {
         cx: &mut Context<Self>,
     ) {
         if let InputEvent::Change = event {
-            self.backend_handle.send(MessageToBackend::SetInstanceMemory {
-                id: self.instance_id,
-                memory: self.get_memory_configuration(cx)
-            });
+            self.send_memory_configuration(cx);
         }
     }
 
@@ -458,10 +706,7 @@ impl InstanceSettingsSubpage {
         cx: &mut Context<Self>,
     ) {
         if let InputEvent::Change = event {
-            self.backend_handle.send(MessageToBackend::SetInstanceJvmFlags {
-                id: self.instance_id,
-                jvm_flags: self.get_jvm_flags_configuration(cx)
-            });
+            self.send_jvm_flags_configuration(cx);
         }
     }
 
@@ -474,6 +719,51 @@ impl InstanceSettingsSubpage {
         }
     }
 
+    pub fn on_resolution_step(
+        &mut self,
+        state: &Entity<InputState>,
+        event: &NumberInputEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            NumberInputEvent::Step(step_action) => {
+                if let Ok(value) = state.read(cx).value().parse::<u32>() {
+                    let value = match step_action {
+                        gpui_component::input::StepAction::Decrement => value.saturating_sub(1).max(1),
+                        gpui_component::input::StepAction::Increment => value.saturating_add(1),
+                    };
+                    state.update(cx, |input, cx| {
+                        input.set_value(value.to_string(), window, cx);
+                    })
+                }
+            },
+        }
+    }
+
+    pub fn on_resolution_changed(
+        &mut self,
+        _: Entity<InputState>,
+        event: &InputEvent,
+        cx: &mut Context<Self>,
+    ) {
+        if let InputEvent::Change = event {
+            self.send_resolution_configuration(cx);
+        }
+    }
+
+    fn get_resolution_configuration(&self, cx: &App) -> InstanceResolutionConfiguration {
+        let width = self.resolution_width_input_state.read(cx).value().parse::<u32>().unwrap_or(0);
+        let height = self.resolution_height_input_state.read(cx).value().parse::<u32>().unwrap_or(0);
+
+        InstanceResolutionConfiguration {
+            enabled: self.resolution_enabled,
+            width,
+            height,
+            fullscreen: self.resolution_fullscreen,
+        }
+    }
+
     fn get_jvm_binary_configuration(&self) -> InstanceJvmBinaryConfiguration {
         InstanceJvmBinaryConfiguration {
             enabled: self.jvm_binary_enabled,
@@ -512,6 +802,12 @@ impl InstanceSettingsSubpage {
         }
     }
 
+    fn get_legacy_fixes_configuration(&self) -> InstanceLegacyFixesConfiguration {
+        InstanceLegacyFixesConfiguration {
+            use_skin_proxy: self.use_legacy_skin_proxy,
+        }
+    }
+
     #[cfg(target_os = "linux")]
     fn is_command_available(command: &str) -> bool {
         std::process::Command::new("sh")
@@ -522,6 +818,30 @@ impl InstanceSettingsSubpage {
             .unwrap_or(false)
     }
 
+    fn select_instance_location(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let receiver = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some(SharedString::new_static("Select Folder"))
+        });
+
+        let backend_handle = self.backend_handle.clone();
+        let id = self.instance_id;
+        self._select_file_task = window.spawn(cx, async move |cx| {
+            let Ok(Ok(Some(paths))) = receiver.await else {
+                return;
+            };
+            let Some(target) = paths.first() else {
+                return;
+            };
+            let target: Arc<Path> = target.as_path().into();
+            _ = cx.update(move |window, cx| {
+                move_instance(id, Some(target), &backend_handle, window, cx);
+            });
+        });
+    }
+
     pub fn select_file(&mut self, message: &'static str, handle: impl FnOnce(&mut Self, Option<Arc<Path>>) + 'static, window: &mut Window, cx: &mut Context<Self>) {
         let receiver = cx.prompt_for_paths(PathPromptOptions {
             files: true,
@@ -556,6 +876,16 @@ impl InstanceSettingsSubpage {
     }
 }
 
+fn move_instance(id: InstanceID, target: Option<Arc<Path>>, backend_handle: &BackendHandle, window: &mut Window, cx: &mut App) {
+    let modal_action = ModalAction::default();
+    backend_handle.send(MessageToBackend::MoveInstance {
+        id,
+        target,
+        modal_action: modal_action.clone(),
+    });
+    crate::modals::generic::show_modal(window, cx, "Moving instance".into(), "Error moving instance".into(), modal_action);
+}
+
 impl Render for InstanceSettingsSubpage {
     fn render(&mut self, _window: &mut gpui::Window, cx: &mut gpui::Context<Self>) -> impl gpui::IntoElement {
         let theme = cx.theme();
@@ -569,11 +899,15 @@ impl Render for InstanceSettingsSubpage {
         let memory_override_enabled = self.memory_override_enabled;
         let jvm_flags_enabled = self.jvm_flags_enabled;
         let jvm_binary_enabled = self.jvm_binary_enabled;
+        let resolution_enabled = self.resolution_enabled;
 
         let jvm_binary_label = opt_path_to_string(&self.jvm_binary_path);
         let glfw_path_label = opt_path_to_string(&self.override_glfw_path);
         let openal_path_label = opt_path_to_string(&self.override_openal_path);
 
+        let instance_folder: Arc<Path> = self.instance.read(cx).dot_minecraft_folder.parent().map(Arc::from).unwrap_or_else(|| self.data.data_folder.clone());
+        let is_external = instance_folder.parent() != Some(&*self.data.data_folder.join("instances"));
+
         let mut basic_content = v_flex()
             .gap_4()
             .size_full()
@@ -602,7 +936,33 @@ impl Render for InstanceSettingsSubpage {
                         }
                     })
                 )
-            );
+            )
+            .child(crate::labelled("Group", Input::new(&self.group_input_state)))
+            .child(crate::labelled(
+                "Location",
+                v_flex().gap_2()
+                    .child(div().text_sm().text_color(theme.muted_foreground)
+                        .child(SharedString::new(instance_folder.display().to_string())))
+                    .child(h_flex().gap_2()
+                        .child(Button::new("open_instance_folder").info().small().icon(IconName::FolderOpen).label("Open").on_click({
+                            let instance_folder = instance_folder.clone();
+                            move |_, window, cx| {
+                                crate::open_folder(&instance_folder, window, cx);
+                            }
+                        }))
+                        .child(Button::new("move_instance").small().label("Move to another folder").on_click(cx.listener(|this, _, window, cx| {
+                            this.select_instance_location(window, cx);
+                        })))
+                        .when(is_external, |this| {
+                            this.child(Button::new("move_instance_back").small().label("Move back to instances folder").on_click({
+                                let backend_handle = self.backend_handle.clone();
+                                let id = self.instance_id;
+                                move |_, window, cx| {
+                                    move_instance(id, None, &backend_handle, window, cx);
+                                }
+                            }))
+                        }))
+            ));
 
         let mut version_content = v_flex().gap_2();
 
@@ -639,6 +999,20 @@ impl Render for InstanceSettingsSubpage {
             }
         }
 
+        version_content = version_content
+            .child(Select::new(&self.version_tracking_select_state).title_prefix("Updates: ").w_full())
+            .when(self.version_tracking_channel.is_some(), |this| {
+                this.child(Checkbox::new("version-tracking-automatic").label("Upgrade without asking").checked(self.version_tracking_automatic).on_click(cx.listener(|page, value, _, cx| {
+                    if page.version_tracking_automatic != *value {
+                        page.version_tracking_automatic = *value;
+                        page.send_version_tracking();
+                        cx.notify();
+                    }
+                })))
+                .child(div().text_sm().text_color(theme.muted_foreground)
+                    .child("A snapshot of the instance is saved before upgrading, so the upgrade can be rolled back"))
+            });
+
         basic_content = basic_content.child(crate::labelled(
             "Version",
             version_content,
@@ -649,16 +1023,20 @@ impl Render for InstanceSettingsSubpage {
             .size_full()
             .child(v_flex()
                 .gap_1()
-                .child(Checkbox::new("memory").label("Set Memory").checked(memory_override_enabled).on_click(cx.listener(|page, value, _, cx| {
-                    if page.memory_override_enabled != *value {
-                        page.memory_override_enabled = *value;
-                        page.backend_handle.send(MessageToBackend::SetInstanceMemory {
-                            id: page.instance_id,
-                            memory: page.get_memory_configuration(cx)
-                        });
-                        cx.notify();
-                    }
-                })))
+                .child(h_flex()
+                    .justify_between()
+                    .child(Checkbox::new("memory").label("Set Memory").checked(memory_override_enabled).on_click(cx.listener(|page, value, window, cx| {
+                        if page.memory_override_enabled != *value {
+                            page.memory_override_enabled = *value;
+                            if *value {
+                                page.send_memory_configuration(cx);
+                            } else {
+                                page.reset_setting(InstanceSetting::Memory, window, cx);
+                            }
+                            cx.notify();
+                        }
+                    })))
+                    .child(self.render_override_indicator("reset_memory", InstanceSetting::Memory, cx)))
                 .child(h_flex()
                     .gap_1()
                     .child(v_flex()
@@ -674,37 +1052,74 @@ impl Render for InstanceSettingsSubpage {
                 )
             ).child(v_flex()
                 .gap_1()
-                .child(Checkbox::new("jvm_flags").label("Add JVM Flags").checked(jvm_flags_enabled).on_click(cx.listener(|page, value, _, cx| {
-                    if page.jvm_flags_enabled != *value {
-                        page.jvm_flags_enabled = *value;
-                        page.backend_handle.send(MessageToBackend::SetInstanceJvmFlags {
-                            id: page.instance_id,
-                            jvm_flags: page.get_jvm_flags_configuration(cx)
-                        });
+                .child(h_flex()
+                    .justify_between()
+                    .child(Checkbox::new("resolution").label("Set Window Size").checked(resolution_enabled).on_click(cx.listener(|page, value, window, cx| {
+                        if page.resolution_enabled != *value {
+                            page.resolution_enabled = *value;
+                            page.send_or_reset_resolution_configuration(window, cx);
+                            cx.notify();
+                        }
+                    })))
+                    .child(self.render_override_indicator("reset_resolution", InstanceSetting::Resolution, cx)))
+                .child(h_flex()
+                    .gap_1()
+                    .child(v_flex()
+                        .w_full()
+                        .gap_1()
+                        .child(NumberInput::new(&self.resolution_width_input_state).small().suffix("px").disabled(!resolution_enabled))
+                        .child(NumberInput::new(&self.resolution_height_input_state).small().suffix("px").disabled(!resolution_enabled))
+                    )
+                    .child(v_flex()
+                        .gap_1()
+                        .child("Width")
+                        .child("Height"))
+                )
+                .child(Checkbox::new("fullscreen").label("Launch in Fullscreen").checked(self.resolution_fullscreen).on_click(cx.listener(|page, value, window, cx| {
+                    if page.resolution_fullscreen != *value {
+                        page.resolution_fullscreen = *value;
+                        page.send_or_reset_resolution_configuration(window, cx);
                         cx.notify();
                     }
                 })))
+            ).child(v_flex()
+                .gap_1()
+                .child(h_flex()
+                    .justify_between()
+                    .child(Checkbox::new("jvm_flags").label("Add JVM Flags").checked(jvm_flags_enabled).on_click(cx.listener(|page, value, window, cx| {
+                        if page.jvm_flags_enabled != *value {
+                            page.jvm_flags_enabled = *value;
+                            if *value {
+                                page.send_jvm_flags_configuration(cx);
+                            } else {
+                                page.reset_setting(InstanceSetting::JvmFlags, window, cx);
+                            }
+                            cx.notify();
+                        }
+                    })))
+                    .child(self.render_override_indicator("reset_jvm_flags", InstanceSetting::JvmFlags, cx)))
                 .child(Input::new(&self.jvm_flags_input_state).disabled(!jvm_flags_enabled))
             )
             .child(v_flex()
                 .gap_1()
-                .child(Checkbox::new("jvm_binary").label("Override JVM Binary").checked(jvm_binary_enabled).on_click(cx.listener(|page, value, _, cx| {
-                    if page.jvm_binary_enabled != *value {
-                        page.jvm_binary_enabled = *value;
-                        page.backend_handle.send(MessageToBackend::SetInstanceJvmBinary {
-                            id: page.instance_id,
-                            jvm_binary: page.get_jvm_binary_configuration()
-                        });
-                        cx.notify();
-                    }
-                })))
+                .child(h_flex()
+                    .justify_between()
+                    .child(Checkbox::new("jvm_binary").label("Override JVM Binary").checked(jvm_binary_enabled).on_click(cx.listener(|page, value, window, cx| {
+                        if page.jvm_binary_enabled != *value {
+                            page.jvm_binary_enabled = *value;
+                            if *value {
+                                page.send_jvm_binary_configuration();
+                            } else {
+                                page.reset_setting(InstanceSetting::JvmBinary, window, cx);
+                            }
+                            cx.notify();
+                        }
+                    })))
+                    .child(self.render_override_indicator("reset_jvm_binary", InstanceSetting::JvmBinary, cx)))
                 .child(Button::new("select_jvm_binary").success().label(jvm_binary_label).disabled(!jvm_binary_enabled).on_click(cx.listener(|this, _, window, cx| {
                     this.select_file("Select Jvm Binary", |this, path| {
                         this.jvm_binary_path = path;
-                        this.backend_handle.send(MessageToBackend::SetInstanceJvmBinary {
-                            id: this.instance_id,
-                            jvm_binary: this.get_jvm_binary_configuration()
-                        });
+                        this.send_jvm_binary_configuration();
                     }, window, cx);
                 })))
             )
@@ -791,10 +1206,199 @@ impl Render for InstanceSettingsSubpage {
             })))
         );
 
+        let runtime_content = runtime_content.child(v_flex()
+            .gap_1()
+            .child("Versions Before 1.6")
+            .child(Checkbox::new("use_legacy_skin_proxy").label("Use Skin Proxy").checked(self.use_legacy_skin_proxy).on_click(cx.listener(|page, value, _, cx| {
+                if page.use_legacy_skin_proxy != *value {
+                    page.use_legacy_skin_proxy = *value;
+                    page.backend_handle.send(MessageToBackend::SetInstanceLegacyFixes {
+                        id: page.instance_id,
+                        legacy_fixes: page.get_legacy_fixes_configuration()
+                    });
+                    cx.notify();
+                }
+            })))
+        );
+
+        let is_server = self.instance.read(cx).configuration.instance_type == InstanceType::Server;
+
+        let runtime_content = runtime_content.when(!is_server, |this| this.child(v_flex()
+            .gap_1()
+            .child("Network Isolation")
+            .child(Checkbox::new("disable_multiplayer").label("Disable multiplayer and Realms").checked(self.network_isolation.disable_multiplayer).on_click(cx.listener(|page, value, _, cx| {
+                if page.network_isolation.disable_multiplayer != *value {
+                    page.network_isolation.disable_multiplayer = *value;
+                    page.backend_handle.send(MessageToBackend::SetInstanceNetworkIsolation {
+                        id: page.instance_id,
+                        network_isolation: page.network_isolation,
+                    });
+                    cx.notify();
+                }
+            })))
+            .child(Checkbox::new("block_connections").label("Block internet connections").checked(self.network_isolation.block_connections).on_click(cx.listener(|page, value, _, cx| {
+                if page.network_isolation.block_connections != *value {
+                    page.network_isolation.block_connections = *value;
+                    page.backend_handle.send(MessageToBackend::SetInstanceNetworkIsolation {
+                        id: page.instance_id,
+                        network_isolation: page.network_isolation,
+                    });
+                    cx.notify();
+                }
+            })))
+            .child(div().text_sm().text_color(cx.theme().muted_foreground)
+                .child("Skins, Realms and mods that download things stop working. Some mods use their own networking and may still connect"))
+        ));
+
+        let runtime_content = runtime_content.child(v_flex()
+            .gap_1()
+            .child("Backups")
+            .child(Checkbox::new("scheduled_backups").label("Back up saves and configs every night").checked(self.scheduled_backups).on_click(cx.listener(|page, value, _, cx| {
+                if page.scheduled_backups != *value {
+                    page.scheduled_backups = *value;
+                    page.backend_handle.send(MessageToBackend::SetInstanceScheduledBackups {
+                        id: page.instance_id,
+                        value: *value,
+                    });
+                    cx.notify();
+                }
+            })))
+            .child(h_flex()
+                .gap_2()
+                .child(Button::new("backup_now").label("Back up now").info().on_click(cx.listener(|page, _, window, cx| {
+                    let modal_action = ModalAction::default();
+                    page.backend_handle.send(MessageToBackend::BackupInstance {
+                        id: page.instance_id,
+                        modal_action: modal_action.clone(),
+                    });
+                    crate::modals::generic::show_modal(window, cx, "Backing up instance".into(), "Error backing up instance".into(), modal_action);
+                })))
+                .child(Button::new("restore_backup").label("Restore…").info().on_click({
+                    let instance = self.instance.clone();
+                    let backend_handle = self.backend_handle.clone();
+                    move |_: &ClickEvent, window, cx| {
+                        let instance = instance.read(cx);
+                        crate::modals::backups::open_backups(instance.id, instance.name.clone(), backend_handle.clone(), window, cx);
+                    }
+                })))
+        );
+
         let actions_content = v_flex()
             .gap_4()
             .size_full()
-            .child(Button::new("shortcut").label("Create shortcut").success().on_click({
+            .when(!is_server, |this| this.child(Button::new("game_options").label("Edit game options").info().on_click({
+                let instances = self.data.instances.clone();
+                let backend_handle = self.backend_handle.clone();
+                let id = self.instance_id;
+                move |_: &ClickEvent, window, cx| {
+                    crate::modals::game_options::open_game_options(id, instances.clone(), backend_handle.clone(), window, cx);
+                }
+            })))
+            .when(is_server, |this| this.child(Button::new("server_properties").label("Edit server properties").info().on_click({
+                let backend_handle = self.backend_handle.clone();
+                let id = self.instance_id;
+                move |_: &ClickEvent, window, cx| {
+                    crate::modals::server_properties::open_server_properties(id, backend_handle.clone(), window, cx);
+                }
+            })))
+            .child(Button::new("verify_files").label("Verify files").info().on_click({
+                let instance = self.instance.clone();
+                let backend_handle = self.backend_handle.clone();
+                move |_: &ClickEvent, window, cx| {
+                    let instance = instance.read(cx);
+                    let id = instance.id;
+                    let name = instance.name.clone();
+                    crate::modals::verify_files::start_verify_files(id, name, &backend_handle, window, cx);
+                }
+            }))
+            .when(!is_server, |this| this.child(Button::new("launch_command").label("Show launch command").info().on_click({
+                let instance = self.instance.clone();
+                let backend_handle = self.backend_handle.clone();
+                move |_: &ClickEvent, window, cx| {
+                    let instance = instance.read(cx);
+                    let id = instance.id;
+                    let name = instance.name.clone();
+                    crate::modals::launch_command::start_show_launch_command(id, name, false, &backend_handle, window, cx);
+                }
+            })))
+            .when(!is_server, |this| this.child(Button::new("launch_script").label("Write launch script").info().on_click({
+                let instance = self.instance.clone();
+                let backend_handle = self.backend_handle.clone();
+                move |_: &ClickEvent, window, cx| {
+                    let instance = instance.read(cx);
+                    let id = instance.id;
+                    let name = instance.name.clone();
+                    crate::modals::launch_command::start_show_launch_command(id, name, true, &backend_handle, window, cx);
+                }
+            })))
+            .child(Button::new("report_problem").label("Report a problem").info().on_click({
+                let instance = self.instance.clone();
+                let backend_handle = self.backend_handle.clone();
+                move |_: &ClickEvent, window, cx| {
+                    let instance = instance.read(cx);
+                    let id = instance.id;
+                    let name = instance.name.clone();
+                    crate::modals::report_problem::open_report_problem(Some((id, name)), backend_handle.clone(), window, cx);
+                }
+            }))
+            .child(Button::new("save_template").label("Save as template").info().on_click({
+                let instance = self.instance.clone();
+                let backend_handle = self.backend_handle.clone();
+                move |_: &ClickEvent, window, cx| {
+                    let instance = instance.read(cx);
+                    let id = instance.id;
+                    let name = instance.name.clone();
+                    crate::modals::instance_template::open_save_template(name, id, backend_handle.clone(), window, cx);
+                }
+            }))
+            .child(Button::new("duplicate").label("Duplicate instance").info().on_click({
+                let instance = self.instance.clone();
+                let instances = self.data.instances.clone();
+                let backend_handle = self.backend_handle.clone();
+                move |_: &ClickEvent, window, cx| {
+                    let instance = instance.read(cx);
+                    let id = instance.id;
+                    let name = instance.name.clone();
+                    crate::modals::duplicate_instance::open_duplicate_instance(id, name, instances.clone(), backend_handle.clone(), window, cx);
+                }
+            }))
+            .child(Button::new("copy_id").label("Copy instance ID").info()
+                .tooltip("The ID stays the same when the instance is renamed, use it with --run-instance in scripts")
+                .on_click({
+                    let instance = self.instance.clone();
+                    move |_: &ClickEvent, window, cx| {
+                        let instance = instance.read(cx);
+                        let reference = instance.configuration.reference(&instance.name);
+                        cx.write_to_clipboard(ClipboardItem::new_string(reference));
+                        window.push_notification((NotificationType::Success, "Copied instance ID to clipboard"), cx);
+                    }
+                }))
+            .child(Button::new("copy_share_code").label("Copy share code").info()
+                .tooltip("Others can import the share code to get the same version, loader and Modrinth content")
+                .on_click({
+                    let instance = self.instance.clone();
+                    let backend_handle = self.backend_handle.clone();
+                    move |_: &ClickEvent, window, cx| {
+                        let id = instance.read(cx).id;
+                        crate::modals::share_code::copy_share_code(id, &backend_handle, window, cx);
+                    }
+                }))
+            .child(Button::new("desktop_shortcut").label("Create desktop shortcut").success().on_click({
+                let instance = self.instance.clone();
+                let backend_handle = self.backend_handle.clone();
+                move |_: &ClickEvent, window, cx| {
+                    let instance = instance.read(cx);
+                    let id = instance.id;
+                    let user_dirs = directories::UserDirs::new();
+                    let Some(desktop) = user_dirs.as_ref().and_then(directories::UserDirs::desktop_dir) else {
+                        window.push_notification((NotificationType::Error, "Unable to find the desktop folder"), cx);
+                        return;
+                    };
+                    let path = desktop.join(shortcut_filename(&instance.name));
+                    backend_handle.send(MessageToBackend::CreateInstanceShortcut { id, path });
+                }
+            }))
+            .child(Button::new("shortcut").label("Create shortcut in folder").success().on_click({
                 let instance = self.instance.clone();
                 let backend_handle = self.backend_handle.clone();
                 move |_: &ClickEvent, _, cx| {
@@ -803,14 +1407,7 @@ impl Render for InstanceSettingsSubpage {
                         .and_then(directories::UserDirs::desktop_dir).unwrap_or(Path::new("."));
                     let instance = instance.read(cx);
                     let id = instance.id;
-                    let name = instance.name.clone();
-
-                    #[cfg(target_os = "linux")]
-                    let suggested_name = format!("{name}.desktop");
-                    #[cfg(target_os = "windows")]
-                    let suggested_name = format!("{name}.lnk");
-                    #[cfg(target_os = "macos")]
-                    let suggested_name = format!("{name}.app");
+                    let suggested_name = shortcut_filename(&instance.name);
 
                     let receiver = cx.prompt_for_new_path(directory, Some(&suggested_name));
                     let backend_handle = backend_handle.clone();
@@ -867,6 +1464,15 @@ impl Render for InstanceSettingsSubpage {
     }
 }
 
+fn shortcut_filename(name: &str) -> String {
+    #[cfg(target_os = "linux")]
+    return format!("{name}.desktop");
+    #[cfg(target_os = "windows")]
+    return format!("{name}.lnk");
+    #[cfg(target_os = "macos")]
+    return format!("{name}.app");
+}
+
 fn opt_path_to_string(path: &Option<Arc<Path>>) -> SharedString {
     if let Some(path) = path {
         SharedString::new(path.to_string_lossy())
@@ -0,0 +1,237 @@
+use std::sync::Arc;
+
+use bridge::{handle::BackendHandle, instance::InstanceID, message::MessageToBackend};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, h_flex, scroll::ScrollableElement, spinner::Spinner, v_flex, ActiveTheme as _, Disableable, Sizable
+};
+use schema::performance::PerformanceSession;
+
+use crate::entity::instance::InstanceEntry;
+
+pub struct InstanceStatisticsSubpage {
+    instance: InstanceID,
+    backend_handle: BackendHandle,
+    sessions: Option<Arc<[PerformanceSession]>>,
+    // Index into sessions of the session shown in the charts
+    selected: usize,
+    _get_sessions_task: Task<()>,
+}
+
+impl InstanceStatisticsSubpage {
+    pub fn new(
+        instance: &Entity<InstanceEntry>,
+        backend_handle: BackendHandle,
+        _window: &mut gpui::Window,
+        cx: &mut gpui::Context<Self>,
+    ) -> Self {
+        let mut this = Self {
+            instance: instance.read(cx).id,
+            backend_handle,
+            sessions: None,
+            selected: 0,
+            _get_sessions_task: Task::ready(()),
+        };
+
+        this.update_sessions(cx);
+
+        this
+    }
+
+    fn update_sessions(&mut self, cx: &mut Context<Self>) {
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self._get_sessions_task = cx.spawn(async move |page, cx| {
+            let result: Arc<[PerformanceSession]> = recv.await.unwrap_or_else(|_| Arc::from([]));
+            let _ = page.update(cx, move |page, cx| {
+                page.selected = result.len().saturating_sub(1);
+                page.sessions = Some(result);
+                cx.notify();
+            });
+        });
+
+        self.backend_handle.send(MessageToBackend::GetInstancePerformance {
+            id: self.instance,
+            channel: send,
+        });
+    }
+}
+
+struct SessionSummary {
+    average_fps: Option<u32>,
+    min_fps: Option<u32>,
+    average_tps: Option<f32>,
+    peak_memory_mib: Option<u32>,
+}
+
+impl SessionSummary {
+    fn new(session: &PerformanceSession) -> Self {
+        let fps: Vec<u32> = session.samples.iter().filter_map(|sample| sample.fps).collect();
+        let tps: Vec<f32> = session.samples.iter().filter_map(|sample| sample.tps).collect();
+
+        Self {
+            average_fps: (!fps.is_empty()).then(|| fps.iter().sum::<u32>() / fps.len() as u32),
+            min_fps: fps.iter().copied().min(),
+            average_tps: (!tps.is_empty()).then(|| tps.iter().sum::<f32>() / tps.len() as f32),
+            peak_memory_mib: session.samples.iter().filter_map(|sample| sample.memory_used_mib).max(),
+        }
+    }
+}
+
+fn version_label(session: &PerformanceSession) -> String {
+    let mut label = format!("{} {}", session.minecraft_version, session.loader.name());
+    if let Some(loader_version) = session.loader_version {
+        label.push(' ');
+        label.push_str(&loader_version);
+    }
+    label
+}
+
+fn format_duration(seconds: u64) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+// A minimal bar chart, one bar per sample scaled against the largest value
+fn render_chart(title: &'static str, values: Vec<Option<f32>>, color: Hsla, cx: &App) -> impl IntoElement {
+    let max = values.iter().flatten().copied().fold(0.0, f32::max);
+    let chart = h_flex()
+        .h(px(96.0))
+        .w_full()
+        .items_end()
+        .gap_px()
+        .border_b_1()
+        .border_color(cx.theme().border)
+        .children(values.into_iter().map(|value| {
+            let fraction = if max > 0.0 { value.unwrap_or(0.0) / max } else { 0.0 };
+            div().flex_1().h(relative(fraction)).bg(color)
+        }));
+
+    v_flex()
+        .gap_1()
+        .child(h_flex().justify_between().text_sm().child(title).child(format!("max {}", max.round())))
+        .child(chart)
+}
+
+impl Render for InstanceStatisticsSubpage {
+    fn render(&mut self, _window: &mut gpui::Window, cx: &mut gpui::Context<Self>) -> impl gpui::IntoElement {
+        let theme = cx.theme();
+
+        let has_sessions = self.sessions.as_ref().is_some_and(|sessions| !sessions.is_empty());
+        let header = h_flex()
+            .gap_3()
+            .mb_1()
+            .ml_1()
+            .child(div().text_lg().child("Statistics"))
+            .child(Button::new("clear").danger().small().label("Clear").disabled(!has_sessions).on_click(cx.listener(|page, _, _, cx| {
+                page.backend_handle.send(MessageToBackend::ClearInstancePerformance { id: page.instance });
+                page.update_sessions(cx);
+            })));
+
+        let mut content = v_flex().p_3().gap_3().size_full();
+
+        match &self.sessions {
+            None => {
+                content = content.child(Spinner::new().large());
+            },
+            Some(sessions) if sessions.is_empty() => {
+                content = content
+                    .child("No sessions have been recorded yet")
+                    .child(div().text_sm().text_color(theme.muted_foreground)
+                        .child("Performance is read from the game log when the game closes, while launch statistics are being recorded. FPS and memory usage are only logged by some mods or when the debug screen is dumped to the log"));
+            },
+            Some(sessions) => {
+                if let Some(session) = sessions.get(self.selected) {
+                    let fps = session.samples.iter().map(|sample| sample.fps.map(|fps| fps as f32)).collect();
+                    let memory = session.samples.iter().map(|sample| sample.memory_used_mib.map(|memory| memory as f32)).collect();
+
+                    content = content
+                        .child(div().border_b_1().border_color(theme.border).text_lg().child(format!("Session {}", version_label(session))))
+                        .when(session.samples.is_empty(), |this| {
+                            this.child(div().text_sm().text_color(theme.muted_foreground).child("No FPS or memory usage was found in the log of this session"))
+                        })
+                        .when(session.samples.iter().any(|sample| sample.fps.is_some()), |this| {
+                            this.child(render_chart("FPS", fps, theme.success, cx))
+                        })
+                        .when(session.samples.iter().any(|sample| sample.memory_used_mib.is_some()), |this| {
+                            this.child(render_chart("Memory (MiB)", memory, theme.info, cx))
+                        });
+                }
+
+                content = content.child(div().border_b_1().border_color(theme.border).text_lg().child("Sessions"));
+
+                // Newest first, noting when the instance changed compared to the session before it
+                for (index, session) in sessions.iter().enumerate().rev() {
+                    let summary = SessionSummary::new(session);
+                    let label = version_label(session);
+
+                    let mut line = String::new();
+                    if let Some(date_time) = chrono::DateTime::from_timestamp(session.started_at, 0) {
+                        let date_time = date_time.with_timezone(&chrono::Local);
+                        line.push_str(&format!("{}: ", date_time.format("%Y-%m-%d %H:%M")));
+                    }
+                    line.push_str(&format!("{}, {} mods, played {}", label, session.mod_count, format_duration(session.play_seconds)));
+                    if let Some(average_fps) = summary.average_fps {
+                        line.push_str(&format!(", {} fps average", average_fps));
+                    }
+                    if let Some(min_fps) = summary.min_fps {
+                        line.push_str(&format!(", {} fps lowest", min_fps));
+                    }
+                    if let Some(average_tps) = summary.average_tps {
+                        line.push_str(&format!(", {:.1} tps average", average_tps));
+                    }
+                    if let Some(peak_memory) = summary.peak_memory_mib {
+                        line.push_str(&format!(", {} MiB peak memory", peak_memory));
+                    }
+                    if session.lag_spikes > 0 {
+                        line.push_str(&format!(", {} lag spikes ({:.1}s behind)", session.lag_spikes, session.lag_millis as f64 / 1000.0));
+                    }
+
+                    let changed = index.checked_sub(1).and_then(|previous| sessions.get(previous)).and_then(|previous| {
+                        let previous_label = version_label(previous);
+                        if previous_label != label {
+                            Some(format!("Updated from {previous_label}"))
+                        } else if previous.mod_count != session.mod_count {
+                            Some(format!("Mods changed from {} to {}", previous.mod_count, session.mod_count))
+                        } else {
+                            None
+                        }
+                    });
+
+                    let selected = index == self.selected;
+                    content = content.child(v_flex()
+                        .id(("session", index))
+                        .p_2()
+                        .rounded(theme.radius)
+                        .cursor_pointer()
+                        .when(selected, |this| this.bg(theme.accent))
+                        .hover(|style| style.bg(theme.secondary_hover))
+                        .child(line)
+                        .when_some(changed, |this, changed| {
+                            this.child(div().text_sm().text_color(theme.warning).child(changed))
+                        })
+                        .on_click(cx.listener(move |page, _, _, cx| {
+                            page.selected = index;
+                            cx.notify();
+                        })));
+                }
+            },
+        }
+
+        v_flex()
+            .p_4()
+            .size_full()
+            .child(header)
+            .child(div()
+                .size_full()
+                .border_1()
+                .rounded(theme.radius)
+                .border_color(theme.border)
+                .child(content.overflow_y_scrollbar())
+            )
+    }
+}
@@ -1,33 +1,57 @@
-use std::sync::{
+use std::{collections::BTreeSet, ops::Range, path::PathBuf, sync::{
     Arc, Mutex, RwLock,
     atomic::{AtomicBool, AtomicUsize, Ordering},
-};
+}};
 
-use bridge::{handle::BackendHandle, message::MessageToBackend};
+use bridge::{handle::BackendHandle, instance::InstanceID, message::{BulkInstanceAction, MessageToBackend}, modal_action::ModalAction};
 use gpui::{prelude::*, *};
 use gpui_component::{
-    alert::Alert, button::{Button, ButtonGroup, ButtonVariants}, checkbox::Checkbox, h_flex, input::{Input, InputEvent, InputState}, select::{Select, SelectDelegate, SelectEvent, SelectItem, SelectState}, skeleton::Skeleton, table::{Table, TableDelegate, TableState}, v_flex, ActiveTheme as _, IconName, IndexPath, Selectable, Sizable, WindowExt
+    alert::Alert, button::{Button, ButtonGroup, ButtonVariants}, checkbox::Checkbox, h_flex, input::{Input, InputEvent, InputState}, scroll::Scrollbar, select::{Select, SelectDelegate, SelectEvent, SelectItem, SelectState}, notification::{Notification, NotificationType}, skeleton::Skeleton, table::{Table, TableDelegate, TableState}, v_flex, ActiveTheme as _, IconName, IndexPath, Selectable, Sizable, WindowExt
 };
-use schema::{loader::Loader, version_manifest::{MinecraftVersionManifest, MinecraftVersionType}};
+use schema::{instance::InstanceJvmBinaryConfiguration, loader::Loader, version_manifest::{MinecraftVersionManifest, MinecraftVersionType}};
 use strum::IntoEnumIterator;
 
 use crate::{
-    component::{instance_list::InstanceList, named_dropdown::{NamedDropdown, NamedDropdownItem}, page_path::PagePath, responsive_grid::ResponsiveGrid}, entity::{instance::InstanceEntries, metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult}, DataEntities}, interface_config::{InstancesViewMode, InterfaceConfig}, ui
+    component::{instance_filter::{InstanceFilter, InstanceSortOrder, PlayedWithin}, instance_list::InstanceList, named_dropdown::{NamedDropdown, NamedDropdownItem}, page_path::PagePath}, entity::{instance::InstanceEntries, metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult}, DataEntities}, interface_config::{InstancesViewMode, InterfaceConfig}, keymap::{LaunchSelected, SelectDown, SelectLeft, SelectRight, SelectUp, INSTANCE_GRID_CONTEXT}, ui
 };
 
+// Matches the minimum width of the cards and the gap between them
+const CARD_MIN_WIDTH: Rems = Rems(16.0);
+const CARD_GAP: Rems = Rems(1.0);
+
 pub struct InstancesPage {
     instance_table: Entity<TableState<InstanceList>>,
     view_dropdown: Entity<SelectState<NamedDropdown<InstancesViewMode>>>,
 
+    filter: InstanceFilter,
+    search_input_state: Entity<InputState>,
+    loader_dropdown: Entity<SelectState<NamedDropdown<Option<Loader>>>>,
+    version_dropdown: Entity<SelectState<NamedDropdown<Option<SharedString>>>>,
+    group_dropdown: Entity<SelectState<NamedDropdown<Option<SharedString>>>>,
+    played_dropdown: Entity<SelectState<NamedDropdown<PlayedWithin>>>,
+    sort_dropdown: Entity<SelectState<NamedDropdown<InstanceSortOrder>>>,
+    version_options: Vec<SharedString>,
+    group_options: Vec<SharedString>,
+
     metadata: Entity<FrontendMetadata>,
     instances: Entity<InstanceEntries>,
 
     backend_handle: BackendHandle,
+    focus_handle: FocusHandle,
+    // Instance highlighted by keyboard navigation in the cards view
+    cursor: Option<usize>,
+    grid_columns: usize,
+    card_scroll_handle: UniformListScrollHandle,
+    // First instance of the topmost visible row and that row, used to keep the view in place when instances
+    // are added, removed or reordered above it
+    card_anchor: Option<(InstanceID, usize)>,
+    _select_paths_task: Option<Task<()>>,
 }
 
 impl InstancesPage {
     pub fn new(data: &DataEntities, window: &mut Window, cx: &mut Context<Self>) -> Self {
         let instance_table = InstanceList::create_table(data, window, cx);
+        cx.observe(&instance_table, |_, _, cx| cx.notify()).detach();
         let view_dropdown = cx.new(|cx| {
             let items = InstancesViewMode::iter().map(|view| {
                 NamedDropdownItem { name: view.name(), item: view }
@@ -46,50 +70,502 @@ impl InstancesPage {
             InterfaceConfig::get_mut(cx).instances_view_mode = view;
         }).detach();
 
+        let search_input_state = cx.new(|cx| InputState::new(window, cx).placeholder("Search instances..."));
+        cx.subscribe(&search_input_state, |this, state, event: &InputEvent, cx| {
+            if let InputEvent::Change = event {
+                let query = state.read(cx).value().to_lowercase();
+                this.update_filter(cx, |filter| filter.query = query.into());
+            }
+        }).detach();
+
+        let loader_dropdown = Self::create_dropdown(
+            std::iter::once(NamedDropdownItem { name: "Any loader".into(), item: None })
+                .chain([Loader::Vanilla, Loader::Fabric, Loader::Forge, Loader::NeoForge].into_iter().map(|loader| {
+                    NamedDropdownItem { name: loader.name().into(), item: Some(loader) }
+                }))
+                .collect(),
+            window, cx, |filter, loader| filter.loader = loader,
+        );
+        let version_dropdown = Self::create_dropdown(
+            vec![NamedDropdownItem { name: "Any version".into(), item: None }],
+            window, cx, |filter, version| filter.version = version,
+        );
+        let group_dropdown = Self::create_dropdown(
+            vec![NamedDropdownItem { name: "Any group".into(), item: None }],
+            window, cx, |filter, group| filter.group = group,
+        );
+        let played_dropdown = Self::create_dropdown(
+            PlayedWithin::iter().map(|played| NamedDropdownItem { name: played.name(), item: played }).collect(),
+            window, cx, |filter, played| filter.played_within = played,
+        );
+
+        let sort_dropdown = cx.new(|cx| {
+            let items = InstanceSortOrder::iter().map(|order| NamedDropdownItem { name: order.name(), item: order }).collect();
+            SelectState::new(NamedDropdown::new(items), Some(IndexPath::new(0)), window, cx)
+        });
+        cx.subscribe(&sort_dropdown, |this, _, event: &SelectEvent<NamedDropdown<InstanceSortOrder>>, cx| {
+            let SelectEvent::Confirm(Some(value)) = event else {
+                return;
+            };
+            let sort_order = value.item;
+            this.instance_table.update(cx, |table, cx| {
+                table.delegate_mut().set_sort_order(sort_order);
+                cx.notify();
+            });
+        }).detach();
+
+        // Focus the page so the cards can be navigated with the keyboard right away
+        let focus_handle = cx.focus_handle();
+        focus_handle.focus(window, cx);
+
         Self {
             instance_table,
             view_dropdown,
+            filter: InstanceFilter::default(),
+            search_input_state,
+            loader_dropdown,
+            version_dropdown,
+            group_dropdown,
+            played_dropdown,
+            sort_dropdown,
+            version_options: Vec::new(),
+            group_options: Vec::new(),
             metadata: data.metadata.clone(),
             instances: data.instances.clone(),
             backend_handle: data.backend_handle.clone(),
+            focus_handle,
+            cursor: None,
+            grid_columns: 1,
+            card_scroll_handle: UniformListScrollHandle::new(),
+            card_anchor: None,
+            _select_paths_task: None,
         }
     }
+
+    pub fn focus_search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.search_input_state.update(cx, |input, cx| input.focus(window, cx));
+    }
+
+    fn move_cursor(&mut self, offset: isize, cx: &mut Context<Self>) {
+        let rows = self.instance_table.read(cx).delegate().rows_count(cx);
+        if rows == 0 {
+            self.cursor = None;
+        } else if let Some(cursor) = self.cursor {
+            self.cursor = Some(cursor.saturating_add_signed(offset).min(rows - 1));
+        } else {
+            self.cursor = Some(0);
+        }
+        if let Some(cursor) = self.cursor {
+            self.card_scroll_handle.scroll_to_item(cursor / self.grid_columns, ScrollStrategy::Nearest);
+        }
+        cx.notify();
+    }
+
+    // The number of cards that fit next to each other, based on the width of the grid in the last frame
+    fn card_columns(&self, window: &Window) -> usize {
+        let rem_size = window.rem_size();
+        let card_width = CARD_MIN_WIDTH.to_pixels(rem_size);
+        let gap = CARD_GAP.to_pixels(rem_size);
+        let width = self.card_scroll_handle.0.borrow().base_handle.bounds().size.width;
+        // The rows are padded by a gap on both sides
+        (((width - gap) / (card_width + gap)).floor() as usize).max(1)
+    }
+
+    // Scrolls by the number of rows the anchor moved, so the cards on screen stay put when the list changes above them
+    fn keep_card_anchor(&mut self, cx: &App) {
+        let Some((anchor_id, anchor_row)) = self.card_anchor else {
+            return;
+        };
+        let Some(index) = self.instance_table.read(cx).delegate().position(anchor_id) else {
+            return;
+        };
+        let row = index / self.grid_columns;
+        if row == anchor_row {
+            return;
+        }
+
+        let scroll_state = self.card_scroll_handle.0.borrow();
+        let Some(item_size) = scroll_state.last_item_size else {
+            return;
+        };
+        let mut offset = scroll_state.base_handle.offset();
+        offset.y -= item_size.item.height * (row as f32 - anchor_row as f32);
+        scroll_state.base_handle.set_offset(offset);
+        self.card_anchor = Some((anchor_id, row));
+    }
+
+    fn render_card_rows(&mut self, visible_rows: Range<usize>, window: &mut Window, cx: &mut Context<Self>) -> Vec<Div> {
+        // The width is only known after layout, render again if the number of columns changed
+        if self.card_columns(window) != self.grid_columns {
+            cx.notify();
+        }
+
+        let columns = self.grid_columns;
+        // Only show the cursor while navigating with the keyboard
+        let cursor = self.cursor.filter(|_| self.focus_handle.is_focused(window));
+        let instance_table = self.instance_table.clone();
+        let (anchor, rows) = self.instance_table.update(cx, |table, cx| {
+            let delegate = table.delegate();
+            let count = delegate.rows_count(cx);
+            let anchor = delegate.item(visible_rows.start * columns).map(|entry| (entry.id, visible_rows.start));
+
+            let rows = visible_rows.map(|row| {
+                let start = row * columns;
+                let end = (start + columns).min(count);
+                h_flex()
+                    .w_full()
+                    .gap_4()
+                    .px_4()
+                    .pt_4()
+                    .children((start..end).map(|i| delegate.render_card(i, cursor == Some(i), &instance_table, cx)))
+                    // Keeps the cards in the last row the same width as the others
+                    .children((end..start + columns).map(|_| div().flex_1().w_full().min_w_64()))
+            }).collect::<Vec<_>>();
+            (anchor, rows)
+        });
+        self.card_anchor = anchor;
+        rows
+    }
+
+    fn launch_selected(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(cursor) = self.cursor else {
+            return;
+        };
+        let Some(entry) = self.instance_table.read(cx).delegate().item(cursor).cloned() else {
+            return;
+        };
+        crate::root::start_instance(entry.id, entry.name, None, &self.backend_handle, window, cx);
+    }
+
+    fn create_dropdown<T: Clone + 'static>(
+        items: Vec<NamedDropdownItem<T>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        apply: impl Fn(&mut InstanceFilter, T) + 'static,
+    ) -> Entity<SelectState<NamedDropdown<T>>> {
+        let dropdown = cx.new(|cx| SelectState::new(NamedDropdown::new(items), Some(IndexPath::new(0)), window, cx));
+        cx.subscribe(&dropdown, move |this, _, event: &SelectEvent<NamedDropdown<T>>, cx| {
+            let SelectEvent::Confirm(Some(value)) = event else {
+                return;
+            };
+            let item = value.item.clone();
+            this.update_filter(cx, |filter| apply(filter, item));
+        }).detach();
+        dropdown
+    }
+
+    fn update_filter(&mut self, cx: &mut Context<Self>, update: impl FnOnce(&mut InstanceFilter)) {
+        update(&mut self.filter);
+        let filter = self.filter.clone();
+        self.instance_table.update(cx, |table, cx| {
+            table.delegate_mut().set_filter(filter, cx);
+            cx.notify();
+        });
+    }
+
+    fn clear_filter(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.search_input_state.update(cx, |input, cx| input.set_value("", window, cx));
+        self.loader_dropdown.update(cx, |select, cx| select.set_selected_index(Some(IndexPath::new(0)), window, cx));
+        self.version_dropdown.update(cx, |select, cx| select.set_selected_index(Some(IndexPath::new(0)), window, cx));
+        self.group_dropdown.update(cx, |select, cx| select.set_selected_index(Some(IndexPath::new(0)), window, cx));
+        self.played_dropdown.update(cx, |select, cx| select.set_selected_index(Some(IndexPath::new(0)), window, cx));
+        self.update_filter(cx, |filter| *filter = InstanceFilter::default());
+    }
+
+    // The version and group filters only offer values that are used by at least one instance
+    fn refresh_filter_options(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let mut versions = BTreeSet::new();
+        let mut groups = BTreeSet::new();
+        for entry in self.instance_table.read(cx).delegate().all_items() {
+            versions.insert(SharedString::new(entry.configuration.minecraft_version.as_str()));
+            if let Some(group) = entry.configuration.group {
+                groups.insert(SharedString::new(group.as_str()));
+            }
+        }
+
+        let mut versions = versions.into_iter().collect::<Vec<_>>();
+        versions.sort_by(|a, b| lexical_sort::natural_lexical_cmp(a, b).reverse());
+        let groups = groups.into_iter().collect::<Vec<_>>();
+
+        if versions != self.version_options {
+            Self::set_dropdown_options(&self.version_dropdown, "Any version", &versions, self.filter.version.as_ref(), window, cx);
+            self.version_options = versions;
+        }
+        if groups != self.group_options {
+            Self::set_dropdown_options(&self.group_dropdown, "Any group", &groups, self.filter.group.as_ref(), window, cx);
+            self.group_options = groups;
+        }
+    }
+
+    fn set_dropdown_options(
+        dropdown: &Entity<SelectState<NamedDropdown<Option<SharedString>>>>,
+        any: &'static str,
+        options: &[SharedString],
+        selected: Option<&SharedString>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let items = std::iter::once(NamedDropdownItem { name: any.into(), item: None })
+            .chain(options.iter().map(|option| NamedDropdownItem { name: option.clone(), item: Some(option.clone()) }))
+            .collect();
+        let row = selected.and_then(|selected| options.iter().position(|option| option == selected)).map(|i| i + 1).unwrap_or(0);
+        dropdown.update(cx, |select, cx| {
+            select.set_items(NamedDropdown::new(items), window, cx);
+            select.set_selected_index(Some(IndexPath::new(row)), window, cx);
+        });
+    }
+
+    fn render_filters(&mut self, cx: &mut Context<Self>) -> Div {
+        h_flex()
+            .gap_2()
+            .px_4()
+            .pt_2()
+            .flex_wrap()
+            .child(div().w_64().child(Input::new(&self.search_input_state).small()))
+            .child(Select::new(&self.loader_dropdown).small().w_40())
+            .child(Select::new(&self.version_dropdown).small().w_40())
+            .child(Select::new(&self.group_dropdown).small().w_40())
+            .child(Select::new(&self.played_dropdown).small().w_40().title_prefix("Played: "))
+            .child(Checkbox::new("has_updates").label("Has updates").checked(self.filter.has_updates).on_click(cx.listener(|this, value, _, cx| {
+                let value = *value;
+                this.update_filter(cx, |filter| filter.has_updates = value);
+            })))
+            .child(Select::new(&self.sort_dropdown).small().w_48().title_prefix("Sort: "))
+            .when(!self.filter.is_empty(), |this| {
+                this.child(Button::new("clear_filter").small().ghost().label("Clear filters").on_click(cx.listener(|this, _, window, cx| {
+                    this.clear_filter(window, cx);
+                })))
+            })
+    }
+
+    fn selected_ids(&self, cx: &App) -> Vec<InstanceID> {
+        self.instance_table.read(cx).delegate().selected_entries().iter().map(|entry| entry.id).collect()
+    }
+
+    fn clear_selection(&mut self, cx: &mut Context<Self>) {
+        self.instance_table.update(cx, |table, cx| {
+            table.delegate_mut().selected.clear();
+            cx.notify();
+        });
+    }
+
+    fn run_bulk_action(&mut self, action: BulkInstanceAction, window: &mut Window, cx: &mut Context<Self>) {
+        let ids = self.selected_ids(cx);
+        if ids.is_empty() {
+            return;
+        }
+
+        let modal_action = ModalAction::default();
+        self.backend_handle.send(MessageToBackend::BulkInstanceAction {
+            ids,
+            action,
+            modal_action: modal_action.clone(),
+        });
+        crate::modals::generic::show_modal(window, cx, "Applying to selected instances".into(),
+            "Error applying to selected instances".into(), modal_action);
+    }
+
+    fn select_paths(
+        &mut self,
+        directories: bool,
+        multiple: bool,
+        message: &'static str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        handle: impl FnOnce(&mut Self, Vec<PathBuf>, &mut Window, &mut Context<Self>) + 'static,
+    ) {
+        let receiver = cx.prompt_for_paths(PathPromptOptions {
+            files: !directories,
+            directories,
+            multiple,
+            prompt: Some(SharedString::new_static(message))
+        });
+
+        let this_entity = cx.entity();
+        self._select_paths_task = Some(window.spawn(cx, async move |cx| {
+            let Ok(result) = receiver.await else {
+                return;
+            };
+            _ = cx.update_window_entity(&this_entity, move |this, window, cx| {
+                match result {
+                    Ok(Some(paths)) if !paths.is_empty() => {
+                        (handle)(this, paths, window, cx);
+                    },
+                    Ok(_) => {},
+                    Err(error) => {
+                        let error = format!("{}", error);
+                        let notification = Notification::new()
+                            .autohide(false)
+                            .with_type(NotificationType::Error)
+                            .title(error);
+                        window.push_notification(notification, cx);
+                    },
+                }
+            });
+        }));
+    }
+
+    fn confirm_bulk_delete(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let names = self.instance_table.read(cx).delegate().selected_entries().iter()
+            .map(|entry| entry.name.clone())
+            .collect::<Vec<_>>();
+        if names.is_empty() {
+            return;
+        }
+
+        let warning_message = SharedString::new(format!("This will permanently delete {} instances and their associated saves, resourcepacks, mods, configuration files, and more. These files will not be recoverable", names.len()));
+        let confirm_label = SharedString::new(format!("Delete {} instances", names.len()));
+        let this_entity = cx.entity();
+        window.open_dialog(cx, move |dialog, _, _| {
+            dialog
+                .title("Delete Instances")
+                .child(v_flex()
+                    .gap_2()
+                    .child(warning_message.clone())
+                    .child(v_flex().children(names.iter().map(|name| div().font_bold().child(name.clone()))))
+                    .child(Button::new("confirm").label(confirm_label.clone()).danger().on_click({
+                        let this_entity = this_entity.clone();
+                        move |_, window, cx| {
+                            window.close_all_dialogs(cx);
+                            this_entity.update(cx, |this, cx| {
+                                this.run_bulk_action(BulkInstanceAction::Delete, window, cx);
+                                this.clear_selection(cx);
+                            });
+                        }
+                    })))
+        });
+    }
+
+    fn render_bulk_actions(&mut self, selected: usize, cx: &mut Context<Self>) -> Div {
+        h_flex()
+            .gap_2()
+            .child(SharedString::new(format!("{selected} selected")))
+            .child(Button::new("bulk_java").small().label("Change Java").on_click(cx.listener(|this, _, window, cx| {
+                this.select_paths(false, false, "Select Java Binary", window, cx, |this, paths, window, cx| {
+                    let jvm_binary = InstanceJvmBinaryConfiguration {
+                        enabled: true,
+                        path: paths.into_iter().next().map(|path| path.as_path().into()),
+                    };
+                    this.run_bulk_action(BulkInstanceAction::SetJvmBinary(jvm_binary), window, cx);
+                });
+            })))
+            .child(Button::new("bulk_mods").small().label("Add Mods").on_click(cx.listener(|this, _, window, cx| {
+                this.select_paths(false, true, "Select mods to install", window, cx, |this, paths, window, cx| {
+                    this.run_bulk_action(BulkInstanceAction::AddMods(paths.into()), window, cx);
+                });
+            })))
+            .child(Button::new("bulk_loader").small().label("Update Loaders").on_click(cx.listener(|this, _, window, cx| {
+                this.run_bulk_action(BulkInstanceAction::UpdateLoader, window, cx);
+            })))
+            .child(Button::new("bulk_export").small().label("Export").on_click(cx.listener(|this, _, window, cx| {
+                this.select_paths(true, false, "Select export folder", window, cx, |this, paths, window, cx| {
+                    let Some(directory) = paths.into_iter().next() else {
+                        return;
+                    };
+                    this.run_bulk_action(BulkInstanceAction::Export(directory.as_path().into()), window, cx);
+                });
+            })))
+            .child(Button::new("bulk_delete").small().danger().label("Delete").on_click(cx.listener(|this, _, window, cx| {
+                this.confirm_bulk_delete(window, cx);
+            })))
+            .child(Button::new("bulk_clear").small().ghost().label("Clear").on_click(cx.listener(|this, _, _, cx| {
+                this.clear_selection(cx);
+            })))
+    }
 }
 
 impl Render for InstancesPage {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.refresh_filter_options(window, cx);
+
         let create_instance = Button::new("create_instance")
             .success()
             .icon(IconName::Plus)
             .label("Create Instance")
             .on_click(cx.listener(|this, _, window, cx| {
                 crate::modals::create_instance::open_create_instance(this.metadata.clone(), this.instances.clone(),
+                    this.backend_handle.clone(), None, window, cx);
+            }));
+        let create_from_template = Button::new("create_from_template")
+            .icon(IconName::Plus)
+            .label("From Template")
+            .on_click(cx.listener(|this, _, window, cx| {
+                crate::modals::instance_template::open_create_from_template(this.metadata.clone(), this.instances.clone(),
+                    this.backend_handle.clone(), window, cx);
+            }));
+        let create_from_code = Button::new("create_from_code")
+            .icon(IconName::Plus)
+            .label("From Code")
+            .on_click(cx.listener(|this, _, window, cx| {
+                crate::modals::share_code::open_import_share_code(None, this.instances.clone(),
                     this.backend_handle.clone(), window, cx);
             }));
+        let add_existing = Button::new("add_existing")
+            .icon(IconName::FolderOpen)
+            .label("Add Folder")
+            .tooltip("Add an instance folder copied from elsewhere, its settings are stored inside the folder")
+            .on_click(cx.listener(|this, _, window, cx| {
+                this.select_paths(true, false, "Select instance folder", window, cx, |this, paths, _, _| {
+                    for path in paths {
+                        this.backend_handle.send(MessageToBackend::AddExternalInstance { path });
+                    }
+                });
+            }));
         let select_view = Select::new(&self.view_dropdown).title_prefix("View: ");
 
         let content = match InterfaceConfig::get(cx).instances_view_mode {
             InstancesViewMode::Cards => {
-                let cards = self.instance_table.update(cx, |table, cx| {
-                    let rows = table.delegate().rows_count(cx);
-                    (0..rows).map(|i| table.delegate().render_card(i, cx)).collect::<Vec<_>>()
-                });
+                self.grid_columns = self.card_columns(window);
+                self.keep_card_anchor(cx);
 
-                let size = Size::new(
-                    gpui::AvailableSpace::MinContent,
-                    gpui::AvailableSpace::MinContent
-                );
+                let instance_count = self.instance_table.read(cx).delegate().rows_count(cx);
+                let row_count = instance_count.div_ceil(self.grid_columns);
 
-                div().p_4().child(ResponsiveGrid::new(size).size_full().gap_4().children(cards)).into_any_element()
+                h_flex()
+                    .size_full()
+                    .overflow_y_hidden()
+                    .track_focus(&self.focus_handle)
+                    .key_context(INSTANCE_GRID_CONTEXT)
+                    .on_action(cx.listener(|this, _: &SelectLeft, _, cx| this.move_cursor(-1, cx)))
+                    .on_action(cx.listener(|this, _: &SelectRight, _, cx| this.move_cursor(1, cx)))
+                    .on_action(cx.listener(|this, _: &SelectUp, _, cx| {
+                        let columns = this.grid_columns as isize;
+                        this.move_cursor(-columns, cx);
+                    }))
+                    .on_action(cx.listener(|this, _: &SelectDown, _, cx| {
+                        let columns = this.grid_columns as isize;
+                        this.move_cursor(columns, cx);
+                    }))
+                    .on_action(cx.listener(|this, _: &LaunchSelected, window, cx| this.launch_selected(window, cx)))
+                    .child(
+                        uniform_list("instance-cards", row_count, cx.processor(Self::render_card_rows))
+                            .size_full()
+                            .pb_4()
+                            .track_scroll(&self.card_scroll_handle),
+                    )
+                    .child(
+                        div()
+                            .w_3()
+                            .h_full()
+                            .py_3()
+                            .child(Scrollbar::vertical(&self.card_scroll_handle)),
+                    )
+                    .into_any_element()
             },
             InstancesViewMode::List => {
                 Table::new(&self.instance_table).bordered(false).into_any_element()
             },
         };
 
-        let title_buttons = h_flex().gap_3().child(create_instance).child(select_view);
+        let mut title_buttons = h_flex().gap_3().child(create_instance).child(create_from_template).child(create_from_code).child(add_existing).child(select_view);
+
+        let selected = self.instance_table.read(cx).delegate().selected.len();
+        if selected > 0 {
+            title_buttons = title_buttons.child(self.render_bulk_actions(selected, cx));
+        }
 
         ui::page(cx, h_flex().gap_8().child("Instances").child(title_buttons))
+            .child(self.render_filters(cx))
             .child(content)
     }
 }
@@ -1,4 +1,6 @@
 pub mod instance;
 pub mod instances_page;
 pub mod modrinth_page;
+pub mod modrinth_project_page;
+pub mod statistics_page;
 pub mod syncing_page;
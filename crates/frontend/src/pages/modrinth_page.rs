@@ -1,38 +1,51 @@
 use std::{ops::Range, sync::{atomic::AtomicBool, Arc}, time::Duration};
 
-use bridge::{instance::{AtomicContentUpdateStatus, ContentUpdateStatus, InstanceID, InstanceContentID, InstanceContentSummary}, message::MessageToBackend, meta::MetadataRequest, modal_action::ModalAction};
+use bridge::{install::ContentCompatibility, instance::{AtomicContentUpdateStatus, ContentUpdateStatus, InstanceID, InstanceContentID, InstanceContentSummary}, message::MessageToBackend, meta::MetadataRequest, modal_action::ModalAction};
 use gpui::{prelude::*, *};
 use gpui_component::{
-    ActiveTheme, Icon, IconName, Selectable, StyledExt, WindowExt, breadcrumb::Breadcrumb, button::{Button, ButtonGroup, ButtonVariant, ButtonVariants}, checkbox::Checkbox, h_flex, input::{Input, InputEvent, InputState}, label::Label, notification::NotificationType, scroll::{ScrollableElement, Scrollbar}, skeleton::Skeleton, tooltip::Tooltip, v_flex
+    ActiveTheme, Icon, IconName, IndexPath, Selectable, Sizable, StyledExt, WindowExt, breadcrumb::Breadcrumb, button::{Button, ButtonGroup, ButtonVariant, ButtonVariants}, checkbox::Checkbox, h_flex, input::{Input, InputEvent, InputState}, label::Label, notification::NotificationType, scroll::{ScrollableElement, Scrollbar}, select::{Select, SelectEvent, SelectState}, skeleton::Skeleton, tooltip::Tooltip, v_flex
 };
 use rustc_hash::{FxHashMap, FxHashSet};
 use schema::{content::ContentSource, loader::Loader, modrinth::{
-    ModrinthHit, ModrinthProjectType, ModrinthSearchRequest, ModrinthSearchResult, ModrinthSideRequirement
-}};
+    ModrinthHit, ModrinthProjectType, ModrinthSearchIndex, ModrinthSearchRequest, ModrinthSearchResult, ModrinthSideRequirement
+}, version_manifest::{MinecraftVersionManifest, MinecraftVersionType}};
 
 use crate::{
-    component::{error_alert::ErrorAlert, page_path::PagePath}, entity::{
-        DataEntities, instance::InstanceEntries, metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult}
-    }, interface_config::InterfaceConfig, ts, ts_short, ui
+    component::{error_alert::ErrorAlert, named_dropdown::{NamedDropdown, NamedDropdownItem}, page_path::PagePath}, entity::{
+        DataEntities, instance::InstanceEntries, metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult, FrontendMetadataState}
+    }, interface_config::InterfaceConfig, pages::instances_page::VersionList, ts, ts_short, ui::{self, PageType}
 };
 
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(250);
+const ANY_VERSION: &str = "Any version";
+
 pub struct ModrinthSearchPage {
     data: DataEntities,
     hits: Vec<ModrinthHit>,
     page_path: PagePath,
     install_for: Option<InstanceID>,
-    loading: Option<Subscription>,
+    loading: Option<(MetadataRequest, Subscription)>,
     pending_clear: bool,
     total_hits: usize,
     search_state: Entity<InputState>,
     _search_input_subscription: Subscription,
     _delayed_clear_task: Task<()>,
+    _search_debounce_task: Task<()>,
     filter_project_type: ModrinthProjectType,
     filter_loaders: FxHashSet<Loader>,
     filter_categories: FxHashSet<&'static str>,
+    filter_version: Option<SharedString>,
+    filter_open_source: bool,
+    sort_index: ModrinthSearchIndex,
+    sort_dropdown: Entity<SelectState<NamedDropdown<ModrinthSearchIndex>>>,
+    version_dropdown: Entity<SelectState<VersionList>>,
+    versions: Entity<FrontendMetadataState>,
+    _versions_subscription: Subscription,
+    _accounts_subscription: Subscription,
     show_categories: Arc<AtomicBool>,
     can_install_latest: bool,
     installed_mods_by_project: FxHashMap<Arc<str>, Vec<InstalledMod>>,
+    compatibility: FxHashMap<Arc<str>, ContentCompatibility>,
     last_search: Arc<str>,
     scroll_handle: UniformListScrollHandle,
     search_error: Option<SharedString>,
@@ -83,6 +96,44 @@ impl ModrinthSearchPage {
             filter_project_type = ModrinthProjectType::Mod;
         }
 
+        let sort_dropdown = cx.new(|cx| {
+            let items = [
+                ("Relevance", ModrinthSearchIndex::Relevance),
+                ("Downloads", ModrinthSearchIndex::Downloads),
+                ("Follows", ModrinthSearchIndex::Follows),
+                ("Newest", ModrinthSearchIndex::Newest),
+                ("Recently updated", ModrinthSearchIndex::Updated),
+            ].into_iter().map(|(name, item)| NamedDropdownItem { name: name.into(), item }).collect();
+            SelectState::new(NamedDropdown::new(items), Some(IndexPath::new(0)), window, cx)
+        });
+        cx.subscribe(&sort_dropdown, |page, _, event: &SelectEvent<NamedDropdown<ModrinthSearchIndex>>, cx| {
+            let SelectEvent::Confirm(Some(value)) = event else {
+                return;
+            };
+            if page.sort_index != value.item {
+                page.sort_index = value.item.clone();
+                page.reload(cx);
+            }
+        }).detach();
+
+        let version_dropdown = cx.new(|cx| SelectState::new(VersionList::default(), None, window, cx).searchable(true));
+        cx.subscribe(&version_dropdown, |page, _, event: &SelectEvent<VersionList>, cx| {
+            let SelectEvent::Confirm(Some(value)) = event else {
+                return;
+            };
+            let version = Some(value.clone()).filter(|version| version.as_str() != ANY_VERSION);
+            if page.filter_version != version {
+                page.filter_version = version;
+                page.reload(cx);
+            }
+        }).detach();
+
+        let versions = FrontendMetadata::request(&data.metadata, MetadataRequest::MinecraftVersionManifest, cx);
+        let _versions_subscription = cx.observe_in(&versions, window, |page, _, window, cx| {
+            page.reload_versions(window, cx);
+        });
+        let _accounts_subscription = cx.observe(&data.accounts, |_, _, cx| cx.notify());
+
         let mut page = Self {
             data: data.clone(),
             hits: Vec::new(),
@@ -94,21 +145,58 @@ impl ModrinthSearchPage {
             search_state,
             _search_input_subscription,
             _delayed_clear_task: Task::ready(()),
+            _search_debounce_task: Task::ready(()),
             filter_project_type,
             filter_loaders: FxHashSet::default(),
             filter_categories: FxHashSet::default(),
+            filter_version: None,
+            filter_open_source: false,
+            sort_index: ModrinthSearchIndex::Relevance,
+            sort_dropdown,
+            version_dropdown,
+            versions,
+            _versions_subscription,
+            _accounts_subscription,
             show_categories: Arc::new(AtomicBool::new(false)),
             can_install_latest,
             installed_mods_by_project,
+            compatibility: FxHashMap::default(),
             last_search: Arc::from(""),
             scroll_handle: UniformListScrollHandle::new(),
             search_error: None,
             image_cache: RetainAllImageCache::new(cx),
         };
+        page.reload_versions(window, cx);
         page.load_more(cx);
         page
     }
 
+    pub fn focus_search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.search_state.update(cx, |state, cx| state.focus(window, cx));
+    }
+
+    fn reload_versions(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let result: FrontendMetadataResult<MinecraftVersionManifest> = self.versions.read(cx).result();
+        let FrontendMetadataResult::Loaded(manifest) = result else {
+            return;
+        };
+
+        let versions: Vec<SharedString> = std::iter::once(SharedString::new_static(ANY_VERSION))
+            .chain(manifest.versions.iter()
+                .filter(|v| matches!(v.r#type, MinecraftVersionType::Release))
+                .map(|v| SharedString::from(v.id.as_str())))
+            .collect();
+        let selected = self.filter_version.clone().unwrap_or(SharedString::new_static(ANY_VERSION));
+
+        self.version_dropdown.update(cx, |select, cx| {
+            select.set_items(VersionList {
+                versions: versions.clone(),
+                matched_versions: versions,
+            }, window, cx);
+            select.set_selected_value(&selected, window, cx);
+        });
+    }
+
     fn on_search_input_event(
         &mut self,
         state: &Entity<InputState>,
@@ -129,7 +217,14 @@ impl ModrinthSearchPage {
 
         let search: Arc<str> = Arc::from(search);
         self.last_search = search.clone();
-        self.reload(cx);
+
+        // Wait for typing to pause so that every keystroke doesn't start a new search
+        self._search_debounce_task = cx.spawn(async |page, cx| {
+            gpui::Timer::after(SEARCH_DEBOUNCE).await;
+            let _ = page.update(cx, |page, cx| {
+                page.reload(cx);
+            });
+        });
     }
 
     fn set_project_type(&mut self, project_type: ModrinthProjectType, window: &mut Window, cx: &mut Context<Self>) {
@@ -168,9 +263,22 @@ impl ModrinthSearchPage {
         self.reload(cx);
     }
 
+    fn set_filter_open_source(&mut self, open_source: bool, cx: &mut Context<Self>) {
+        if self.filter_open_source == open_source {
+            return;
+        }
+        self.filter_open_source = open_source;
+        self.reload(cx);
+    }
+
     fn reload(&mut self, cx: &mut Context<Self>) {
         self.pending_clear = true;
-        self.loading = None;
+        self._search_debounce_task = Task::ready(());
+
+        // The results of the previous query are no longer needed
+        if let Some((request, _)) = self.loading.take() {
+            FrontendMetadata::cancel(&self.data.metadata, &request, cx);
+        }
 
         self._delayed_clear_task = cx.spawn(async |page, cx| {
             gpui::Timer::after(Duration::from_millis(300)).await;
@@ -245,17 +353,27 @@ impl ModrinthSearchPage {
             facets.push(']');
         }
 
+        if let Some(version) = &self.filter_version {
+            facets.push_str(",[\"versions:");
+            facets.push_str(version);
+            facets.push_str("\"]");
+        }
+
+        if self.filter_open_source {
+            facets.push_str(",[\"open_source:true\"]");
+        }
+
         facets.push(']');
 
-        let request = ModrinthSearchRequest {
+        let request = MetadataRequest::ModrinthSearch(ModrinthSearchRequest {
             query,
             facets: Some(facets.into()),
-            index: schema::modrinth::ModrinthSearchIndex::Relevance,
+            index: self.sort_index.clone(),
             offset,
             limit: 20,
-        };
+        });
 
-        let data = FrontendMetadata::request(&self.data.metadata, MetadataRequest::ModrinthSearch(request), cx);
+        let data = FrontendMetadata::request(&self.data.metadata, request.clone(), cx);
 
         let result: FrontendMetadataResult<ModrinthSearchResult> = data.read(cx).result();
         match result {
@@ -265,7 +383,8 @@ impl ModrinthSearchPage {
                     match result {
                         FrontendMetadataResult::Loading => {},
                         FrontendMetadataResult::Loaded(result) => {
-                            page.apply_search_data(result);
+                            let project_ids = page.apply_search_data(result);
+                            page.check_compatibility(project_ids, cx);
                             page.loading = None;
                             cx.notify();
                         },
@@ -276,10 +395,11 @@ impl ModrinthSearchPage {
                         },
                     }
                 });
-                self.loading = Some(subscription);
+                self.loading = Some((request, subscription));
             },
             FrontendMetadataResult::Loaded(result) => {
-                self.apply_search_data(result);
+                let project_ids = self.apply_search_data(result);
+                self.check_compatibility(project_ids, cx);
             },
             FrontendMetadataResult::Error(shared_string) => {
                 self.search_error = Some(shared_string);
@@ -287,7 +407,8 @@ impl ModrinthSearchPage {
         }
     }
 
-    fn apply_search_data(&mut self, search_result: &ModrinthSearchResult) {
+    // Returns the projects whose compatibility with the instance still needs to be checked
+    fn apply_search_data(&mut self, search_result: &ModrinthSearchResult) -> Arc<[Arc<str>]> {
         if self.pending_clear {
             self.pending_clear = false;
             self.hits.clear();
@@ -303,6 +424,41 @@ impl ModrinthSearchPage {
             hit
         }));
         self.total_hits = search_result.total_hits;
+
+        if self.install_for.is_none() {
+            return Arc::from([]);
+        }
+        // Modpacks create a new instance, so their compatibility with this one doesn't matter
+        search_result.hits.iter()
+            .filter(|hit| hit.project_type != ModrinthProjectType::Modpack && !self.compatibility.contains_key(&hit.project_id))
+            .map(|hit| hit.project_id.clone())
+            .collect()
+    }
+
+    fn check_compatibility(&mut self, project_ids: Arc<[Arc<str>]>, cx: &mut Context<Self>) {
+        let Some(install_for) = self.install_for else {
+            return;
+        };
+        if project_ids.is_empty() {
+            return;
+        }
+
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self.data.backend_handle.send(MessageToBackend::CheckModrinthCompatibility {
+            id: install_for,
+            project_ids,
+            channel: send,
+        });
+
+        cx.spawn(async move |page, cx| {
+            let Ok(result) = recv.await else {
+                return;
+            };
+            _ = page.update(cx, |page, cx| {
+                page.compatibility.extend(result.iter().cloned());
+                cx.notify();
+            });
+        }).detach();
     }
 
     fn render_items(&mut self, visible_range: Range<usize>, _window: &mut Window, cx: &mut Context<Self>) -> Vec<Div> {
@@ -321,7 +477,7 @@ impl ModrinthSearchPage {
                         return div()
                             .pl_3()
                             .pt_3()
-                            .child(Skeleton::new().w_full().h(px(28.0 * 4.0)).rounded_lg());
+                            .child(Skeleton::new().w_full().h(rems(7.0)).rounded_lg());
                     }
                 };
 
@@ -380,6 +536,15 @@ impl ModrinthSearchPage {
 
                 let environment = h_flex().gap_1().font_bold().child(env_icon).child(env_name);
 
+                let compatibility = self.compatibility.get(&hit.project_id).map(|compatibility| {
+                    let (icon, text, color) = match compatibility {
+                        ContentCompatibility::Compatible => (IconName::Check, "Compatible", theme.success),
+                        ContentCompatibility::DifferentVersion => (IconName::TriangleAlert, "Needs different version", theme.warning),
+                        ContentCompatibility::Incompatible => (IconName::CircleX, "Incompatible", theme.danger),
+                    };
+                    h_flex().gap_1().font_bold().text_color(color).child(Icon::new(icon)).child(text)
+                });
+
                 let categories = hit.display_categories.iter().flat_map(|categories| {
                     categories.iter().map(|category| {
                         let icon = icon_for(category).unwrap_or("icons/diamond.svg");
@@ -476,16 +641,18 @@ impl ModrinthSearchPage {
                     .child(
                         Button::new(("open", index))
                             .label("Open Page")
-                            .icon(IconName::Globe)
+                            .icon(IconName::Info)
                             .info()
                             .on_click({
-                                let project_type = hit.project_type.as_str();
                                 let project_id = hit.project_id.clone();
-                                move |_, _, cx| {
-                                    cx.open_url(&format!(
-                                        "https://modrinth.com/{}/{}",
-                                        project_type, project_id
-                                    ));
+                                let install_for = self.install_for;
+                                let path = self.page_path.pages().clone();
+                                move |_, window, cx| {
+                                    let page = PageType::ModrinthProject {
+                                        project_id: project_id.as_ref().into(),
+                                        installing_for: install_for,
+                                    };
+                                    crate::root::switch_page(page, &path, window, cx);
                                 }
                             }),
                     );
@@ -503,7 +670,7 @@ impl ModrinthSearchPage {
                     .child(image.rounded_lg().size_16().min_w_16().min_h_16())
                     .child(
                         v_flex()
-                            .h(px(104.0))
+                            .h(rems(6.5))
                             .flex_grow()
                             .gap_1()
                             .overflow_hidden()
@@ -519,13 +686,14 @@ impl ModrinthSearchPage {
                             .child(
                                 div()
                                     .flex_auto()
-                                    .line_height(px(20.0))
+                                    .line_height(rems(1.25))
                                     .line_clamp(2)
                                     .child(description),
                             )
                             .child(
                                 h_flex()
                                     .gap_2p5()
+                                    .children(compatibility)
                                     .children(std::iter::once(environment).chain(categories)),
                             ),
                     )
@@ -666,8 +834,26 @@ impl Render for ModrinthSearchPage {
         let mut top_bar = h_flex()
             .w_full()
             .gap_3()
-            .child(Input::new(&self.search_state));
-
+            .child(Input::new(&self.search_state))
+            .child(Select::new(&self.sort_dropdown).w_56().title_prefix("Sort: "));
+
+        if self.data.accounts.read(cx).modrinth_account.is_some() {
+            top_bar = top_bar
+                .child(Button::new("followed").label("Followed").icon(IconName::Star).on_click({
+                    let backend_handle = self.data.backend_handle.clone();
+                    let install_for = self.install_for;
+                    let path = self.page_path.pages().clone();
+                    move |_, window, cx| {
+                        crate::modals::modrinth_account::open_follows(&backend_handle, install_for, path.clone(), window, cx);
+                    }
+                }))
+                .child(Button::new("notifications").label("Notifications").icon(IconName::Bell).on_click({
+                    let backend_handle = self.data.backend_handle.clone();
+                    move |_, window, cx| {
+                        crate::modals::modrinth_account::open_notifications(&backend_handle, window, cx);
+                    }
+                }));
+        }
 
         if self.can_install_latest {
             let tooltip = |window: &mut Window, cx: &mut App| {
@@ -790,7 +976,14 @@ impl Render for ModrinthSearchPage {
             .overflow_y_scrollbar()
             .child(v_flex().h_full().gap_3()
                 .child(type_button_group)
+                .child(Select::new(&self.version_dropdown).small())
                 .when_some(loader_button_group, |this, group| this.child(group))
+                .child(Checkbox::new("open-source")
+                    .label("Open source")
+                    .checked(self.filter_open_source)
+                    .on_click(cx.listener(|page, value, _, cx| {
+                        page.set_filter_open_source(*value, cx);
+                    })))
                 .child(category)
             );
 
@@ -799,7 +992,7 @@ impl Render for ModrinthSearchPage {
     }
 }
 
-fn format_downloads(downloads: usize) -> String {
+pub fn format_downloads(downloads: usize) -> String {
     if downloads >= 1_000_000_000 {
         format!("{}B Downloads", (downloads / 10_000_000) as f64 / 100.0)
     } else if downloads >= 1_000_000 {
@@ -0,0 +1,504 @@
+use std::sync::Arc;
+
+use bridge::{
+    install::{ContentDownload, ContentInstall, ContentInstallFile, ContentInstallPath, InstallTarget}, instance::InstanceID, meta::MetadataRequest
+};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, StyledExt, WindowExt, button::{Button, ButtonVariants}, checkbox::Checkbox, h_flex, notification::NotificationType, scroll::ScrollableElement, skeleton::Skeleton, spinner::Spinner, tab::{Tab, TabBar}, v_flex, Sizable
+};
+use rustc_hash::FxHashSet;
+use schema::{
+    content::ContentSource, loader::Loader, modrinth::{
+        ModrinthDependencyType, ModrinthLoader, ModrinthProject, ModrinthProjectDependencies, ModrinthProjectType, ModrinthProjectVersion, ModrinthProjectVersionsRequest, ModrinthProjectVersionsResult, ModrinthVersionStatus, ModrinthVersionType
+    }
+};
+use ustr::Ustr;
+
+use crate::{
+    component::{error_alert::ErrorAlert, markdown::Markdown, page_path::PagePath, responsive_grid::ResponsiveGrid}, entity::{
+        DataEntities, metadata::{AsMetadataResult, FrontendMetadata, FrontendMetadataResult, FrontendMetadataState}
+    }, pages::modrinth_page::format_downloads, root, ui::{self, PageType}
+};
+
+const VERSIONS_PER_PAGE: usize = 50;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ProjectTab {
+    Description,
+    Gallery,
+    Versions,
+    Dependencies,
+}
+
+pub struct ModrinthProjectPage {
+    data: DataEntities,
+    page_path: PagePath,
+    project_id: Arc<str>,
+    install_for: Option<InstanceID>,
+    project: Entity<FrontendMetadataState>,
+    versions: Entity<FrontendMetadataState>,
+    dependencies: Entity<FrontendMetadataState>,
+    tab: ProjectTab,
+    only_compatible: bool,
+    shown_versions: usize,
+    expanded_changelogs: FxHashSet<Arc<str>>,
+    image_cache: Entity<RetainAllImageCache>,
+}
+
+impl ModrinthProjectPage {
+    pub fn new(project_id: Ustr, install_for: Option<InstanceID>, page_path: PagePath, data: &DataEntities, _window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let project_id: Arc<str> = Arc::from(project_id.as_str());
+
+        let project = FrontendMetadata::request(&data.metadata, MetadataRequest::ModrinthProject(project_id.clone()), cx);
+        let versions = FrontendMetadata::request(&data.metadata, MetadataRequest::ModrinthProjectVersions(ModrinthProjectVersionsRequest {
+            project_id: project_id.clone(),
+            game_versions: None,
+            loaders: None,
+        }), cx);
+        let dependencies = FrontendMetadata::request(&data.metadata, MetadataRequest::ModrinthProjectDependencies(project_id.clone()), cx);
+
+        cx.observe(&project, |_, _, cx| cx.notify()).detach();
+        cx.observe(&versions, |_, _, cx| cx.notify()).detach();
+        cx.observe(&dependencies, |_, _, cx| cx.notify()).detach();
+
+        Self {
+            data: data.clone(),
+            page_path,
+            project_id,
+            install_for,
+            project,
+            versions,
+            dependencies,
+            tab: ProjectTab::Description,
+            only_compatible: install_for.is_some(),
+            shown_versions: VERSIONS_PER_PAGE,
+            expanded_changelogs: FxHashSet::default(),
+            image_cache: RetainAllImageCache::new(cx),
+        }
+    }
+
+    // The game version and loader of the instance that content is being installed for
+    fn install_target_info(&self, cx: &App) -> Option<(InstanceID, Ustr, Loader)> {
+        let id = self.install_for?;
+        let entry = self.data.instances.read(cx).entries.get(&id)?.read(cx);
+        Some((id, entry.configuration.minecraft_version, entry.configuration.loader))
+    }
+
+    pub fn is_compatible(version: &ModrinthProjectVersion, project_type: ModrinthProjectType, minecraft_version: Ustr, loader: Loader) -> bool {
+        let game_version_matches = version.game_versions.as_ref().is_some_and(|versions| versions.contains(&minecraft_version));
+        if !game_version_matches {
+            return false;
+        }
+        if project_type != ModrinthProjectType::Mod {
+            return true;
+        }
+        let modrinth_loader = loader.as_modrinth_loader();
+        modrinth_loader != ModrinthLoader::Unknown && version.loaders.as_ref().is_some_and(|loaders| loaders.contains(&modrinth_loader))
+    }
+
+    fn install_version(&self, version: &ModrinthProjectVersion, window: &mut Window, cx: &mut App) {
+        let Some((id, minecraft_version, loader)) = self.install_target_info(cx) else {
+            return;
+        };
+
+        let mut files = Vec::new();
+        for dependency in version.dependencies.iter().flatten() {
+            if dependency.dependency_type != ModrinthDependencyType::Required {
+                continue;
+            }
+            let Some(project_id) = &dependency.project_id else {
+                continue;
+            };
+            files.push(ContentInstallFile {
+                replace_old: None,
+                path: ContentInstallPath::Automatic,
+                download: ContentDownload::Modrinth {
+                    project_id: project_id.clone(),
+                    version_id: dependency.version_id.clone(),
+                },
+                content_source: ContentSource::ModrinthProject { project: project_id.clone() },
+            });
+        }
+        files.push(ContentInstallFile {
+            replace_old: None,
+            path: ContentInstallPath::Automatic,
+            download: ContentDownload::Modrinth {
+                project_id: self.project_id.clone(),
+                version_id: Some(version.id.clone()),
+            },
+            content_source: ContentSource::ModrinthProject { project: self.project_id.clone() },
+        });
+
+        let content_install = ContentInstall {
+            target: InstallTarget::Instance(id),
+            loader_hint: loader,
+            version_hint: Some(minecraft_version.as_str().into()),
+            files: files.into(),
+        };
+        root::start_install(content_install, &self.data.backend_handle, window, cx);
+    }
+
+    fn open_project(&self, project_id: &str, window: &mut Window, cx: &mut App) {
+        let page = PageType::ModrinthProject {
+            project_id: project_id.into(),
+            installing_for: self.install_for,
+        };
+        root::switch_page(page, self.page_path.pages(), window, cx);
+    }
+
+    fn render_header(&self, project: &ModrinthProject, cx: &mut Context<Self>) -> Div {
+        let theme = cx.theme();
+
+        let icon = if let Some(icon_url) = &project.icon_url && !icon_url.is_empty() {
+            gpui::img(SharedUri::from(icon_url))
+                .with_fallback(|| Skeleton::new().rounded_lg().size_16().into_any_element())
+        } else {
+            gpui::img(ImageSource::Resource(Resource::Embedded("images/default_mod.png".into())))
+        };
+
+        let title = project.title.clone().map(SharedString::new).unwrap_or(SharedString::new_static("Unnamed"));
+        let description = project.description.clone().map(SharedString::new).unwrap_or_default();
+
+        let stats = h_flex()
+            .gap_3()
+            .text_color(theme.muted_foreground)
+            .child(h_flex().gap_0p5().child(Icon::empty().path("icons/download.svg")).child(format_downloads(project.downloads)))
+            .child(h_flex().gap_0p5().child(Icon::empty().path("icons/heart.svg")).child(format!("{} Followers", project.followers)));
+
+        let links = [
+            ("source", "Source", &project.source_url),
+            ("issues", "Issues", &project.issues_url),
+            ("wiki", "Wiki", &project.wiki_url),
+            ("discord", "Discord", &project.discord_url),
+        ].into_iter().filter_map(|(id, label, url)| {
+            let url = url.clone().filter(|url| !url.is_empty())?;
+            Some(Button::new(id).label(label).small().outline().on_click(move |_, _, cx| {
+                cx.open_url(&url);
+            }))
+        });
+
+        let install_button = Button::new("install").success().icon(Icon::empty().path("icons/download.svg")).label("Install").on_click({
+            let data = self.data.clone();
+            let project_id = self.project_id.clone();
+            let project_type = project.project_type;
+            let install_for = self.install_for;
+            let title = title.clone();
+            move |_, window, cx| {
+                if project_type == ModrinthProjectType::Other {
+                    window.push_notification((NotificationType::Error, "Don't know how to handle this type of content"), cx);
+                    return;
+                }
+                crate::modals::modrinth_install::open(title.as_str(), project_id.clone(), project_type, install_for, &data, window, cx);
+            }
+        });
+
+        // Modpacks create a new instance, so they can't be installed into existing ones
+        let versions: FrontendMetadataResult<ModrinthProjectVersionsResult> = self.versions.read(cx).result();
+        let install_multiple_button = match versions {
+            FrontendMetadataResult::Loaded(versions) if !matches!(project.project_type, ModrinthProjectType::Modpack | ModrinthProjectType::Other) => {
+                let data = self.data.clone();
+                let project_id = self.project_id.clone();
+                let project_type = project.project_type;
+                let versions = versions.0.clone();
+                let title = title.clone();
+                Some(Button::new("install_multiple").success().outline().icon(Icon::empty().path("icons/download.svg")).label("Install to Instances")
+                    .on_click(move |_, window, cx| {
+                        crate::modals::modrinth_install_multiple::open(title.as_str(), project_id.clone(), project_type, &versions, &data, window, cx);
+                    }))
+            },
+            _ => None,
+        };
+
+        let browser_button = Button::new("open_browser").info().icon(IconName::Globe).label("Open in Browser").on_click({
+            let url = format!("https://modrinth.com/{}/{}", project.project_type.as_str(), project.slug.as_ref().unwrap_or(&project.id));
+            move |_, _, cx| {
+                cx.open_url(&url);
+            }
+        });
+
+        h_flex()
+            .p_4()
+            .gap_4()
+            .child(icon.rounded_lg().size_16().min_w_16().min_h_16())
+            .child(v_flex()
+                .flex_1()
+                .gap_1()
+                .overflow_hidden()
+                .child(div().text_xl().font_bold().child(title))
+                .child(div().line_clamp(2).child(description))
+                .child(stats)
+                .child(h_flex().gap_2().flex_wrap().children(links)))
+            .child(v_flex().gap_2().child(install_button).children(install_multiple_button).child(browser_button))
+    }
+
+    fn render_description(&self, project: &ModrinthProject) -> AnyElement {
+        match &project.body {
+            Some(body) if !body.trim().is_empty() => Markdown::new("description", SharedString::new(body.clone())).into_any_element(),
+            _ => div().child("This project has no description").into_any_element(),
+        }
+    }
+
+    fn render_gallery(&self, project: &ModrinthProject, cx: &mut Context<Self>) -> AnyElement {
+        if project.gallery.is_empty() {
+            return div().child("This project has no gallery images").into_any_element();
+        }
+
+        let theme = cx.theme();
+        let mut gallery = project.gallery.to_vec();
+        gallery.sort_by_key(|image| !image.featured);
+
+        let images = gallery.into_iter().enumerate().map(|(index, image)| {
+            let url = image.url.clone();
+            v_flex()
+                .id(("gallery", index))
+                .w(rems(20.0))
+                .gap_1()
+                .p_2()
+                .rounded_lg()
+                .border_1()
+                .border_color(theme.border)
+                .cursor_pointer()
+                .on_click(move |_, _, cx| {
+                    cx.open_url(&url);
+                })
+                .child(gpui::img(SharedUri::from(&image.url))
+                    .with_fallback(|| Skeleton::new().rounded_md().w_full().h(rems(11.25)).into_any_element())
+                    .w_full()
+                    .h(rems(11.25))
+                    .object_fit(ObjectFit::Contain)
+                    .rounded_md())
+                .when_some(image.title.clone(), |this, title| this.child(div().font_semibold().child(SharedString::new(title))))
+                .when_some(image.description.clone(), |this, description| {
+                    this.child(div().text_sm().text_color(theme.muted_foreground).line_clamp(2).child(SharedString::new(description)))
+                })
+                .into_any_element()
+        }).collect::<Vec<_>>();
+
+        let size = Size::new(gpui::AvailableSpace::MinContent, gpui::AvailableSpace::MinContent);
+        ResponsiveGrid::new(size).w_full().gap_4().children(images).into_any_element()
+    }
+
+    fn render_versions(&self, project: &ModrinthProject, cx: &mut Context<Self>) -> AnyElement {
+        let result: FrontendMetadataResult<ModrinthProjectVersionsResult> = self.versions.read(cx).result();
+        let versions = match result {
+            FrontendMetadataResult::Loading => return h_flex().justify_center().child(Spinner::new().large()).into_any_element(),
+            FrontendMetadataResult::Loaded(versions) => versions.0.clone(),
+            FrontendMetadataResult::Error(error) => {
+                return ErrorAlert::new("versions_error", "Error requesting versions from Modrinth".into(), error).into_any_element();
+            },
+        };
+
+        let target = self.install_target_info(cx);
+        let project_type = project.project_type;
+
+        let versions = versions.iter()
+            .filter(|version| !matches!(version.status, Some(ModrinthVersionStatus::Draft | ModrinthVersionStatus::Unlisted)))
+            .filter(|version| {
+                !self.only_compatible || target.is_none_or(|(_, minecraft_version, loader)| {
+                    Self::is_compatible(version, project_type, minecraft_version, loader)
+                })
+            })
+            .collect::<Vec<_>>();
+        let total = versions.len();
+
+        let theme = cx.theme();
+        let rows = versions.into_iter().take(self.shown_versions).enumerate().map(|(index, version)| {
+            let name = version.name.clone().or(version.version_number.clone()).map(SharedString::new).unwrap_or_default();
+            let (version_type, color) = match version.version_type {
+                Some(ModrinthVersionType::Release) | None => ("Release", theme.success),
+                Some(ModrinthVersionType::Beta) => ("Beta", theme.warning),
+                Some(ModrinthVersionType::Alpha) => ("Alpha", theme.danger),
+                Some(ModrinthVersionType::Other) => ("Other", theme.muted_foreground),
+            };
+
+            let game_versions = version.game_versions.as_ref().map(|versions| {
+                versions.iter().map(|version| version.as_str()).collect::<Vec<_>>().join(", ")
+            }).unwrap_or_default();
+            let loaders = version.loaders.as_ref().map(|loaders| {
+                loaders.iter().map(|loader| loader.pretty_name()).collect::<Vec<_>>().join(", ")
+            }).unwrap_or_default();
+            let published = version.date_published.as_ref().and_then(|date| date.split('T').next()).unwrap_or_default().to_string();
+
+            let expanded = self.expanded_changelogs.contains(&version.id);
+            let has_changelog = version.changelog.as_ref().is_some_and(|changelog| !changelog.trim().is_empty());
+
+            let install_button = target.map(|_| {
+                let version = version.clone();
+                Button::new(("install", index)).success().small().label("Install").on_click(cx.listener(move |page, _, window, cx| {
+                    page.install_version(&version, window, cx);
+                }))
+            });
+
+            let changelog_button = has_changelog.then(|| {
+                let id = version.id.clone();
+                Button::new(("changelog", index))
+                    .small()
+                    .outline()
+                    .label("Changelog")
+                    .icon(if expanded { IconName::ChevronDown } else { IconName::ChevronRight })
+                    .on_click(cx.listener(move |page, _, _, cx| {
+                        if !page.expanded_changelogs.remove(&id) {
+                            page.expanded_changelogs.insert(id.clone());
+                        }
+                        cx.notify();
+                    }))
+            });
+
+            v_flex()
+                .p_3()
+                .gap_2()
+                .rounded_lg()
+                .border_1()
+                .border_color(theme.border)
+                .child(h_flex()
+                    .gap_3()
+                    .child(div().text_color(color).font_semibold().w_16().child(version_type))
+                    .child(v_flex()
+                        .flex_1()
+                        .overflow_hidden()
+                        .child(div().font_semibold().truncate().child(name))
+                        .child(div().text_sm().text_color(theme.muted_foreground).truncate()
+                            .child(format!("{loaders} · {game_versions}"))))
+                    .child(div().text_sm().text_color(theme.muted_foreground).child(published))
+                    .children(changelog_button)
+                    .children(install_button))
+                .when(expanded, |this| {
+                    this.children(version.changelog.clone().map(|changelog| {
+                        Markdown::new(("changelog", index), SharedString::new(changelog))
+                    }))
+                })
+        }).collect::<Vec<_>>();
+
+        let compatible_checkbox = target.map(|_| {
+            Checkbox::new("only_compatible")
+                .label("Only show versions compatible with this instance")
+                .checked(self.only_compatible)
+                .on_click(cx.listener(|page, value, _, cx| {
+                    page.only_compatible = *value;
+                    page.shown_versions = VERSIONS_PER_PAGE;
+                    cx.notify();
+                }))
+        });
+
+        v_flex()
+            .gap_2()
+            .children(compatible_checkbox)
+            .when(rows.is_empty(), |this| this.child("No versions found"))
+            .children(rows)
+            .when(total > self.shown_versions, |this| {
+                this.child(Button::new("show_more").outline().label(format!("Show more ({} remaining)", total - self.shown_versions))
+                    .on_click(cx.listener(|page, _, _, cx| {
+                        page.shown_versions += VERSIONS_PER_PAGE;
+                        cx.notify();
+                    })))
+            })
+            .into_any_element()
+    }
+
+    fn render_dependencies(&self, cx: &mut Context<Self>) -> AnyElement {
+        let result: FrontendMetadataResult<ModrinthProjectDependencies> = self.dependencies.read(cx).result();
+        let dependencies = match result {
+            FrontendMetadataResult::Loading => return h_flex().justify_center().child(Spinner::new().large()).into_any_element(),
+            FrontendMetadataResult::Loaded(dependencies) => dependencies.projects.clone(),
+            FrontendMetadataResult::Error(error) => {
+                return ErrorAlert::new("dependencies_error", "Error requesting dependencies from Modrinth".into(), error).into_any_element();
+            },
+        };
+
+        if dependencies.is_empty() {
+            return div().child("This project has no dependencies").into_any_element();
+        }
+
+        let theme = cx.theme();
+        let rows = dependencies.iter().enumerate().map(|(index, dependency)| {
+            let icon = if let Some(icon_url) = &dependency.icon_url && !icon_url.is_empty() {
+                gpui::img(SharedUri::from(icon_url))
+                    .with_fallback(|| Skeleton::new().rounded_md().size_10().into_any_element())
+            } else {
+                gpui::img(ImageSource::Resource(Resource::Embedded("images/default_mod.png".into())))
+            };
+            let title = dependency.title.clone().map(SharedString::new).unwrap_or(SharedString::new_static("Unnamed"));
+            let description = dependency.description.clone().map(SharedString::new).unwrap_or_default();
+            let project_id = dependency.id.clone();
+
+            h_flex()
+                .p_2()
+                .gap_3()
+                .rounded_lg()
+                .border_1()
+                .border_color(theme.border)
+                .child(icon.rounded_md().size_10().min_w_10().min_h_10())
+                .child(v_flex()
+                    .flex_1()
+                    .overflow_hidden()
+                    .child(div().font_semibold().child(title))
+                    .child(div().text_sm().text_color(theme.muted_foreground).truncate().child(description)))
+                .child(Button::new(("view", index)).info().small().label("View").on_click(cx.listener(move |page, _, window, cx| {
+                    page.open_project(&project_id, window, cx);
+                })))
+        }).collect::<Vec<_>>();
+
+        v_flex().gap_2().children(rows).into_any_element()
+    }
+}
+
+impl Render for ModrinthProjectPage {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let breadcrumb = self.page_path.create_breadcrumb(&self.data, cx);
+        let page = ui::page(cx, breadcrumb);
+
+        let result: FrontendMetadataResult<ModrinthProject> = self.project.read(cx).result();
+        let project = match result {
+            FrontendMetadataResult::Loading => {
+                return page.child(h_flex().size_full().justify_center().child(Spinner::new().large()));
+            },
+            FrontendMetadataResult::Loaded(project) => project.clone(),
+            FrontendMetadataResult::Error(error) => {
+                return page.child(div().p_4().child(ErrorAlert::new("project_error", "Error requesting project from Modrinth".into(), error)));
+            },
+        };
+
+        let content = match self.tab {
+            ProjectTab::Description => self.render_description(&project),
+            ProjectTab::Gallery => self.render_gallery(&project, cx),
+            ProjectTab::Versions => self.render_versions(&project, cx),
+            ProjectTab::Dependencies => self.render_dependencies(cx),
+        };
+
+        let selected_index = match self.tab {
+            ProjectTab::Description => 0,
+            ProjectTab::Gallery => 1,
+            ProjectTab::Versions => 2,
+            ProjectTab::Dependencies => 3,
+        };
+
+        page.image_cache(self.image_cache.clone())
+            .child(self.render_header(&project, cx))
+            .child(TabBar::new("project_tabs")
+                .prefix(div().w_4())
+                .selected_index(selected_index)
+                .underline()
+                .child(Tab::new().label("Description"))
+                .child(Tab::new().label(format!("Gallery ({})", project.gallery.len())))
+                .child(Tab::new().label("Versions"))
+                .child(Tab::new().label("Dependencies"))
+                .on_click(cx.listener(|page, index, _, cx| {
+                    page.tab = match *index {
+                        0 => ProjectTab::Description,
+                        1 => ProjectTab::Gallery,
+                        2 => ProjectTab::Versions,
+                        3 => ProjectTab::Dependencies,
+                        _ => return,
+                    };
+                    cx.notify();
+                })))
+            .child(div()
+                .id("project_content")
+                .flex_1()
+                .min_h_0()
+                .p_4()
+                .overflow_y_scrollbar()
+                .child(content))
+    }
+}
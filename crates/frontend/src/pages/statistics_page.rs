@@ -0,0 +1,130 @@
+use bridge::{handle::BackendHandle, message::{LaunchMetricsSummary, MessageToBackend}};
+use gpui::{prelude::*, *};
+use gpui_component::{
+    button::{Button, ButtonVariants}, checkbox::Checkbox, h_flex, scroll::ScrollableElement, spinner::Spinner, v_flex, ActiveTheme as _, Disableable, Sizable
+};
+
+use crate::{entity::DataEntities, ui};
+
+pub struct StatisticsPage {
+    backend_handle: BackendHandle,
+    summary: Option<LaunchMetricsSummary>,
+    _get_summary_task: Task<()>,
+}
+
+impl StatisticsPage {
+    pub fn new(data: &DataEntities, _window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let mut page = Self {
+            backend_handle: data.backend_handle.clone(),
+            summary: None,
+            _get_summary_task: Task::ready(()),
+        };
+
+        page.update_summary(cx);
+
+        page
+    }
+}
+
+impl StatisticsPage {
+    pub fn update_summary(&mut self, cx: &mut Context<Self>) {
+        let (send, recv) = tokio::sync::oneshot::channel();
+        self._get_summary_task = cx.spawn(async move |page, cx| {
+            let result: LaunchMetricsSummary = recv.await.unwrap_or_default();
+            let _ = page.update(cx, move |page, cx| {
+                page.summary = Some(result);
+                cx.notify();
+            });
+        });
+
+        self.backend_handle.send(MessageToBackend::GetLaunchMetrics {
+            channel: send,
+        });
+    }
+}
+
+fn format_duration(seconds: u64) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+impl Render for StatisticsPage {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(summary) = &self.summary else {
+            let content = v_flex().size_full().p_3().gap_3()
+                .child(Spinner::new().with_size(gpui_component::Size::Large));
+            return ui::page(cx, h_flex().gap_8().child("Statistics")).child(content).overflow_y_scrollbar();
+        };
+
+        let recording = summary.recording;
+
+        let backend_handle = self.backend_handle.clone();
+        let record_checkbox = Checkbox::new("record")
+            .label("Record launch statistics")
+            .checked(recording)
+            .on_click(cx.listener(move |page, value, _, cx| {
+                backend_handle.send(MessageToBackend::SetLaunchMetricsRecording {
+                    value: *value,
+                });
+                page.update_summary(cx);
+            }));
+
+        let mut content = v_flex().size_full().p_3().gap_3()
+            .child("Statistics are only recorded if you opt in and are stored on this computer")
+            .child(record_checkbox);
+
+        if summary.upload_available {
+            let backend_handle = self.backend_handle.clone();
+            content = content.child(Checkbox::new("upload")
+                .label("Share anonymous launch statistics to help improve the launcher")
+                .disabled(!recording)
+                .checked(recording && summary.uploading)
+                .on_click(cx.listener(move |page, value, _, cx| {
+                    backend_handle.send(MessageToBackend::SetLaunchMetricsUpload {
+                        value: *value,
+                    });
+                    page.update_summary(cx);
+                })));
+        }
+
+        content = content.child(div().border_b_1().border_color(cx.theme().border).text_lg().child("Overview"))
+            .child(format!("Launches: {}", summary.total_launches))
+            .child(format!("Failed launches: {}", summary.failed_launches))
+            .child(format!("Crashes: {}", summary.crashes))
+            .child(format!("Time played: {}", format_duration(summary.total_play_seconds)))
+            .when_some(summary.average_launch_millis, |this, millis| {
+                this.child(format!("Average launch time: {:.1}s", millis as f64 / 1000.0))
+            });
+
+        if !summary.instances.is_empty() {
+            content = content.child(div().border_b_1().border_color(cx.theme().border).text_lg().child("Instances"));
+
+            for instance in summary.instances.iter() {
+                let mut line = format!("{}: {} launches, {} played", instance.name, instance.launches, format_duration(instance.play_seconds));
+                if instance.crashes > 0 {
+                    line.push_str(&format!(", {} crashes", instance.crashes));
+                }
+                if let Some(date_time) = chrono::DateTime::from_timestamp(instance.last_launched, 0) {
+                    let date_time = date_time.with_timezone(&chrono::Local);
+                    line.push_str(&format!(", last launched {}", date_time.format("%Y-%m-%d %H:%M")));
+                }
+                content = content.child(div().text_color(cx.theme().muted_foreground).child(line));
+            }
+        }
+
+        let backend_handle = self.backend_handle.clone();
+        content = content.child(Button::new("clear").danger().label("Clear statistics")
+            .disabled(summary.total_launches == 0)
+            .on_click(cx.listener(move |page, _, _, cx| {
+                backend_handle.send(MessageToBackend::ClearLaunchMetrics);
+                page.update_summary(cx);
+            })).w_72());
+
+        ui::page(cx, h_flex().gap_8().child("Statistics")).child(content).overflow_y_scrollbar()
+    }
+}
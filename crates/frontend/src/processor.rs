@@ -2,9 +2,10 @@ use std::{collections::HashMap, sync::{Arc, atomic::AtomicBool}};
 
 use bridge::{instance::InstanceStatus, message::{BridgeNotificationType, MessageToFrontend}};
 use gpui::{AnyWindowHandle, App, AppContext, Entity, SharedString, TitlebarOptions, Window, WindowDecorations, WindowHandle, WindowOptions, px, size};
-use gpui_component::{notification::{Notification, NotificationType}, Root, WindowExt};
+use gpui_component::{dialog::DialogButtonProps, notification::{Notification, NotificationType}, Root, WindowExt};
+use schema::instance::InstanceType;
 
-use crate::{entity::{DataEntities, account::AccountEntries, instance::InstanceEntries, metadata::FrontendMetadata}, game_output::{GameOutput, GameOutputRoot}, interface_config::InterfaceConfig};
+use crate::{deep_link::DeepLink, entity::{DataEntities, account::AccountEntries, background_jobs::BackgroundJobs, instance::InstanceEntries, metadata::FrontendMetadata, server_status::ServerStatuses}, game_output::{GameOutput, GameOutputRoot}, interface_config::{InterfaceConfig, LaunchBehaviour}, tray::TrayAction, ui::PageType};
 
 pub struct Processor {
     data: DataEntities,
@@ -12,6 +13,9 @@ pub struct Processor {
     main_window_handle: Option<AnyWindowHandle>,
     main_window_hidden: Arc<AtomicBool>,
     waiting_for_window: Vec<MessageToFrontend>,
+    // The uuid or name of the instance to launch on startup
+    auto_launch: Option<SharedString>,
+    pending_link: Option<DeepLink>,
 }
 
 impl Processor {
@@ -22,12 +26,132 @@ impl Processor {
             main_window_handle: None,
             main_window_hidden,
             waiting_for_window: Vec::new(),
+            auto_launch: None,
+            pending_link: None,
         }
     }
 
+    // Instances are loaded in the background, so the instance is launched once both it and the main window exist
+    pub fn set_auto_launch(&mut self, reference: SharedString, cx: &mut App) {
+        self.auto_launch = Some(reference);
+        self.try_auto_launch(cx);
+    }
+
+    fn try_auto_launch(&mut self, cx: &mut App) {
+        let Some(reference) = &self.auto_launch else {
+            return;
+        };
+        let Some(handle) = self.main_window_handle else {
+            return;
+        };
+        let Some(id) = InstanceEntries::find_id_by_reference(&self.data.instances, reference, cx) else {
+            return;
+        };
+        let Some(name) = InstanceEntries::find_name_by_id(&self.data.instances, id, cx) else {
+            return;
+        };
+
+        self.auto_launch = None;
+
+        let backend_handle = self.data.backend_handle.clone();
+        _ = handle.update(cx, |_, window, cx| {
+            crate::root::start_instance(id, name, None, &backend_handle, window, cx);
+        });
+    }
+
+    // Like auto launching, links which refer to an instance wait until it has been loaded
+    pub fn open_link(&mut self, link: &str, cx: &mut App) {
+        log::info!("Opening link {link}");
+        self.show_main_window(cx);
+
+        let Some(deep_link) = DeepLink::parse(link) else {
+            log::warn!("Unknown link {link}");
+            if let Some(handle) = self.main_window_handle {
+                let message = SharedString::new(format!("Unable to open unknown link {link}"));
+                _ = handle.update(cx, |_, window, cx| {
+                    window.push_notification((NotificationType::Warning, message), cx);
+                });
+            }
+            return;
+        };
+
+        self.pending_link = Some(deep_link);
+        self.try_open_link(cx);
+    }
+
+    fn try_open_link(&mut self, cx: &mut App) {
+        let Some(deep_link) = &self.pending_link else {
+            return;
+        };
+        let Some(handle) = self.main_window_handle else {
+            return;
+        };
+
+        let instance_id = match deep_link {
+            DeepLink::Launch(reference) | DeepLink::Instance(reference, _) => {
+                let Some(id) = InstanceEntries::find_id_by_reference(&self.data.instances, reference, cx) else {
+                    return;
+                };
+                Some(id)
+            },
+            DeepLink::ModrinthSearch(_) | DeepLink::ModrinthProject(_) | DeepLink::ImportShareCode(_) => None,
+        };
+
+        let Some(deep_link) = self.pending_link.take() else {
+            return;
+        };
+
+        let backend_handle = self.data.backend_handle.clone();
+        let instances = self.data.instances.clone();
+        _ = handle.update(cx, |_, window, cx| {
+            match deep_link {
+                DeepLink::Launch(_) => {
+                    let Some(id) = instance_id else {
+                        return;
+                    };
+                    let Some(name) = InstanceEntries::find_name_by_id(&instances, id, cx) else {
+                        return;
+                    };
+                    // Any website can open a link, so the user confirms before the game is started
+                    let title = SharedString::new(format!("Launch {name}?"));
+                    window.open_dialog(cx, move |dialog, _, _| {
+                        let name = name.clone();
+                        let backend_handle = backend_handle.clone();
+                        dialog
+                            .title(title.clone())
+                            .confirm()
+                            .button_props(DialogButtonProps::default().ok_text("Launch"))
+                            .child("A link opened in the launcher wants to start this instance")
+                            .on_ok(move |_, window, cx| {
+                                crate::root::start_instance(id, name.clone(), None, &backend_handle, window, cx);
+                                true
+                            })
+                    });
+                },
+                DeepLink::Instance(_, subpage) => {
+                    let Some(id) = instance_id else {
+                        return;
+                    };
+                    crate::root::switch_page(PageType::InstancePage(id, subpage), &[PageType::Instances], window, cx);
+                },
+                DeepLink::ModrinthSearch(project_type) => {
+                    crate::root::switch_page(PageType::Modrinth { installing_for: None, project_type }, &[], window, cx);
+                },
+                DeepLink::ModrinthProject(project_id) => {
+                    let search = PageType::Modrinth { installing_for: None, project_type: None };
+                    crate::root::switch_page(PageType::ModrinthProject { project_id, installing_for: None }, &[search], window, cx);
+                },
+                DeepLink::ImportShareCode(code) => {
+                    crate::modals::share_code::open_import_share_code(Some(code), instances, backend_handle, window, cx);
+                },
+            }
+        });
+    }
+
     pub fn set_main_window_handle(&mut self, window: AnyWindowHandle, cx: &mut App) {
         self.main_window_handle = Some(window);
         self.process_messages_waiting_for_window(cx);
+        self.try_open_link(cx);
     }
 
     pub fn process_messages_waiting_for_window(&mut self, cx: &mut App) {
@@ -36,9 +160,42 @@ impl Processor {
         }
     }
 
+    pub fn show_main_window(&mut self, cx: &mut App) {
+        if let Some(handle) = self.main_window_handle
+            && handle.update(cx, |_, window, _| window.activate_window()).is_ok()
+        {
+            return;
+        }
+
+        self.main_window_handle = Some(crate::open_main_window(&self.data, cx));
+        self.main_window_hidden.store(false, std::sync::atomic::Ordering::SeqCst);
+        self.process_messages_waiting_for_window(cx);
+    }
+
+    pub fn handle_tray_action(&mut self, action: TrayAction, cx: &mut App) {
+        match action {
+            TrayAction::OpenLauncher => self.show_main_window(cx),
+            TrayAction::Launch(name) => {
+                let Some(id) = InstanceEntries::find_id_by_reference(&self.data.instances, &name, cx) else {
+                    return;
+                };
+                self.show_main_window(cx);
+                let Some(handle) = self.main_window_handle else {
+                    return;
+                };
+                let backend_handle = self.data.backend_handle.clone();
+                _ = handle.update(cx, |_, window, cx| {
+                    crate::root::start_instance(id, name, None, &backend_handle, window, cx);
+                });
+            },
+            TrayAction::Quit => cx.quit(),
+        }
+    }
+
     #[inline(always)]
     pub fn with_main_window(&mut self, message: MessageToFrontend, cx: &mut App, func: impl FnOnce(&mut Processor, MessageToFrontend, &mut Window, &mut App)) {
-        let Some(handle) = self.main_window_handle else {
+        // The main window can be closed while the launcher keeps running in the tray
+        let Some(handle) = self.main_window_handle.filter(|handle| cx.windows().contains(handle)) else {
             self.waiting_for_window.push(message);
             return;
         };
@@ -53,8 +210,9 @@ impl Processor {
             MessageToFrontend::AccountsUpdated {
                 accounts,
                 selected_account,
+                modrinth_account,
             } => {
-                AccountEntries::set(&self.data.accounts, accounts, selected_account, cx);
+                AccountEntries::set(&self.data.accounts, accounts, selected_account, modrinth_account, cx);
             },
             MessageToFrontend::InstanceAdded {
                 id,
@@ -80,6 +238,8 @@ impl Processor {
                     resource_packs_state,
                     cx,
                 );
+                self.try_auto_launch(cx);
+                self.try_open_link(cx);
             },
             MessageToFrontend::InstanceRemoved { id } => {
                 InstanceEntries::remove(&self.data.instances, id, cx);
@@ -93,13 +253,30 @@ impl Processor {
                 status,
             } => {
                 if status == InstanceStatus::Running {
-                    if InterfaceConfig::get(cx).hide_main_window_on_launch {
-                        if let Some(handle) = self.main_window_handle.take() {
-                            self.main_window_hidden.store(true, std::sync::atomic::Ordering::SeqCst);
-                            _ = handle.update(cx, |_, window, _| {
-                                window.remove_window();
-                            });
-                        }
+                    // Servers are controlled through the launcher console, so they always keep it open
+                    let is_server = configuration.instance_type == InstanceType::Server;
+                    match InterfaceConfig::get(cx).launch_behaviour {
+                        LaunchBehaviour::KeepOpen => {},
+                        _ if is_server => {},
+                        LaunchBehaviour::Minimize => {
+                            if let Some(handle) = self.main_window_handle {
+                                _ = handle.update(cx, |_, window, _| {
+                                    window.minimize_window();
+                                });
+                            }
+                        },
+                        LaunchBehaviour::Hide => {
+                            if let Some(handle) = self.main_window_handle.take() {
+                                self.main_window_hidden.store(true, std::sync::atomic::Ordering::SeqCst);
+                                _ = handle.update(cx, |_, window, _| {
+                                    window.remove_window();
+                                });
+                            }
+                        },
+                        LaunchBehaviour::Close => {
+                            // The game runs in its own process, so it keeps running after the launcher quits
+                            cx.quit();
+                        },
                     }
                 } else if status == InstanceStatus::NotRunning {
                     if self.main_window_handle.is_none() && self.main_window_hidden.load(std::sync::atomic::Ordering::SeqCst) {
@@ -120,6 +297,19 @@ impl Processor {
                     cx,
                 );
             },
+            MessageToFrontend::InstanceTunnelUpdated { id, address } => {
+                InstanceEntries::set_tunnel_address(&self.data.instances, id, address.map(SharedString::new), cx);
+            },
+            MessageToFrontend::InstanceExited { .. } => {},
+            MessageToFrontend::InstanceJvmCrashed { .. } => {
+                self.with_main_window(message, cx, |this, message, window, cx| {
+                    let MessageToFrontend::InstanceJvmCrashed { id, name, report } = message else {
+                        unreachable!();
+                    };
+
+                    crate::modals::jvm_crash::open(id, name, report, this.data.backend_handle.clone(), window, cx);
+                });
+            },
             MessageToFrontend::InstanceWorldsUpdated { id, worlds } => {
                 InstanceEntries::set_worlds(&self.data.instances, id, worlds, cx);
             },
@@ -167,12 +357,18 @@ impl Processor {
                     window.close_all_dialogs(cx);
                 });
             },
-            MessageToFrontend::CreateGameOutputWindow { id, keep_alive } => {
+            MessageToFrontend::CreateGameOutputWindow { id, keep_alive, console } => {
+                let title = if console.is_some() {
+                    SharedString::new_static("Minecraft Server Console")
+                } else {
+                    SharedString::new_static("Minecraft Game Output")
+                };
+                let console = console.map(|instance_id| (instance_id, self.data.backend_handle.clone()));
                 let options = WindowOptions {
                     app_id: Some("PandoraLauncher".into()),
                     window_min_size: Some(size(px(360.0), px(240.0))),
                     titlebar: Some(TitlebarOptions {
-                        title: Some(SharedString::new_static("Minecraft Game Output")),
+                        title: Some(title),
                         ..Default::default()
                     }),
                     window_decorations: Some(WindowDecorations::Server),
@@ -181,7 +377,7 @@ impl Processor {
                 _ = cx.open_window(options, |window, cx| {
                     let game_output = cx.new(|_| GameOutput::default());
                     let game_output_root = cx
-                        .new(|cx| GameOutputRoot::new(keep_alive, game_output.clone(), window, cx));
+                        .new(|cx| GameOutputRoot::new(keep_alive, game_output.clone(), console, window, cx));
                     window.activate_window();
                     let window_handle = window.window_handle().downcast::<Root>().unwrap();
                     self.game_output_windows.insert(id, (window_handle, game_output.clone()));
@@ -217,7 +413,70 @@ impl Processor {
 
                     crate::modals::update_prompt::open_update_prompt(update, this.data.backend_handle.clone(), window, cx);
                 });
-            }
+            },
+            MessageToFrontend::UpdateInstalled { .. } => {
+                self.with_main_window(message, cx, |this, message, window, cx| {
+                    let MessageToFrontend::UpdateInstalled { version } = message else {
+                        unreachable!();
+                    };
+
+                    crate::modals::update_prompt::open_update_installed(version, &this.data, window, cx);
+                });
+            },
+            MessageToFrontend::MinecraftVersionReleased { .. } => {
+                self.with_main_window(message, cx, |this, message, window, cx| {
+                    let MessageToFrontend::MinecraftVersionReleased { version, snapshot } = message else {
+                        unreachable!();
+                    };
+
+                    if !InterfaceConfig::get(cx).new_version_notifications.should_notify(snapshot) {
+                        return;
+                    }
+                    crate::modals::create_instance::notify_version_released(SharedString::new(version.as_str()), snapshot, &this.data, window, cx);
+                });
+            },
+            MessageToFrontend::InstanceUpgradeAvailable { .. } => {
+                self.with_main_window(message, cx, |this, message, window, cx| {
+                    let MessageToFrontend::InstanceUpgradeAvailable { id, name, version } = message else {
+                        unreachable!();
+                    };
+
+                    crate::modals::instance_upgrade::notify_upgrade_available(id, name, version, this.data.backend_handle.clone(), window, cx);
+                });
+            },
+            MessageToFrontend::InstanceUpgraded { .. } => {
+                self.with_main_window(message, cx, |this, message, window, cx| {
+                    let MessageToFrontend::InstanceUpgraded { id, name, from, to } = message else {
+                        unreachable!();
+                    };
+
+                    crate::modals::instance_upgrade::notify_upgraded(id, name, from, to, this.data.backend_handle.clone(), window, cx);
+                });
+            },
+            MessageToFrontend::ServerStatusUpdated { address, status } => {
+                ServerStatuses::set(&self.data.server_statuses, address, status, cx);
+            },
+            MessageToFrontend::PersistentFileRecovered { .. } => {
+                self.with_main_window(message, cx, |this, message, window, cx| {
+                    let MessageToFrontend::PersistentFileRecovered { file, corrupt_path, restored_from, backups } = message else {
+                        unreachable!();
+                    };
+
+                    crate::modals::file_recovery::open_file_recovery(file, corrupt_path, restored_from, backups, this.data.backend_handle.clone(), window, cx);
+                });
+            },
+            MessageToFrontend::BackgroundJobsUpdated { jobs } => {
+                BackgroundJobs::set(&self.data.background_jobs, jobs, cx);
+            },
+            MessageToFrontend::StrayInstancesFound { .. } => {
+                self.with_main_window(message, cx, |this, message, window, cx| {
+                    let MessageToFrontend::StrayInstancesFound { instances } = message else {
+                        unreachable!();
+                    };
+
+                    crate::modals::stray_instances::open_stray_instances(instances, this.data.backend_handle.clone(), window, cx);
+                });
+            },
         }
     }
 }
@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use gpui::{App, Global, SharedString};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entity::{instance::InstanceEntries, DataEntities},
+    instance_window,
+    interface_config::InterfaceConfig,
+    pages::instance::instance_page::InstanceSubpageType,
+    root::LauncherRootGlobal,
+};
+
+// Written right before relaunching into an installed update and removed again by the new process
+const RELAUNCH_FILE: &str = "relaunch.json";
+
+// The window and tab state which interface.json doesn't keep, the open page and window bounds are already saved there
+#[derive(Default, Serialize, Deserialize)]
+pub struct RelaunchState {
+    #[serde(default)]
+    pub instance_subpage: Option<InstanceSubpageType>,
+    // Instance references, see InstanceEntries::find_reference_by_id
+    #[serde(default)]
+    pub instance_windows: Vec<(SharedString, InstanceSubpageType)>,
+}
+
+impl Global for RelaunchState {}
+
+impl RelaunchState {
+    pub fn save(data: &DataEntities, cx: &mut App) {
+        let instance_subpage = cx.try_global::<LauncherRootGlobal>()
+            .and_then(|global| global.root.read(cx).ui.read(cx).instance_subpage(cx));
+
+        let instance_windows = instance_window::open_windows(cx).into_iter()
+            .filter_map(|(id, subpage)| {
+                let reference = InstanceEntries::find_reference_by_id(&data.instances, id, cx)?;
+                Some((reference, subpage))
+            })
+            .collect();
+
+        let state = RelaunchState { instance_subpage, instance_windows };
+        if let Ok(bytes) = serde_json::to_vec(&state) {
+            _ = crate::interface_config::write_safe(&data.data_folder.join(RELAUNCH_FILE), &bytes);
+        }
+
+        InterfaceConfig::force_save(cx);
+    }
+
+    // Only the first start after a relaunch restores the state
+    pub fn take(launcher_dir: &Path) -> Option<Self> {
+        let path = launcher_dir.join(RELAUNCH_FILE);
+        let data = std::fs::read(&path).ok()?;
+        _ = std::fs::remove_file(&path);
+        serde_json::from_slice(&data).ok()
+    }
+
+    pub fn reopen_instance_windows(data: &DataEntities, cx: &mut App) {
+        let Some(state) = cx.try_global::<RelaunchState>() else {
+            return;
+        };
+        let windows = state.instance_windows.clone();
+        cx.remove_global::<RelaunchState>();
+
+        for (reference, subpage) in windows {
+            if let Some(id) = InstanceEntries::find_id_by_reference(&data.instances, &reference, cx) {
+                instance_window::open(id, subpage, data, cx);
+            }
+        }
+    }
+}
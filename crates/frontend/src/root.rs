@@ -7,14 +7,16 @@ use bridge::{
     message::{MessageToBackend, QuickPlayLaunch},
     modal_action::ModalAction,
 };
+use uuid::Uuid;
 use gpui::{prelude::*, *};
-use gpui_component::{breadcrumb::Breadcrumb, scroll::{ScrollableElement, ScrollbarAxis}, v_flex, Root, StyledExt};
+use gpui_component::{breadcrumb::Breadcrumb, h_flex, scroll::{ScrollableElement, ScrollbarAxis}, v_flex, Root, StyledExt};
 use parking_lot::RwLock;
 
-use crate::{entity::DataEntities, modals, ui::{LauncherUI, PageType}, CloseWindow, MAIN_FONT};
+use crate::{entity::{account::AccountEntries, DataEntities}, interface_config::{InterfaceConfig, ThemeModePreference}, modals, ui::{LauncherUI, PageType}, CloseWindow, MAIN_FONT};
 
 pub struct LauncherRootGlobal {
     pub root: Entity<LauncherRoot>,
+    pub window: AnyWindowHandle,
 }
 
 impl Global for LauncherRootGlobal {}
@@ -24,6 +26,7 @@ pub struct LauncherRoot {
     pub panic_message: Arc<RwLock<Option<String>>>,
     pub deadlock_message: Arc<RwLock<Option<String>>>,
     pub backend_handle: BackendHandle,
+    accounts: Entity<AccountEntries>,
     focus_handle: FocusHandle,
 }
 
@@ -35,6 +38,14 @@ impl LauncherRoot {
     ) -> Self {
         let launcher_ui = cx.new(|cx| LauncherUI::new(data, window, cx));
 
+        cx.observe_window_appearance(window, |_, _, cx| {
+            if InterfaceConfig::get(cx).theme_mode == ThemeModePreference::System {
+                crate::theme::apply(cx);
+            }
+        }).detach();
+        // The title bar shows the selected account
+        cx.observe(&data.accounts, |_, _, cx| cx.notify()).detach();
+
         let focus_handle = cx.focus_handle();
         focus_handle.focus(window, cx);
 
@@ -43,6 +54,7 @@ impl LauncherRoot {
             panic_message: data.panic_messages.panic_message.clone(),
             deadlock_message: data.panic_messages.deadlock_message.clone(),
             backend_handle: data.backend_handle.clone(),
+            accounts: data.accounts.clone(),
             focus_handle,
         }
     }
@@ -70,12 +82,27 @@ impl Render for LauncherRoot {
         let dialog_layer = Root::render_dialog_layer(window, cx);
         let notification_layer = Root::render_notification_layer(window, cx);
 
+        let title_bar = has_csd_titlebar(window).then(|| {
+            let selected_account = self.accounts.read(cx).selected_account.clone().map(|account| {
+                h_flex()
+                    .gap_2()
+                    .text_sm()
+                    .child(crate::ui::account_head(Some(&account), cx).size_5().min_w_5().min_h_5())
+                    .child(SharedString::new(account.username.clone()))
+            });
+            gpui_component::TitleBar::new().child(h_flex()
+                .w_full()
+                .pr_2()
+                .justify_between()
+                .child("Pandora")
+                .children(selected_account))
+        });
+
         v_flex()
             .size_full()
             .font_family(MAIN_FONT)
-            .when(has_csd_titlebar(window), |this| {
-                this.child(gpui_component::TitleBar::new().child("Pandora"))
-            })
+            .text_size(crate::theme::base_text_size(cx))
+            .children(title_bar)
             .child(self.ui.clone())
             .children(sheet_layer)
             .children(dialog_layer)
@@ -105,8 +132,7 @@ pub fn start_new_account_login(
     window: &mut Window,
     cx: &mut App,
 ) {
-    let modal_action = ModalAction::default();
-
+    let modal_action = ModalAction::retryable(|modal_action| MessageToBackend::AddNewAccount { modal_action });
     backend_handle.send(MessageToBackend::AddNewAccount {
         modal_action: modal_action.clone(),
     });
@@ -115,6 +141,23 @@ pub fn start_new_account_login(
     modals::generic::show_modal(window, cx, title, "Error adding account".into(), modal_action);
 }
 
+// Logs into an account again after its login was revoked, the existing entry is kept and updated in place
+pub fn start_account_reauth(
+    uuid: Uuid,
+    backend_handle: &BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let modal_action = ModalAction::retryable(move |modal_action| MessageToBackend::ReauthenticateAccount { uuid, modal_action });
+    backend_handle.send(MessageToBackend::ReauthenticateAccount {
+        uuid,
+        modal_action: modal_action.clone(),
+    });
+
+    let title = SharedString::new_static("Logging in again");
+    modals::generic::show_modal(window, cx, title, "Error logging in".into(), modal_action);
+}
+
 pub fn start_instance(
     id: InstanceID,
     name: SharedString,
@@ -123,8 +166,35 @@ pub fn start_instance(
     window: &mut Window,
     cx: &mut App,
 ) {
-    let modal_action = ModalAction::default();
+    let (send, recv) = tokio::sync::oneshot::channel();
+    backend_handle.send(MessageToBackend::ValidateInstanceContent { id, channel: send });
+
+    let backend_handle = backend_handle.clone();
+    window.spawn(cx, async move |cx| {
+        let problems = recv.await.unwrap_or_default();
+        _ = cx.update(move |window, cx| {
+            if problems.is_empty() {
+                launch_instance(id, name, quick_play, &backend_handle, window, cx);
+            } else {
+                modals::content_problems::open(id, name, quick_play, problems, backend_handle, window, cx);
+            }
+        });
+    }).detach();
+}
 
+// Launches without validating the installed content first
+pub fn launch_instance(
+    id: InstanceID,
+    name: SharedString,
+    quick_play: Option<QuickPlayLaunch>,
+    backend_handle: &BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let modal_action = ModalAction::retryable({
+        let quick_play = quick_play.clone();
+        move |modal_action| MessageToBackend::StartInstance { id, quick_play: quick_play.clone(), modal_action }
+    });
     backend_handle.send(MessageToBackend::StartInstance {
         id,
         quick_play,
@@ -135,16 +205,36 @@ pub fn start_instance(
     modals::generic::show_modal(window, cx, title, "Error starting instance".into(), modal_action);
 }
 
+// Downloads everything needed to play the instance offline, without launching it
+pub fn prepare_instance(
+    id: InstanceID,
+    name: SharedString,
+    backend_handle: &BackendHandle,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let modal_action = ModalAction::retryable(move |modal_action| MessageToBackend::PrepareInstance { id, modal_action });
+    backend_handle.send(MessageToBackend::PrepareInstance {
+        id,
+        modal_action: modal_action.clone(),
+    });
+
+    let title: SharedString = format!("Downloading {}", name).into();
+    modals::generic::show_modal(window, cx, title, "Error downloading files".into(), modal_action);
+}
+
 pub fn start_install(
     content_install: ContentInstall,
     backend_handle: &BackendHandle,
     window: &mut Window,
     cx: &mut App,
 ) {
-    let modal_action = ModalAction::default();
-
+    let modal_action = ModalAction::retryable({
+        let content_install = content_install.clone();
+        move |modal_action| MessageToBackend::InstallContent { content: content_install.clone(), modal_action }
+    });
     backend_handle.send(MessageToBackend::InstallContent {
-        content: content_install.clone(),
+        content: content_install,
         modal_action: modal_action.clone(),
     });
 
@@ -157,8 +247,7 @@ pub fn start_update_check(
     window: &mut Window,
     cx: &mut App,
 ) {
-    let modal_action = ModalAction::default();
-
+    let modal_action = ModalAction::retryable(move |modal_action| MessageToBackend::UpdateCheck { instance, modal_action });
     backend_handle.send(MessageToBackend::UpdateCheck {
         instance,
         modal_action: modal_action.clone(),
@@ -175,8 +264,7 @@ pub fn update_single_mod(
     window: &mut Window,
     cx: &mut App,
 ) {
-    let modal_action = ModalAction::default();
-
+    let modal_action = ModalAction::retryable(move |modal_action| MessageToBackend::UpdateContent { instance, content_id: mod_id, modal_action });
     backend_handle.send(MessageToBackend::UpdateContent {
         instance,
         content_id: mod_id,
@@ -192,8 +280,10 @@ pub fn upload_log_file(
     window: &mut Window,
     cx: &mut App,
 ) {
-    let modal_action = ModalAction::default();
-
+    let modal_action = ModalAction::retryable({
+        let path = path.clone();
+        move |modal_action| MessageToBackend::UploadLogFile { path: path.clone(), modal_action }
+    });
     backend_handle.send(MessageToBackend::UploadLogFile {
         path,
         modal_action: modal_action.clone(),
@@ -203,12 +293,26 @@ pub fn upload_log_file(
     modals::generic::show_modal(window, cx, title, "Error uploading log file".into(), modal_action);
 }
 
+pub fn data_entities(cx: &App) -> DataEntities {
+    cx.global::<DataEntities>().clone()
+}
+
 pub fn switch_page(
     page: PageType,
     breadcrumbs: &[PageType],
     window: &mut Window,
     cx: &mut App,
 ) {
+    // Pages always open in the main window, even when requested from a popped out window
+    let main_window = cx.global::<LauncherRootGlobal>().window;
+    if window.window_handle() != main_window {
+        _ = main_window.update(cx, |_, window, cx| {
+            window.activate_window();
+            switch_page(page, breadcrumbs, window, cx);
+        });
+        return;
+    }
+
     cx.update_global::<LauncherRootGlobal, ()>(|global, cx| {
         global.root.update(cx, |launcher_root, cx| {
             launcher_root.ui.update(cx, |ui, cx| {
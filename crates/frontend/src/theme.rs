@@ -0,0 +1,183 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use gpui::{px, rems, App, Rems, SharedString, WindowAppearance};
+use gpui_component::{Theme, ThemeMode, ThemeRegistry};
+use rustc_hash::FxHashMap;
+
+use crate::{interface_config::{InterfaceConfig, ThemeModePreference, DEFAULT_FONT_SIZE}, MAIN_FONT};
+
+const THEME_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// User themes live in the themes folder as JSON or TOML. The theme registry only understands JSON, so every
+// theme is mirrored (and converted when needed) into a generated folder which the registry watches for changes
+pub fn init(theme_folder: PathBuf, generated_folder: PathBuf, cx: &mut App) {
+    _ = std::fs::create_dir_all(&theme_folder);
+    _ = std::fs::create_dir_all(&generated_folder);
+    sync_theme_files(&theme_folder, &generated_folder);
+
+    apply(cx);
+
+    _ = ThemeRegistry::watch_dir(generated_folder.clone(), cx, |cx| {
+        apply(cx);
+    });
+
+    cx.background_executor().spawn(async move {
+        let mut last_modified = theme_files_modified(&theme_folder);
+        loop {
+            gpui::Timer::after(THEME_POLL_INTERVAL).await;
+
+            let modified = theme_files_modified(&theme_folder);
+            if modified != last_modified {
+                sync_theme_files(&theme_folder, &generated_folder);
+                last_modified = modified;
+            }
+        }
+    }).detach();
+}
+
+pub fn effective_mode(cx: &App) -> ThemeMode {
+    match InterfaceConfig::get(cx).theme_mode {
+        ThemeModePreference::Dark => ThemeMode::Dark,
+        ThemeModePreference::Light => ThemeMode::Light,
+        ThemeModePreference::System => match cx.window_appearance() {
+            WindowAppearance::Light | WindowAppearance::VibrantLight => ThemeMode::Light,
+            WindowAppearance::Dark | WindowAppearance::VibrantDark => ThemeMode::Dark,
+        },
+    }
+}
+
+pub fn apply(cx: &mut App) {
+    let mode = effective_mode(cx);
+    Theme::change(mode, None, cx);
+
+    let theme = Theme::global_mut(cx);
+    theme.font_family = SharedString::new_static(MAIN_FONT);
+    theme.scrollbar_show = gpui_component::scroll::ScrollbarShow::Always;
+
+    let interface_config = InterfaceConfig::get(cx);
+    let theme_name = if mode.is_dark() {
+        interface_config.active_theme.clone()
+    } else {
+        interface_config.active_light_theme.clone()
+    };
+    let config = ThemeRegistry::global(cx).themes().get(&SharedString::new(theme_name.trim_ascii())).cloned();
+    if let Some(config) = config && config.mode == mode {
+        Theme::global_mut(cx).apply_config(&config);
+    }
+
+    // The theme font size is used as the rem size, so this scales everything sized in rems
+    let ui_scale = InterfaceConfig::get(cx).ui_scale();
+    Theme::global_mut(cx).font_size = px(DEFAULT_FONT_SIZE * ui_scale);
+
+    cx.refresh_windows();
+}
+
+// Relative to the rem size so that it follows the UI scale
+pub fn base_text_size(cx: &App) -> Rems {
+    rems(InterfaceConfig::get(cx).font_size() / DEFAULT_FONT_SIZE)
+}
+
+// Remembers the theme for the mode it was made for, so light and dark themes can be picked separately
+pub fn select(name: SharedString, cx: &mut App) {
+    let Some(config) = ThemeRegistry::global(cx).themes().get(&name).cloned() else {
+        return;
+    };
+
+    let current_mode = effective_mode(cx);
+    let interface_config = InterfaceConfig::get_mut(cx);
+    if config.mode.is_dark() {
+        interface_config.active_theme = name;
+    } else {
+        interface_config.active_light_theme = name;
+    }
+    if config.mode != current_mode {
+        interface_config.theme_mode = if config.mode.is_dark() {
+            ThemeModePreference::Dark
+        } else {
+            ThemeModePreference::Light
+        };
+    }
+
+    apply(cx);
+}
+
+fn theme_files_modified(theme_folder: &Path) -> FxHashMap<PathBuf, (SystemTime, u64)> {
+    let Ok(read_dir) = std::fs::read_dir(theme_folder) else {
+        return FxHashMap::default();
+    };
+
+    read_dir.filter_map(|entry| {
+        let entry = entry.ok()?;
+        let metadata = entry.metadata().ok()?;
+        Some((entry.path(), (metadata.modified().ok()?, metadata.len())))
+    }).collect()
+}
+
+fn sync_theme_files(theme_folder: &Path, generated_folder: &Path) {
+    let mut generated = Vec::new();
+
+    // Themes bundled with the launcher, such as the high contrast variants
+    for path in crate::Assets::iter().filter(|path| path.starts_with("themes/")) {
+        let Some(file) = crate::Assets::get(&path) else {
+            continue;
+        };
+        let Some(filename) = Path::new(path.as_ref()).file_name() else {
+            continue;
+        };
+        let target = generated_folder.join(format!("builtin-{}", filename.to_string_lossy()));
+        if std::fs::read(&target).ok().as_deref() != Some(file.data.as_ref()) {
+            _ = crate::interface_config::write_safe(&target, &file.data);
+        }
+        generated.push(target);
+    }
+
+    if let Ok(read_dir) = std::fs::read_dir(theme_folder) {
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+
+            let json = match path.extension().and_then(|extension| extension.to_str()) {
+                Some("json") => std::fs::read(&path).ok(),
+                Some("toml") => match convert_toml_theme(&path) {
+                    Ok(json) => Some(json),
+                    Err(error) => {
+                        log::error!("Unable to load theme {path:?}: {error}");
+                        None
+                    },
+                },
+                _ => None,
+            };
+            let Some(json) = json else {
+                continue;
+            };
+
+            let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or_default();
+            let target = generated_folder.join(format!("{stem}.{extension}.json"));
+            if std::fs::read(&target).ok().as_deref() != Some(json.as_slice()) {
+                _ = crate::interface_config::write_safe(&target, &json);
+            }
+            generated.push(target);
+        }
+    }
+
+    // Remove themes which were deleted from the themes folder
+    if let Ok(read_dir) = std::fs::read_dir(generated_folder) {
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            if !generated.contains(&path) {
+                _ = std::fs::remove_file(path);
+            }
+        }
+    }
+}
+
+fn convert_toml_theme(path: &Path) -> anyhow::Result<Vec<u8>> {
+    let text = std::fs::read_to_string(path)?;
+    let value: serde_json::Value = toml::from_str(&text)?;
+    Ok(serde_json::to_vec_pretty(&value)?)
+}
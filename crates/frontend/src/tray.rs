@@ -0,0 +1,241 @@
+use std::{str::FromStr, time::Duration};
+
+use global_hotkey::{hotkey::HotKey, GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+use gpui::{App, Entity, SharedString};
+use tray_icon::{
+    menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu},
+    Icon, MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent,
+};
+
+use crate::entity::instance::InstanceEntries;
+
+// Favorites are shown first, followed by the most recently played instances
+const MAX_TRAY_INSTANCES: usize = 8;
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+const OPEN_ID: &str = "open";
+const QUIT_ID: &str = "quit";
+const LAUNCH_ID_PREFIX: &str = "launch:";
+
+pub enum TrayAction {
+    OpenLauncher,
+    Launch(SharedString),
+    Quit,
+}
+
+// Returns whether the tray icon was created
+pub fn init(
+    instances: Entity<InstanceEntries>,
+    tray_enabled: bool,
+    hotkey: &str,
+    on_action: impl Fn(TrayAction, &mut App) + 'static,
+    cx: &mut App,
+) -> bool {
+    let tray = if tray_enabled {
+        TrayHandle::create()
+    } else {
+        None
+    };
+    let hotkey_manager = register_hotkey(hotkey);
+
+    if tray.is_none() && hotkey_manager.is_none() {
+        return false;
+    }
+
+    let has_tray = tray.is_some();
+
+    cx.spawn(async move |cx| {
+        // The manager unregisters the hotkey when dropped
+        let _hotkey_manager = hotkey_manager;
+        let mut instance_names: Vec<SharedString> = Vec::new();
+
+        loop {
+            gpui::Timer::after(POLL_INTERVAL).await;
+
+            let result = cx.update(|cx| {
+                if let Some(tray) = &tray {
+                    let mut entries: Vec<_> = instances.read(cx).entries.values().map(|entry| entry.read(cx)).collect();
+                    // Stable, so that the instances are still ordered by when they were last played
+                    entries.sort_by_key(|entry| !entry.configuration.favorite);
+                    let names: Vec<SharedString> = entries.into_iter()
+                        .take(MAX_TRAY_INSTANCES)
+                        .map(|entry| entry.name.clone())
+                        .collect();
+                    if names != instance_names {
+                        tray.set_instances(names.clone());
+                        instance_names = names;
+                    }
+                }
+
+                while let Ok(event) = MenuEvent::receiver().try_recv() {
+                    let id = event.id.as_ref();
+                    let action = if id == OPEN_ID {
+                        TrayAction::OpenLauncher
+                    } else if id == QUIT_ID {
+                        TrayAction::Quit
+                    } else if let Some(name) = id.strip_prefix(LAUNCH_ID_PREFIX) {
+                        TrayAction::Launch(SharedString::new(name))
+                    } else {
+                        continue;
+                    };
+                    on_action(action, cx);
+                }
+
+                while let Ok(event) = TrayIconEvent::receiver().try_recv() {
+                    if let TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. } = event {
+                        on_action(TrayAction::OpenLauncher, cx);
+                    }
+                }
+
+                while let Ok(event) = GlobalHotKeyEvent::receiver().try_recv() {
+                    if event.state == HotKeyState::Pressed {
+                        on_action(TrayAction::OpenLauncher, cx);
+                    }
+                }
+            });
+
+            if result.is_err() {
+                break;
+            }
+        }
+    }).detach();
+
+    has_tray
+}
+
+fn register_hotkey(hotkey: &str) -> Option<GlobalHotKeyManager> {
+    let hotkey = hotkey.trim();
+    if hotkey.is_empty() {
+        return None;
+    }
+
+    let hotkey = match HotKey::from_str(hotkey) {
+        Ok(hotkey) => hotkey,
+        Err(error) => {
+            log::error!("Invalid global hotkey {hotkey:?}: {error}");
+            return None;
+        },
+    };
+
+    let manager = match GlobalHotKeyManager::new() {
+        Ok(manager) => manager,
+        Err(error) => {
+            log::error!("Unable to create global hotkey manager: {error}");
+            return None;
+        },
+    };
+
+    if let Err(error) = manager.register(hotkey) {
+        log::error!("Unable to register global hotkey: {error}");
+        return None;
+    }
+
+    Some(manager)
+}
+
+fn load_icon() -> Option<Icon> {
+    let image = image::load_from_memory(include_bytes!("../../../package/windows_icons/icon_32x32.png")).ok()?.into_rgba8();
+    let (width, height) = image.dimensions();
+    Icon::from_rgba(image.into_raw(), width, height).ok()
+}
+
+fn build_menu(instance_names: &[SharedString]) -> Menu {
+    let menu = Menu::new();
+
+    let launch = Submenu::new("Launch", !instance_names.is_empty());
+    for name in instance_names {
+        _ = launch.append(&MenuItem::with_id(format!("{LAUNCH_ID_PREFIX}{name}"), name.as_str(), true, None));
+    }
+
+    _ = menu.append_items(&[
+        &MenuItem::with_id(OPEN_ID, "Open Pandora", true, None),
+        &PredefinedMenuItem::separator(),
+        &launch,
+        &PredefinedMenuItem::separator(),
+        &MenuItem::with_id(QUIT_ID, "Quit", true, None),
+    ]);
+
+    menu
+}
+
+fn build_tray(instance_names: &[SharedString]) -> Option<TrayIcon> {
+    let mut builder = TrayIconBuilder::new()
+        .with_tooltip("Pandora")
+        .with_menu(Box::new(build_menu(instance_names)));
+    if let Some(icon) = load_icon() {
+        builder = builder.with_icon(icon);
+    }
+
+    match builder.build() {
+        Ok(tray) => Some(tray),
+        Err(error) => {
+            log::error!("Unable to create tray icon: {error}");
+            None
+        },
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+struct TrayHandle {
+    tray: TrayIcon,
+}
+
+#[cfg(not(target_os = "linux"))]
+impl TrayHandle {
+    fn create() -> Option<Self> {
+        Some(Self { tray: build_tray(&[])? })
+    }
+
+    fn set_instances(&self, instance_names: Vec<SharedString>) {
+        self.tray.set_menu(Some(Box::new(build_menu(&instance_names))));
+    }
+}
+
+// On Linux the tray is implemented with GTK, which needs to run its own event loop on the thread that owns the tray
+#[cfg(target_os = "linux")]
+struct TrayHandle {
+    sender: std::sync::mpsc::Sender<Vec<SharedString>>,
+}
+
+#[cfg(target_os = "linux")]
+impl TrayHandle {
+    fn create() -> Option<Self> {
+        let (sender, receiver) = std::sync::mpsc::channel::<Vec<SharedString>>();
+        let (created_sender, created_receiver) = std::sync::mpsc::channel();
+
+        std::thread::Builder::new().name("tray".into()).spawn(move || {
+            if let Err(error) = gtk::init() {
+                log::error!("Unable to initialize GTK for the tray icon: {error}");
+                _ = created_sender.send(false);
+                return;
+            }
+
+            let Some(tray) = build_tray(&[]) else {
+                _ = created_sender.send(false);
+                return;
+            };
+            _ = created_sender.send(true);
+
+            gtk::glib::timeout_add_local(POLL_INTERVAL, move || {
+                loop {
+                    match receiver.try_recv() {
+                        Ok(instance_names) => tray.set_menu(Some(Box::new(build_menu(&instance_names)))),
+                        Err(std::sync::mpsc::TryRecvError::Empty) => return gtk::glib::ControlFlow::Continue,
+                        Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                            gtk::main_quit();
+                            return gtk::glib::ControlFlow::Break;
+                        },
+                    }
+                }
+            });
+
+            gtk::main();
+        }).ok()?;
+
+        created_receiver.recv().unwrap_or(false).then_some(Self { sender })
+    }
+
+    fn set_instances(&self, instance_names: Vec<SharedString>) {
+        _ = self.sender.send(instance_names);
+    }
+}
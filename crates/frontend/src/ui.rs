@@ -1,19 +1,20 @@
 use std::sync::Arc;
 
-use bridge::{instance::InstanceID, message::MessageToBackend};
+use bridge::{account::Account, instance::InstanceID, message::MessageToBackend};
 use gpui::{prelude::*, *};
 use gpui_component::{
-    ActiveTheme as _, Disableable, Icon, IconName, WindowExt, button::{Button, ButtonVariants}, h_flex, input::{Input, InputState}, resizable::{ResizablePanelEvent, ResizableState, h_resizable, resizable_panel}, scroll::ScrollableElement, sidebar::SidebarFooter, v_flex
+    ActiveTheme as _, Disableable, Icon, IconName, WindowExt, button::{Button, ButtonVariants}, h_flex, input::{Input, InputState}, resizable::{ResizablePanelEvent, ResizableState, h_resizable, resizable_panel}, scroll::ScrollableElement, sidebar::SidebarFooter, tooltip::Tooltip, v_flex
 };
 use rand::Rng;
 use schema::modrinth::ModrinthProjectType;
 use serde::{Deserialize, Serialize};
+use ustr::Ustr;
 use uuid::Uuid;
 
 use crate::{
     component::{menu::{MenuGroup, MenuGroupItem}, page_path::PagePath}, entity::{
         DataEntities, instance::{InstanceAddedEvent, InstanceEntries, InstanceModifiedEvent, InstanceMovedToTopEvent, InstanceRemovedEvent}
-    }, interface_config::InterfaceConfig, modals, pages::{instance::instance_page::{InstancePage, InstanceSubpageType}, instances_page::InstancesPage, modrinth_page::ModrinthSearchPage, syncing_page::SyncingPage}, png_render_cache, root
+    }, interface_config::InterfaceConfig, keymap::{FocusSearch, OpenSettings}, modals, pages::{instance::instance_page::{InstancePage, InstanceSubpageType}, instances_page::InstancesPage, modrinth_page::ModrinthSearchPage, modrinth_project_page::ModrinthProjectPage, statistics_page::StatisticsPage, syncing_page::SyncingPage}, png_render_cache, relaunch::RelaunchState, root
 };
 
 pub struct LauncherUI {
@@ -26,16 +27,22 @@ pub struct LauncherUI {
     _instance_modified_subscription: Subscription,
     _instance_removed_subscription: Subscription,
     _instance_moved_to_top_subscription: Subscription,
+    _background_jobs_subscription: Subscription,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum PageType {
     Instances,
+    Statistics,
     Syncing,
     Modrinth {
         installing_for: Option<InstanceID>,
         project_type: Option<ModrinthProjectType>,
     },
+    ModrinthProject {
+        project_id: Ustr,
+        installing_for: Option<InstanceID>,
+    },
     InstancePage(InstanceID, InstanceSubpageType),
 }
 
@@ -43,18 +50,23 @@ impl PageType {
     fn to_serialized(&self, data: &DataEntities, cx: &App) -> SerializedPageType {
         match self {
             PageType::Instances => SerializedPageType::Instances,
+            PageType::Statistics => SerializedPageType::Statistics,
             PageType::Syncing => SerializedPageType::Syncing,
             PageType::Modrinth { installing_for, .. } => {
                 if let Some(installing_for) = installing_for {
-                    if let Some(name) = InstanceEntries::find_name_by_id(&data.instances, *installing_for, cx) {
-                        return SerializedPageType::Modrinth { installing_for: Some(name) };
+                    if let Some(reference) = InstanceEntries::find_reference_by_id(&data.instances, *installing_for, cx) {
+                        return SerializedPageType::Modrinth { installing_for: Some(reference) };
                     }
                 }
                 SerializedPageType::Modrinth { installing_for: None }
             },
+            PageType::ModrinthProject { project_id, installing_for } => {
+                let installing_for = installing_for.and_then(|id| InstanceEntries::find_reference_by_id(&data.instances, id, cx));
+                SerializedPageType::ModrinthProject { project_id: project_id.as_str().into(), installing_for }
+            },
             PageType::InstancePage(id, _) => {
-                if let Some(name) = InstanceEntries::find_name_by_id(&data.instances, *id, cx) {
-                    SerializedPageType::InstancePage(name)
+                if let Some(reference) = InstanceEntries::find_reference_by_id(&data.instances, *id, cx) {
+                    SerializedPageType::InstancePage(reference)
                 } else {
                     SerializedPageType::Instances
                 }
@@ -65,17 +77,22 @@ impl PageType {
     fn from_serialized(serialized: &SerializedPageType, data: &DataEntities, cx: &App) -> Self {
         match serialized {
             SerializedPageType::Instances => PageType::Instances,
+            SerializedPageType::Statistics => PageType::Statistics,
             SerializedPageType::Syncing => PageType::Syncing,
             SerializedPageType::Modrinth { installing_for } => {
                 if let Some(installing_for) = installing_for {
-                    if let Some(id) = InstanceEntries::find_id_by_name(&data.instances, installing_for, cx) {
+                    if let Some(id) = InstanceEntries::find_id_by_reference(&data.instances, installing_for, cx) {
                         return PageType::Modrinth { installing_for: Some(id), project_type: None };
                     }
                 }
                 PageType::Modrinth { installing_for: None, project_type: None }
             },
-            SerializedPageType::InstancePage(name) => {
-                if let Some(id) = InstanceEntries::find_id_by_name(&data.instances, name, cx) {
+            SerializedPageType::ModrinthProject { project_id, installing_for } => {
+                let installing_for = installing_for.as_ref().and_then(|reference| InstanceEntries::find_id_by_reference(&data.instances, reference, cx));
+                PageType::ModrinthProject { project_id: project_id.as_str().into(), installing_for }
+            },
+            SerializedPageType::InstancePage(reference) => {
+                if let Some(id) = InstanceEntries::find_id_by_reference(&data.instances, reference, cx) {
                     PageType::InstancePage(id, InstanceSubpageType::Quickplay)
                 } else {
                     PageType::Instances
@@ -90,21 +107,32 @@ impl PageType {
 pub enum SerializedPageType {
     #[default]
     Instances,
+    Statistics,
     Syncing,
     Modrinth {
         installing_for: Option<SharedString>,
     },
+    ModrinthProject {
+        project_id: SharedString,
+        installing_for: Option<SharedString>,
+    },
     InstancePage(SharedString),
 }
 
 #[derive(Clone)]
 pub enum LauncherPage {
     Instances(Entity<InstancesPage>),
+    Statistics(Entity<StatisticsPage>),
     Syncing(Entity<SyncingPage>),
     Modrinth {
         installing_for: Option<InstanceID>,
         page: Entity<ModrinthSearchPage>,
     },
+    ModrinthProject {
+        project_id: Ustr,
+        installing_for: Option<InstanceID>,
+        page: Entity<ModrinthProjectPage>,
+    },
     InstancePage(InstanceID, InstanceSubpageType, Entity<InstancePage>),
 }
 
@@ -112,23 +140,47 @@ impl LauncherPage {
     pub fn into_any_element(self) -> AnyElement {
         match self {
             LauncherPage::Instances(entity) => entity.into_any_element(),
+            LauncherPage::Statistics(entity) => entity.into_any_element(),
             LauncherPage::Syncing(entity) => entity.into_any_element(),
             LauncherPage::Modrinth { page, .. } => page.into_any_element(),
+            LauncherPage::ModrinthProject { page, .. } => page.into_any_element(),
             LauncherPage::InstancePage(_, _, entity) => entity.into_any_element(),
         }
     }
 
+    pub fn focus_search(&self, window: &mut Window, cx: &mut App) {
+        match self {
+            LauncherPage::Instances(entity) => entity.update(cx, |page, cx| page.focus_search(window, cx)),
+            LauncherPage::Modrinth { page, .. } => page.update(cx, |page, cx| page.focus_search(window, cx)),
+            _ => {},
+        }
+    }
+
     pub fn page_type(&self) -> PageType {
         match self {
             LauncherPage::Instances(_) => PageType::Instances,
+            LauncherPage::Statistics(_) => PageType::Statistics,
             LauncherPage::Syncing(_) => PageType::Syncing,
             LauncherPage::Modrinth { installing_for, .. } => PageType::Modrinth { installing_for: *installing_for, project_type: None },
+            LauncherPage::ModrinthProject { project_id, installing_for, .. } => PageType::ModrinthProject { project_id: *project_id, installing_for: *installing_for },
             LauncherPage::InstancePage(id, subpage, _) => PageType::InstancePage(*id, *subpage),
         }
     }
 }
 
 impl LauncherUI {
+    pub fn data(&self) -> &DataEntities {
+        &self.data
+    }
+
+    pub fn instance_subpage(&self, cx: &App) -> Option<InstanceSubpageType> {
+        if let LauncherPage::InstancePage(_, _, page) = &self.page {
+            Some(page.read(cx).subpage_type())
+        } else {
+            None
+        }
+    }
+
     pub fn new(data: &DataEntities, window: &mut Window, cx: &mut Context<Self>) -> Self {
         let sidebar_state = cx.new(|_| ResizableState::default());
 
@@ -188,8 +240,15 @@ impl LauncherUI {
                 cx.notify();
             });
 
+        let _background_jobs_subscription = cx.observe(&data.background_jobs, |_, _, cx| cx.notify());
+
         let config = InterfaceConfig::get(cx);
-        let page_type = PageType::from_serialized(&config.main_page, data, cx);
+        let mut page_type = PageType::from_serialized(&config.main_page, data, cx);
+        if let PageType::InstancePage(_, subpage) = &mut page_type
+            && let Some(relaunch_subpage) = cx.try_global::<RelaunchState>().and_then(|state| state.instance_subpage)
+        {
+            *subpage = relaunch_subpage;
+        }
         let page_path: Vec<PageType> = config.page_path.iter().map(|page| PageType::from_serialized(page, data, cx)).collect();
 
         let mut default_sidebar_width = InterfaceConfig::get(cx).sidebar_width;
@@ -207,6 +266,7 @@ impl LauncherUI {
             _instance_modified_subscription,
             _instance_removed_subscription,
             _instance_moved_to_top_subscription,
+            _background_jobs_subscription,
         }
     }
 
@@ -216,6 +276,9 @@ impl LauncherUI {
             PageType::Instances => {
                 LauncherPage::Instances(cx.new(|cx| InstancesPage::new(data, window, cx)))
             },
+            PageType::Statistics => {
+                LauncherPage::Statistics(cx.new(|cx| StatisticsPage::new(data, window, cx)))
+            },
             PageType::Syncing => {
                 LauncherPage::Syncing(cx.new(|cx| SyncingPage::new(data, window, cx)))
             },
@@ -228,6 +291,16 @@ impl LauncherUI {
                     page,
                 }
             },
+            PageType::ModrinthProject { project_id, installing_for } => {
+                let page = cx.new(|cx| {
+                    ModrinthProjectPage::new(project_id, installing_for, path, data, window, cx)
+                });
+                LauncherPage::ModrinthProject {
+                    project_id,
+                    installing_for,
+                    page,
+                }
+            },
             PageType::InstancePage(id, subpage) => {
                 LauncherPage::InstancePage(id, subpage, cx.new(|cx| {
                     InstancePage::new(id, subpage, path, data, window, cx)
@@ -236,6 +309,11 @@ impl LauncherUI {
         }
     }
 
+    fn open_settings(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let build = modals::settings::build_settings_sheet(&self.data, window, cx);
+        window.open_sheet_at(gpui_component::Placement::Left, cx, build);
+    }
+
     pub fn switch_page(&mut self, page: PageType, breadcrumbs: &[PageType], window: &mut Window, cx: &mut Context<Self>) {
         if self.page.page_type() == page {
             return;
@@ -261,6 +339,11 @@ impl Render for LauncherUI {
                 .active(page_type == PageType::Instances)
                 .on_click(cx.listener(|launcher, _, window, cx| {
                     launcher.switch_page(PageType::Instances, &[], window, cx);
+                })))
+            .child(MenuGroupItem::new("Statistics")
+                .active(page_type == PageType::Statistics)
+                .on_click(cx.listener(|launcher, _, window, cx| {
+                    launcher.switch_page(PageType::Statistics, &[], window, cx);
                 })));
 
         let launcher_group = MenuGroup::new("Content")
@@ -303,20 +386,10 @@ impl Render for LauncherUI {
         }
 
         let accounts = self.data.accounts.read(cx);
-        let (account_head, account_name) = if let Some(account) = &accounts.selected_account {
-            let account_name = SharedString::new(account.username.clone());
-            let head = if let Some(head) = &account.head {
-                let resize = png_render_cache::ImageTransformation::Resize { width: 32, height: 32 };
-                png_render_cache::render_with_transform(Arc::clone(head), resize, cx)
-            } else {
-                gpui::img(ImageSource::Resource(Resource::Embedded("images/default_head.png".into())))
-            };
-            (head, account_name)
+        let (selected_head, account_name, needs_reauth) = if let Some(account) = accounts.selected_account.clone() {
+            (account_head(Some(&account), cx), SharedString::new(account.username.clone()), account.needs_reauth)
         } else {
-            (
-                gpui::img(ImageSource::Resource(Resource::Embedded("images/default_head.png".into()))),
-                "No Account".into(),
-            )
+            (account_head(None, cx), "No Account".into(), false)
         };
 
         let pandora_icon = Icon::empty().path("icons/pandora.svg");
@@ -325,8 +398,11 @@ impl Render for LauncherUI {
             .w_full()
             .justify_center()
             .text_size(rems(0.9375))
-            .child(account_head.size_8().min_w_8().min_h_8())
-            .child(account_name))
+            .child(selected_head.size_8().min_w_8().min_h_8())
+            .child(account_name)
+            .when(needs_reauth, |this| {
+                this.child(Icon::new(IconName::TriangleAlert).text_color(cx.theme().warning))
+            }))
             .on_click({
                 let accounts = self.data.accounts.clone();
                 let backend_handle = self.data.backend_handle.clone();
@@ -339,20 +415,15 @@ impl Render for LauncherUI {
                     let accounts = accounts.clone();
                     let backend_handle = backend_handle.clone();
                     window.open_sheet_at(gpui_component::Placement::Left, cx, move |sheet, window, cx| {
-                        let (accounts, selected_account) = {
+                        let (accounts, selected_account, modrinth_account) = {
                             let accounts = accounts.read(cx);
-                            (accounts.accounts.clone(), accounts.selected_account_uuid)
+                            (accounts.accounts.clone(), accounts.selected_account_uuid, accounts.modrinth_account.clone())
                         };
 
                         let trash_icon = Icon::default().path("icons/trash-2.svg");
 
                         let items = accounts.iter().map(|account| {
-                            let head = if let Some(head) = &account.head {
-                                let resize = png_render_cache::ImageTransformation::Resize { width: 32, height: 32 };
-                                png_render_cache::render_with_transform(Arc::clone(head), resize, cx)
-                            } else {
-                                gpui::img(ImageSource::Resource(Resource::Embedded("images/default_head.png".into())))
-                            };
+                            let head = account_head(Some(account), cx);
                             let account_name = SharedString::new(account.username.clone());
 
                             let selected = Some(account.uuid) == selected_account;
@@ -377,6 +448,21 @@ impl Render for LauncherUI {
                                             }
                                         })
                                     }))
+                                .when(account.needs_reauth, |this| {
+                                    this.child(Button::new((account_name.clone(), 2))
+                                        .icon(IconName::TriangleAlert)
+                                        .label("Log in again")
+                                        .tooltip("The login for this account has expired")
+                                        .h_10()
+                                        .warning()
+                                        .on_click({
+                                            let backend_handle = backend_handle.clone();
+                                            let uuid = account.uuid;
+                                            move |_, window, cx| {
+                                                crate::root::start_account_reauth(uuid, &backend_handle, window, cx);
+                                            }
+                                        }))
+                                })
                                 .child(Button::new((account_name.clone(), 1))
                                     .icon(trash_icon.clone())
                                     .h_10()
@@ -455,6 +541,7 @@ impl Render for LauncherUI {
                                     }
                                 }))
                                 .children(items)
+                                .child(modals::modrinth_account::render_account_section(modrinth_account, &backend_handle, cx))
                             )
 
                     });
@@ -471,11 +558,31 @@ impl Render for LauncherUI {
                     .text_color(cx.theme().sidebar_accent_foreground)
             })
             .child(IconName::Settings)
+            .tooltip(|window, cx| Tooltip::new("Settings").build(window, cx))
+            .on_click(cx.listener(|this, _, window, cx| {
+                this.open_settings(window, cx);
+            }));
+
+        let job_count = self.data.background_jobs.read(cx).jobs.len();
+        let jobs_button = div()
+            .id("background-jobs-button")
+            .flex()
+            .items_center()
+            .gap_1()
+            .p_2()
+            .rounded(cx.theme().radius)
+            .hover(|this| {
+                this.bg(cx.theme().sidebar_accent)
+                    .text_color(cx.theme().sidebar_accent_foreground)
+            })
+            .when(job_count == 0, |this| this.text_color(cx.theme().muted_foreground))
+            .child(IconName::Loader)
+            .when(job_count > 0, |this| this.child(job_count.to_string()))
+            .tooltip(|window, cx| Tooltip::new("Background tasks").build(window, cx))
             .on_click({
-                let data = self.data.clone();
+                let background_jobs = self.data.background_jobs.clone();
                 move |_, window, cx| {
-                    let build = modals::settings::build_settings_sheet(&data, window, cx);
-                    window.open_sheet_at(gpui_component::Placement::Left, cx, build);
+                    modals::background_jobs::open_background_jobs(background_jobs.clone(), window, cx);
                 }
             });
 
@@ -489,7 +596,7 @@ impl Render for LauncherUI {
             .text_size(rems(0.9375))
             .child(pandora_icon.size_8().min_w_8().min_h_8())
             .child("Pandora");
-        let footer = h_flex().pb_3().px_3().flex_wrap().justify_center().w_full().child(settings_button).child(account_button);
+        let footer = h_flex().pb_3().px_3().flex_wrap().justify_center().w_full().child(settings_button).child(jobs_button).child(account_button);
         let sidebar = v_flex()
             .w_full()
             .bg(cx.theme().sidebar)
@@ -504,10 +611,40 @@ impl Render for LauncherUI {
                 .overflow_y_scrollbar())
             .child(footer);
 
-        h_resizable("container")
+        let container = h_resizable("container")
             .with_state(&self.sidebar_state)
             .child(resizable_panel().size(px(self.default_sidebar_width)).size_range(px(130.)..px(200.)).child(sidebar))
-            .child(self.page.clone().into_any_element())
+            .child(self.page.clone().into_any_element());
+
+        div()
+            .size_full()
+            .on_action(cx.listener(|this, _: &OpenSettings, window, cx| {
+                this.open_settings(window, cx);
+            }))
+            .on_action(cx.listener(|this, _: &FocusSearch, window, cx| {
+                this.page.focus_search(window, cx);
+            }))
+            .drag_over::<ExternalPaths>(|style, _, _, cx| style.bg(cx.theme().drop_target))
+            .on_drop(cx.listener(|this, paths: &ExternalPaths, window, cx| {
+                // Content is added to the instance which is open, if any
+                let current_instance = match this.page.page_type() {
+                    PageType::InstancePage(id, _) => Some(id),
+                    PageType::Modrinth { installing_for, .. } | PageType::ModrinthProject { installing_for, .. } => installing_for,
+                    PageType::Instances | PageType::Statistics | PageType::Syncing => None,
+                };
+                modals::import_files::open(paths.paths().to_vec(), current_instance, &this.data, window, cx);
+            }))
+            .child(container)
+    }
+}
+
+// The player head of the account, or a default head for accounts without a skin
+pub fn account_head(account: Option<&Account>, cx: &mut App) -> gpui::Img {
+    if let Some(head) = account.and_then(|account| account.head.as_ref()) {
+        let resize = png_render_cache::ImageTransformation::Resize { width: 32, height: 32 };
+        png_render_cache::render_with_transform(Arc::clone(head), resize, cx)
+    } else {
+        gpui::img(ImageSource::Resource(Resource::Embedded("images/default_head.png".into())))
     }
 }
 
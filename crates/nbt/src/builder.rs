@@ -0,0 +1,107 @@
+use crate::{CompoundRefMut, ListRefMut};
+
+// Values that can be placed directly into a compound or list, used by the nbt! macro
+pub trait NBTValue {
+    fn insert_into_compound(self, compound: &mut CompoundRefMut<'_>, key: &str);
+    fn insert_into_list(self, list: &mut ListRefMut<'_>);
+}
+
+macro_rules! impl_value {
+    ($name:ident, $value_type:ty, $node:ident) => {
+        paste::paste! {
+            impl NBTValue for $value_type {
+                fn insert_into_compound(self, compound: &mut CompoundRefMut<'_>, key: &str) {
+                    compound.[<insert_ $name>](key, self);
+                }
+
+                fn insert_into_list(self, list: &mut ListRefMut<'_>) {
+                    list.[<insert_ $name>](self);
+                }
+            }
+        }
+    };
+}
+
+super::enumerate_basic_types!(impl_value);
+
+impl NBTValue for &str {
+    fn insert_into_compound(self, compound: &mut CompoundRefMut<'_>, key: &str) {
+        compound.insert_string(key, self.to_string());
+    }
+
+    fn insert_into_list(self, list: &mut ListRefMut<'_>) {
+        list.insert_string(self.to_string());
+    }
+}
+
+// Minecraft has no boolean tag, booleans are stored as bytes
+impl NBTValue for bool {
+    fn insert_into_compound(self, compound: &mut CompoundRefMut<'_>, key: &str) {
+        compound.insert_byte(key, self as i8);
+    }
+
+    fn insert_into_list(self, list: &mut ListRefMut<'_>) {
+        list.insert_byte(self as i8);
+    }
+}
+
+// Builds an NBT tree from a json-like description, e.g.
+// nbt!({ "Data": { "LevelName": "world", "version": 19133, "DataPacks": { "Enabled": ["vanilla"] } } })
+// Unsuffixed integers are ints and unsuffixed floats are doubles like in Rust, use suffixes (1i8, 0.5f32) for other
+// types. Lists take the type of their first element
+#[macro_export]
+macro_rules! nbt {
+    ({ $($body:tt)* }) => {{
+        let mut nbt = $crate::NBT::new();
+        {
+            #[allow(unused_mut, unused_variables)]
+            let mut compound = nbt.as_compound_mut().unwrap();
+            $crate::nbt!(@compound compound; $($body)*);
+        }
+        nbt
+    }};
+
+    (@compound $compound:ident;) => {};
+    (@compound $compound:ident; $key:literal : { $($inner:tt)* } $(, $($rest:tt)*)?) => {
+        {
+            #[allow(unused_mut, unused_variables)]
+            let mut inner = $compound.create_compound($key);
+            $crate::nbt!(@compound inner; $($inner)*);
+        }
+        $crate::nbt!(@compound $compound; $($($rest)*)?);
+    };
+    (@compound $compound:ident; $key:literal : [ $($inner:tt)* ] $(, $($rest:tt)*)?) => {
+        {
+            #[allow(unused_mut, unused_variables)]
+            let mut inner = $compound.create_list($key, $crate::TAG_END_ID);
+            $crate::nbt!(@list inner; $($inner)*);
+        }
+        $crate::nbt!(@compound $compound; $($($rest)*)?);
+    };
+    (@compound $compound:ident; $key:literal : $value:expr $(, $($rest:tt)*)?) => {
+        $crate::NBTValue::insert_into_compound($value, &mut $compound, $key);
+        $crate::nbt!(@compound $compound; $($($rest)*)?);
+    };
+
+    (@list $list:ident;) => {};
+    (@list $list:ident; { $($inner:tt)* } $(, $($rest:tt)*)?) => {
+        {
+            #[allow(unused_mut, unused_variables)]
+            let mut inner = $list.create_compound();
+            $crate::nbt!(@compound inner; $($inner)*);
+        }
+        $crate::nbt!(@list $list; $($($rest)*)?);
+    };
+    (@list $list:ident; [ $($inner:tt)* ] $(, $($rest:tt)*)?) => {
+        {
+            #[allow(unused_mut, unused_variables)]
+            let mut inner = $list.create_list($crate::TAG_END_ID);
+            $crate::nbt!(@list inner; $($inner)*);
+        }
+        $crate::nbt!(@list $list; $($($rest)*)?);
+    };
+    (@list $list:ident; $value:expr $(, $($rest:tt)*)?) => {
+        $crate::NBTValue::insert_into_list($value, &mut $list);
+        $crate::nbt!(@list $list; $($($rest)*)?);
+    };
+}
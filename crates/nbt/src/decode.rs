@@ -1,120 +1,230 @@
 use std::borrow::Cow;
 
 use super::*;
-use anyhow::bail;
 use byteorder::{BigEndian, ByteOrder, ReadBytesExt};
 
 const DECODE_CAPACITY: usize = 2_097_152;
 
+// Limits applied while decoding. The size is an estimate of the memory used by the decoded tree, like the game's
+// NbtAccounter, so that a small file can't expand into a huge allocation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeLimits {
+    // Compounds and lists nested inside the root
+    pub max_depth: usize,
+    pub max_nodes: usize,
+    pub max_string_length: usize,
+    pub max_array_length: usize,
+    pub max_size: usize,
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 512,
+            max_nodes: usize::MAX,
+            max_string_length: u16::MAX as usize,
+            max_array_length: usize::MAX,
+            max_size: DECODE_CAPACITY,
+        }
+    }
+}
+
+impl DecodeLimits {
+    // For NBT which came from somewhere else, e.g. imported worlds or files sent by a server
+    pub fn untrusted() -> Self {
+        Self {
+            max_depth: 512,
+            max_nodes: 262_144,
+            max_string_length: 32_767,
+            max_array_length: 1_048_576,
+            max_size: DECODE_CAPACITY,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum DecodeError {
+    #[error("unexpected end of data")]
+    UnexpectedEof,
+    #[error("root must be a compound, got type id {0}")]
+    InvalidRootType(u8),
+    #[error("unknown type id {0}")]
+    UnknownType(u8),
+    #[error("nesting is deeper than the limit of {0}")]
+    TooDeep(usize),
+    #[error("more than {0} tags")]
+    TooManyNodes(usize),
+    #[error("string of length {length} is longer than the limit of {max}")]
+    StringTooLong { length: usize, max: usize },
+    #[error("array of length {length} is longer than the limit of {max}")]
+    ArrayTooLong { length: usize, max: usize },
+    #[error("nbt is larger than the limit of {0} bytes")]
+    TooLarge(usize),
+    #[error("length cannot be negative")]
+    NegativeLength,
+    #[error("list of TAG_End cannot have elements")]
+    EndList,
+    #[error("duplicate key {0:?} in compound")]
+    DuplicateKey(String),
+    #[error("invalid string: {0}")]
+    InvalidString(#[from] cesu8::Cesu8DecodingError),
+}
+
+impl From<std::io::Error> for DecodeError {
+    fn from(_: std::io::Error) -> Self {
+        // Reading from a slice can only fail by running out of bytes
+        Self::UnexpectedEof
+    }
+}
+
+struct Decoder<'l> {
+    limits: &'l DecodeLimits,
+    nodes: Slab<NBTNode>,
+    size: usize,
+}
+
+impl Decoder<'_> {
+    fn add_size(&mut self, size: usize) -> Result<(), DecodeError> {
+        self.size = self.size.saturating_add(size);
+        if self.size > self.limits.max_size {
+            return Err(DecodeError::TooLarge(self.limits.max_size));
+        }
+        Ok(())
+    }
+
+    fn insert(&mut self, node: NBTNode) -> Result<usize, DecodeError> {
+        if self.nodes.len() >= self.limits.max_nodes {
+            return Err(DecodeError::TooManyNodes(self.limits.max_nodes));
+        }
+        Ok(self.nodes.insert(node))
+    }
+
+    fn check_array_length(&self, length: usize) -> Result<(), DecodeError> {
+        if length > self.limits.max_array_length {
+            return Err(DecodeError::ArrayTooLong { length, max: self.limits.max_array_length });
+        }
+        Ok(())
+    }
+}
+
 pub fn read_protocol(bytes: &mut &[u8]) -> anyhow::Result<NBT> {
+    Ok(read_protocol_with_limits(bytes, &DecodeLimits::default())?)
+}
+
+pub fn read_named(bytes: &mut &[u8]) -> anyhow::Result<NBT> {
+    Ok(read_named_with_limits(bytes, &DecodeLimits::default())?)
+}
+
+pub fn read_protocol_with_limits(bytes: &mut &[u8], limits: &DecodeLimits) -> Result<NBT, DecodeError> {
     let type_id: u8 = bytes.read_u8()?;
     if type_id == TAG_END_ID.0 {
         return Ok(NBT::new());
     }
 
-    let mut size = 0;
-
-    let mut nodes = Slab::new();
-    let root_index = read_node(bytes, &mut nodes, type_id, 0, &mut size)?;
+    let mut decoder = Decoder {
+        limits,
+        nodes: Slab::new(),
+        size: 0,
+    };
+    let root_index = read_node(bytes, &mut decoder, type_id, 0)?;
 
     Ok(NBT {
         root_name: String::new(),
         root_index,
-        nodes,
+        nodes: decoder.nodes,
     })
 }
 
-pub fn read_named(bytes: &mut &[u8]) -> anyhow::Result<NBT> {
+pub fn read_named_with_limits(bytes: &mut &[u8], limits: &DecodeLimits) -> Result<NBT, DecodeError> {
     let type_id: u8 = bytes.read_u8()?;
     if type_id == TAG_END_ID.0 {
         return Ok(NBT::new());
     } else if type_id != TAG_COMPOUND_ID.0 {
-        bail!("nbt_decode: root must be a compound, got type_id = {type_id}");
+        return Err(DecodeError::InvalidRootType(type_id));
     }
 
-    let mut size = 0;
-
-    let mut nodes = Slab::new();
-    let name = read_string(bytes, &mut size)?;
-    let children = read_compound(bytes, &mut nodes, 0, &mut size)?;
-    let root_index = nodes.insert(NBTNode::Compound(children));
+    let mut decoder = Decoder {
+        limits,
+        nodes: Slab::new(),
+        size: 0,
+    };
+    let name = read_string(bytes, &mut decoder)?;
+    let children = read_compound(bytes, &mut decoder, 0)?;
+    let root_index = decoder.insert(NBTNode::Compound(children))?;
 
     Ok(NBT {
         root_name: name.into_owned(),
         root_index,
-        nodes,
+        nodes: decoder.nodes,
     })
 }
 
 #[inline]
 fn read_node(
     bytes: &mut &[u8],
-    nodes: &mut Slab<NBTNode>,
+    decoder: &mut Decoder,
     type_id: u8,
     depth: usize,
-    size: &mut usize,
-) -> anyhow::Result<usize> {
+) -> Result<usize, DecodeError> {
     debug_assert!(type_id != TAG_END_ID.0, "read_node must not be called with TAG_END");
 
     let node = match TagType(type_id) {
         TAG_BYTE_ID => {
-            *size += 1;
+            decoder.add_size(1)?;
             NBTNode::Byte(bytes.read_i8()?)
         },
         TAG_SHORT_ID => {
-            *size += 2;
+            decoder.add_size(2)?;
             NBTNode::Short(bytes.read_i16::<BigEndian>()?)
         },
         TAG_INT_ID => {
-            *size += 4;
+            decoder.add_size(4)?;
             NBTNode::Int(bytes.read_i32::<BigEndian>()?)
         },
         TAG_LONG_ID => {
-            *size += 8;
+            decoder.add_size(8)?;
             NBTNode::Long(bytes.read_i64::<BigEndian>()?)
         },
         TAG_FLOAT_ID => {
-            *size += 4;
+            decoder.add_size(4)?;
             NBTNode::Float(bytes.read_f32::<BigEndian>()?)
         },
         TAG_DOUBLE_ID => {
-            *size += 8;
+            decoder.add_size(8)?;
             NBTNode::Double(bytes.read_f64::<BigEndian>()?)
         },
-        TAG_BYTE_ARRAY_ID => NBTNode::ByteArray(read_byte_array(bytes, size)?),
-        TAG_STRING_ID => NBTNode::String(read_string(bytes, size)?.into_owned()),
+        TAG_BYTE_ARRAY_ID => NBTNode::ByteArray(read_byte_array(bytes, decoder)?),
+        TAG_STRING_ID => NBTNode::String(read_string(bytes, decoder)?.into_owned()),
         TAG_LIST_ID => {
-            if depth > 512 {
-                bail!("tried to read NBT tag with too high complexity, depth > 512")
+            if depth >= decoder.limits.max_depth {
+                return Err(DecodeError::TooDeep(decoder.limits.max_depth));
             }
 
-            let (type_id, children) = read_list(bytes, nodes, depth + 1, size)?;
+            let (type_id, children) = read_list(bytes, decoder, depth + 1)?;
             NBTNode::List {
                 type_id: TagType(type_id),
                 children,
             }
         },
         TAG_COMPOUND_ID => {
-            if depth > 512 {
-                bail!("tried to read NBT tag with too high complexity, depth > 512")
+            if depth >= decoder.limits.max_depth {
+                return Err(DecodeError::TooDeep(decoder.limits.max_depth));
             }
 
-            NBTNode::Compound(read_compound(bytes, nodes, depth + 1, size)?)
+            NBTNode::Compound(read_compound(bytes, decoder, depth + 1)?)
         },
-        TAG_INT_ARRAY_ID => NBTNode::IntArray(read_int_array(bytes, size)?),
-        TAG_LONG_ARRAY_ID => NBTNode::LongArray(read_long_array(bytes, size)?),
-        _ => bail!("unknown type id: {}", type_id),
+        TAG_INT_ARRAY_ID => NBTNode::IntArray(read_int_array(bytes, decoder)?),
+        TAG_LONG_ARRAY_ID => NBTNode::LongArray(read_long_array(bytes, decoder)?),
+        _ => return Err(DecodeError::UnknownType(type_id)),
     };
-    let idx = nodes.insert(node);
-    Ok(idx)
+    decoder.insert(node)
 }
 
 fn read_compound(
     bytes: &mut &[u8],
-    nodes: &mut Slab<NBTNode>,
+    decoder: &mut Decoder,
     depth: usize,
-    size: &mut usize,
-) -> anyhow::Result<NBTCompound> {
+) -> Result<NBTCompound, DecodeError> {
     let mut children = NBTCompound(Vec::new());
 
     loop {
@@ -122,13 +232,13 @@ fn read_compound(
         if type_id == TAG_END_ID.0 {
             break Ok(children);
         } else {
-            *size += 8;
+            decoder.add_size(8)?;
 
-            let name = read_string(bytes, size)?;
-            let node = read_node(bytes, nodes, type_id, depth, size)?;
+            let name = read_string(bytes, decoder)?;
+            let node = read_node(bytes, decoder, type_id, depth)?;
 
             match children.binary_search(name.as_ref()) {
-                Ok(_) => bail!("read_compound: duplicate key"),
+                Ok(_) => return Err(DecodeError::DuplicateKey(name.into_owned())),
                 Err(index) => {
                     children.0.insert(index, (name.into(), node));
                 },
@@ -138,19 +248,17 @@ fn read_compound(
 }
 
 #[inline]
-fn read_byte_array(bytes: &mut &[u8], size: &mut usize) -> anyhow::Result<Vec<i8>> {
+fn read_byte_array(bytes: &mut &[u8], decoder: &mut Decoder) -> Result<Vec<i8>, DecodeError> {
     let length: i32 = bytes.read_i32::<BigEndian>()?;
     if length < 0 {
-        bail!("read_byte_array: length cannot be negative");
+        return Err(DecodeError::NegativeLength);
     } else if bytes.len() < length as _ {
-        bail!("read_byte_array: not enough bytes to read byte array");
+        return Err(DecodeError::UnexpectedEof);
     }
     let length = length as usize;
 
-    *size += length;
-    if *size > DECODE_CAPACITY {
-        bail!("read_byte_array: nbt too large, capacity reached")
-    }
+    decoder.check_array_length(length)?;
+    decoder.add_size(length)?;
 
     let (arr_bytes, rest_bytes) = bytes.split_at(length);
     *bytes = rest_bytes;
@@ -160,21 +268,17 @@ fn read_byte_array(bytes: &mut &[u8], size: &mut usize) -> anyhow::Result<Vec<i8
 }
 
 #[inline]
-fn read_string<'a>(bytes: &mut &'a [u8], size: &mut usize) -> anyhow::Result<Cow<'a, str>> {
+fn read_string<'a>(bytes: &mut &'a [u8], decoder: &mut Decoder) -> Result<Cow<'a, str>, DecodeError> {
     let length: u16 = bytes.read_u16::<BigEndian>()?;
     if bytes.len() < length as _ {
-        bail!(
-            "read_string: not enough bytes ({} remaining) to read string of length {}",
-            bytes.len(),
-            length
-        );
+        return Err(DecodeError::UnexpectedEof);
     }
     let length = length as usize;
 
-    *size += length + 24;
-    if *size > DECODE_CAPACITY {
-        bail!("read_string: nbt too large, capacity reached")
+    if length > decoder.limits.max_string_length {
+        return Err(DecodeError::StringTooLong { length, max: decoder.limits.max_string_length });
     }
+    decoder.add_size(length + 24)?;
 
     let (str_bytes, rest_bytes) = bytes.split_at(length);
     *bytes = rest_bytes;
@@ -184,10 +288,9 @@ fn read_string<'a>(bytes: &mut &'a [u8], size: &mut usize) -> anyhow::Result<Cow
 
 fn read_list(
     bytes: &mut &[u8],
-    nodes: &mut Slab<NBTNode>,
+    decoder: &mut Decoder,
     depth: usize,
-    size: &mut usize,
-) -> anyhow::Result<(u8, Vec<usize>)> {
+) -> Result<(u8, Vec<usize>), DecodeError> {
     let type_id: u8 = bytes.read_u8()?;
 
     let length: i32 = bytes.read_i32::<BigEndian>()?;
@@ -195,21 +298,19 @@ fn read_list(
     if length <= 0 {
         Ok((type_id, Vec::new()))
     } else if bytes.len() < length as _ {
-        bail!("read_list: not enough bytes to read list");
+        Err(DecodeError::UnexpectedEof)
     } else if type_id == TAG_END_ID.0 {
-        bail!("read_list: type cannot be TAG_END for non-zero length list");
+        Err(DecodeError::EndList)
     } else {
         let length = length as usize;
 
-        *size += length * 8;
-        if *size > DECODE_CAPACITY {
-            bail!("read_list: nbt too large, capacity reached")
-        }
+        decoder.check_array_length(length)?;
+        decoder.add_size(length * 8)?;
 
         let mut children = Vec::with_capacity(length);
 
         for _ in 0..length {
-            children.push(read_node(bytes, nodes, type_id, depth, size)?);
+            children.push(read_node(bytes, decoder, type_id, depth)?);
         }
 
         Ok((type_id, children))
@@ -217,19 +318,17 @@ fn read_list(
 }
 
 #[inline]
-fn read_int_array(bytes: &mut &[u8], size: &mut usize) -> anyhow::Result<Vec<i32>> {
+fn read_int_array(bytes: &mut &[u8], decoder: &mut Decoder) -> Result<Vec<i32>, DecodeError> {
     let length: i32 = bytes.read_i32::<BigEndian>()?;
     if length < 0 {
-        bail!("read_int_array: length cannot be negative");
+        return Err(DecodeError::NegativeLength);
     } else if bytes.len() < (length as usize) * 4 {
-        bail!("read_int_array: not enough bytes to read int array");
+        return Err(DecodeError::UnexpectedEof);
     }
     let length = length as usize;
 
-    *size += length * 4;
-    if *size > DECODE_CAPACITY {
-        bail!("read_int_array: nbt too large, capacity reached")
-    }
+    decoder.check_array_length(length)?;
+    decoder.add_size(length * 4)?;
 
     let (arr_bytes, rest_bytes) = bytes.split_at(length * 4);
     *bytes = rest_bytes;
@@ -240,20 +339,18 @@ fn read_int_array(bytes: &mut &[u8], size: &mut usize) -> anyhow::Result<Vec<i32
 }
 
 #[inline]
-fn read_long_array(bytes: &mut &[u8], size: &mut usize) -> anyhow::Result<Vec<i64>> {
+fn read_long_array(bytes: &mut &[u8], decoder: &mut Decoder) -> Result<Vec<i64>, DecodeError> {
     let length: i32 = bytes.read_i32::<BigEndian>()?;
 
     if length < 0 {
-        bail!("read_long_array: length cannot be negative");
+        return Err(DecodeError::NegativeLength);
     } else if bytes.len() < (length as usize) * 8 {
-        bail!("read_long_array: not enough bytes to read long array");
+        return Err(DecodeError::UnexpectedEof);
     }
     let length = length as usize;
 
-    *size += length * 8;
-    if *size > DECODE_CAPACITY {
-        bail!("read_long_array: nbt too large, capacity reached")
-    }
+    decoder.check_array_length(length)?;
+    decoder.add_size(length * 8)?;
 
     let (arr_bytes, rest_bytes) = bytes.split_at(length * 8);
     *bytes = rest_bytes;
@@ -262,3 +359,129 @@ fn read_long_array(bytes: &mut &[u8], size: &mut usize) -> anyhow::Result<Vec<i6
     byteorder::BigEndian::read_i64_into(arr_bytes, values.as_mut_slice());
     Ok(values)
 }
+
+#[cfg(test)]
+mod tests {
+    use byteorder::{BigEndian, WriteBytesExt};
+
+    use super::{DecodeError, DecodeLimits, read_named_with_limits, read_protocol_with_limits};
+
+    // A named root compound called "root" containing a single tag called "value"
+    fn named_root(type_id: u8, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![10, 0, 4];
+        bytes.extend_from_slice(b"root");
+        bytes.extend_from_slice(&[type_id, 0, 5]);
+        bytes.extend_from_slice(b"value");
+        bytes.extend_from_slice(payload);
+        bytes.push(0);
+        bytes
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let limits = DecodeLimits { max_depth: 16, ..DecodeLimits::default() };
+
+        let nested = |depth: usize| {
+            let mut bytes = vec![10, 0, 0];
+            for _ in 0..depth {
+                bytes.extend_from_slice(&[10, 0, 1, b'a']);
+            }
+            bytes.extend(std::iter::repeat_n(0, depth + 1));
+            bytes
+        };
+        assert!(read_named_with_limits(&mut nested(16).as_slice(), &limits).is_ok());
+        assert!(matches!(read_named_with_limits(&mut nested(17).as_slice(), &limits), Err(DecodeError::TooDeep(16))));
+
+        // Lists of lists count towards the depth as well
+        let mut lists = vec![10, 0, 0];
+        for index in 0..20 {
+            if index == 0 {
+                lists.extend_from_slice(&[9, 0, 1, b'a']);
+            }
+            lists.push(9);
+            lists.write_i32::<BigEndian>(1).unwrap();
+        }
+        lists.push(0);
+        lists.write_i32::<BigEndian>(0).unwrap();
+        lists.push(0);
+        assert!(matches!(read_named_with_limits(&mut lists.as_slice(), &limits), Err(DecodeError::TooDeep(16))));
+
+        // Deep nesting is rejected with the default limits instead of overflowing the stack
+        assert!(matches!(read_named_with_limits(&mut nested(100_000).as_slice(), &DecodeLimits::default()), Err(DecodeError::TooDeep(_))));
+    }
+
+    #[test]
+    fn test_oversized_length_prefixes() {
+        let limits = DecodeLimits::untrusted();
+
+        // Lengths far beyond the input don't allocate, they run out of data
+        for type_id in [7, 9, 11, 12] {
+            let mut payload = Vec::new();
+            if type_id == 9 {
+                payload.push(3);
+            }
+            payload.write_i32::<BigEndian>(i32::MAX).unwrap();
+            let result = read_named_with_limits(&mut named_root(type_id, &payload).as_slice(), &limits);
+            assert!(matches!(result, Err(DecodeError::UnexpectedEof)), "type {type_id}: {result:?}");
+        }
+
+        for type_id in [7, 11, 12] {
+            let mut payload = Vec::new();
+            payload.write_i32::<BigEndian>(-1).unwrap();
+            let result = read_named_with_limits(&mut named_root(type_id, &payload).as_slice(), &limits);
+            assert!(matches!(result, Err(DecodeError::NegativeLength)), "type {type_id}: {result:?}");
+        }
+
+        // Lengths which are backed by data are still checked against the limits
+        let limits = DecodeLimits { max_array_length: 8, ..DecodeLimits::untrusted() };
+        let mut payload = Vec::new();
+        payload.write_i32::<BigEndian>(9).unwrap();
+        payload.extend_from_slice(&[0; 9 * 8]);
+        for type_id in [7, 11, 12] {
+            let result = read_named_with_limits(&mut named_root(type_id, &payload).as_slice(), &limits);
+            assert!(matches!(result, Err(DecodeError::ArrayTooLong { length: 9, max: 8 })), "type {type_id}: {result:?}");
+        }
+
+        let limits = DecodeLimits { max_size: 1024, ..DecodeLimits::untrusted() };
+        let mut payload = Vec::new();
+        payload.write_i32::<BigEndian>(4096).unwrap();
+        payload.extend_from_slice(&[0; 4096]);
+        let result = read_named_with_limits(&mut named_root(7, &payload).as_slice(), &limits);
+        assert!(matches!(result, Err(DecodeError::TooLarge(1024))), "{result:?}");
+
+        let mut payload = Vec::new();
+        payload.write_u16::<BigEndian>(40_000).unwrap();
+        payload.extend_from_slice(&[b'a'; 40_000]);
+        let result = read_named_with_limits(&mut named_root(8, &payload).as_slice(), &DecodeLimits::untrusted());
+        assert!(matches!(result, Err(DecodeError::StringTooLong { length: 40_000, .. })), "{result:?}");
+    }
+
+    #[test]
+    fn test_truncated_input() {
+        let nbt = crate::nbt!({
+            "byte": 1i8,
+            "short": 2i16,
+            "int": 3,
+            "long": 4i64,
+            "float": 0.5f32,
+            "double": 0.25,
+            "string": "text",
+            "bytes": vec![1i8, 2, 3],
+            "ints": vec![1i32, 2, 3],
+            "longs": vec![1i64, 2, 3],
+            "list": [{ "a": 1 }, { "b": [1, 2] }],
+            "compound": { "nested": { "value": "deep" } }
+        });
+
+        let named = crate::encode::write_named(&nbt);
+        assert_eq!(read_named_with_limits(&mut named.as_slice(), &DecodeLimits::untrusted()).unwrap(), nbt);
+        for length in 0..named.len() {
+            assert!(read_named_with_limits(&mut &named[..length], &DecodeLimits::untrusted()).is_err(), "length {length}");
+        }
+
+        let protocol = crate::encode::write_protocol(&nbt);
+        for length in 1..protocol.len() {
+            assert!(read_protocol_with_limits(&mut &protocol[..length], &DecodeLimits::untrusted()).is_err(), "length {length}");
+        }
+    }
+}
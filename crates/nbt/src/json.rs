@@ -0,0 +1,385 @@
+use serde_json::{Map, Number, Value};
+
+use crate::{
+    CompoundRefMut, ListRef, ListRefMut, NBT, NBTRef, NBTValue, TAG_BYTE_ARRAY_ID, TAG_BYTE_ID, TAG_COMPOUND_ID, TAG_DOUBLE_ID,
+    TAG_END_ID, TAG_FLOAT_ID, TAG_INT_ARRAY_ID, TAG_INT_ID, TAG_LIST_ID, TAG_LONG_ARRAY_ID, TAG_LONG_ID, TAG_SHORT_ID,
+    TAG_STRING_ID, TagType,
+};
+
+// Conversion between NBT and JSON, for showing NBT data to the user and reading it back after it has been edited
+//
+// NBT -> JSON:
+// - All numeric tags become numbers. Floats are written using their shortest representation, so 0.1f32 is 0.1 rather
+//   than 0.10000000149011612. NaN and infinite floats become null
+// - Strings, lists and compounds become strings, arrays and objects
+// - Byte, int and long arrays become arrays of numbers
+//
+// JSON -> NBT, when there is no hint to take the type from:
+// - Integers become ints, or longs if they don't fit in an int. Integers too large for a long and numbers with a
+//   fraction or exponent become doubles
+// - Booleans become bytes, like Minecraft stores them
+// - Arrays become lists. The elements of a list must all have the same type, except that numbers are widened: a list
+//   is a list of longs if any integer doesn't fit in an int, and a list of doubles if any number isn't an integer
+// - Empty arrays become empty lists without an element type
+// - null can't be converted
+//
+// from_json_with_hint takes the types from an existing NBT where the JSON value is compatible, so that converting to
+// JSON and back gives the same NBT. Numbers keep their tag type and must still be in its range, arrays of numbers stay
+// byte, int or long arrays, and lists keep their element type. Values which can't have the hinted type, e.g. a string
+// replacing a number, are converted as if there was no hint
+
+#[derive(thiserror::Error, Debug)]
+pub enum JsonError {
+    #[error("root must be an object")]
+    RootNotObject,
+    #[error("{path}: null can't be converted to NBT")]
+    Null { path: String },
+    #[error("{path}: {value} isn't a valid {expected}")]
+    InvalidNumber { path: String, value: Number, expected: &'static str },
+    #[error("{path}: elements of a list must all have the same type")]
+    MixedList { path: String },
+}
+
+pub fn to_json(nbt: &NBT) -> Value {
+    reference_to_json(nbt.as_reference())
+}
+
+pub fn reference_to_json(value: NBTRef<'_>) -> Value {
+    match value {
+        NBTRef::Byte(v) => Value::from(*v),
+        NBTRef::Short(v) => Value::from(*v),
+        NBTRef::Int(v) => Value::from(*v),
+        NBTRef::Long(v) => Value::from(*v),
+        // Going through the string keeps the shortest representation of the f32 instead of its exact value as f64
+        NBTRef::Float(v) => v.to_string().parse::<f64>().ok().and_then(Number::from_f64).map_or(Value::Null, Value::Number),
+        NBTRef::Double(v) => Number::from_f64(*v).map_or(Value::Null, Value::Number),
+        NBTRef::ByteArray(v) => v.iter().copied().map(Value::from).collect(),
+        NBTRef::String(v) => Value::String(v.clone()),
+        NBTRef::List(list) => list.into_iter().map(reference_to_json).collect(),
+        NBTRef::Compound(compound) => {
+            let mut map = Map::new();
+            for (key, value) in compound {
+                map.insert(key.to_string(), reference_to_json(value));
+            }
+            Value::Object(map)
+        },
+        NBTRef::IntArray(v) => v.iter().copied().map(Value::from).collect(),
+        NBTRef::LongArray(v) => v.iter().copied().map(Value::from).collect(),
+    }
+}
+
+pub fn from_json(value: &Value) -> Result<NBT, JsonError> {
+    convert_root(value, None)
+}
+
+pub fn from_json_with_hint(value: &Value, hint: &NBT) -> Result<NBT, JsonError> {
+    convert_root(value, Some(hint.as_reference()))
+}
+
+fn convert_root(value: &Value, hint: Option<NBTRef<'_>>) -> Result<NBT, JsonError> {
+    let Value::Object(map) = value else {
+        return Err(JsonError::RootNotObject);
+    };
+
+    let mut nbt = NBT::new();
+    if let Some(mut compound) = nbt.as_compound_mut() {
+        write_compound(&mut compound, map, hint, "")?;
+    }
+    Ok(nbt)
+}
+
+// Where a converted value is placed, so that the same code can fill both compounds and lists
+enum Target<'t, 'a> {
+    Compound(&'t mut CompoundRefMut<'a>, &'t str),
+    List(&'t mut ListRefMut<'a>),
+}
+
+impl<'t> Target<'t, '_> {
+    fn insert(self, value: impl NBTValue) {
+        match self {
+            Target::Compound(compound, key) => value.insert_into_compound(compound, key),
+            Target::List(list) => value.insert_into_list(list),
+        }
+    }
+
+    fn create_compound(self) -> CompoundRefMut<'t> {
+        match self {
+            Target::Compound(compound, key) => compound.create_compound(key),
+            Target::List(list) => list.create_compound(),
+        }
+    }
+
+    fn create_list(self, type_id: TagType) -> ListRefMut<'t> {
+        match self {
+            Target::Compound(compound, key) => compound.create_list(key, type_id),
+            Target::List(list) => list.create_list(type_id),
+        }
+    }
+}
+
+fn write_compound(
+    compound: &mut CompoundRefMut<'_>,
+    map: &Map<String, Value>,
+    hint: Option<NBTRef<'_>>,
+    path: &str,
+) -> Result<(), JsonError> {
+    let hint = hint.and_then(NBTRef::as_compound);
+
+    for (key, value) in map {
+        let path = if path.is_empty() { key.clone() } else { format!("{path}.{key}") };
+        let hint = hint.and_then(|hint| hint.find(key));
+        let type_id = match hint {
+            Some(hint) if is_compatible(value, hint.tag_type()) => hint.tag_type(),
+            _ => natural_type(value, &path)?,
+        };
+        write_value(Target::Compound(compound, key), value, type_id, hint, &path)?;
+    }
+
+    Ok(())
+}
+
+fn write_value(
+    target: Target<'_, '_>,
+    value: &Value,
+    type_id: TagType,
+    hint: Option<NBTRef<'_>>,
+    path: &str,
+) -> Result<(), JsonError> {
+    match (value, type_id) {
+        (Value::Bool(v), TAG_BYTE_ID) => target.insert(*v),
+        (Value::Number(v), TAG_BYTE_ID) => target.insert(integer::<i8>(v, "byte", path)?),
+        (Value::Number(v), TAG_SHORT_ID) => target.insert(integer::<i16>(v, "short", path)?),
+        (Value::Number(v), TAG_INT_ID) => target.insert(integer::<i32>(v, "int", path)?),
+        (Value::Number(v), TAG_LONG_ID) => target.insert(integer::<i64>(v, "long", path)?),
+        (Value::Number(v), TAG_FLOAT_ID) => target.insert(float(v, path)? as f32),
+        (Value::Number(v), TAG_DOUBLE_ID) => target.insert(float(v, path)?),
+        (Value::String(v), TAG_STRING_ID) => target.insert(v.as_str()),
+        (Value::Array(values), TAG_BYTE_ARRAY_ID) => target.insert(integers::<i8>(values, "byte", path)?),
+        (Value::Array(values), TAG_INT_ARRAY_ID) => target.insert(integers::<i32>(values, "int", path)?),
+        (Value::Array(values), TAG_LONG_ARRAY_ID) => target.insert(integers::<i64>(values, "long", path)?),
+        (Value::Array(values), TAG_LIST_ID) => {
+            let hint = hint.and_then(NBTRef::as_list);
+            let element_type = list_element_type(values, hint.map(|hint| hint.children_type), path)?;
+            let mut list = target.create_list(element_type);
+            let mut hints = hint.map(ListRef::into_iter);
+            for (index, value) in values.iter().enumerate() {
+                let path = format!("{path}[{index}]");
+                let hint = hints.as_mut().and_then(Iterator::next);
+                write_value(Target::List(&mut list), value, element_type, hint, &path)?;
+            }
+        },
+        (Value::Object(map), TAG_COMPOUND_ID) => {
+            let mut compound = target.create_compound();
+            write_compound(&mut compound, map, hint, path)?;
+        },
+        (Value::Null, _) => return Err(JsonError::Null { path: path.into() }),
+        _ => unreachable!("type {type_id:?} was chosen for an incompatible value"),
+    }
+
+    Ok(())
+}
+
+// The type a value becomes when there is no hint
+fn natural_type(value: &Value, path: &str) -> Result<TagType, JsonError> {
+    Ok(match value {
+        Value::Null => return Err(JsonError::Null { path: path.into() }),
+        Value::Bool(_) => TAG_BYTE_ID,
+        Value::Number(number) => match number.as_i64() {
+            Some(v) if i32::try_from(v).is_ok() => TAG_INT_ID,
+            Some(_) => TAG_LONG_ID,
+            None => TAG_DOUBLE_ID,
+        },
+        Value::String(_) => TAG_STRING_ID,
+        Value::Array(_) => TAG_LIST_ID,
+        Value::Object(_) => TAG_COMPOUND_ID,
+    })
+}
+
+// Whether the value can be stored as the given type, numbers that are out of range are still compatible so that
+// they are reported as errors instead of silently changing the type
+fn is_compatible(value: &Value, type_id: TagType) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(_) => type_id == TAG_BYTE_ID,
+        Value::Number(_) => matches!(
+            type_id,
+            TAG_BYTE_ID | TAG_SHORT_ID | TAG_INT_ID | TAG_LONG_ID | TAG_FLOAT_ID | TAG_DOUBLE_ID
+        ),
+        Value::String(_) => type_id == TAG_STRING_ID,
+        Value::Array(values) => match type_id {
+            TAG_LIST_ID => true,
+            TAG_BYTE_ARRAY_ID | TAG_INT_ARRAY_ID | TAG_LONG_ARRAY_ID => values.iter().all(Value::is_number),
+            _ => false,
+        },
+        Value::Object(_) => type_id == TAG_COMPOUND_ID,
+    }
+}
+
+fn list_element_type(values: &[Value], hint: Option<TagType>, path: &str) -> Result<TagType, JsonError> {
+    if let Some(hint) = hint && hint != TAG_END_ID && values.iter().all(|value| is_compatible(value, hint)) {
+        return Ok(hint);
+    }
+
+    let mut element_type = TAG_END_ID;
+    for (index, value) in values.iter().enumerate() {
+        let value_type = natural_type(value, &format!("{path}[{index}]"))?;
+        element_type = match (element_type, value_type) {
+            (TAG_END_ID, _) => value_type,
+            (a, b) if a == b => a,
+            (TAG_INT_ID, TAG_LONG_ID) | (TAG_LONG_ID, TAG_INT_ID) => TAG_LONG_ID,
+            (TAG_INT_ID | TAG_LONG_ID | TAG_DOUBLE_ID, TAG_INT_ID | TAG_LONG_ID | TAG_DOUBLE_ID) => TAG_DOUBLE_ID,
+            _ => return Err(JsonError::MixedList { path: path.into() }),
+        };
+    }
+    Ok(element_type)
+}
+
+fn integer<T: TryFrom<i64>>(number: &Number, expected: &'static str, path: &str) -> Result<T, JsonError> {
+    number.as_i64().and_then(|v| T::try_from(v).ok()).ok_or_else(|| JsonError::InvalidNumber {
+        path: path.into(),
+        value: number.clone(),
+        expected,
+    })
+}
+
+fn float(number: &Number, path: &str) -> Result<f64, JsonError> {
+    number.as_f64().ok_or_else(|| JsonError::InvalidNumber {
+        path: path.into(),
+        value: number.clone(),
+        expected: "number",
+    })
+}
+
+fn integers<T: TryFrom<i64>>(values: &[Value], expected: &'static str, path: &str) -> Result<Vec<T>, JsonError> {
+    values.iter().enumerate().map(|(index, value)| match value {
+        Value::Number(number) => integer(number, expected, &format!("{path}[{index}]")),
+        _ => unreachable!("arrays are only converted to byte/int/long arrays when all elements are numbers"),
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        NBT, NBTRef, TAG_BYTE_ID, TAG_COMPOUND_ID, TAG_DOUBLE_ID, TAG_END_ID, TAG_INT_ID, TAG_LONG_ID, TAG_SHORT_ID, TAG_STRING_ID,
+    };
+
+    use super::{JsonError, from_json, from_json_with_hint, to_json};
+
+    fn typed_nbt() -> NBT {
+        let mut nbt = NBT::new();
+        let mut root = nbt.as_compound_mut().unwrap();
+        root.insert_byte("byte", -5);
+        root.insert_short("short", i16::MIN);
+        root.insert_int("int", i32::MAX);
+        root.insert_long("long", i64::MAX);
+        root.insert_float("float", 0.1);
+        root.insert_double("double", 0.1);
+        root.insert_string("string", "text".into());
+        root.insert_byte_array("bytes", vec![-1, 0, 1]);
+        root.insert_int_array("ints", vec![i32::MIN, 0, i32::MAX]);
+        root.insert_long_array("longs", vec![i64::MIN, i64::MAX]);
+
+        let mut shorts = root.create_list("shorts", TAG_SHORT_ID);
+        shorts.insert_short(1);
+        shorts.insert_short(2);
+
+        let mut nested = root.create_compound("nested");
+        nested.insert_byte("flag", 1);
+        let mut compounds = nested.create_list("compounds", TAG_COMPOUND_ID);
+        compounds.create_compound().insert_float("value", 1.5);
+
+        root.create_list("empty", TAG_END_ID);
+        nbt
+    }
+
+    #[test]
+    fn test_round_trip_with_hint() {
+        let nbt = typed_nbt();
+        let value = to_json(&nbt);
+
+        assert_eq!(value["float"], json!(0.1));
+        assert_eq!(value["long"].as_i64(), Some(i64::MAX));
+        assert_eq!(value["bytes"], json!([-1, 0, 1]));
+        assert_eq!(value["longs"], json!([i64::MIN, i64::MAX]));
+
+        assert_eq!(from_json_with_hint(&value, &nbt).unwrap(), nbt);
+    }
+
+    #[test]
+    fn test_natural_types() {
+        let nbt = from_json(&json!({
+            "bool": true,
+            "int": 1,
+            "long": 9007199254740993i64,
+            "huge": u64::MAX,
+            "fraction": 1.5,
+            "longs": [1, 3000000000i64],
+            "doubles": [1, 1.5],
+            "strings": ["a", "b"],
+            "empty": [],
+        })).unwrap();
+        let root = nbt.as_compound().unwrap();
+
+        assert_eq!(root.find("bool"), Some(NBTRef::Byte(&1)));
+        assert_eq!(root.find("int"), Some(NBTRef::Int(&1)));
+        // Longs above 2^53 aren't rounded through a double
+        assert_eq!(root.find("long"), Some(NBTRef::Long(&9007199254740993)));
+        assert_eq!(root.find("huge").map(|value| value.tag_type()), Some(TAG_DOUBLE_ID));
+        assert_eq!(root.find("fraction"), Some(NBTRef::Double(&1.5)));
+
+        let children_type = |key: &str| root.find_list_of_any(key).unwrap().children_type;
+        assert_eq!(children_type("longs"), TAG_LONG_ID);
+        assert_eq!(children_type("doubles"), TAG_DOUBLE_ID);
+        assert_eq!(children_type("strings"), TAG_STRING_ID);
+        assert_eq!(children_type("empty"), TAG_END_ID);
+    }
+
+    #[test]
+    fn test_hinted_bools_and_ranges() {
+        let nbt = typed_nbt();
+
+        let converted = from_json_with_hint(&json!({ "byte": true }), &nbt).unwrap();
+        assert_eq!(converted.as_compound().unwrap().find("byte"), Some(NBTRef::Byte(&1)));
+
+        let error = from_json_with_hint(&json!({ "byte": 300 }), &nbt).unwrap_err();
+        assert!(matches!(error, JsonError::InvalidNumber { ref path, expected: "byte", .. } if path == "byte"), "{error}");
+
+        let error = from_json_with_hint(&json!({ "bytes": [1, 128] }), &nbt).unwrap_err();
+        assert!(matches!(error, JsonError::InvalidNumber { ref path, .. } if path == "bytes[1]"), "{error}");
+    }
+
+    // Values which can't have the hinted type are converted without the hint instead of reaching the unreachable
+    // branches for incompatible types
+    #[test]
+    fn test_incompatible_hints() {
+        let nbt = typed_nbt();
+
+        let converted = from_json_with_hint(&json!({ "bytes": ["a", "b"], "int": "text", "float": false }), &nbt).unwrap();
+        let root = converted.as_compound().unwrap();
+        assert_eq!(root.find_list_of_any("bytes").unwrap().children_type, TAG_STRING_ID);
+        assert_eq!(root.find("int").map(|value| value.tag_type()), Some(TAG_STRING_ID));
+        assert_eq!(root.find("float").map(|value| value.tag_type()), Some(TAG_BYTE_ID));
+
+        let converted = from_json_with_hint(&json!({ "shorts": [{ "a": 1 }] }), &nbt).unwrap();
+        assert_eq!(converted.as_compound().unwrap().find_list_of_any("shorts").unwrap().children_type, TAG_COMPOUND_ID);
+
+        assert!(matches!(from_json_with_hint(&json!({ "bytes": [1, "a"] }), &nbt), Err(JsonError::MixedList { .. })));
+        assert!(matches!(from_json_with_hint(&json!({ "longs": [1, null] }), &nbt), Err(JsonError::Null { .. })));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(from_json(&json!([1])), Err(JsonError::RootNotObject)));
+
+        let error = from_json(&json!({ "a": { "b": [1, null] } })).unwrap_err();
+        assert!(matches!(error, JsonError::Null { ref path } if path == "a.b[1]"), "{error}");
+
+        let error = from_json(&json!({ "list": ["a", 1] })).unwrap_err();
+        assert!(matches!(error, JsonError::MixedList { ref path } if path == "list"), "{error}");
+
+        let plain = from_json(&json!({ "int": 1 })).unwrap();
+        assert_eq!(plain.as_compound().unwrap().find("int").map(|value| value.tag_type()), Some(TAG_INT_ID));
+    }
+}
@@ -1,9 +1,12 @@
-pub use reference::{CompoundRef, CompoundRefMut, ListRef, ListRefMut, NBTRef, NBTRefMut};
+pub use builder::NBTValue;
+pub use reference::{CompoundEntry, CompoundRef, CompoundRefMut, ListRef, ListRefMut, NBTRef, NBTRefMut};
 use slab::Slab;
 use std::{fmt::Debug, ptr::NonNull, result};
 
+mod builder;
 pub mod decode;
 pub mod encode;
+pub mod json;
 mod pretty;
 pub mod stringified;
 
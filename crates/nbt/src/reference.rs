@@ -337,7 +337,7 @@ impl<'a> CompoundRef<'a> {
         }
     }
 
-    pub fn find(&self, key: &str) -> Option<NBTRef<'_>> {
+    pub fn find(&self, key: &str) -> Option<NBTRef<'a>> {
         let idx = self.find_idx(key)?;
         Some(self.nbt.get_reference(idx))
     }
@@ -552,6 +552,79 @@ impl<'a> CompoundRefMut<'a> {
             false
         }
     }
+
+    pub fn entry<'k>(&mut self, key: &'k str) -> CompoundEntry<'_, 'k> {
+        CompoundEntry {
+            compound: CompoundRefMut {
+                nbt: self.nbt,
+                node_idx: self.node_idx,
+            },
+            key,
+        }
+    }
+}
+
+// A key of a compound which may or may not be present, see CompoundRefMut::entry
+pub struct CompoundEntry<'a, 'k> {
+    compound: CompoundRefMut<'a>,
+    key: &'k str,
+}
+
+impl<'a, 'k> CompoundEntry<'a, 'k> {
+    pub fn key(&self) -> &'k str {
+        self.key
+    }
+
+    pub fn get(self) -> Option<NBTRefMut<'a>> {
+        let idx = self.compound.find_idx(self.key)?;
+        Some(self.compound.nbt.get_reference_mut(idx))
+    }
+
+    // Returns the existing compound, a value of another type under the same key is replaced
+    pub fn or_insert_compound(mut self) -> CompoundRefMut<'a> {
+        let idx = match self.compound.find_idx(self.key) {
+            Some(idx) if matches!(self.compound.get_node(idx), NBTNode::Compound(_)) => idx,
+            _ => self.replace(NBTNode::Compound(Default::default())),
+        };
+        CompoundRefMut {
+            nbt: self.compound.nbt,
+            node_idx: idx,
+        }
+    }
+
+    // Returns the existing list if it can hold elements of the type, a value of another type under the same key is
+    // replaced with an empty list
+    pub fn or_insert_list(mut self, type_id: TagType) -> ListRefMut<'a> {
+        let idx = match self.compound.find_idx(self.key) {
+            Some(idx) if matches!(
+                self.compound.get_node(idx),
+                NBTNode::List { type_id: list_type_id, children }
+                    if *list_type_id == type_id || (*list_type_id == super::TAG_END_ID && children.is_empty())
+            ) => idx,
+            _ => self.replace(NBTNode::List {
+                type_id,
+                children: Default::default(),
+            }),
+        };
+        ListRefMut {
+            nbt: self.compound.nbt,
+            node_idx: idx,
+        }
+    }
+
+    // Inserts the value if the key isn't present, an existing value is kept even if it has another type
+    pub fn or_insert(mut self, value: impl super::NBTValue) -> NBTRefMut<'a> {
+        if !self.compound.contains_key(self.key) {
+            value.insert_into_compound(&mut self.compound, self.key);
+        }
+        let idx = self.compound.find_idx(self.key).unwrap();
+        self.compound.nbt.get_reference_mut(idx)
+    }
+
+    fn replace(&mut self, node: NBTNode) -> usize {
+        self.compound.remove(self.key);
+        self.compound.insert_node(self.key, node)
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -655,6 +728,45 @@ impl<'a> ListRef<'a> {
             index: 0,
         }
     }
+
+    // The compounds in the list, other elements are skipped
+    pub fn compounds(self) -> impl Iterator<Item = CompoundRef<'a>> {
+        self.into_iter().filter_map(NBTRef::as_compound)
+    }
+}
+
+impl<'a> IntoIterator for ListRef<'a> {
+    type Item = NBTRef<'a>;
+    type IntoIter = ListIterator<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let indices = match self.nbt.nodes.get(self.node_idx) {
+            Some(NBTNode::List { children, .. }) => children.as_slice(),
+            _ => unsafe { unreachable_unchecked() },
+        };
+        ListIterator {
+            nbt: self.nbt,
+            indices,
+            index: 0,
+        }
+    }
+}
+
+impl<'a> IntoIterator for CompoundRef<'a> {
+    type Item = (&'a str, NBTRef<'a>);
+    type IntoIter = CompoundIterator<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let compound = match self.nbt.nodes.get(self.node_idx) {
+            Some(NBTNode::Compound(compound)) => compound,
+            _ => unsafe { unreachable_unchecked() },
+        };
+        CompoundIterator {
+            nbt: self.nbt,
+            compound,
+            index: 0,
+        }
+    }
 }
 
 pub struct ListRefMut<'a> {
@@ -684,8 +796,13 @@ impl<'a> ListRefMut<'a> {
     }
 
     fn insert_node(&mut self, node: NBTNode) -> usize {
-        let (type_id, _) = self.get_self_node_mut();
-        if type_id != node.get_type() {
+        let (type_id, children) = self.get_self_node_mut();
+        // Empty lists have no element type yet, the first element decides it
+        if type_id == super::TAG_END_ID && children.is_empty() {
+            if let Some(NBTNode::List { type_id, .. }) = self.nbt.nodes.get_mut(self.node_idx) {
+                *type_id = node.get_type();
+            }
+        } else if type_id != node.get_type() {
             panic!("Tried to insert {:?} into a list of {:?}", node.get_type(), type_id);
         }
 
@@ -719,6 +836,26 @@ impl<'a> ListRefMut<'a> {
         Some(self.nbt.get_reference(*idx))
     }
 
+    pub fn get_mut(&mut self, index: usize) -> Option<NBTRefMut<'_>> {
+        let (_, children) = self.get_self_node();
+        let idx = *children.get(index)?;
+        Some(self.nbt.get_reference_mut(idx))
+    }
+
+    // Calls the function with each compound in the list, for modifying every element without indexing
+    pub fn for_each_compound_mut(&mut self, mut func: impl FnMut(CompoundRefMut<'_>)) {
+        let (_, children) = self.get_self_node();
+        let children = children.clone();
+        for idx in children {
+            if let NBTNode::Compound(_) = self.nbt.nodes[idx] {
+                func(CompoundRefMut {
+                    nbt: self.nbt,
+                    node_idx: idx,
+                });
+            }
+        }
+    }
+
     super::enumerate_basic_types!(super::get_list);
 
     pub fn get_numeric<T: num::FromPrimitive>(&self, index: usize) -> Option<T> {
@@ -0,0 +1,589 @@
+use std::{collections::HashMap, fmt::Write, net::SocketAddr, path::Path, sync::Arc, time::Duration};
+
+use backend::{data_location::LauncherLocation, frontend_events::{self, EVENT_BUFFER, PROGRESS_INTERVAL}};
+use bridge::{
+    handle::{BackendHandle, FrontendReceiver},
+    instance::{InstanceID, InstanceStatus},
+    keep_alive::KeepAlive,
+    message::{MessageToBackend, MessageToFrontend},
+    modal_action::ModalAction,
+};
+use parking_lot::RwLock;
+use schema::{instance::{InstanceConfiguration, InstanceType}, loader::Loader};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::broadcast,
+};
+use uuid::Uuid;
+
+// Longest request line accepted before and after authenticating, longer lines disconnect the client
+const UNAUTHENTICATED_LINE_LIMIT: usize = 4 * 1024;
+const LINE_LIMIT: usize = 16 * 1024 * 1024;
+// Scrapers send a short GET request, anything bigger or slower than this is dropped
+const METRICS_REQUEST_LIMIT: u64 = 8 * 1024;
+const METRICS_MAX_HEADERS: usize = 64;
+const METRICS_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const UNAUTHORIZED: i64 = -32001;
+const UNKNOWN_INSTANCE: i64 = -32002;
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+#[derive(Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+struct DaemonInstance {
+    name: Arc<str>,
+    configuration: InstanceConfiguration,
+    status: InstanceStatus,
+}
+
+impl DaemonInstance {
+    fn to_json(&self) -> Value {
+        frontend_events::instance_json(&self.name, &self.configuration, self.status)
+    }
+}
+
+struct Daemon {
+    backend_handle: BackendHandle,
+    // Clients must authenticate with this token before making requests, not needed when talking over stdio
+    token: Option<Arc<str>>,
+    instances: RwLock<HashMap<InstanceID, DaemonInstance>>,
+    events: broadcast::Sender<Arc<str>>,
+}
+
+// Runs the backend without the gpui frontend, exposing it as JSON-RPC 2.0 over a local TCP socket. Requests and
+// notifications are newline delimited JSON. The port and the token clients must authenticate with are written to
+// daemon.json in the launcher directory
+pub fn run(location: LauncherLocation, port: u16, metrics_address: Option<SocketAddr>) {
+    let token: [u8; 32] = rand::random();
+    let (daemon, frontend_recv) = Daemon::start(&location, Some(hex::encode(token).into()));
+
+    create_runtime().block_on(async move {
+        let listener = match TcpListener::bind(("127.0.0.1", port)).await {
+            Ok(listener) => listener,
+            Err(error) => {
+                log::error!("Unable to start daemon on port {port}: {error}");
+                std::process::exit(1);
+            },
+        };
+        let port = listener.local_addr().map(|address| address.port()).unwrap_or(port);
+
+        if let Some(token) = &daemon.token && let Err(error) = write_daemon_info(&location.launcher_dir, port, token) {
+            log::error!("Unable to write daemon.json: {error}");
+            std::process::exit(1);
+        }
+        log::info!("Daemon listening on 127.0.0.1:{port}");
+
+        tokio::spawn(pump_backend_messages(daemon.clone(), frontend_recv));
+        if let Some(metrics_address) = metrics_address {
+            tokio::spawn(serve_metrics(daemon.clone(), metrics_address));
+        }
+
+        loop {
+            match listener.accept().await {
+                Ok((stream, address)) => {
+                    log::debug!("Daemon connection from {address}");
+                    let (read, write) = stream.into_split();
+                    tokio::spawn(handle_connection(daemon.clone(), read, write, ConnectionState::default()));
+                },
+                Err(error) => log::error!("Unable to accept daemon connection: {error}"),
+            }
+        }
+    });
+}
+
+// Same protocol as the daemon but over stdin and stdout, for scripts and CI harnesses driving a single launcher
+// process. Events are sent without subscribing, and the launcher exits once stdin is closed
+pub fn run_json_io(location: LauncherLocation) {
+    let (daemon, frontend_recv) = Daemon::start(&location, None);
+
+    create_runtime().block_on(async move {
+        let state = ConnectionState {
+            authenticated: true,
+            events: Some(daemon.events.subscribe()),
+        };
+        tokio::spawn(pump_backend_messages(daemon.clone(), frontend_recv));
+        handle_connection(daemon, tokio::io::stdin(), tokio::io::stdout(), state).await;
+    });
+
+    std::process::exit(0);
+}
+
+fn create_runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_all()
+        .build()
+        .expect("Failed to initialize Tokio runtime")
+}
+
+fn write_daemon_info(launcher_dir: &Path, port: u16, token: &str) -> std::io::Result<()> {
+    let info = json!({
+        "port": port,
+        "token": token,
+        "pid": std::process::id(),
+    });
+    let path = launcher_dir.join("daemon.json");
+
+    // The token lets anyone who can read it control the launcher, so only the current user may read the file. The mode
+    // only applies when the file is created, so a file left behind by an older version is replaced
+    _ = std::fs::remove_file(&path);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(&path)?;
+    std::io::Write::write_all(&mut file, &serde_json::to_vec_pretty(&info)?)
+}
+
+fn notification(method: &str, params: Value) -> Arc<str> {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params }).to_string().into()
+}
+
+impl Daemon {
+    fn start(location: &LauncherLocation, token: Option<Arc<str>>) -> (Arc<Self>, FrontendReceiver) {
+        let (backend_recv, backend_handle, frontend_recv, frontend_handle) = bridge::handle::create_pair();
+        backend::start(location.clone(), frontend_handle, backend_handle.clone(), backend_recv);
+
+        let daemon = Arc::new(Daemon {
+            backend_handle,
+            token,
+            instances: RwLock::new(HashMap::new()),
+            events: broadcast::channel(EVENT_BUFFER).0,
+        });
+        (daemon, frontend_recv)
+    }
+
+    // Prometheus text format
+    fn metrics(&self) -> String {
+        let (instances, running_instances) = {
+            let instances = self.instances.read();
+            let running = instances.values().filter(|instance| instance.status == InstanceStatus::Running).count();
+            (instances.len(), running)
+        };
+
+        let metrics: [(&str, &str, &str, u64); 5] = [
+            ("pandora_downloads_in_flight", "gauge", "Downloads currently in progress", backend::download_metrics::downloads_in_flight() as u64),
+            ("pandora_downloaded_bytes_total", "counter", "Bytes downloaded since the daemon started", backend::download_metrics::downloaded_bytes()),
+            ("pandora_instances", "gauge", "Instances known to the launcher", instances as u64),
+            ("pandora_running_instances", "gauge", "Instances which are currently running", running_instances as u64),
+            ("pandora_backend_queue_depth", "gauge", "Requests waiting to be handled by the backend", self.backend_handle.queue_depth() as u64),
+        ];
+
+        let mut output = String::new();
+        for (name, kind, help, value) in metrics {
+            _ = writeln!(output, "# HELP {name} {help}");
+            _ = writeln!(output, "# TYPE {name} {kind}");
+            _ = writeln!(output, "{name} {value}");
+        }
+        output
+    }
+
+    fn emit(&self, method: &str, params: Value) {
+        // Sending only fails when nobody is subscribed
+        _ = self.events.send(notification(method, params));
+    }
+
+    fn lookup_instance(&self, id: InstanceID) -> Option<(Option<Uuid>, Arc<str>)> {
+        self.instances.read().get(&id).map(|instance| (instance.configuration.uuid, instance.name.clone()))
+    }
+
+    // Instances are referred to by their id, which stays the same when they are renamed, or by their name
+    fn find_instance(&self, params: &Value) -> Result<InstanceID, RpcError> {
+        let reference = params.get("id").or_else(|| params.get("name")).and_then(Value::as_str)
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Missing instance id or name"))?;
+        self.instances.read().iter()
+            .filter_map(|(id, instance)| Some((instance.configuration.reference_match(&instance.name, reference)?, *id)))
+            .min_by_key(|(rank, _)| *rank)
+            .map(|(_, id)| id)
+            .ok_or_else(|| RpcError::new(UNKNOWN_INSTANCE, format!("Unknown instance {reference}")))
+    }
+
+    fn handle_request(self: &Arc<Self>, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "list_instances" => {
+                let instances = self.instances.read();
+                Ok(Value::Array(instances.values().map(DaemonInstance::to_json).collect()))
+            },
+            "create_instance" => {
+                #[derive(Deserialize)]
+                struct CreateInstanceParams {
+                    name: String,
+                    minecraft_version: String,
+                    #[serde(default = "default_loader")]
+                    loader: Loader,
+                    #[serde(default)]
+                    loader_version: Option<String>,
+                    #[serde(default)]
+                    group: Option<String>,
+                    #[serde(default)]
+                    instance_type: InstanceType,
+                    #[serde(default)]
+                    accept_eula: bool,
+                    #[serde(default)]
+                    install_fabric_api: bool,
+                }
+
+                fn default_loader() -> Loader {
+                    Loader::Vanilla
+                }
+
+                let params: CreateInstanceParams = serde_json::from_value(params)
+                    .map_err(|error| RpcError::new(INVALID_PARAMS, error.to_string()))?;
+                if params.loader == Loader::Unknown {
+                    return Err(RpcError::new(INVALID_PARAMS, "Unknown loader"));
+                }
+
+                self.backend_handle.send(MessageToBackend::CreateInstance {
+                    name: params.name.as_str().into(),
+                    version: params.minecraft_version.as_str().into(),
+                    loader: params.loader,
+                    preferred_loader_version: params.loader_version.as_deref().map(Into::into),
+                    group: params.group.as_deref().map(Into::into),
+                    icon: None,
+                    instance_type: params.instance_type,
+                    accept_eula: params.accept_eula,
+                    install_fabric_api: params.install_fabric_api,
+                });
+                Ok(Value::Null)
+            },
+            "delete_instance" => {
+                let id = self.find_instance(&params)?;
+                self.backend_handle.send(MessageToBackend::DeleteInstance { id });
+                Ok(Value::Null)
+            },
+            "start_instance" => {
+                let id = self.find_instance(&params)?;
+                let modal_action = ModalAction::default();
+                self.backend_handle.send(MessageToBackend::StartInstance {
+                    id,
+                    quick_play: None,
+                    modal_action: modal_action.clone(),
+                });
+
+                let action_id: u64 = rand::random();
+                tokio::spawn(report_progress(self.clone(), action_id, modal_action));
+                Ok(json!({ "action_id": action_id }))
+            },
+            "prepare_instance" => {
+                let id = self.find_instance(&params)?;
+                let modal_action = ModalAction::default();
+                self.backend_handle.send(MessageToBackend::PrepareInstance {
+                    id,
+                    modal_action: modal_action.clone(),
+                });
+
+                let action_id: u64 = rand::random();
+                tokio::spawn(report_progress(self.clone(), action_id, modal_action));
+                Ok(json!({ "action_id": action_id }))
+            },
+            "kill_instance" => {
+                let id = self.find_instance(&params)?;
+                self.backend_handle.send(MessageToBackend::KillInstance { id });
+                Ok(Value::Null)
+            },
+            "send_instance_input" => {
+                let id = self.find_instance(&params)?;
+                let input = params.get("input").and_then(Value::as_str)
+                    .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Missing input"))?;
+                self.backend_handle.send(MessageToBackend::SendInstanceInput { id, input: input.into() });
+                Ok(Value::Null)
+            },
+            _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("Unknown method {method}"))),
+        }
+    }
+}
+
+async fn pump_backend_messages(daemon: Arc<Daemon>, mut frontend_recv: FrontendReceiver) {
+    // The backend only streams game output while the keep alive is held
+    let mut game_output_keep_alive: HashMap<usize, KeepAlive> = HashMap::new();
+
+    // Instances from the initial load are already queued, so clients know the instance list is complete after this
+    while let Some(message) = frontend_recv.try_recv() {
+        handle_backend_message(&daemon, message, &mut game_output_keep_alive);
+    }
+    daemon.emit("ready", Value::Null);
+
+    while let Some(message) = frontend_recv.recv().await {
+        handle_backend_message(&daemon, message, &mut game_output_keep_alive);
+    }
+
+    log::error!("Backend has shut down, stopping daemon");
+    std::process::exit(1);
+}
+
+fn handle_backend_message(daemon: &Daemon, message: MessageToFrontend, game_output_keep_alive: &mut HashMap<usize, KeepAlive>) {
+    match message {
+        MessageToFrontend::InstanceAdded { id, name, configuration, .. } => {
+            let instance = DaemonInstance {
+                name: name.as_str().into(),
+                configuration,
+                status: InstanceStatus::NotRunning,
+            };
+            daemon.emit("instance_added", instance.to_json());
+            daemon.instances.write().insert(id, instance);
+        },
+        MessageToFrontend::InstanceRemoved { id } => {
+            if let Some(instance) = daemon.instances.write().remove(&id) {
+                daemon.emit("instance_removed", frontend_events::instance_reference(instance.configuration.uuid, &instance.name));
+            }
+        },
+        MessageToFrontend::InstanceModified { id, name, configuration, status, .. } => {
+            let instance = DaemonInstance {
+                name: name.as_str().into(),
+                configuration,
+                status,
+            };
+            daemon.emit("instance_modified", instance.to_json());
+            daemon.instances.write().insert(id, instance);
+        },
+        message => {
+            if let Some((event, data)) = frontend_events::message_event(&message, |id| daemon.lookup_instance(id)) {
+                daemon.emit(event, data);
+            }
+            if let MessageToFrontend::CreateGameOutputWindow { id, keep_alive, .. } = message {
+                game_output_keep_alive.insert(id, keep_alive);
+            }
+        },
+    }
+}
+
+async fn report_progress(daemon: Arc<Daemon>, action_id: u64, modal_action: ModalAction) {
+    loop {
+        tokio::time::sleep(PROGRESS_INTERVAL).await;
+
+        if let Some(error) = &*modal_action.error.read().unwrap() {
+            daemon.emit("action_finished", json!({ "action_id": action_id, "error": error }));
+            return;
+        }
+
+        // Nothing else can update the action once the backend has dropped its reference
+        if modal_action.refcnt() <= 1 {
+            modal_action.set_finished();
+        }
+
+        let trackers: Vec<Value> = modal_action.trackers.trackers.read().unwrap().iter().map(|tracker| {
+            let (count, total) = tracker.get();
+            json!({
+                "title": tracker.get_title(),
+                "count": count,
+                "total": total,
+                "finished": tracker.get_finished_at().is_some(),
+            })
+        }).collect();
+        let visit_url = modal_action.visit_url.read().unwrap().as_ref().map(|visit_url| {
+            json!({ "message": visit_url.message, "url": visit_url.url, "code": visit_url.code })
+        });
+        daemon.emit("progress", json!({ "action_id": action_id, "trackers": trackers, "visit_url": visit_url }));
+
+        if modal_action.get_finished_at().is_some() {
+            daemon.emit("action_finished", json!({ "action_id": action_id, "error": null }));
+            return;
+        }
+    }
+}
+
+// Serves the metrics over plain HTTP, so a daemon running on a home server can be monitored
+async fn serve_metrics(daemon: Arc<Daemon>, address: SocketAddr) {
+    let listener = match TcpListener::bind(address).await {
+        Ok(listener) => listener,
+        Err(error) => {
+            log::error!("Unable to serve metrics on {address}: {error}");
+            return;
+        },
+    };
+    log::info!("Serving metrics on http://{address}/metrics");
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(handle_metrics_request(daemon.clone(), stream));
+            },
+            Err(error) => log::error!("Unable to accept metrics connection: {error}"),
+        }
+    }
+}
+
+async fn handle_metrics_request(daemon: Arc<Daemon>, stream: TcpStream) {
+    let (read, mut write) = stream.into_split();
+    let read_request = async move {
+        let mut lines = BufReader::new(read.take(METRICS_REQUEST_LIMIT)).lines();
+        let request_line = lines.next_line().await.ok()??;
+        // The headers are read before responding, otherwise closing the connection may reset it before the response arrives
+        for _ in 0..METRICS_MAX_HEADERS {
+            match lines.next_line().await {
+                Ok(Some(line)) if !line.is_empty() => {},
+                _ => break,
+            }
+        }
+        Some(request_line)
+    };
+    let Ok(Some(request_line)) = tokio::time::timeout(METRICS_REQUEST_TIMEOUT, read_request).await else {
+        return;
+    };
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next();
+    let path = parts.next().and_then(|target| target.split('?').next());
+    let (status, body) = match (method, path) {
+        (Some("GET"), Some("/metrics")) => ("200 OK", daemon.metrics()),
+        _ => ("404 Not Found", "Not found\n".to_string()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    _ = write.write_all(response.as_bytes()).await;
+    _ = write.shutdown().await;
+}
+
+#[derive(Default)]
+struct ConnectionState {
+    authenticated: bool,
+    events: Option<broadcast::Receiver<Arc<str>>>,
+}
+
+async fn handle_connection(
+    daemon: Arc<Daemon>,
+    read: impl AsyncRead + Unpin,
+    mut write: impl AsyncWrite + Unpin,
+    mut state: ConnectionState,
+) {
+    let mut reader = BufReader::new(read);
+    // Kept between iterations since read_until leaves partially read lines in it when the event branch wins
+    let mut buffer = Vec::new();
+
+    loop {
+        let limit = if state.authenticated { LINE_LIMIT } else { UNAUTHENTICATED_LINE_LIMIT };
+        let remaining = limit.saturating_sub(buffer.len()) as u64;
+
+        tokio::select! {
+            read = (&mut reader).take(remaining).read_until(b'\n', &mut buffer) => match read {
+                Ok(0) => return,
+                Ok(_) if !buffer.ends_with(b"\n") => {
+                    if buffer.len() >= limit {
+                        log::warn!("Daemon client sent a request longer than {limit} bytes, disconnecting");
+                    }
+                    return;
+                },
+                Ok(_) => {},
+                Err(_) => return,
+            },
+            event = async { state.events.as_mut().unwrap().recv().await }, if state.events.is_some() => match event {
+                Ok(event) => {
+                    if write_line(&mut write, &event).await.is_err() {
+                        return;
+                    }
+                    continue;
+                },
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("Daemon client fell behind by {skipped} events, disconnecting");
+                    return;
+                },
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+        }
+
+        let Ok(line) = String::from_utf8(std::mem::take(&mut buffer)) else {
+            return;
+        };
+        if let Some(response) = handle_line(&daemon, &line, &mut state) && write_line(&mut write, &response).await.is_err() {
+            return;
+        }
+    }
+}
+
+// Doesn't return early on the first differing byte, so the time taken doesn't reveal how much of the token was right
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0, |difference, (a, b)| difference | (a ^ b)) == 0
+}
+
+fn handle_line(daemon: &Arc<Daemon>, line: &str, state: &mut ConnectionState) -> Option<String> {
+    if line.trim().is_empty() {
+        return None;
+    }
+
+    let request: RpcRequest = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(error) => {
+            let code = if serde_json::from_str::<Value>(line).is_ok() { INVALID_REQUEST } else { PARSE_ERROR };
+            return Some(error_response(Value::Null, RpcError::new(code, error.to_string())));
+        },
+    };
+
+    let result = if request.method == "authenticate" {
+        let token = request.params.get("token").and_then(Value::as_str);
+        let valid = match (&daemon.token, token) {
+            (None, _) => true,
+            (Some(expected), Some(token)) => constant_time_eq(expected.as_bytes(), token.as_bytes()),
+            (Some(_), None) => false,
+        };
+        if valid {
+            state.authenticated = true;
+            Ok(Value::Null)
+        } else {
+            Err(RpcError::new(UNAUTHORIZED, "Invalid token"))
+        }
+    } else if !state.authenticated {
+        Err(RpcError::new(UNAUTHORIZED, "Not authenticated"))
+    } else if request.method == "subscribe" {
+        if state.events.is_none() {
+            state.events = Some(daemon.events.subscribe());
+        }
+        Ok(Value::Null)
+    } else {
+        daemon.handle_request(&request.method, request.params)
+    };
+
+    // Requests without an id are notifications and don't get a response
+    if request.id.is_null() {
+        return None;
+    }
+
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": request.id, "result": result }).to_string(),
+        Err(error) => error_response(request.id, error),
+    })
+}
+
+fn error_response(id: Value, error: RpcError) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    }).to_string()
+}
+
+async fn write_line(write: &mut (impl AsyncWriteExt + Unpin), line: &str) -> std::io::Result<()> {
+    write.write_all(line.as_bytes()).await?;
+    write.write_all(b"\n").await?;
+    write.flush().await
+}
@@ -0,0 +1,118 @@
+use std::{
+    fs::{File, OpenOptions, TryLockError}, io::{BufRead, BufReader, Read, Write}, net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream}, path::Path, time::Duration
+};
+
+use tokio::sync::mpsc::UnboundedSender;
+
+// Links clicked while the launcher is open are passed to a new process by the OS. The first launcher window holds
+// a lock and listens on a local socket, later processes hand their link to it and exit instead of opening a second
+// launcher. macOS already sends links to the running application, see `on_open_urls`
+
+const LOCK_FILE: &str = "links.lock";
+// The port is kept next to the lock, since a locked file can't be read by other processes on Windows
+const PORT_FILE: &str = "links.port";
+// Share codes are links too, so this is much longer than any other link
+const MAX_LINK_LENGTH: u64 = 1024 * 1024;
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+// The launcher holding the lock may not have written its port yet if both were started at the same time
+const CONNECT_ATTEMPTS: usize = 10;
+
+// Returns true if the link was handed to the launcher that is already running, in which case this process should
+// exit. Otherwise links sent by later processes are forwarded to `open_link_send`
+pub fn forward_or_listen(launcher_dir: &Path, link: Option<&str>, open_link_send: UnboundedSender<String>) -> bool {
+    let lock = match OpenOptions::new().read(true).write(true).create(true).truncate(false).open(launcher_dir.join(LOCK_FILE)) {
+        Ok(lock) => lock,
+        Err(error) => {
+            log::error!("Unable to open {LOCK_FILE}: {error}");
+            return false;
+        },
+    };
+
+    match lock.try_lock() {
+        Ok(()) => {
+            if let Err(error) = listen(launcher_dir, lock, open_link_send) {
+                log::error!("Unable to listen for links from other launcher processes: {error}");
+            }
+            false
+        },
+        Err(TryLockError::WouldBlock) => {
+            // Without a link there is nothing to hand off, a second launcher is opened like before
+            let Some(link) = link else {
+                return false;
+            };
+            match forward(launcher_dir, link) {
+                Ok(()) => {
+                    log::info!("Sent link to the launcher that is already running");
+                    true
+                },
+                Err(error) => {
+                    log::warn!("Unable to send link to the launcher that is already running, opening it here instead: {error}");
+                    false
+                },
+            }
+        },
+        Err(TryLockError::Error(error)) => {
+            log::error!("Unable to lock {LOCK_FILE}: {error}");
+            false
+        },
+    }
+}
+
+fn listen(launcher_dir: &Path, lock: File, open_link_send: UnboundedSender<String>) -> std::io::Result<()> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let port = listener.local_addr()?.port();
+    std::fs::write(launcher_dir.join(PORT_FILE), port.to_string())?;
+
+    std::thread::spawn(move || {
+        // The lock is held for as long as the process runs
+        let _lock = lock;
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(error) => {
+                    log::error!("Unable to accept link connection: {error}");
+                    continue;
+                },
+            };
+            match read_link(stream) {
+                Ok(link) if !link.is_empty() => {
+                    if open_link_send.send(link).is_err() {
+                        return;
+                    }
+                },
+                Ok(_) => {},
+                Err(error) => log::warn!("Unable to read link from another launcher process: {error}"),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+fn read_link(stream: TcpStream) -> std::io::Result<String> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut link = String::new();
+    BufReader::new(stream.take(MAX_LINK_LENGTH)).read_line(&mut link)?;
+    Ok(link.trim().to_string())
+}
+
+fn forward(launcher_dir: &Path, link: &str) -> std::io::Result<()> {
+    let mut last_error = None;
+    for _ in 0..CONNECT_ATTEMPTS {
+        let port = std::fs::read_to_string(launcher_dir.join(PORT_FILE))
+            .ok()
+            .and_then(|port| port.trim().parse::<u16>().ok());
+        if let Some(port) = port {
+            match TcpStream::connect_timeout(&SocketAddr::from((Ipv4Addr::LOCALHOST, port)), READ_TIMEOUT) {
+                Ok(mut stream) => {
+                    stream.write_all(link.as_bytes())?;
+                    stream.write_all(b"\n")?;
+                    return stream.flush();
+                },
+                Err(error) => last_error = Some(error),
+            }
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    Err(last_error.unwrap_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, format!("{PORT_FILE} is missing"))))
+}
@@ -2,83 +2,124 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::fmt::Write;
 use std::time::SystemTime;
 
-use bridge::message::MessageToFrontend;
-use bridge::modal_action::{ModalAction, ProgressTrackerFinishType};
+use backend::data_location::LauncherLocation;
+use bridge::instance::InstanceID;
+use bridge::message::{MessageToBackend, MessageToFrontend};
+use bridge::modal_action::{ModalAction, ProgressTracker, ProgressTrackerFinishType};
 use clap::Parser;
 use fern::colors::ColoredLevelConfig;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use native_dialog::DialogBuilder;
 use parking_lot::RwLock;
+use uuid::Uuid;
 
 #[derive(Parser, Debug)]
 #[command()]
 struct Args {
-    /// Instance to launch, instead of opening the launcher
+    /// Uuid or name of the instance to launch, instead of opening the launcher
     #[arg(long)]
     run_instance: Option<String>,
+    /// Uuid of the instance to launch. Used by desktop shortcuts, since unlike the name it never changes
+    #[arg(long, conflicts_with = "run_instance")]
+    run_instance_id: Option<Uuid>,
+    /// Uuid or name of the instance to download all game files for without launching it, so it can be played offline
+    /// later
+    #[arg(long, conflicts_with_all = ["run_instance", "run_instance_id"])]
+    prepare_instance: Option<String>,
+    /// Log in by entering a code shown in the terminal on any device, instead of a browser redirect. Remembered for
+    /// later logins
+    #[arg(long)]
+    device_code_login: bool,
+    /// Run the backend without a window, controlled over JSON-RPC on a local socket
+    #[arg(long)]
+    daemon: bool,
+    /// Port for the daemon to listen on, a random free port is used by default
+    #[arg(long, default_value_t = 0)]
+    daemon_port: u16,
+    /// Address to serve Prometheus metrics on at /metrics while running as a daemon, e.g. 127.0.0.1:9464
+    #[arg(long, requires = "daemon")]
+    metrics_address: Option<SocketAddr>,
+    /// Read newline delimited JSON-RPC requests from stdin and write responses and events to stdout
+    #[arg(long, conflicts_with_all = ["run_instance", "run_instance_id", "prepare_instance", "daemon"])]
+    json_io: bool,
+    /// Directory to store launcher data in, instead of the platform data directory or the one chosen in the settings
+    #[arg(long)]
+    data_dir: Option<PathBuf>,
+    /// Record all messages between the interface and the backend into the replays folder, for reproducing bugs
+    #[arg(long)]
+    record_replay: bool,
+    /// Send the recorded messages of a replay file to the backend again, printing everything it sends back
+    #[arg(long, conflicts_with_all = ["run_instance", "run_instance_id", "prepare_instance", "daemon", "json_io", "record_replay"])]
+    replay: Option<PathBuf>,
+    /// A pandora:// or modrinth:// link to open in the launcher, passed by the OS when one is clicked
+    #[arg(conflicts_with_all = ["run_instance", "run_instance_id", "prepare_instance", "daemon", "json_io", "replay"])]
+    link: Option<String>,
 }
 
+pub mod daemon;
+pub mod link_handoff;
 pub mod panic;
+pub mod replay;
 
 fn main() {
     let args = Args::parse();
 
-    let data_dir = if let Some(portable_dir) = get_portable_dir() {
-        portable_dir
+    let location = LauncherLocation::resolve(args.data_dir, get_portable_dir());
+    _ = std::env::set_current_dir(&location.launcher_dir);
+
+    // Stdout is reserved for the protocol when scripting the launcher
+    let console: fern::Output = if args.json_io {
+        std::io::stderr().into()
     } else {
-        let base_dirs = directories::BaseDirs::new().unwrap();
-        base_dirs.data_dir().into()
+        std::io::stdout().into()
     };
-
-    let launcher_dir = data_dir.join("PandoraLauncher");
-    _ = std::env::set_current_dir(&launcher_dir);
-
-    let log_path = launcher_dir.join("launcher.log");
-    if log_path.exists() {
-        let old_log_path = launcher_dir.join("launcher.log.old");
-        _ = std::fs::rename(log_path, old_log_path);
-    }
-
-    if let Err(error) = setup_logging(log::LevelFilter::Debug) {
+    if let Err(error) = setup_logging(log::LevelFilter::Debug, console, location.launcher_dir.join("logs")) {
         eprintln!("Unable to enable logging: {error:?}");
     }
 
-    log::debug!("DEBUG logging enabled");
-    log::trace!("TRACE logging enabled");
-
     panic::install_logging_hook();
 
-    if let Some(run_instance) = args.run_instance {
-        let (backend_recv, backend_handle, mut frontend_recv, frontend_handle) = bridge::handle::create_pair();
-
-        backend::start(launcher_dir.clone(), frontend_handle, backend_handle.clone(), backend_recv);
-
-        while let Some(message) = frontend_recv.try_recv() {
-            if let MessageToFrontend::InstanceAdded { id, name, .. } = message {
-                if name.as_str() == run_instance.as_str() {
-                    println!("Starting instance {}", run_instance);
-                    let modal_action = ModalAction::default();
-                    backend_handle.send(bridge::message::MessageToBackend::StartInstance {
-                        id,
-                        quick_play: None,
-                        modal_action: modal_action.clone()
-                    });
-                    run_modal_action(modal_action);
-                    std::thread::sleep(std::time::Duration::from_millis(100));
-                    return;
-                }
-            }
+    if args.record_replay {
+        let timestamp = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+        let path = location.launcher_dir.join("replays").join(format!("replay-{timestamp}.jsonl"));
+        match bridge::replay::start_recording(&path, option_env!("PANDORA_RELEASE_VERSION")) {
+            Ok(()) => log::info!("Recording replay to {}", path.display()),
+            Err(error) => log::error!("Unable to start recording replay: {error}"),
         }
+    }
 
-        show_error(format!("Unable to find instance {}", run_instance));
-        std::process::exit(1);
+    if let Some(replay) = args.replay {
+        if let Err(error) = replay::run(location, &replay) {
+            show_error(error);
+            std::process::exit(1);
+        }
+    } else if let Some(run_instance) = args.run_instance.or(args.run_instance_id.map(|id| id.to_string())) {
+        run_instance_action(location, &run_instance, "Starting instance", args.device_code_login, |id, modal_action| {
+            MessageToBackend::StartInstance {
+                id,
+                quick_play: None,
+                modal_action,
+            }
+        });
+    } else if let Some(prepare_instance) = args.prepare_instance {
+        let success = run_instance_action(location, &prepare_instance, "Downloading files for", args.device_code_login, |id, modal_action| {
+            MessageToBackend::PrepareInstance { id, modal_action }
+        });
+        if !success {
+            std::process::exit(1);
+        }
+    } else if args.daemon {
+        daemon::run(location, args.daemon_port, args.metrics_address);
+    } else if args.json_io {
+        daemon::run_json_io(location);
     } else {
-        run_gui(launcher_dir);
+        run_gui(location, args.link);
     }
 }
 
@@ -92,7 +133,49 @@ fn show_error(error: String) {
         .show();
 }
 
-fn run_modal_action(modal_action: ModalAction) {
+// Runs an action for the instance with the given uuid or name in the terminal, returning whether it succeeded
+fn run_instance_action(
+    location: LauncherLocation,
+    instance: &str,
+    description: &str,
+    device_code_login: bool,
+    create_message: impl FnOnce(InstanceID, ModalAction) -> MessageToBackend,
+) -> bool {
+    let (backend_recv, backend_handle, mut frontend_recv, frontend_handle) = bridge::handle::create_pair();
+
+    backend::start(location, frontend_handle, backend_handle.clone(), backend_recv);
+
+    if device_code_login {
+        backend_handle.send(MessageToBackend::SetDeviceCodeLogin { value: true });
+    }
+
+    let mut instances = Vec::new();
+    while let Some(message) = frontend_recv.try_recv() {
+        if let MessageToFrontend::InstanceAdded { id, name, configuration, .. } = message {
+            instances.push((id, name, configuration));
+        }
+    }
+
+    // Favorites are checked first, so that they win when the name only matches when ignoring case
+    instances.sort_by_key(|(_, _, configuration)| !configuration.favorite);
+    let found = instances.iter()
+        .filter_map(|(id, name, configuration)| Some((configuration.reference_match(name, instance)?, *id, name)))
+        .min_by_key(|(rank, _, _)| *rank);
+
+    let Some((_, id, name)) = found else {
+        show_error(format!("Unable to find instance {}", instance));
+        std::process::exit(1);
+    };
+
+    println!("{} {}", description, name);
+    let modal_action = ModalAction::default();
+    backend_handle.send(create_message(id, modal_action.clone()));
+    let success = run_modal_action(modal_action);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    success
+}
+
+fn run_modal_action(modal_action: ModalAction) -> bool {
     let m = MultiProgress::new();
     let sty = ProgressStyle::with_template(
         "[{elapsed_precise}] {bar:40.cyan/blue} {msg}",
@@ -103,12 +186,19 @@ fn run_modal_action(modal_action: ModalAction) {
     let mut opened = HashSet::new();
     let mut progress_bars = HashMap::new();
 
+    let overall = m.add(ProgressBar::new(1000));
+    overall.set_style(sty.clone());
+    overall.set_message("Overall");
+
     loop {
         std::thread::sleep(std::time::Duration::from_millis(100));
 
         if let Some(error) = &*modal_action.error.read().unwrap() {
+            if let Some(visit_url) = &*modal_action.visit_url.read().unwrap() {
+                _ = m.println(format!("{}: {}", visit_url.message, visit_url.url));
+            }
             show_error(error.to_string());
-            return;
+            return false;
         }
 
         if modal_action.refcnt() <= 1 {
@@ -116,11 +206,16 @@ fn run_modal_action(modal_action: ModalAction) {
         }
 
         if modal_action.get_finished_at().is_some() {
-            return;
+            return true;
         }
 
         if let Some(visit_url) = &*modal_action.visit_url.write().unwrap() {
-            if opened.insert(visit_url.url.clone()) {
+            if let Some(code) = &visit_url.code {
+                // Entering the code works from any device, so there's no need for a browser on this machine
+                if opened.insert(code.clone()) {
+                    _ = m.println(format!("To continue, go to {} and enter the code {}", visit_url.url, code));
+                }
+            } else if opened.insert(visit_url.url.clone()) {
                 _ = m.println(format!("Open this URL in your browser to continue: {}", visit_url.url));
                 let open = DialogBuilder::message()
                     .set_title("Open URL")
@@ -131,39 +226,77 @@ fn run_modal_action(modal_action: ModalAction) {
                 if open {
                     _ = open::that_detached(&*visit_url.url);
                 } else {
-                    return;
+                    return false;
                 }
             }
         }
 
+        if let Some(progress) = modal_action.trackers.get_overall_float() {
+            overall.set_position((progress * 1000.0) as u64);
+            match modal_action.trackers.get_overall_eta() {
+                Some(eta) => overall.set_message(format!("Overall, about {}s left", eta.as_secs().max(1))),
+                None => overall.set_message("Overall"),
+            }
+        }
+
         let trackers = modal_action.trackers.trackers.read().unwrap();
+        let mut previous = overall.clone();
         for tracker in &*trackers {
-            let id = tracker.id();
-
-            let pb = progress_bars.entry(id).or_insert_with(|| {
-                let pb = m.add(ProgressBar::new(200));
-                pb.set_style(sty.clone());
-                pb
-            });
-
-            if pb.is_finished() && tracker.get_finished_at().is_some() {
-                continue;
-            }
+            previous = update_progress_bar(tracker, 0, &previous, &m, &sty, &mut progress_bars);
+        }
+        drop(trackers);
+    }
+}
 
+// Updates the bar for the tracker and its children, which are placed below it in order and indented. Returns the
+// last bar so that the next tracker can be placed after it
+fn update_progress_bar(
+    tracker: &ProgressTracker,
+    depth: usize,
+    previous: &ProgressBar,
+    m: &MultiProgress,
+    sty: &ProgressStyle,
+    progress_bars: &mut HashMap<usize, ProgressBar>,
+) -> ProgressBar {
+    let pb = progress_bars.entry(tracker.id()).or_insert_with(|| {
+        let pb = m.insert_after(previous, ProgressBar::new(200));
+        pb.set_style(sty.clone());
+        pb
+    }).clone();
+
+    if !(pb.is_finished() && tracker.get_finished_at().is_some()) {
+        // Parents are shown as a percentage so that the progress of their children is included
+        if tracker.children().is_empty() {
             let (count, total) = tracker.get();
             pb.set_length(total as u64);
             pb.set_position(count as u64);
-            pb.set_message(tracker.get_title().to_string());
+        } else {
+            pb.set_length(1000);
+            pb.set_position((tracker.get_float().unwrap_or(0.0) * 1000.0) as u64);
+        }
+        pb.set_message(format!("{}{}", "  ".repeat(depth), tracker.get_title()));
 
-            if tracker.get_finished_at().is_some() {
-                pb.finish();
-            }
+        if tracker.get_finished_at().is_some() {
+            pb.finish();
         }
-        drop(trackers);
     }
+
+    let mut last = pb;
+    for child in tracker.children() {
+        last = update_progress_bar(&child, depth + 1, &last, m, sty, progress_bars);
+    }
+    last
 }
 
-fn run_gui(launcher_dir: PathBuf) {
+fn run_gui(location: LauncherLocation, link: Option<String>) {
+    let (open_link_send, open_link_recv) = tokio::sync::mpsc::unbounded_channel();
+    if link_handoff::forward_or_listen(&location.launcher_dir, link.as_deref(), open_link_send.clone()) {
+        return;
+    }
+    if let Some(link) = link {
+        _ = open_link_send.send(link);
+    }
+
     let panic_message = Arc::new(RwLock::new(None));
     let deadlock_message = Arc::new(RwLock::new(None));
 
@@ -201,11 +334,11 @@ fn run_gui(launcher_dir: PathBuf) {
         }
     });
 
-    backend::start(launcher_dir.clone(), frontend_handle, backend_handle.clone(), backend_recv);
-    frontend::start(launcher_dir.clone(), panic_message, deadlock_message, backend_handle, frontend_recv);
+    backend::start(location.clone(), frontend_handle, backend_handle.clone(), backend_recv);
+    frontend::start(location.launcher_dir, location.cache_dir, panic_message, deadlock_message, backend_handle, frontend_recv, open_link_send, open_link_recv);
 }
 
-fn setup_logging(level: log::LevelFilter) -> Result<(), fern::InitError> {
+fn setup_logging(level: log::LevelFilter, console: fern::Output, logs_dir: PathBuf) -> Result<(), fern::InitError> {
     let base_config = fern::Dispatch::new()
         .level_for("pandora_launcher", level)
         .level_for("auth", level)
@@ -217,18 +350,15 @@ fn setup_logging(level: log::LevelFilter) -> Result<(), fern::InitError> {
     let colors_line = ColoredLevelConfig::new().info(fern::colors::Color::BrightWhite);
 
     let file_config = fern::Dispatch::new()
-        .format(|out, message, record| {
-            out.finish(format_args!(
-                "[{time} {level} {target}] {message}",
-                time = humantime::format_rfc3339_seconds(SystemTime::now()),
-                level = record.level(),
-                target = record.target(),
-                message = message
-            ))
-        })
-        .chain(fern::log_file("launcher.log")?);
+        .format(format_plain)
+        .chain(fern::Output::writer(Box::new(backend::log_files::RotatingLogFile::new(logs_dir)?), "\n"));
+
+    // Recent lines are kept in memory for problem reports
+    let capture_config = fern::Dispatch::new()
+        .format(format_plain)
+        .chain(fern::Output::call(backend::log_capture::capture));
 
-    let stdout_config = fern::Dispatch::new()
+    let console_config = fern::Dispatch::new()
         .format(move |out, message, record| {
             out.finish(format_args!(
                 "{color_line}[{time} {level} {target}{color_line}] {message}\x1B[0m",
@@ -242,16 +372,30 @@ fn setup_logging(level: log::LevelFilter) -> Result<(), fern::InitError> {
                 message = message
             ))
         })
-        .chain(std::io::stdout());
+        .chain(console);
 
     base_config
         .chain(file_config)
-        .chain(stdout_config)
+        .chain(capture_config)
+        .chain(console_config)
         .apply()?;
 
+    // Debug logging is enabled by the backend once its configuration has been loaded
+    backend::log_files::set_debug_logging(false);
+
     Ok(())
 }
 
+fn format_plain(out: fern::FormatCallback, message: &std::fmt::Arguments, record: &log::Record) {
+    out.finish(format_args!(
+        "[{time} {level} {target}] {message}",
+        time = humantime::format_rfc3339_seconds(SystemTime::now()),
+        level = record.level(),
+        target = record.target(),
+        message = message
+    ))
+}
+
 fn get_portable_dir() -> Option<PathBuf> {
     let current_exe = std::env::current_exe().ok()?;
     let file_name = current_exe.file_name()?;
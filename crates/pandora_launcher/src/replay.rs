@@ -0,0 +1,110 @@
+use std::{collections::HashMap, io::BufRead, path::Path, time::{Duration, Instant}};
+
+use backend::data_location::LauncherLocation;
+use bridge::{
+    handle::FrontendReceiver,
+    instance::InstanceID,
+    replay::{ReplayDirection, ReplayEntry, ReplayHeader, ReplayInstanceId, ReplayMessage, REPLAY_VERSION},
+};
+use ustr::Ustr;
+
+// Long idle periods in the recording are skipped over
+const MAX_WAIT: Duration = Duration::from_secs(5);
+// Time given to the backend to finish handling the last messages before exiting
+const FINAL_WAIT: Duration = Duration::from_secs(3);
+
+#[derive(Default)]
+struct InstanceNames {
+    recorded: HashMap<ReplayInstanceId, Ustr>,
+    live: HashMap<Ustr, InstanceID>,
+}
+
+impl InstanceNames {
+    fn resolve(&self, id: ReplayInstanceId) -> Option<InstanceID> {
+        self.live.get(self.recorded.get(&id)?).copied()
+    }
+}
+
+// Sends the backend messages of a recorded session to a fresh backend with the same timing, printing everything the
+// backend sends back so that it can be compared against the recording
+pub fn run(location: LauncherLocation, path: &Path) -> Result<(), String> {
+    let file = std::fs::File::open(path).map_err(|error| format!("Unable to open replay {}: {error}", path.display()))?;
+    let mut lines = std::io::BufReader::new(file).lines();
+
+    let header = lines.next()
+        .and_then(Result::ok)
+        .and_then(|line| serde_json::from_str::<ReplayHeader>(&line).ok())
+        .ok_or_else(|| format!("{} is not a replay file", path.display()))?;
+    if header.replay_version != REPLAY_VERSION {
+        return Err(format!(
+            "{} was recorded by {} with replay format {}, this launcher only supports format {}",
+            path.display(),
+            header.launcher_version.as_deref().unwrap_or("an unknown version"),
+            header.replay_version,
+            REPLAY_VERSION,
+        ));
+    }
+
+    let (backend_recv, backend_handle, mut frontend_recv, frontend_handle) = bridge::handle::create_pair();
+    backend::start(location, frontend_handle, backend_handle.clone(), backend_recv);
+
+    let mut names = InstanceNames::default();
+    let started = Instant::now();
+    let mut skipped_time = Duration::ZERO;
+    let mut previous_at = Duration::ZERO;
+
+    for (line_number, line) in lines.enumerate() {
+        let line = line.map_err(|error| format!("Unable to read replay: {error}"))?;
+        let entry: ReplayEntry = match serde_json::from_str(&line) {
+            Ok(entry) => entry,
+            Err(error) => {
+                println!("!! Skipping invalid entry on line {}: {error}", line_number + 2);
+                continue;
+            },
+        };
+
+        let at = Duration::from_millis(entry.at_millis);
+        skipped_time += at.saturating_sub(previous_at).saturating_sub(MAX_WAIT);
+        previous_at = at;
+        wait_until(started + at.saturating_sub(skipped_time), &mut frontend_recv, &mut names);
+
+        match (entry.direction, entry.message) {
+            (ReplayDirection::ToFrontend, Some(ReplayMessage::InstanceNamed { id, name })) => {
+                names.recorded.insert(id, name);
+            },
+            (ReplayDirection::ToFrontend, _) => {},
+            (ReplayDirection::ToBackend, Some(message)) => {
+                match message.into_backend_message(|id| names.resolve(id)) {
+                    Some(message) => {
+                        println!("-> {}", bridge::replay::summarize(&message));
+                        backend_handle.send(message);
+                    },
+                    None => println!("!! Skipping message for an unknown instance: {}", entry.summary),
+                }
+            },
+            (ReplayDirection::ToBackend, None) => println!("!! Unable to replay: {}", entry.summary),
+        }
+    }
+
+    wait_until(Instant::now() + FINAL_WAIT, &mut frontend_recv, &mut names);
+    Ok(())
+}
+
+fn wait_until(deadline: Instant, frontend_recv: &mut FrontendReceiver, names: &mut InstanceNames) {
+    loop {
+        while let Some(message) = frontend_recv.try_recv() {
+            if let Some(ReplayMessage::InstanceNamed { id, name }) = ReplayMessage::from_frontend_message(&message) {
+                names.live.insert(name, InstanceID { index: id.index, generation: id.generation });
+            }
+            if !matches!(message, bridge::message::MessageToFrontend::Refresh) {
+                println!("<- {}", bridge::replay::summarize(&message));
+            }
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return;
+        }
+        std::thread::sleep((deadline - now).min(Duration::from_millis(50)));
+    }
+}
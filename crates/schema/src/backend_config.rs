@@ -1,12 +1,171 @@
+use std::{path::PathBuf, sync::Arc};
+
 use enumset::{EnumSet, EnumSetType};
 use serde::{Deserialize, Serialize};
 
+use crate::instance::InstanceDefaultsConfiguration;
+
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct BackendConfig {
     #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
     pub sync_targets: EnumSet<SyncTarget>,
     #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
     pub dont_open_game_output_when_launching: bool,
+    // Always copy content library files into instances instead of using reflinks or hard links
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub dont_link_content_files: bool,
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub instance_defaults: InstanceDefaultsConfiguration,
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub natives_repositories: Vec<Arc<str>>,
+    // Command used to share an instance with friends, `{port}` is replaced with the local port
+    #[serde(default, skip_serializing_if = "crate::skip_if_none", deserialize_with = "crate::try_deserialize")]
+    pub tunnel_command: Option<Arc<str>>,
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub debug_logging: bool,
+    // Log in by entering a code on another device instead of being redirected back from the browser
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub device_code_login: bool,
+    // Both are opt-in, metrics are only uploaded when they are also recorded
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub record_launch_metrics: bool,
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub upload_launch_metrics: bool,
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub parental_controls: ParentalControlsConfiguration,
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub parental_playtime: ParentalPlaytime,
+    // Instance folders which were moved out of the instances directory, e.g. onto another drive
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub external_instances: Vec<PathBuf>,
+    // Maximum number of instances running at the same time, unlimited when unset
+    #[serde(default, skip_serializing_if = "crate::skip_if_none", deserialize_with = "crate::try_deserialize")]
+    pub max_running_instances: Option<u32>,
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub memory_budget: MemoryBudgetMode,
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub event_stream: EventStreamConfiguration,
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub backups: BackupConfiguration,
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub update_channel: UpdateChannel,
+    // Set by "Skip this version" in the update prompt, only a newer version is offered afterwards
+    #[serde(default, skip_serializing_if = "crate::skip_if_none", deserialize_with = "crate::try_deserialize")]
+    pub skipped_update_version: Option<Arc<str>>,
+    // Unix timestamp until which updates aren't offered, set by "Remind me later" in the update prompt
+    #[serde(default, skip_serializing_if = "crate::skip_if_none", deserialize_with = "crate::try_deserialize")]
+    pub update_reminder_after: Option<i64>,
+    // Chosen randomly on the first update check, decides whether this launcher is part of a staged rollout
+    #[serde(default, skip_serializing_if = "crate::skip_if_none", deserialize_with = "crate::try_deserialize")]
+    pub update_rollout_seed: Option<u32>,
+}
+
+// Where scheduled backups of instances are written to and how many are kept
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct BackupConfiguration {
+    // Defaults to the backups folder of the launcher, can be a folder synced by another program or a network drive
+    #[serde(default, skip_serializing_if = "crate::skip_if_none", deserialize_with = "crate::try_deserialize")]
+    pub directory: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "crate::skip_if_none", deserialize_with = "crate::try_deserialize")]
+    pub keep: Option<u32>,
+}
+
+impl BackupConfiguration {
+    pub const DEFAULT_KEEP: u32 = 7;
+
+    pub fn keep(&self) -> u32 {
+        self.keep.unwrap_or(Self::DEFAULT_KEEP)
+    }
+}
+
+// Opt-in WebSocket on localhost which streams launcher events to companion tools, e.g. OBS overlays
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct EventStreamConfiguration {
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "crate::skip_if_none", deserialize_with = "crate::try_deserialize")]
+    pub port: Option<u16>,
+    // Clients must pass this as the `token` query parameter, generated the first time the stream is enabled
+    #[serde(default, skip_serializing_if = "crate::skip_if_none", deserialize_with = "crate::try_deserialize")]
+    pub token: Option<Arc<str>>,
+}
+
+impl EventStreamConfiguration {
+    pub const DEFAULT_PORT: u16 = 25595;
+
+    pub fn port(&self) -> u16 {
+        self.port.unwrap_or(Self::DEFAULT_PORT)
+    }
+}
+
+// What happens when the maximum heap of every running instance plus the one being launched exceeds physical memory
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MemoryBudgetMode {
+    Ignore,
+    #[default]
+    Warn,
+    Block,
+}
+
+// Which update manifest is checked for new launcher versions, beta and nightly builds are published as pre-releases
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, strum::EnumIter)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+    Nightly,
+}
+
+impl UpdateChannel {
+    pub fn name(self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "Stable",
+            UpdateChannel::Beta => "Beta",
+            UpdateChannel::Nightly => "Nightly",
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ParentalControlsConfiguration {
+    // Hex encoded PBKDF2-HMAC-SHA256 of the PIN, restrictions are only enforced while this is set
+    #[serde(default, skip_serializing_if = "crate::skip_if_none", deserialize_with = "crate::try_deserialize")]
+    pub pin_hash: Option<Arc<str>>,
+    #[serde(default, skip_serializing_if = "crate::skip_if_none", deserialize_with = "crate::try_deserialize")]
+    pub pin_salt: Option<Arc<str>>,
+    // PBKDF2 iterations used for the hash. Hashes without it are a single sha1 of the salt followed by the PIN, from
+    // before PBKDF2 was used, and are upgraded the next time the PIN is entered
+    #[serde(default, skip_serializing_if = "crate::skip_if_none", deserialize_with = "crate::try_deserialize")]
+    pub pin_iterations: Option<u32>,
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub restrictions: ParentalRestrictions,
+}
+
+impl ParentalControlsConfiguration {
+    pub fn is_enabled(&self) -> bool {
+        self.pin_hash.is_some()
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct ParentalRestrictions {
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub block_instance_creation: bool,
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub block_content_install: bool,
+    #[serde(default, skip_serializing_if = "crate::skip_if_none", deserialize_with = "crate::try_deserialize")]
+    pub daily_playtime_minutes: Option<u32>,
+}
+
+// Time spent playing on the given local day, reset when the day changes
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ParentalPlaytime {
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub day: Arc<str>,
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub seconds: u64,
 }
 
 #[derive(Debug, enum_map::Enum, EnumSetType, strum::EnumIter)]
@@ -1,5 +1,6 @@
 use std::{collections::HashMap, sync::Arc};
 
+use indexmap::IndexMap;
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug)]
@@ -8,20 +9,42 @@ pub struct FabricModJson {
     pub id: Arc<str>,
     pub version: Arc<str>,
     pub name: Option<Arc<str>>,
-    // pub description: Option<Arc<str>>,
+    pub description: Option<Arc<str>>,
     pub authors: Option<Vec<Person>>,
     pub icon: Option<Icon>,
-    // #[serde(alias = "requires")]
-    // pub depends: Option<HashMap<Arc<str>, Dependency>>,
-    // pub breaks: Option<HashMap<Arc<str>, Dependency>>,
+    #[serde(default, alias = "requires", deserialize_with = "crate::try_deserialize")]
+    pub depends: IndexMap<Arc<str>, VersionPredicates>,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub recommends: IndexMap<Arc<str>, VersionPredicates>,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub breaks: IndexMap<Arc<str>, VersionPredicates>,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub provides: Vec<Arc<str>>,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub jars: Vec<NestedJar>,
 }
 
-// #[derive(Deserialize, Debug)]
-// #[serde(untagged)]
-// enum Dependency {
-//     Single(Arc<str>),
-//     Multiple(Vec<Arc<str>>)
-// }
+#[derive(Deserialize, Debug)]
+pub struct NestedJar {
+    pub file: Arc<str>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum VersionPredicates {
+    Single(Arc<str>),
+    Multiple(Vec<Arc<str>>),
+}
+
+impl VersionPredicates {
+    // Multiple predicates are alternatives, any of them can be satisfied
+    pub fn to_range_string(&self) -> Arc<str> {
+        match self {
+            VersionPredicates::Single(predicate) => predicate.clone(),
+            VersionPredicates::Multiple(predicates) => predicates.join(" || ").into(),
+        }
+    }
+}
 
 #[derive(Deserialize, Debug)]
 #[serde(untagged)]
@@ -166,5 +166,45 @@ impl LegacyVersionInfo {
 #[derive(Debug)]
 pub struct ForgeMavenManifest(pub Vec<Ustr>);
 
+impl ForgeMavenManifest {
+    pub fn versions_for_minecraft(&self, minecraft_version: &str) -> Vec<Ustr> {
+        loader_versions_for_minecraft(&self.0, minecraft_version, false)
+    }
+}
+
 #[derive(Debug)]
 pub struct NeoforgeMavenManifest(pub Vec<Ustr>);
+
+impl NeoforgeMavenManifest {
+    pub fn versions_for_minecraft(&self, minecraft_version: &str) -> Vec<Ustr> {
+        loader_versions_for_minecraft(&self.0, minecraft_version, true)
+    }
+}
+
+// Loader versions which support the Minecraft version, newest first
+pub fn loader_versions_for_minecraft(loader_versions: &[Ustr], minecraft_version: &str, neoforge_versioning: bool) -> Vec<Ustr> {
+    let mut minecraft_version_parts = VersionFragment::string_to_parts(minecraft_version);
+    if neoforge_versioning {
+        // 1.21.5 -> 21.5
+        // 25w14craftmine -> 0.25w14craftmine
+        // 1.21 -> 21.0
+        // 26.1 -> 26.1.0
+        if minecraft_version_parts[0] == VersionFragment::String("25w14craftmine".into()) {
+            minecraft_version_parts.insert(0, VersionFragment::Number(0))
+        } else {
+            if minecraft_version_parts.len() < 3 {
+                minecraft_version_parts.push(VersionFragment::Number(0))
+            }
+            if minecraft_version_parts[0] == VersionFragment::Number(1) {
+                minecraft_version_parts.remove(0);
+            }
+        }
+    }
+
+    let mut versions: Vec<_> = loader_versions.iter()
+        .map(|version| (VersionFragment::string_to_parts(version), *version))
+        .filter(|(parts, _)| parts.starts_with(&minecraft_version_parts))
+        .collect();
+    versions.sort_by(|(a, _), (b, _)| b.cmp(a));
+    versions.into_iter().map(|(_, version)| version).collect()
+}
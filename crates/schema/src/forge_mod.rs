@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use serde::Deserialize;
 
@@ -6,7 +6,9 @@ use crate::fabric_mod::Person;
 
 #[derive(Deserialize, Debug)]
 pub struct ModsToml {
-    pub mods: Vec<ModsTomlMod>
+    pub mods: Vec<ModsTomlMod>,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub dependencies: HashMap<Arc<str>, Vec<ModsTomlDependency>>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -17,6 +19,29 @@ pub struct ModsTomlMod {
     pub logo_file: Option<Arc<str>>,
     pub version: Option<Arc<str>>,
     pub authors: Option<Arc<str>>,
+    pub description: Option<Arc<str>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ModsTomlDependency {
+    pub mod_id: Arc<str>,
+    // Forge
+    pub mandatory: Option<bool>,
+    // NeoForge
+    pub r#type: Option<ModsTomlDependencyType>,
+    pub version_range: Option<Arc<str>>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ModsTomlDependencyType {
+    Required,
+    Optional,
+    Incompatible,
+    Discouraged,
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Deserialize, Debug)]
@@ -41,4 +66,8 @@ pub struct McModInfoMod {
     pub logo_file: Option<Arc<str>>,
     pub version: Option<Arc<str>>,
     pub author_list: Option<Vec<Person>>,
+    pub description: Option<Arc<str>>,
+    // Entries are in the form of "modid@[range]"
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub required_mods: Vec<Arc<str>>,
 }
@@ -1,31 +1,179 @@
-use std::{path::Path, sync::Arc};
+use std::{collections::{BTreeMap, BTreeSet}, path::Path, sync::Arc};
 
 use serde::{Deserialize, Serialize};
 use ustr::Ustr;
+use uuid::Uuid;
 
 use crate::loader::Loader;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct InstanceConfiguration {
+    // Stays the same when the instance is renamed or moved, so shortcuts and scripts refer to the instance by it.
+    // Instances created before this existed are given one when they are loaded
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_none")]
+    pub uuid: Option<Uuid>,
     pub minecraft_version: Ustr,
     pub loader: Loader,
     #[serde(default, skip_serializing_if = "crate::skip_if_none")]
     pub preferred_loader_version: Option<Ustr>,
-    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "is_default_memory_configuration")]
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_none")]
     pub memory: Option<InstanceMemoryConfiguration>,
-    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "is_default_jvm_flags_configuration")]
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_none")]
     pub jvm_flags: Option<InstanceJvmFlagsConfiguration>,
-    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "is_default_jvm_binary_configuration")]
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_none")]
     pub jvm_binary: Option<InstanceJvmBinaryConfiguration>,
     #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "is_default_linux_wrapper_configuration")]
     pub linux_wrapper: Option<InstanceLinuxWrapperConfiguration>,
     #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "is_default_system_libraries_configuration")]
     pub system_libraries: Option<InstanceSystemLibrariesConfiguration>,
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "is_default_legacy_fixes_configuration")]
+    pub legacy_fixes: Option<InstanceLegacyFixesConfiguration>,
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_none")]
+    pub resolution: Option<InstanceResolutionConfiguration>,
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_none")]
+    pub network_isolation: Option<InstanceNetworkIsolationConfiguration>,
+    // Saves and configs are backed up every night, see `BackupConfiguration`
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_default")]
+    pub scheduled_backups: bool,
     #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_none")]
     pub instance_fallback_icon: Option<Ustr>,
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_default")]
+    pub instance_type: InstanceType,
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_none")]
+    pub group: Option<Ustr>,
+    // Favorites are listed before other instances in the library and the tray
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_default")]
+    pub favorite: bool,
+    // Unix timestamp in seconds
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_none")]
+    pub last_played: Option<i64>,
+    // Mod ids of content that the user disabled, so that it stays disabled when it's installed again
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "BTreeSet::is_empty")]
+    pub disabled_content: BTreeSet<Arc<str>>,
+    // Named sets of content to disable, so different selections of mods can be used without duplicating the instance
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "BTreeMap::is_empty")]
+    pub content_profiles: BTreeMap<Arc<str>, InstanceContentProfile>,
+    // The profile that was applied last, the content may have been changed since
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_none")]
+    pub active_content_profile: Option<Arc<str>>,
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_none")]
+    pub version_tracking: Option<InstanceVersionTracking>,
+    // Names the instance had before being renamed, shortcuts created before instances had a uuid refer to these
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "BTreeSet::is_empty")]
+    pub previous_names: BTreeSet<Arc<str>>,
+}
+
+impl InstanceConfiguration {
+    pub fn new(minecraft_version: Ustr, loader: Loader) -> Self {
+        Self {
+            uuid: None,
+            minecraft_version,
+            loader,
+            preferred_loader_version: None,
+            memory: None,
+            jvm_flags: None,
+            jvm_binary: None,
+            linux_wrapper: None,
+            system_libraries: None,
+            legacy_fixes: None,
+            resolution: None,
+            network_isolation: None,
+            scheduled_backups: false,
+            instance_fallback_icon: None,
+            instance_type: InstanceType::default(),
+            group: None,
+            favorite: false,
+            last_played: None,
+            disabled_content: BTreeSet::new(),
+            content_profiles: BTreeMap::new(),
+            active_content_profile: None,
+            version_tracking: None,
+            previous_names: BTreeSet::new(),
+        }
+    }
+
+    // How closely a reference from a shortcut, script or setting matches this instance, lower is better. References
+    // are usually the uuid, but older ones are the name of the instance
+    pub fn reference_match(&self, name: &str, reference: &str) -> Option<u8> {
+        if let Some(uuid) = self.uuid && reference.parse::<Uuid>().is_ok_and(|reference| reference == uuid) {
+            Some(0)
+        } else if name == reference {
+            Some(1)
+        } else if self.previous_names.iter().any(|previous| &**previous == reference) {
+            Some(2)
+        } else if name.eq_ignore_ascii_case(reference) {
+            Some(3)
+        } else {
+            None
+        }
+    }
+
+    // The reference to store for this instance, falling back to the name until the instance has been given a uuid
+    pub fn reference(&self, name: &str) -> String {
+        match self.uuid {
+            Some(uuid) => uuid.to_string(),
+            None => name.to_string(),
+        }
+    }
+
+    // Values which are turned off don't replace the defaults. Configurations from before the defaults existed store
+    // these too, where they meant that nothing was changed
+    pub fn with_defaults(mut self, defaults: &InstanceDefaultsConfiguration) -> Self {
+        if !self.memory.is_some_and(|memory| memory.is_enabled()) {
+            self.memory = defaults.memory;
+        }
+        if !self.jvm_flags.as_ref().is_some_and(InstanceJvmFlagsConfiguration::is_enabled) {
+            self.jvm_flags = defaults.jvm_flags.clone();
+        }
+        if !self.jvm_binary.as_ref().is_some_and(InstanceJvmBinaryConfiguration::is_enabled) {
+            self.jvm_binary = defaults.jvm_binary.clone();
+        }
+        if !self.resolution.is_some_and(|resolution| resolution.is_enabled()) {
+            self.resolution = defaults.resolution;
+        }
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InstanceType {
+    #[default]
+    Client,
+    Server,
+}
+
+// Keeps an instance on the newest Minecraft version, upgrading it when a new version is released
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstanceVersionTracking {
+    pub channel: VersionTrackingChannel,
+    // Upgrade without asking the user first
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub automatic: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionTrackingChannel {
+    Release,
+    // Snapshots, pre-releases and releases, whichever is newest
+    Snapshot,
 }
 
-#[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+// Settings that instances inherit unless they have their own value. The Java runtime is chosen through `jvm_binary`
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstanceDefaultsConfiguration {
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_none")]
+    pub memory: Option<InstanceMemoryConfiguration>,
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_none")]
+    pub jvm_flags: Option<InstanceJvmFlagsConfiguration>,
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_none")]
+    pub jvm_binary: Option<InstanceJvmBinaryConfiguration>,
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_none")]
+    pub resolution: Option<InstanceResolutionConfiguration>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
 pub struct InstanceMemoryConfiguration {
     pub enabled: bool,
     pub min: u32,
@@ -35,6 +183,10 @@ pub struct InstanceMemoryConfiguration {
 impl InstanceMemoryConfiguration {
     pub const DEFAULT_MIN: u32 = 512;
     pub const DEFAULT_MAX: u32 = 4096;
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
 }
 
 impl Default for InstanceMemoryConfiguration {
@@ -47,41 +199,57 @@ impl Default for InstanceMemoryConfiguration {
     }
 }
 
-fn is_default_memory_configuration(config: &Option<InstanceMemoryConfiguration>) -> bool {
-    if let Some(config) = config {
-        !config.enabled
-            && config.min == InstanceMemoryConfiguration::DEFAULT_MIN
-            && config.max == InstanceMemoryConfiguration::DEFAULT_MAX
-    } else {
-        true
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InstanceResolutionConfiguration {
+    pub enabled: bool,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub fullscreen: bool,
+}
+
+impl InstanceResolutionConfiguration {
+    pub const DEFAULT_WIDTH: u32 = 854;
+    pub const DEFAULT_HEIGHT: u32 = 480;
+
+    // Fullscreen is applied without a custom size too
+    pub fn is_enabled(&self) -> bool {
+        self.enabled || self.fullscreen
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+impl Default for InstanceResolutionConfiguration {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            width: Self::DEFAULT_WIDTH,
+            height: Self::DEFAULT_HEIGHT,
+            fullscreen: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
 pub struct InstanceJvmFlagsConfiguration {
     pub enabled: bool,
     pub flags: Arc<str>,
 }
 
-fn is_default_jvm_flags_configuration(config: &Option<InstanceJvmFlagsConfiguration>) -> bool {
-    if let Some(config) = config {
-        !config.enabled && config.flags.trim_ascii().is_empty()
-    } else {
-        true
+impl InstanceJvmFlagsConfiguration {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
 pub struct InstanceJvmBinaryConfiguration {
     pub enabled: bool,
     pub path: Option<Arc<Path>>,
 }
 
-fn is_default_jvm_binary_configuration(config: &Option<InstanceJvmBinaryConfiguration>) -> bool {
-    if let Some(config) = config {
-        !config.enabled && config.path.is_none()
-    } else {
-        true
+impl InstanceJvmBinaryConfiguration {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
     }
 }
 
@@ -113,6 +281,54 @@ fn is_default_linux_wrapper_configuration(config: &Option<InstanceLinuxWrapperCo
     }
 }
 
+// Keeps the game offline, for children or for testing mods without the network getting in the way
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InstanceNetworkIsolationConfiguration {
+    // Multiplayer and Realms are disabled in the title screen, only for versions from 1.6 onwards
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub disable_multiplayer: bool,
+    // Connections made through Java's networking, which includes Realms and most mods, are sent to a proxy which
+    // doesn't exist
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub block_connections: bool,
+}
+
+impl InstanceNetworkIsolationConfiguration {
+    pub fn is_enabled(&self) -> bool {
+        self.disable_multiplayer || self.block_connections
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstanceContentProfile {
+    // Mod ids, or filenames for content without an id, of the mods and resource packs disabled by the profile
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub disabled: BTreeSet<Arc<str>>,
+}
+
+// Workarounds only applied to versions older than 1.6
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct InstanceLegacyFixesConfiguration {
+    // Skins were served from a server that no longer exists, a proxy redirects those requests to one that works
+    #[serde(default = "crate::default_true", deserialize_with = "crate::try_deserialize")]
+    pub use_skin_proxy: bool,
+}
+
+impl Default for InstanceLegacyFixesConfiguration {
+    fn default() -> Self {
+        Self {
+            use_skin_proxy: true,
+        }
+    }
+}
+
+fn is_default_legacy_fixes_configuration(config: &Option<InstanceLegacyFixesConfiguration>) -> bool {
+    if let Some(config) = config {
+        config.use_skin_proxy
+    } else {
+        true
+    }
+}
 
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
 pub struct InstanceSystemLibrariesConfiguration {
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use ustr::Ustr;
+
+use crate::loader::Loader;
+
+// One line of the append-only history file in the instance folder
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InstanceHistoryEntry {
+    // Unix timestamp in seconds
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub event: InstanceHistoryEvent,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InstanceHistoryEvent {
+    Launched,
+    ContentInstalled {
+        name: String,
+    },
+    ContentUpdated {
+        name: String,
+    },
+    ContentRemoved {
+        name: String,
+    },
+    SettingChanged {
+        setting: String,
+    },
+    MinecraftVersionChanged {
+        from: Ustr,
+        to: Ustr,
+    },
+    LoaderChanged {
+        loader: Loader,
+        #[serde(default, skip_serializing_if = "crate::skip_if_none")]
+        loader_version: Option<Ustr>,
+    },
+    BackupRestored,
+}
+
+impl InstanceHistoryEvent {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Launched => "Launched".to_string(),
+            Self::ContentInstalled { name } => format!("Installed {name}"),
+            Self::ContentUpdated { name } => format!("Updated {name}"),
+            Self::ContentRemoved { name } => format!("Removed {name}"),
+            Self::SettingChanged { setting } => format!("Changed {setting}"),
+            Self::MinecraftVersionChanged { from, to } => format!("Changed Minecraft version from {from} to {to}"),
+            Self::LoaderChanged { loader, loader_version: Some(loader_version) } => {
+                format!("Changed loader to {} {loader_version}", loader.name())
+            },
+            Self::LoaderChanged { loader, loader_version: None } => format!("Changed loader to {}", loader.name()),
+            Self::BackupRestored => "Restored a backup".to_string(),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use ustr::Ustr;
+
+use crate::{instance::{InstanceMemoryConfiguration, InstanceResolutionConfiguration, InstanceType}, loader::Loader};
+
+// Shared as a compressed code, so only content that can be downloaded from Modrinth is included. Share codes come from
+// anyone, so only settings which can't run anything on the importing computer are included, never e.g. jvm flags
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InstanceShare {
+    pub name: Arc<str>,
+    pub minecraft_version: Ustr,
+    pub loader: Loader,
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_none")]
+    pub preferred_loader_version: Option<Ustr>,
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_none")]
+    pub memory: Option<InstanceMemoryConfiguration>,
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_none")]
+    pub resolution: Option<InstanceResolutionConfiguration>,
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_default")]
+    pub instance_type: InstanceType,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub content: Vec<InstanceShareContent>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InstanceShareContent {
+    // Single letter names keep the code short
+    #[serde(rename = "p")]
+    pub project: Arc<str>,
+    // The newest version compatible with the instance is used when the exact version isn't known
+    #[serde(rename = "v", default, skip_serializing_if = "crate::skip_if_none")]
+    pub version: Option<Arc<str>>,
+}
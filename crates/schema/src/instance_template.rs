@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::instance::InstanceConfiguration;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InstanceTemplate {
+    pub configuration: InstanceConfiguration,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub content: Vec<InstanceTemplateContent>,
+    #[serde(default, deserialize_with = "crate::try_deserialize", skip_serializing_if = "crate::skip_if_none")]
+    pub options: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InstanceTemplateContent {
+    // Path relative to .minecraft, eg. `mods/sodium.jar` or `mods/sodium.jar.disabled`
+    pub path: Arc<str>,
+    pub sha1: Arc<str>,
+    #[serde(default, skip_serializing_if = "crate::skip_if_none")]
+    pub modrinth_project: Option<Arc<str>>,
+}
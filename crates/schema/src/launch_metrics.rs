@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use ustr::Ustr;
+
+use crate::loader::Loader;
+
+// Launches recorded on this computer, only kept when the user has opted in
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct LaunchMetrics {
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub launches: Vec<LaunchRecord>,
+    // Unix timestamp in seconds of the newest launch that has been uploaded
+    #[serde(default, skip_serializing_if = "crate::skip_if_default", deserialize_with = "crate::try_deserialize")]
+    pub uploaded_until: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LaunchRecord {
+    // Unix timestamp in seconds
+    pub started_at: i64,
+    // Only shown locally, never uploaded
+    pub instance: Ustr,
+    pub minecraft_version: Ustr,
+    pub loader: Loader,
+    pub outcome: LaunchOutcome,
+    // How long it took from pressing start until the game process was running
+    pub launch_millis: u64,
+    #[serde(default, skip_serializing_if = "crate::skip_if_none")]
+    pub play_seconds: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchOutcome {
+    // The game is still running, or the launcher was closed before it exited
+    Running,
+    Exited,
+    Crashed,
+    Failed,
+}
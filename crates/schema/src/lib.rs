@@ -10,15 +10,24 @@ pub mod fabric_mod;
 pub mod forge;
 pub mod forge_mod;
 pub mod instance;
+pub mod instance_history;
+pub mod instance_share;
+pub mod instance_template;
 pub mod java_runtime_component;
 pub mod java_runtimes;
+pub mod launch_metrics;
 pub mod loader;
 pub mod maven;
 pub mod modification;
 pub mod modrinth;
 pub mod mrpack;
 pub mod pandora_update;
+pub mod patch_notes;
+pub mod performance;
+pub mod quilt_mod;
+pub mod realms;
 pub mod resourcepack;
+pub mod server_status;
 pub mod version;
 pub mod version_manifest;
 
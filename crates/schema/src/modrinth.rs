@@ -103,6 +103,46 @@ impl ModrinthProjectType {
 #[derive(Debug, Clone, Deserialize)]
 pub struct ModrinthProjectVersionsResult(pub Arc<[ModrinthProjectVersion]>);
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModrinthProject {
+    pub id: Arc<str>,
+    pub slug: Option<Arc<str>>,
+    pub title: Option<Arc<str>>,
+    pub description: Option<Arc<str>>,
+    // Markdown
+    pub body: Option<Arc<str>>,
+    pub project_type: ModrinthProjectType,
+    pub client_side: Option<ModrinthSideRequirement>,
+    pub server_side: Option<ModrinthSideRequirement>,
+    pub icon_url: Option<Arc<str>>,
+    #[serde(default)]
+    pub downloads: usize,
+    #[serde(default)]
+    pub followers: usize,
+    pub categories: Option<Arc<[Ustr]>>,
+    #[serde(default)]
+    pub gallery: Arc<[ModrinthGalleryImage]>,
+    pub source_url: Option<Arc<str>>,
+    pub issues_url: Option<Arc<str>>,
+    pub wiki_url: Option<Arc<str>>,
+    pub discord_url: Option<Arc<str>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModrinthGalleryImage {
+    pub url: Arc<str>,
+    #[serde(default)]
+    pub featured: bool,
+    pub title: Option<Arc<str>>,
+    pub description: Option<Arc<str>>,
+}
+
+// Projects that any version of a project depends on
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModrinthProjectDependencies {
+    pub projects: Arc<[ModrinthProject]>,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct ModrinthProjectVersion {
     pub game_versions: Option<Arc<[Ustr]>>,
@@ -115,6 +155,10 @@ pub struct ModrinthProjectVersion {
     pub version_type: Option<ModrinthVersionType>,
     pub status: Option<ModrinthVersionStatus>,
     pub files: Arc<[ModrinthFile]>,
+    #[serde(default)]
+    pub changelog: Option<Arc<str>>,
+    #[serde(default)]
+    pub date_published: Option<Arc<str>>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -147,6 +191,8 @@ pub enum ModrinthLoader {
     Iris,
     Optifine,
     Canvas,
+    // Datapacks, which are installed into a world instead of the instance
+    Datapack,
     // Other
     #[serde(other)]
     Unknown,
@@ -159,6 +205,7 @@ impl ModrinthLoader {
             ModrinthLoader::Minecraft => Some("resourcepacks"),
             ModrinthLoader::Iris | ModrinthLoader::Optifine => Some("shaderpacks"),
             ModrinthLoader::Canvas => Some("resourcepacks"),
+            ModrinthLoader::Datapack => None,
             ModrinthLoader::Unknown => None,
         }
     }
@@ -172,6 +219,7 @@ impl ModrinthLoader {
             Self::Iris => "Iris",
             Self::Optifine => "Optifine",
             Self::Canvas => "Canvas",
+            Self::Datapack => "Datapack",
             Self::Unknown => "Unknown",
         }
     }
@@ -185,6 +233,7 @@ impl ModrinthLoader {
             Self::Iris => "iris",
             Self::Optifine => "optifine",
             Self::Canvas => "canvas",
+            Self::Datapack => "datapack",
             Self::Unknown => "unknown",
         }
     }
@@ -198,6 +247,7 @@ impl ModrinthLoader {
             "Iris" | "iris" => Self::Iris,
             "Optifine" | "optifine" => Self::Optifine,
             "Canvas" | "canvas" => Self::Canvas,
+            "Datapack" | "datapack" => Self::Datapack,
             _ => Self::Unknown,
         }
     }
@@ -237,7 +287,27 @@ pub struct ModrinthFile {
 #[derive(Debug, Clone, Deserialize)]
 pub struct ModrinthHashes {
     pub sha1: Arc<str>,
+    #[serde(default)]
+    pub sha512: Option<Arc<str>>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct ModrinthVersionFileUpdateResult(pub ModrinthProjectVersion);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModrinthUser {
+    pub id: Arc<str>,
+    pub username: Arc<str>,
+    pub avatar_url: Option<Arc<str>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModrinthNotification {
+    pub id: Arc<str>,
+    pub title: Arc<str>,
+    pub text: Arc<str>,
+    // Relative to https://modrinth.com
+    pub link: Arc<str>,
+    pub read: bool,
+    pub created: Arc<str>,
+}
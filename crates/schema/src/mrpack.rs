@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use serde::Deserialize;
 
@@ -10,6 +10,9 @@ pub struct ModrinthIndexJson {
     pub version_id: Arc<str>,
     pub name: Arc<str>,
     pub files: Arc<[ModrinthModpackFileDownload]>,
+    // The Minecraft version and mod loader, e.g. "minecraft" and "fabric-loader"
+    #[serde(default)]
+    pub dependencies: HashMap<Arc<str>, Arc<str>>,
 
     // Unofficial
     #[serde(default, deserialize_with = "crate::try_deserialize")]
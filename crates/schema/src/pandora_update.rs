@@ -2,11 +2,24 @@ use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use serde::Deserialize;
 
+use crate::backend_config::UpdateChannel;
+
 #[derive(Debug, Clone)]
 pub enum UpdateInstallType {
     AppImage(PathBuf),
     Executable,
     App(PathBuf),
+    // Installed with an installer on Windows, updated by running the installer of the new version
+    Installer {
+        kind: WindowsInstallerKind,
+        per_machine: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowsInstallerKind {
+    Msi,
+    Nsis,
 }
 
 impl UpdateInstallType {
@@ -15,6 +28,8 @@ impl UpdateInstallType {
             UpdateInstallType::AppImage(..) => "appimage",
             UpdateInstallType::Executable => "executable",
             UpdateInstallType::App(..) => "app",
+            UpdateInstallType::Installer { kind: WindowsInstallerKind::Msi, .. } => "msi",
+            UpdateInstallType::Installer { kind: WindowsInstallerKind::Nsis, .. } => "nsis",
         }
     }
 }
@@ -23,6 +38,7 @@ impl UpdateInstallType {
 pub struct UpdatePrompt {
     pub old_version: Arc<str>,
     pub new_version: Arc<str>,
+    pub channel: UpdateChannel,
     pub install_type: UpdateInstallType,
     pub exe: UpdateManifestExe,
 }
@@ -30,7 +46,10 @@ pub struct UpdatePrompt {
 #[derive(Deserialize, Debug, Clone)]
 pub struct UpdateManifest {
     pub version: Arc<str>,
-    pub downloads: UpdateManifestArchs
+    pub downloads: UpdateManifestArchs,
+    // Percentage of launchers which are offered this version, lowered to throttle a bad release
+    #[serde(default)]
+    pub rollout_percentage: Option<u8>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -51,4 +70,25 @@ pub struct UpdateManifestExe {
     pub size: usize,
     pub sha1: Arc<str>,
     pub sig: Arc<str>,
+    // Patches from previous versions, the patched executable must match sha1 and sig of the full download
+    #[serde(default)]
+    pub patches: Vec<UpdateManifestPatch>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct UpdateManifestPatch {
+    pub from: Arc<str>,
+    pub format: UpdatePatchFormat,
+    pub download: Arc<str>,
+    pub size: usize,
+    pub sha1: Arc<str>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdatePatchFormat {
+    // bsdiff 4.x patch
+    Bsdiff,
+    // zstd frame created with --patch-from, using the old executable as the reference
+    Zstd,
 }
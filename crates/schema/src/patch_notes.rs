@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use ustr::Ustr;
+
+pub const LAUNCHER_CONTENT_URL: &str = "https://launchercontent.mojang.com";
+pub const JAVA_PATCH_NOTES_URL: &str = "https://launchercontent.mojang.com/v2/javaPatchNotes.json";
+
+#[derive(Deserialize, Debug)]
+pub struct JavaPatchNotes {
+    pub entries: Vec<JavaPatchNotesEntry>,
+}
+
+impl JavaPatchNotes {
+    pub fn find(&self, version: &str) -> Option<&JavaPatchNotesEntry> {
+        self.entries.iter().find(|entry| entry.version.as_str() == version)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct JavaPatchNotesEntry {
+    pub title: Arc<str>,
+    pub version: Ustr,
+    pub image: Option<PatchNotesImage>,
+    // Relative to the v2 directory of the launcher content url, points to a JavaPatchNote
+    pub content_path: Arc<str>,
+    pub id: Arc<str>,
+    #[serde(default)]
+    pub date: Option<Arc<str>>,
+    #[serde(default)]
+    pub short_text: Option<Arc<str>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PatchNotesImage {
+    // Relative to the launcher content url
+    pub url: Arc<str>,
+    #[serde(default)]
+    pub title: Option<Arc<str>>,
+}
+
+impl PatchNotesImage {
+    pub fn absolute_url(&self) -> String {
+        if self.url.starts_with("https://") {
+            self.url.to_string()
+        } else {
+            format!("{LAUNCHER_CONTENT_URL}{}", self.url)
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct JavaPatchNote {
+    pub title: Arc<str>,
+    pub version: Ustr,
+    // Html
+    pub body: Arc<str>,
+}
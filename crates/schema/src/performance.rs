@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+use ustr::Ustr;
+
+use crate::loader::Loader;
+
+// Performance read from the game log after each session, stored in the instance folder
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct PerformanceHistory {
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub sessions: Vec<PerformanceSession>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PerformanceSession {
+    // Unix timestamp in seconds
+    pub started_at: i64,
+    pub play_seconds: u64,
+    // What the instance looked like during the session, so sessions before and after an update can be compared
+    pub minecraft_version: Ustr,
+    pub loader: Loader,
+    #[serde(default, skip_serializing_if = "crate::skip_if_none")]
+    pub loader_version: Option<Ustr>,
+    #[serde(default)]
+    pub mod_count: usize,
+    // "Can't keep up!" warnings from the integrated server and how far behind they reported it was
+    #[serde(default, skip_serializing_if = "crate::skip_if_default")]
+    pub lag_spikes: u32,
+    #[serde(default, skip_serializing_if = "crate::skip_if_default")]
+    pub lag_millis: u64,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub samples: Vec<PerformanceSample>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+pub struct PerformanceSample {
+    // Seconds since the start of the log
+    pub offset_seconds: u32,
+    #[serde(default, skip_serializing_if = "crate::skip_if_none")]
+    pub fps: Option<u32>,
+    #[serde(default, skip_serializing_if = "crate::skip_if_none")]
+    pub tps: Option<f32>,
+    #[serde(default, skip_serializing_if = "crate::skip_if_none")]
+    pub memory_used_mib: Option<u32>,
+    #[serde(default, skip_serializing_if = "crate::skip_if_none")]
+    pub memory_max_mib: Option<u32>,
+}
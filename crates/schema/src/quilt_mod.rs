@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::fabric_mod::{Icon, VersionPredicates};
+
+#[derive(Deserialize, Debug)]
+pub struct QuiltModJson {
+    pub quilt_loader: QuiltLoader,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct QuiltLoader {
+    pub id: Arc<str>,
+    pub version: Arc<str>,
+    #[serde(default)]
+    pub metadata: QuiltMetadata,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub depends: Vec<QuiltDependency>,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub breaks: Vec<QuiltDependency>,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub provides: Vec<QuiltProvides>,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub jars: Vec<Arc<str>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum QuiltProvides {
+    Id(Arc<str>),
+    Object {
+        id: Arc<str>,
+    },
+}
+
+impl QuiltProvides {
+    pub fn id(self) -> Arc<str> {
+        match self {
+            QuiltProvides::Id(id) => id,
+            QuiltProvides::Object { id } => id,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct QuiltMetadata {
+    pub name: Option<Arc<str>>,
+    pub description: Option<Arc<str>>,
+    // Maps the name of a contributor to their role
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub contributors: IndexMap<Arc<str>, Arc<str>>,
+    pub icon: Option<Icon>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum QuiltDependency {
+    Id(Arc<str>),
+    Object {
+        id: Arc<str>,
+        #[serde(default, deserialize_with = "crate::try_deserialize")]
+        versions: Option<VersionPredicates>,
+        #[serde(default)]
+        optional: bool,
+    },
+}
+
+impl QuiltDependency {
+    pub fn id(&self) -> &Arc<str> {
+        match self {
+            QuiltDependency::Id(id) => id,
+            QuiltDependency::Object { id, .. } => id,
+        }
+    }
+
+    pub fn versions(&self) -> Option<&VersionPredicates> {
+        match self {
+            QuiltDependency::Id(_) => None,
+            QuiltDependency::Object { versions, .. } => versions.as_ref(),
+        }
+    }
+
+    pub fn optional(&self) -> bool {
+        match self {
+            QuiltDependency::Id(_) => false,
+            QuiltDependency::Object { optional, .. } => *optional,
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RealmsWorlds {
+    #[serde(default)]
+    pub servers: Vec<RealmsServer>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RealmsServer {
+    pub id: i64,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub owner: Option<Arc<str>>,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub name: Option<Arc<str>>,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub motd: Option<Arc<str>>,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub state: RealmsServerState,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub expired: bool,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub max_players: u32,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub minigame_name: Option<Arc<str>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum RealmsServerState {
+    Open,
+    Closed,
+    Uninitialized,
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RealmsLivePlayerLists {
+    #[serde(default)]
+    pub lists: Vec<RealmsLivePlayerList>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RealmsLivePlayerList {
+    pub server_id: i64,
+    // JSON encoded list of players, as a string
+    #[serde(default)]
+    pub player_list: Arc<str>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RealmsLivePlayer {
+    #[serde(default)]
+    pub online: bool,
+}
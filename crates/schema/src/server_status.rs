@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+// Response to the status request of the Server List Ping protocol
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerStatusResponse {
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub version: ServerStatusVersion,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub players: ServerStatusPlayers,
+    // Text component, either a plain string or a json object
+    #[serde(default)]
+    pub description: serde_json::Value,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub favicon: Option<Arc<str>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ServerStatusVersion {
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub name: Arc<str>,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub protocol: i32,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ServerStatusPlayers {
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub max: i64,
+    #[serde(default, deserialize_with = "crate::try_deserialize")]
+    pub online: i64,
+}
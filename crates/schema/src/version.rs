@@ -123,6 +123,11 @@ pub enum OsName {
 pub enum OsArch {
     Arm64,
     X86,
+    #[serde(rename = "x86_64")]
+    X86_64,
+    Riscv64,
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -39,3 +39,14 @@ pub enum MinecraftVersionType {
     OldBeta,
     OldAlpha,
 }
+
+impl MinecraftVersionType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MinecraftVersionType::Release => "release",
+            MinecraftVersionType::Snapshot => "snapshot",
+            MinecraftVersionType::OldBeta => "old_beta",
+            MinecraftVersionType::OldAlpha => "old_alpha",
+        }
+    }
+}
@@ -27,6 +27,7 @@ env -u CARGO_PACKAGER_SIGN_PRIVATE_KEY cargo packager --config '{'\
 '  "productName": "PandoraLauncher",'\
 '  "version": "'"$version"'",'\
 '  "identifier": "com.moulberry.pandoralauncher",'\
+'  "deepLinkProtocols": [{ "schemes": ["pandora", "modrinth"] }],'\
 '  "resources": [],'\
 '  "binaries": [{ "path": "PandoraLauncher-macOS", "main": true }],'\
 '  "icons": ["package/mac.icns"]'\
@@ -23,6 +23,7 @@ env -u CARGO_PACKAGER_SIGN_PRIVATE_KEY cargo packager --config '{'\
 '  "productName": "Pandora Launcher",'\
 '  "version": "'"$version"'",'\
 '  "identifier": "com.moulberry.pandoralauncher",'\
+'  "deepLinkProtocols": [{ "schemes": ["pandora", "modrinth"] }],'\
 '  "resources": [],'\
 '  "binaries": [{ "path": "PandoraLauncher-Windows.exe", "main": true }],'\
 '  "icons": ["package/windows.ico"]'\